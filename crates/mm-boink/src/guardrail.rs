@@ -64,7 +64,13 @@ fn scan_expr(expr: &Expr, profile: &mut ProblemProfile, depth: u32) {
         | Expr::Tan(inner)
         | Expr::Arcsin(inner)
         | Expr::Arccos(inner)
-        | Expr::Arctan(inner) => {
+        | Expr::Arctan(inner)
+        | Expr::Sinh(inner)
+        | Expr::Cosh(inner)
+        | Expr::Tanh(inner)
+        | Expr::Arcsinh(inner)
+        | Expr::Arccosh(inner)
+        | Expr::Arctanh(inner) => {
             profile.has_trig = true;
             scan_expr(inner, profile, depth + 1);
             profile.complexity += 2;
@@ -84,6 +90,18 @@ fn scan_expr(expr: &Expr, profile: &mut ProblemProfile, depth: u32) {
             profile.complexity += 8; // Integrals are harder
         }
 
+        // ========== Calculus - Limits ==========
+        Expr::Limit {
+            expr: inner,
+            approaching,
+            ..
+        } => {
+            profile.has_calculus_diff = true;
+            scan_expr(inner, profile, depth + 1);
+            scan_expr(approaching, profile, depth + 1);
+            profile.complexity += 5;
+        }
+
         // ========== Number Theory ==========
         Expr::GCD(a, b) | Expr::LCM(a, b) | Expr::Mod(a, b) => {
             profile.has_number_theory = true;
@@ -189,8 +207,17 @@ fn scan_expr(expr: &Expr, profile: &mut ProblemProfile, depth: u32) {
             profile.complexity += factors.len() as u32;
         }
 
+        // ========== Case analysis ==========
+        Expr::Piecewise(branches) => {
+            for (condition, value) in branches {
+                scan_expr(&condition.expr, profile, depth + 1);
+                scan_expr(value, profile, depth + 1);
+            }
+            profile.complexity += 2 * branches.len() as u32;
+        }
+
         // ========== Base cases ==========
-        Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E => {
+        Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => {
             profile.complexity += 1;
         }
     }
@@ -14,10 +14,11 @@
 
 use candle_core::Device;
 use mm_core::{Expr, SymbolTable};
+use mm_rules::{Rule, RuleContext};
 use rand::prelude::*;
 
 use crate::encoder::ExpressionEncoder;
-use crate::training::TrainingExample;
+use crate::training::{TrainingConfig, TrainingExample};
 
 /// Rule ID constants matching those in mm-rules
 mod rule_ids {
@@ -107,10 +108,11 @@ impl DataGenerator {
     }
 
     fn make_example(&self, expr: &Expr, rule: u32, value: f32) -> TrainingExample {
-        let tokens = self.encoder.encode_tokens(&self.encoder.tokenize(expr));
+        let tokens = self.encoder.encode_ids(expr);
         TrainingExample {
             tokens,
             target_rule: rule,
+            rule_target: 1.0,
             target_value: value,
         }
     }
@@ -967,6 +969,247 @@ impl DataGenerator {
         let per_cat = size / 20;
         self.generate_dataset(per_cat)
     }
+
+    // =========================================================================
+    // AUGMENTATION
+    // =========================================================================
+
+    /// Equivalent variants of `expr`: up to `count` distinct expressions,
+    /// none equal to `expr` itself.
+    ///
+    /// Each variant applies a few rewrites below the root: swapping the
+    /// operands of `+` or `*`, wrapping a subterm in `+ 0` or `* 1`, or
+    /// dropping such an identity. The root is left in place so a rule that
+    /// matched the original still matches. A variant is kept only if it has
+    /// the original's canonical form and agrees with it numerically.
+    pub fn augment(&mut self, expr: &Expr, count: usize) -> Vec<Expr> {
+        let canonical = expr.canonicalize();
+        let mut variants: Vec<Expr> = Vec::new();
+
+        for _ in 0..count * 10 {
+            if variants.len() == count {
+                break;
+            }
+            let mut variant = expr.clone();
+            for _ in 0..self.rng.gen_range(1..=3) {
+                let rewrite = *REWRITES.choose(&mut self.rng).unwrap();
+                let mut target = Some(self.rng.gen_range(0..variant.size()));
+                variant = map_children(&variant, &mut |child| {
+                    rewrite_nth(child, &mut target, rewrite)
+                });
+            }
+            if variant != *expr
+                && !variants.contains(&variant)
+                && variant.canonicalize() == canonical
+                && variant.approx_equals(expr, 10, 1e-9)
+            {
+                variants.push(variant);
+            }
+        }
+
+        variants
+    }
+
+    /// `expr` with `x`, `y` and `z` consistently permuted.
+    pub fn rename_variables(&mut self, expr: &Expr) -> Expr {
+        let mut targets = [self.x, self.y, self.z];
+        targets.shuffle(&mut self.rng);
+        let sources = [self.x, self.y, self.z];
+        rename(expr, &|v| {
+            sources
+                .iter()
+                .position(|s| *s == v)
+                .map_or(v, |i| targets[i])
+        })
+    }
+
+    /// Training examples for the states of a solution path.
+    ///
+    /// `states[i + 1]` is reached from `states[i]` by the rule `rules[i]`, and
+    /// the last state is the solved form. Each state is labelled with the
+    /// rule applied next (the no-op label for the solved form) and
+    /// a value from [`discounted_values`] with the config's
+    /// [`value_discount`](TrainingConfig::value_discount).
+    ///
+    /// # Panics
+    ///
+    /// Panics unless there is exactly one more state than rules.
+    pub fn path_examples(
+        &self,
+        states: &[Expr],
+        rules: &[u32],
+        config: &TrainingConfig,
+    ) -> Vec<TrainingExample> {
+        assert_eq!(states.len(), rules.len() + 1, "one rule per step");
+        let values = discounted_values(rules.len(), config.value_discount);
+        states
+            .iter()
+            .zip(rules.iter().copied().chain([rule_ids::NO_OP]))
+            .zip(values)
+            .map(|((state, rule), value)| self.make_example(state, rule, value))
+            .collect()
+    }
+
+    /// Negative policy examples for the states of a solution path.
+    ///
+    /// At each state before the last, every rule in `rules` that applies at
+    /// the root is tried. A result that is not a later state of the path and
+    /// is more complex than the state it came from leads away from the goal,
+    /// so the rule is labelled with target probability 0.0. Values follow
+    /// [`discounted_values`], as in [`path_examples`](Self::path_examples).
+    ///
+    /// Rules are labelled `id - 1`, the inverse of the mapping used by
+    /// [`PolicyNetwork`](crate::PolicyNetwork).
+    pub fn mine_negatives(
+        &self,
+        path: &[Expr],
+        rules: &[Rule],
+        config: &TrainingConfig,
+    ) -> Vec<TrainingExample> {
+        let ctx = RuleContext::default();
+        let Some(steps) = path.len().checked_sub(1) else {
+            return Vec::new();
+        };
+        let values = discounted_values(steps, config.value_discount);
+        let mut examples = Vec::new();
+
+        for (i, state) in path[..steps].iter().enumerate() {
+            for rule in rules.iter().filter(|r| r.can_apply(state, &ctx)) {
+                let Some(label) = rule.id.0.checked_sub(1) else {
+                    continue;
+                };
+                let leads_away = rule.apply(state, &ctx).iter().any(|app| {
+                    !path[i + 1..].contains(&app.result)
+                        && app.result.complexity() > state.complexity()
+                });
+                if leads_away {
+                    let mut example = self.make_example(state, label, values[i]);
+                    example.rule_target = 0.0;
+                    examples.push(example);
+                }
+            }
+        }
+
+        examples
+    }
+
+    /// Training examples for augmented variants of `expr`, labelled like the
+    /// original. About half also have their variables renamed.
+    pub fn augment_example(
+        &mut self,
+        expr: &Expr,
+        rule: u32,
+        value: f32,
+        count: usize,
+    ) -> Vec<TrainingExample> {
+        self.augment(expr, count)
+            .into_iter()
+            .map(|variant| {
+                let variant = if self.rng.gen_bool(0.5) {
+                    self.rename_variables(&variant)
+                } else {
+                    variant
+                };
+                self.make_example(&variant, rule, value)
+            })
+            .collect()
+    }
+}
+
+/// Value targets for the `steps + 1` states of a solution path.
+///
+/// A state `k` steps from the end gets `discount^k`, so the solved form
+/// scores 1.0 and earlier states geometrically less.
+pub fn discounted_values(steps: usize, discount: f32) -> Vec<f32> {
+    (0..=steps)
+        .map(|i| discount.powi((steps - i) as i32))
+        .collect()
+}
+
+/// An answer-preserving rewrite of a single node.
+#[derive(Clone, Copy)]
+enum Rewrite {
+    Commute,
+    AddZero,
+    MulOne,
+    DropIdentity,
+}
+
+const REWRITES: [Rewrite; 4] = [
+    Rewrite::Commute,
+    Rewrite::AddZero,
+    Rewrite::MulOne,
+    Rewrite::DropIdentity,
+];
+
+impl Rewrite {
+    fn apply(self, expr: &Expr) -> Expr {
+        match (self, expr) {
+            (Rewrite::Commute, Expr::Add(a, b)) => Expr::Add(b.clone(), a.clone()),
+            (Rewrite::Commute, Expr::Mul(a, b)) => Expr::Mul(b.clone(), a.clone()),
+            (Rewrite::AddZero, e) => Expr::Add(Box::new(e.clone()), Box::new(Expr::int(0))),
+            (Rewrite::MulOne, e) => Expr::Mul(Box::new(e.clone()), Box::new(Expr::int(1))),
+            (Rewrite::DropIdentity, Expr::Add(a, b)) if b.is_zero() => a.as_ref().clone(),
+            (Rewrite::DropIdentity, Expr::Add(a, b)) if a.is_zero() => b.as_ref().clone(),
+            (Rewrite::DropIdentity, Expr::Mul(a, b)) if b.is_one() => a.as_ref().clone(),
+            (Rewrite::DropIdentity, Expr::Mul(a, b)) if a.is_one() => b.as_ref().clone(),
+            _ => expr.clone(),
+        }
+    }
+}
+
+/// Apply `rewrite` to the `target`-th node of `expr` in pre-order, then
+/// clear `target`.
+fn rewrite_nth(expr: &Expr, target: &mut Option<usize>, rewrite: Rewrite) -> Expr {
+    match target {
+        Some(0) => {
+            *target = None;
+            return rewrite.apply(expr);
+        }
+        Some(n) => *n -= 1,
+        None => return expr.clone(),
+    }
+    map_children(expr, &mut |child| rewrite_nth(child, target, rewrite))
+}
+
+/// Rebuild `expr` with `f` applied to each direct subexpression. Node types
+/// the generator never produces are returned unchanged.
+fn map_children(expr: &Expr, f: &mut dyn FnMut(&Expr) -> Expr) -> Expr {
+    let mut go = |e: &Expr| Box::new(f(e));
+    match expr {
+        Expr::Add(a, b) => Expr::Add(go(a), go(b)),
+        Expr::Sub(a, b) => Expr::Sub(go(a), go(b)),
+        Expr::Mul(a, b) => Expr::Mul(go(a), go(b)),
+        Expr::Div(a, b) => Expr::Div(go(a), go(b)),
+        Expr::Pow(a, b) => Expr::Pow(go(a), go(b)),
+        Expr::Neg(e) => Expr::Neg(go(e)),
+        Expr::Sin(e) => Expr::Sin(go(e)),
+        Expr::Cos(e) => Expr::Cos(go(e)),
+        Expr::Exp(e) => Expr::Exp(go(e)),
+        Expr::Ln(e) => Expr::Ln(go(e)),
+        Expr::Sqrt(e) => Expr::Sqrt(go(e)),
+        Expr::Equation { lhs, rhs } => Expr::Equation {
+            lhs: go(lhs),
+            rhs: go(rhs),
+        },
+        Expr::Derivative { expr, var } => Expr::Derivative {
+            expr: go(expr),
+            var: *var,
+        },
+        _ => expr.clone(),
+    }
+}
+
+/// Rename every variable of `expr` through `f`.
+fn rename(expr: &Expr, f: &dyn Fn(mm_core::Symbol) -> mm_core::Symbol) -> Expr {
+    match expr {
+        Expr::Var(v) => Expr::Var(f(*v)),
+        Expr::Derivative { expr, var } => Expr::Derivative {
+            expr: Box::new(rename(expr, f)),
+            var: f(*var),
+        },
+        _ => map_children(expr, &mut |child| rename(child, f)),
+    }
 }
 
 #[cfg(test)]
@@ -1006,4 +1249,120 @@ mod tests {
         let examples = gen.generate_quotient_rule(10);
         assert!(examples.len() >= 20);
     }
+
+    #[test]
+    fn test_augment_preserves_meaning() {
+        let mut gen = DataGenerator::new(Device::Cpu);
+        let (x, y) = (Expr::Var(gen.x), Expr::Var(gen.y));
+
+        // 2x + (y + 3) = 7
+        let expr = Expr::Equation {
+            lhs: Box::new(Expr::Add(
+                Box::new(Expr::Mul(Box::new(Expr::int(2)), Box::new(x))),
+                Box::new(Expr::Add(Box::new(y), Box::new(Expr::int(3)))),
+            )),
+            rhs: Box::new(Expr::int(7)),
+        };
+
+        let variants = gen.augment(&expr, 5);
+        assert!(!variants.is_empty());
+        for variant in &variants {
+            assert_ne!(*variant, expr);
+            assert_eq!(variant.canonicalize(), expr.canonicalize());
+            assert!(variant.approx_equals(&expr, 10, 1e-9));
+            // The root stays an equation
+            assert!(matches!(variant, Expr::Equation { .. }));
+        }
+
+        let examples = gen.augment_example(&expr, rule_ids::LINEAR_SOLVE, 1.0, 5);
+        assert_eq!(examples.len(), 5);
+        assert!(examples
+            .iter()
+            .all(|e| e.target_rule == rule_ids::LINEAR_SOLVE));
+    }
+
+    #[test]
+    fn test_rename_variables_is_bijective() {
+        let mut gen = DataGenerator::new(Device::Cpu);
+        let expr = Expr::Sub(Box::new(Expr::Var(gen.x)), Box::new(Expr::Var(gen.y)));
+        for _ in 0..10 {
+            let renamed = gen.rename_variables(&expr);
+            assert!(renamed.alpha_equivalent(&expr));
+        }
+    }
+
+    #[test]
+    fn test_discounted_values() {
+        let values = discounted_values(3, 0.5);
+        assert_eq!(values, vec![0.125, 0.25, 0.5, 1.0]);
+        assert_eq!(discounted_values(0, 0.9), vec![1.0]);
+
+        // x + 0 + 0 → x + 0 → x
+        let gen = DataGenerator::new(Device::Cpu);
+        let x = Expr::Var(gen.x);
+        let plus_zero = |e: Expr| Expr::Add(Box::new(e), Box::new(Expr::int(0)));
+        let states = [plus_zero(plus_zero(x.clone())), plus_zero(x.clone()), x];
+        let rules = [rule_ids::IDENTITY_ADD_ZERO, rule_ids::IDENTITY_ADD_ZERO];
+        let examples = gen.path_examples(&states, &rules, &TrainingConfig::default());
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[2].target_value, 1.0);
+        assert_eq!(examples[2].target_rule, rule_ids::NO_OP);
+        assert!((examples[1].target_value - 0.9).abs() < 1e-6);
+        assert!((examples[0].target_value - 0.81).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mine_negatives() {
+        use mm_rules::{RuleApplication, RuleCategory, RuleId};
+
+        fn rule(id: u32, apply: fn(&Expr, &RuleContext) -> Vec<RuleApplication>) -> Rule {
+            Rule {
+                id: RuleId(id),
+                name: "test",
+                category: RuleCategory::Simplification,
+                description: "",
+                domains: &[],
+                requires: &[],
+                roots: &[],
+                is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
+                apply,
+                reversible: false,
+                cost: 1,
+                ..Default::default()
+            }
+        }
+        fn rewrite(result: Expr) -> Vec<RuleApplication> {
+            vec![RuleApplication {
+                result,
+                justification: String::new(),
+                condition: None,
+            }]
+        }
+
+        // Good: a + b → a, which drops the + 0 below
+        let good = rule(3, |expr, _ctx| match expr {
+            Expr::Add(a, _) => rewrite(a.as_ref().clone()),
+            _ => vec![],
+        });
+        // Bad: e → e · 1
+        let bad = rule(4, |expr, _ctx| {
+            rewrite(Expr::Mul(Box::new(expr.clone()), Box::new(Expr::int(1))))
+        });
+
+        let gen = DataGenerator::new(Device::Cpu);
+        let x = Expr::Var(gen.x);
+        let path = [Expr::Add(Box::new(x.clone()), Box::new(Expr::int(0))), x];
+        let config = TrainingConfig::default();
+
+        // Only the bad rule is mined
+        let negatives = gen.mine_negatives(&path, &[good, bad], &config);
+        assert_eq!(negatives.len(), 1);
+        assert_eq!(negatives[0].target_rule, 3);
+
+        // and it gets a lower target than the rule on the path
+        let positives = gen.path_examples(&path, &[2], &config);
+        assert_eq!(positives[0].target_rule, 2);
+        assert!(negatives[0].rule_target < positives[0].rule_target);
+        assert_eq!(negatives[0].target_value, positives[0].target_value);
+    }
 }
@@ -17,6 +17,9 @@ pub const START_TOKEN: u32 = 1;
 pub const END_TOKEN: u32 = 2;
 pub const UNK_TOKEN: u32 = 3;
 
+/// Placeholder for a subtree dropped by [`TruncationPolicy::Summarize`].
+pub const SUB_TOKEN: &str = "<SUB>";
+
 /// Vocabulary for expression encoding.
 #[derive(Debug, Clone)]
 pub struct Vocabulary {
@@ -41,7 +44,8 @@ impl Vocabulary {
             // Numbers (26-35)
             "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", // Variables (36-45)
             "x", "y", "z", "a", "b", "c", "n", "t", "u", "v", // Keywords (46-50)
-            "pi", "e", "inf", "neg", "frac",
+            "pi", "e", "inf", "neg", "frac", // Placeholder (51)
+            SUB_TOKEN,
         ];
 
         let token_to_id: HashMap<String, u32> = tokens
@@ -77,10 +81,23 @@ impl Vocabulary {
     }
 }
 
+/// How [`ExpressionEncoder`] fits an expression whose tokens exceed the
+/// maximum sequence length. Both policies are deterministic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TruncationPolicy {
+    /// Keep the leading tokens and drop the rest.
+    #[default]
+    Truncate,
+    /// Replace every subtree below some depth with [`SUB_TOKEN`], using the
+    /// deepest cut-off that fits.
+    Summarize,
+}
+
 /// Encoder for converting expressions to tensors.
 pub struct ExpressionEncoder {
     vocab: Vocabulary,
     max_length: usize,
+    truncation: TruncationPolicy,
     device: Device,
 }
 
@@ -90,25 +107,75 @@ impl ExpressionEncoder {
         Self {
             vocab: Vocabulary::new(),
             max_length: 64,
+            truncation: TruncationPolicy::default(),
             device,
         }
     }
 
-    /// Set maximum sequence length.
+    /// Set maximum sequence length, including the start and end tokens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is less than 2.
     pub fn with_max_length(mut self, len: usize) -> Self {
+        assert!(len >= 2, "max length must fit the start and end tokens");
         self.max_length = len;
         self
     }
 
+    /// Set how over-long expressions are shortened.
+    pub fn with_truncation(mut self, policy: TruncationPolicy) -> Self {
+        self.truncation = policy;
+        self
+    }
+
     /// Tokenize an expression into a sequence of token strings.
     pub fn tokenize(&self, expr: &Expr) -> Vec<String> {
         let mut tokens = Vec::new();
-        self.tokenize_recursive(expr, &mut tokens);
+        self.tokenize_recursive(expr, &mut tokens, None);
         tokens
     }
 
-    /// Recursively tokenize an expression.
-    fn tokenize_recursive(&self, expr: &Expr, tokens: &mut Vec<String>) {
+    /// Tokenize an expression, shortened by the truncation policy so that
+    /// it fits between the start and end tokens.
+    pub fn tokenize_fitted(&self, expr: &Expr) -> Vec<String> {
+        let budget = self.max_length - 2;
+        let mut tokens = self.tokenize(expr);
+        if tokens.len() <= budget {
+            return tokens;
+        }
+        match self.truncation {
+            TruncationPolicy::Truncate => {
+                tokens.truncate(budget);
+                tokens
+            }
+            TruncationPolicy::Summarize => {
+                // Deeper cut-offs only add tokens, and the full expression
+                // does not fit, so this stops
+                let mut best = Vec::new();
+                for depth in 0.. {
+                    let mut summary = Vec::new();
+                    self.tokenize_recursive(expr, &mut summary, Some(depth));
+                    if summary.len() > budget {
+                        break;
+                    }
+                    best = summary;
+                }
+                best
+            }
+        }
+    }
+
+    /// Recursively tokenize an expression, replacing subtrees below `depth`
+    /// levels with [`SUB_TOKEN`].
+    fn tokenize_recursive(&self, expr: &Expr, tokens: &mut Vec<String>, depth: Option<usize>) {
+        let depth = match depth {
+            Some(0) => {
+                tokens.push(SUB_TOKEN.to_string());
+                return;
+            }
+            limit => limit.map(|d| d - 1),
+        };
         match expr {
             Expr::Const(r) => {
                 if r.is_integer() {
@@ -143,117 +210,171 @@ impl ExpressionEncoder {
             Expr::E => {
                 tokens.push("e".to_string());
             }
+            Expr::Infinity { negative } => {
+                if *negative {
+                    tokens.push("neg".to_string());
+                }
+                tokens.push("inf".to_string());
+            }
             Expr::Neg(e) => {
                 tokens.push("neg".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Add(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("+".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Sub(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("-".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Mul(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("*".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Div(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("/".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Pow(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("^".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Sqrt(e) => {
                 tokens.push("sqrt".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Sin(e) => {
                 tokens.push("sin".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Cos(e) => {
                 tokens.push("cos".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Tan(e) => {
                 tokens.push("tan".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Arcsin(e) => {
                 tokens.push("arcsin".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Arccos(e) => {
                 tokens.push("arccos".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Arctan(e) => {
                 tokens.push("arctan".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Sinh(e) => {
+                tokens.push("sinh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Cosh(e) => {
+                tokens.push("cosh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Tanh(e) => {
+                tokens.push("tanh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Arcsinh(e) => {
+                tokens.push("arcsinh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Arccosh(e) => {
+                tokens.push("arccosh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Arctanh(e) => {
+                tokens.push("arctanh".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Ln(e) => {
                 tokens.push("ln".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Exp(e) => {
                 tokens.push("exp".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Abs(e) => {
                 tokens.push("abs".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Derivative { expr: e, .. } => {
                 tokens.push("d/dx".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Integral { expr: e, .. } => {
                 tokens.push("∫".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            Expr::Limit {
+                expr: e,
+                approaching,
+                ..
+            } => {
+                tokens.push("lim".to_string());
+                tokens.push("(".to_string());
+                self.tokenize_recursive(e, tokens, depth);
+                tokens.push(",".to_string());
+                self.tokenize_recursive(approaching, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Sum(terms) => {
@@ -263,7 +384,7 @@ impl ExpressionEncoder {
                     if i > 0 {
                         tokens.push("+".to_string());
                     }
-                    self.tokenize_recursive(&term.expr, tokens);
+                    self.tokenize_recursive(&term.expr, tokens, depth);
                 }
                 tokens.push(")".to_string());
             }
@@ -274,83 +395,83 @@ impl ExpressionEncoder {
                     if i > 0 {
                         tokens.push("*".to_string());
                     }
-                    self.tokenize_recursive(&factor.base, tokens);
+                    self.tokenize_recursive(&factor.base, tokens, depth);
                 }
                 tokens.push(")".to_string());
             }
             Expr::Equation { lhs, rhs } => {
-                self.tokenize_recursive(lhs, tokens);
+                self.tokenize_recursive(lhs, tokens, depth);
                 tokens.push("=".to_string());
-                self.tokenize_recursive(rhs, tokens);
+                self.tokenize_recursive(rhs, tokens, depth);
             }
             // Number theory
             Expr::GCD(a, b) => {
                 tokens.push("gcd".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::LCM(a, b) => {
                 tokens.push("lcm".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Mod(a, b) => {
                 tokens.push("mod".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Binomial(n, k) => {
                 tokens.push("C".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(n, tokens);
+                self.tokenize_recursive(n, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(k, tokens);
+                self.tokenize_recursive(k, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Floor(e) => {
                 tokens.push("floor".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Ceiling(e) => {
                 tokens.push("ceil".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Factorial(e) => {
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push("!".to_string());
             }
             Expr::Gte(a, b) => {
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push(">=".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
             }
             Expr::Gt(a, b) => {
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push(">".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
             }
             Expr::Lte(a, b) => {
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("<=".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
             }
             Expr::Lt(a, b) => {
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("<".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
             }
             Expr::Summation {
                 var,
@@ -364,11 +485,11 @@ impl ExpressionEncoder {
                 let idx = var.to_usize() % var_tokens.len();
                 tokens.push(format!("_{}", var_tokens[idx]));
                 tokens.push("=".to_string());
-                self.tokenize_recursive(from, tokens);
+                self.tokenize_recursive(from, tokens, depth);
                 tokens.push("^".to_string());
-                self.tokenize_recursive(to, tokens);
+                self.tokenize_recursive(to, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(body, tokens);
+                self.tokenize_recursive(body, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::BigProduct {
@@ -383,52 +504,66 @@ impl ExpressionEncoder {
                 let idx = var.to_usize() % var_tokens.len();
                 tokens.push(format!("_{}", var_tokens[idx]));
                 tokens.push("=".to_string());
-                self.tokenize_recursive(from, tokens);
+                self.tokenize_recursive(from, tokens, depth);
                 tokens.push("^".to_string());
-                self.tokenize_recursive(to, tokens);
+                self.tokenize_recursive(to, tokens, depth);
                 tokens.push(",".to_string());
-                self.tokenize_recursive(body, tokens);
+                self.tokenize_recursive(body, tokens, depth);
                 tokens.push(")".to_string());
             }
             // Quantifiers
             Expr::ForAll { body, .. } => {
                 tokens.push("forall".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(body, tokens);
+                self.tokenize_recursive(body, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Exists { body, .. } => {
                 tokens.push("exists".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(body, tokens);
+                self.tokenize_recursive(body, tokens, depth);
                 tokens.push(")".to_string());
             }
             // Logical connectives
             Expr::And(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("and".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Or(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("or".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Not(e) => {
                 tokens.push("not".to_string());
                 tokens.push("(".to_string());
-                self.tokenize_recursive(e, tokens);
+                self.tokenize_recursive(e, tokens, depth);
                 tokens.push(")".to_string());
             }
             Expr::Implies(a, b) => {
                 tokens.push("(".to_string());
-                self.tokenize_recursive(a, tokens);
+                self.tokenize_recursive(a, tokens, depth);
                 tokens.push("=>".to_string());
-                self.tokenize_recursive(b, tokens);
+                self.tokenize_recursive(b, tokens, depth);
+                tokens.push(")".to_string());
+            }
+            // Piecewise: value, condition pairs
+            Expr::Piecewise(branches) => {
+                tokens.push("piecewise".to_string());
+                tokens.push("(".to_string());
+                for (i, (condition, value)) in branches.iter().enumerate() {
+                    if i > 0 {
+                        tokens.push(",".to_string());
+                    }
+                    self.tokenize_recursive(value, tokens, depth);
+                    tokens.push("if".to_string());
+                    self.tokenize_recursive(&condition.expr, tokens, depth);
+                }
                 tokens.push(")".to_string());
             }
         }
@@ -452,23 +587,22 @@ impl ExpressionEncoder {
         ids
     }
 
+    /// Encode an expression to exactly [`max_length`](Self::max_length)
+    /// token IDs.
+    pub fn encode_ids(&self, expr: &Expr) -> Vec<u32> {
+        self.encode_tokens(&self.tokenize_fitted(expr))
+    }
+
     /// Encode an expression to a tensor.
     pub fn encode(&self, expr: &Expr) -> Result<Tensor> {
-        let tokens = self.tokenize(expr);
-        let ids = self.encode_tokens(&tokens);
+        let ids = self.encode_ids(expr);
 
         Tensor::new(ids.as_slice(), &self.device)
     }
 
     /// Encode a batch of expressions.
     pub fn encode_batch(&self, exprs: &[Expr]) -> Result<Tensor> {
-        let batch: Vec<Vec<u32>> = exprs
-            .iter()
-            .map(|e| {
-                let tokens = self.tokenize(e);
-                self.encode_tokens(&tokens)
-            })
-            .collect();
+        let batch: Vec<Vec<u32>> = exprs.iter().map(|e| self.encode_ids(e)).collect();
 
         let flat: Vec<u32> = batch.into_iter().flatten().collect();
         let batch_size = exprs.len();
@@ -519,4 +653,57 @@ mod tests {
         let tensor = encoder.encode(&expr).unwrap();
         assert_eq!(tensor.dims(), &[encoder.max_length()]);
     }
+
+    /// `x + x + ⋯ + x` with `n` terms, nested to the left.
+    fn long_sum(n: usize) -> Expr {
+        let mut symbols = mm_core::SymbolTable::new();
+        let x = Expr::Var(symbols.intern("x"));
+        (1..n).fold(x.clone(), |acc, _| {
+            Expr::Add(Box::new(acc), Box::new(x.clone()))
+        })
+    }
+
+    #[test]
+    fn test_truncation_policies() {
+        let small = Expr::int(7);
+        let huge = long_sum(200);
+
+        // Truncation fills the sequence exactly, without padding
+        let encoder = ExpressionEncoder::new(Device::Cpu).with_max_length(16);
+        let ids = encoder.encode_ids(&huge);
+        assert_eq!(ids.len(), 16);
+        assert_eq!(ids[0], START_TOKEN);
+        assert_eq!(ids[15], END_TOKEN);
+        assert!(!ids.contains(&PAD_TOKEN));
+
+        for policy in [TruncationPolicy::Truncate, TruncationPolicy::Summarize] {
+            let encoder = ExpressionEncoder::new(Device::Cpu)
+                .with_max_length(16)
+                .with_truncation(policy);
+
+            // Never longer than max_length, and the same every time
+            assert!(encoder.tokenize_fitted(&huge).len() <= 14);
+            assert_eq!(encoder.encode_ids(&huge), encoder.encode_ids(&huge));
+
+            // A small expression is unaffected
+            assert_eq!(encoder.tokenize_fitted(&small), encoder.tokenize(&small));
+            assert_eq!(encoder.encode_ids(&small).len(), 16);
+        }
+    }
+
+    #[test]
+    fn test_summarize_keeps_outer_structure() {
+        let encoder = ExpressionEncoder::new(Device::Cpu)
+            .with_max_length(16)
+            .with_truncation(TruncationPolicy::Summarize);
+
+        let tokens = encoder.tokenize_fitted(&long_sum(200));
+        assert!(tokens.len() <= 14);
+        assert!(tokens.contains(&SUB_TOKEN.to_string()));
+        // Parentheses stay balanced, unlike a plain cut
+        let open = tokens.iter().filter(|t| *t == "(").count();
+        let close = tokens.iter().filter(|t| *t == ")").count();
+        assert_eq!(open, close);
+        assert_eq!(tokens.last().unwrap(), ")");
+    }
 }
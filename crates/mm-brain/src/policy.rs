@@ -6,9 +6,11 @@
 
 //! Policy network for rule selection.
 
-use candle_core::{Device, Result};
+use candle_core::{DType, Device, Result};
+use candle_nn::{VarBuilder, VarMap};
 use mm_core::Expr;
 use mm_rules::RuleId;
+use std::path::Path;
 
 use crate::encoder::ExpressionEncoder;
 use crate::network::{MathNetwork, NetworkConfig};
@@ -41,6 +43,29 @@ impl PolicyNetwork {
         })
     }
 
+    /// Wrap an existing network, e.g. one restored from a checkpoint.
+    pub fn from_network(network: MathNetwork, device: Device) -> Self {
+        let encoder = ExpressionEncoder::new(device.clone());
+
+        Self {
+            network,
+            encoder,
+            device,
+        }
+    }
+
+    /// Load weights saved by [`Trainer::save`](crate::Trainer::save).
+    ///
+    /// `config` must match the network the checkpoint was trained with.
+    pub fn load<P: AsRef<Path>>(path: P, config: NetworkConfig, device: Device) -> Result<Self> {
+        let mut varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &device);
+        let network = MathNetwork::new_with_vb(config, vb)?;
+        varmap.load(path)?;
+
+        Ok(Self::from_network(network, device))
+    }
+
     /// Get rule probabilities for an expression.
     ///
     /// Returns a vector of probabilities, one per rule.
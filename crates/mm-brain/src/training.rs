@@ -6,9 +6,10 @@
 
 //! Training utilities for the neural network.
 
-use candle_core::{DType, Device, Result, Tensor};
+use candle_core::{DType, Device, Result, Tensor, D};
 use candle_nn::optim::{AdamW, ParamsAdamW};
 use candle_nn::{Optimizer, VarMap};
+use rand::prelude::*;
 
 use crate::encoder::ExpressionEncoder;
 use crate::network::{MathNetwork, NetworkConfig};
@@ -24,8 +25,24 @@ pub struct TrainingConfig {
     pub batch_size: usize,
     /// Number of epochs.
     pub epochs: usize,
-    /// Value loss weight (policy loss weight is 1.0).
+    /// Seed for the per-epoch shuffle, so runs are reproducible.
+    pub seed: u64,
+    /// Fraction of the examples held out for validation; 0.0 disables
+    /// validation and early stopping.
+    pub validation_split: f32,
+    /// Epochs without a better validation loss before training stops.
+    pub patience: usize,
+    /// Policy loss weight.
+    pub policy_weight: f32,
+    /// Value loss weight.
     pub value_weight: f32,
+    /// Weight of the policy entropy bonus; 0.0 disables it.
+    pub entropy_coeff: f32,
+    /// Clip gradients to this global L2 norm. `None` disables clipping.
+    pub max_grad_norm: Option<f32>,
+    /// Discount γ for value targets along a solution path: a state `k`
+    /// steps from the solved form is labelled `γ^k`.
+    pub value_discount: f32,
 }
 
 impl Default for TrainingConfig {
@@ -35,7 +52,14 @@ impl Default for TrainingConfig {
             weight_decay: 0.01,
             batch_size: 32,
             epochs: 100,
+            seed: 42,
+            validation_split: 0.0,
+            patience: 5,
+            policy_weight: 1.0,
             value_weight: 0.5,
+            entropy_coeff: 0.0,
+            max_grad_norm: None,
+            value_discount: 0.9,
         }
     }
 }
@@ -47,6 +71,9 @@ pub struct TrainingExample {
     pub tokens: Vec<u32>,
     /// Target rule probabilities (one-hot or soft).
     pub target_rule: u32,
+    /// Target probability of `target_rule`: 1.0 for a rule to take, lower
+    /// for one that leads away from the goal.
+    pub rule_target: f32,
     /// Target value (-1 to 1).
     pub target_value: f32,
 }
@@ -59,6 +86,8 @@ pub struct Trainer {
     encoder: ExpressionEncoder,
     config: TrainingConfig,
     device: Device,
+    skipped_updates: usize,
+    rng: StdRng,
 }
 
 impl Trainer {
@@ -87,8 +116,10 @@ impl Trainer {
             network,
             optimizer,
             encoder,
+            rng: StdRng::seed_from_u64(training_config.seed),
             config: training_config,
             device,
+            skipped_updates: 0,
         })
     }
 
@@ -96,6 +127,51 @@ impl Trainer {
     ///
     /// Returns (policy_loss, value_loss).
     pub fn train_step(&mut self, examples: &[TrainingExample]) -> Result<(f32, f32)> {
+        let (policy_loss, value_loss, total_loss) = self.losses(examples)?;
+
+        // Backward pass
+        let mut grads = total_loss.backward()?;
+        let vars = self.varmap.all_vars();
+        let mut squared_norm = 0f32;
+        for var in &vars {
+            if let Some(grad) = grads.get(var) {
+                squared_norm += grad.sqr()?.sum_all()?.to_scalar::<f32>()?;
+            }
+        }
+        let grad_norm = squared_norm.sqrt();
+        let loss_val: f32 = total_loss.to_scalar()?;
+
+        // A non-finite update would corrupt every weight it touches
+        if !loss_val.is_finite() || !grad_norm.is_finite() {
+            self.skipped_updates += 1;
+            println!(
+                "Skipping update: loss={}, grad_norm={}",
+                loss_val, grad_norm
+            );
+        } else {
+            if let Some(max_norm) = self.config.max_grad_norm {
+                if grad_norm > max_norm {
+                    let scale = (max_norm / grad_norm) as f64;
+                    for var in &vars {
+                        if let Some(grad) = grads.get(var) {
+                            let clipped = (grad * scale)?;
+                            grads.insert(var, clipped);
+                        }
+                    }
+                }
+            }
+            self.optimizer.step(&grads)?;
+        }
+
+        let policy_loss_val: f32 = policy_loss.to_scalar()?;
+        let value_loss_val: f32 = value_loss.to_scalar()?;
+
+        Ok((policy_loss_val, value_loss_val))
+    }
+
+    /// Weighted losses of the network on `examples`, as
+    /// `(policy_loss, value_loss, total_loss)`.
+    fn losses(&self, examples: &[TrainingExample]) -> Result<(Tensor, Tensor, Tensor)> {
         let batch_size = examples.len();
         let seq_len = self.encoder.max_length();
 
@@ -110,55 +186,122 @@ impl Trainer {
             .collect();
 
         let target_rules: Vec<u32> = examples.iter().map(|e| e.target_rule).collect();
+        let rule_targets: Vec<f32> = examples.iter().map(|e| e.rule_target).collect();
         let target_values: Vec<f32> = examples.iter().map(|e| e.target_value).collect();
 
         // Create tensors
         let tokens =
             Tensor::new(tokens.as_slice(), &self.device)?.reshape((batch_size, seq_len))?;
-        let target_rules = Tensor::new(target_rules.as_slice(), &self.device)?;
+        let target_rules =
+            Tensor::new(target_rules.as_slice(), &self.device)?.reshape((batch_size, 1))?;
+        let rule_targets = Tensor::new(rule_targets.as_slice(), &self.device)?;
         let target_values =
             Tensor::new(target_values.as_slice(), &self.device)?.reshape((batch_size, 1))?;
 
         // Forward pass
         let (policy_logits, values) = self.network.forward(&tokens)?;
 
-        // Policy loss: cross-entropy
-        let policy_loss = candle_nn::loss::cross_entropy(&policy_logits, &target_rules)?;
+        // Policy loss: binary cross-entropy on the labelled rule's probability.
+        // A target of 1.0 is ordinary cross-entropy; 0.0 pushes the rule down.
+        let log_softmax = candle_nn::ops::log_softmax(&policy_logits, D::Minus1)?;
+        let log_probs = log_softmax.gather(&target_rules, 1)?.squeeze(1)?;
+        let log_not_probs = log_probs
+            .exp()?
+            .affine(-1.0, 1.0)?
+            .clamp(1e-7f32, 1.0f32)?
+            .log()?;
+        let policy_loss = ((&rule_targets * &log_probs)?
+            + (rule_targets.affine(-1.0, 1.0)? * &log_not_probs)?)?
+            .mean_all()?
+            .neg()?;
 
         // Value loss: MSE
         let value_loss = candle_nn::loss::mse(&values, &target_values)?;
 
-        // Combined loss
-        let total_loss = (&policy_loss + &value_loss * self.config.value_weight as f64)?;
+        // Combined loss. A zero weight leaves its term out of the graph, so
+        // that head gets no gradient at all.
+        let mut total_loss = (&policy_loss * self.config.policy_weight as f64)?;
+        if self.config.value_weight != 0.0 {
+            total_loss = (total_loss + (&value_loss * self.config.value_weight as f64)?)?;
+        }
+        if self.config.entropy_coeff != 0.0 {
+            // Subtracting the entropy rewards a less peaked policy
+            let entropy = (log_softmax.exp()? * &log_softmax)?
+                .sum(D::Minus1)?
+                .mean_all()?
+                .neg()?;
+            total_loss = (total_loss - (entropy * self.config.entropy_coeff as f64)?)?;
+        }
 
-        // Backward pass
-        self.optimizer.backward_step(&total_loss)?;
+        Ok((policy_loss, value_loss, total_loss))
+    }
 
-        let policy_loss_val: f32 = policy_loss.to_scalar()?;
-        let value_loss_val: f32 = value_loss.to_scalar()?;
+    /// Weighted total loss on `examples`, without updating the weights.
+    pub fn evaluate(&self, examples: &[TrainingExample]) -> Result<f32> {
+        let (_, _, total_loss) = self.losses(examples)?;
+        total_loss.to_scalar()
+    }
 
-        Ok((policy_loss_val, value_loss_val))
+    /// Number of updates skipped because the loss or gradient was not
+    /// finite.
+    pub fn skipped_updates(&self) -> usize {
+        self.skipped_updates
+    }
+
+    /// Shuffle `0..num_examples` and split it into mini-batches for one
+    /// epoch.
+    ///
+    /// The order comes from the seeded RNG, so two trainers with the same
+    /// [`seed`](TrainingConfig::seed) produce the same sequence of epochs.
+    pub fn epoch_batches(&mut self, num_examples: usize) -> Vec<Vec<usize>> {
+        let mut order: Vec<usize> = (0..num_examples).collect();
+        order.shuffle(&mut self.rng);
+        order
+            .chunks(self.config.batch_size.max(1))
+            .map(|chunk| chunk.to_vec())
+            .collect()
     }
 
     /// Train on all examples for multiple epochs.
+    ///
+    /// With a nonzero [`validation_split`](TrainingConfig::validation_split)
+    /// a seeded random subset is held out. Training stops once the
+    /// validation loss has not improved for
+    /// [`patience`](TrainingConfig::patience) epochs, and the weights from
+    /// the best epoch are restored.
+    ///
+    /// Returns the mean (policy_loss, value_loss) of each epoch trained.
     pub fn train(&mut self, examples: &[TrainingExample]) -> Result<Vec<(f32, f32)>> {
         let mut history = Vec::new();
 
+        let num_validation = (examples.len() as f32 * self.config.validation_split) as usize;
+        let mut order: Vec<usize> = (0..examples.len()).collect();
+        // Only spend randomness on the split when something is held out,
+        // so the batch order does not depend on the split being enabled
+        if num_validation > 0 {
+            order.shuffle(&mut self.rng);
+        }
+        let (validation, training) = order.split_at(num_validation);
+        let validation: Vec<TrainingExample> =
+            validation.iter().map(|&i| examples[i].clone()).collect();
+
+        let vars = self.varmap.all_vars();
+        let mut best_loss = f32::INFINITY;
+        let mut best_weights: Option<Vec<Tensor>> = None;
+        let mut stale_epochs = 0;
+
         for epoch in 0..self.config.epochs {
             let mut epoch_policy_loss = 0.0;
             let mut epoch_value_loss = 0.0;
             let mut num_batches = 0;
 
-            // Simple batching (no shuffle for simplicity)
-            for batch_start in (0..examples.len()).step_by(self.config.batch_size) {
-                let batch_end = (batch_start + self.config.batch_size).min(examples.len());
-                let batch = &examples[batch_start..batch_end];
-
-                if batch.is_empty() {
-                    continue;
-                }
+            for indices in self.epoch_batches(training.len()) {
+                let batch: Vec<TrainingExample> = indices
+                    .into_iter()
+                    .map(|i| examples[training[i]].clone())
+                    .collect();
 
-                let (policy_loss, value_loss) = self.train_step(batch)?;
+                let (policy_loss, value_loss) = self.train_step(&batch)?;
                 epoch_policy_loss += policy_loss;
                 epoch_value_loss += value_loss;
                 num_batches += 1;
@@ -177,6 +320,35 @@ impl Trainer {
                     epoch, epoch_policy_loss, epoch_value_loss
                 );
             }
+
+            if validation.is_empty() {
+                continue;
+            }
+            let validation_loss = self.evaluate(&validation)?;
+            if validation_loss < best_loss {
+                best_loss = validation_loss;
+                stale_epochs = 0;
+                best_weights = Some(
+                    vars.iter()
+                        .map(|var| var.as_tensor().copy())
+                        .collect::<Result<_>>()?,
+                );
+            } else {
+                stale_epochs += 1;
+                if stale_epochs >= self.config.patience {
+                    println!(
+                        "Early stopping at epoch {}: best validation_loss={:.4}",
+                        epoch, best_loss
+                    );
+                    break;
+                }
+            }
+        }
+
+        if let Some(weights) = best_weights {
+            for (var, weights) in vars.iter().zip(weights) {
+                var.set(&weights)?;
+            }
         }
 
         Ok(history)
@@ -237,6 +409,7 @@ mod tests {
         let example = TrainingExample {
             tokens: vec![1, 26, 4, 27, 2], // <START> 0 + 1 <END>
             target_rule: 0,                // const_fold
+            rule_target: 1.0,              // Rule to take
             target_value: 1.0,             // Good state
         };
 
@@ -245,4 +418,147 @@ mod tests {
         assert!(policy_loss >= 0.0);
         assert!(value_loss >= 0.0);
     }
+
+    #[test]
+    fn test_zero_value_weight_freezes_value_head() {
+        let config = TrainingConfig {
+            value_weight: 0.0,
+            entropy_coeff: 0.01,
+            ..TrainingConfig::default()
+        };
+        let mut trainer = Trainer::new(NetworkConfig::default(), config, Device::Cpu).unwrap();
+        let weights = |trainer: &Trainer, name: &str| -> Vec<f32> {
+            let data = trainer.varmap.data().lock().unwrap();
+            data[name]
+                .as_tensor()
+                .flatten_all()
+                .unwrap()
+                .to_vec1()
+                .unwrap()
+        };
+
+        let example = TrainingExample {
+            tokens: vec![1, 26, 4, 27, 2],
+            target_rule: 0,
+            rule_target: 1.0,
+            target_value: 1.0,
+        };
+        let value_before = weights(&trainer, "value.weight");
+        let policy_before = weights(&trainer, "policy.weight");
+        trainer.train_step(&[example]).unwrap();
+
+        assert_eq!(weights(&trainer, "value.weight"), value_before);
+        assert_ne!(weights(&trainer, "policy.weight"), policy_before);
+    }
+
+    #[test]
+    fn test_extreme_targets_keep_weights_finite() {
+        let config = TrainingConfig {
+            max_grad_norm: Some(1.0),
+            ..TrainingConfig::default()
+        };
+        let mut trainer = Trainer::new(NetworkConfig::default(), config, Device::Cpu).unwrap();
+        let example = |target_value| TrainingExample {
+            tokens: vec![1, 26, 4, 27, 2],
+            target_rule: 0,
+            rule_target: 1.0,
+            target_value,
+        };
+        let all_finite = |trainer: &Trainer| {
+            trainer.varmap.all_vars().iter().all(|var| {
+                let values: Vec<f32> = var.as_tensor().flatten_all().unwrap().to_vec1().unwrap();
+                values.iter().all(|v| v.is_finite())
+            })
+        };
+
+        // A huge but finite gradient is clipped
+        trainer.train_step(&[example(1e15)]).unwrap();
+        assert_eq!(trainer.skipped_updates(), 0);
+        assert!(all_finite(&trainer));
+
+        // An overflowing loss skips the update
+        trainer.train_step(&[example(f32::MAX)]).unwrap();
+        assert_eq!(trainer.skipped_updates(), 1);
+        assert!(all_finite(&trainer));
+    }
+
+    #[test]
+    fn test_epoch_batches_are_seeded() {
+        let trainer = |seed| {
+            let config = TrainingConfig {
+                batch_size: 4,
+                seed,
+                ..TrainingConfig::default()
+            };
+            Trainer::new(NetworkConfig::default(), config, Device::Cpu).unwrap()
+        };
+        let (mut a, mut b, mut c) = (trainer(7), trainer(7), trainer(8));
+
+        let epochs_a: Vec<_> = (0..3).map(|_| a.epoch_batches(10)).collect();
+        let epochs_b: Vec<_> = (0..3).map(|_| b.epoch_batches(10)).collect();
+        let epochs_c: Vec<_> = (0..3).map(|_| c.epoch_batches(10)).collect();
+        assert_eq!(epochs_a, epochs_b);
+        assert_ne!(epochs_a, epochs_c);
+
+        // Every example appears once per epoch, in batches of at most 4
+        let batches = &epochs_a[0];
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [4, 4, 2]);
+        let mut seen: Vec<usize> = batches.concat();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        // and the order changes between epochs
+        assert_ne!(epochs_a[0], epochs_a[1]);
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        // With a zero learning rate the validation loss never improves
+        let config = TrainingConfig {
+            learning_rate: 0.0,
+            epochs: 50,
+            batch_size: 4,
+            validation_split: 0.25,
+            patience: 3,
+            ..TrainingConfig::default()
+        };
+        let mut trainer = Trainer::new(NetworkConfig::default(), config, Device::Cpu).unwrap();
+        let examples: Vec<TrainingExample> = (0..8)
+            .map(|i| TrainingExample {
+                tokens: vec![1, 26 + i, 2],
+                target_rule: i,
+                rule_target: 1.0,
+                target_value: 0.5,
+            })
+            .collect();
+
+        let history = trainer.train(&examples).unwrap();
+        // One epoch sets the best loss, then three without improvement
+        assert_eq!(history.len(), 4);
+    }
+
+    #[test]
+    fn test_no_validation_split_keeps_batch_order() {
+        let config = TrainingConfig {
+            learning_rate: 0.0,
+            epochs: 1,
+            batch_size: 4,
+            ..TrainingConfig::default()
+        };
+        let mut trained =
+            Trainer::new(NetworkConfig::default(), config.clone(), Device::Cpu).unwrap();
+        let mut fresh = Trainer::new(NetworkConfig::default(), config, Device::Cpu).unwrap();
+        let examples: Vec<TrainingExample> = (0..8)
+            .map(|i| TrainingExample {
+                tokens: vec![1, 26 + i, 2],
+                target_rule: i,
+                rule_target: 1.0,
+                target_value: 0.5,
+            })
+            .collect();
+
+        // Training one epoch draws only that epoch's batch order
+        trained.train(&examples).unwrap();
+        fresh.epoch_batches(examples.len());
+        assert_eq!(trained.epoch_batches(10), fresh.epoch_batches(10));
+    }
 }
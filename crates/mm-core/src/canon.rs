@@ -11,8 +11,11 @@
 //!
 //! For example: `x + 1` and `1 + x` both canonicalize to the same form.
 
+use crate::proof::Constraint;
 use crate::{Expr, Factor, Rational, Symbol, Term};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 impl Expr {
     /// Convert this expression to canonical form.
@@ -30,6 +33,15 @@ impl Expr {
         self.canonicalize_with_depth(0)
     }
 
+    /// Hash of the canonical form.
+    ///
+    /// `Hash` on `Expr` is structural, like `==`, so `x + 1` and `1 + x` are
+    /// distinct set entries. Expressions with equal canonical forms share
+    /// this hash, which makes it a key for deduplicating up to equivalence.
+    pub fn canonical_hash(&self) -> u64 {
+        hash_of(&self.canonicalize())
+    }
+
     /// Maximum recursion depth for canonicalization to prevent stack overflow.
     const MAX_CANON_DEPTH: usize = 100;
 
@@ -67,10 +79,17 @@ impl Expr {
 
         match self {
             // Atoms don't need simplification
-            Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E => self.clone(),
+            Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => {
+                self.clone()
+            }
 
             // Unary operations
-            Expr::Neg(e) => Expr::Neg(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Neg(e) => match e.canonicalize_with_depth(depth) {
+                Expr::Infinity { negative } => Expr::Infinity {
+                    negative: !negative,
+                },
+                inner => Expr::Neg(Box::new(inner)),
+            },
             Expr::Sqrt(e) => Expr::Sqrt(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Sin(e) => Expr::Sin(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Cos(e) => Expr::Cos(Box::new(e.canonicalize_with_depth(depth))),
@@ -78,6 +97,12 @@ impl Expr {
             Expr::Arcsin(e) => Expr::Arcsin(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Arccos(e) => Expr::Arccos(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Arctan(e) => Expr::Arctan(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Sinh(e) => Expr::Sinh(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Cosh(e) => Expr::Cosh(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Tanh(e) => Expr::Tanh(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Arcsinh(e) => Expr::Arcsinh(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Arccosh(e) => Expr::Arccosh(Box::new(e.canonicalize_with_depth(depth))),
+            Expr::Arctanh(e) => Expr::Arctanh(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Ln(e) => Expr::Ln(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Exp(e) => Expr::Exp(Box::new(e.canonicalize_with_depth(depth))),
             Expr::Abs(e) => Expr::Abs(Box::new(e.canonicalize_with_depth(depth))),
@@ -133,6 +158,17 @@ impl Expr {
                 expr: Box::new(expr.canonicalize_with_depth(depth)),
                 var: *var,
             },
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => Expr::Limit {
+                expr: Box::new(expr.canonicalize_with_depth(depth)),
+                var: *var,
+                approaching: Box::new(approaching.canonicalize_with_depth(depth)),
+                direction: *direction,
+            },
 
             // Equation
             Expr::Equation { lhs, rhs } => Expr::Equation {
@@ -229,6 +265,21 @@ impl Expr {
                 Box::new(a.canonicalize_with_depth(depth)),
                 Box::new(b.canonicalize_with_depth(depth)),
             ),
+
+            // Case analysis: branch order is significant
+            Expr::Piecewise(branches) => Expr::Piecewise(
+                branches
+                    .iter()
+                    .map(|(condition, value)| {
+                        (
+                            Constraint {
+                                expr: condition.expr.canonicalize_with_depth(depth),
+                            },
+                            value.canonicalize_with_depth(depth),
+                        )
+                    })
+                    .collect(),
+            ),
         }
     }
 
@@ -351,7 +402,9 @@ impl Expr {
                 // Constant folding for integer exponents
                 if let (Expr::Const(r), Expr::Const(e)) = (base.as_ref(), exp.as_ref()) {
                     if e.is_integer() && e.numer().abs() <= 10 {
-                        return Expr::Const(r.pow(e.numer() as i32));
+                        if let Some(value) = r.checked_pow(e.numer() as i32) {
+                            return Expr::Const(value);
+                        }
                     }
                 }
                 self.clone()
@@ -359,12 +412,20 @@ impl Expr {
 
             // Sum: collect like terms and sort
             Expr::Sum(terms) => {
+                // Numeric factors go into the coefficient, so `2*x` with
+                // coefficient 1 and `x` with coefficient 2 collect together
+                let mut split = Vec::with_capacity(terms.len());
+                for term in terms {
+                    split_term(term.coeff, &term.expr, &mut split);
+                }
+
                 // Collect like terms
                 let mut term_map: HashMap<Expr, Rational> = HashMap::new();
-                for term in terms {
-                    *term_map
-                        .entry(term.expr.clone())
-                        .or_insert(Rational::from_integer(0)) = term_map[&term.expr] + term.coeff;
+                for term in split {
+                    term_map
+                        .entry(term.expr)
+                        .and_modify(|coeff| *coeff = *coeff + term.coeff)
+                        .or_insert(term.coeff);
                 }
 
                 // Remove zero terms
@@ -386,6 +447,9 @@ impl Expr {
                     if term.coeff.is_one() {
                         return term.expr.clone();
                     }
+                    if term.expr.is_one() {
+                        return Expr::Const(term.coeff);
+                    }
                 }
 
                 Expr::Sum(new_terms)
@@ -440,11 +504,148 @@ impl Expr {
     }
 }
 
+/// Push `coeff * expr` onto `out` as terms whose expressions carry no
+/// numeric factor. Constants become coefficients of `1`, and nested sums are
+/// flattened.
+fn split_term(coeff: Rational, expr: &Expr, out: &mut Vec<Term>) {
+    match expr {
+        Expr::Neg(e) => split_term(-coeff, e, out),
+        Expr::Sum(terms) => {
+            for term in terms {
+                split_term(coeff * term.coeff, &term.expr, out);
+            }
+        }
+        _ => {
+            let (factor, rest) = split_coefficient(expr);
+            out.push(Term {
+                coeff: coeff * factor,
+                expr: rest,
+            });
+        }
+    }
+}
+
+/// Split a canonical product into its numeric factor and the rest:
+/// `2 * x * 3` gives `(6, x)`, `x / 4` gives `(1/4, x)` and `5` gives `(5, 1)`.
+fn split_coefficient(expr: &Expr) -> (Rational, Expr) {
+    match expr {
+        Expr::Const(r) => (*r, Expr::int(1)),
+        Expr::Neg(e) => {
+            let (factor, rest) = split_coefficient(e);
+            (-factor, rest)
+        }
+        Expr::Mul(a, b) => {
+            let (fa, ra) = split_coefficient(a);
+            let (fb, rb) = split_coefficient(b);
+            let rest = if ra.is_one() {
+                rb
+            } else if rb.is_one() {
+                ra
+            } else if ra > rb {
+                Expr::Mul(Box::new(rb), Box::new(ra))
+            } else {
+                Expr::Mul(Box::new(ra), Box::new(rb))
+            };
+            (fa * fb, rest)
+        }
+        Expr::Div(a, b) => match b.as_ref() {
+            Expr::Const(r) if !r.is_zero() => {
+                let (factor, rest) = split_coefficient(a);
+                (factor / *r, rest)
+            }
+            _ => (Rational::from_integer(1), expr.clone()),
+        },
+        _ => (Rational::from_integer(1), expr.clone()),
+    }
+}
+
+/// Memoized [`Expr::canonicalize`].
+///
+/// Search canonicalizes the same states over and over: once to deduplicate
+/// them, again to rank them, again to report them. A cache kept for the
+/// length of one search computes each canonical form once.
+#[derive(Debug, Clone, Default)]
+pub struct CanonCache {
+    forms: HashMap<Expr, Expr>,
+    hits: usize,
+}
+
+impl CanonCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The canonical form of `expr`, computed on first request only.
+    pub fn canonicalize(&mut self, expr: &Expr) -> Expr {
+        if let Some(form) = self.forms.get(expr) {
+            self.hits += 1;
+            return form.clone();
+        }
+        let form = expr.canonicalize();
+        self.forms.insert(expr.clone(), form.clone());
+        form
+    }
+
+    /// [`Expr::canonical_hash`], reusing cached canonical forms.
+    pub fn canonical_hash(&mut self, expr: &Expr) -> u64 {
+        hash_of(&self.canonicalize(expr))
+    }
+
+    /// How many canonical forms were actually computed.
+    pub fn computed(&self) -> usize {
+        self.forms.len()
+    }
+
+    /// How many requests were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+}
+
+fn hash_of(expr: &Expr) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    expr.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::SymbolTable;
 
+    #[test]
+    fn test_canon_cache() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let expr = Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::int(0)));
+        let other = Expr::Mul(Box::new(Expr::int(4)), Box::new(Expr::int(5)));
+
+        let mut cache = CanonCache::new();
+        for _ in 0..10 {
+            assert_eq!(cache.canonicalize(&expr), Expr::Var(x));
+        }
+        assert_eq!(cache.canonicalize(&other), Expr::int(20));
+        assert_eq!(cache.computed(), 2);
+        assert_eq!(cache.hits(), 9);
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        let mut symbols = SymbolTable::new();
+        let x = Expr::Var(symbols.intern("x"));
+        let sum = |a: &Expr, b: &Expr| Expr::Add(Box::new(a.clone()), Box::new(b.clone()));
+
+        let a = sum(&x, &Expr::int(1));
+        let b = sum(&Expr::int(1), &x);
+        assert_ne!(a, b);
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        assert_ne!(a.canonical_hash(), sum(&x, &Expr::int(2)).canonical_hash());
+
+        let mut cache = CanonCache::new();
+        assert_eq!(cache.canonical_hash(&b), a.canonical_hash());
+    }
+
     #[test]
     fn test_constant_folding() {
         // 2 + 3 = 5
@@ -503,4 +704,68 @@ mod tests {
 
         assert_eq!(expr1.canonicalize(), expr2.canonicalize());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sum_coefficient_split() {
+        let mut symbols = SymbolTable::new();
+        let x = Expr::Var(symbols.intern("x"));
+        let y = Expr::Var(symbols.intern("y"));
+        let term = |coeff: i64, expr: Expr| Term {
+            coeff: Rational::from_integer(coeff),
+            expr,
+        };
+        let two_x = Expr::Mul(Box::new(Expr::int(2)), Box::new(x.clone()));
+        let x_two = Expr::Mul(Box::new(x.clone()), Box::new(Expr::int(2)));
+
+        // 2x + y + 3, split every which way
+        let expected = Expr::Sum(vec![
+            term(2, x.clone()),
+            term(1, y.clone()),
+            term(3, Expr::int(1)),
+        ])
+        .canonicalize();
+        let variants = [
+            Expr::Sum(vec![
+                term(1, two_x.clone()),
+                term(1, y.clone()),
+                term(1, Expr::int(3)),
+            ]),
+            Expr::Sum(vec![
+                term(1, x_two),
+                term(1, y.clone()),
+                term(3, Expr::int(1)),
+            ]),
+            Expr::Sum(vec![
+                term(-1, Expr::Neg(Box::new(two_x.clone()))),
+                term(1, Expr::int(3)),
+                term(-1, Expr::Neg(Box::new(y.clone()))),
+            ]),
+            Expr::Sum(vec![
+                term(4, Expr::Div(Box::new(x.clone()), Box::new(Expr::int(2)))),
+                term(1, y.clone()),
+                term(1, Expr::int(3)),
+            ]),
+            Expr::Sum(vec![
+                term(1, Expr::Sum(vec![term(2, x.clone()), term(1, y.clone())])),
+                term(3, Expr::int(1)),
+            ]),
+        ];
+        for variant in variants {
+            assert_eq!(variant.canonicalize(), expected);
+        }
+        let Expr::Sum(terms) = &expected else {
+            panic!("Expected a sum, got {:?}", expected);
+        };
+        assert!(terms.iter().all(|t| split_coefficient(&t.expr).0.is_one()));
+
+        // A lone scaled term folds away
+        assert_eq!(
+            Expr::Sum(vec![term(1, two_x.clone()), term(-1, x.clone())]).canonicalize(),
+            x
+        );
+        assert_eq!(
+            Expr::Sum(vec![term(2, Expr::int(3))]).canonicalize(),
+            Expr::int(6)
+        );
+    }
+}
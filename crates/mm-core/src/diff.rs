@@ -0,0 +1,332 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Structural differences between expressions.
+//!
+//! Used to point at the part of an expression a step rewrote:
+//!
+//! ```text
+//! d/dx(x²) + 3   vs   2 · x + 3   →   [DiffPoint { path: [0], .. }]
+//! ```
+
+use crate::{Expr, Symbol};
+use std::collections::HashMap;
+
+/// A subtree that differs between two expressions.
+#[derive(Debug, Clone)]
+pub struct DiffPoint {
+    /// Child indices from the root down to the changed node.
+    pub path: Vec<usize>,
+    /// The subtree in the original expression.
+    pub before: Expr,
+    /// The subtree in the other expression.
+    pub after: Expr,
+}
+
+impl Expr {
+    /// Find the outermost subtrees where `self` and `other` differ.
+    ///
+    /// Nodes of the same shape are compared child by child, so only the
+    /// changed parts are reported. A node whose operator, arity or attached
+    /// data (constant value, bound variable) changed is reported whole.
+    /// Returns an empty list for structurally equal expressions.
+    pub fn diff(&self, other: &Expr) -> Vec<DiffPoint> {
+        let mut points = Vec::new();
+        diff_at(self, other, &mut Vec::new(), &mut points);
+        points
+    }
+
+    /// Whether `self` and `other` are equal up to a consistent renaming of
+    /// variables, e.g. `x² + x` and `y² + y`.
+    ///
+    /// The renaming must be a bijection: `x² + x` is not equivalent to
+    /// `x² + y`, and `x + y` is not equivalent to `x + x`. Bound variables of
+    /// derivatives, integrals, sums and quantifiers take part in the same
+    /// renaming. The comparison is structural, so callers wanting equality
+    /// modulo algebra should canonicalize both sides first.
+    pub fn alpha_equivalent(&self, other: &Expr) -> bool {
+        let mut renaming = Renaming::default();
+        alpha_at(self, other, &mut renaming)
+    }
+}
+
+/// A partial bijection between the variables of two expressions.
+#[derive(Default)]
+struct Renaming {
+    forward: HashMap<Symbol, Symbol>,
+    backward: HashMap<Symbol, Symbol>,
+}
+
+impl Renaming {
+    /// Record `x ↦ y`, failing if it clashes with an earlier pairing.
+    fn bind(&mut self, x: Symbol, y: Symbol) -> bool {
+        let fwd = *self.forward.entry(x).or_insert(y);
+        let bwd = *self.backward.entry(y).or_insert(x);
+        fwd == y && bwd == x
+    }
+}
+
+fn alpha_at(a: &Expr, b: &Expr, renaming: &mut Renaming) -> bool {
+    let heads_match = match (a, b) {
+        (Expr::Var(x), Expr::Var(y)) => renaming.bind(*x, *y),
+        (
+            Expr::Limit {
+                var: x,
+                direction: d,
+                ..
+            },
+            Expr::Limit {
+                var: y,
+                direction: e,
+                ..
+            },
+        ) => d == e && renaming.bind(*x, *y),
+        (Expr::Derivative { var: x, .. }, Expr::Derivative { var: y, .. })
+        | (Expr::Integral { var: x, .. }, Expr::Integral { var: y, .. })
+        | (Expr::Summation { var: x, .. }, Expr::Summation { var: y, .. })
+        | (Expr::BigProduct { var: x, .. }, Expr::BigProduct { var: y, .. })
+        | (Expr::ForAll { var: x, .. }, Expr::ForAll { var: y, .. })
+        | (Expr::Exists { var: x, .. }, Expr::Exists { var: y, .. }) => renaming.bind(*x, *y),
+        _ => same_head(a, b),
+    };
+    let (left, right) = (children(a), children(b));
+    heads_match
+        && left.len() == right.len()
+        && left
+            .into_iter()
+            .zip(right)
+            .all(|(x, y)| alpha_at(x, y, renaming))
+}
+
+fn diff_at(a: &Expr, b: &Expr, path: &mut Vec<usize>, points: &mut Vec<DiffPoint>) {
+    let (left, right) = (children(a), children(b));
+    if !same_head(a, b) || left.len() != right.len() {
+        points.push(DiffPoint {
+            path: path.clone(),
+            before: a.clone(),
+            after: b.clone(),
+        });
+        return;
+    }
+    for (i, (x, y)) in left.into_iter().zip(right).enumerate() {
+        path.push(i);
+        diff_at(x, y, path, points);
+        path.pop();
+    }
+}
+
+/// Whether two nodes have the same operator and non-child data.
+pub(crate) fn same_head(a: &Expr, b: &Expr) -> bool {
+    if a.kind() != b.kind() {
+        return false;
+    }
+    match (a, b) {
+        (Expr::Const(x), Expr::Const(y)) => x == y,
+        (Expr::Var(x), Expr::Var(y)) => x == y,
+        (Expr::Infinity { negative: x }, Expr::Infinity { negative: y }) => x == y,
+        (Expr::Sum(x), Expr::Sum(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(s, t)| s.coeff == t.coeff)
+        }
+        (Expr::Derivative { var: x, .. }, Expr::Derivative { var: y, .. })
+        | (Expr::Integral { var: x, .. }, Expr::Integral { var: y, .. })
+        | (Expr::Summation { var: x, .. }, Expr::Summation { var: y, .. })
+        | (Expr::BigProduct { var: x, .. }, Expr::BigProduct { var: y, .. }) => x == y,
+        (
+            Expr::Limit {
+                var: x,
+                direction: d,
+                ..
+            },
+            Expr::Limit {
+                var: y,
+                direction: e,
+                ..
+            },
+        ) => x == y && d == e,
+        // A domain appearing or disappearing shows up as an arity change
+        (Expr::ForAll { var: x, .. }, Expr::ForAll { var: y, .. })
+        | (Expr::Exists { var: x, .. }, Expr::Exists { var: y, .. }) => x == y,
+        _ => true,
+    }
+}
+
+/// Direct subexpressions of `$expr`, in a fixed order, borrowed through
+/// `$iter` and `$as_ref` so the shared and mutable walks stay in step.
+macro_rules! children {
+    ($expr:expr, $iter:ident, $as_ref:ident $(, $mut:tt)?) => {
+        match $expr {
+            Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => vec![],
+            Expr::Neg(e)
+            | Expr::Sqrt(e)
+            | Expr::Sin(e)
+            | Expr::Cos(e)
+            | Expr::Tan(e)
+            | Expr::Arcsin(e)
+            | Expr::Arccos(e)
+            | Expr::Arctan(e)
+            | Expr::Sinh(e)
+            | Expr::Cosh(e)
+            | Expr::Tanh(e)
+            | Expr::Arcsinh(e)
+            | Expr::Arccosh(e)
+            | Expr::Arctanh(e)
+            | Expr::Ln(e)
+            | Expr::Exp(e)
+            | Expr::Abs(e)
+            | Expr::Floor(e)
+            | Expr::Ceiling(e)
+            | Expr::Factorial(e)
+            | Expr::Not(e) => vec![e],
+            Expr::Add(a, b)
+            | Expr::Sub(a, b)
+            | Expr::Mul(a, b)
+            | Expr::Div(a, b)
+            | Expr::Pow(a, b)
+            | Expr::GCD(a, b)
+            | Expr::LCM(a, b)
+            | Expr::Mod(a, b)
+            | Expr::Binomial(a, b)
+            | Expr::Gte(a, b)
+            | Expr::Gt(a, b)
+            | Expr::Lte(a, b)
+            | Expr::Lt(a, b)
+            | Expr::And(a, b)
+            | Expr::Or(a, b)
+            | Expr::Implies(a, b) => vec![a, b],
+            Expr::Equation { lhs, rhs } => vec![lhs, rhs],
+            Expr::Sum(terms) => terms.$iter().map(|t| &$($mut)? t.expr).collect(),
+            Expr::Product(factors) => factors
+                .$iter()
+                .flat_map(|f| [&$($mut)? f.base, &$($mut)? f.power])
+                .collect(),
+            Expr::Derivative { expr, .. } | Expr::Integral { expr, .. } => vec![expr],
+            Expr::Limit {
+                expr, approaching, ..
+            } => vec![expr, approaching],
+            Expr::Summation { from, to, body, .. } | Expr::BigProduct { from, to, body, .. } => {
+                vec![from, to, body]
+            }
+            Expr::ForAll { domain, body, .. } | Expr::Exists { domain, body, .. } => domain
+                .$iter()
+                .map(|d| d.$as_ref())
+                .chain([body.$as_ref()])
+                .collect(),
+            Expr::Piecewise(branches) => branches
+                .$iter()
+                .flat_map(|(condition, value)| [&$($mut)? condition.expr, value])
+                .collect(),
+        }
+    };
+}
+
+/// Direct subexpressions, in a fixed order.
+pub(crate) fn children(expr: &Expr) -> Vec<&Expr> {
+    children!(expr, iter, as_ref)
+}
+
+/// Direct subexpressions, mutably, in the same order as [`children`].
+pub(crate) fn children_mut(expr: &mut Expr) -> Vec<&mut Expr> {
+    children!(expr, iter_mut, as_mut, mut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LimitDirection, SymbolTable};
+
+    #[test]
+    fn test_diff_points_at_rewritten_operand() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let three = || Box::new(Expr::int(3));
+
+        // d/dx(x^2) + 3  vs  2*x + 3
+        let before = Expr::Add(
+            Box::new(Expr::Derivative {
+                expr: Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+                var: x,
+            }),
+            three(),
+        );
+        let after = Expr::Add(
+            Box::new(Expr::Mul(Box::new(Expr::int(2)), Box::new(Expr::Var(x)))),
+            three(),
+        );
+
+        let points = before.diff(&after);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].path, vec![0]);
+        assert!(matches!(points[0].before, Expr::Derivative { .. }));
+        assert!(matches!(points[0].after, Expr::Mul(_, _)));
+
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_descends_into_same_shape() {
+        let mut symbols = SymbolTable::new();
+        let p = symbols.intern("p");
+        let q = symbols.intern("q");
+
+        // ¬(p ∧ q) vs ¬(p ∧ p): only the second conjunct changed
+        let and = |b| Expr::Not(Box::new(Expr::And(Box::new(Expr::Var(p)), Box::new(b))));
+        let points = and(Expr::Var(q)).diff(&and(Expr::Var(p)));
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].path, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_alpha_equivalent() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let y = symbols.intern("y");
+        let square_plus = |a, b| {
+            Expr::Add(
+                Box::new(Expr::Pow(Box::new(Expr::Var(a)), Box::new(Expr::int(2)))),
+                Box::new(Expr::Var(b)),
+            )
+        };
+
+        // x² + x ≡ y² + y
+        assert!(square_plus(x, x).alpha_equivalent(&square_plus(y, y)));
+        // x² + x ≢ x² + y, in either direction
+        assert!(!square_plus(x, x).alpha_equivalent(&square_plus(x, y)));
+        assert!(!square_plus(x, y).alpha_equivalent(&square_plus(x, x)));
+        // x² + y ≡ y² + x
+        assert!(square_plus(x, y).alpha_equivalent(&square_plus(y, x)));
+        // Constants are not renamed
+        let shifted = Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::int(1)));
+        let other = Expr::Add(Box::new(Expr::Var(y)), Box::new(Expr::int(2)));
+        assert!(!shifted.alpha_equivalent(&other));
+
+        // Limits from different sides are not renamings of each other
+        let limit = |var, direction| Expr::Limit {
+            expr: Box::new(Expr::Var(var)),
+            var,
+            approaching: Box::new(Expr::int(0)),
+            direction,
+        };
+        assert!(limit(x, LimitDirection::Left).alpha_equivalent(&limit(y, LimitDirection::Left)));
+        assert!(!limit(x, LimitDirection::Left).alpha_equivalent(&limit(y, LimitDirection::Right)));
+    }
+
+    #[test]
+    fn test_children_mut_follows_children() {
+        let mut symbols = SymbolTable::new();
+        let mut expr = crate::parse::Parser::new(&mut symbols)
+            .parse("sum(i, 1, n, i^2) + sin(x) * y")
+            .unwrap()
+            .canonicalize();
+        let children: Vec<Expr> = expr.children().into_iter().cloned().collect();
+
+        for (i, child) in expr.children_mut().into_iter().enumerate() {
+            assert_eq!(*child, children[i]);
+            *child = Expr::int(i as i64);
+        }
+        let rewritten: Vec<Expr> = (0..children.len()).map(|i| Expr::int(i as i64)).collect();
+        assert_eq!(expr.children(), rewritten.iter().collect::<Vec<_>>());
+    }
+}
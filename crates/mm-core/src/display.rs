@@ -0,0 +1,1013 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Human-readable rendering of expressions.
+//!
+//! Expressions hold interned symbols, so rendering needs the [`SymbolTable`]
+//! that created them. Parentheses are only inserted where precedence
+//! requires them.
+//!
+//! ```text
+//! expr.display(&symbols)   →  ∀x. (x > 0 ⇒ x² > 0)
+//! expr.to_ascii(&symbols)  →  forall x. (x > 0 => x^2 > 0)
+//! expr.to_latex(&symbols)  →  \forall x.\ (x > 0 \Rightarrow x^{2} > 0)
+//! ```
+
+use crate::proof::Constraint;
+use crate::{Expr, LimitDirection, Rational, Symbol, SymbolTable};
+use std::fmt;
+
+/// Output notation for rendered expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// Plain text with Unicode math symbols (`√`, `²`, `∀`, `⇒`).
+    Unicode,
+    /// LaTeX source (`\sqrt{}`, `^{}`, `\forall`, `\Rightarrow`).
+    Latex,
+    /// Plain ASCII in the parser's input syntax (`sqrt()`, `^`, `*`, `>=`).
+    Ascii,
+}
+
+// Binding strength, loosest first.
+pub(crate) const PREC_QUANT: u8 = 0;
+pub(crate) const PREC_IMPLIES: u8 = 1;
+pub(crate) const PREC_OR: u8 = 2;
+pub(crate) const PREC_AND: u8 = 3;
+pub(crate) const PREC_NOT: u8 = 4;
+pub(crate) const PREC_REL: u8 = 5;
+pub(crate) const PREC_ADD: u8 = 6;
+pub(crate) const PREC_MUL: u8 = 7;
+pub(crate) const PREC_NEG: u8 = 8;
+pub(crate) const PREC_POW: u8 = 9;
+pub(crate) const PREC_POSTFIX: u8 = 10;
+pub(crate) const PREC_ATOM: u8 = 11;
+
+/// An expression paired with its symbol table, ready to format.
+///
+/// Created by [`Expr::display`] or [`Expr::display_as`].
+pub struct ExprDisplay<'a> {
+    expr: &'a Expr,
+    symbols: &'a SymbolTable,
+    notation: Notation,
+    decimals: Option<usize>,
+    implicit_multiplication: bool,
+}
+
+impl ExprDisplay<'_> {
+    /// Write non-integer rational constants as decimals rounded to
+    /// `precision` places, with trailing zeros dropped: `1/3` becomes
+    /// `0.3333` at precision 4 and `1/2` becomes `0.5`.
+    ///
+    /// Only constants change; `√2` and `π` stay symbolic.
+    pub fn with_decimals(mut self, precision: usize) -> Self {
+        self.decimals = Some(precision);
+        self
+    }
+
+    /// Leave out the multiplication sign where juxtaposition reads as a
+    /// product, as textbooks do: `2*x` becomes `2x`, `3*x*y` becomes `3xy`
+    /// and `2*sin(x)` becomes `2sin(x)`. The sign stays before a number, so
+    /// `2*3` is still `2 · 3`, and after a fraction written with a slash.
+    /// A space separates names that would otherwise run together, as in
+    /// `π x`.
+    ///
+    /// ASCII output keeps every sign, since the parser needs them.
+    pub fn with_implicit_multiplication(mut self) -> Self {
+        self.implicit_multiplication = true;
+        self
+    }
+}
+
+impl fmt::Display for ExprDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let renderer = Renderer {
+            symbols: self.symbols,
+            notation: self.notation,
+            decimals: self.decimals,
+            implicit_multiplication: self.implicit_multiplication,
+        };
+        f.write_str(&renderer.render(self.expr, PREC_QUANT))
+    }
+}
+
+impl Expr {
+    /// Render with Unicode math symbols, resolving names through `symbols`.
+    pub fn display<'a>(&'a self, symbols: &'a SymbolTable) -> ExprDisplay<'a> {
+        self.display_as(symbols, Notation::Unicode)
+    }
+
+    /// Render in the given notation.
+    pub fn display_as<'a>(
+        &'a self,
+        symbols: &'a SymbolTable,
+        notation: Notation,
+    ) -> ExprDisplay<'a> {
+        ExprDisplay {
+            expr: self,
+            symbols,
+            notation,
+            decimals: None,
+            implicit_multiplication: false,
+        }
+    }
+
+    /// Render as LaTeX source.
+    pub fn to_latex(&self, symbols: &SymbolTable) -> String {
+        self.display_as(symbols, Notation::Latex).to_string()
+    }
+
+    /// Render as plain ASCII.
+    ///
+    /// The parser reads the output back, except for inequalities, logical
+    /// connectives, quantifiers (`forall`, `exists`) and piecewise
+    /// definitions, which it has no syntax for.
+    pub fn to_ascii(&self, symbols: &SymbolTable) -> String {
+        self.display_as(symbols, Notation::Ascii).to_string()
+    }
+}
+
+struct Renderer<'a> {
+    symbols: &'a SymbolTable,
+    notation: Notation,
+    decimals: Option<usize>,
+    implicit_multiplication: bool,
+}
+
+impl Renderer<'_> {
+    fn latex(&self) -> bool {
+        self.notation == Notation::Latex
+    }
+
+    fn ascii(&self) -> bool {
+        self.notation == Notation::Ascii
+    }
+
+    /// Pick the Unicode or LaTeX spelling of a token.
+    ///
+    /// ASCII output uses the Unicode spelling.
+    fn pick<'s>(&self, unicode: &'s str, latex: &'s str) -> &'s str {
+        if self.latex() {
+            latex
+        } else {
+            unicode
+        }
+    }
+
+    /// Pick the ASCII, Unicode or LaTeX spelling of a token.
+    fn pick3<'s>(&self, ascii: &'s str, unicode: &'s str, latex: &'s str) -> &'s str {
+        match self.notation {
+            Notation::Ascii => ascii,
+            Notation::Unicode => unicode,
+            Notation::Latex => latex,
+        }
+    }
+
+    /// Render `expr`, parenthesizing it if it binds looser than `min`.
+    fn render(&self, expr: &Expr, min: u8) -> String {
+        let body = self.render_bare(expr);
+        if self.precedence(expr) < min {
+            format!("({})", body)
+        } else {
+            body
+        }
+    }
+
+    fn precedence(&self, expr: &Expr) -> u8 {
+        precedence(expr, self.notation, self.decimals.is_some())
+    }
+
+    fn render_bare(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Const(r) => self.rational(*r),
+            Expr::Var(v) => self.var(*v),
+            Expr::Pi => self.pick3("pi", "π", "\\pi").to_string(),
+            Expr::E => "e".to_string(),
+            Expr::Infinity { negative } => format!(
+                "{}{}",
+                if *negative { "-" } else { "" },
+                self.pick3("inf", "∞", "\\infty")
+            ),
+
+            Expr::Neg(e) => format!("-{}", self.render(e, PREC_NEG)),
+            Expr::Sqrt(e) => match self.notation {
+                Notation::Ascii => self.function("sqrt", "", e),
+                Notation::Unicode => format!("√{}", self.render(e, PREC_ATOM)),
+                Notation::Latex => format!("\\sqrt{{{}}}", self.render(e, PREC_QUANT)),
+            },
+            Expr::Sin(e) => self.function("sin", "\\sin", e),
+            Expr::Cos(e) => self.function("cos", "\\cos", e),
+            Expr::Tan(e) => self.function("tan", "\\tan", e),
+            Expr::Arcsin(e) => self.function("arcsin", "\\arcsin", e),
+            Expr::Arccos(e) => self.function("arccos", "\\arccos", e),
+            Expr::Arctan(e) => self.function("arctan", "\\arctan", e),
+            Expr::Sinh(e) => self.function("sinh", "\\sinh", e),
+            Expr::Cosh(e) => self.function("cosh", "\\cosh", e),
+            Expr::Tanh(e) => self.function("tanh", "\\tanh", e),
+            Expr::Arcsinh(e) => self.function("arcsinh", "\\operatorname{arcsinh}", e),
+            Expr::Arccosh(e) => self.function("arccosh", "\\operatorname{arccosh}", e),
+            Expr::Arctanh(e) => self.function("arctanh", "\\operatorname{arctanh}", e),
+            Expr::Ln(e) => self.function("ln", "\\ln", e),
+            Expr::Exp(e) => self.function("exp", "\\exp", e),
+            Expr::Abs(e) if self.ascii() => self.function("abs", "", e),
+            Expr::Floor(e) if self.ascii() => self.function("floor", "", e),
+            Expr::Ceiling(e) if self.ascii() => self.function("ceil", "", e),
+            Expr::Abs(e) => self.delimited(("|", "|"), ("\\left|", "\\right|"), e),
+            Expr::Floor(e) => self.delimited(("⌊", "⌋"), ("\\lfloor ", " \\rfloor"), e),
+            Expr::Ceiling(e) => self.delimited(("⌈", "⌉"), ("\\lceil ", " \\rceil"), e),
+
+            Expr::Add(a, b) => {
+                let lhs = self.render(a, PREC_ADD);
+                match b.as_ref() {
+                    Expr::Neg(inner) => format!("{} - {}", lhs, self.render(inner, PREC_ADD + 1)),
+                    Expr::Const(r) if r.is_negative() => {
+                        format!("{} - {}", lhs, self.rational(-*r))
+                    }
+                    _ => format!("{} + {}", lhs, self.render(b, PREC_ADD)),
+                }
+            }
+            Expr::Sub(a, b) => format!(
+                "{} - {}",
+                self.render(a, PREC_ADD),
+                self.render(b, PREC_ADD + 1)
+            ),
+            // Factors of a nested product are closed, being parenthesized
+            // unless they bind tighter; a left operand such as `x / 2` that
+            // binds exactly as tight is not
+            Expr::Mul(a, b) => self.product(
+                self.render(a, PREC_MUL),
+                matches!(a.as_ref(), Expr::Mul(..)) || self.precedence(a) != PREC_MUL,
+                self.render(b, PREC_MUL + 1),
+            ),
+            Expr::Div(a, b) => {
+                if self.latex() {
+                    format!(
+                        "\\frac{{{}}}{{{}}}",
+                        self.render(a, PREC_QUANT),
+                        self.render(b, PREC_QUANT)
+                    )
+                } else {
+                    format!(
+                        "{} / {}",
+                        self.render(a, PREC_MUL),
+                        self.render(b, PREC_MUL + 1)
+                    )
+                }
+            }
+            Expr::Pow(base, exp) => self.power(base, exp),
+
+            Expr::Sum(terms) => {
+                if terms.is_empty() {
+                    return "0".to_string();
+                }
+                let mut out = String::new();
+                for (i, term) in terms.iter().enumerate() {
+                    let magnitude = term.coeff.abs();
+                    let body = if magnitude.is_one() {
+                        self.render(&term.expr, PREC_ADD + 1)
+                    } else {
+                        let coeff = Expr::Const(magnitude);
+                        self.product(
+                            self.render(&coeff, PREC_MUL),
+                            self.precedence(&coeff) != PREC_MUL,
+                            self.render(&term.expr, PREC_MUL + 1),
+                        )
+                    };
+                    match (i, term.coeff.is_negative()) {
+                        (0, false) => out.push_str(&body),
+                        (0, true) => out.push_str(&format!("-{}", body)),
+                        (_, false) => out.push_str(&format!(" + {}", body)),
+                        (_, true) => out.push_str(&format!(" - {}", body)),
+                    }
+                }
+                out
+            }
+            Expr::Product(factors) => {
+                if factors.is_empty() {
+                    return "1".to_string();
+                }
+                factors
+                    .iter()
+                    .map(|f| {
+                        if f.power.is_one() {
+                            self.render(&f.base, PREC_MUL + 1)
+                        } else {
+                            self.power(&f.base, &f.power)
+                        }
+                    })
+                    .reduce(|left, right| self.product(left, true, right))
+                    .unwrap_or_default()
+            }
+
+            Expr::Derivative { expr, var } if self.ascii() => {
+                format!(
+                    "diff({}, {})",
+                    self.render(expr, PREC_QUANT),
+                    self.var(*var)
+                )
+            }
+            Expr::Integral { expr, var } if self.ascii() => {
+                format!("int({}, {})", self.render(expr, PREC_QUANT), self.var(*var))
+            }
+            Expr::Derivative { expr, var } => {
+                let d = if self.latex() {
+                    format!("\\frac{{d}}{{d{}}}", self.var(*var))
+                } else {
+                    format!("d/d{}", self.var(*var))
+                };
+                format!("{}({})", d, self.render(expr, PREC_QUANT))
+            }
+            Expr::Integral { expr, var } => format!(
+                "{} {}{}d{}",
+                self.pick("∫", "\\int"),
+                self.render(expr, PREC_MUL),
+                self.pick(" ", " \\, "),
+                self.var(*var)
+            ),
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => self.limit(expr, *var, approaching, *direction),
+
+            Expr::Equation { lhs, rhs } => self.relation(lhs, " = ", rhs),
+            Expr::Gte(a, b) => self.relation(a, self.pick3(" >= ", " ≥ ", " \\geq "), b),
+            Expr::Gt(a, b) => self.relation(a, " > ", b),
+            Expr::Lte(a, b) => self.relation(a, self.pick3(" <= ", " ≤ ", " \\leq "), b),
+            Expr::Lt(a, b) => self.relation(a, " < ", b),
+
+            Expr::GCD(a, b) => self.function2("gcd", "\\gcd", a, b),
+            Expr::LCM(a, b) => self.function2("lcm", "\\operatorname{lcm}", a, b),
+            // `25 % -4` would read back as a percent, so a signed modulus
+            // is parenthesized
+            Expr::Mod(a, b) => {
+                let modulus = if self.ascii() {
+                    PREC_NEG + 1
+                } else {
+                    PREC_MUL + 1
+                };
+                format!(
+                    "{}{}{}",
+                    self.render(a, PREC_MUL),
+                    self.pick3(" % ", " mod ", " \\bmod "),
+                    self.render(b, modulus)
+                )
+            }
+            Expr::Factorial(e) => format!("{}!", self.render(e, PREC_ATOM)),
+            Expr::Binomial(n, k) => match self.notation {
+                Notation::Ascii => self.function2("binomial", "", n, k),
+                Notation::Unicode => self.function2("C", "", n, k),
+                Notation::Latex => {
+                    format!(
+                        "\\binom{{{}}}{{{}}}",
+                        self.render(n, PREC_QUANT),
+                        self.render(k, PREC_QUANT)
+                    )
+                }
+            },
+
+            Expr::Summation {
+                var,
+                from,
+                to,
+                body,
+            } => self.big_operator(("sum", "Σ", "\\sum"), *var, from, to, body),
+            Expr::BigProduct {
+                var,
+                from,
+                to,
+                body,
+            } => self.big_operator(("prod", "Π", "\\prod"), *var, from, to, body),
+
+            Expr::ForAll { var, domain, body } => {
+                self.quantifier(("forall ", "∀", "\\forall "), *var, domain.as_deref(), body)
+            }
+            Expr::Exists { var, domain, body } => {
+                self.quantifier(("exists ", "∃", "\\exists "), *var, domain.as_deref(), body)
+            }
+            Expr::And(a, b) => format!(
+                "{}{}{}",
+                self.render(a, PREC_AND),
+                self.pick3(" and ", " ∧ ", " \\land "),
+                self.render(b, PREC_AND + 1)
+            ),
+            Expr::Or(a, b) => format!(
+                "{}{}{}",
+                self.render(a, PREC_OR),
+                self.pick3(" or ", " ∨ ", " \\lor "),
+                self.render(b, PREC_OR + 1)
+            ),
+            Expr::Implies(a, b) => format!(
+                "{}{}{}",
+                self.render(a, PREC_IMPLIES + 1),
+                self.pick3(" => ", " ⇒ ", " \\Rightarrow "),
+                self.render(b, PREC_IMPLIES)
+            ),
+            Expr::Not(e) => format!(
+                "{}{}",
+                self.pick3("not ", "¬", "\\lnot "),
+                self.render(e, PREC_REL + 1)
+            ),
+
+            Expr::Piecewise(branches) => self.piecewise(branches),
+        }
+    }
+
+    fn rational(&self, r: Rational) -> String {
+        if let Some(precision) = self.decimals.filter(|_| !r.is_integer()) {
+            let decimal = format!("{:.*}", precision, r.to_f64());
+            return if decimal.contains('.') {
+                decimal
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            } else {
+                decimal
+            };
+        }
+        if r.is_integer() || !self.latex() {
+            return r.to_string();
+        }
+        let sign = if r.is_negative() { "-" } else { "" };
+        format!("{}\\frac{{{}}}{{{}}}", sign, r.numer().abs(), r.denom())
+    }
+
+    fn var(&self, v: Symbol) -> String {
+        let name = self.symbols.resolve(v).unwrap_or("?");
+        if self.latex() && name.chars().count() > 1 {
+            format!("\\mathrm{{{}}}", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn times(&self) -> &'static str {
+        self.pick3(" * ", " · ", " \\cdot ")
+    }
+
+    /// Join rendered factors, leaving out the sign when asked to and
+    /// juxtaposition is unambiguous. `left_closed` says the left factor does
+    /// not end in an operator that would take `right` as its operand, as the
+    /// slash of `1/2` does.
+    fn product(&self, left: String, left_closed: bool, right: String) -> String {
+        let implicit = self.implicit_multiplication
+            && !self.ascii()
+            && left_closed
+            && right
+                .chars()
+                .next()
+                .is_some_and(|c| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+')));
+        if !implicit {
+            return format!("{}{}{}", left, self.times(), right);
+        }
+        // Single letters can run together, as in `xy`; longer names need a
+        // space, as in `x sin(x)` or LaTeX's `\pi x`
+        fn single_letter(mut chars: impl Iterator<Item = char>) -> bool {
+            chars.next().is_some_and(char::is_alphabetic)
+                && !chars.next().is_some_and(char::is_alphabetic)
+        }
+        let names_meet =
+            left.ends_with(char::is_alphabetic) && right.starts_with(char::is_alphabetic);
+        if names_meet && !(single_letter(left.chars().rev()) && single_letter(right.chars())) {
+            format!("{} {}", left, right)
+        } else {
+            format!("{}{}", left, right)
+        }
+    }
+
+    fn function(&self, unicode: &str, latex: &str, arg: &Expr) -> String {
+        format!(
+            "{}({})",
+            self.pick(unicode, latex),
+            self.render(arg, PREC_QUANT)
+        )
+    }
+
+    fn function2(&self, unicode: &str, latex: &str, a: &Expr, b: &Expr) -> String {
+        format!(
+            "{}({}, {})",
+            self.pick(unicode, latex),
+            self.render(a, PREC_QUANT),
+            self.render(b, PREC_QUANT)
+        )
+    }
+
+    fn delimited(&self, unicode: (&str, &str), latex: (&str, &str), arg: &Expr) -> String {
+        let (open, close) = if self.latex() { latex } else { unicode };
+        format!("{}{}{}", open, self.render(arg, PREC_QUANT), close)
+    }
+
+    fn relation(&self, a: &Expr, op: &str, b: &Expr) -> String {
+        format!(
+            "{}{}{}",
+            self.render(a, PREC_REL + 1),
+            op,
+            self.render(b, PREC_REL + 1)
+        )
+    }
+
+    fn power(&self, base: &Expr, exp: &Expr) -> String {
+        let base = self.render(base, PREC_POW + 1);
+        if self.latex() {
+            return format!("{}^{{{}}}", base, self.render(exp, PREC_QUANT));
+        }
+        match exp {
+            Expr::Const(r) if r.is_integer() && !self.ascii() => {
+                format!("{}{}", base, superscript(r.numer()))
+            }
+            _ => format!("{}^{}", base, self.render(exp, PREC_POW)),
+        }
+    }
+
+    fn big_operator(
+        &self,
+        symbol: (&str, &str, &str),
+        var: Symbol,
+        from: &Expr,
+        to: &Expr,
+        body: &Expr,
+    ) -> String {
+        let (var, from, to) = (
+            self.var(var),
+            self.render(from, PREC_QUANT),
+            self.render(to, PREC_QUANT),
+        );
+        match self.notation {
+            Notation::Ascii => {
+                let body = self.render(body, PREC_QUANT);
+                format!("{}({}, {}, {}, {})", symbol.0, var, from, to, body)
+            }
+            Notation::Unicode => {
+                let body = self.render(body, PREC_MUL);
+                format!("{}[{}={}..{}] {}", symbol.1, var, from, to, body)
+            }
+            Notation::Latex => {
+                let body = self.render(body, PREC_MUL);
+                format!("{}_{{{}={}}}^{{{}}} {}", symbol.2, var, from, to, body)
+            }
+        }
+    }
+
+    fn limit(
+        &self,
+        expr: &Expr,
+        var: Symbol,
+        approaching: &Expr,
+        direction: LimitDirection,
+    ) -> String {
+        let side = match direction {
+            LimitDirection::Both => "",
+            LimitDirection::Left => self.pick3("-", "⁻", "^{-}"),
+            LimitDirection::Right => self.pick3("+", "⁺", "^{+}"),
+        };
+        let (var, point) = (self.var(var), self.render(approaching, PREC_QUANT));
+        match self.notation {
+            Notation::Ascii => {
+                let body = self.render(expr, PREC_QUANT);
+                format!("lim({}, {} -> {}{})", body, var, point, side)
+            }
+            Notation::Unicode => {
+                let body = self.render(expr, PREC_MUL);
+                format!("lim[{}→{}{}] {}", var, point, side, body)
+            }
+            Notation::Latex => {
+                let body = self.render(expr, PREC_MUL);
+                format!("\\lim_{{{} \\to {}{}}} {}", var, point, side, body)
+            }
+        }
+    }
+
+    fn piecewise(&self, branches: &[(Constraint, Expr)]) -> String {
+        let cases: Vec<_> = branches
+            .iter()
+            .map(|(condition, value)| {
+                let (value, condition) = (
+                    self.render(value, PREC_QUANT),
+                    self.render(&condition.expr, PREC_QUANT),
+                );
+                match self.notation {
+                    Notation::Latex => format!("{} & {}", value, condition),
+                    _ => format!("{} if {}", value, condition),
+                }
+            })
+            .collect();
+        match self.notation {
+            Notation::Ascii => format!("piecewise({})", cases.join(", ")),
+            Notation::Unicode => format!("{{ {} }}", cases.join("; ")),
+            Notation::Latex => format!("\\begin{{cases}} {} \\end{{cases}}", cases.join(" \\\\ ")),
+        }
+    }
+
+    fn quantifier(
+        &self,
+        symbol: (&str, &str, &str),
+        var: Symbol,
+        domain: Option<&Expr>,
+        body: &Expr,
+    ) -> String {
+        let mut out = format!(
+            "{}{}",
+            self.pick3(symbol.0, symbol.1, symbol.2),
+            self.var(var)
+        );
+        if let Some(domain) = domain {
+            out.push_str(&format!(
+                "{}({})",
+                self.pick(" ", "\\,"),
+                self.render(domain, PREC_QUANT)
+            ));
+        }
+        out.push_str(self.pick(". ", ".\\ "));
+        // Connectives in the body are parenthesized so the scope is explicit
+        out.push_str(&self.render(body, PREC_NOT));
+        out
+    }
+}
+
+/// Binding strength of `expr` when rendered in `notation`, with or without
+/// decimal constants.
+pub(crate) fn precedence(expr: &Expr, notation: Notation, decimals: bool) -> u8 {
+    match expr {
+        Expr::Const(r) if !r.is_integer() && notation != Notation::Latex && !decimals => PREC_MUL,
+        Expr::Const(r) if r.is_negative() => PREC_NEG,
+        Expr::Infinity { negative: true } => PREC_NEG,
+        Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => PREC_ATOM,
+
+        Expr::Neg(_) => PREC_NEG,
+        Expr::Sqrt(_) if notation == Notation::Unicode => PREC_NEG,
+        Expr::Div(_, _) if notation == Notation::Latex => PREC_ATOM,
+        Expr::Add(_, _) | Expr::Sub(_, _) | Expr::Sum(_) => PREC_ADD,
+        Expr::Mul(_, _) | Expr::Div(_, _) | Expr::Product(_) | Expr::Mod(_, _) => PREC_MUL,
+        Expr::Pow(_, _) => PREC_POW,
+        Expr::Factorial(_) => PREC_POSTFIX,
+
+        Expr::Integral { .. }
+        | Expr::Limit { .. }
+        | Expr::Summation { .. }
+        | Expr::BigProduct { .. }
+            if notation != Notation::Ascii =>
+        {
+            PREC_ADD
+        }
+
+        Expr::Equation { .. }
+        | Expr::Gte(_, _)
+        | Expr::Gt(_, _)
+        | Expr::Lte(_, _)
+        | Expr::Lt(_, _) => PREC_REL,
+
+        Expr::Not(_) => PREC_NOT,
+        Expr::And(_, _) => PREC_AND,
+        Expr::Or(_, _) => PREC_OR,
+        Expr::Implies(_, _) => PREC_IMPLIES,
+        Expr::ForAll { .. } | Expr::Exists { .. } => PREC_QUANT,
+
+        Expr::Sqrt(_)
+        | Expr::Sin(_)
+        | Expr::Cos(_)
+        | Expr::Tan(_)
+        | Expr::Arcsin(_)
+        | Expr::Arccos(_)
+        | Expr::Arctan(_)
+        | Expr::Sinh(_)
+        | Expr::Cosh(_)
+        | Expr::Tanh(_)
+        | Expr::Arcsinh(_)
+        | Expr::Arccosh(_)
+        | Expr::Arctanh(_)
+        | Expr::Ln(_)
+        | Expr::Exp(_)
+        | Expr::Abs(_)
+        | Expr::Floor(_)
+        | Expr::Ceiling(_)
+        | Expr::GCD(_, _)
+        | Expr::LCM(_, _)
+        | Expr::Binomial(_, _)
+        | Expr::Derivative { .. }
+        | Expr::Integral { .. }
+        | Expr::Limit { .. }
+        | Expr::Summation { .. }
+        | Expr::BigProduct { .. }
+        | Expr::Piecewise(_) => PREC_ATOM,
+    }
+}
+
+/// Write an integer with Unicode superscript digits.
+fn superscript(n: i64) -> String {
+    n.to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            _ => '⁹',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Factor, Term};
+
+    /// ∀x. (x > 0 ⇒ x² > 0)
+    fn positive_square(x: Symbol) -> Expr {
+        Expr::ForAll {
+            var: x,
+            domain: None,
+            body: Box::new(Expr::Implies(
+                Box::new(Expr::Gt(Box::new(Expr::Var(x)), Box::new(Expr::int(0)))),
+                Box::new(Expr::Gt(
+                    Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+                    Box::new(Expr::int(0)),
+                )),
+            )),
+        }
+    }
+
+    #[test]
+    fn test_forall_unicode() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        assert_eq!(
+            positive_square(x).display(&symbols).to_string(),
+            "∀x. (x > 0 ⇒ x² > 0)"
+        );
+    }
+
+    #[test]
+    fn test_forall_latex() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        assert_eq!(
+            positive_square(x).to_latex(&symbols),
+            "\\forall x.\\ (x > 0 \\Rightarrow x^{2} > 0)"
+        );
+    }
+
+    #[test]
+    fn test_connectives() {
+        let mut symbols = SymbolTable::new();
+        let p = symbols.intern("p");
+        let q = symbols.intern("q");
+        let x = symbols.intern("x");
+        let (vp, vq) = (|| Box::new(Expr::Var(p)), || Box::new(Expr::Var(q)));
+
+        // ¬(p ∧ q) ∨ q
+        let expr = Expr::Or(Box::new(Expr::Not(Box::new(Expr::And(vp(), vq())))), vq());
+        assert_eq!(expr.display(&symbols).to_string(), "¬(p ∧ q) ∨ q");
+        assert_eq!(expr.to_latex(&symbols), "\\lnot (p \\land q) \\lor q");
+
+        // ∃x (x ≥ 1). x = 2
+        let exists = Expr::Exists {
+            var: x,
+            domain: Some(Box::new(Expr::Gte(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(1)),
+            ))),
+            body: Box::new(Expr::Equation {
+                lhs: Box::new(Expr::Var(x)),
+                rhs: Box::new(Expr::int(2)),
+            }),
+        };
+        assert_eq!(exists.display(&symbols).to_string(), "∃x (x ≥ 1). x = 2");
+        assert_eq!(
+            exists.to_latex(&symbols),
+            "\\exists x\\,(x \\geq 1).\\ x = 2"
+        );
+    }
+
+    #[test]
+    fn test_ascii() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        assert_eq!(
+            positive_square(x).to_ascii(&symbols),
+            "forall x. (x > 0 => x^2 > 0)"
+        );
+
+        // √(x + 1) · |x| ≥ π
+        let expr = Expr::Gte(
+            Box::new(Expr::Mul(
+                Box::new(Expr::Sqrt(Box::new(Expr::Add(
+                    Box::new(Expr::Var(x)),
+                    Box::new(Expr::int(1)),
+                )))),
+                Box::new(Expr::Abs(Box::new(Expr::Var(x)))),
+            )),
+            Box::new(Expr::Pi),
+        );
+        assert_eq!(expr.to_ascii(&symbols), "sqrt(x + 1) * abs(x) >= pi");
+        assert!(expr.to_ascii(&symbols).is_ascii());
+
+        // Operands that would read back differently are parenthesized
+        let expr = Expr::Mul(Box::new(Expr::Var(x)), Box::new(Expr::frac(-1, 2)));
+        assert_eq!(expr.to_ascii(&symbols), "x * (-1/2)");
+        let expr = Expr::Mod(Box::new(Expr::int(25)), Box::new(Expr::int(-4)));
+        assert_eq!(expr.to_ascii(&symbols), "25 % (-4)");
+        assert_eq!(expr.display(&symbols).to_string(), "25 mod -4");
+    }
+
+    #[test]
+    fn test_calculus_and_big_operators() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let y = symbols.intern("y");
+        let k = symbols.intern("k");
+        let n = symbols.intern("n");
+        let var = |v| Box::new(Expr::Var(v));
+        let square = |v| Expr::Pow(var(v), Box::new(Expr::int(2)));
+
+        let sum = Expr::Sum(vec![
+            Term {
+                coeff: Rational::from(2),
+                expr: square(x),
+            },
+            Term {
+                coeff: Rational::from(-1),
+                expr: Expr::Var(y),
+            },
+        ]);
+        let product = Expr::Product(vec![
+            Factor {
+                base: Expr::Var(x),
+                power: Expr::int(3),
+            },
+            Factor {
+                base: Expr::Var(y),
+                power: Expr::int(1),
+            },
+        ]);
+        let summation = Expr::Summation {
+            var: k,
+            from: Box::new(Expr::int(1)),
+            to: var(n),
+            body: Box::new(square(k)),
+        };
+        let integral = Expr::Integral {
+            expr: Box::new(square(x)),
+            var: x,
+        };
+        let derivative = Expr::Derivative {
+            expr: Box::new(Expr::Sin(var(x))),
+            var: x,
+        };
+        let fraction = Expr::Div(
+            Box::new(Expr::int(1)),
+            Box::new(Expr::Add(var(x), Box::new(Expr::int(1)))),
+        );
+
+        let cases = [
+            (&sum, "2 * x^2 - y", "2 · x² - y", "2 \\cdot x^{2} - y"),
+            (&product, "x^3 * y", "x³ · y", "x^{3} \\cdot y"),
+            (
+                &summation,
+                "sum(k, 1, n, k^2)",
+                "Σ[k=1..n] k²",
+                "\\sum_{k=1}^{n} k^{2}",
+            ),
+            (&integral, "int(x^2, x)", "∫ x² dx", "\\int x^{2} \\, dx"),
+            (
+                &derivative,
+                "diff(sin(x), x)",
+                "d/dx(sin(x))",
+                "\\frac{d}{dx}(\\sin(x))",
+            ),
+            (&fraction, "1 / (x + 1)", "1 / (x + 1)", "\\frac{1}{x + 1}"),
+        ];
+        for (expr, ascii, unicode, latex) in cases {
+            assert_eq!(expr.to_ascii(&symbols), ascii);
+            assert_eq!(expr.display(&symbols).to_string(), unicode);
+            assert_eq!(expr.to_latex(&symbols), latex);
+        }
+    }
+
+    #[test]
+    fn test_decimals() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // (1/3)·x + √2 - 1/2
+        let expr = Expr::Sub(
+            Box::new(Expr::Add(
+                Box::new(Expr::Mul(
+                    Box::new(Expr::frac(1, 3)),
+                    Box::new(Expr::Var(x)),
+                )),
+                Box::new(Expr::Sqrt(Box::new(Expr::int(2)))),
+            )),
+            Box::new(Expr::frac(1, 2)),
+        );
+        assert_eq!(expr.display(&symbols).to_string(), "1/3 · x + √2 - 1/2");
+        assert_eq!(
+            expr.display(&symbols).with_decimals(4).to_string(),
+            "0.3333 · x + √2 - 0.5"
+        );
+        assert_eq!(
+            Expr::frac(-2, 3)
+                .display(&symbols)
+                .with_decimals(2)
+                .to_string(),
+            "-0.67"
+        );
+        assert_eq!(
+            Expr::int(7).display(&symbols).with_decimals(2).to_string(),
+            "7"
+        );
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = crate::parse::Parser::new(&mut symbols);
+        let cases: Vec<_> = [
+            ("2*x", "2x", "2x"),
+            ("3*x*y", "3xy", "3xy"),
+            ("x*y", "xy", "xy"),
+            ("2*3", "2 · 3", "2 \\cdot 3"),
+            ("2*x*3", "2x · 3", "2x \\cdot 3"),
+            ("x*(-y)", "x · -y", "x \\cdot -y"),
+            ("2*x^2*y", "2x²y", "2x^{2}y"),
+            ("x*sin(x)", "x sin(x)", "x\\sin(x)"),
+            ("pi*x", "πx", "\\pi x"),
+            ("theta*x", "theta x", "\\mathrm{theta}x"),
+            ("(x + 1)*(x - 1)", "(x + 1)(x - 1)", "(x + 1)(x - 1)"),
+            ("(1/2)*x", "1 / 2 · x", "\\frac{1}{2}x"),
+            ("(x/2)*y", "x / 2 · y", "\\frac{x}{2}y"),
+        ]
+        .into_iter()
+        .map(|(input, unicode, latex)| (parser.parse(input).unwrap(), unicode, latex))
+        .collect();
+
+        for (expr, unicode, latex) in cases {
+            let implicit = |notation| {
+                expr.display_as(&symbols, notation)
+                    .with_implicit_multiplication()
+                    .to_string()
+            };
+            assert_eq!(implicit(Notation::Unicode), unicode);
+            assert_eq!(implicit(Notation::Latex), latex);
+            // ASCII stays parseable
+            assert_eq!(implicit(Notation::Ascii), expr.to_ascii(&symbols));
+        }
+
+        // Coefficients of canonical sums and products join the same way
+        let expr = crate::parse::Parser::new(&mut symbols)
+            .parse("3*x*y + 2*x")
+            .unwrap();
+        let rendered = expr
+            .canonicalize()
+            .display(&symbols)
+            .with_implicit_multiplication()
+            .to_string();
+        assert!(!rendered.contains('·'), "{}", rendered);
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let vx = || Box::new(Expr::Var(x));
+
+        // |x| as cases
+        let expr = Expr::Piecewise(vec![
+            (
+                Constraint {
+                    expr: Expr::Lt(vx(), Box::new(Expr::int(0))),
+                },
+                Expr::Neg(vx()),
+            ),
+            (
+                Constraint {
+                    expr: Expr::Gte(vx(), Box::new(Expr::int(0))),
+                },
+                Expr::Var(x),
+            ),
+        ]);
+        assert_eq!(
+            expr.display(&symbols).to_string(),
+            "{ -x if x < 0; x if x ≥ 0 }"
+        );
+        assert_eq!(
+            expr.to_latex(&symbols),
+            "\\begin{cases} -x & x < 0 \\\\ x & x \\geq 0 \\end{cases}"
+        );
+        assert_eq!(
+            expr.to_ascii(&symbols),
+            "piecewise(-x if x < 0, x if x >= 0)"
+        );
+    }
+}
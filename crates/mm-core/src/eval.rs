@@ -8,12 +8,40 @@
 //!
 //! Evaluates expressions to floating-point values given variable bindings.
 
-use crate::{Expr, Rational, Symbol};
+use crate::{numtheory, Expr, LimitDirection, Rational, Symbol};
 use std::collections::HashMap;
 
 /// Environment mapping variables to their values.
 pub type Env = HashMap<Symbol, f64>;
 
+/// Unit in which trigonometric angles are read and written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleMode {
+    /// Angles in radians: `sin(π/6) = 1/2`.
+    #[default]
+    Radians,
+    /// Angles in degrees: `sin(30) = 1/2`.
+    Degrees,
+}
+
+impl AngleMode {
+    /// Convert an angle in this mode to radians.
+    pub fn radians(self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => angle,
+            AngleMode::Degrees => angle.to_radians(),
+        }
+    }
+
+    /// Convert an angle in radians to this mode.
+    pub fn angle(self, radians: f64) -> f64 {
+        match self {
+            AngleMode::Radians => radians,
+            AngleMode::Degrees => radians.to_degrees(),
+        }
+    }
+}
+
 /// Compute GCD using Euclidean algorithm.
 fn gcd(mut a: i64, mut b: i64) -> i64 {
     while b != 0 {
@@ -24,6 +52,104 @@ fn gcd(mut a: i64, mut b: i64) -> i64 {
     a
 }
 
+/// `a^b mod m` for integer constants, by repeated squaring.
+///
+/// `a^b` itself would overflow `f64` precision long before `b` gets large.
+/// The sign follows the dividend, like the float `%` used otherwise.
+fn const_mod_pow(power: &Expr, modulus: &Expr) -> Option<f64> {
+    let Expr::Pow(base, exp) = power else {
+        return None;
+    };
+    let (Expr::Const(a), Expr::Const(b), Expr::Const(m)) = (base.as_ref(), exp.as_ref(), modulus)
+    else {
+        return None;
+    };
+    if !(a.is_integer() && b.is_integer() && m.is_integer()) || b.is_negative() || m.is_zero() {
+        return None;
+    }
+    let (a, b, m) = (a.numer(), b.numer(), m.numer());
+    let r = numtheory::mod_pow(a.unsigned_abs(), b as u64, m.unsigned_abs()) as f64;
+    Some(if a < 0 && b % 2 == 1 { -r } else { r })
+}
+
+/// Steps `h` at which a limit is sampled, relative to the size of the point.
+///
+/// Smaller steps lose more to rounding than they gain: `(cos h - 1)/h²`
+/// already has only five correct digits at `h = 1e-5`.
+const LIMIT_STEPS: [f64; 5] = [1e-1, 1e-2, 1e-3, 1e-4, 1e-5];
+
+/// `lim f(t)` as `t` approaches `point` from the side of `sign` (`1` for
+/// above, `-1` for below).
+///
+/// `f` is sampled at [`LIMIT_STEPS`] and the samples extrapolated to
+/// `h = 0`; the limit exists when extrapolations from the coarser and the
+/// finer samples agree. Polynomial extrapolation suits errors that are
+/// smooth in `h`; Aitken's Δ² is tried after it for errors like `h^(1/3)`,
+/// which shrink geometrically at these steps. Samples that at least double
+/// in size from step to step, keeping their sign, give `±∞`. Anything else
+/// (a sample outside the domain, oscillation, slow growth like `ln t`)
+/// gives `None`.
+fn one_sided_limit(f: impl Fn(f64) -> Option<f64>, point: f64, sign: f64) -> Option<f64> {
+    let scale = point.abs().max(1.0);
+    let steps = LIMIT_STEPS.map(|h| h * scale);
+    let mut samples = [0.0; LIMIT_STEPS.len()];
+    for (sample, h) in samples.iter_mut().zip(steps) {
+        *sample = f(point + sign * h).filter(|v| v.is_finite())?;
+    }
+
+    let growing = samples
+        .windows(2)
+        .all(|w| w[0].signum() == w[1].signum() && w[1].abs() >= 2.0 * w[0].abs());
+    if growing && samples[0] != 0.0 {
+        return Some(f64::INFINITY.copysign(samples[0]));
+    }
+
+    let agree = |coarse: f64, fine: f64| (coarse - fine).abs() <= 1e-4 * fine.abs().max(1.0);
+    let coarse = extrapolate_to_zero(&steps[..3], &samples[..3]);
+    let fine = extrapolate_to_zero(&steps[2..], &samples[2..]);
+    if agree(coarse, fine) {
+        return Some(fine);
+    }
+    let (coarse, fine) = (aitken(&samples[..3]), aitken(&samples[2..]));
+    agree(coarse, fine).then_some(fine)
+}
+
+/// Aitken's Δ² estimate of the limit of the sequence `x[0], x[1], x[2]`.
+fn aitken(x: &[f64]) -> f64 {
+    let (d0, d1) = (x[1] - x[0], x[2] - x[1]);
+    if d1 == d0 {
+        x[2]
+    } else {
+        x[2] - d1 * d1 / (d1 - d0)
+    }
+}
+
+/// Value at 0 of the polynomial through the points `(xs[i], ys[i])`.
+fn extrapolate_to_zero(xs: &[f64], ys: &[f64]) -> f64 {
+    xs.iter()
+        .zip(ys)
+        .enumerate()
+        .map(|(i, (xi, yi))| {
+            let weight: f64 = xs
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, xj)| xj / (xj - xi))
+                .product();
+            yi * weight
+        })
+        .sum()
+}
+
+/// Whether one-sided limits `left` and `right` are the same limit.
+fn limits_agree(left: f64, right: f64) -> bool {
+    if left.is_infinite() || right.is_infinite() {
+        left == right
+    } else {
+        (left - right).abs() <= 1e-4 * left.abs().max(right.abs()).max(1.0)
+    }
+}
+
 /// Compute factorial.
 fn factorial(n: u64) -> u64 {
     (1..=n).product()
@@ -63,56 +189,98 @@ impl Expr {
     /// assert_eq!(expr.evaluate(&env), Some(3.0));
     /// ```
     pub fn evaluate(&self, env: &Env) -> Option<f64> {
+        self.evaluate_with(env, AngleMode::Radians)
+    }
+
+    /// Evaluate this expression numerically, reading angles in `mode`.
+    ///
+    /// Arguments of `sin`, `cos` and `tan` are taken in `mode`, and the
+    /// results of `arcsin`, `arccos` and `arctan` are returned in it.
+    pub fn evaluate_with(&self, env: &Env, mode: AngleMode) -> Option<f64> {
+        self.evaluate_in(env, mode, false)
+    }
+
+    /// Like [`Expr::evaluate_with`], also evaluating derivatives.
+    ///
+    /// `d/dx(f)` is approximated by the central difference
+    /// `(f(x + h) - f(x - h)) / 2h`, accurate to roughly `1e-8` relative to
+    /// the size of `f`; `d/dx(∫ f dx)` is `f` itself. Integrals elsewhere
+    /// still fail to evaluate.
+    pub fn evaluate_differentiating(&self, env: &Env, mode: AngleMode) -> Option<f64> {
+        self.evaluate_in(env, mode, true)
+    }
+
+    fn evaluate_in(&self, env: &Env, mode: AngleMode, differentiate: bool) -> Option<f64> {
         match self {
             Expr::Const(r) => Some(r.to_f64()),
             Expr::Var(s) => env.get(s).copied(),
             Expr::Pi => Some(std::f64::consts::PI),
             Expr::E => Some(std::f64::consts::E),
+            Expr::Infinity { negative: false } => Some(f64::INFINITY),
+            Expr::Infinity { negative: true } => Some(f64::NEG_INFINITY),
 
-            Expr::Neg(e) => e.evaluate(env).map(|x| -x),
+            Expr::Neg(e) => e.evaluate_in(env, mode, differentiate).map(|x| -x),
             Expr::Sqrt(e) => {
-                let val = e.evaluate(env)?;
+                let val = e.evaluate_in(env, mode, differentiate)?;
                 if val >= 0.0 {
                     Some(val.sqrt())
                 } else {
                     None // Complex result
                 }
             }
-            Expr::Sin(e) => e.evaluate(env).map(|x| x.sin()),
-            Expr::Cos(e) => e.evaluate(env).map(|x| x.cos()),
-            Expr::Tan(e) => e.evaluate(env).map(|x| x.tan()),
-            Expr::Arcsin(e) => e.evaluate(env).map(|x| x.asin()),
-            Expr::Arccos(e) => e.evaluate(env).map(|x| x.acos()),
-            Expr::Arctan(e) => e.evaluate(env).map(|x| x.atan()),
+            Expr::Sin(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.radians(x).sin()),
+            Expr::Cos(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.radians(x).cos()),
+            Expr::Tan(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.radians(x).tan()),
+            Expr::Arcsin(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.angle(x.asin())),
+            Expr::Arccos(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.angle(x.acos())),
+            Expr::Arctan(e) => e
+                .evaluate_in(env, mode, differentiate)
+                .map(|x| mode.angle(x.atan())),
+            Expr::Sinh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.sinh()),
+            Expr::Cosh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.cosh()),
+            Expr::Tanh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.tanh()),
+            Expr::Arcsinh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.asinh()),
+            Expr::Arccosh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.acosh()),
+            Expr::Arctanh(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.atanh()),
             Expr::Ln(e) => {
-                let val = e.evaluate(env)?;
+                let val = e.evaluate_in(env, mode, differentiate)?;
                 if val > 0.0 {
                     Some(val.ln())
                 } else {
                     None // Domain error
                 }
             }
-            Expr::Exp(e) => e.evaluate(env).map(|x| x.exp()),
-            Expr::Abs(e) => e.evaluate(env).map(|x| x.abs()),
+            Expr::Exp(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.exp()),
+            Expr::Abs(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.abs()),
 
             Expr::Add(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 Some(va + vb)
             }
             Expr::Sub(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 Some(va - vb)
             }
             Expr::Mul(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 Some(va * vb)
             }
             Expr::Div(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 if vb.abs() < 1e-15 {
                     None // Division by zero
                 } else {
@@ -120,15 +288,15 @@ impl Expr {
                 }
             }
             Expr::Pow(base, exp) => {
-                let vb = base.evaluate(env)?;
-                let ve = exp.evaluate(env)?;
+                let vb = base.evaluate_in(env, mode, differentiate)?;
+                let ve = exp.evaluate_in(env, mode, differentiate)?;
                 Some(vb.powf(ve))
             }
 
             Expr::Sum(terms) => {
                 let mut sum = 0.0;
                 for term in terms {
-                    let val = term.expr.evaluate(env)?;
+                    let val = term.expr.evaluate_in(env, mode, differentiate)?;
                     sum += term.coeff.to_f64() * val;
                 }
                 Some(sum)
@@ -136,55 +304,100 @@ impl Expr {
             Expr::Product(factors) => {
                 let mut prod = 1.0;
                 for factor in factors {
-                    let base = factor.base.evaluate(env)?;
-                    let power = factor.power.evaluate(env)?;
+                    let base = factor.base.evaluate_in(env, mode, differentiate)?;
+                    let power = factor.power.evaluate_in(env, mode, differentiate)?;
                     prod *= base.powf(power);
                 }
                 Some(prod)
             }
 
+            Expr::Derivative { expr, var } if differentiate => match expr.as_ref() {
+                Expr::Integral {
+                    expr: integrand,
+                    var: v,
+                } if v == var => integrand.evaluate_in(env, mode, true),
+                _ => {
+                    let x = *env.get(var)?;
+                    // Balances truncation error against rounding error
+                    let h = f64::EPSILON.cbrt() * x.abs().max(1.0);
+                    let mut shifted = env.clone();
+                    shifted.insert(*var, x + h);
+                    let above = expr.evaluate_in(&shifted, mode, true)?;
+                    shifted.insert(*var, x - h);
+                    let below = expr.evaluate_in(&shifted, mode, true)?;
+                    Some((above - below) / (2.0 * h))
+                }
+            },
+
             // Calculus expressions can't be directly evaluated
             Expr::Derivative { .. } | Expr::Integral { .. } => None,
 
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => {
+                let point = approaching.evaluate_in(env, mode, differentiate)?;
+                let at = |t: f64| {
+                    let mut shifted = env.clone();
+                    shifted.insert(*var, t);
+                    expr.evaluate_in(&shifted, mode, differentiate)
+                };
+                if point.is_infinite() {
+                    // t → ±∞ is h = ±1/t → 0 from above, from one side only
+                    return one_sided_limit(|h| at(point.signum() / h), 0.0, 1.0);
+                }
+                match direction {
+                    LimitDirection::Left => one_sided_limit(at, point, -1.0),
+                    LimitDirection::Right => one_sided_limit(at, point, 1.0),
+                    LimitDirection::Both => {
+                        let left = one_sided_limit(at, point, -1.0)?;
+                        let right = one_sided_limit(at, point, 1.0)?;
+                        limits_agree(left, right).then_some(right)
+                    }
+                }
+            }
+
             // Equations return the difference (lhs - rhs)
             // Useful for checking if a solution satisfies the equation
             Expr::Equation { lhs, rhs } => {
-                let vl = lhs.evaluate(env)?;
-                let vr = rhs.evaluate(env)?;
+                let vl = lhs.evaluate_in(env, mode, differentiate)?;
+                let vr = rhs.evaluate_in(env, mode, differentiate)?;
                 Some(vl - vr)
             }
 
             // Comparison operators - return 1.0 for true, 0.0 for false
             Expr::Gte(lhs, rhs) => {
-                let vl = lhs.evaluate(env)?;
-                let vr = rhs.evaluate(env)?;
+                let vl = lhs.evaluate_in(env, mode, differentiate)?;
+                let vr = rhs.evaluate_in(env, mode, differentiate)?;
                 Some(if vl >= vr { 1.0 } else { 0.0 })
             }
             Expr::Gt(lhs, rhs) => {
-                let vl = lhs.evaluate(env)?;
-                let vr = rhs.evaluate(env)?;
+                let vl = lhs.evaluate_in(env, mode, differentiate)?;
+                let vr = rhs.evaluate_in(env, mode, differentiate)?;
                 Some(if vl > vr { 1.0 } else { 0.0 })
             }
             Expr::Lte(lhs, rhs) => {
-                let vl = lhs.evaluate(env)?;
-                let vr = rhs.evaluate(env)?;
+                let vl = lhs.evaluate_in(env, mode, differentiate)?;
+                let vr = rhs.evaluate_in(env, mode, differentiate)?;
                 Some(if vl <= vr { 1.0 } else { 0.0 })
             }
             Expr::Lt(lhs, rhs) => {
-                let vl = lhs.evaluate(env)?;
-                let vr = rhs.evaluate(env)?;
+                let vl = lhs.evaluate_in(env, mode, differentiate)?;
+                let vr = rhs.evaluate_in(env, mode, differentiate)?;
                 Some(if vl < vr { 1.0 } else { 0.0 })
             }
 
             // Number theory operations
             Expr::GCD(a, b) => {
-                let va = a.evaluate(env)? as i64;
-                let vb = b.evaluate(env)? as i64;
+                let va = a.evaluate_in(env, mode, differentiate)? as i64;
+                let vb = b.evaluate_in(env, mode, differentiate)? as i64;
                 Some(gcd(va.abs(), vb.abs()) as f64)
             }
             Expr::LCM(a, b) => {
-                let va = a.evaluate(env)? as i64;
-                let vb = b.evaluate(env)? as i64;
+                let va = a.evaluate_in(env, mode, differentiate)? as i64;
+                let vb = b.evaluate_in(env, mode, differentiate)? as i64;
                 if va == 0 || vb == 0 {
                     Some(0.0)
                 } else {
@@ -192,18 +405,21 @@ impl Expr {
                 }
             }
             Expr::Mod(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                if let Some(value) = const_mod_pow(a, b) {
+                    return Some(value);
+                }
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 if vb.abs() < 1e-15 {
                     None // Mod by zero
                 } else {
                     Some(va % vb)
                 }
             }
-            Expr::Floor(e) => e.evaluate(env).map(|x| x.floor()),
-            Expr::Ceiling(e) => e.evaluate(env).map(|x| x.ceil()),
+            Expr::Floor(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.floor()),
+            Expr::Ceiling(e) => e.evaluate_in(env, mode, differentiate).map(|x| x.ceil()),
             Expr::Factorial(e) => {
-                let n = e.evaluate(env)? as u64;
+                let n = e.evaluate_in(env, mode, differentiate)? as u64;
                 if n > 20 {
                     None // Overflow risk
                 } else {
@@ -211,12 +427,12 @@ impl Expr {
                 }
             }
             Expr::Binomial(n_expr, k_expr) => {
-                let n = n_expr.evaluate(env)? as u64;
-                let k = k_expr.evaluate(env)? as u64;
-                if k > n || n > 20 {
+                let n = n_expr.evaluate_in(env, mode, differentiate)? as u64;
+                let k = k_expr.evaluate_in(env, mode, differentiate)? as u64;
+                if k > n {
                     None
                 } else {
-                    Some((factorial(n) / (factorial(k) * factorial(n - k))) as f64)
+                    numtheory::binomial(n, k).map(|c| c as f64)
                 }
             }
             // Summation and Product - evaluate when bounds are constant integers
@@ -226,8 +442,8 @@ impl Expr {
                 to,
                 body,
             } => {
-                let from_val = from.evaluate(env)? as i64;
-                let to_val = to.evaluate(env)? as i64;
+                let from_val = from.evaluate_in(env, mode, differentiate)? as i64;
+                let to_val = to.evaluate_in(env, mode, differentiate)? as i64;
                 if (to_val - from_val).abs() > 1000 {
                     return None; // Prevent runaway
                 }
@@ -235,7 +451,7 @@ impl Expr {
                 let mut local_env = env.clone();
                 for i in from_val..=to_val {
                     local_env.insert(*var, i as f64);
-                    sum += body.evaluate(&local_env)?;
+                    sum += body.evaluate_in(&local_env, mode, differentiate)?;
                 }
                 Some(sum)
             }
@@ -245,8 +461,8 @@ impl Expr {
                 to,
                 body,
             } => {
-                let from_val = from.evaluate(env)? as i64;
-                let to_val = to.evaluate(env)? as i64;
+                let from_val = from.evaluate_in(env, mode, differentiate)? as i64;
+                let to_val = to.evaluate_in(env, mode, differentiate)? as i64;
                 if (to_val - from_val).abs() > 100 {
                     return None; // Prevent overflow
                 }
@@ -254,7 +470,7 @@ impl Expr {
                 let mut local_env = env.clone();
                 for i in from_val..=to_val {
                     local_env.insert(*var, i as f64);
-                    prod *= body.evaluate(&local_env)?;
+                    prod *= body.evaluate_in(&local_env, mode, differentiate)?;
                 }
                 Some(prod)
             }
@@ -264,32 +480,90 @@ impl Expr {
 
             // Logical connectives - return 1.0 for true, 0.0 for false
             Expr::And(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 Some(if va != 0.0 && vb != 0.0 { 1.0 } else { 0.0 })
             }
             Expr::Or(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 Some(if va != 0.0 || vb != 0.0 { 1.0 } else { 0.0 })
             }
             Expr::Not(e) => {
-                let v = e.evaluate(env)?;
+                let v = e.evaluate_in(env, mode, differentiate)?;
                 Some(if v == 0.0 { 1.0 } else { 0.0 })
             }
             Expr::Implies(a, b) => {
-                let va = a.evaluate(env)?;
-                let vb = b.evaluate(env)?;
+                let va = a.evaluate_in(env, mode, differentiate)?;
+                let vb = b.evaluate_in(env, mode, differentiate)?;
                 // P → Q is equivalent to ¬P ∨ Q
                 Some(if va == 0.0 || vb != 0.0 { 1.0 } else { 0.0 })
             }
+
+            // Piecewise - the first branch whose constraint holds
+            Expr::Piecewise(branches) => {
+                for (condition, value) in branches {
+                    if condition.expr.evaluate_in(env, mode, differentiate)? != 0.0 {
+                        return value.evaluate_in(env, mode, differentiate);
+                    }
+                }
+                None // No branch covers this point
+            }
         }
     }
 
+    /// Whether this expression, as a function of `var`, is continuous at
+    /// `point`: defined there, with both one-sided limits equal to its value.
+    ///
+    /// Limits are found numerically as for [`Expr::Limit`], so the answer is
+    /// `None` when one of them cannot be decided (as for `sin(1/x)` at 0,
+    /// or `√x` at 0, which has no left side) or when other variables are
+    /// free.
+    pub fn is_continuous_at(&self, var: Symbol, point: f64) -> Option<bool> {
+        if self.free_vars().iter().any(|v| *v != var) {
+            return None;
+        }
+        let at = |t: f64| self.evaluate(&Env::from([(var, t)]));
+        let Some(value) = at(point).filter(|v| v.is_finite()) else {
+            return Some(false);
+        };
+        let left = one_sided_limit(at, point, -1.0)?;
+        let right = one_sided_limit(at, point, 1.0)?;
+        Some(limits_agree(left, value) && limits_agree(right, value))
+    }
+
+    /// Whether this expression, as a function of `var`, is differentiable at
+    /// `point`: continuous there, with the difference quotient
+    /// `(f(point + h) - f(point))/h` tending to the same finite limit from
+    /// both sides.
+    ///
+    /// `None` when undecidable, as for [`Expr::is_continuous_at`].
+    pub fn is_differentiable_at(&self, var: Symbol, point: f64) -> Option<bool> {
+        if !self.is_continuous_at(var, point)? {
+            return Some(false);
+        }
+        let at = |t: f64| self.evaluate(&Env::from([(var, t)]));
+        let value = at(point)?;
+        let quotient = |t: f64| at(t).map(|v| (v - value) / (t - point));
+        let left = one_sided_limit(quotient, point, -1.0)?;
+        let right = one_sided_limit(quotient, point, 1.0)?;
+        Some(left.is_finite() && right.is_finite() && limits_agree(left, right))
+    }
+
     /// Check if this expression approximately equals another at random points.
     ///
     /// Useful for quick verification that two expressions are equivalent.
+    /// `tolerance` is used both as the absolute and the relative tolerance;
+    /// see [`Expr::approx_equals_tol`].
     pub fn approx_equals(&self, other: &Expr, num_tests: usize, tolerance: f64) -> bool {
+        self.approx_equals_tol(other, num_tests, tolerance, tolerance)
+    }
+
+    /// Check approximate equality at random points with separate tolerances.
+    ///
+    /// Two values agree when `|a - b| ≤ atol + rtol · max(|a|, |b|)`, so large
+    /// magnitudes are compared relatively and values near zero absolutely.
+    pub fn approx_equals_tol(&self, other: &Expr, num_tests: usize, atol: f64, rtol: f64) -> bool {
         use rand::Rng;
         let mut rng = rand::thread_rng();
 
@@ -320,7 +594,7 @@ impl Expr {
             // Evaluate both
             match (self.evaluate(&env), other.evaluate(&env)) {
                 (Some(v1), Some(v2)) => {
-                    if (v1 - v2).abs() > tolerance * (1.0 + v1.abs().max(v2.abs())) {
+                    if (v1 - v2).abs() > atol + rtol * v1.abs().max(v2.abs()) {
                         return false;
                     }
                 }
@@ -346,7 +620,7 @@ impl Expr {
                     vars.push(*s);
                 }
             }
-            Expr::Const(_) | Expr::Pi | Expr::E => {}
+            Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => {}
             Expr::Neg(e)
             | Expr::Sqrt(e)
             | Expr::Sin(e)
@@ -355,6 +629,12 @@ impl Expr {
             | Expr::Arcsin(e)
             | Expr::Arccos(e)
             | Expr::Arctan(e)
+            | Expr::Sinh(e)
+            | Expr::Cosh(e)
+            | Expr::Tanh(e)
+            | Expr::Arcsinh(e)
+            | Expr::Arccosh(e)
+            | Expr::Arctanh(e)
             | Expr::Ln(e)
             | Expr::Exp(e)
             | Expr::Abs(e) => {
@@ -385,6 +665,17 @@ impl Expr {
                     vars.push(*var);
                 }
             }
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                ..
+            } => {
+                expr.collect_vars(vars);
+                // The bound variable is not free
+                vars.retain(|v| v != var);
+                approaching.collect_vars(vars);
+            }
             Expr::Equation { lhs, rhs }
             | Expr::GCD(lhs, rhs)
             | Expr::LCM(lhs, rhs)
@@ -433,6 +724,12 @@ impl Expr {
             Expr::Not(e) => {
                 e.collect_vars(vars);
             }
+            Expr::Piecewise(branches) => {
+                for (condition, value) in branches {
+                    condition.expr.collect_vars(vars);
+                    value.collect_vars(vars);
+                }
+            }
         }
     }
 
@@ -447,7 +744,7 @@ impl Expr {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::SymbolTable;
+    use crate::{Constraint, SymbolTable};
 
     #[test]
     fn test_constant_evaluation() {
@@ -506,4 +803,236 @@ mod tests {
 
         assert!(expr1.approx_equals(&expr2, 10, 1e-10));
     }
+
+    #[test]
+    fn test_inverse_hyperbolic_evaluation() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let mut env = Env::new();
+        let at = |f: fn(Box<Expr>) -> Expr, env: &Env| f(Box::new(Expr::Var(x))).evaluate(env);
+
+        // Each inverts its hyperbolic function
+        env.insert(x, 0.5_f64.sinh());
+        assert!((at(Expr::Arcsinh, &env).unwrap() - 0.5).abs() < 1e-12);
+        env.insert(x, 2.0_f64.cosh());
+        assert!((at(Expr::Arccosh, &env).unwrap() - 2.0).abs() < 1e-12);
+        env.insert(x, 0.25_f64.tanh());
+        assert!((at(Expr::Arctanh, &env).unwrap() - 0.25).abs() < 1e-12);
+
+        env.insert(x, 0.0);
+        assert_eq!(at(Expr::Arcsinh, &env), Some(0.0));
+        assert!(at(Expr::Arccosh, &env).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_angle_mode() {
+        // sin(30): 1/2 in degrees, sin of 30 radians otherwise
+        let expr = Expr::Sin(Box::new(Expr::int(30)));
+        let env = Env::new();
+        let degrees = expr.evaluate_with(&env, AngleMode::Degrees).unwrap();
+        assert!((degrees - 0.5).abs() < 1e-12);
+        assert_eq!(expr.evaluate(&env), Some(30f64.sin()));
+
+        // Inverse functions answer in the same unit
+        let expr = Expr::Arctan(Box::new(Expr::int(1)));
+        let degrees = expr.evaluate_with(&env, AngleMode::Degrees).unwrap();
+        assert!((degrees - 45.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_evaluate_differentiating() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let mut env = Env::new();
+        env.insert(x, 2.0);
+
+        // d/dx(x³) = 3x² = 12 at x = 2
+        let cube = Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(3)));
+        let derivative = Expr::Derivative {
+            expr: Box::new(cube.clone()),
+            var: x,
+        };
+        assert_eq!(derivative.evaluate(&env), None);
+        let value = derivative
+            .evaluate_differentiating(&env, AngleMode::Radians)
+            .unwrap();
+        assert!((value - 12.0).abs() < 1e-6);
+
+        // d/dx(∫x³ dx) is the integrand, but a bare integral has no value
+        let integral = Expr::Integral {
+            expr: Box::new(cube),
+            var: x,
+        };
+        let derivative = Expr::Derivative {
+            expr: Box::new(integral.clone()),
+            var: x,
+        };
+        let mode = AngleMode::Radians;
+        assert_eq!(derivative.evaluate_differentiating(&env, mode), Some(8.0));
+        assert_eq!(integral.evaluate_differentiating(&env, mode), None);
+    }
+
+    #[test]
+    fn test_one_sided_limits() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = crate::parse::Parser::new(&mut symbols);
+        let mut limit = |s: &str| parser.parse(s).unwrap().evaluate(&Env::new());
+
+        // 1/x blows up with opposite signs on the two sides of 0
+        assert_eq!(limit("lim(1/x, x -> 0+)"), Some(f64::INFINITY));
+        assert_eq!(limit("lim(1/x, x -> 0-)"), Some(f64::NEG_INFINITY));
+        assert_eq!(limit("lim(1/x, x -> 0)"), None);
+
+        // 1/x² does so with the same sign, so the two-sided limit exists
+        assert_eq!(limit("lim(1/x^2, x -> 0)"), Some(f64::INFINITY));
+
+        let close = |value: Option<f64>, expected: f64| (value.unwrap() - expected).abs() < 1e-6;
+        assert!(close(limit("lim(sin(x)/x, x -> 0)"), 1.0));
+        assert!(close(limit("lim((cos(x) - 1)/x^2, x -> 0)"), -0.5));
+        assert!(close(
+            limit("lim((1 + x)^(1/x), x -> 0+)"),
+            std::f64::consts::E
+        ));
+        assert!(close(limit("lim(abs(x)/x, x -> 0-)"), -1.0));
+        assert_eq!(limit("lim(abs(x)/x, x -> 0)"), None);
+
+        // Oscillation has no limit; sqrt(x) is undefined left of 0
+        assert_eq!(limit("lim(sin(1/x), x -> 0+)"), None);
+        assert_eq!(limit("lim(sqrt(x), x -> 0-)"), None);
+    }
+
+    #[test]
+    fn test_limits_at_infinity() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = crate::parse::Parser::new(&mut symbols);
+        let mut limit = |s: &str| parser.parse(s).unwrap().evaluate(&Env::new());
+
+        assert!(limit("lim(1/x, x -> inf)").unwrap().abs() < 1e-9);
+        assert!(limit("lim(exp(x), x -> -inf)").unwrap().abs() < 1e-9);
+        let e = limit("lim((1 + 1/x)^x, x -> ∞)").unwrap();
+        assert!((e - std::f64::consts::E).abs() < 1e-6);
+
+        // Divergence is detected, with its sign
+        assert_eq!(limit("lim(x, x -> inf)"), Some(f64::INFINITY));
+        assert_eq!(limit("lim(x, x -> -inf)"), Some(f64::NEG_INFINITY));
+        assert_eq!(limit("lim(x^2, x -> -inf)"), Some(f64::INFINITY));
+        assert_eq!(limit("lim(sin(x), x -> inf)"), None);
+
+        assert_eq!(limit("inf"), Some(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_continuity_and_differentiability() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let mut parser = crate::parse::Parser::new(&mut symbols);
+        let mut parse = |s: &str| parser.parse(s).unwrap();
+
+        // |x| has a corner at 0
+        let abs = parse("abs(x)");
+        assert_eq!(abs.is_continuous_at(x, 0.0), Some(true));
+        assert_eq!(abs.is_differentiable_at(x, 0.0), Some(false));
+        assert_eq!(abs.is_differentiable_at(x, 1.0), Some(true));
+
+        let square = parse("x^2");
+        for point in [-100.0, -3.0, -0.5, 0.0, 1.0, 2.5, 1000.0] {
+            assert_eq!(square.is_continuous_at(x, point), Some(true), "{}", point);
+            assert_eq!(
+                square.is_differentiable_at(x, point),
+                Some(true),
+                "{}",
+                point
+            );
+        }
+
+        // Undefined at the point, and a vertical tangent
+        assert_eq!(parse("1/x").is_continuous_at(x, 0.0), Some(false));
+        assert_eq!(parse("1/x").is_differentiable_at(x, 0.0), Some(false));
+        let cbrt = parse("abs(x)^(1/3)");
+        assert_eq!(cbrt.is_continuous_at(x, 0.0), Some(true));
+        assert_eq!(cbrt.is_differentiable_at(x, 0.0), Some(false));
+
+        // As written, x·sin(1/x) has no value at 0 to be continuous with
+        assert_eq!(parse("x * sin(1/x)").is_continuous_at(x, 0.0), Some(false));
+
+        // Undecidable: a missing side, another free variable
+        assert_eq!(parse("sqrt(x)").is_continuous_at(x, 0.0), None);
+        assert_eq!(parse("x + y").is_continuous_at(x, 0.0), None);
+    }
+
+    #[test]
+    fn test_piecewise_evaluation() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let vx = || Box::new(Expr::Var(x));
+
+        // f(x) = x² for x < 1, 2x - 1 otherwise
+        let expr = Expr::Piecewise(vec![
+            (
+                Constraint {
+                    expr: Expr::Lt(vx(), Box::new(Expr::int(1))),
+                },
+                Expr::Pow(vx(), Box::new(Expr::int(2))),
+            ),
+            (
+                Constraint {
+                    expr: Expr::Gte(vx(), Box::new(Expr::int(1))),
+                },
+                Expr::Sub(
+                    Box::new(Expr::Mul(Box::new(Expr::int(2)), vx())),
+                    Box::new(Expr::int(1)),
+                ),
+            ),
+        ]);
+
+        let at = |value: f64| {
+            let mut env = Env::new();
+            env.insert(x, value);
+            expr.evaluate(&env)
+        };
+        assert_eq!(at(-3.0), Some(9.0));
+        assert_eq!(at(0.5), Some(0.25));
+        assert_eq!(at(1.0), Some(1.0));
+        assert_eq!(at(4.0), Some(7.0));
+        assert_eq!(expr.free_vars(), vec![x]);
+
+        // A point no branch covers is undefined
+        let partial = Expr::Piecewise(vec![(
+            Constraint {
+                expr: Expr::Gt(vx(), Box::new(Expr::int(0))),
+            },
+            Expr::Var(x),
+        )]);
+        let mut env = Env::new();
+        env.insert(x, -1.0);
+        assert_eq!(partial.evaluate(&env), None);
+    }
+
+    #[test]
+    fn test_modular_power() {
+        let env = Env::new();
+        let mod_pow = |a: i64, b: i64, m: i64| {
+            Expr::Mod(
+                Box::new(Expr::Pow(Box::new(Expr::int(a)), Box::new(Expr::int(b)))),
+                Box::new(Expr::int(m)),
+            )
+        };
+
+        // 7^256 is far beyond f64 precision
+        assert_eq!(mod_pow(7, 256, 13).evaluate(&env), Some(9.0));
+        assert_eq!(
+            mod_pow(2, 1_000_000, 1_000_000_007).evaluate(&env),
+            Some(235_042_059.0)
+        );
+
+        // Agrees with computing the power first where that is exact
+        for a in -4..=6i64 {
+            for b in 0..=6u32 {
+                for m in [2i64, 3, 7, 10, -5] {
+                    let naive = (a.pow(b) % m) as f64;
+                    assert_eq!(mod_pow(a, b as i64, m).evaluate(&env), Some(naive));
+                }
+            }
+        }
+    }
 }
@@ -9,7 +9,8 @@
 //! The [`Expr`] enum is the core data structure representing mathematical expressions
 //! as an abstract syntax tree (AST).
 
-use crate::{Rational, Symbol};
+use crate::proof::Constraint;
+use crate::{MathError, Rational, Symbol, SymbolTable};
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 
@@ -36,6 +37,9 @@ pub enum Expr {
     /// Mathematical constant e (Euler's number)
     E,
 
+    /// Infinity, `∞` or `-∞`, as the point of a [`Expr::Limit`] or its value
+    Infinity { negative: bool },
+
     // ========== Unary Operations ==========
     /// Negation: -a
     Neg(Box<Expr>),
@@ -61,6 +65,24 @@ pub enum Expr {
     /// Inverse tangent: arctan(a)
     Arctan(Box<Expr>),
 
+    /// Hyperbolic sine: sinh(a)
+    Sinh(Box<Expr>),
+
+    /// Hyperbolic cosine: cosh(a)
+    Cosh(Box<Expr>),
+
+    /// Hyperbolic tangent: tanh(a)
+    Tanh(Box<Expr>),
+
+    /// Inverse hyperbolic sine: arcsinh(a)
+    Arcsinh(Box<Expr>),
+
+    /// Inverse hyperbolic cosine: arccosh(a)
+    Arccosh(Box<Expr>),
+
+    /// Inverse hyperbolic tangent: arctanh(a)
+    Arctanh(Box<Expr>),
+
     /// Natural logarithm: ln(a)
     Ln(Box<Expr>),
 
@@ -104,6 +126,14 @@ pub enum Expr {
     /// Integral: ∫ expr dx
     Integral { expr: Box<Expr>, var: Symbol },
 
+    /// Limit: lim_{var→approaching} expr, taken from one side or both
+    Limit {
+        expr: Box<Expr>,
+        var: Symbol,
+        approaching: Box<Expr>,
+        direction: LimitDirection,
+    },
+
     // ========== Relations ==========
     /// Equation: lhs = rhs
     Equation { lhs: Box<Expr>, rhs: Box<Expr> },
@@ -192,6 +222,144 @@ pub enum Expr {
 
     /// Implication: P → Q
     Implies(Box<Expr>, Box<Expr>),
+
+    // ========== Case Analysis ==========
+    /// Piecewise definition: the value of the first branch whose constraint
+    /// holds.
+    /// Example: |x| = { -x if x < 0; x if x ≥ 0 }
+    Piecewise(Vec<(Constraint, Expr)>),
+}
+
+/// The side a [`Expr::Limit`] approaches its point from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum LimitDirection {
+    /// From below: `x → a⁻`
+    Left,
+    /// From above: `x → a⁺`
+    Right,
+    /// From both sides, which must agree
+    #[default]
+    Both,
+}
+
+/// The variant of an [`Expr`] without its contents.
+///
+/// Used where only the shape of the root matters, e.g. to index rules by
+/// the nodes they can rewrite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ExprKind {
+    Const,
+    Var,
+    Pi,
+    E,
+    Infinity,
+    Neg,
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Arcsin,
+    Arccos,
+    Arctan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Arcsinh,
+    Arccosh,
+    Arctanh,
+    Ln,
+    Exp,
+    Abs,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Sum,
+    Product,
+    Derivative,
+    Integral,
+    Limit,
+    Equation,
+    Gte,
+    Gt,
+    Lte,
+    Lt,
+    GCD,
+    LCM,
+    Mod,
+    Floor,
+    Ceiling,
+    Factorial,
+    Binomial,
+    Summation,
+    BigProduct,
+    ForAll,
+    Exists,
+    And,
+    Or,
+    Not,
+    Implies,
+    Piecewise,
+}
+
+impl ExprKind {
+    /// Every kind, in declaration order.
+    pub const ALL: &'static [ExprKind] = &[
+        ExprKind::Const,
+        ExprKind::Var,
+        ExprKind::Pi,
+        ExprKind::E,
+        ExprKind::Infinity,
+        ExprKind::Neg,
+        ExprKind::Sqrt,
+        ExprKind::Sin,
+        ExprKind::Cos,
+        ExprKind::Tan,
+        ExprKind::Arcsin,
+        ExprKind::Arccos,
+        ExprKind::Arctan,
+        ExprKind::Sinh,
+        ExprKind::Cosh,
+        ExprKind::Tanh,
+        ExprKind::Arcsinh,
+        ExprKind::Arccosh,
+        ExprKind::Arctanh,
+        ExprKind::Ln,
+        ExprKind::Exp,
+        ExprKind::Abs,
+        ExprKind::Add,
+        ExprKind::Sub,
+        ExprKind::Mul,
+        ExprKind::Div,
+        ExprKind::Pow,
+        ExprKind::Sum,
+        ExprKind::Product,
+        ExprKind::Derivative,
+        ExprKind::Integral,
+        ExprKind::Limit,
+        ExprKind::Equation,
+        ExprKind::Gte,
+        ExprKind::Gt,
+        ExprKind::Lte,
+        ExprKind::Lt,
+        ExprKind::GCD,
+        ExprKind::LCM,
+        ExprKind::Mod,
+        ExprKind::Floor,
+        ExprKind::Ceiling,
+        ExprKind::Factorial,
+        ExprKind::Binomial,
+        ExprKind::Summation,
+        ExprKind::BigProduct,
+        ExprKind::ForAll,
+        ExprKind::Exists,
+        ExprKind::And,
+        ExprKind::Or,
+        ExprKind::Not,
+        ExprKind::Implies,
+        ExprKind::Piecewise,
+    ];
 }
 
 /// A term in a sum: coefficient × expression
@@ -253,6 +421,7 @@ impl PartialEq for Expr {
             (Expr::Var(a), Expr::Var(b)) => a == b,
             (Expr::Pi, Expr::Pi) => true,
             (Expr::E, Expr::E) => true,
+            (Expr::Infinity { negative: a }, Expr::Infinity { negative: b }) => a == b,
             (Expr::Neg(a), Expr::Neg(b)) => a == b,
             (Expr::Sqrt(a), Expr::Sqrt(b)) => a == b,
             (Expr::Sin(a), Expr::Sin(b)) => a == b,
@@ -261,6 +430,12 @@ impl PartialEq for Expr {
             (Expr::Arcsin(a), Expr::Arcsin(b)) => a == b,
             (Expr::Arccos(a), Expr::Arccos(b)) => a == b,
             (Expr::Arctan(a), Expr::Arctan(b)) => a == b,
+            (Expr::Sinh(a), Expr::Sinh(b)) => a == b,
+            (Expr::Cosh(a), Expr::Cosh(b)) => a == b,
+            (Expr::Tanh(a), Expr::Tanh(b)) => a == b,
+            (Expr::Arcsinh(a), Expr::Arcsinh(b)) => a == b,
+            (Expr::Arccosh(a), Expr::Arccosh(b)) => a == b,
+            (Expr::Arctanh(a), Expr::Arctanh(b)) => a == b,
             (Expr::Ln(a), Expr::Ln(b)) => a == b,
             (Expr::Exp(a), Expr::Exp(b)) => a == b,
             (Expr::Abs(a), Expr::Abs(b)) => a == b,
@@ -277,6 +452,20 @@ impl PartialEq for Expr {
             (Expr::Integral { expr: e1, var: v1 }, Expr::Integral { expr: e2, var: v2 }) => {
                 e1 == e2 && v1 == v2
             }
+            (
+                Expr::Limit {
+                    expr: e1,
+                    var: v1,
+                    approaching: a1,
+                    direction: d1,
+                },
+                Expr::Limit {
+                    expr: e2,
+                    var: v2,
+                    approaching: a2,
+                    direction: d2,
+                },
+            ) => e1 == e2 && v1 == v2 && a1 == a2 && d1 == d2,
             (Expr::Equation { lhs: l1, rhs: r1 }, Expr::Equation { lhs: l2, rhs: r2 }) => {
                 l1 == l2 && r1 == r2
             }
@@ -284,6 +473,75 @@ impl PartialEq for Expr {
             (Expr::Gt(a1, a2), Expr::Gt(b1, b2)) => a1 == b1 && a2 == b2,
             (Expr::Lte(a1, a2), Expr::Lte(b1, b2)) => a1 == b1 && a2 == b2,
             (Expr::Lt(a1, a2), Expr::Lt(b1, b2)) => a1 == b1 && a2 == b2,
+            (Expr::GCD(a1, a2), Expr::GCD(b1, b2))
+            | (Expr::LCM(a1, a2), Expr::LCM(b1, b2))
+            | (Expr::Mod(a1, a2), Expr::Mod(b1, b2))
+            | (Expr::Binomial(a1, a2), Expr::Binomial(b1, b2))
+            | (Expr::And(a1, a2), Expr::And(b1, b2))
+            | (Expr::Or(a1, a2), Expr::Or(b1, b2))
+            | (Expr::Implies(a1, a2), Expr::Implies(b1, b2)) => a1 == b1 && a2 == b2,
+            (Expr::Floor(a), Expr::Floor(b))
+            | (Expr::Ceiling(a), Expr::Ceiling(b))
+            | (Expr::Factorial(a), Expr::Factorial(b))
+            | (Expr::Not(a), Expr::Not(b)) => a == b,
+            (
+                Expr::Summation {
+                    var: v1,
+                    from: f1,
+                    to: t1,
+                    body: b1,
+                },
+                Expr::Summation {
+                    var: v2,
+                    from: f2,
+                    to: t2,
+                    body: b2,
+                },
+            )
+            | (
+                Expr::BigProduct {
+                    var: v1,
+                    from: f1,
+                    to: t1,
+                    body: b1,
+                },
+                Expr::BigProduct {
+                    var: v2,
+                    from: f2,
+                    to: t2,
+                    body: b2,
+                },
+            ) => v1 == v2 && f1 == f2 && t1 == t2 && b1 == b2,
+            (
+                Expr::ForAll {
+                    var: v1,
+                    domain: d1,
+                    body: b1,
+                },
+                Expr::ForAll {
+                    var: v2,
+                    domain: d2,
+                    body: b2,
+                },
+            )
+            | (
+                Expr::Exists {
+                    var: v1,
+                    domain: d1,
+                    body: b1,
+                },
+                Expr::Exists {
+                    var: v2,
+                    domain: d2,
+                    body: b2,
+                },
+            ) => v1 == v2 && d1 == d2 && b1 == b2,
+            (Expr::Piecewise(a), Expr::Piecewise(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((c1, e1), (c2, e2))| c1.expr == c2.expr && e1 == e2)
+            }
             _ => false,
         }
     }
@@ -323,6 +581,7 @@ impl Hash for Expr {
             Expr::Const(r) => r.hash(state),
             Expr::Var(s) => s.hash(state),
             Expr::Pi | Expr::E => {} // discriminant already hashed
+            Expr::Infinity { negative } => negative.hash(state),
             Expr::Neg(e)
             | Expr::Sqrt(e)
             | Expr::Sin(e)
@@ -331,6 +590,12 @@ impl Hash for Expr {
             | Expr::Arcsin(e)
             | Expr::Arccos(e)
             | Expr::Arctan(e)
+            | Expr::Sinh(e)
+            | Expr::Cosh(e)
+            | Expr::Tanh(e)
+            | Expr::Arcsinh(e)
+            | Expr::Arccosh(e)
+            | Expr::Arctanh(e)
             | Expr::Ln(e)
             | Expr::Exp(e)
             | Expr::Abs(e) => e.hash(state),
@@ -348,6 +613,17 @@ impl Hash for Expr {
                 expr.hash(state);
                 var.hash(state);
             }
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => {
+                expr.hash(state);
+                var.hash(state);
+                approaching.hash(state);
+                direction.hash(state);
+            }
             Expr::Equation { lhs, rhs }
             | Expr::GCD(lhs, rhs)
             | Expr::LCM(lhs, rhs)
@@ -388,6 +664,12 @@ impl Hash for Expr {
                 b.hash(state);
             }
             Expr::Not(e) => e.hash(state),
+            Expr::Piecewise(branches) => {
+                for (condition, value) in branches {
+                    condition.expr.hash(state);
+                    value.hash(state);
+                }
+            }
         }
     }
 }
@@ -462,6 +744,7 @@ impl Ord for Expr {
         match (self, other) {
             (Expr::Const(a), Expr::Const(b)) => a.cmp(b),
             (Expr::Var(a), Expr::Var(b)) => a.cmp(b),
+            (Expr::Infinity { negative: a }, Expr::Infinity { negative: b }) => b.cmp(a),
             (Expr::Neg(a), Expr::Neg(b)) => a.cmp(b),
             (Expr::Sqrt(a), Expr::Sqrt(b)) => a.cmp(b),
             (Expr::Sin(a), Expr::Sin(b)) => a.cmp(b),
@@ -470,6 +753,12 @@ impl Ord for Expr {
             (Expr::Arcsin(a), Expr::Arcsin(b)) => a.cmp(b),
             (Expr::Arccos(a), Expr::Arccos(b)) => a.cmp(b),
             (Expr::Arctan(a), Expr::Arctan(b)) => a.cmp(b),
+            (Expr::Sinh(a), Expr::Sinh(b)) => a.cmp(b),
+            (Expr::Cosh(a), Expr::Cosh(b)) => a.cmp(b),
+            (Expr::Tanh(a), Expr::Tanh(b)) => a.cmp(b),
+            (Expr::Arcsinh(a), Expr::Arcsinh(b)) => a.cmp(b),
+            (Expr::Arccosh(a), Expr::Arccosh(b)) => a.cmp(b),
+            (Expr::Arctanh(a), Expr::Arctanh(b)) => a.cmp(b),
             (Expr::Ln(a), Expr::Ln(b)) => a.cmp(b),
             (Expr::Exp(a), Expr::Exp(b)) => a.cmp(b),
             (Expr::Abs(a), Expr::Abs(b)) => a.cmp(b),
@@ -480,6 +769,105 @@ impl Ord for Expr {
             | (Expr::Pow(a1, a2), Expr::Pow(b1, b2)) => a1.cmp(b1).then_with(|| a2.cmp(b2)),
             (Expr::Sum(a), Expr::Sum(b)) => a.cmp(b),
             (Expr::Product(a), Expr::Product(b)) => a.cmp(b),
+            (Expr::Derivative { expr: e1, var: v1 }, Expr::Derivative { expr: e2, var: v2 })
+            | (Expr::Integral { expr: e1, var: v1 }, Expr::Integral { expr: e2, var: v2 }) => {
+                e1.cmp(e2).then_with(|| v1.cmp(v2))
+            }
+            (
+                Expr::Limit {
+                    expr: e1,
+                    var: v1,
+                    approaching: a1,
+                    direction: d1,
+                },
+                Expr::Limit {
+                    expr: e2,
+                    var: v2,
+                    approaching: a2,
+                    direction: d2,
+                },
+            ) => e1
+                .cmp(e2)
+                .then_with(|| v1.cmp(v2))
+                .then_with(|| a1.cmp(a2))
+                .then_with(|| d1.cmp(d2)),
+            (Expr::Equation { lhs: a1, rhs: a2 }, Expr::Equation { lhs: b1, rhs: b2 })
+            | (Expr::Gte(a1, a2), Expr::Gte(b1, b2))
+            | (Expr::Gt(a1, a2), Expr::Gt(b1, b2))
+            | (Expr::Lte(a1, a2), Expr::Lte(b1, b2))
+            | (Expr::Lt(a1, a2), Expr::Lt(b1, b2))
+            | (Expr::GCD(a1, a2), Expr::GCD(b1, b2))
+            | (Expr::LCM(a1, a2), Expr::LCM(b1, b2))
+            | (Expr::Mod(a1, a2), Expr::Mod(b1, b2))
+            | (Expr::Binomial(a1, a2), Expr::Binomial(b1, b2))
+            | (Expr::And(a1, a2), Expr::And(b1, b2))
+            | (Expr::Or(a1, a2), Expr::Or(b1, b2))
+            | (Expr::Implies(a1, a2), Expr::Implies(b1, b2)) => a1.cmp(b1).then_with(|| a2.cmp(b2)),
+            (Expr::Floor(a), Expr::Floor(b))
+            | (Expr::Ceiling(a), Expr::Ceiling(b))
+            | (Expr::Factorial(a), Expr::Factorial(b))
+            | (Expr::Not(a), Expr::Not(b)) => a.cmp(b),
+            (
+                Expr::Summation {
+                    var: v1,
+                    from: f1,
+                    to: t1,
+                    body: b1,
+                },
+                Expr::Summation {
+                    var: v2,
+                    from: f2,
+                    to: t2,
+                    body: b2,
+                },
+            )
+            | (
+                Expr::BigProduct {
+                    var: v1,
+                    from: f1,
+                    to: t1,
+                    body: b1,
+                },
+                Expr::BigProduct {
+                    var: v2,
+                    from: f2,
+                    to: t2,
+                    body: b2,
+                },
+            ) => v1
+                .cmp(v2)
+                .then_with(|| f1.cmp(f2))
+                .then_with(|| t1.cmp(t2))
+                .then_with(|| b1.cmp(b2)),
+            (
+                Expr::ForAll {
+                    var: v1,
+                    domain: d1,
+                    body: b1,
+                },
+                Expr::ForAll {
+                    var: v2,
+                    domain: d2,
+                    body: b2,
+                },
+            )
+            | (
+                Expr::Exists {
+                    var: v1,
+                    domain: d1,
+                    body: b1,
+                },
+                Expr::Exists {
+                    var: v2,
+                    domain: d2,
+                    body: b2,
+                },
+            ) => v1.cmp(v2).then_with(|| d1.cmp(d2)).then_with(|| b1.cmp(b2)),
+            (Expr::Piecewise(a), Expr::Piecewise(b)) => a
+                .iter()
+                .map(|(c, e)| (&c.expr, e))
+                .cmp(b.iter().map(|(c, e)| (&c.expr, e))),
+            // Remaining variants (Pi, E) carry no data
             _ => Ordering::Equal,
         }
     }
@@ -528,6 +916,71 @@ impl Expr {
         Expr::Const(Rational::new(numer, denom))
     }
 
+    /// Create a variable interned in the thread-local default symbol table.
+    #[cfg(feature = "global-interner")]
+    pub fn var(name: &str) -> Self {
+        Expr::Var(crate::SymbolTable::intern_global(name))
+    }
+
+    /// The variant of this expression.
+    pub fn kind(&self) -> ExprKind {
+        match self {
+            Expr::Const(..) => ExprKind::Const,
+            Expr::Var(..) => ExprKind::Var,
+            Expr::Pi => ExprKind::Pi,
+            Expr::E => ExprKind::E,
+            Expr::Infinity { .. } => ExprKind::Infinity,
+            Expr::Neg(..) => ExprKind::Neg,
+            Expr::Sqrt(..) => ExprKind::Sqrt,
+            Expr::Sin(..) => ExprKind::Sin,
+            Expr::Cos(..) => ExprKind::Cos,
+            Expr::Tan(..) => ExprKind::Tan,
+            Expr::Arcsin(..) => ExprKind::Arcsin,
+            Expr::Arccos(..) => ExprKind::Arccos,
+            Expr::Arctan(..) => ExprKind::Arctan,
+            Expr::Sinh(..) => ExprKind::Sinh,
+            Expr::Cosh(..) => ExprKind::Cosh,
+            Expr::Tanh(..) => ExprKind::Tanh,
+            Expr::Arcsinh(..) => ExprKind::Arcsinh,
+            Expr::Arccosh(..) => ExprKind::Arccosh,
+            Expr::Arctanh(..) => ExprKind::Arctanh,
+            Expr::Ln(..) => ExprKind::Ln,
+            Expr::Exp(..) => ExprKind::Exp,
+            Expr::Abs(..) => ExprKind::Abs,
+            Expr::Add(..) => ExprKind::Add,
+            Expr::Sub(..) => ExprKind::Sub,
+            Expr::Mul(..) => ExprKind::Mul,
+            Expr::Div(..) => ExprKind::Div,
+            Expr::Pow(..) => ExprKind::Pow,
+            Expr::Sum(..) => ExprKind::Sum,
+            Expr::Product(..) => ExprKind::Product,
+            Expr::Derivative { .. } => ExprKind::Derivative,
+            Expr::Integral { .. } => ExprKind::Integral,
+            Expr::Limit { .. } => ExprKind::Limit,
+            Expr::Equation { .. } => ExprKind::Equation,
+            Expr::Gte(..) => ExprKind::Gte,
+            Expr::Gt(..) => ExprKind::Gt,
+            Expr::Lte(..) => ExprKind::Lte,
+            Expr::Lt(..) => ExprKind::Lt,
+            Expr::GCD(..) => ExprKind::GCD,
+            Expr::LCM(..) => ExprKind::LCM,
+            Expr::Mod(..) => ExprKind::Mod,
+            Expr::Floor(..) => ExprKind::Floor,
+            Expr::Ceiling(..) => ExprKind::Ceiling,
+            Expr::Factorial(..) => ExprKind::Factorial,
+            Expr::Binomial(..) => ExprKind::Binomial,
+            Expr::Summation { .. } => ExprKind::Summation,
+            Expr::BigProduct { .. } => ExprKind::BigProduct,
+            Expr::ForAll { .. } => ExprKind::ForAll,
+            Expr::Exists { .. } => ExprKind::Exists,
+            Expr::And(..) => ExprKind::And,
+            Expr::Or(..) => ExprKind::Or,
+            Expr::Not(..) => ExprKind::Not,
+            Expr::Implies(..) => ExprKind::Implies,
+            Expr::Piecewise(_) => ExprKind::Piecewise,
+        }
+    }
+
     /// Check if this expression is a constant zero.
     pub fn is_zero(&self) -> bool {
         matches!(self, Expr::Const(r) if r.is_zero())
@@ -548,6 +1001,182 @@ impl Expr {
         matches!(self, Expr::Var(_))
     }
 
+    /// Direct subexpressions, in a fixed order.
+    ///
+    /// Bound variables are not subexpressions: `Σ_{i=1}^{n} i²` has children
+    /// `1`, `n` and `i²`.
+    pub fn children(&self) -> Vec<&Expr> {
+        crate::diff::children(self)
+    }
+
+    /// Direct subexpressions, mutably, in the same order as
+    /// [`children`](Self::children).
+    pub fn children_mut(&mut self) -> Vec<&mut Expr> {
+        crate::diff::children_mut(self)
+    }
+
+    /// Rewrite the symbols of an expression built against `from` so they
+    /// refer to the same names in `to`, interning names `to` lacks.
+    ///
+    /// Returns [`MathError::UndefinedVariable`] if a symbol is not in `from`.
+    pub fn remap_symbols(
+        &self,
+        from: &SymbolTable,
+        to: &mut SymbolTable,
+    ) -> Result<Expr, MathError> {
+        self.map_symbols(&mut |symbol| match from.resolve(symbol) {
+            Some(name) => Ok(to.intern(name)),
+            None => Err(MathError::UndefinedVariable(format!("{:?}", symbol))),
+        })
+    }
+
+    /// Apply `f` to every symbol, bound or free.
+    fn map_symbols<F>(&self, f: &mut F) -> Result<Expr, MathError>
+    where
+        F: FnMut(Symbol) -> Result<Symbol, MathError>,
+    {
+        let go = |e: &Expr, f: &mut F| e.map_symbols(f).map(Box::new);
+        Ok(match self {
+            Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => self.clone(),
+            Expr::Var(v) => Expr::Var(f(*v)?),
+            Expr::Neg(e) => Expr::Neg(go(e, f)?),
+            Expr::Sqrt(e) => Expr::Sqrt(go(e, f)?),
+            Expr::Sin(e) => Expr::Sin(go(e, f)?),
+            Expr::Cos(e) => Expr::Cos(go(e, f)?),
+            Expr::Tan(e) => Expr::Tan(go(e, f)?),
+            Expr::Arcsin(e) => Expr::Arcsin(go(e, f)?),
+            Expr::Arccos(e) => Expr::Arccos(go(e, f)?),
+            Expr::Arctan(e) => Expr::Arctan(go(e, f)?),
+            Expr::Sinh(e) => Expr::Sinh(go(e, f)?),
+            Expr::Cosh(e) => Expr::Cosh(go(e, f)?),
+            Expr::Tanh(e) => Expr::Tanh(go(e, f)?),
+            Expr::Arcsinh(e) => Expr::Arcsinh(go(e, f)?),
+            Expr::Arccosh(e) => Expr::Arccosh(go(e, f)?),
+            Expr::Arctanh(e) => Expr::Arctanh(go(e, f)?),
+            Expr::Ln(e) => Expr::Ln(go(e, f)?),
+            Expr::Exp(e) => Expr::Exp(go(e, f)?),
+            Expr::Abs(e) => Expr::Abs(go(e, f)?),
+            Expr::Floor(e) => Expr::Floor(go(e, f)?),
+            Expr::Ceiling(e) => Expr::Ceiling(go(e, f)?),
+            Expr::Factorial(e) => Expr::Factorial(go(e, f)?),
+            Expr::Not(e) => Expr::Not(go(e, f)?),
+            Expr::Add(a, b) => Expr::Add(go(a, f)?, go(b, f)?),
+            Expr::Sub(a, b) => Expr::Sub(go(a, f)?, go(b, f)?),
+            Expr::Mul(a, b) => Expr::Mul(go(a, f)?, go(b, f)?),
+            Expr::Div(a, b) => Expr::Div(go(a, f)?, go(b, f)?),
+            Expr::Pow(a, b) => Expr::Pow(go(a, f)?, go(b, f)?),
+            Expr::Gte(a, b) => Expr::Gte(go(a, f)?, go(b, f)?),
+            Expr::Gt(a, b) => Expr::Gt(go(a, f)?, go(b, f)?),
+            Expr::Lte(a, b) => Expr::Lte(go(a, f)?, go(b, f)?),
+            Expr::Lt(a, b) => Expr::Lt(go(a, f)?, go(b, f)?),
+            Expr::GCD(a, b) => Expr::GCD(go(a, f)?, go(b, f)?),
+            Expr::LCM(a, b) => Expr::LCM(go(a, f)?, go(b, f)?),
+            Expr::Mod(a, b) => Expr::Mod(go(a, f)?, go(b, f)?),
+            Expr::Binomial(a, b) => Expr::Binomial(go(a, f)?, go(b, f)?),
+            Expr::And(a, b) => Expr::And(go(a, f)?, go(b, f)?),
+            Expr::Or(a, b) => Expr::Or(go(a, f)?, go(b, f)?),
+            Expr::Implies(a, b) => Expr::Implies(go(a, f)?, go(b, f)?),
+            Expr::Equation { lhs, rhs } => Expr::Equation {
+                lhs: go(lhs, f)?,
+                rhs: go(rhs, f)?,
+            },
+            Expr::Sum(terms) => Expr::Sum(
+                terms
+                    .iter()
+                    .map(|t| {
+                        Ok(Term {
+                            coeff: t.coeff,
+                            expr: t.expr.map_symbols(f)?,
+                        })
+                    })
+                    .collect::<Result<_, MathError>>()?,
+            ),
+            Expr::Product(factors) => Expr::Product(
+                factors
+                    .iter()
+                    .map(|factor| {
+                        Ok(Factor {
+                            base: factor.base.map_symbols(f)?,
+                            power: factor.power.map_symbols(f)?,
+                        })
+                    })
+                    .collect::<Result<_, MathError>>()?,
+            ),
+            Expr::Derivative { expr, var } => Expr::Derivative {
+                expr: go(expr, f)?,
+                var: f(*var)?,
+            },
+            Expr::Integral { expr, var } => Expr::Integral {
+                expr: go(expr, f)?,
+                var: f(*var)?,
+            },
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => Expr::Limit {
+                expr: go(expr, f)?,
+                var: f(*var)?,
+                approaching: go(approaching, f)?,
+                direction: *direction,
+            },
+            Expr::Summation {
+                var,
+                from,
+                to,
+                body,
+            } => Expr::Summation {
+                var: f(*var)?,
+                from: go(from, f)?,
+                to: go(to, f)?,
+                body: go(body, f)?,
+            },
+            Expr::BigProduct {
+                var,
+                from,
+                to,
+                body,
+            } => Expr::BigProduct {
+                var: f(*var)?,
+                from: go(from, f)?,
+                to: go(to, f)?,
+                body: go(body, f)?,
+            },
+            Expr::ForAll { var, domain, body } => Expr::ForAll {
+                var: f(*var)?,
+                domain: domain.as_ref().map(|d| go(d, f)).transpose()?,
+                body: go(body, f)?,
+            },
+            Expr::Exists { var, domain, body } => Expr::Exists {
+                var: f(*var)?,
+                domain: domain.as_ref().map(|d| go(d, f)).transpose()?,
+                body: go(body, f)?,
+            },
+            Expr::Piecewise(branches) => Expr::Piecewise(
+                branches
+                    .iter()
+                    .map(|(condition, value)| {
+                        Ok((
+                            Constraint {
+                                expr: condition.expr.map_symbols(f)?,
+                            },
+                            value.map_symbols(f)?,
+                        ))
+                    })
+                    .collect::<Result<_, MathError>>()?,
+            ),
+        })
+    }
+
+    /// Number of nodes in the expression tree.
+    ///
+    /// Same measure as [`complexity`](Self::complexity), under the name used
+    /// for size limits.
+    pub fn size(&self) -> usize {
+        self.complexity()
+    }
+
     /// Compute a rough node count for the expression AST.
     ///
     /// The complexity is defined as 1 for atomic nodes (constants, variables, Pi, E),
@@ -566,7 +1195,7 @@ impl Expr {
     /// ```
     pub fn complexity(&self) -> usize {
         match self {
-            Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E => 1,
+            Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => 1,
             Expr::Neg(e)
             | Expr::Sqrt(e)
             | Expr::Sin(e)
@@ -575,6 +1204,12 @@ impl Expr {
             | Expr::Arcsin(e)
             | Expr::Arccos(e)
             | Expr::Arctan(e)
+            | Expr::Sinh(e)
+            | Expr::Cosh(e)
+            | Expr::Tanh(e)
+            | Expr::Arcsinh(e)
+            | Expr::Arccosh(e)
+            | Expr::Arctanh(e)
             | Expr::Ln(e)
             | Expr::Exp(e)
             | Expr::Abs(e) => 1 + e.complexity(),
@@ -591,6 +1226,9 @@ impl Expr {
                     .sum::<usize>()
             }
             Expr::Derivative { expr, .. } | Expr::Integral { expr, .. } => 1 + expr.complexity(),
+            Expr::Limit {
+                expr, approaching, ..
+            } => 1 + expr.complexity() + approaching.complexity(),
             Expr::Equation { lhs, rhs }
             | Expr::GCD(lhs, rhs)
             | Expr::LCM(lhs, rhs)
@@ -611,6 +1249,12 @@ impl Expr {
                 1 + a.complexity() + b.complexity()
             }
             Expr::Not(e) => 1 + e.complexity(),
+            Expr::Piecewise(branches) => {
+                1 + branches
+                    .iter()
+                    .map(|(c, e)| c.expr.complexity() + e.complexity())
+                    .sum::<usize>()
+            }
         }
     }
 }
@@ -630,6 +1274,59 @@ mod tests {
         assert_ne!(a, c);
     }
 
+    #[test]
+    fn test_hash_set_dedup() {
+        use std::collections::HashSet;
+
+        let mut symbols = SymbolTable::new();
+        let (x, n) = (symbols.intern("x"), symbols.intern("n"));
+        let var = |s| Box::new(Expr::Var(s));
+        let exprs = || {
+            vec![
+                Expr::Add(var(x), Box::new(Expr::int(1))),
+                Expr::GCD(var(x), Box::new(Expr::int(6))),
+                Expr::Binomial(var(n), Box::new(Expr::int(2))),
+                Expr::Factorial(var(n)),
+                Expr::Not(Box::new(Expr::Gt(var(x), Box::new(Expr::int(0))))),
+                Expr::Summation {
+                    var: n,
+                    from: Box::new(Expr::int(1)),
+                    to: Box::new(Expr::int(10)),
+                    body: var(n),
+                },
+                Expr::ForAll {
+                    var: x,
+                    domain: None,
+                    body: Box::new(Expr::Gte(var(x), var(x))),
+                },
+            ]
+        };
+
+        // Structurally equal expressions collapse
+        let set: HashSet<Expr> = exprs().into_iter().chain(exprs()).collect();
+        assert_eq!(set.len(), exprs().len());
+        for expr in exprs() {
+            assert_eq!(expr, expr.clone());
+            assert_eq!(expr.cmp(&expr.clone()), Ordering::Equal);
+        }
+
+        // Structurally different ones coexist, even if equivalent
+        let mut set = set;
+        assert!(set.insert(Expr::Add(Box::new(Expr::int(1)), var(x))));
+        assert!(set.insert(Expr::GCD(var(x), Box::new(Expr::int(4)))));
+        assert!(set.insert(Expr::Summation {
+            var: n,
+            from: Box::new(Expr::int(0)),
+            to: Box::new(Expr::int(10)),
+            body: var(n),
+        }));
+        assert_eq!(set.len(), exprs().len() + 3);
+        assert_ne!(
+            Expr::Factorial(var(n)).cmp(&Expr::Factorial(var(x))),
+            Ordering::Equal
+        );
+    }
+
     #[test]
     fn test_expr_complexity() {
         // 5
@@ -641,4 +1338,67 @@ mod tests {
         let expr = Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::int(1)));
         assert_eq!(expr.complexity(), 3);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "global-interner")]
+    #[test]
+    fn test_global_var() {
+        assert_eq!(Expr::var("x"), Expr::var("x"));
+        assert_ne!(Expr::var("x"), Expr::var("y"));
+    }
+
+    #[test]
+    fn test_expr_kind() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let deriv = Expr::Derivative {
+            expr: Box::new(Expr::Var(x)),
+            var: x,
+        };
+
+        assert_eq!(Expr::int(1).kind(), ExprKind::Const);
+        assert_eq!(deriv.kind(), ExprKind::Derivative);
+        assert_eq!(Expr::Sin(Box::new(deriv)).kind(), ExprKind::Sin);
+        assert!(ExprKind::ALL.contains(&ExprKind::Implies));
+    }
+
+    #[test]
+    fn test_remap_symbols() {
+        let mut source = SymbolTable::new();
+        source.intern("unused");
+        let x = source.intern("x");
+        let mut target = SymbolTable::new();
+        let y = target.intern("y");
+
+        // d/dx(x + y-from-source) has a bound and a free occurrence of x
+        let expr = Expr::Derivative {
+            expr: Box::new(Expr::Add(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::Var(source.intern("y"))),
+            )),
+            var: x,
+        };
+        let remapped = expr.remap_symbols(&source, &mut target).unwrap();
+        let x_target = target.get("x").unwrap();
+        assert_eq!(
+            remapped,
+            Expr::Derivative {
+                expr: Box::new(Expr::Add(
+                    Box::new(Expr::Var(x_target)),
+                    Box::new(Expr::Var(y)),
+                )),
+                var: x_target,
+            }
+        );
+
+        // A symbol the source table never issued cannot be named
+        let mut other = SymbolTable::new();
+        for name in ["a", "b", "c"] {
+            other.intern(name);
+        }
+        let stranger = Expr::Var(other.intern("d"));
+        assert!(matches!(
+            stranger.remap_symbols(&source, &mut SymbolTable::new()),
+            Err(MathError::UndefinedVariable(_))
+        ));
+    }
+}
@@ -14,6 +14,7 @@
 //! - Canonicalization - Converting expressions to a unique normal form
 //! - Evaluation - Numerical evaluation of expressions
 //! - Parsing - String to expression conversion
+//! - Display - Unicode and LaTeX rendering
 //!
 //! ## Example
 //!
@@ -43,21 +44,34 @@
 //! ```
 
 pub mod canon;
+pub mod diff;
+pub mod display;
 pub mod error;
 pub mod eval;
 pub mod expr;
+pub mod mathml;
+pub mod numtheory;
 pub mod parse;
+pub mod pattern;
 pub mod proof;
 pub mod rational;
 pub mod search;
 pub mod symbol;
+pub mod validate;
 
+pub use canon::CanonCache;
+pub use diff::DiffPoint;
+pub use display::{ExprDisplay, Notation};
 pub use error::MathError;
-pub use expr::{Expr, Factor, Term};
+pub use eval::AngleMode;
+pub use expr::{Expr, ExprKind, Factor, LimitDirection, Term};
 pub use proof::{
-    Constraint, Domain, Goal, GoalId, GoalStatus, HypId, Hypothesis, HypothesisOrigin, Proof,
-    ProofState, ProofStep, Variable,
+    replay, Constraint, Domain, Goal, GoalId, GoalStatus, HypId, Hypothesis, HypothesisOrigin,
+    Proof, ProofError, ProofState, ProofStep, Variable,
 };
 pub use rational::Rational;
 pub use search::{NeuralHint, ProofSearchEngine, SearchConfig, SearchStats};
-pub use symbol::{Symbol, SymbolTable};
+pub use symbol::{is_metavar, Symbol, SymbolTable};
+pub use validate::ValidationError;
+#[cfg(feature = "global-interner")]
+pub use symbol::with_global_symbols;
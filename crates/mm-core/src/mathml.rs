@@ -0,0 +1,439 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Presentation MathML rendering of expressions.
+//!
+//! For browsers that render MathML natively. Layout follows the LaTeX
+//! renderer: fractions and roots are drawn, so only operators need
+//! parentheses, and those only where precedence requires them.
+//!
+//! ```text
+//! (x + 1)/2  →  <math …><mfrac><mrow><mi>x</mi><mo>+</mo><mn>1</mn></mrow><mn>2</mn></mfrac></math>
+//! ```
+
+use crate::display::{
+    precedence, Notation, PREC_ADD, PREC_AND, PREC_ATOM, PREC_IMPLIES, PREC_MUL, PREC_NEG,
+    PREC_NOT, PREC_OR, PREC_POW, PREC_QUANT, PREC_REL,
+};
+use crate::proof::Constraint;
+use crate::{Expr, LimitDirection, Rational, Symbol, SymbolTable};
+
+/// The invisible operator between a function name and its argument.
+const APPLY: &str = "<mo>&#x2061;</mo>";
+
+impl Expr {
+    /// Render as a presentation MathML `<math>` element.
+    pub fn to_mathml(&self, symbols: &SymbolTable) -> String {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+            MathMl { symbols }.render(self, PREC_QUANT)
+        )
+    }
+}
+
+struct MathMl<'a> {
+    symbols: &'a SymbolTable,
+}
+
+impl MathMl<'_> {
+    /// Render `expr`, parenthesizing it if it binds looser than `min`.
+    fn render(&self, expr: &Expr, min: u8) -> String {
+        let body = self.render_bare(expr);
+        if precedence(expr, Notation::Latex, false) < min {
+            fenced("(", &body, ")")
+        } else {
+            body
+        }
+    }
+
+    fn render_bare(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Const(r) => rational(*r),
+            Expr::Var(v) => self.var(*v),
+            Expr::Pi => "<mi>π</mi>".to_string(),
+            Expr::E => "<mi>e</mi>".to_string(),
+            Expr::Infinity { negative: false } => "<mi>∞</mi>".to_string(),
+            Expr::Infinity { negative: true } => row(&["<mo>-</mo>", "<mi>∞</mi>"]),
+
+            Expr::Neg(e) => row(&["<mo>-</mo>", &self.render(e, PREC_NEG)]),
+            Expr::Sqrt(e) => format!("<msqrt>{}</msqrt>", self.render(e, PREC_QUANT)),
+            Expr::Sin(e) => self.function("sin", &[e]),
+            Expr::Cos(e) => self.function("cos", &[e]),
+            Expr::Tan(e) => self.function("tan", &[e]),
+            Expr::Arcsin(e) => self.function("arcsin", &[e]),
+            Expr::Arccos(e) => self.function("arccos", &[e]),
+            Expr::Arctan(e) => self.function("arctan", &[e]),
+            Expr::Sinh(e) => self.function("sinh", &[e]),
+            Expr::Cosh(e) => self.function("cosh", &[e]),
+            Expr::Tanh(e) => self.function("tanh", &[e]),
+            Expr::Arcsinh(e) => self.function("arcsinh", &[e]),
+            Expr::Arccosh(e) => self.function("arccosh", &[e]),
+            Expr::Arctanh(e) => self.function("arctanh", &[e]),
+            Expr::Ln(e) => self.function("ln", &[e]),
+            Expr::Exp(e) => self.function("exp", &[e]),
+            Expr::Abs(e) => fenced("|", &self.render(e, PREC_QUANT), "|"),
+            Expr::Floor(e) => fenced("⌊", &self.render(e, PREC_QUANT), "⌋"),
+            Expr::Ceiling(e) => fenced("⌈", &self.render(e, PREC_QUANT), "⌉"),
+
+            Expr::Add(a, b) => {
+                let lhs = self.render(a, PREC_ADD);
+                match b.as_ref() {
+                    Expr::Neg(inner) => {
+                        row(&[&lhs, "<mo>-</mo>", &self.render(inner, PREC_ADD + 1)])
+                    }
+                    Expr::Const(r) if r.is_negative() => row(&[&lhs, "<mo>-</mo>", &rational(-*r)]),
+                    _ => row(&[&lhs, "<mo>+</mo>", &self.render(b, PREC_ADD)]),
+                }
+            }
+            Expr::Sub(a, b) => row(&[
+                &self.render(a, PREC_ADD),
+                "<mo>-</mo>",
+                &self.render(b, PREC_ADD + 1),
+            ]),
+            Expr::Mul(a, b) => row(&[
+                &self.render(a, PREC_MUL),
+                "<mo>·</mo>",
+                &self.render(b, PREC_MUL + 1),
+            ]),
+            Expr::Div(a, b) => format!(
+                "<mfrac>{}{}</mfrac>",
+                self.render(a, PREC_QUANT),
+                self.render(b, PREC_QUANT)
+            ),
+            Expr::Pow(base, exp) => self.power(base, exp),
+
+            Expr::Sum(terms) => {
+                if terms.is_empty() {
+                    return "<mn>0</mn>".to_string();
+                }
+                let mut parts = Vec::new();
+                for (i, term) in terms.iter().enumerate() {
+                    if term.coeff.is_negative() {
+                        parts.push("<mo>-</mo>".to_string());
+                    } else if i > 0 {
+                        parts.push("<mo>+</mo>".to_string());
+                    }
+                    let magnitude = term.coeff.abs();
+                    if magnitude.is_one() {
+                        parts.push(self.render(&term.expr, PREC_ADD + 1));
+                    } else {
+                        parts.push(rational(magnitude));
+                        parts.push("<mo>·</mo>".to_string());
+                        parts.push(self.render(&term.expr, PREC_MUL + 1));
+                    }
+                }
+                row(&parts.iter().map(String::as_str).collect::<Vec<_>>())
+            }
+            Expr::Product(factors) => {
+                if factors.is_empty() {
+                    return "<mn>1</mn>".to_string();
+                }
+                let parts: Vec<_> = factors
+                    .iter()
+                    .map(|f| {
+                        if f.power.is_one() {
+                            self.render(&f.base, PREC_MUL + 1)
+                        } else {
+                            self.power(&f.base, &f.power)
+                        }
+                    })
+                    .collect();
+                format!("<mrow>{}</mrow>", parts.join("<mo>·</mo>"))
+            }
+
+            Expr::Derivative { expr, var } => row(&[
+                &format!(
+                    "<mfrac><mi>d</mi><mrow><mi>d</mi>{}</mrow></mfrac>",
+                    self.var(*var)
+                ),
+                &fenced("(", &self.render(expr, PREC_QUANT), ")"),
+            ]),
+            Expr::Integral { expr, var } => row(&[
+                "<mo>∫</mo>",
+                &self.render(expr, PREC_MUL),
+                "<mspace width=\"0.167em\"/><mi>d</mi>",
+                &self.var(*var),
+            ]),
+            Expr::Limit {
+                expr,
+                var,
+                approaching,
+                direction,
+            } => self.limit(expr, *var, approaching, *direction),
+
+            Expr::Equation { lhs, rhs } => self.relation(lhs, "=", rhs),
+            Expr::Gte(a, b) => self.relation(a, "≥", b),
+            Expr::Gt(a, b) => self.relation(a, "&gt;", b),
+            Expr::Lte(a, b) => self.relation(a, "≤", b),
+            Expr::Lt(a, b) => self.relation(a, "&lt;", b),
+
+            Expr::GCD(a, b) => self.function("gcd", &[a, b]),
+            Expr::LCM(a, b) => self.function("lcm", &[a, b]),
+            Expr::Mod(a, b) => row(&[
+                &self.render(a, PREC_MUL),
+                "<mo>mod</mo>",
+                &self.render(b, PREC_MUL + 1),
+            ]),
+            Expr::Factorial(e) => row(&[&self.render(e, PREC_ATOM), "<mo>!</mo>"]),
+            Expr::Binomial(n, k) => fenced(
+                "(",
+                &format!(
+                    "<mfrac linethickness=\"0\">{}{}</mfrac>",
+                    self.render(n, PREC_QUANT),
+                    self.render(k, PREC_QUANT)
+                ),
+                ")",
+            ),
+
+            Expr::Summation {
+                var,
+                from,
+                to,
+                body,
+            } => self.big_operator("∑", *var, from, to, body),
+            Expr::BigProduct {
+                var,
+                from,
+                to,
+                body,
+            } => self.big_operator("∏", *var, from, to, body),
+
+            Expr::ForAll { var, domain, body } => {
+                self.quantifier("∀", *var, domain.as_deref(), body)
+            }
+            Expr::Exists { var, domain, body } => {
+                self.quantifier("∃", *var, domain.as_deref(), body)
+            }
+            Expr::And(a, b) => row(&[
+                &self.render(a, PREC_AND),
+                "<mo>∧</mo>",
+                &self.render(b, PREC_AND + 1),
+            ]),
+            Expr::Or(a, b) => row(&[
+                &self.render(a, PREC_OR),
+                "<mo>∨</mo>",
+                &self.render(b, PREC_OR + 1),
+            ]),
+            Expr::Implies(a, b) => row(&[
+                &self.render(a, PREC_IMPLIES + 1),
+                "<mo>⇒</mo>",
+                &self.render(b, PREC_IMPLIES),
+            ]),
+            Expr::Not(e) => row(&["<mo>¬</mo>", &self.render(e, PREC_REL + 1)]),
+
+            Expr::Piecewise(branches) => self.piecewise(branches),
+        }
+    }
+
+    fn var(&self, v: Symbol) -> String {
+        format!(
+            "<mi>{}</mi>",
+            escape(self.symbols.resolve(v).unwrap_or("?"))
+        )
+    }
+
+    fn function(&self, name: &str, args: &[&Expr]) -> String {
+        let args: Vec<_> = args.iter().map(|a| self.render(a, PREC_QUANT)).collect();
+        row(&[
+            &format!("<mi>{}</mi>", name),
+            APPLY,
+            &fenced("(", &args.join("<mo>,</mo>"), ")"),
+        ])
+    }
+
+    fn relation(&self, a: &Expr, op: &str, b: &Expr) -> String {
+        row(&[
+            &self.render(a, PREC_REL + 1),
+            &format!("<mo>{}</mo>", op),
+            &self.render(b, PREC_REL + 1),
+        ])
+    }
+
+    fn power(&self, base: &Expr, exp: &Expr) -> String {
+        format!(
+            "<msup>{}{}</msup>",
+            self.render(base, PREC_POW + 1),
+            self.render(exp, PREC_QUANT)
+        )
+    }
+
+    fn big_operator(
+        &self,
+        symbol: &str,
+        var: Symbol,
+        from: &Expr,
+        to: &Expr,
+        body: &Expr,
+    ) -> String {
+        row(&[
+            &format!(
+                "<munderover><mo>{}</mo>{}{}</munderover>",
+                symbol,
+                row(&[&self.var(var), "<mo>=</mo>", &self.render(from, PREC_QUANT)]),
+                self.render(to, PREC_QUANT)
+            ),
+            &self.render(body, PREC_MUL),
+        ])
+    }
+
+    fn limit(
+        &self,
+        expr: &Expr,
+        var: Symbol,
+        approaching: &Expr,
+        direction: LimitDirection,
+    ) -> String {
+        let point = self.render(approaching, PREC_QUANT);
+        let point = match direction {
+            LimitDirection::Both => point,
+            LimitDirection::Left => format!("<msup>{}<mo>-</mo></msup>", point),
+            LimitDirection::Right => format!("<msup>{}<mo>+</mo></msup>", point),
+        };
+        row(&[
+            &format!(
+                "<munder><mo>lim</mo>{}</munder>",
+                row(&[&self.var(var), "<mo>→</mo>", &point])
+            ),
+            &self.render(expr, PREC_MUL),
+        ])
+    }
+
+    fn piecewise(&self, branches: &[(Constraint, Expr)]) -> String {
+        let rows: String = branches
+            .iter()
+            .map(|(condition, value)| {
+                format!(
+                    "<mtr><mtd>{}</mtd><mtd><mtext>if&#xA0;</mtext>{}</mtd></mtr>",
+                    self.render(value, PREC_QUANT),
+                    self.render(&condition.expr, PREC_QUANT)
+                )
+            })
+            .collect();
+        row(&["<mo>{</mo>", &format!("<mtable>{}</mtable>", rows)])
+    }
+
+    fn quantifier(&self, symbol: &str, var: Symbol, domain: Option<&Expr>, body: &Expr) -> String {
+        let mut parts = vec![format!("<mo>{}</mo>", symbol), self.var(var)];
+        if let Some(domain) = domain {
+            parts.push(fenced("(", &self.render(domain, PREC_QUANT), ")"));
+        }
+        parts.push("<mo>.</mo>".to_string());
+        // Connectives in the body are parenthesized so the scope is explicit
+        parts.push(self.render(body, PREC_NOT));
+        row(&parts.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+}
+
+/// A number: `<mn>` for integers, `<mfrac>` otherwise, signed with `<mo>`.
+fn rational(r: Rational) -> String {
+    let magnitude = if r.is_integer() {
+        format!("<mn>{}</mn>", r.numer().abs())
+    } else {
+        format!(
+            "<mfrac><mn>{}</mn><mn>{}</mn></mfrac>",
+            r.numer().abs(),
+            r.denom()
+        )
+    };
+    if r.is_negative() {
+        row(&["<mo>-</mo>", &magnitude])
+    } else {
+        magnitude
+    }
+}
+
+fn row(parts: &[&str]) -> String {
+    format!("<mrow>{}</mrow>", parts.concat())
+}
+
+fn fenced(open: &str, body: &str, close: &str) -> String {
+    format!("<mrow><mo>{}</mo>{}<mo>{}</mo></mrow>", open, body, close)
+}
+
+/// Escape the characters XML reserves.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+
+    fn mathml(input: &str) -> String {
+        let mut symbols = SymbolTable::new();
+        let expr = Parser::new(&mut symbols).parse(input).unwrap();
+        let out = expr.to_mathml(&symbols);
+        let inner = out
+            .strip_prefix("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">")
+            .and_then(|s| s.strip_suffix("</math>"))
+            .unwrap();
+        assert!(balanced(inner), "unbalanced MathML: {}", out);
+        inner.to_string()
+    }
+
+    /// Whether every opening tag is closed in order.
+    fn balanced(xml: &str) -> bool {
+        let mut open = Vec::new();
+        for tag in xml.split('<').skip(1) {
+            let tag = &tag[..tag.find('>').unwrap()];
+            if tag.ends_with('/') {
+                continue;
+            }
+            match tag.strip_prefix('/') {
+                Some(name) => {
+                    if open.pop() != Some(name) {
+                        return false;
+                    }
+                }
+                None => open.push(tag.split(' ').next().unwrap()),
+            }
+        }
+        open.is_empty()
+    }
+
+    #[test]
+    fn test_fraction() {
+        assert_eq!(
+            mathml("(x + 1)/2"),
+            "<mfrac><mrow><mi>x</mi><mo>+</mo><mn>1</mn></mrow><mn>2</mn></mfrac>"
+        );
+        // Drawn fractions need no parentheses around their operands
+        assert_eq!(
+            mathml("1/(x - y)"),
+            "<mfrac><mn>1</mn><mrow><mi>x</mi><mo>-</mo><mi>y</mi></mrow></mfrac>"
+        );
+    }
+
+    #[test]
+    fn test_power() {
+        assert_eq!(mathml("x^2"), "<msup><mi>x</mi><mn>2</mn></msup>");
+        // A compound base is parenthesized, the exponent is not
+        assert_eq!(
+            mathml("(x + 1)^(n + 1)"),
+            "<msup><mrow><mo>(</mo><mrow><mi>x</mi><mo>+</mo><mn>1</mn></mrow><mo>)</mo></mrow>\
+             <mrow><mi>n</mi><mo>+</mo><mn>1</mn></mrow></msup>"
+        );
+        assert_eq!(
+            mathml("sqrt(x^2 + 1)"),
+            "<msqrt><mrow><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mn>1</mn></mrow></msqrt>"
+        );
+    }
+
+    #[test]
+    fn test_precedence_and_escaping() {
+        // (a + b)·c keeps its parentheses, a + b·c needs none
+        assert!(mathml("(a + b)*c").starts_with("<mrow><mrow><mo>(</mo>"));
+        assert!(!mathml("a + b*c").contains("<mo>(</mo>"));
+        let mut symbols = SymbolTable::new();
+        let x = Box::new(Expr::Var(symbols.intern("x")));
+        let lt = Expr::Lt(x, Box::new(Expr::int(1))).to_mathml(&symbols);
+        assert!(lt.contains("<mi>x</mi><mo>&lt;</mo><mn>1</mn>"));
+        assert!(mathml("sin(x)").contains(APPLY));
+    }
+}
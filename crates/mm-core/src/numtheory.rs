@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Integer number-theory primitives.
+//!
+//! Shared by the number-theory rules and problem synthesis. Everything works
+//! on `u64`; callers holding a [`Rational`](crate::Rational) check
+//! `is_integer()` and convert the numerator first.
+
+/// Whether `n` is prime.
+///
+/// Deterministic Miller–Rabin: the fixed witness set below is exact for every
+/// `u64`.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for p in WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 = d · 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    WITNESSES.iter().all(|&a| {
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Prime factorization as `(prime, exponent)` pairs in increasing order.
+///
+/// `360 = 2³ · 3² · 5` gives `[(2, 3), (3, 2), (5, 1)]`. Zero and one have no
+/// prime factors and give an empty list.
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    if n == 0 {
+        return factors;
+    }
+    let mut p = 2;
+    while p <= n / p {
+        let mut e = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((p, e));
+        }
+        if is_prime(n) {
+            break;
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Euler's totient φ(n): how many of `1..=n` are coprime to `n`.
+///
+/// φ(0) is taken to be 0.
+pub fn euler_totient(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |phi, (p, _)| phi / p * (p - 1))
+}
+
+/// All positive divisors of `n` in increasing order. Empty for zero.
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut divs = vec![1];
+    for (p, e) in factorize(n) {
+        let current = divs.len();
+        let mut power = 1;
+        for _ in 0..e {
+            power *= p;
+            for i in 0..current {
+                divs.push(divs[i] * power);
+            }
+        }
+    }
+    divs.sort_unstable();
+    divs
+}
+
+/// The binomial coefficient C(n, k), or `None` if it overflows `u64`.
+///
+/// C(n, k) is zero for `k > n`.
+pub fn binomial(n: u64, k: u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    // Each partial product C(n, i) is an integer
+    (0..k.min(n - k)).try_fold(1u64, |acc, i| {
+        let next = acc as u128 * (n - i) as u128 / (i + 1) as u128;
+        u64::try_from(next).ok()
+    })
+}
+
+/// `base^exp mod modulus` by repeated squaring, without forming `base^exp`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prime() {
+        assert!(is_prime(97));
+        assert!(is_prime(2));
+        assert!(!is_prime(1));
+        assert!(!is_prime(91)); // 7 · 13
+        assert!(is_prime(1_000_000_007));
+        // Strong pseudoprime to bases 2, 3, 5, 7
+        assert!(!is_prime(3_215_031_751));
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(97), vec![(97, 1)]);
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(
+            factorize(2 * 1_000_000_007),
+            vec![(2, 1), (1_000_000_007, 1)]
+        );
+    }
+
+    #[test]
+    fn test_totient_and_divisors() {
+        assert_eq!(euler_totient(12), 4);
+        assert_eq!(euler_totient(97), 96);
+        assert_eq!(euler_totient(1), 1);
+        assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(1), vec![1]);
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(3, 5), Some(0));
+        assert_eq!(binomial(60, 30), Some(118_264_581_564_861_424));
+        assert_eq!(binomial(100, 50), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(7, 256, 13), 9);
+        assert_eq!(mod_pow(2, 0, 1), 0);
+        assert_eq!(mod_pow(3, 1_000_000_000_000, 1_000_000_007), 570_188_345);
+    }
+}
@@ -7,22 +7,38 @@
 //! Expression parsing from strings.
 //!
 //! This module provides functionality to parse mathematical expressions
-//! from string representations.
+//! from string representations. [`tokenize`] exposes the token stream on its
+//! own, with byte spans, for tools such as syntax highlighters.
 //!
 //! # Supported Syntax
 //!
-//! - Numbers: `42`, `3.14`, `1/2`
+//! - Numbers: `42`, `3.14`, `1/2`, mixed numbers `1 1/2`, percents `25%`
 //! - Variables: `x`, `y`, `theta`
+//! - Constants: `pi`, `e`, `inf` (or `∞`)
 //! - Operators: `+`, `-`, `*`, `/`, `^`, `%` (mod), `!` (factorial), `=` (equation)
+//!
+//! An integer followed by a fraction of integers is a mixed number, so
+//! `1 1/2` is `3/2`. A `%` directly after a number is a percent unless an
+//! operand follows: `25% * 4` is `1/4 * 4`, while `25 % 4` is `25 mod 4`.
+//! A sign after `%` is an operator, so `50% - 10` is `1/2 - 10` and a
+//! negative modulus needs parentheses: `5 % (-3)`.
 //! - Parentheses: `(`, `)`
 //! - Functions:
-//!   - Trig: `sin`, `cos`, `tan`
+//!   - Trig: `sin`, `cos`, `tan`, `arcsin`, `arccos`, `arctan`
+//!   - Hyperbolic: `sinh`, `cosh`, `tanh`, `arcsinh`, `arccosh`, `arctanh`
 //!   - Exp/Log: `ln`, `exp`
-//!   - Misc: `sqrt`, `abs`, `floor`, `ceil`
+//!   - Misc: `sqrt`, `abs` (or `|x|`), `floor`, `ceil`
 //!   - Number Theory: `gcd(a,b)`, `lcm(a,b)`, `binomial(n,k)`
-//!   - Calculus: `diff(expr, var)`, `int(expr, var)`
+//!   - Calculus: `diff(expr, var)`, `int(expr, var)`, `lim(expr, var -> point)`,
+//!     with `point+` or `point-` for a limit from above or below
 //!   - Big Ops: `sum(var, from, to, body)`, `prod(var, from, to, body)`
 //!
+//! Common synonyms such as `atan`, `tan⁻¹` and `log_e` resolve to the names
+//! above; [`Parser::add_alias`] adds more.
+//!
+//! [`parse_latex`] reads the same expressions written in a subset of LaTeX,
+//! such as `\frac{x}{2} + \sqrt{x}`.
+//!
 //! # Example
 //!
 //! ```rust
@@ -34,22 +50,69 @@
 //! let expr = parser.parse("x^2 + 2*x + 1").unwrap();
 //! ```
 
-use crate::{Expr, MathError, Rational, Symbol, SymbolTable};
+use crate::symbol::METAVAR_PREFIX;
+use crate::{Expr, LimitDirection, MathError, Rational, Symbol, SymbolTable};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Function names accepted in place of the built-in ones.
+const FUNCTION_ALIASES: &[(&str, &str)] = &[
+    ("asin", "arcsin"),
+    ("acos", "arccos"),
+    ("atan", "arctan"),
+    ("sin⁻¹", "arcsin"),
+    ("cos⁻¹", "arccos"),
+    ("tan⁻¹", "arctan"),
+    ("asinh", "arcsinh"),
+    ("acosh", "arccosh"),
+    ("atanh", "arctanh"),
+    ("arsinh", "arcsinh"),
+    ("arcosh", "arccosh"),
+    ("artanh", "arctanh"),
+    ("log_e", "ln"),
+    ("ceiling", "ceil"),
+    ("choose", "binomial"),
+];
 
 /// A simple recursive descent parser for mathematical expressions.
 pub struct Parser<'a> {
     symbols: &'a mut SymbolTable,
+    /// Aliases added at runtime, checked before [`FUNCTION_ALIASES`]
+    aliases: HashMap<String, String>,
 }
 
 impl<'a> Parser<'a> {
     /// Create a new parser with the given symbol table.
     pub fn new(symbols: &'a mut SymbolTable) -> Self {
-        Self { symbols }
+        Self {
+            symbols,
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Accept `alias` as another name for the function `canonical`.
+    ///
+    /// `canonical` must be a built-in name such as `arctan`; aliases do not
+    /// chain. An alias must lex as one identifier and overrides any built-in
+    /// alias of the same name.
+    pub fn add_alias(&mut self, alias: impl Into<String>, canonical: impl Into<String>) {
+        self.aliases.insert(alias.into(), canonical.into());
+    }
+
+    /// The built-in function name `name` stands for.
+    fn resolve_alias<'n>(&'n self, name: &'n str) -> &'n str {
+        if let Some(canonical) = self.aliases.get(name) {
+            return canonical;
+        }
+        FUNCTION_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map_or(name, |(_, canonical)| canonical)
     }
 
     /// Parse an expression from a string.
     pub fn parse(&mut self, input: &str) -> Result<Expr, MathError> {
-        let tokens = tokenize(input)?;
+        let tokens: Vec<TokenKind> = tokenize(input)?.into_iter().map(|t| t.kind).collect();
         let mut pos = 0;
         let expr = self.parse_equation(&tokens, &mut pos)?;
 
@@ -64,11 +127,11 @@ impl<'a> Parser<'a> {
     }
 
     // Level 1: Equations (=)
-    fn parse_equation(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
+    fn parse_equation(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
         let lhs = self.parse_additive(tokens, pos)?;
 
         if *pos < tokens.len() {
-            if let Token::Eq = tokens[*pos] {
+            if let TokenKind::Eq = tokens[*pos] {
                 *pos += 1;
                 let rhs = self.parse_additive(tokens, pos)?;
                 return Ok(Expr::Equation {
@@ -82,17 +145,17 @@ impl<'a> Parser<'a> {
     }
 
     // Level 2: Additive (+, -)
-    fn parse_additive(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
+    fn parse_additive(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
         let mut left = self.parse_multiplicative(tokens, pos)?;
 
         while *pos < tokens.len() {
             match &tokens[*pos] {
-                Token::Plus => {
+                TokenKind::Plus => {
                     *pos += 1;
                     let right = self.parse_multiplicative(tokens, pos)?;
                     left = Expr::Add(Box::new(left), Box::new(right));
                 }
-                Token::Minus => {
+                TokenKind::Minus => {
                     *pos += 1;
                     let right = self.parse_multiplicative(tokens, pos)?;
                     left = Expr::Sub(Box::new(left), Box::new(right));
@@ -107,26 +170,26 @@ impl<'a> Parser<'a> {
     // Level 3: Multiplicative (*, /, %)
     fn parse_multiplicative(
         &mut self,
-        tokens: &[Token],
+        tokens: &[TokenKind],
         pos: &mut usize,
     ) -> Result<Expr, MathError> {
-        let mut left = self.parse_power(tokens, pos)?;
+        let mut left = self.parse_unary(tokens, pos)?;
 
         while *pos < tokens.len() {
             match &tokens[*pos] {
-                Token::Star => {
+                TokenKind::Star => {
                     *pos += 1;
-                    let right = self.parse_power(tokens, pos)?;
+                    let right = self.parse_unary(tokens, pos)?;
                     left = Expr::Mul(Box::new(left), Box::new(right));
                 }
-                Token::Slash => {
+                TokenKind::Slash => {
                     *pos += 1;
-                    let right = self.parse_power(tokens, pos)?;
+                    let right = self.parse_unary(tokens, pos)?;
                     left = Expr::Div(Box::new(left), Box::new(right));
                 }
-                Token::Percent => {
+                TokenKind::Percent => {
                     *pos += 1;
-                    let right = self.parse_power(tokens, pos)?;
+                    let right = self.parse_unary(tokens, pos)?;
                     left = Expr::Mod(Box::new(left), Box::new(right));
                 }
                 _ => break,
@@ -136,35 +199,46 @@ impl<'a> Parser<'a> {
         Ok(left)
     }
 
-    // Level 4: Power (^) - Right associative
-    fn parse_power(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
-        let base = self.parse_unary(tokens, pos)?;
-
-        if *pos < tokens.len() && matches!(tokens[*pos], Token::Caret) {
-            *pos += 1;
-            let exp = self.parse_power(tokens, pos)?; // Recursion for right associativity
-            return Ok(Expr::Pow(Box::new(base), Box::new(exp)));
+    // Level 4: Unary (-, +) - Binds looser than ^, so -x^2 = -(x^2)
+    fn parse_unary(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
+        if *pos < tokens.len() {
+            match tokens[*pos] {
+                TokenKind::Minus => {
+                    *pos += 1;
+                    let expr = self.parse_unary(tokens, pos)?;
+                    return Ok(Expr::Neg(Box::new(expr)));
+                }
+                TokenKind::Plus => {
+                    // Unary plus is a no-op
+                    *pos += 1;
+                    return self.parse_unary(tokens, pos);
+                }
+                _ => {}
+            }
         }
 
-        Ok(base)
+        self.parse_power(tokens, pos)
     }
 
-    // Level 5: Unary (-)
-    fn parse_unary(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
-        if *pos < tokens.len() && matches!(tokens[*pos], Token::Minus) {
+    // Level 5: Power (^) - Right associative
+    fn parse_power(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
+        let base = self.parse_postfix(tokens, pos)?;
+
+        if *pos < tokens.len() && matches!(tokens[*pos], TokenKind::Caret) {
             *pos += 1;
-            let expr = self.parse_unary(tokens, pos)?;
-            return Ok(Expr::Neg(Box::new(expr)));
+            // Recursion for right associativity; the exponent may carry a sign
+            let exp = self.parse_unary(tokens, pos)?;
+            return Ok(Expr::Pow(Box::new(base), Box::new(exp)));
         }
 
-        self.parse_postfix(tokens, pos)
+        Ok(base)
     }
 
     // Level 6: Postfix (!)
-    fn parse_postfix(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
+    fn parse_postfix(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
         let mut expr = self.parse_primary(tokens, pos)?;
 
-        while *pos < tokens.len() && matches!(tokens[*pos], Token::Bang) {
+        while *pos < tokens.len() && matches!(tokens[*pos], TokenKind::Bang) {
             *pos += 1;
             expr = Expr::Factorial(Box::new(expr));
         }
@@ -173,35 +247,40 @@ impl<'a> Parser<'a> {
     }
 
     // Level 7: Primary (Number, Var, Paren, Function)
-    fn parse_primary(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Expr, MathError> {
+    fn parse_primary(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
         if *pos >= tokens.len() {
             return Err(MathError::ParseError("Unexpected end of input".to_string()));
         }
 
         match &tokens[*pos] {
-            Token::Number(n) => {
+            TokenKind::Number(n) => {
                 *pos += 1;
                 Ok(Expr::Const(*n))
             }
-            Token::Ident(name) => {
+            TokenKind::Ident(name) => {
                 *pos += 1;
 
                 // Check if it's a function call
-                if *pos < tokens.len() && matches!(tokens[*pos], Token::LParen) {
+                if *pos < tokens.len() && matches!(tokens[*pos], TokenKind::LParen) {
                     *pos += 1; // consume '('
+                    if matches!(self.resolve_alias(name), "lim" | "limit") {
+                        return self.parse_limit(tokens, pos);
+                    }
                     let args = self.parse_args(tokens, pos)?;
 
-                    if *pos >= tokens.len() || !matches!(tokens[*pos], Token::RParen) {
+                    if *pos >= tokens.len() || !matches!(tokens[*pos], TokenKind::RParen) {
                         return Err(MathError::ParseError("Expected ')'".to_string()));
                     }
                     *pos += 1; // consume ')'
 
-                    return self.construct_function_call(name, args);
+                    let name = self.resolve_alias(name).to_string();
+                    return self.construct_function_call(&name, args);
                 }
 
                 // It's a variable or constant
                 match name.as_str() {
                     "pi" | "Pi" | "PI" | "π" | "Π" => Ok(Expr::Pi),
+                    "inf" | "infinity" | "∞" => Ok(Expr::Infinity { negative: false }),
                     "e" | "E" => Ok(Expr::E),
                     _ => {
                         let symbol = self.symbols.intern(name);
@@ -209,17 +288,28 @@ impl<'a> Parser<'a> {
                     }
                 }
             }
-            Token::LParen => {
+            TokenKind::LParen => {
                 *pos += 1;
                 let expr = self.parse_equation(tokens, pos)?; // Reset precedence for inside parens
 
-                if *pos >= tokens.len() || !matches!(tokens[*pos], Token::RParen) {
+                if *pos >= tokens.len() || !matches!(tokens[*pos], TokenKind::RParen) {
                     return Err(MathError::ParseError("Expected ')'".to_string()));
                 }
                 *pos += 1;
 
                 Ok(expr)
             }
+            TokenKind::AbsOpen => {
+                *pos += 1;
+                let expr = self.parse_equation(tokens, pos)?;
+
+                if *pos >= tokens.len() || !matches!(tokens[*pos], TokenKind::AbsClose) {
+                    return Err(MathError::ParseError("Expected closing '|'".to_string()));
+                }
+                *pos += 1;
+
+                Ok(Expr::Abs(Box::new(expr)))
+            }
             _ => Err(MathError::ParseError(format!(
                 "Unexpected token: {:?}",
                 tokens[*pos]
@@ -227,11 +317,73 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_args(&mut self, tokens: &[Token], pos: &mut usize) -> Result<Vec<Expr>, MathError> {
+    /// The rest of `lim(expr, var -> point)` after the `(`.
+    ///
+    /// A `+` or `-` right before the closing `)` makes the limit one-sided.
+    fn parse_limit(&mut self, tokens: &[TokenKind], pos: &mut usize) -> Result<Expr, MathError> {
+        let expr = self.parse_equation(tokens, pos)?;
+        if !matches!(tokens.get(*pos), Some(TokenKind::Comma)) {
+            return Err(MathError::ParseError("Expected ',' in lim".to_string()));
+        }
+        *pos += 1;
+        let var = match (tokens.get(*pos), tokens.get(*pos + 1)) {
+            (Some(TokenKind::Ident(name)), Some(TokenKind::Arrow)) => self.symbols.intern(name),
+            _ => {
+                return Err(MathError::ParseError(
+                    "Expected 'var -> point' in lim".to_string(),
+                ))
+            }
+        };
+        *pos += 2;
+
+        // The point runs to the matching ')'
+        let mut depth = 0usize;
+        let mut end = *pos;
+        while end < tokens.len() {
+            match tokens[end] {
+                TokenKind::LParen => depth += 1,
+                TokenKind::RParen if depth == 0 => break,
+                TokenKind::RParen => depth -= 1,
+                _ => {}
+            }
+            end += 1;
+        }
+        if end == tokens.len() {
+            return Err(MathError::ParseError("Expected ')'".to_string()));
+        }
+        let (point_end, direction) = match tokens[end - 1] {
+            TokenKind::Plus => (end - 1, LimitDirection::Right),
+            TokenKind::Minus => (end - 1, LimitDirection::Left),
+            _ => (end, LimitDirection::Both),
+        };
+        let point_tokens = &tokens[*pos..point_end];
+        let mut point_pos = 0;
+        let approaching = self.parse_additive(point_tokens, &mut point_pos)?;
+        if point_pos != point_tokens.len() {
+            return Err(MathError::ParseError(format!(
+                "Unexpected token: {:?}",
+                point_tokens[point_pos]
+            )));
+        }
+        *pos = end + 1;
+
+        Ok(Expr::Limit {
+            expr: Box::new(expr),
+            var,
+            approaching: Box::new(approaching),
+            direction,
+        })
+    }
+
+    fn parse_args(
+        &mut self,
+        tokens: &[TokenKind],
+        pos: &mut usize,
+    ) -> Result<Vec<Expr>, MathError> {
         let mut args = Vec::new();
-        if *pos < tokens.len() && !matches!(tokens[*pos], Token::RParen) {
+        if *pos < tokens.len() && !matches!(tokens[*pos], TokenKind::RParen) {
             args.push(self.parse_equation(tokens, pos)?);
-            while *pos < tokens.len() && matches!(tokens[*pos], Token::Comma) {
+            while *pos < tokens.len() && matches!(tokens[*pos], TokenKind::Comma) {
                 *pos += 1;
                 args.push(self.parse_equation(tokens, pos)?);
             }
@@ -245,6 +397,15 @@ impl<'a> Parser<'a> {
             ("sin", 1) => Ok(Expr::Sin(Box::new(args[0].clone()))),
             ("cos", 1) => Ok(Expr::Cos(Box::new(args[0].clone()))),
             ("tan", 1) => Ok(Expr::Tan(Box::new(args[0].clone()))),
+            ("arcsin", 1) => Ok(Expr::Arcsin(Box::new(args[0].clone()))),
+            ("arccos", 1) => Ok(Expr::Arccos(Box::new(args[0].clone()))),
+            ("arctan", 1) => Ok(Expr::Arctan(Box::new(args[0].clone()))),
+            ("sinh", 1) => Ok(Expr::Sinh(Box::new(args[0].clone()))),
+            ("cosh", 1) => Ok(Expr::Cosh(Box::new(args[0].clone()))),
+            ("tanh", 1) => Ok(Expr::Tanh(Box::new(args[0].clone()))),
+            ("arcsinh", 1) => Ok(Expr::Arcsinh(Box::new(args[0].clone()))),
+            ("arccosh", 1) => Ok(Expr::Arccosh(Box::new(args[0].clone()))),
+            ("arctanh", 1) => Ok(Expr::Arctanh(Box::new(args[0].clone()))),
             ("ln", 1) => Ok(Expr::Ln(Box::new(args[0].clone()))),
             ("exp", 1) => Ok(Expr::Exp(Box::new(args[0].clone()))),
             ("sqrt", 1) => Ok(Expr::Sqrt(Box::new(args[0].clone()))),
@@ -341,26 +502,71 @@ impl<'a> Parser<'a> {
 // Tokenizer
 // ============================================================================
 
-#[derive(Debug, Clone)]
-enum Token {
+/// Kind of a lexical token.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Numeric literal, with mixed numbers and percents already folded.
     Number(Rational),
+    /// Variable or function name.
     Ident(String),
+    /// `+`
     Plus,
+    /// `-`
     Minus,
+    /// `*`
     Star,
+    /// `/`
     Slash,
+    /// `^`
     Caret,
+    /// `%` (mod)
     Percent,
+    /// `!`
     Bang,
+    /// `=`
     Eq,
+    /// `(`
     LParen,
+    /// `)`
     RParen,
+    /// `,`
     Comma,
+    /// `|` opening an absolute value
+    AbsOpen,
+    /// `|` closing an absolute value
+    AbsClose,
+    /// `->` or `→`, in a limit
+    Arrow,
+}
+
+/// A token and the byte range of the input it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// What was lexed.
+    pub kind: TokenKind,
+    /// Byte offsets into the input, end exclusive.
+    pub span: Range<usize>,
 }
 
-fn tokenize(input: &str) -> Result<Vec<Token>, MathError> {
+/// Split `input` into tokens without parsing it.
+///
+/// Only unknown characters and malformed numbers are errors, so incomplete
+/// input such as `sin(x +` still lexes. A folded literal (`1 1/2`, `25%`)
+/// spans all of its parts.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, MathError> {
     let mut tokens = Vec::new();
     let chars: Vec<char> = input.chars().collect();
+    // Byte offset of each char, plus the end of input
+    let offsets: Vec<usize> = input
+        .char_indices()
+        .map(|(at, _)| at)
+        .chain([input.len()])
+        .collect();
+    let token = |kind, start: usize, end: usize| Token {
+        kind,
+        span: offsets[start]..offsets[end],
+    };
+    let mut open_bars = 0usize;
     let mut i = 0;
 
     while i < chars.len() {
@@ -372,111 +578,743 @@ fn tokenize(input: &str) -> Result<Vec<Token>, MathError> {
             continue;
         }
 
+        if c == '→' || (c == '-' && chars.get(i + 1) == Some(&'>')) {
+            let len = if c == '→' { 1 } else { 2 };
+            tokens.push(token(TokenKind::Arrow, i, i + len));
+            i += len;
+            continue;
+        }
+
+        // Reciprocal superscript, as in (x + 1)⁻¹
+        if chars[i..].starts_with(&['⁻', '¹']) {
+            for kind in [
+                TokenKind::Caret,
+                TokenKind::LParen,
+                TokenKind::Minus,
+                TokenKind::Number(Rational::from_integer(1)),
+                TokenKind::RParen,
+            ] {
+                tokens.push(token(kind, i, i + 2));
+            }
+            i += 2;
+            continue;
+        }
+
         // Single character tokens
-        match c {
-            '+' => {
-                tokens.push(Token::Plus);
+        let single = match c {
+            '+' => Some(TokenKind::Plus),
+            '-' => Some(TokenKind::Minus),
+            '*' => Some(TokenKind::Star),
+            '/' => Some(TokenKind::Slash),
+            '^' => Some(TokenKind::Caret),
+            '%' => Some(TokenKind::Percent),
+            '!' => Some(TokenKind::Bang),
+            '=' => Some(TokenKind::Eq),
+            '(' => Some(TokenKind::LParen),
+            ')' => Some(TokenKind::RParen),
+            ',' => Some(TokenKind::Comma),
+            _ => None,
+        };
+        if let Some(kind) = single {
+            tokens.push(token(kind, i, i + 1));
+            i += 1;
+            continue;
+        }
+
+        // A bar closes the innermost open absolute value when it follows a
+        // complete operand, and opens a new one otherwise, so `|x| + |y|`
+        // and `||x| - 1|` both pair up.
+        if c == '|' {
+            let closes = open_bars > 0 && tokens.last().is_some_and(|t| ends_operand(&t.kind));
+            let kind = if closes {
+                open_bars -= 1;
+                TokenKind::AbsClose
+            } else {
+                open_bars += 1;
+                TokenKind::AbsOpen
+            };
+            tokens.push(token(kind, i, i + 1));
+            i += 1;
+            continue;
+        }
+
+        // Numbers
+        if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
                 i += 1;
-                continue;
             }
-            '-' => {
-                tokens.push(Token::Minus);
+
+            let num_str: String = chars[start..i].iter().collect();
+            tokens.push(token(TokenKind::Number(number(&num_str)?), start, i));
+            continue;
+        }
+
+        if c == '∞' {
+            tokens.push(token(TokenKind::Ident(c.to_string()), i, i + 1));
+            i += 1;
+            continue;
+        }
+
+        // Identifiers, and metavariables such as ?a
+        let metavar = c == METAVAR_PREFIX
+            && chars
+                .get(i + 1)
+                .is_some_and(|&next| next.is_alphabetic() || next == '_');
+        if c.is_alphabetic() || c == '_' || metavar {
+            let start = i;
+            if metavar {
                 i += 1;
-                continue;
             }
-            '*' => {
-                tokens.push(Token::Star);
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
                 i += 1;
-                continue;
             }
-            '/' => {
-                tokens.push(Token::Slash);
-                i += 1;
-                continue;
+            // Inverse-function superscript, as in tan⁻¹. On any other name
+            // it is left for the reciprocal below: x⁻¹ is x^(-1).
+            if chars[i..].starts_with(&['⁻', '¹']) {
+                let name: String = chars[start..i + 2].iter().collect();
+                if FUNCTION_ALIASES.iter().any(|&(alias, _)| alias == name) {
+                    i += 2;
+                }
             }
-            '^' => {
-                tokens.push(Token::Caret);
-                i += 1;
-                continue;
+
+            let ident: String = chars[start..i].iter().collect();
+            tokens.push(token(TokenKind::Ident(ident), start, i));
+            continue;
+        }
+
+        return Err(MathError::ParseError(format!("Unknown character: {}", c)));
+    }
+
+    Ok(fold_literals(tokens))
+}
+
+/// The value of a run of digits and dots.
+fn number(num_str: &str) -> Result<Rational, MathError> {
+    // Parse as integer or decimal
+    if num_str.contains('.') {
+        // Parse as decimal, convert to rational
+        let val: f64 = num_str
+            .parse()
+            .map_err(|_| MathError::ParseError(format!("Invalid number: {}", num_str)))?;
+
+        // Approximate as rational (simple approach)
+        let scale = 1_000_000i64;
+        let numer = (val * scale as f64).round() as i64;
+        Ok(Rational::new(numer, scale))
+    } else {
+        let val: i64 = num_str
+            .parse()
+            .map_err(|_| MathError::ParseError(format!("Invalid integer: {}", num_str)))?;
+        Ok(Rational::from_integer(val))
+    }
+}
+
+/// Whether a token can be the last one of an operand.
+///
+/// A `%` can, as a percent: the bar in `|50%|` closes.
+fn ends_operand(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Number(_)
+            | TokenKind::Ident(_)
+            | TokenKind::RParen
+            | TokenKind::Bang
+            | TokenKind::Percent
+            | TokenKind::AbsClose
+    )
+}
+
+/// Fold mixed numbers (`1 1/2`) and percents (`25%`) into single literals.
+fn fold_literals(tokens: Vec<Token>) -> Vec<Token> {
+    let kind = |i: usize| tokens.get(i).map(|t: &Token| &t.kind);
+    let integer = |i: usize| match kind(i) {
+        Some(TokenKind::Number(n)) if n.is_integer() => Some(*n),
+        _ => None,
+    };
+    let starts_operand = |i: usize| {
+        matches!(
+            kind(i),
+            Some(TokenKind::Number(_) | TokenKind::Ident(_) | TokenKind::LParen)
+        )
+    };
+    let literal = |value, first: usize, last: usize| Token {
+        kind: TokenKind::Number(value),
+        span: tokens[first].span.start..tokens[last].span.end,
+    };
+
+    let mut folded: Vec<Token> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        // whole numer / denom, not itself part of a fraction or power
+        let mixed = match (integer(i), integer(i + 1), kind(i + 2), integer(i + 3)) {
+            (Some(whole), Some(numer), Some(TokenKind::Slash), Some(denom))
+                if !denom.is_zero()
+                    && !matches!(
+                        folded.last().map(|t| &t.kind),
+                        Some(TokenKind::Slash | TokenKind::Caret)
+                    )
+                    && !matches!(kind(i + 4), Some(TokenKind::Caret | TokenKind::Bang)) =>
+            {
+                Some(whole + numer / denom)
             }
-            '%' => {
-                tokens.push(Token::Percent);
-                i += 1;
+            _ => None,
+        };
+        if let Some(value) = mixed {
+            folded.push(literal(value, i, i + 3));
+            i += 4;
+            continue;
+        }
+
+        if let (Some(TokenKind::Number(n)), Some(TokenKind::Percent)) = (kind(i), kind(i + 1)) {
+            if !starts_operand(i + 2) {
+                folded.push(literal(*n / Rational::from_integer(100), i, i + 1));
+                i += 2;
                 continue;
             }
-            '!' => {
-                tokens.push(Token::Bang);
+        }
+
+        folded.push(tokens[i].clone());
+        i += 1;
+    }
+    folded
+}
+
+// ============================================================================
+// LaTeX input
+// ============================================================================
+
+/// Greek letters read as variables named after the command: `\theta` is `theta`.
+const GREEK_LETTERS: &[&str] = &[
+    "alpha",
+    "beta",
+    "gamma",
+    "delta",
+    "epsilon",
+    "varepsilon",
+    "zeta",
+    "eta",
+    "theta",
+    "vartheta",
+    "iota",
+    "kappa",
+    "lambda",
+    "mu",
+    "nu",
+    "xi",
+    "rho",
+    "sigma",
+    "tau",
+    "upsilon",
+    "phi",
+    "varphi",
+    "chi",
+    "psi",
+    "omega",
+];
+
+/// Functions written as a single command, like `\sin`.
+const LATEX_FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh", "ln", "exp",
+];
+
+/// Parse a LaTeX math expression.
+///
+/// Accepts a subset of LaTeX math mode:
+///
+/// - Numbers, single-letter variables and Greek letters (`\theta`); `e` is
+///   Euler's number and `\pi` is π
+/// - `+`, `-`, `*`, `/`, `\cdot`, `\times`, `\div`, `=`, `!`, and
+///   juxtaposition as multiplication: `2x`, `x\sin x`
+/// - Powers `x^2`, `x^{n+1}`; without braces a superscript is one token
+/// - Grouping with `{}`, `()`, `[]`, `\left( … \right)`, and `|x|`
+/// - `\frac{a}{b}` (also `\dfrac`, `\tfrac`), `\sqrt{x}`, `\sqrt[n]{x}`,
+///   `\binom{n}{k}`
+/// - `\sin`, `\cos`, `\tan`, their inverses and hyperbolic forms, `\ln` and
+///   `\exp`, with or without parentheses (`\sin x`, `\sin(2x)`), and powers
+///   of them (`\sin^2 x`; `\sin^{-1} x` is `\arcsin x`)
+/// - Indefinite integrals `\int x^2 \, dx`, also with `\mathrm{d}x`
+/// - `\sum_{i=1}^{n} body` and `\prod_{i=1}^{n} body`, where the body
+///   extends over one product: `\sum_{i=1}^{n} i^2 + 1` adds 1 to the sum
+///
+/// Spacing commands (`\,`, `\;`, `\quad`, …) are ignored. Any other command
+/// is an error naming it, as are definite integrals, which have no
+/// expression form.
+///
+/// ```rust
+/// use mm_core::{parse::parse_latex, SymbolTable};
+///
+/// let mut symbols = SymbolTable::new();
+/// let expr = parse_latex(r"\frac{x}{2} + \sqrt{x}", &mut symbols).unwrap();
+/// assert_eq!(expr.to_latex(&symbols), r"\frac{x}{2} + \sqrt{x}");
+/// ```
+pub fn parse_latex(input: &str, symbols: &mut SymbolTable) -> Result<Expr, MathError> {
+    let mut parser = LatexParser {
+        tokens: tokenize_latex(input)?,
+        pos: 0,
+        symbols,
+        integrals: 0,
+    };
+    let expr = parser.parse_equation()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(MathError::ParseError(format!(
+            "Unexpected token at end of input: {:?}",
+            token
+        ))),
+    }
+}
+
+/// A lexical token of LaTeX input.
+#[derive(Debug, Clone, PartialEq)]
+enum LatexToken {
+    Number(Rational),
+    /// A letter, or the text of `\mathrm{…}`
+    Name(String),
+    /// A command without its backslash: `frac` for `\frac`
+    Command(String),
+    /// Any other single character: operators, braces, brackets
+    Char(char),
+}
+
+/// Commands that only adjust spacing or delimiter size.
+fn is_ignored_command(name: &str) -> bool {
+    matches!(
+        name,
+        "," | ";" | ":" | "!" | " " | "quad" | "qquad" | "left" | "right" | "displaystyle"
+    )
+}
+
+fn tokenize_latex(input: &str) -> Result<Vec<LatexToken>, MathError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
                 i += 1;
-                continue;
             }
-            '=' => {
-                tokens.push(Token::Eq);
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(LatexToken::Number(number(&text)?));
+        } else if c.is_ascii_alphabetic() {
+            tokens.push(LatexToken::Name(c.to_string()));
+            i += 1;
+        } else if c == '\\' {
+            i += 1;
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphabetic() {
                 i += 1;
-                continue;
             }
-            '(' => {
-                tokens.push(Token::LParen);
+            // Control symbols such as \, are one character long
+            if i == start && i < chars.len() {
                 i += 1;
-                continue;
             }
-            ')' => {
-                tokens.push(Token::RParen);
-                i += 1;
-                continue;
+            let name: String = chars[start..i].iter().collect();
+            if name.is_empty() {
+                return Err(MathError::ParseError("Trailing '\\'".to_string()));
             }
-            ',' => {
-                tokens.push(Token::Comma);
-                i += 1;
+            if is_ignored_command(&name) {
                 continue;
             }
-            _ => {}
+            if name == "mathrm" {
+                // Upright text such as the d of an integral: one name
+                let text = braced_text(&chars, &mut i)?;
+                tokens.push(LatexToken::Name(text));
+            } else {
+                tokens.push(LatexToken::Command(name));
+            }
+        } else {
+            tokens.push(LatexToken::Char(c));
+            i += 1;
         }
+    }
+    Ok(tokens)
+}
 
-        // Numbers
-        if c.is_ascii_digit() || c == '.' {
-            let start = i;
-            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
-                i += 1;
-            }
+/// Read `{text}` starting at `chars[*i]`, skipping leading whitespace.
+fn braced_text(chars: &[char], i: &mut usize) -> Result<String, MathError> {
+    while *i < chars.len() && chars[*i].is_whitespace() {
+        *i += 1;
+    }
+    if chars.get(*i) != Some(&'{') {
+        return Err(MathError::ParseError(
+            "Expected '{' after \\mathrm".to_string(),
+        ));
+    }
+    let start = *i + 1;
+    let end = chars[start..]
+        .iter()
+        .position(|&c| c == '}')
+        .map(|n| start + n)
+        .ok_or_else(|| MathError::ParseError("Expected '}'".to_string()))?;
+    *i = end + 1;
+    Ok(chars[start..end]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string())
+}
 
-            let num_str: String = chars[start..i].iter().collect();
+/// Recursive descent over LaTeX tokens, with the same precedence levels as
+/// [`Parser`].
+struct LatexParser<'a> {
+    tokens: Vec<LatexToken>,
+    pos: usize,
+    symbols: &'a mut SymbolTable,
+    /// Integrals whose `dx` has not been read yet
+    integrals: usize,
+}
+
+impl LatexParser<'_> {
+    fn peek(&self) -> Option<&LatexToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        match self.peek() {
+            Some(LatexToken::Char(c)) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn peek_command(&self) -> Option<&str> {
+        match self.peek() {
+            Some(LatexToken::Command(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn eat_char(&mut self, c: char) -> bool {
+        if self.peek_char() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_char(&mut self, c: char) -> Result<(), MathError> {
+        if self.eat_char(c) {
+            Ok(())
+        } else {
+            Err(MathError::ParseError(format!("Expected '{}'", c)))
+        }
+    }
+
+    /// The variable of a `dx` at the current position, if there is one.
+    fn differential(&self) -> Option<&str> {
+        match (self.peek(), self.tokens.get(self.pos + 1)) {
+            (Some(LatexToken::Name(d)), Some(LatexToken::Name(var))) if d == "d" => Some(var),
+            _ => None,
+        }
+    }
+
+    // Level 1: Equations (=)
+    fn parse_equation(&mut self) -> Result<Expr, MathError> {
+        let lhs = self.parse_additive()?;
+        if self.eat_char('=') {
+            let rhs = self.parse_additive()?;
+            return Ok(Expr::Equation {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            });
+        }
+        Ok(lhs)
+    }
 
-            // Parse as integer or decimal
-            if num_str.contains('.') {
-                // Parse as decimal, convert to rational
-                let val: f64 = num_str
-                    .parse()
-                    .map_err(|_| MathError::ParseError(format!("Invalid number: {}", num_str)))?;
-
-                // Approximate as rational (simple approach)
-                let scale = 1_000_000i64;
-                let numer = (val * scale as f64).round() as i64;
-                tokens.push(Token::Number(Rational::new(numer, scale)));
+    // Level 2: Additive (+, -)
+    fn parse_additive(&mut self) -> Result<Expr, MathError> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            if self.eat_char('+') {
+                let right = self.parse_multiplicative()?;
+                left = Expr::Add(Box::new(left), Box::new(right));
+            } else if self.eat_char('-') {
+                let right = self.parse_multiplicative()?;
+                left = Expr::Sub(Box::new(left), Box::new(right));
             } else {
-                let val: i64 = num_str
-                    .parse()
-                    .map_err(|_| MathError::ParseError(format!("Invalid integer: {}", num_str)))?;
-                tokens.push(Token::Number(Rational::from_integer(val)));
+                return Ok(left);
             }
-            continue;
         }
+    }
 
-        // Identifiers
-        if c.is_alphabetic() || c == '_' {
-            let start = i;
-            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
-                i += 1;
+    // Level 3: Multiplicative (\cdot, \times, *, /, \div, juxtaposition)
+    fn parse_multiplicative(&mut self) -> Result<Expr, MathError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            if self.eat_char('*') || self.eat_command(&["cdot", "times"]) {
+                let right = self.parse_unary()?;
+                left = Expr::Mul(Box::new(left), Box::new(right));
+            } else if self.eat_char('/') || self.eat_command(&["div"]) {
+                let right = self.parse_unary()?;
+                left = Expr::Div(Box::new(left), Box::new(right));
+            } else if self.starts_operand() {
+                let right = self.parse_power()?;
+                left = Expr::Mul(Box::new(left), Box::new(right));
+            } else {
+                return Ok(left);
             }
+        }
+    }
 
-            let ident: String = chars[start..i].iter().collect();
-            tokens.push(Token::Ident(ident));
-            continue;
+    fn eat_command(&mut self, names: &[&str]) -> bool {
+        if self.peek_command().is_some_and(|c| names.contains(&c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
         }
+    }
 
-        return Err(MathError::ParseError(format!("Unknown character: {}", c)));
+    /// Whether the next token begins an operand multiplied by juxtaposition.
+    fn starts_operand(&self) -> bool {
+        if self.integrals > 0 && self.differential().is_some() {
+            return false;
+        }
+        match self.peek() {
+            Some(LatexToken::Number(_) | LatexToken::Name(_)) => true,
+            Some(LatexToken::Char(c)) => matches!(c, '(' | '[' | '{'),
+            Some(LatexToken::Command(name)) => !matches!(
+                name.as_str(),
+                "cdot" | "times" | "div" | "int" | "sum" | "prod"
+            ),
+            None => false,
+        }
     }
 
-    Ok(tokens)
+    // Level 4: Unary (-, +)
+    fn parse_unary(&mut self) -> Result<Expr, MathError> {
+        if self.eat_char('-') {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        if self.eat_char('+') {
+            return self.parse_unary();
+        }
+        self.parse_power()
+    }
+
+    // Level 5: Power (^) - Right associative
+    fn parse_power(&mut self) -> Result<Expr, MathError> {
+        let base = self.parse_postfix()?;
+        if self.eat_char('^') {
+            let exp = self.parse_superscript()?;
+            return Ok(Expr::Pow(Box::new(base), Box::new(exp)));
+        }
+        Ok(base)
+    }
+
+    /// A superscript or subscript: a braced group or a single token.
+    fn parse_superscript(&mut self) -> Result<Expr, MathError> {
+        if self.peek_char() == Some('{') {
+            return self.parse_group();
+        }
+        if self.eat_char('-') {
+            return Ok(Expr::Neg(Box::new(self.parse_primary()?)));
+        }
+        self.parse_primary()
+    }
+
+    // Level 6: Postfix (!)
+    fn parse_postfix(&mut self) -> Result<Expr, MathError> {
+        let mut expr = self.parse_primary()?;
+        while self.eat_char('!') {
+            expr = Expr::Factorial(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    /// `{ expr }`
+    fn parse_group(&mut self) -> Result<Expr, MathError> {
+        self.expect_char('{')?;
+        let expr = self.parse_equation()?;
+        self.expect_char('}')?;
+        Ok(expr)
+    }
+
+    // Level 7: Primary (number, name, group, command)
+    fn parse_primary(&mut self) -> Result<Expr, MathError> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| MathError::ParseError("Unexpected end of input".to_string()))?;
+        self.pos += 1;
+        match token {
+            LatexToken::Number(n) => Ok(Expr::Const(n)),
+            LatexToken::Name(name) if name == "e" => Ok(Expr::E),
+            LatexToken::Name(name) => Ok(Expr::Var(self.symbols.intern(&name))),
+            LatexToken::Char('{') => {
+                self.pos -= 1;
+                self.parse_group()
+            }
+            LatexToken::Char(open @ ('(' | '[' | '|')) => {
+                let close = match open {
+                    '(' => ')',
+                    '[' => ']',
+                    _ => '|',
+                };
+                let expr = self.parse_equation()?;
+                self.expect_char(close)?;
+                Ok(if open == '|' {
+                    Expr::Abs(Box::new(expr))
+                } else {
+                    expr
+                })
+            }
+            LatexToken::Char(c) => Err(MathError::ParseError(format!(
+                "Unexpected character: {}",
+                c
+            ))),
+            LatexToken::Command(name) => self.parse_command(&name),
+        }
+    }
+
+    fn parse_command(&mut self, name: &str) -> Result<Expr, MathError> {
+        match name {
+            "pi" => Ok(Expr::Pi),
+            "infty" => Ok(Expr::Infinity { negative: false }),
+            "frac" | "dfrac" | "tfrac" => {
+                let numer = self.parse_group()?;
+                let denom = self.parse_group()?;
+                Ok(Expr::Div(Box::new(numer), Box::new(denom)))
+            }
+            "binom" => {
+                let n = self.parse_group()?;
+                let k = self.parse_group()?;
+                Ok(Expr::Binomial(Box::new(n), Box::new(k)))
+            }
+            "sqrt" => {
+                let index = if self.eat_char('[') {
+                    let index = self.parse_equation()?;
+                    self.expect_char(']')?;
+                    Some(index)
+                } else {
+                    None
+                };
+                let radicand = self.parse_group()?;
+                Ok(match index {
+                    Some(n) => Expr::Pow(
+                        Box::new(radicand),
+                        Box::new(Expr::Div(Box::new(Expr::int(1)), Box::new(n))),
+                    ),
+                    None => Expr::Sqrt(Box::new(radicand)),
+                })
+            }
+            "int" => self.parse_integral(),
+            "sum" | "prod" => self.parse_big_operator(name),
+            _ if LATEX_FUNCTIONS.contains(&name) => self.parse_function(name),
+            _ if GREEK_LETTERS.contains(&name) => Ok(Expr::Var(self.symbols.intern(name))),
+            _ => Err(MathError::ParseError(format!(
+                "Unsupported LaTeX command: \\{}",
+                name
+            ))),
+        }
+    }
+
+    /// `\sin x`, `\sin(2x)`, `\sin^2 x`, `\sin^{-1} x`
+    fn parse_function(&mut self, name: &str) -> Result<Expr, MathError> {
+        let power = if self.eat_char('^') {
+            Some(self.parse_superscript()?)
+        } else {
+            None
+        };
+        let inverse = power == Some(Expr::Neg(Box::new(Expr::int(1))));
+        let name = match name {
+            "sin" | "cos" | "tan" if inverse => format!("arc{}", name),
+            _ => name.to_string(),
+        };
+
+        let arg = if matches!(self.peek_char(), Some('(' | '{')) {
+            self.parse_primary()?
+        } else {
+            // Unparenthesized: the following run of numbers and letters
+            let mut arg = self.parse_power()?;
+            while matches!(
+                self.peek(),
+                Some(LatexToken::Number(_) | LatexToken::Name(_))
+            ) && !(self.integrals > 0 && self.differential().is_some())
+            {
+                let factor = self.parse_power()?;
+                arg = Expr::Mul(Box::new(arg), Box::new(factor));
+            }
+            arg
+        };
+
+        let applied = Parser::new(self.symbols).construct_function_call(&name, vec![arg])?;
+        Ok(match power {
+            Some(_) if inverse => applied,
+            Some(power) => Expr::Pow(Box::new(applied), Box::new(power)),
+            None => applied,
+        })
+    }
+
+    /// `\int body \, dx`
+    fn parse_integral(&mut self) -> Result<Expr, MathError> {
+        if matches!(self.peek_char(), Some('_' | '^')) {
+            return Err(MathError::ParseError(
+                "Definite integrals are not supported".to_string(),
+            ));
+        }
+        self.integrals += 1;
+        let body = self.parse_additive();
+        self.integrals -= 1;
+        let body = body?;
+
+        let var = self
+            .differential()
+            .map(str::to_string)
+            .ok_or_else(|| MathError::ParseError("Expected 'dx' after integral".to_string()))?;
+        self.pos += 2;
+        Ok(Expr::Integral {
+            expr: Box::new(body),
+            var: self.symbols.intern(&var),
+        })
+    }
+
+    /// `\sum_{i=1}^{n} body`, `\prod_{i=1}^{n} body`
+    fn parse_big_operator(&mut self, name: &str) -> Result<Expr, MathError> {
+        let bounds = || MathError::ParseError(format!("Expected \\{}_{{i=a}}^{{b}}", name));
+        if !self.eat_char('_') || !self.eat_char('{') {
+            return Err(bounds());
+        }
+        let var = match self.peek().cloned() {
+            Some(LatexToken::Name(var)) => var,
+            _ => return Err(bounds()),
+        };
+        self.pos += 1;
+        if !self.eat_char('=') {
+            return Err(bounds());
+        }
+        let from = self.parse_additive()?;
+        self.expect_char('}')?;
+        if !self.eat_char('^') {
+            return Err(bounds());
+        }
+        let to = self.parse_superscript()?;
+        let body = self.parse_multiplicative()?;
+
+        let var = self.symbols.intern(&var);
+        let (from, to, body) = (Box::new(from), Box::new(to), Box::new(body));
+        Ok(if name == "sum" {
+            Expr::Summation {
+                var,
+                from,
+                to,
+                body,
+            }
+        } else {
+            Expr::BigProduct {
+                var,
+                from,
+                to,
+                body,
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -533,6 +1371,27 @@ mod tests {
         assert!(matches!(expr, Expr::Sin(_)));
     }
 
+    #[test]
+    fn test_parse_hyperbolic() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+
+        let expr = parser.parse("tanh(x)").unwrap();
+        assert!(matches!(expr, Expr::Tanh(_)));
+        assert!(matches!(
+            parser.parse("sinh(x) + cosh(x)").unwrap(),
+            Expr::Add(_, _)
+        ));
+
+        let expr = parser.parse("arcsinh(x) - arctanh(x)").unwrap();
+        assert!(matches!(expr, Expr::Sub(ref a, ref b)
+            if matches!(**a, Expr::Arcsinh(_)) && matches!(**b, Expr::Arctanh(_))));
+        assert!(matches!(
+            parser.parse("arccosh(2)").unwrap(),
+            Expr::Arccosh(_)
+        ));
+    }
+
     #[test]
     fn test_parse_equation() {
         let mut symbols = SymbolTable::new();
@@ -556,4 +1415,389 @@ mod tests {
         let expr = parser.parse("diff(x^2, x)").unwrap();
         assert!(matches!(expr, Expr::Derivative { .. }));
     }
+
+    #[test]
+    fn test_parse_limit() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let mut parser = Parser::new(&mut symbols);
+
+        for (input, direction) in [
+            ("lim(1/x, x -> 0)", LimitDirection::Both),
+            ("lim(1/x, x -> 0+)", LimitDirection::Right),
+            ("limit(1/x, x → 0-)", LimitDirection::Left),
+        ] {
+            let expr = parser.parse(input).unwrap();
+            assert_eq!(
+                expr,
+                Expr::Limit {
+                    expr: Box::new(parser.parse("1/x").unwrap()),
+                    var: x,
+                    approaching: Box::new(Expr::int(0)),
+                    direction,
+                },
+                "{}",
+                input
+            );
+        }
+
+        // The sign is only a direction right before the `)`
+        let expr = parser.parse("lim(x, x -> 1 - 2)").unwrap();
+        assert!(matches!(
+            expr,
+            Expr::Limit { ref approaching, direction: LimitDirection::Both, .. }
+                if **approaching == parser.parse("1 - 2").unwrap()
+        ));
+        let expr = parser.parse("lim(x, x -> (1 - 2)-)").unwrap();
+        assert!(matches!(
+            expr,
+            Expr::Limit {
+                direction: LimitDirection::Left,
+                ..
+            }
+        ));
+
+        assert!(parser.parse("lim(x, 0)").is_err());
+        assert!(parser.parse("lim(x, x ->)").is_err());
+        assert!(parser.parse("lim(x, x -> 0").is_err());
+
+        drop(parser);
+        let expr = Parser::new(&mut symbols)
+            .parse("lim((x + 1)/x, x -> 0-)")
+            .unwrap();
+        let ascii = expr.to_ascii(&symbols);
+        assert_eq!(ascii, "lim((x + 1) / x, x -> 0-)");
+        assert_eq!(Parser::new(&mut symbols).parse(&ascii).unwrap(), expr);
+        assert_eq!(
+            expr.to_latex(&symbols),
+            "\\lim_{x \\to 0^{-}} \\frac{x + 1}{x}"
+        );
+    }
+
+    #[test]
+    fn test_parse_infinity() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let infinity = Expr::Infinity { negative: false };
+
+        assert_eq!(parser.parse("inf").unwrap(), infinity);
+        assert_eq!(parser.parse("∞").unwrap(), infinity);
+        assert_eq!(
+            parser.parse("-∞").unwrap().canonicalize(),
+            Expr::Infinity { negative: true }
+        );
+        assert_eq!(parse_latex("\\infty", parser.symbols).unwrap(), infinity);
+
+        let expr = parser.parse("lim(1/x, x -> ∞)").unwrap();
+        assert!(matches!(expr, Expr::Limit { ref approaching, .. } if **approaching == infinity));
+        drop(parser);
+        assert_eq!(expr.to_ascii(&symbols), "lim(1 / x, x -> inf)");
+        assert_eq!(
+            expr.to_latex(&symbols),
+            "\\lim_{x \\to \\infty} \\frac{1}{x}"
+        );
+    }
+
+    #[test]
+    fn test_parse_unary_minus_binds_looser_than_power() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let expr = parser.parse("-x^2").unwrap();
+        let x = symbols.get("x").unwrap();
+
+        // -x^2 = -(x^2)
+        let square = Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)));
+        assert_eq!(expr, Expr::Neg(Box::new(square)));
+
+        // (-x)^2 keeps the sign inside
+        let mut parser = Parser::new(&mut symbols);
+        let expr = parser.parse("(-x)^2").unwrap();
+        assert!(matches!(expr, Expr::Pow(base, _) if matches!(*base, Expr::Neg(_))));
+
+        // Signed exponents: 2^-1 = 2^(-1)
+        let mut parser = Parser::new(&mut symbols);
+        let expr = parser.parse("2^-1").unwrap();
+        let minus_one = Expr::Neg(Box::new(Expr::int(1)));
+        assert_eq!(expr, Expr::Pow(Box::new(Expr::int(2)), Box::new(minus_one)));
+    }
+
+    #[test]
+    fn test_parse_unary_plus() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+
+        assert_eq!(parser.parse("+5").unwrap(), Expr::int(5));
+        assert_eq!(
+            parser.parse("3 * +2").unwrap(),
+            Expr::Mul(Box::new(Expr::int(3)), Box::new(Expr::int(2)))
+        );
+    }
+
+    #[test]
+    fn test_parse_double_negation() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let expr = parser.parse("--x").unwrap();
+        let x = symbols.get("x").unwrap();
+
+        assert_eq!(expr, Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Var(x))))));
+    }
+
+    #[test]
+    fn test_parse_mixed_number() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+
+        assert_eq!(
+            parser.parse("1 1/2").unwrap(),
+            Expr::Const(Rational::new(3, 2))
+        );
+
+        // 1 1/2 + 1/2 = 2
+        let expr = parser.parse("1 1/2 + 1/2").unwrap();
+        assert_eq!(expr.canonicalize(), Expr::int(2));
+
+        // Not a mixed number: 11/2 and 1 * 1/2
+        assert_eq!(
+            parser.parse("11/2").unwrap().canonicalize(),
+            Expr::Const(Rational::new(11, 2))
+        );
+        assert!(matches!(parser.parse("1 * 1/2").unwrap(), Expr::Div(_, _)));
+    }
+
+    #[test]
+    fn test_parse_percent() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+
+        assert_eq!(
+            parser.parse("25%").unwrap(),
+            Expr::Const(Rational::new(1, 4))
+        );
+
+        // 50% * 40 = 20
+        let expr = parser.parse("50% * 40").unwrap();
+        assert_eq!(expr.canonicalize(), Expr::int(20));
+
+        // With an operand after it, % is still mod
+        assert!(matches!(parser.parse("25 % 4").unwrap(), Expr::Mod(_, _)));
+
+        // A sign after % is an operator, so % is a percent
+        let half = || Box::new(Expr::Const(Rational::new(1, 2)));
+        assert_eq!(
+            parser.parse("50% - 10").unwrap(),
+            Expr::Sub(half(), Box::new(Expr::int(10)))
+        );
+        assert_eq!(
+            parser.parse("50% + 10").unwrap(),
+            Expr::Add(half(), Box::new(Expr::int(10)))
+        );
+        assert_eq!(
+            parser.parse("100% - 20%").unwrap(),
+            Expr::Sub(
+                Box::new(Expr::int(1)),
+                Box::new(Expr::Const(Rational::new(1, 5)))
+            )
+        );
+
+        // A negative modulus needs parentheses
+        assert!(matches!(parser.parse("5 % (-3)").unwrap(), Expr::Mod(_, _)));
+    }
+
+    #[test]
+    fn test_tokenize_spans() {
+        let tokens = tokenize("sin(x) + 2").unwrap();
+        let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Ident("sin".to_string()),
+                TokenKind::LParen,
+                TokenKind::Ident("x".to_string()),
+                TokenKind::RParen,
+                TokenKind::Plus,
+                TokenKind::Number(Rational::from_integer(2)),
+            ]
+        );
+        let spans: Vec<Range<usize>> = tokens.iter().map(|t| t.span.clone()).collect();
+        assert_eq!(spans, vec![0..3, 3..4, 4..5, 5..6, 7..8, 9..10]);
+    }
+
+    #[test]
+    fn test_tokenize_incomplete_input() {
+        // Lexes even though it does not parse
+        let tokens = tokenize("sin(x +").unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[3].kind, TokenKind::Plus);
+
+        // Byte spans step over multi-byte identifiers; folded literals cover all parts
+        let tokens = tokenize("θ + 1 1/2").unwrap();
+        assert_eq!(tokens[0].span, 0..2);
+        assert_eq!(tokens[2].span, 5..10);
+        assert!(tokenize("x $ y").is_err());
+    }
+
+    #[test]
+    fn test_parse_function_aliases() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+
+        for input in ["atan(x)", "arctan(x)", "tan⁻¹(x)"] {
+            assert!(matches!(parser.parse(input).unwrap(), Expr::Arctan(_)));
+        }
+        // On anything but a function name, ⁻¹ is a reciprocal
+        for (input, power) in [
+            ("x⁻¹", "x^(-1)"),
+            ("(x + 1)⁻¹", "(x + 1)^(-1)"),
+            ("sin(x)⁻¹", "sin(x)^(-1)"),
+            ("2*x⁻¹", "2*x^(-1)"),
+        ] {
+            assert_eq!(
+                parser.parse(input).unwrap(),
+                parser.parse(power).unwrap(),
+                "{input}"
+            );
+        }
+        assert!(matches!(parser.parse("asin(x)").unwrap(), Expr::Arcsin(_)));
+        assert!(matches!(parser.parse("log_e(x)").unwrap(), Expr::Ln(_)));
+        assert!(matches!(parser.parse("|x|").unwrap(), Expr::Abs(_)));
+        assert_eq!(
+            parser.parse("|x - 1|").unwrap(),
+            parser.parse("abs(x - 1)").unwrap()
+        );
+
+        // Aliases apply to calls only, so `atan` is still a variable name
+        assert!(matches!(parser.parse("atan").unwrap(), Expr::Var(_)));
+
+        // Extensible at runtime
+        assert!(parser.parse("arctg(x)").is_err());
+        parser.add_alias("arctg", "arctan");
+        assert!(matches!(parser.parse("arctg(x)").unwrap(), Expr::Arctan(_)));
+    }
+
+    #[test]
+    fn test_parse_abs_bars() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let abs = |e: Expr| Expr::Abs(Box::new(e));
+
+        let expr = parser.parse("|x-3|").unwrap();
+        assert_eq!(expr, parser.parse("abs(x - 3)").unwrap());
+        assert!(matches!(expr, Expr::Abs(ref e) if matches!(**e, Expr::Sub(_, _))));
+
+        let expr = parser.parse("|x| + |y|").unwrap();
+        let (x, y) = (parser.parse("x").unwrap(), parser.parse("y").unwrap());
+        assert_eq!(
+            expr,
+            Expr::Add(Box::new(abs(x.clone())), Box::new(abs(y.clone())))
+        );
+
+        // Nested bars pair from the inside out
+        let expr = parser.parse("||x| - 1|").unwrap();
+        let inner = Expr::Sub(Box::new(abs(x.clone())), Box::new(Expr::int(1)));
+        assert_eq!(expr, abs(inner));
+        let expr = parser.parse("|x - |y||").unwrap();
+        assert_eq!(expr, abs(Expr::Sub(Box::new(x), Box::new(abs(y)))));
+
+        // Coexists with the function form
+        assert_eq!(
+            parser.parse("abs(|x|)").unwrap(),
+            parser.parse("||x||").unwrap()
+        );
+
+        // A percent before a closing bar
+        assert_eq!(
+            parser.parse("|50%|").unwrap(),
+            Expr::Abs(Box::new(Expr::Const(Rational::new(1, 2))))
+        );
+
+        // Unbalanced or stray bars
+        assert!(parser.parse("|x").is_err());
+        assert!(parser.parse("x|").is_err());
+        assert!(parser.parse("|x||").is_err());
+
+        let kinds: Vec<TokenKind> = tokenize("|x| + |y|")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .filter(|k| matches!(k, TokenKind::AbsOpen | TokenKind::AbsClose))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::AbsOpen,
+                TokenKind::AbsClose,
+                TokenKind::AbsOpen,
+                TokenKind::AbsClose
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_latex() {
+        let mut symbols = SymbolTable::new();
+        let expr = parse_latex(r"\frac{x}{2} + \sqrt{x}", &mut symbols).unwrap();
+        let x = Expr::Var(symbols.get("x").unwrap());
+        assert_eq!(
+            expr,
+            Expr::Add(
+                Box::new(Expr::Div(Box::new(x.clone()), Box::new(Expr::int(2)))),
+                Box::new(Expr::Sqrt(Box::new(x.clone())))
+            )
+        );
+
+        // Agrees with the plain-text parser
+        let cases = [
+            (r"2 \cdot x^{n+1}", "2 * x^(n+1)"),
+            (r"3x^2 - \frac{1}{x}", "3*x^2 - 1/x"),
+            (r"\sin^2 x + \cos^2 x = 1", "sin(x)^2 + cos(x)^2 = 1"),
+            (r"\sin^{-1}(x)", "arcsin(x)"),
+            (r"\ln|x| + e^{2x}", "ln(abs(x)) + e^(2*x)"),
+            (r"\left( \theta + \pi \right)", "theta + pi"),
+            (r"\sqrt[3]{x}", "x^(1/3)"),
+            (r"\int x^2 \, dx", "int(x^2, x)"),
+            (r"\int \sin 2x \, \mathrm{d}x", "int(sin(2*x), x)"),
+        ];
+        for (latex, plain) in cases {
+            let expected = Parser::new(&mut symbols).parse(plain).unwrap();
+            assert_eq!(
+                parse_latex(latex, &mut symbols).unwrap(),
+                expected,
+                "{}",
+                latex
+            );
+        }
+
+        let expr = parse_latex(r"\sum_{i=1}^{n} i^2 + 1", &mut symbols).unwrap();
+        match expr {
+            Expr::Add(sum, one) => {
+                assert!(matches!(*sum, Expr::Summation { ref body, .. }
+                    if matches!(**body, Expr::Pow(_, _))));
+                assert_eq!(*one, Expr::int(1));
+            }
+            other => panic!("expected a sum plus one, got {:?}", other),
+        }
+        let expr = parse_latex(r"\binom{n}{2}", &mut symbols).unwrap();
+        assert!(matches!(expr, Expr::Binomial(_, ref k) if **k == Expr::int(2)));
+        let expr = parse_latex(r"\prod_{k=1}^{5} k", &mut symbols).unwrap();
+        assert!(matches!(expr, Expr::BigProduct { .. }));
+    }
+
+    #[test]
+    fn test_parse_latex_errors() {
+        let mut symbols = SymbolTable::new();
+        let message = |input: &str, symbols: &mut SymbolTable| match parse_latex(input, symbols) {
+            Err(MathError::ParseError(message)) => message,
+            other => panic!("expected a parse error for {}, got {:?}", input, other),
+        };
+
+        assert_eq!(
+            message(r"\foo{x}", &mut symbols),
+            r"Unsupported LaTeX command: \foo"
+        );
+        assert!(message(r"\int_0^1 x \, dx", &mut symbols).contains("Definite integrals"));
+        assert!(message(r"\int x^2", &mut symbols).contains("dx"));
+        assert!(parse_latex(r"\frac{x}{2", &mut symbols).is_err());
+        assert!(parse_latex(r"\sum_{i}^{n} i", &mut symbols).is_err());
+    }
 }
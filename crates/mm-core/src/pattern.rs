@@ -0,0 +1,489 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Wildcard pattern matching and substitution.
+//!
+//! A pattern is an ordinary expression in which some variables are
+//! wildcards standing for arbitrary subtrees. Matching binds each wildcard
+//! to the subtree it lines up with; instantiating a template replaces the
+//! bound variables again:
+//!
+//! ```text
+//! pattern  sin(x)² + cos(x)²   wildcards [x]
+//! subject  sin(2t)² + cos(2t)²  →  { x ↦ 2t }
+//! ```
+//!
+//! [`match_pattern`] takes the wildcards from the pattern itself: its
+//! metavariables, written `?a`, `?b`, … (see [`is_metavar`]).
+//!
+//! Sums and products match up to order and grouping: `sin(?t)² + cos(?t)²`
+//! matches `cos(x)² + sin(x)²`, and in `?a + ?b` against `x + y + z` one
+//! wildcard takes a single term while the other takes the remaining two.
+
+use crate::diff::{children, same_head};
+use crate::proof::Constraint;
+use crate::{is_metavar, Expr, Factor, Symbol, Term};
+use std::collections::HashMap;
+
+/// Subtrees bound to wildcards by a successful match.
+pub type Bindings = HashMap<Symbol, Expr>;
+
+/// Match `subject` against `pattern`, where every symbol in `wildcards`
+/// stands for any subtree.
+///
+/// A wildcard occurring more than once must bind to equal subtrees each
+/// time. Everything else has to match exactly: same operators, constants
+/// and non-wildcard variables, except that the operands of `+` and `*` may
+/// come in any order and a wildcard operand may absorb several. A wildcard
+/// used as the bound variable of a derivative, integral, sum or quantifier
+/// binds to a variable.
+pub fn match_wildcards(pattern: &Expr, subject: &Expr, wildcards: &[Symbol]) -> Option<Bindings> {
+    let mut bindings = Bindings::new();
+    let is_wildcard = |v: Symbol| wildcards.contains(&v);
+    match_at(pattern, subject, &is_wildcard, &mut bindings).then_some(bindings)
+}
+
+/// Match `subject` against `pattern`, where the pattern's metavariables
+/// stand for any subtree.
+///
+/// `?a + ?a` matches `x + x` with `?a ↦ x` but not `x + y`. Otherwise
+/// behaves like [`match_wildcards`].
+pub fn match_pattern(pattern: &Expr, subject: &Expr) -> Option<Bindings> {
+    let mut bindings = Bindings::new();
+    match_at(pattern, subject, &is_metavar, &mut bindings).then_some(bindings)
+}
+
+/// Replace every variable bound in `bindings` with its subtree.
+///
+/// Bound variables of derivatives, integrals, sums and quantifiers are
+/// renamed when they are bound to a variable, and left alone otherwise.
+pub fn instantiate(template: &Expr, bindings: &Bindings) -> Expr {
+    let go = |e: &Expr| Box::new(instantiate(e, bindings));
+    let var = |v: &Symbol| match bindings.get(v) {
+        Some(Expr::Var(w)) => *w,
+        _ => *v,
+    };
+    match template {
+        Expr::Var(v) => bindings.get(v).cloned().unwrap_or_else(|| template.clone()),
+        Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => template.clone(),
+        Expr::Neg(e) => Expr::Neg(go(e)),
+        Expr::Sqrt(e) => Expr::Sqrt(go(e)),
+        Expr::Sin(e) => Expr::Sin(go(e)),
+        Expr::Cos(e) => Expr::Cos(go(e)),
+        Expr::Tan(e) => Expr::Tan(go(e)),
+        Expr::Arcsin(e) => Expr::Arcsin(go(e)),
+        Expr::Arccos(e) => Expr::Arccos(go(e)),
+        Expr::Arctan(e) => Expr::Arctan(go(e)),
+        Expr::Sinh(e) => Expr::Sinh(go(e)),
+        Expr::Cosh(e) => Expr::Cosh(go(e)),
+        Expr::Tanh(e) => Expr::Tanh(go(e)),
+        Expr::Arcsinh(e) => Expr::Arcsinh(go(e)),
+        Expr::Arccosh(e) => Expr::Arccosh(go(e)),
+        Expr::Arctanh(e) => Expr::Arctanh(go(e)),
+        Expr::Ln(e) => Expr::Ln(go(e)),
+        Expr::Exp(e) => Expr::Exp(go(e)),
+        Expr::Abs(e) => Expr::Abs(go(e)),
+        Expr::Floor(e) => Expr::Floor(go(e)),
+        Expr::Ceiling(e) => Expr::Ceiling(go(e)),
+        Expr::Factorial(e) => Expr::Factorial(go(e)),
+        Expr::Not(e) => Expr::Not(go(e)),
+        Expr::Add(a, b) => Expr::Add(go(a), go(b)),
+        Expr::Sub(a, b) => Expr::Sub(go(a), go(b)),
+        Expr::Mul(a, b) => Expr::Mul(go(a), go(b)),
+        Expr::Div(a, b) => Expr::Div(go(a), go(b)),
+        Expr::Pow(a, b) => Expr::Pow(go(a), go(b)),
+        Expr::Gte(a, b) => Expr::Gte(go(a), go(b)),
+        Expr::Gt(a, b) => Expr::Gt(go(a), go(b)),
+        Expr::Lte(a, b) => Expr::Lte(go(a), go(b)),
+        Expr::Lt(a, b) => Expr::Lt(go(a), go(b)),
+        Expr::GCD(a, b) => Expr::GCD(go(a), go(b)),
+        Expr::LCM(a, b) => Expr::LCM(go(a), go(b)),
+        Expr::Mod(a, b) => Expr::Mod(go(a), go(b)),
+        Expr::Binomial(a, b) => Expr::Binomial(go(a), go(b)),
+        Expr::And(a, b) => Expr::And(go(a), go(b)),
+        Expr::Or(a, b) => Expr::Or(go(a), go(b)),
+        Expr::Implies(a, b) => Expr::Implies(go(a), go(b)),
+        Expr::Equation { lhs, rhs } => Expr::Equation {
+            lhs: go(lhs),
+            rhs: go(rhs),
+        },
+        Expr::Sum(terms) => Expr::Sum(
+            terms
+                .iter()
+                .map(|t| Term {
+                    coeff: t.coeff,
+                    expr: instantiate(&t.expr, bindings),
+                })
+                .collect(),
+        ),
+        Expr::Product(factors) => Expr::Product(
+            factors
+                .iter()
+                .map(|f| Factor {
+                    base: instantiate(&f.base, bindings),
+                    power: instantiate(&f.power, bindings),
+                })
+                .collect(),
+        ),
+        Expr::Derivative { expr, var: v } => Expr::Derivative {
+            expr: go(expr),
+            var: var(v),
+        },
+        Expr::Integral { expr, var: v } => Expr::Integral {
+            expr: go(expr),
+            var: var(v),
+        },
+        Expr::Limit {
+            expr,
+            var: v,
+            approaching,
+            direction,
+        } => Expr::Limit {
+            expr: go(expr),
+            var: var(v),
+            approaching: go(approaching),
+            direction: *direction,
+        },
+        Expr::Summation {
+            var: v,
+            from,
+            to,
+            body,
+        } => Expr::Summation {
+            var: var(v),
+            from: go(from),
+            to: go(to),
+            body: go(body),
+        },
+        Expr::BigProduct {
+            var: v,
+            from,
+            to,
+            body,
+        } => Expr::BigProduct {
+            var: var(v),
+            from: go(from),
+            to: go(to),
+            body: go(body),
+        },
+        Expr::ForAll {
+            var: v,
+            domain,
+            body,
+        } => Expr::ForAll {
+            var: var(v),
+            domain: domain.as_ref().map(|d| go(d)),
+            body: go(body),
+        },
+        Expr::Exists {
+            var: v,
+            domain,
+            body,
+        } => Expr::Exists {
+            var: var(v),
+            domain: domain.as_ref().map(|d| go(d)),
+            body: go(body),
+        },
+        Expr::Piecewise(branches) => Expr::Piecewise(
+            branches
+                .iter()
+                .map(|(condition, value)| {
+                    (
+                        Constraint {
+                            expr: instantiate(&condition.expr, bindings),
+                        },
+                        instantiate(value, bindings),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn match_at(
+    pattern: &Expr,
+    subject: &Expr,
+    is_wildcard: &dyn Fn(Symbol) -> bool,
+    bindings: &mut Bindings,
+) -> bool {
+    if let Expr::Var(v) = pattern {
+        if is_wildcard(*v) {
+            return bind(*v, subject, bindings);
+        }
+    }
+    if let (Some((mut patterns, join)), Some((subjects, _))) =
+        (ac_operands(pattern), ac_operands(subject))
+    {
+        if same_head(pattern, subject) && subjects.len() <= MAX_AC_OPERANDS {
+            // Fixed operands first: they narrow the search most
+            patterns.sort_by_key(|p| matches!(p, Expr::Var(v) if is_wildcard(*v)));
+            return match_operands(&patterns, subjects, join, is_wildcard, bindings);
+        }
+    }
+    let heads_match = match (pattern, subject) {
+        (Expr::Derivative { var: x, .. }, Expr::Derivative { var: y, .. })
+        | (Expr::Integral { var: x, .. }, Expr::Integral { var: y, .. })
+        | (Expr::Summation { var: x, .. }, Expr::Summation { var: y, .. })
+        | (Expr::BigProduct { var: x, .. }, Expr::BigProduct { var: y, .. })
+        | (Expr::ForAll { var: x, .. }, Expr::ForAll { var: y, .. })
+        | (Expr::Exists { var: x, .. }, Expr::Exists { var: y, .. })
+            if is_wildcard(*x) =>
+        {
+            bind(*x, &Expr::Var(*y), bindings)
+        }
+        _ => same_head(pattern, subject),
+    };
+    let (left, right) = (children(pattern), children(subject));
+    heads_match
+        && left.len() == right.len()
+        && left
+            .into_iter()
+            .zip(right)
+            .all(|(p, s)| match_at(p, s, is_wildcard, bindings))
+}
+
+/// Flattened sums and products above this size are matched in order only;
+/// unordered matching tries every subset of the operands.
+const MAX_AC_OPERANDS: usize = 12;
+
+type Join = fn(Box<Expr>, Box<Expr>) -> Expr;
+
+/// The operands of a chain of `+` or `*`, with the operator to rebuild it.
+fn ac_operands(expr: &Expr) -> Option<(Vec<&Expr>, Join)> {
+    fn flatten<'a>(expr: &'a Expr, sum: bool, operands: &mut Vec<&'a Expr>) {
+        match (expr, sum) {
+            (Expr::Add(a, b), true) | (Expr::Mul(a, b), false) => {
+                flatten(a, sum, operands);
+                flatten(b, sum, operands);
+            }
+            _ => operands.push(expr),
+        }
+    }
+    let (sum, join): (bool, Join) = match expr {
+        Expr::Add(..) => (true, Expr::Add),
+        Expr::Mul(..) => (false, Expr::Mul),
+        _ => return None,
+    };
+    let mut operands = Vec::new();
+    flatten(expr, sum, &mut operands);
+    Some((operands, join))
+}
+
+/// Share `subjects` out among `patterns`, each taking one operand, except
+/// that a wildcard may take several, joined back with `join`.
+fn match_operands(
+    patterns: &[&Expr],
+    subjects: Vec<&Expr>,
+    join: Join,
+    is_wildcard: &dyn Fn(Symbol) -> bool,
+    bindings: &mut Bindings,
+) -> bool {
+    let Some((&first, rest)) = patterns.split_first() else {
+        return subjects.is_empty();
+    };
+    let n = subjects.len();
+    let is_free = |p: &Expr| matches!(p, Expr::Var(v) if is_wildcard(*v));
+    // Every pattern takes at least one operand, and fixed ones exactly one
+    if n < patterns.len() || (!patterns.iter().any(|p| is_free(p)) && n != patterns.len()) {
+        return false;
+    }
+    let wildcard = match first {
+        Expr::Var(v) if is_wildcard(*v) => Some(*v),
+        _ => None,
+    };
+    // Bitmasks over `subjects`; a last wildcard takes all that is left
+    let all = (1usize << n) - 1;
+    let most = n - rest.len();
+    let exact = !rest.iter().any(|p| is_free(p));
+    let choices: Vec<usize> = match wildcard {
+        Some(_) if rest.is_empty() => vec![all],
+        // A bound wildcard can only take the operands it is bound to
+        Some(v) if bindings.contains_key(&v) => bound_operands(&bindings[&v], &subjects)
+            .into_iter()
+            .collect(),
+        Some(_) => (1..=all)
+            .filter(|mask| {
+                let size = mask.count_ones() as usize;
+                size == most || (!exact && size < most)
+            })
+            .collect(),
+        // A fixed operand can only match one with the same head
+        None => (0..n)
+            .filter(|&i| std::mem::discriminant(first) == std::mem::discriminant(subjects[i]))
+            .map(|i| 1 << i)
+            .collect(),
+    };
+    for mask in choices {
+        let taken = |i: &usize| mask & (1 << i) != 0;
+        let others = (0..n).filter(|i| !taken(i)).map(|i| subjects[i]).collect();
+        let saved = bindings.clone();
+        let matched = match wildcard {
+            Some(v) => {
+                let operand = (0..n)
+                    .filter(taken)
+                    .map(|i| subjects[i].clone())
+                    .reduce(|a, b| join(Box::new(a), Box::new(b)))
+                    .expect("masks are nonempty");
+                bind(v, &operand, bindings)
+            }
+            None => match_at(
+                first,
+                subjects[mask.trailing_zeros() as usize],
+                is_wildcard,
+                bindings,
+            ),
+        };
+        if matched && match_operands(rest, others, join, is_wildcard, bindings) {
+            return true;
+        }
+        *bindings = saved;
+    }
+    false
+}
+
+/// The operands among `subjects` that join back into `bound`, as a
+/// bitmask, if there are any.
+///
+/// Subjects are flattened, so a subject equal to `bound` is never a chain
+/// of the joining operator. Equal operands are interchangeable, so the
+/// first in order are taken.
+fn bound_operands(bound: &Expr, subjects: &[&Expr]) -> Option<usize> {
+    if let Some(i) = subjects.iter().position(|&s| s == bound) {
+        return Some(1 << i);
+    }
+    let (wanted, _) = ac_operands(bound)?;
+    let mut mask = 0usize;
+    let mut next = 0;
+    for operand in wanted {
+        let i = (next..subjects.len()).find(|&i| subjects[i] == operand)?;
+        mask |= 1 << i;
+        next = i + 1;
+    }
+    Some(mask)
+}
+
+/// Bind `wildcard` to `subject`, or check it against an earlier binding.
+fn bind(wildcard: Symbol, subject: &Expr, bindings: &mut Bindings) -> bool {
+    match bindings.get(&wildcard) {
+        Some(bound) => bound == subject,
+        None => {
+            bindings.insert(wildcard, subject.clone());
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Parser;
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_match_and_instantiate() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let pattern = parser.parse("sin(x)^2 + cos(x)^2").unwrap();
+        let subject = parser.parse("sin(2*t)^2 + cos(2*t)^2").unwrap();
+        let mismatched = parser.parse("sin(2*t)^2 + cos(t)^2").unwrap();
+        let template = parser.parse("1 - cos(x)^2").unwrap();
+        let expected = parser.parse("1 - cos(2*t)^2").unwrap();
+        let x = symbols.intern("x");
+
+        let bindings = match_wildcards(&pattern, &subject, &[x]).unwrap();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(instantiate(&template, &bindings), expected);
+
+        // x must bind to the same subtree both times
+        assert!(match_wildcards(&pattern, &mismatched, &[x]).is_none());
+        // Without wildcards only the pattern itself matches
+        assert!(match_wildcards(&pattern, &subject, &[]).is_none());
+        assert_eq!(
+            match_wildcards(&pattern, &pattern, &[]),
+            Some(Bindings::new())
+        );
+    }
+
+    #[test]
+    fn test_match_pattern() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let pattern = parser.parse("?a + ?a").unwrap();
+        let twice = parser.parse("x + x").unwrap();
+        let distinct = parser.parse("x + y").unwrap();
+        let template = parser.parse("2 * ?a").unwrap();
+        let expected = parser.parse("2 * x").unwrap();
+        let (a, x) = (symbols.intern("?a"), symbols.intern("x"));
+
+        let bindings = match_pattern(&pattern, &twice).unwrap();
+        assert_eq!(bindings, Bindings::from([(a, Expr::Var(x))]));
+        assert_eq!(instantiate(&template, &bindings), expected);
+        assert!(match_pattern(&pattern, &distinct).is_none());
+
+        // Ordinary variables in a pattern only match themselves
+        assert!(match_pattern(&twice, &twice).is_some());
+        assert!(match_pattern(&twice, &distinct).is_none());
+    }
+
+    #[test]
+    fn test_commutative_match() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let pythagoras = parser.parse("sin(?t)^2 + cos(?t)^2").unwrap();
+        let reversed = parser.parse("cos(x)^2 + sin(x)^2").unwrap();
+        let mixed = parser.parse("cos(x)^2 + sin(y)^2").unwrap();
+        let split = parser.parse("?a + ?b").unwrap();
+        let sum = parser.parse("x + y + z").unwrap();
+        let product = parser.parse("2 * ?a * y").unwrap();
+        let (t, x) = (symbols.intern("?t"), symbols.intern("x"));
+
+        let bindings = match_pattern(&pythagoras, &reversed).unwrap();
+        assert_eq!(bindings, Bindings::from([(t, Expr::Var(x))]));
+        assert!(match_pattern(&pythagoras, &mixed).is_none());
+
+        // Every term lands in exactly one wildcard
+        let mut parser = Parser::new(&mut symbols);
+        let rest = parser.parse("y + z").unwrap();
+        let subject = parser.parse("y * (x + 1) * 2").unwrap();
+        let factor = parser.parse("x + 1").unwrap();
+        let (a, b) = (symbols.intern("?a"), symbols.intern("?b"));
+        let bindings = match_pattern(&split, &sum).unwrap();
+        assert_eq!(bindings, Bindings::from([(a, Expr::Var(x)), (b, rest)]));
+        assert_eq!(match_pattern(&product, &subject).unwrap()[&a], factor);
+        assert!(match_pattern(&split, &Expr::Var(x)).is_none());
+    }
+
+    #[test]
+    fn test_commutative_match_prunes() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let pattern = parser.parse("?a + ?a + ?b + ?c").unwrap();
+        let terms = (1..=12).map(|i| format!("x{i}")).collect::<Vec<_>>();
+        let distinct = parser.parse(&terms.join(" + ")).unwrap();
+        let repeated = parser.parse("x + y + z + y + w").unwrap();
+        let (a, b) = (symbols.intern("?a"), symbols.intern("?b"));
+
+        // No term repeats, so every way of sharing them out fails
+        assert!(match_pattern(&pattern, &distinct).is_none());
+        let bindings = match_pattern(&pattern, &repeated).unwrap();
+        assert_eq!(bindings[&a], Expr::Var(symbols.intern("y")));
+        assert_eq!(bindings[&b], Expr::Var(symbols.intern("x")));
+    }
+
+    #[test]
+    fn test_bound_variable_wildcard() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let pattern = parser.parse("diff(f, x)").unwrap();
+        let subject = parser.parse("diff(t^2, t)").unwrap();
+        let template = parser.parse("diff(f, x) + x").unwrap();
+        let expected = parser.parse("diff(t^2, t) + t").unwrap();
+        let (f, x) = (symbols.intern("f"), symbols.intern("x"));
+
+        let bindings = match_wildcards(&pattern, &subject, &[f, x]).unwrap();
+        assert_eq!(instantiate(&template, &bindings), expected);
+    }
+}
@@ -23,7 +23,10 @@
 //! state.add_goal(goal);             // a+b+c ≥ 3
 //! ```
 
+use crate::eval::Env;
 use crate::{Expr, Symbol, SymbolTable};
+use std::collections::HashSet;
+use thiserror::Error;
 
 // ============================================================================
 // Core Types
@@ -83,6 +86,12 @@ pub struct ProofState {
     /// Counter for generating unique goal IDs
     next_goal_id: u32,
     
+    /// Hypotheses that have been discharged and may no longer be used
+    discharged: HashSet<HypId>,
+    
+    /// Open case branches: (case_number, total_cases, first hypothesis in the branch)
+    case_stack: Vec<(usize, usize, u32)>,
+    
     /// Symbol table for variable names
     pub symbols: SymbolTable,
 }
@@ -98,6 +107,8 @@ impl ProofState {
             search_direction: SearchDirection::Forward,
             next_hyp_id: 0,
             next_goal_id: 0,
+            discharged: HashSet::new(),
+            case_stack: Vec::new(),
             symbols: SymbolTable::new(),
         }
     }
@@ -199,6 +210,303 @@ impl Default for ProofState {
     }
 }
 
+// ============================================================================
+// Case Splits - Proving a goal by exhaustive cases
+// ============================================================================
+
+impl ProofState {
+    /// Split an open goal into one subgoal per case.
+    ///
+    /// Each subgoal restates the goal and comes with its case constraint as a
+    /// `CaseSplit` hypothesis. The cases are taken to be exhaustive (e.g. the
+    /// disjuncts of `x < 0 ∨ x = 0 ∨ x > 0`); checking that is up to the caller.
+    /// Once every subgoal is proved, [`ProofState::combine_cases`] closes the
+    /// parent goal.
+    ///
+    /// Returns each subgoal with the hypothesis for its case, which should be
+    /// discharged once that case is done.
+    pub fn split_cases(
+        &mut self,
+        goal_id: GoalId,
+        cases: &[Constraint],
+    ) -> Result<Vec<(GoalId, HypId)>, ProofError> {
+        let goal_expr = self
+            .goals
+            .iter()
+            .find(|g| g.id == goal_id && matches!(g.status, GoalStatus::Open))
+            .map(|g| g.expr.clone())
+            .ok_or(ProofError::GoalNotOpen(goal_id))?;
+
+        let total_cases = cases.len();
+        let branches: Vec<(GoalId, HypId)> = cases
+            .iter()
+            .enumerate()
+            .map(|(i, case)| {
+                let sub = self.add_goal(goal_expr.clone());
+                let hyp = self.add_hypothesis(
+                    case.expr.clone(),
+                    HypothesisOrigin::CaseSplit {
+                        case_number: i + 1,
+                        total_cases,
+                    },
+                );
+                (sub, hyp)
+            })
+            .collect();
+
+        let subgoals = branches.iter().map(|(g, _)| *g).collect();
+        if let Some(goal) = self.goals.iter_mut().find(|g| g.id == goal_id) {
+            goal.status = GoalStatus::Split(subgoals);
+        }
+        Ok(branches)
+    }
+
+    /// Close a split goal once all of its cases are proved.
+    ///
+    /// Returns `true` if the goal is now proved, `false` if some case is still
+    /// open or the goal was not split.
+    pub fn combine_cases(&mut self, goal_id: GoalId) -> bool {
+        let Some(GoalStatus::Split(subgoals)) = self
+            .goals
+            .iter()
+            .find(|g| g.id == goal_id)
+            .map(|g| g.status.clone())
+        else {
+            return false;
+        };
+
+        let mut steps = Vec::new();
+        for sub in &subgoals {
+            match self.goals.iter().find(|g| g.id == *sub).map(|g| &g.status) {
+                Some(GoalStatus::Proved(proof)) => steps.extend(proof.steps.iter().cloned()),
+                _ => return false,
+            }
+        }
+
+        let proof = Proof {
+            steps,
+            justification: format!("By exhaustive case analysis ({} cases)", subgoals.len()),
+        };
+        self.mark_proved(goal_id, proof);
+        true
+    }
+}
+
+// ============================================================================
+// Transcript - Recording and replaying proofs
+// ============================================================================
+
+/// Why a recorded proof failed to replay.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ProofError {
+    /// A step cites a hypothesis that does not exist at that point.
+    #[error("Step {step} cites unknown hypothesis {hyp:?}")]
+    UnknownHypothesis { step: usize, hyp: HypId },
+
+    /// A step gives no justification.
+    #[error("Step {step} has no justification")]
+    MissingJustification { step: usize },
+
+    /// A step asserts a closed statement that evaluates to false.
+    #[error("Step {step} asserts a false statement")]
+    FalseStatement { step: usize },
+
+    /// No hypothesis with this ID exists.
+    #[error("Unknown hypothesis {0:?}")]
+    NoSuchHypothesis(HypId),
+
+    /// The hypothesis was discharged and is out of scope.
+    #[error("Hypothesis {0:?} has been discharged")]
+    Discharged(HypId),
+
+    /// `end_case` was called outside a case branch.
+    #[error("Not inside a case branch")]
+    NoOpenCase,
+
+    /// No open goal with this ID exists.
+    #[error("Goal {0:?} is not an open goal")]
+    GoalNotOpen(GoalId),
+}
+
+impl ProofState {
+    /// Introduce an assumption.
+    ///
+    /// Inside a case branch (see [`ProofState::begin_case`]) the assumption is
+    /// recorded as a `CaseSplit` and is discharged when the branch ends;
+    /// otherwise it is a plain `Assumption` that stays until discharged.
+    pub fn assume(&mut self, constraint: Constraint) -> HypId {
+        let origin = match self.case_stack.last() {
+            Some(&(case_number, total_cases, _)) => HypothesisOrigin::CaseSplit {
+                case_number,
+                total_cases,
+            },
+            None => HypothesisOrigin::Assumption,
+        };
+        self.add_hypothesis(constraint.expr, origin)
+    }
+
+    /// Look up a hypothesis for use in a step.
+    ///
+    /// Fails if the hypothesis does not exist or has been discharged.
+    pub fn use_hypothesis(&self, id: HypId) -> Result<&Hypothesis, ProofError> {
+        let hyp = self
+            .hypotheses
+            .iter()
+            .find(|h| h.id == id)
+            .ok_or(ProofError::NoSuchHypothesis(id))?;
+        if self.discharged.contains(&id) {
+            return Err(ProofError::Discharged(id));
+        }
+        Ok(hyp)
+    }
+
+    /// Whether a hypothesis exists and is still in scope.
+    pub fn is_available(&self, id: HypId) -> bool {
+        self.use_hypothesis(id).is_ok()
+    }
+
+    /// Discharge a hypothesis so it can no longer be used.
+    ///
+    /// Everything derived from it is discharged too, since those facts
+    /// only hold under the discharged assumption.
+    pub fn discharge(&mut self, id: HypId) -> Result<(), ProofError> {
+        self.use_hypothesis(id)?;
+        self.discharged.insert(id);
+        // Derived hypotheses always come after the ones they cite
+        for hyp in &self.hypotheses {
+            if let HypothesisOrigin::Derived { from, .. } = &hyp.origin {
+                if from.iter().any(|f| self.discharged.contains(f)) {
+                    self.discharged.insert(hyp.id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Enter branch `case_number` of a `total_cases`-way case split.
+    ///
+    /// Assumptions made until the matching [`ProofState::end_case`] belong
+    /// to this branch.
+    pub fn begin_case(&mut self, case_number: usize, total_cases: usize) {
+        self.case_stack
+            .push((case_number, total_cases, self.next_hyp_id));
+    }
+
+    /// Leave the current case branch, discharging its assumptions.
+    pub fn end_case(&mut self) -> Result<(), ProofError> {
+        let (_, _, first) = self.case_stack.pop().ok_or(ProofError::NoOpenCase)?;
+        let scoped: Vec<HypId> = self
+            .hypotheses
+            .iter()
+            .filter(|h| h.id.0 >= first && matches!(h.origin, HypothesisOrigin::CaseSplit { .. }))
+            .map(|h| h.id)
+            .filter(|id| !self.discharged.contains(id))
+            .collect();
+        for id in scoped {
+            self.discharge(id)?;
+        }
+        Ok(())
+    }
+}
+
+impl ProofState {
+    /// Record the derivations made in this state as an ordered transcript.
+    ///
+    /// Each derived hypothesis becomes one step, in the order it was
+    /// introduced. Givens, assumptions and case splits are part of the
+    /// initial state a transcript is replayed against, not of the transcript.
+    pub fn record(&self) -> Vec<ProofStep> {
+        self.hypotheses
+            .iter()
+            .filter_map(|h| match &h.origin {
+                HypothesisOrigin::Derived {
+                    from,
+                    justification,
+                } => Some(ProofStep {
+                    expr: h.expr.clone(),
+                    justification: justification.clone(),
+                    used_hypotheses: from.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Re-apply a recorded transcript to `initial`, checking every transition.
+///
+/// A step is legal if it is justified, only cites hypotheses that are in
+/// scope when it is applied, and is not a closed statement that evaluates to
+/// false. Each step is added as a derived hypothesis; a step matching an
+/// open goal proves that goal. `initial` must hold the same givens, in the
+/// same order, as the state the transcript was recorded from so that
+/// hypothesis IDs line up.
+pub fn replay(steps: &[ProofStep], mut initial: ProofState) -> Result<ProofState, ProofError> {
+    for (i, step) in steps.iter().enumerate() {
+        if step.justification.trim().is_empty() {
+            return Err(ProofError::MissingJustification { step: i });
+        }
+        if let Some(&hyp) = step
+            .used_hypotheses
+            .iter()
+            .find(|id| !initial.is_available(**id))
+        {
+            return Err(ProofError::UnknownHypothesis { step: i, hyp });
+        }
+        if is_false_statement(&step.expr) {
+            return Err(ProofError::FalseStatement { step: i });
+        }
+
+        initial.add_hypothesis(
+            step.expr.clone(),
+            HypothesisOrigin::Derived {
+                from: step.used_hypotheses.clone(),
+                justification: step.justification.clone(),
+            },
+        );
+
+        let proved: Vec<GoalId> = initial
+            .open_goals()
+            .iter()
+            .filter(|g| g.expr == step.expr)
+            .map(|g| g.id)
+            .collect();
+        for goal_id in proved {
+            let proof = Proof {
+                steps: steps[..=i].to_vec(),
+                justification: step.justification.clone(),
+            };
+            initial.mark_proved(goal_id, proof);
+        }
+    }
+    Ok(initial)
+}
+
+/// Whether `expr` is a closed relation or proposition that evaluates to false.
+fn is_false_statement(expr: &Expr) -> bool {
+    let is_statement = matches!(
+        expr,
+        Expr::Equation { .. }
+            | Expr::Gte(_, _)
+            | Expr::Gt(_, _)
+            | Expr::Lte(_, _)
+            | Expr::Lt(_, _)
+            | Expr::And(_, _)
+            | Expr::Or(_, _)
+            | Expr::Not(_)
+            | Expr::Implies(_, _)
+    );
+    if !is_statement || !expr.free_vars().is_empty() {
+        return false;
+    }
+    match (expr, expr.evaluate(&Env::new())) {
+        // Equations evaluate to lhs - rhs
+        (Expr::Equation { .. }, Some(diff)) => diff.abs() > 1e-9,
+        (_, Some(truth)) => truth == 0.0,
+        (_, None) => false,
+    }
+}
+
 // ============================================================================
 // Hypothesis - Known facts
 // ============================================================================
@@ -322,6 +630,25 @@ pub struct Constraint {
     pub expr: Expr,
 }
 
+impl Constraint {
+    /// The constraint `expr ≠ 0`, written `¬(expr = 0)`.
+    pub fn nonzero(expr: Expr) -> Self {
+        Constraint {
+            expr: Expr::Not(Box::new(Expr::Equation {
+                lhs: Box::new(expr),
+                rhs: Box::new(Expr::int(0)),
+            })),
+        }
+    }
+
+    /// The constraint `expr > 0`.
+    pub fn positive(expr: Expr) -> Self {
+        Constraint {
+            expr: Expr::Gt(Box::new(expr), Box::new(Expr::int(0))),
+        }
+    }
+}
+
 // ============================================================================
 // Proof - Proof of a goal
 // ============================================================================
@@ -466,4 +793,184 @@ mod tests {
         state.mark_proved(goal_id, Proof::by_am_gm());
         assert!(state.is_complete());
     }
+
+    /// a = 2 ⊢ a + 1 = 3 ⊢ a + 2 = 4
+    fn two_step_proof() -> (ProofState, Vec<ProofStep>) {
+        let mut state = ProofState::new();
+        let a = state.add_variable("a", Domain::Real);
+        let eq = |lhs: Expr, rhs: i64| Expr::Equation {
+            lhs: Box::new(lhs),
+            rhs: Box::new(Expr::int(rhs)),
+        };
+        let plus = |n: i64| Expr::Add(Box::new(Expr::Var(a)), Box::new(Expr::int(n)));
+
+        let given = state.add_given(eq(Expr::Var(a), 2));
+        state.add_goal(eq(plus(2), 4));
+        let initial = state;
+
+        let mut session = ProofState::new();
+        session.add_variable("a", Domain::Real);
+        session.add_given(eq(Expr::Var(a), 2));
+        let h1 = session.add_hypothesis(
+            eq(plus(1), 3),
+            HypothesisOrigin::Derived {
+                from: vec![given],
+                justification: "Add 1 to both sides".to_string(),
+            },
+        );
+        session.add_hypothesis(
+            eq(plus(2), 4),
+            HypothesisOrigin::Derived {
+                from: vec![h1],
+                justification: "Add 1 to both sides".to_string(),
+            },
+        );
+
+        (initial, session.record())
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let (initial, steps) = two_step_proof();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].used_hypotheses, vec![HypId(1)]);
+
+        let replayed = replay(&steps, initial).unwrap();
+        assert!(replayed.is_complete());
+        assert_eq!(replayed.hypotheses.len(), 3);
+    }
+
+    #[test]
+    fn test_replay_detects_tampering() {
+        // Citing a hypothesis that was never introduced
+        let (initial, mut steps) = two_step_proof();
+        steps[1].used_hypotheses = vec![HypId(7)];
+        assert_eq!(
+            replay(&steps, initial).unwrap_err(),
+            ProofError::UnknownHypothesis {
+                step: 1,
+                hyp: HypId(7)
+            }
+        );
+
+        // Replacing a step with a false closed statement
+        let (initial, mut steps) = two_step_proof();
+        steps[0].expr = Expr::Equation {
+            lhs: Box::new(Expr::Add(Box::new(Expr::int(2)), Box::new(Expr::int(2)))),
+            rhs: Box::new(Expr::int(5)),
+        };
+        assert_eq!(
+            replay(&steps, initial).unwrap_err(),
+            ProofError::FalseStatement { step: 0 }
+        );
+    }
+
+    #[test]
+    fn test_assume_use_discharge() {
+        let mut state = ProofState::new();
+        let x = state.add_variable("x", Domain::Real);
+        let positive = Expr::Gt(Box::new(Expr::Var(x)), Box::new(Expr::int(0)));
+
+        let h = state.assume(Constraint { expr: positive });
+        assert!(matches!(
+            state.use_hypothesis(h).unwrap().origin,
+            HypothesisOrigin::Assumption
+        ));
+
+        // x² > 0 follows from x > 0
+        let derived = state.add_hypothesis(
+            Expr::Gt(
+                Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+                Box::new(Expr::int(0)),
+            ),
+            HypothesisOrigin::Derived {
+                from: vec![h],
+                justification: "Square of a positive number".to_string(),
+            },
+        );
+
+        state.discharge(h).unwrap();
+        assert_eq!(
+            state.use_hypothesis(h).unwrap_err(),
+            ProofError::Discharged(h)
+        );
+        assert!(!state.is_available(derived));
+        assert_eq!(
+            state.use_hypothesis(HypId(9)).unwrap_err(),
+            ProofError::NoSuchHypothesis(HypId(9))
+        );
+    }
+
+    #[test]
+    fn test_case_assumptions_are_scoped() {
+        let mut state = ProofState::new();
+        let x = state.add_variable("x", Domain::Real);
+        let given = state.add_given(Expr::Var(x));
+
+        state.begin_case(1, 2);
+        let h = state.assume(Constraint {
+            expr: Expr::Lt(Box::new(Expr::Var(x)), Box::new(Expr::int(0))),
+        });
+        assert!(matches!(
+            state.use_hypothesis(h).unwrap().origin,
+            HypothesisOrigin::CaseSplit {
+                case_number: 1,
+                total_cases: 2
+            }
+        ));
+        state.end_case().unwrap();
+
+        assert!(!state.is_available(h));
+        assert!(state.is_available(given));
+        assert_eq!(state.end_case().unwrap_err(), ProofError::NoOpenCase);
+    }
+
+    #[test]
+    fn test_split_cases_by_sign() {
+        let mut state = ProofState::new();
+        let x = state.add_variable("x", Domain::Real);
+        let var = || Box::new(Expr::Var(x));
+        let zero = || Box::new(Expr::int(0));
+
+        // |x| ≥ 0, split on x < 0 ∨ x = 0 ∨ x > 0
+        let goal = state.add_goal(Expr::Gte(Box::new(Expr::Abs(var())), zero()));
+        let cases = [
+            Constraint {
+                expr: Expr::Lt(var(), zero()),
+            },
+            Constraint {
+                expr: Expr::Equation {
+                    lhs: var(),
+                    rhs: zero(),
+                },
+            },
+            Constraint {
+                expr: Expr::Gt(var(), zero()),
+            },
+        ];
+        let branches = state.split_cases(goal, &cases).unwrap();
+        assert_eq!(branches.len(), 3);
+        assert!(matches!(
+            state.use_hypothesis(branches[2].1).unwrap().origin,
+            HypothesisOrigin::CaseSplit {
+                case_number: 3,
+                total_cases: 3
+            }
+        ));
+        assert_eq!(
+            state.split_cases(goal, &cases).unwrap_err(),
+            ProofError::GoalNotOpen(goal)
+        );
+
+        let reasons = ["|x| = -x > 0", "|0| = 0", "|x| = x > 0"];
+        for (i, &(sub, hyp)) in branches.iter().enumerate() {
+            assert!(!state.combine_cases(goal));
+            state.mark_proved(sub, Proof::new(reasons[i].to_string()));
+            state.discharge(hyp).unwrap();
+        }
+
+        assert!(state.combine_cases(goal));
+        assert!(state.is_complete());
+        assert!(branches.iter().all(|(_, h)| !state.is_available(*h)));
+    }
 }
@@ -10,7 +10,7 @@
 //! `1/3 * 3 = 1` exactly, no floating-point surprises.
 
 use num_rational::Ratio;
-use num_traits::{One, Signed, Zero};
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Signed, Zero};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
 use std::fmt;
@@ -121,6 +121,8 @@ impl Rational {
     }
 
     /// Raise to an integer power.
+    ///
+    /// Overflows `i64` for large results; see [`Rational::checked_pow`].
     pub fn pow(&self, exp: i32) -> Self {
         if exp >= 0 {
             Rational(self.0.pow(exp))
@@ -128,6 +130,74 @@ impl Rational {
             Rational(self.0.recip().pow(-exp))
         }
     }
+
+    /// Raise to an integer power, or `None` if the result does not fit in
+    /// `i64` or a zero is raised to a negative power.
+    pub fn checked_pow(&self, exp: i32) -> Option<Self> {
+        if exp < 0 && self.is_zero() {
+            return None;
+        }
+        let base = if exp >= 0 { self.0 } else { self.0.recip() };
+        let numer = base.numer().checked_pow(exp.unsigned_abs())?;
+        let denom = base.denom().checked_pow(exp.unsigned_abs())?;
+        // Powers of coprime integers stay coprime
+        Some(Rational(Ratio::new_raw(numer, denom)))
+    }
+
+    /// `self + other`, or `None` if it does not fit in `i64`.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(&other.0).map(Rational)
+    }
+
+    /// `self - other`, or `None` if it does not fit in `i64`.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.0.checked_sub(&other.0).map(Rational)
+    }
+
+    /// `self · other`, or `None` if it does not fit in `i64`.
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.0.checked_mul(&other.0).map(Rational)
+    }
+
+    /// `self / other`, or `None` if `other` is zero or the quotient does not
+    /// fit in `i64`.
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.0.checked_div(&other.0).map(Rational)
+    }
+
+    /// Expand into simple continued-fraction coefficients.
+    ///
+    /// `355/113 = 3 + 1/(7 + 1/16)` gives `[3, 7, 16]`. The first coefficient
+    /// is the floor, so negative values start with a negative term. The
+    /// expansion is finite and its last term is never 1 unless it is the only
+    /// term.
+    pub fn to_continued_fraction(&self) -> Vec<i64> {
+        let (mut numer, mut denom) = (self.numer(), self.denom());
+        let mut coeffs = Vec::new();
+        while denom != 0 {
+            coeffs.push(numer.div_euclid(denom));
+            (numer, denom) = (denom, numer.rem_euclid(denom));
+        }
+        coeffs
+    }
+
+    /// Rebuild a rational from continued-fraction coefficients.
+    ///
+    /// Inverse of [`Rational::to_continued_fraction`]. An empty slice is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a zero coefficient leads to a division by zero, e.g. `[1, 0]`.
+    pub fn from_continued_fraction(coeffs: &[i64]) -> Self {
+        let Some((&last, rest)) = coeffs.split_last() else {
+            return Rational::from_integer(0);
+        };
+        rest.iter()
+            .rev()
+            .fold(Rational::from_integer(last), |tail, &a| {
+                Rational::from_integer(a) + tail.recip()
+            })
+    }
 }
 
 // ============================================================================
@@ -297,4 +367,52 @@ mod tests {
         assert_eq!(half.pow(2), Rational::new(1, 4));
         assert_eq!(half.pow(-1), Rational::from_integer(2));
     }
+
+    #[test]
+    fn test_checked_power() {
+        let ten = Rational::from_integer(10);
+        assert_eq!(
+            ten.checked_pow(18),
+            Some(Rational::from_integer(10i64.pow(18)))
+        );
+        assert_eq!(ten.checked_pow(20), None);
+        assert_eq!(
+            Rational::new(-2, 3).checked_pow(-3),
+            Some(Rational::new(-27, 8))
+        );
+        assert_eq!(Rational::from_integer(0).checked_pow(-1), None);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = Rational::from_integer(i64::MAX);
+        let half = Rational::new(1, 2);
+        assert_eq!(half.checked_add(&half), Some(Rational::from_integer(1)));
+        assert_eq!(half.checked_sub(&half), Some(Rational::from_integer(0)));
+        assert_eq!(half.checked_mul(&half), Some(Rational::new(1, 4)));
+        assert_eq!(half.checked_div(&half), Some(Rational::from_integer(1)));
+        assert_eq!(half.checked_div(&Rational::from_integer(0)), None);
+        assert_eq!(max.checked_add(&half), None);
+        assert_eq!(max.checked_mul(&max), None);
+        assert_eq!(Rational::from_integer(i64::MIN).checked_sub(&max), None);
+        assert_eq!(max.checked_div(&Rational::new(1, 2)), None);
+    }
+
+    #[test]
+    fn test_continued_fraction() {
+        let pi_approx = Rational::new(355, 113);
+        assert_eq!(pi_approx.to_continued_fraction(), vec![3, 7, 16]);
+        assert_eq!(Rational::from_continued_fraction(&[3, 7, 16]), pi_approx);
+
+        // Floor first, so -7/3 = -3 + 1/(1 + 1/2)
+        let neg = Rational::new(-7, 3);
+        assert_eq!(neg.to_continued_fraction(), vec![-3, 1, 2]);
+        assert_eq!(Rational::from_continued_fraction(&[-3, 1, 2]), neg);
+
+        assert_eq!(Rational::from_integer(5).to_continued_fraction(), vec![5]);
+        assert_eq!(
+            Rational::from_continued_fraction(&[]),
+            Rational::from_integer(0)
+        );
+    }
 }
@@ -10,13 +10,50 @@
 //! and symbols can be compared by simple integer comparison.
 
 use serde::{Deserialize, Serialize};
-use string_interner::{DefaultBackend, DefaultSymbol, StringInterner};
+#[cfg(feature = "global-interner")]
+use std::cell::RefCell;
+use string_interner::{DefaultBackend, DefaultSymbol, StringInterner, Symbol as _};
 
 /// An interned symbol representing a variable name.
 ///
 /// Symbols are cheap to copy and compare (just an integer under the hood).
 pub type Symbol = DefaultSymbol;
 
+/// Prefix marking a name as a pattern metavariable, as in `?a`.
+pub const METAVAR_PREFIX: char = '?';
+
+/// Metavariables are issued from the upper half of the symbol space, so a
+/// symbol alone tells whether it is one.
+const METAVAR_OFFSET: usize = 1 << 31;
+
+/// Whether `symbol` is a pattern metavariable, i.e. was interned from a
+/// name starting with [`METAVAR_PREFIX`].
+///
+/// Metavariables stand for arbitrary subtrees in
+/// [`match_pattern`](crate::pattern::match_pattern).
+pub fn is_metavar(symbol: Symbol) -> bool {
+    symbol.to_usize() >= METAVAR_OFFSET
+}
+
+/// The symbol the interner itself issued for `symbol`'s name.
+fn interned(symbol: Symbol) -> Option<Symbol> {
+    if is_metavar(symbol) {
+        Symbol::try_from_usize(symbol.to_usize() - METAVAR_OFFSET)
+    } else {
+        Some(symbol)
+    }
+}
+
+/// Tag a freshly interned `symbol` as a metavariable if its name says so.
+fn tagged(name: &str, symbol: Symbol) -> Symbol {
+    if name.starts_with(METAVAR_PREFIX) {
+        Symbol::try_from_usize(symbol.to_usize() + METAVAR_OFFSET)
+            .expect("too many interned symbols")
+    } else {
+        symbol
+    }
+}
+
 /// A table for interning variable symbols.
 ///
 /// Use a single `SymbolTable` throughout your application to ensure
@@ -51,20 +88,22 @@ impl SymbolTable {
     /// Intern a string, returning its symbol.
     ///
     /// If the string has been interned before, returns the existing symbol.
+    /// Names starting with [`METAVAR_PREFIX`] give metavariables.
     pub fn intern(&mut self, s: &str) -> Symbol {
-        self.interner.get_or_intern(s)
+        tagged(s, self.interner.get_or_intern(s))
     }
 
     /// Get the string for a symbol, if it exists.
     pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
-        self.interner.resolve(symbol)
+        self.interner
+            .resolve(interned(symbol)?)
+            // A metavariable symbol must name a metavariable
+            .filter(|name| name.starts_with(METAVAR_PREFIX) == is_metavar(symbol))
     }
 
     /// Get the string for a symbol, panicking if not found.
     pub fn resolve_unchecked(&self, symbol: Symbol) -> &str {
-        self.interner
-            .resolve(symbol)
-            .expect("Symbol not found in table")
+        self.resolve(symbol).expect("Symbol not found in table")
     }
 
     /// Check if a string has been interned.
@@ -74,7 +113,7 @@ impl SymbolTable {
 
     /// Get the symbol for a string if it exists.
     pub fn get(&self, s: &str) -> Option<Symbol> {
-        self.interner.get(s)
+        self.interner.get(s).map(|symbol| tagged(s, symbol))
     }
 
     /// Get the number of interned symbols.
@@ -88,6 +127,37 @@ impl SymbolTable {
     }
 }
 
+#[cfg(feature = "global-interner")]
+thread_local! {
+    static GLOBAL_SYMBOLS: RefCell<SymbolTable> = RefCell::new(SymbolTable::new());
+}
+
+/// Run `f` with this thread's default symbol table.
+///
+/// Symbols from the default table are only meaningful on the thread that
+/// interned them. Libraries wanting isolation should keep an explicit
+/// [`SymbolTable`] instead.
+#[cfg(feature = "global-interner")]
+pub fn with_global_symbols<R>(f: impl FnOnce(&mut SymbolTable) -> R) -> R {
+    GLOBAL_SYMBOLS.with(|table| f(&mut table.borrow_mut()))
+}
+
+#[cfg(feature = "global-interner")]
+impl SymbolTable {
+    /// Intern `name` in this thread's default symbol table.
+    ///
+    /// # Example
+    ///
+    /// ```text
+    /// use mm_core::SymbolTable;
+    ///
+    /// assert_eq!(SymbolTable::intern_global("x"), SymbolTable::intern_global("x"));
+    /// ```
+    pub fn intern_global(name: &str) -> Symbol {
+        with_global_symbols(|table| table.intern(name))
+    }
+}
+
 /// Wrapper for serializing symbols with their string representation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableSymbol {
@@ -156,4 +226,30 @@ mod tests {
         assert!(table.contains("y"));
         assert!(!table.contains("z"));
     }
+
+    #[test]
+    fn test_metavariables() {
+        let mut table = SymbolTable::new();
+        let x = table.intern("x");
+        let a = table.intern("?a");
+
+        assert!(is_metavar(a));
+        assert!(!is_metavar(x));
+        assert_eq!(table.intern("?a"), a);
+        assert_eq!(table.get("?a"), Some(a));
+        assert_eq!(table.resolve(a), Some("?a"));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[cfg(feature = "global-interner")]
+    #[test]
+    fn test_global_interner() {
+        let x = SymbolTable::intern_global("x");
+        assert_eq!(x, SymbolTable::intern_global("x"));
+        assert_ne!(x, SymbolTable::intern_global("y"));
+        assert_eq!(
+            with_global_symbols(|t| t.resolve(x).map(str::to_string)),
+            Some("x".into())
+        );
+    }
 }
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Sanity checks for hand-built expression trees.
+//!
+//! The [`Expr`] constructors accept any shape, including ones no parser or
+//! rule should produce: a sum with no terms, a summation whose index never
+//! occurs in its body. [`Expr::validate`] catches these in tests and debug
+//! assertions.
+
+use crate::diff::children;
+use crate::{Expr, Symbol};
+use thiserror::Error;
+
+/// Why an expression tree is malformed.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// A `Sum` with no terms.
+    #[error("Sum has no terms")]
+    EmptySum,
+
+    /// A `Product` with no factors.
+    #[error("Product has no factors")]
+    EmptyProduct,
+
+    /// A `Piecewise` with no branches.
+    #[error("Piecewise has no branches")]
+    EmptyPiecewise,
+
+    /// A summation, product or quantifier whose bound variable does not
+    /// occur in its body.
+    #[error("Bound variable {0:?} does not occur in the body")]
+    UnusedBoundVar(Symbol),
+
+    /// A derivative with respect to a variable its operand does not contain.
+    #[error("Derivative with respect to {0:?}, which does not occur")]
+    MissingVar(Symbol),
+
+    /// A division or modulus by the literal zero.
+    #[error("Denominator is zero")]
+    ZeroDenominator,
+}
+
+impl Expr {
+    /// Check the tree for malformed nodes, reporting the first one found
+    /// in pre-order.
+    ///
+    /// This is about shape, not meaning: `1/x` passes even though it is
+    /// undefined at `x = 0`, while `1/0` does not.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            Expr::Sum(terms) if terms.is_empty() => return Err(ValidationError::EmptySum),
+            Expr::Product(factors) if factors.is_empty() => {
+                return Err(ValidationError::EmptyProduct)
+            }
+            Expr::Piecewise(branches) if branches.is_empty() => {
+                return Err(ValidationError::EmptyPiecewise)
+            }
+            Expr::Summation { var, body, .. }
+            | Expr::BigProduct { var, body, .. }
+            | Expr::ForAll { var, body, .. }
+            | Expr::Exists { var, body, .. }
+                if !body.free_vars().contains(var) =>
+            {
+                return Err(ValidationError::UnusedBoundVar(*var))
+            }
+            Expr::Derivative { expr, var } if !expr.free_vars().contains(var) => {
+                return Err(ValidationError::MissingVar(*var))
+            }
+            Expr::Div(_, denominator) | Expr::Mod(_, denominator) if denominator.is_zero() => {
+                return Err(ValidationError::ZeroDenominator)
+            }
+            _ => {}
+        }
+
+        children(self).into_iter().try_for_each(Expr::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SymbolTable, Term};
+
+    #[test]
+    fn test_validate() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let i = symbols.intern("i");
+
+        // Σ_{i=1}^{x} i² / (x + 1)
+        let valid = Expr::Div(
+            Box::new(Expr::Summation {
+                var: i,
+                from: Box::new(Expr::int(1)),
+                to: Box::new(Expr::Var(x)),
+                body: Box::new(Expr::Pow(Box::new(Expr::Var(i)), Box::new(Expr::int(2)))),
+            }),
+            Box::new(Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::int(1)))),
+        );
+        assert_eq!(valid.validate(), Ok(()));
+
+        // The error surfaces from deep inside the tree
+        let empty = Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::Sum(vec![])));
+        assert_eq!(empty.validate(), Err(ValidationError::EmptySum));
+
+        let unused = Expr::Summation {
+            var: i,
+            from: Box::new(Expr::int(1)),
+            to: Box::new(Expr::Var(x)),
+            body: Box::new(Expr::Var(x)),
+        };
+        assert_eq!(unused.validate(), Err(ValidationError::UnusedBoundVar(i)));
+
+        let derivative = Expr::Derivative {
+            expr: Box::new(Expr::Sum(vec![Term {
+                coeff: 2.into(),
+                expr: Expr::Var(x),
+            }])),
+            var: i,
+        };
+        assert_eq!(derivative.validate(), Err(ValidationError::MissingVar(i)));
+
+        let division = Expr::Div(Box::new(Expr::Var(x)), Box::new(Expr::int(0)));
+        assert_eq!(division.validate(), Err(ValidationError::ZeroDenominator));
+    }
+}
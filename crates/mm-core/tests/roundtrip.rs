@@ -0,0 +1,204 @@
+//! Round-trip property test: rendering an expression as ASCII and parsing
+//! it back must give the same expression, up to canonical form.
+//!
+//! Usage: cargo test --test roundtrip -p mm-core
+//!
+//! Failing cases are shrunk by proptest to a minimal expression, so a
+//! precedence slip such as printing `-(x^2)` as `-x^2` shows up as itself.
+//!
+//! The printer writes `x + -1` as `x - 1` and leaves `a + (b - c)`
+//! unparenthesized, so sums are compared as sorted lists of signed terms,
+//! and fractions read back as quotients are folded.
+
+use mm_core::parse::Parser;
+use mm_core::{Expr, LimitDirection, Symbol, SymbolTable};
+use proptest::prelude::*;
+
+/// Names the generated expressions draw their variables from.
+const VARIABLES: [&str; 3] = ["x", "y", "theta"];
+
+/// An arbitrary expression over `vars`, of bounded size.
+///
+/// Covers every variant the ASCII printer and the parser share; the
+/// relations, connectives, quantifiers and piecewise definitions have no
+/// ASCII syntax to read back.
+fn arb_expr(vars: Vec<Symbol>) -> impl Strategy<Value = Expr> {
+    let bound = vars.clone();
+    let leaf = prop_oneof![
+        (-20i64..=20).prop_map(Expr::int),
+        (-9i64..=9, 2i64..=9).prop_map(|(n, d)| Expr::frac(n, d)),
+        proptest::sample::select(vars).prop_map(Expr::Var),
+        Just(Expr::Pi),
+        Just(Expr::E),
+    ];
+
+    leaf.prop_recursive(4, 32, 3, move |inner| {
+        let var = proptest::sample::select(bound.clone());
+        let boxed = || inner.clone().prop_map(Box::new);
+        prop_oneof![
+            boxed().prop_map(Expr::Neg),
+            boxed().prop_map(Expr::Sqrt),
+            boxed().prop_map(Expr::Sin),
+            boxed().prop_map(Expr::Arctan),
+            boxed().prop_map(Expr::Cosh),
+            boxed().prop_map(Expr::Ln),
+            boxed().prop_map(Expr::Exp),
+            boxed().prop_map(Expr::Abs),
+            boxed().prop_map(Expr::Floor),
+            boxed().prop_map(Expr::Factorial),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Add(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Sub(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Mul(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Div(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Pow(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Mod(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::GCD(a, b)),
+            (boxed(), boxed()).prop_map(|(a, b)| Expr::Binomial(a, b)),
+            (boxed(), var.clone()).prop_map(|(expr, var)| Expr::Derivative { expr, var }),
+            (boxed(), var.clone()).prop_map(|(expr, var)| Expr::Integral { expr, var }),
+            (boxed(), var.clone(), boxed(), arb_direction()).prop_map(
+                |(expr, var, approaching, direction)| Expr::Limit {
+                    expr,
+                    var,
+                    approaching,
+                    direction,
+                }
+            ),
+            (var, boxed(), boxed(), boxed()).prop_map(|(var, from, to, body)| {
+                Expr::Summation {
+                    var,
+                    from,
+                    to,
+                    body,
+                }
+            }),
+        ]
+    })
+}
+
+fn arb_direction() -> impl Strategy<Value = LimitDirection> {
+    prop_oneof![
+        Just(LimitDirection::Left),
+        Just(LimitDirection::Right),
+        Just(LimitDirection::Both),
+    ]
+}
+
+/// Rewrite every chain of `+`, `-` and negation as a sum of its signed
+/// terms in sorted order, so spellings the printer does not tell apart
+/// compare equal.
+fn normalize(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Add(..) | Expr::Sub(..) | Expr::Neg(_) => {
+            let mut terms = Vec::new();
+            signed_terms(expr, false, &mut terms);
+            terms.sort();
+            let mut terms = terms.into_iter().map(|(negative, term)| match term {
+                Expr::Const(r) if negative => Expr::Const(-r),
+                term if negative => Expr::Neg(Box::new(term)),
+                term => term,
+            });
+            let first = terms.next().expect("a sum has a term");
+            terms.fold(first, |sum, term| Expr::Add(Box::new(sum), Box::new(term)))
+        }
+        Expr::Sqrt(a) => Expr::Sqrt(norm(a)),
+        Expr::Sin(a) => Expr::Sin(norm(a)),
+        Expr::Arctan(a) => Expr::Arctan(norm(a)),
+        Expr::Cosh(a) => Expr::Cosh(norm(a)),
+        Expr::Ln(a) => Expr::Ln(norm(a)),
+        Expr::Exp(a) => Expr::Exp(norm(a)),
+        Expr::Abs(a) => Expr::Abs(norm(a)),
+        Expr::Floor(a) => Expr::Floor(norm(a)),
+        Expr::Factorial(a) => Expr::Factorial(norm(a)),
+        Expr::Mul(a, b) => Expr::Mul(norm(a), norm(b)),
+        // `1/2` reads back as a quotient rather than a fraction
+        Expr::Div(a, b) => match (normalize(a), normalize(b)) {
+            (Expr::Const(n), Expr::Const(d)) if !d.is_zero() => Expr::Const(n / d),
+            (a, b) => Expr::Div(Box::new(a), Box::new(b)),
+        },
+        Expr::Pow(a, b) => Expr::Pow(norm(a), norm(b)),
+        Expr::Mod(a, b) => Expr::Mod(norm(a), norm(b)),
+        Expr::GCD(a, b) => Expr::GCD(norm(a), norm(b)),
+        Expr::Binomial(a, b) => Expr::Binomial(norm(a), norm(b)),
+        Expr::Derivative { expr, var } => Expr::Derivative {
+            expr: norm(expr),
+            var: *var,
+        },
+        Expr::Integral { expr, var } => Expr::Integral {
+            expr: norm(expr),
+            var: *var,
+        },
+        Expr::Limit {
+            expr,
+            var,
+            approaching,
+            direction,
+        } => Expr::Limit {
+            expr: norm(expr),
+            var: *var,
+            approaching: norm(approaching),
+            direction: *direction,
+        },
+        Expr::Summation {
+            var,
+            from,
+            to,
+            body,
+        } => Expr::Summation {
+            var: *var,
+            from: norm(from),
+            to: norm(to),
+            body: norm(body),
+        },
+        _ => expr.clone(),
+    }
+}
+
+fn norm(expr: &Expr) -> Box<Expr> {
+    Box::new(normalize(expr))
+}
+
+fn signed_terms(expr: &Expr, negative: bool, terms: &mut Vec<(bool, Expr)>) {
+    match expr {
+        Expr::Add(a, b) => {
+            signed_terms(a, negative, terms);
+            signed_terms(b, negative, terms);
+        }
+        Expr::Sub(a, b) => {
+            signed_terms(a, negative, terms);
+            signed_terms(b, !negative, terms);
+        }
+        Expr::Neg(a) => signed_terms(a, !negative, terms),
+        _ => match normalize(expr) {
+            Expr::Const(r) if r.is_negative() => terms.push((!negative, Expr::Const(-r))),
+            term => terms.push((negative, term)),
+        },
+    }
+}
+
+/// A symbol table holding [`VARIABLES`], and their symbols.
+fn variables() -> (SymbolTable, Vec<Symbol>) {
+    let mut symbols = SymbolTable::new();
+    let vars = VARIABLES.iter().map(|name| symbols.intern(name)).collect();
+    (symbols, vars)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn ascii_round_trip(expr in arb_expr(variables().1)) {
+        let (mut symbols, _) = variables();
+        let ascii = expr.to_ascii(&symbols);
+        let parsed = Parser::new(&mut symbols)
+            .parse(&ascii)
+            .map_err(|e| TestCaseError::fail(format!("{}: {:?}", ascii, e)))?;
+        prop_assert_eq!(
+            normalize(&parsed).canonicalize(),
+            normalize(&expr).canonicalize(),
+            "{} read back as {}",
+            ascii,
+            parsed.to_ascii(&symbols)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use mm_core::{parse::Parser, Expr, SymbolTable};
+use mm_core::{parse::Parser, Expr, LimitDirection, SymbolTable};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
@@ -137,12 +137,19 @@ fn expr_to_token_stream(
         Expr::Arcsin(e) => unary!(Arcsin, e),
         Expr::Arccos(e) => unary!(Arccos, e),
         Expr::Arctan(e) => unary!(Arctan, e),
+        Expr::Sinh(e) => unary!(Sinh, e),
+        Expr::Cosh(e) => unary!(Cosh, e),
+        Expr::Tanh(e) => unary!(Tanh, e),
+        Expr::Arcsinh(e) => unary!(Arcsinh, e),
+        Expr::Arccosh(e) => unary!(Arccosh, e),
+        Expr::Arctanh(e) => unary!(Arctanh, e),
         Expr::Ln(e) => unary!(Ln, e),
         Expr::Exp(e) => unary!(Exp, e),
         Expr::Sqrt(e) => unary!(Sqrt, e),
         Expr::Abs(e) => unary!(Abs, e),
         Expr::Pi => quote! { mm_core::Expr::Pi },
         Expr::E => quote! { mm_core::Expr::E },
+        Expr::Infinity { negative } => quote! { mm_core::Expr::Infinity { negative: #negative } },
         Expr::Sum(terms) => {
             let term_tokens = terms.iter().map(|term| {
                 let coeff_n = term.coeff.numer();
@@ -191,6 +198,29 @@ fn expr_to_token_stream(
                 }
             }
         }
+        Expr::Limit {
+            expr,
+            var,
+            approaching,
+            direction,
+        } => {
+            let inner = expr_to_token_stream(expr, runtime_symbol_table, temp_symbols);
+            let var_name = temp_symbols.resolve(*var).expect("Symbol not found");
+            let point = expr_to_token_stream(approaching, runtime_symbol_table, temp_symbols);
+            let direction = match direction {
+                LimitDirection::Left => quote! { mm_core::LimitDirection::Left },
+                LimitDirection::Right => quote! { mm_core::LimitDirection::Right },
+                LimitDirection::Both => quote! { mm_core::LimitDirection::Both },
+            };
+            quote! {
+                mm_core::Expr::Limit {
+                    expr: Box::new(#inner),
+                    var: #runtime_symbol_table.intern(#var_name),
+                    approaching: Box::new(#point),
+                    direction: #direction,
+                }
+            }
+        }
         Expr::Equation { lhs, rhs } => {
             let l = expr_to_token_stream(lhs, runtime_symbol_table, temp_symbols);
             let r = expr_to_token_stream(rhs, runtime_symbol_table, temp_symbols);
@@ -250,6 +280,14 @@ fn expr_to_token_stream(
                 }
             }
         }
+        Expr::Piecewise(branches) => {
+            let branches = branches.iter().map(|(condition, value)| {
+                let c = expr_to_token_stream(&condition.expr, runtime_symbol_table, temp_symbols);
+                let v = expr_to_token_stream(value, runtime_symbol_table, temp_symbols);
+                quote! { (mm_core::Constraint { expr: #c }, #v) }
+            });
+            quote! { mm_core::Expr::Piecewise(vec![#(#branches),*]) }
+        }
         // Quantifiers and logical connectives - fallback (not normally parsed from expr macro)
         Expr::ForAll { .. }
         | Expr::Exists { .. }
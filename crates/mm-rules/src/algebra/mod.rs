@@ -6,8 +6,9 @@
 
 //! Algebraic transformation rules.
 
+use crate::polynomial::{rational_roots, PolynomialNF};
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{numtheory, Constraint, Expr, ExprKind, Rational, Term};
 
 /// Get all algebra rules.
 pub fn algebra_rules() -> Vec<Rule> {
@@ -49,6 +50,13 @@ fn constant_fold() -> Rule {
         description: "Evaluate constant expressions: 2 + 3 → 5",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[
+            ExprKind::Add,
+            ExprKind::Sub,
+            ExprKind::Mul,
+            ExprKind::Div,
+            ExprKind::Pow,
+        ],
         is_applicable: |expr, _ctx| match expr {
             Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Div(a, b) => {
                 matches!(a.as_ref(), Expr::Const(_)) && matches!(b.as_ref(), Expr::Const(_))
@@ -69,6 +77,7 @@ fn constant_fold() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Const(*r1 + *r2),
                             justification: format!("{} + {} = {}", r1, r2, *r1 + *r2),
+                            condition: None,
                         }];
                     }
                 }
@@ -77,6 +86,7 @@ fn constant_fold() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Const(*r1 - *r2),
                             justification: format!("{} - {} = {}", r1, r2, *r1 - *r2),
+                            condition: None,
                         }];
                     }
                 }
@@ -85,6 +95,7 @@ fn constant_fold() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Const(*r1 * *r2),
                             justification: format!("{} × {} = {}", r1, r2, *r1 * *r2),
+                            condition: None,
                         }];
                     }
                 }
@@ -94,6 +105,7 @@ fn constant_fold() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Const(*r1 / *r2),
                                 justification: format!("{} ÷ {} = {}", r1, r2, *r1 / *r2),
+                                condition: None,
                             }];
                         }
                     }
@@ -101,10 +113,13 @@ fn constant_fold() -> Rule {
                 Expr::Pow(base, exp) => {
                     if let (Expr::Const(r), Expr::Const(e)) = (base.as_ref(), exp.as_ref()) {
                         if e.is_integer() && e.numer().abs() <= 10 {
-                            let result = r.pow(e.numer() as i32);
+                            let Some(result) = r.checked_pow(e.numer() as i32) else {
+                                return vec![];
+                            };
                             return vec![RuleApplication {
                                 result: Expr::Const(result),
                                 justification: format!("{}^{} = {}", r, e, result),
+                                condition: None,
                             }];
                         }
                     }
@@ -115,6 +130,7 @@ fn constant_fold() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -130,6 +146,7 @@ fn identity_add_zero() -> Rule {
         description: "Remove additive identity: x + 0 → x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             if let Expr::Add(a, b) = expr {
                 return a.is_zero() || b.is_zero();
@@ -142,12 +159,14 @@ fn identity_add_zero() -> Rule {
                     return vec![RuleApplication {
                         result: b.as_ref().clone(),
                         justification: "0 + x = x".to_string(),
+                        condition: None,
                     }];
                 }
                 if b.is_zero() {
                     return vec![RuleApplication {
                         result: a.as_ref().clone(),
                         justification: "x + 0 = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -155,6 +174,7 @@ fn identity_add_zero() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -170,6 +190,7 @@ fn identity_mul_one() -> Rule {
         description: "Remove multiplicative identity: x * 1 → x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(a, b) = expr {
                 return a.is_one() || b.is_one();
@@ -182,12 +203,14 @@ fn identity_mul_one() -> Rule {
                     return vec![RuleApplication {
                         result: b.as_ref().clone(),
                         justification: "1 × x = x".to_string(),
+                        condition: None,
                     }];
                 }
                 if b.is_one() {
                     return vec![RuleApplication {
                         result: a.as_ref().clone(),
                         justification: "x × 1 = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -195,6 +218,7 @@ fn identity_mul_one() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -210,6 +234,7 @@ fn zero_mul() -> Rule {
         description: "Multiply by zero: x * 0 → 0",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(a, b) = expr {
                 return a.is_zero() || b.is_zero();
@@ -222,6 +247,7 @@ fn zero_mul() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::int(0),
                         justification: "x × 0 = 0".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -229,6 +255,7 @@ fn zero_mul() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -244,6 +271,7 @@ fn collect_like_terms() -> Rule {
         description: "Collect like terms: ax + bx → (a+b)x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Check for pattern: (c1 * x) + (c2 * x) or x + x
             if let Expr::Add(a, b) = expr {
@@ -267,6 +295,7 @@ fn collect_like_terms() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Mul(Box::new(Expr::int(2)), a.clone()),
                         justification: "x + x = 2x".to_string(),
+                        condition: None,
                     }];
                 }
                 // General case
@@ -279,12 +308,14 @@ fn collect_like_terms() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::int(0),
                                 justification: format!("{}x + {}x = 0", coeff_a, coeff_b),
+                                condition: None,
                             }];
                         }
                         if new_coeff.is_one() {
                             return vec![RuleApplication {
                                 result: base_a.clone(),
                                 justification: format!("{}x + {}x = x", coeff_a, coeff_b),
+                                condition: None,
                             }];
                         }
                         return vec![RuleApplication {
@@ -293,6 +324,7 @@ fn collect_like_terms() -> Rule {
                                 Box::new(base_a.clone()),
                             ),
                             justification: format!("{}x + {}x = {}x", coeff_a, coeff_b, new_coeff),
+                            condition: None,
                         }];
                     }
                 }
@@ -301,6 +333,7 @@ fn collect_like_terms() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -336,6 +369,7 @@ fn distribute() -> Rule {
         description: "Distribute: a(b + c) → ab + ac",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(a, b) = expr {
                 // a * (b + c) or (a + b) * c
@@ -354,6 +388,7 @@ fn distribute() -> Rule {
                             Box::new(Expr::Mul(a.clone(), b2.clone())),
                         ),
                         justification: "a(b + c) = ab + ac".to_string(),
+                        condition: None,
                     }];
                 }
                 // (a + b) * c = a*c + b*c
@@ -364,6 +399,7 @@ fn distribute() -> Rule {
                             Box::new(Expr::Mul(a2.clone(), b.clone())),
                         ),
                         justification: "(a + b)c = ac + bc".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -371,6 +407,7 @@ fn distribute() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -386,6 +423,7 @@ fn factor_common() -> Rule {
         description: "Factor common: ab + ac → a(b + c)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Check for ab + ac pattern
             if let Expr::Add(left, right) = expr {
@@ -405,6 +443,7 @@ fn factor_common() -> Rule {
                                 Box::new(Expr::Add(b1.clone(), b2.clone())),
                             ),
                             justification: "ab + ac = a(b + c)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -413,6 +452,7 @@ fn factor_common() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -425,29 +465,24 @@ fn difference_of_squares() -> Rule {
         id: RuleId(8),
         name: "difference_of_squares",
         category: RuleCategory::Factoring,
-        description: "Factor difference of squares: a² - b² → (a+b)(a-b)",
+        description: "Factor difference of squares: a² - b² → (a-b)(a+b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sub],
         is_applicable: |expr, _ctx| {
-            // Check for a² - b² pattern
-            if let Expr::Sub(left, right) = expr {
-                let left_is_square =
-                    matches!(left.as_ref(), Expr::Pow(_, exp) if exp.as_ref() == &Expr::int(2));
-                let right_is_square =
-                    matches!(right.as_ref(), Expr::Pow(_, exp) if exp.as_ref() == &Expr::int(2));
-                return left_is_square && right_is_square;
-            }
-            false
+            // Check for a² - b² pattern; 4 counts as 2²
+            matches!(expr, Expr::Sub(left, right) if power_bases(left, right, 2).is_some())
         },
         apply: |expr, _ctx| {
             if let Expr::Sub(left, right) = expr {
-                if let (Expr::Pow(a, _), Expr::Pow(b, _)) = (left.as_ref(), right.as_ref()) {
+                if let Some((a, b)) = power_bases(left, right, 2) {
                     return vec![RuleApplication {
                         result: Expr::Mul(
-                            Box::new(Expr::Add(a.clone(), b.clone())),
-                            Box::new(Expr::Sub(a.clone(), b.clone())),
+                            Box::new(Expr::Sub(Box::new(a.clone()), Box::new(b.clone()))),
+                            Box::new(Expr::Add(Box::new(a), Box::new(b))),
                         ),
-                        justification: "a² - b² = (a + b)(a - b)".to_string(),
+                        justification: "a² - b² = (a - b)(a + b)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -455,6 +490,49 @@ fn difference_of_squares() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+/// The bases `a`, `b` of `left = aⁿ` and `right = bⁿ`.
+///
+/// A positive rational constant counts as an n-th power when its root is
+/// rational, so `x² - 4` reads as `x² - 2²`. At least one side must be a
+/// genuine power: two constants are arithmetic, not factoring.
+fn power_bases(left: &Expr, right: &Expr, n: u32) -> Option<(Expr, Expr)> {
+    let base = |e: &Expr| match e {
+        Expr::Pow(base, exp) if exp.as_ref() == &Expr::int(n as i64) => Some(base.as_ref().clone()),
+        Expr::Const(c) if c.is_positive() => {
+            let root = |k: i64| {
+                let r = (k as f64).powf(1.0 / n as f64).round() as i64;
+                (r.checked_pow(n) == Some(k)).then_some(r)
+            };
+            Some(Expr::Const(Rational::new(
+                root(c.numer())?,
+                root(c.denom())?,
+            )))
+        }
+        _ => None,
+    };
+    if matches!((left, right), (Expr::Const(_), Expr::Const(_))) {
+        return None;
+    }
+    Some((base(left)?, base(right)?))
+}
+
+/// `a·b`, with a constant factor written first.
+fn product_of(a: &Expr, b: &Expr) -> Expr {
+    match b {
+        Expr::Const(_) => Expr::Mul(Box::new(b.clone()), Box::new(a.clone())),
+        _ => Expr::Mul(Box::new(a.clone()), Box::new(b.clone())),
+    }
+}
+
+/// `a²`, folded when `a` is a constant.
+fn square_of(a: &Expr) -> Expr {
+    match a {
+        Expr::Const(c) => Expr::Const(*c * *c),
+        _ => Expr::Pow(Box::new(a.clone()), Box::new(Expr::int(2))),
     }
 }
 
@@ -470,6 +548,7 @@ fn perfect_square_sum() -> Rule {
         description: "Factor perfect square: a² + 2ab + b² → (a + b)²",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Match a² + 2ab + b² pattern
             if let Expr::Add(left, right) = expr {
@@ -504,6 +583,7 @@ fn perfect_square_sum() -> Rule {
                                                 Box::new(Expr::Const(Rational::from(2))),
                                             ),
                                             justification: "a² + 2ab + b² = (a + b)²".to_string(),
+                                            condition: None,
                                         }];
                                     }
                                 }
@@ -516,6 +596,7 @@ fn perfect_square_sum() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -531,6 +612,7 @@ fn perfect_square_diff() -> Rule {
         description: "Factor perfect square: a² - 2ab + b² → (a - b)²",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Sub],
         is_applicable: |_expr, _ctx| {
             // This requires more complex pattern matching
             false
@@ -541,6 +623,7 @@ fn perfect_square_diff() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -556,12 +639,14 @@ fn power_of_one() -> Rule {
         description: "x^1 = x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(r) if r.numer() == 1 && r.denom() == 1)),
         apply: |expr, _ctx| {
             if let Expr::Pow(base, _) = expr {
                 vec![RuleApplication {
                     result: base.as_ref().clone(),
                     justification: "x^1 = x".to_string(),
+                    condition: None,
                 }]
             } else {
                 vec![]
@@ -569,6 +654,7 @@ fn power_of_one() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -584,12 +670,14 @@ fn power_of_zero() -> Rule {
         description: "x^0 = 1 (where x ≠ 0)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(r) if r.is_zero())),
         apply: |expr, _ctx| {
             if let Expr::Pow(_, _) = expr {
                 vec![RuleApplication {
                     result: Expr::int(1),
                     justification: "x^0 = 1".to_string(),
+                    condition: None,
                 }]
             } else {
                 vec![]
@@ -597,6 +685,7 @@ fn power_of_zero() -> Rule {
         },
         reversible: false, // Not reversible - we lose base info
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -612,6 +701,7 @@ fn power_add() -> Rule {
         description: "x^a * x^b = x^(a+b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(left, right) = expr {
                 // Check if both are powers with same base
@@ -635,6 +725,7 @@ fn power_add() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Pow(base1.clone(), Box::new(new_exp)),
                             justification: "x^a * x^b = x^(a+b)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -643,6 +734,7 @@ fn power_add() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -658,6 +750,7 @@ fn power_mul() -> Rule {
         description: "(x^a)^b = x^(a*b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(inner, _) if matches!(inner.as_ref(), Expr::Pow(_, _))),
         apply: |expr, _ctx| {
             if let Expr::Pow(inner, outer_exp) = expr {
@@ -669,6 +762,7 @@ fn power_mul() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Pow(base.clone(), Box::new(new_exp)),
                         justification: "(x^a)^b = x^(a*b)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -676,6 +770,7 @@ fn power_mul() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -691,6 +786,7 @@ fn binomial_expand() -> Rule {
         description: "Expand (a + b)² → a² + 2ab + b²",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match (something)^2 where something is an Add
             if let Expr::Pow(base, exp) = expr {
@@ -723,6 +819,7 @@ fn binomial_expand() -> Rule {
                             return vec![RuleApplication {
                                 result,
                                 justification: "(a + b)² = a² + 2ab + b²".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -732,6 +829,7 @@ fn binomial_expand() -> Rule {
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -747,6 +845,7 @@ fn binomial_expand_diff() -> Rule {
         description: "Expand (a - b)² → a² - 2ab + b²",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(base, exp) = expr {
                 if let Expr::Const(e) = exp.as_ref() {
@@ -778,6 +877,7 @@ fn binomial_expand_diff() -> Rule {
                             return vec![RuleApplication {
                                 result,
                                 justification: "(a - b)² = a² - 2ab + b²".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -787,6 +887,7 @@ fn binomial_expand_diff() -> Rule {
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -802,6 +903,7 @@ fn sub_same() -> Rule {
         description: "x - x = 0",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sub],
         is_applicable: |expr, _ctx| {
             if let Expr::Sub(a, b) = expr {
                 return a == b;
@@ -814,6 +916,7 @@ fn sub_same() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Const(Rational::from(0)),
                         justification: "x - x = 0".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -821,6 +924,7 @@ fn sub_same() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -842,9 +946,14 @@ pub fn advanced_algebra_rules() -> Vec<Rule> {
         fractional_distribute(),
         // Double negative
         double_negative(),
+        fold_signs(),
+        // Nested absolute values
+        abs_strip_sign(),
+        abs_even_power(),
         // Binomial identities - NOW ENABLED
         binomial_square_expand(),
         binomial_cube_expand(),
+        binomial_theorem(),
         // Subtraction to addition - NOW ENABLED
         sub_to_add(),
         // Division to multiplication - NOW ENABLED
@@ -861,32 +970,26 @@ fn sum_of_cubes() -> Rule {
         description: "a³ + b³ = (a+b)(a² - ab + b²)",
         domains: &[Domain::Algebra],
         requires: &[],
-        is_applicable: |expr, _ctx| {
-            if let Expr::Add(left, right) = expr {
-                if let (Expr::Pow(_, exp1), Expr::Pow(_, exp2)) = (left.as_ref(), right.as_ref()) {
-                    if let (Expr::Const(e1), Expr::Const(e2)) = (exp1.as_ref(), exp2.as_ref()) {
-                        return *e1 == Rational::from_integer(3)
-                            && *e2 == Rational::from_integer(3);
-                    }
-                }
-            }
-            false
-        },
+        roots: &[ExprKind::Add],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::Add(left, right) if power_bases(left, right, 3).is_some()),
         apply: |expr, _ctx| {
             if let Expr::Add(left, right) = expr {
-                if let (Expr::Pow(a, _), Expr::Pow(b, _)) = (left.as_ref(), right.as_ref()) {
+                if let Some((a, b)) = power_bases(left, right, 3) {
                     // (a+b)(a² - ab + b²)
-                    let a_plus_b = Expr::Add(a.clone(), b.clone());
-                    let a_sq = Expr::Pow(a.clone(), Box::new(Expr::int(2)));
-                    let ab = Expr::Mul(a.clone(), b.clone());
-                    let b_sq = Expr::Pow(b.clone(), Box::new(Expr::int(2)));
                     let second_factor = Expr::Add(
-                        Box::new(Expr::Sub(Box::new(a_sq), Box::new(ab))),
-                        Box::new(b_sq),
+                        Box::new(Expr::Sub(
+                            Box::new(square_of(&a)),
+                            Box::new(product_of(&a, &b)),
+                        )),
+                        Box::new(square_of(&b)),
                     );
                     return vec![RuleApplication {
-                        result: Expr::Mul(Box::new(a_plus_b), Box::new(second_factor)),
+                        result: Expr::Mul(
+                            Box::new(Expr::Add(Box::new(a), Box::new(b))),
+                            Box::new(second_factor),
+                        ),
                         justification: "a³ + b³ = (a+b)(a² - ab + b²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -894,6 +997,7 @@ fn sum_of_cubes() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -906,32 +1010,26 @@ fn diff_of_cubes() -> Rule {
         description: "a³ - b³ = (a-b)(a² + ab + b²)",
         domains: &[Domain::Algebra],
         requires: &[],
-        is_applicable: |expr, _ctx| {
-            if let Expr::Sub(left, right) = expr {
-                if let (Expr::Pow(_, exp1), Expr::Pow(_, exp2)) = (left.as_ref(), right.as_ref()) {
-                    if let (Expr::Const(e1), Expr::Const(e2)) = (exp1.as_ref(), exp2.as_ref()) {
-                        return *e1 == Rational::from_integer(3)
-                            && *e2 == Rational::from_integer(3);
-                    }
-                }
-            }
-            false
-        },
+        roots: &[ExprKind::Sub],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(left, right) if power_bases(left, right, 3).is_some()),
         apply: |expr, _ctx| {
             if let Expr::Sub(left, right) = expr {
-                if let (Expr::Pow(a, _), Expr::Pow(b, _)) = (left.as_ref(), right.as_ref()) {
+                if let Some((a, b)) = power_bases(left, right, 3) {
                     // (a-b)(a² + ab + b²)
-                    let a_minus_b = Expr::Sub(a.clone(), b.clone());
-                    let a_sq = Expr::Pow(a.clone(), Box::new(Expr::int(2)));
-                    let ab = Expr::Mul(a.clone(), b.clone());
-                    let b_sq = Expr::Pow(b.clone(), Box::new(Expr::int(2)));
                     let second_factor = Expr::Add(
-                        Box::new(Expr::Add(Box::new(a_sq), Box::new(ab))),
-                        Box::new(b_sq),
+                        Box::new(Expr::Add(
+                            Box::new(square_of(&a)),
+                            Box::new(product_of(&a, &b)),
+                        )),
+                        Box::new(square_of(&b)),
                     );
                     return vec![RuleApplication {
-                        result: Expr::Mul(Box::new(a_minus_b), Box::new(second_factor)),
+                        result: Expr::Mul(
+                            Box::new(Expr::Sub(Box::new(a), Box::new(b))),
+                            Box::new(second_factor),
+                        ),
                         justification: "a³ - b³ = (a-b)(a² + ab + b²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -939,6 +1037,7 @@ fn diff_of_cubes() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -951,6 +1050,7 @@ fn sophie_germain() -> Rule {
         description: "a⁴ + 4b⁴ = (a² + 2b² + 2ab)(a² + 2b² - 2ab)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Match a⁴ + 4b⁴
             if let Expr::Add(left, right) = expr {
@@ -999,6 +1099,7 @@ fn sophie_germain() -> Rule {
                                 result: Expr::Mul(Box::new(factor1), Box::new(factor2)),
                                 justification: "a⁴ + 4b⁴ = (a² + 2b² + 2ab)(a² + 2b² - 2ab)"
                                     .to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1008,6 +1109,7 @@ fn sophie_germain() -> Rule {
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1020,6 +1122,7 @@ fn binomial_square_expand() -> Rule {
         description: "(a+b)² = a² + 2ab + b²",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(base, exp) = expr {
                 if let Expr::Const(e) = exp.as_ref() {
@@ -1046,6 +1149,7 @@ fn binomial_square_expand() -> Rule {
                             Box::new(b_sq),
                         ),
                         justification: "(a+b)² = a² + 2ab + b²".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1053,6 +1157,7 @@ fn binomial_square_expand() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1065,6 +1170,7 @@ fn binomial_cube_expand() -> Rule {
         description: "(a+b)³ = a³ + 3a²b + 3ab² + b³",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(base, exp) = expr {
                 if let Expr::Const(e) = exp.as_ref() {
@@ -1102,6 +1208,7 @@ fn binomial_cube_expand() -> Rule {
                             Box::new(b_cubed),
                         ),
                         justification: "(a+b)³ = a³ + 3a²b + 3ab² + b³".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1109,10 +1216,91 @@ fn binomial_cube_expand() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
-// x^a / x^b = x^(a-b)
+// (a+b)^n = Σ C(n,k) a^(n-k) b^k
+fn binomial_theorem() -> Rule {
+    Rule {
+        id: RuleId(311),
+        name: "binomial_theorem",
+        category: RuleCategory::Expansion,
+        description: "(a+b)^n = Σ C(n,k) a^(n-k) b^k",
+        domains: &[Domain::Algebra],
+        requires: &[],
+        roots: &[ExprKind::Pow],
+        is_applicable: |expr, ctx| binomial_power(expr, ctx.max_expand_power).is_some(),
+        apply: |expr, ctx| {
+            let Some((a, b, negate, n)) = binomial_power(expr, ctx.max_expand_power) else {
+                return vec![];
+            };
+            let mut terms = Vec::new();
+            for k in 0..=n {
+                let Some(c) = numtheory::binomial(n as u64, k as u64) else {
+                    return vec![];
+                };
+                let mut coeff = Rational::from_integer(c as i64);
+                // (a - b)^n alternates in sign
+                if negate && k % 2 == 1 {
+                    coeff = -coeff;
+                }
+                let factors: Vec<Expr> = [(a, n - k), (b, k)]
+                    .into_iter()
+                    .filter(|&(_, e)| e > 0)
+                    .map(|(base, e)| power(base.clone(), e as i64))
+                    .collect();
+                let expr = factors
+                    .into_iter()
+                    .reduce(|x, y| Expr::Mul(Box::new(x), Box::new(y)))
+                    .unwrap_or(Expr::int(1));
+                terms.push(Term { coeff, expr });
+            }
+            vec![RuleApplication {
+                result: Expr::Sum(terms),
+                justification: format!(
+                    "Binomial theorem: (a+b)^{} = Σ C({},k) a^({}-k) b^k",
+                    n, n, n
+                ),
+                condition: None,
+            }]
+        },
+        reversible: true,
+        cost: 4,
+        ..Default::default()
+    }
+}
+
+/// Match `(a ± b)^n` with a constant integer `2 ≤ n ≤ max_power`, returning
+/// `(a, b, is_difference, n)`.
+fn binomial_power(expr: &Expr, max_power: u32) -> Option<(&Expr, &Expr, bool, u32)> {
+    let Expr::Pow(base, exp) = expr else {
+        return None;
+    };
+    let Expr::Const(e) = exp.as_ref() else {
+        return None;
+    };
+    if !e.is_integer() || e.numer() < 2 || e.numer() > max_power as i64 {
+        return None;
+    }
+    let n = e.numer() as u32;
+    match base.as_ref() {
+        Expr::Add(a, b) => Some((a, b, false, n)),
+        Expr::Sub(a, b) => Some((a, b, true, n)),
+        _ => None,
+    }
+}
+
+/// Split `x^a` into `(x, a)`, reading a bare `x` as `x^1`.
+fn as_power(expr: &Expr) -> Option<(&Expr, Expr)> {
+    match expr {
+        Expr::Pow(base, exp) => Some((base, exp.as_ref().clone())),
+        Expr::Const(_) => None,
+        _ => Some((expr, Expr::int(1))),
+    }
+}
+
+// x^a / x^b = x^(a-b), also x^a / x and x / x^b
 fn power_subtract() -> Rule {
     Rule {
         id: RuleId(305),
@@ -1121,21 +1309,38 @@ fn power_subtract() -> Rule {
         description: "x^a / x^b = x^(a-b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             if let Expr::Div(num, den) = expr {
-                if let (Expr::Pow(base1, _), Expr::Pow(base2, _)) = (num.as_ref(), den.as_ref()) {
-                    return base1 == base2;
-                }
+                let (Some((base1, _)), Some((base2, _))) = (as_power(num), as_power(den)) else {
+                    return false;
+                };
+                // Leave x/x to the x/x = 1 rule
+                return base1 == base2 && (num != den);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Div(num, den) = expr {
-                if let (Expr::Pow(base, exp1), Expr::Pow(_, exp2)) = (num.as_ref(), den.as_ref()) {
-                    let new_exp = Expr::Sub(exp1.clone(), exp2.clone());
+                if let (Some((base, exp1)), Some((_, exp2))) = (as_power(num), as_power(den)) {
+                    let result = match (&exp1, &exp2) {
+                        (Expr::Const(a), Expr::Const(b))
+                            if *a - *b == Rational::from_integer(1) =>
+                        {
+                            base.clone()
+                        }
+                        (Expr::Const(a), Expr::Const(b)) => {
+                            Expr::Pow(Box::new(base.clone()), Box::new(Expr::Const(*a - *b)))
+                        }
+                        _ => Expr::Pow(
+                            Box::new(base.clone()),
+                            Box::new(Expr::Sub(Box::new(exp1), Box::new(exp2))),
+                        ),
+                    };
                     return vec![RuleApplication {
-                        result: Expr::Pow(base.clone(), Box::new(new_exp)),
+                        result,
                         justification: "x^a / x^b = x^(a-b)".to_string(),
+                        condition: ctx.side_condition(Constraint::nonzero(base.clone())),
                     }];
                 }
             }
@@ -1143,6 +1348,7 @@ fn power_subtract() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1155,6 +1361,7 @@ fn negative_exponent() -> Rule {
         description: "x^(-n) = 1/x^n",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(_, exp) = expr {
                 if let Expr::Neg(_) = exp.as_ref() {
@@ -1185,12 +1392,14 @@ fn negative_exponent() -> Rule {
                         Box::new(Expr::Pow(base.clone(), positive_exp)),
                     ),
                     justification: "x^(-n) = 1/x^n".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1203,6 +1412,7 @@ fn fractional_distribute() -> Rule {
         description: "(a/b)^n = a^n / b^n",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(base, _) = expr {
                 return matches!(base.as_ref(), Expr::Div(_, _));
@@ -1217,6 +1427,7 @@ fn fractional_distribute() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(num_pow), Box::new(den_pow)),
                         justification: "(a/b)^n = a^n / b^n".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1224,6 +1435,7 @@ fn fractional_distribute() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1236,6 +1448,7 @@ fn double_negative() -> Rule {
         description: "--x = x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Neg],
         is_applicable: |expr, _ctx| {
             if let Expr::Neg(inner) = expr {
                 return matches!(inner.as_ref(), Expr::Neg(_));
@@ -1248,6 +1461,7 @@ fn double_negative() -> Rule {
                     return vec![RuleApplication {
                         result: x.as_ref().clone(),
                         justification: "--x = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1255,6 +1469,130 @@ fn double_negative() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
+    }
+}
+
+// -(a · -b) = a · b: cancel signs in pairs through products and quotients
+fn fold_signs() -> Rule {
+    Rule {
+        id: RuleId(371),
+        name: "fold_signs",
+        category: RuleCategory::Simplification,
+        description: "-(a · -b) = a · b",
+        domains: &[Domain::Algebra],
+        requires: &[],
+        roots: &[ExprKind::Neg, ExprKind::Mul, ExprKind::Div],
+        is_applicable: |expr, _ctx| {
+            let mut signs = 0;
+            strip_signs(expr, &mut signs);
+            signs >= 2
+        },
+        apply: |expr, _ctx| {
+            let mut signs = 0;
+            let unsigned = strip_signs(expr, &mut signs);
+            if signs < 2 {
+                return vec![];
+            }
+            let result = if signs % 2 == 1 {
+                Expr::Neg(Box::new(unsigned))
+            } else {
+                unsigned
+            };
+            vec![RuleApplication {
+                result,
+                justification: format!("{} negations cancel in pairs", signs),
+                condition: None,
+            }]
+        },
+        reversible: true,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+/// `expr` with every negation reachable through `-`, `·` and `/` removed,
+/// counting them in `signs`.
+fn strip_signs(expr: &Expr, signs: &mut usize) -> Expr {
+    match expr {
+        Expr::Neg(inner) => {
+            *signs += 1;
+            strip_signs(inner, signs)
+        }
+        Expr::Mul(a, b) => Expr::Mul(
+            Box::new(strip_signs(a, signs)),
+            Box::new(strip_signs(b, signs)),
+        ),
+        Expr::Div(a, b) => Expr::Div(
+            Box::new(strip_signs(a, signs)),
+            Box::new(strip_signs(b, signs)),
+        ),
+        _ => expr.clone(),
+    }
+}
+
+// |-|-x|| = |x|: signs and absolute values under an absolute value
+fn abs_strip_sign() -> Rule {
+    Rule {
+        id: RuleId(372),
+        name: "abs_strip_sign",
+        category: RuleCategory::Simplification,
+        description: "|-a| = ||a|| = |a|",
+        domains: &[Domain::Algebra],
+        requires: &[],
+        roots: &[ExprKind::Abs],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::Abs(inner) if matches!(inner.as_ref(), Expr::Neg(_) | Expr::Abs(_))),
+        apply: |expr, _ctx| {
+            let Expr::Abs(mut inner) = expr.clone() else {
+                return vec![];
+            };
+            while let Expr::Neg(a) | Expr::Abs(a) = *inner {
+                inner = a;
+            }
+            vec![RuleApplication {
+                result: Expr::Abs(inner),
+                justification: "|-a| = ||a|| = |a|".to_string(),
+                condition: None,
+            }]
+        },
+        reversible: true,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+// |a²ⁿ| = a²ⁿ
+fn abs_even_power() -> Rule {
+    Rule {
+        id: RuleId(373),
+        name: "abs_even_power",
+        category: RuleCategory::Simplification,
+        description: "|a²ⁿ| = a²ⁿ: even powers are never negative",
+        domains: &[Domain::Algebra],
+        requires: &[],
+        roots: &[ExprKind::Abs],
+        is_applicable: |expr, _ctx| {
+            if let Expr::Abs(inner) = expr {
+                if let Expr::Pow(_, exp) = inner.as_ref() {
+                    return matches!(exp.as_ref(), Expr::Const(n)
+                        if n.is_integer() && !n.is_zero() && n.numer() % 2 == 0);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Abs(inner) = expr {
+                return vec![RuleApplication {
+                    result: inner.as_ref().clone(),
+                    justification: "|a²ⁿ| = a²ⁿ: even powers are never negative".to_string(),
+                    condition: None,
+                }];
+            }
+            vec![]
+        },
+        reversible: true,
+        cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1267,18 +1605,21 @@ fn sub_to_add() -> Rule {
         description: "a - b = a + (-b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sub],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Sub(a, b) = expr {
                 return vec![RuleApplication {
                     result: Expr::Add(a.clone(), Box::new(Expr::Neg(b.clone()))),
                     justification: "a - b = a + (-b)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1291,6 +1632,7 @@ fn div_to_mul() -> Rule {
         description: "a / b = a * (1/b)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Div(a, b) = expr {
@@ -1298,12 +1640,14 @@ fn div_to_mul() -> Rule {
                 return vec![RuleApplication {
                     result: Expr::Mul(a.clone(), Box::new(reciprocal)),
                     justification: "a / b = a * (1/b)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1332,6 +1676,7 @@ pub fn phase4_algebra_rules() -> Vec<Rule> {
         sqrt_square(),
         cube_root_cube(),
         nth_root_power(),
+        radical_extract(),
         rationalize_denominator(),
         conjugate_multiply(),
         sum_of_cubes_factor(),
@@ -1376,10 +1721,12 @@ fn log_product() -> Rule {
         description: "log(ab) = log(a) + log(b)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Logarithm],
+        roots: &[ExprKind::Ln],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1392,10 +1739,12 @@ fn log_quotient() -> Rule {
         description: "log(a/b) = log(a) - log(b)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Logarithm],
+        roots: &[ExprKind::Ln],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1408,18 +1757,20 @@ fn log_power() -> Rule {
         description: "log(a^n) = n*log(a)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Ln],
         is_applicable: |expr, _| {
             if let Expr::Ln(inner) = expr {
                 return matches!(inner.as_ref(), Expr::Pow(_, _));
             }
             false
         },
-        apply: |expr, _| {
+        apply: |expr, ctx| {
             if let Expr::Ln(inner) = expr {
                 if let Expr::Pow(base, exp) = inner.as_ref() {
                     return vec![RuleApplication {
                         result: Expr::Mul(exp.clone(), Box::new(Expr::Ln(base.clone()))),
                         justification: "log(a^n) = n*log(a)".to_string(),
+                        condition: ctx.side_condition(Constraint::positive(base.as_ref().clone())),
                     }];
                 }
             }
@@ -1427,6 +1778,7 @@ fn log_power() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1439,10 +1791,12 @@ fn log_base_change() -> Rule {
         description: "log_b(a) = ln(a)/ln(b)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Logarithm],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1455,15 +1809,18 @@ fn log_one() -> Rule {
         description: "ln(1) = 0",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Ln],
         is_applicable: |expr, _| matches!(expr, Expr::Ln(inner) if matches!(inner.as_ref(), Expr::Const(r) if r.numer() == 1 && r.denom() == 1)),
         apply: |_, _| {
             vec![RuleApplication {
                 result: Expr::int(0),
                 justification: "ln(1) = 0".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1476,15 +1833,18 @@ fn log_same_base() -> Rule {
         description: "ln(e) = 1",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Ln],
         is_applicable: |expr, _| matches!(expr, Expr::Ln(inner) if matches!(inner.as_ref(), Expr::E)),
         apply: |_, _| {
             vec![RuleApplication {
                 result: Expr::int(1),
                 justification: "ln(e) = 1".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1497,10 +1857,12 @@ fn exp_product() -> Rule {
         description: "e^a * e^b = e^(a+b)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Exponential],
+        roots: &[ExprKind::Mul],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1513,10 +1875,12 @@ fn exp_quotient() -> Rule {
         description: "e^a / e^b = e^(a-b)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Exponential],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1529,10 +1893,12 @@ fn exp_power() -> Rule {
         description: "(e^a)^b = e^(ab)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Exponential],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1545,15 +1911,18 @@ fn exp_zero() -> Rule {
         description: "e^0 = 1",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Exp],
         is_applicable: |expr, _| matches!(expr, Expr::Exp(inner) if matches!(inner.as_ref(), Expr::Const(r) if r.is_zero())),
         apply: |_, _| {
             vec![RuleApplication {
                 result: Expr::int(1),
                 justification: "e^0 = 1".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1566,15 +1935,18 @@ fn exp_one() -> Rule {
         description: "e^1 = e",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Exp],
         is_applicable: |expr, _| matches!(expr, Expr::Exp(inner) if matches!(inner.as_ref(), Expr::Const(r) if r.numer() == 1 && r.denom() == 1)),
         apply: |_, _| {
             vec![RuleApplication {
                 result: Expr::E,
                 justification: "e^1 = e".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1587,13 +1959,15 @@ fn exp_ln() -> Rule {
         description: "e^(ln(x)) = x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Exp],
         is_applicable: |expr, _| matches!(expr, Expr::Exp(inner) if matches!(inner.as_ref(), Expr::Ln(_))),
-        apply: |expr, _| {
+        apply: |expr, ctx| {
             if let Expr::Exp(inner) = expr {
                 if let Expr::Ln(x) = inner.as_ref() {
                     return vec![RuleApplication {
                         result: (**x).clone(),
                         justification: "e^(ln(x)) = x".to_string(),
+                        condition: ctx.side_condition(Constraint::positive((**x).clone())),
                     }];
                 }
             }
@@ -1601,6 +1975,7 @@ fn exp_ln() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1613,6 +1988,7 @@ fn ln_exp() -> Rule {
         description: "ln(e^x) = x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Ln],
         is_applicable: |expr, _| matches!(expr, Expr::Ln(inner) if matches!(inner.as_ref(), Expr::Exp(_))),
         apply: |expr, _| {
             if let Expr::Ln(inner) = expr {
@@ -1620,6 +1996,7 @@ fn ln_exp() -> Rule {
                     return vec![RuleApplication {
                         result: (**x).clone(),
                         justification: "ln(e^x) = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1627,6 +2004,7 @@ fn ln_exp() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1639,6 +2017,7 @@ fn sqrt_product() -> Rule {
         description: "√(ab) = √a * √b",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sqrt],
         is_applicable: |expr, _| matches!(expr, Expr::Sqrt(inner) if matches!(inner.as_ref(), Expr::Mul(_, _))),
         apply: |expr, _| {
             if let Expr::Sqrt(inner) = expr {
@@ -1649,13 +2028,15 @@ fn sqrt_product() -> Rule {
                             Box::new(Expr::Sqrt(b.clone())),
                         ),
                         justification: "√(ab) = √a * √b".to_string(),
+                        condition: None,
                     }];
                 }
             }
             vec![]
         },
-        reversible: true,
+        reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1668,10 +2049,12 @@ fn sqrt_quotient() -> Rule {
         description: "√(a/b) = √a / √b",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sqrt],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1684,6 +2067,7 @@ fn sqrt_square() -> Rule {
         description: "√(x²) = |x|",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Sqrt],
         is_applicable: |expr, _| {
             if let Expr::Sqrt(inner) = expr {
                 if let Expr::Pow(_, exp) = inner.as_ref() {
@@ -1698,6 +2082,7 @@ fn sqrt_square() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Abs(base.clone()),
                         justification: "√(x²) = |x|".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1705,6 +2090,7 @@ fn sqrt_square() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1717,10 +2103,12 @@ fn cube_root_cube() -> Rule {
         description: "∛(x³) = x",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1733,13 +2121,184 @@ fn nth_root_power() -> Rule {
         description: "ⁿ√(xⁿ) = |x| (even n) or x (odd n)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
+/// [`RuleContext`](crate::RuleContext) metadata key: when set to `"true"`,
+/// radical extraction treats variables as nonnegative and writes `√(x²)` as
+/// `x` instead of `|x|`.
+pub const ASSUME_NONNEGATIVE: &str = "assume_nonnegative";
+
+// ⁿ√(aⁿb) = a·ⁿ√b
+fn radical_extract() -> Rule {
+    Rule {
+        id: RuleId(370),
+        name: "radical_extract",
+        category: RuleCategory::Simplification,
+        description: "ⁿ√(aⁿb) = a·ⁿ√b: pull perfect n-th powers out of a radical",
+        domains: &[Domain::Algebra],
+        requires: &[],
+        roots: &[ExprKind::Sqrt, ExprKind::Pow],
+        is_applicable: |expr, ctx| extract_radical(expr, assumes_nonnegative(ctx)).is_some(),
+        apply: |expr, ctx| match extract_radical(expr, assumes_nonnegative(ctx)) {
+            Some(result) => vec![RuleApplication {
+                result,
+                justification: "Extract perfect powers from the radical: ⁿ√(aⁿb) = a·ⁿ√b"
+                    .to_string(),
+                condition: None,
+            }],
+            None => vec![],
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+fn assumes_nonnegative(ctx: &crate::RuleContext) -> bool {
+    ctx.metadata
+        .get(ASSUME_NONNEGATIVE)
+        .is_some_and(|v| v == "true")
+}
+
+/// Split `√r` or `r^(1/n)` into its radicand and index.
+fn radical_parts(expr: &Expr) -> Option<(&Expr, i64)> {
+    match expr {
+        Expr::Sqrt(inner) => Some((inner, 2)),
+        Expr::Pow(base, exp) => match exp.as_ref() {
+            Expr::Const(r) if r.numer() == 1 && r.denom() >= 2 => Some((base, r.denom())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Flatten a radicand into an integer coefficient and `(base, exponent)` factors.
+fn radicand_factors(expr: &Expr, coeff: &mut i64, factors: &mut Vec<(Expr, i64)>) -> bool {
+    match expr {
+        Expr::Mul(a, b) => {
+            radicand_factors(a, coeff, factors) && radicand_factors(b, coeff, factors)
+        }
+        Expr::Const(r) if r.is_integer() => match coeff.checked_mul(r.numer()) {
+            Some(c) => {
+                *coeff = c;
+                true
+            }
+            None => false,
+        },
+        Expr::Const(_) => false,
+        Expr::Pow(base, exp) => match exp.as_ref() {
+            Expr::Const(k) if k.is_integer() && k.is_positive() => {
+                factors.push((base.as_ref().clone(), k.numer()));
+                true
+            }
+            _ => {
+                factors.push((expr.clone(), 1));
+                true
+            }
+        },
+        _ => {
+            factors.push((expr.clone(), 1));
+            true
+        }
+    }
+}
+
+fn power(base: Expr, exp: i64) -> Expr {
+    if exp == 1 {
+        base
+    } else {
+        Expr::Pow(Box::new(base), Box::new(Expr::int(exp)))
+    }
+}
+
+/// Pull perfect n-th powers out of a radical, or `None` if there are none.
+///
+/// Even roots put extracted symbolic factors inside `|·|` unless
+/// `nonnegative` is set.
+fn extract_radical(expr: &Expr, nonnegative: bool) -> Option<Expr> {
+    let (radicand, n) = radical_parts(expr)?;
+    let mut coeff = 1;
+    let mut factors = Vec::new();
+    if !radicand_factors(radicand, &mut coeff, &mut factors) || coeff == 0 {
+        return None;
+    }
+
+    // Odd roots of negatives are real: ∛(-8) = -2
+    let sign = if coeff < 0 {
+        if n % 2 == 0 {
+            return None;
+        }
+        coeff = coeff.checked_neg()?;
+        -1
+    } else {
+        1
+    };
+
+    let (mut outside_coeff, mut inside_coeff) = (1i64, 1i64);
+    for (p, e) in numtheory::factorize(coeff as u64) {
+        let (p, e) = (p as i64, i64::from(e));
+        outside_coeff *= p.pow((e / n) as u32);
+        inside_coeff *= p.pow((e % n) as u32);
+    }
+
+    let mut outside = Vec::new();
+    let mut inside = Vec::new();
+    for (base, k) in factors {
+        let (q, r) = (k / n, k % n);
+        if q > 0 {
+            let is_nonnegative_const = matches!(&base, Expr::Const(c) if !c.is_negative());
+            let needs_abs = n % 2 == 0 && q % 2 == 1 && !nonnegative && !is_nonnegative_const;
+            let extracted = if needs_abs {
+                Expr::Abs(Box::new(base.clone()))
+            } else {
+                base.clone()
+            };
+            outside.push(power(extracted, q));
+        }
+        if r > 0 {
+            inside.push(power(base, r));
+        }
+    }
+
+    if outside_coeff == 1 && outside.is_empty() && sign == 1 {
+        return None;
+    }
+
+    if inside_coeff != 1 {
+        inside.insert(0, Expr::int(inside_coeff));
+    }
+    let radical = inside
+        .into_iter()
+        .reduce(|a, b| Expr::Mul(Box::new(a), Box::new(b)))
+        .map(|radicand| {
+            if n == 2 {
+                Expr::Sqrt(Box::new(radicand))
+            } else {
+                Expr::Pow(
+                    Box::new(radicand),
+                    Box::new(Expr::Const(Rational::new(1, n))),
+                )
+            }
+        });
+
+    let coefficient = sign * outside_coeff;
+    if coefficient != 1 {
+        outside.insert(0, Expr::int(coefficient));
+    }
+    outside.extend(radical);
+    outside
+        .into_iter()
+        .reduce(|a, b| Expr::Mul(Box::new(a), Box::new(b)))
+        .or(Some(Expr::int(1)))
+}
+
 // 1/(a+b√c) * (a-b√c)/(a-b√c) = (a-b√c)/(a²-b²c)
 fn rationalize_denominator() -> Rule {
     Rule {
@@ -1749,10 +2308,12 @@ fn rationalize_denominator() -> Rule {
         description: "Rationalize denominator with conjugate",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1765,10 +2326,12 @@ fn conjugate_multiply() -> Rule {
         description: "(a+b)(a-b) = a² - b²",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Mul],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1781,10 +2344,12 @@ fn sum_of_cubes_factor() -> Rule {
         description: "a³ + b³ = (a+b)(a² - ab + b²)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1797,10 +2362,12 @@ fn diff_of_cubes_factor() -> Rule {
         description: "a³ - b³ = (a-b)(a² + ab + b²)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sub],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1813,10 +2380,12 @@ fn perfect_cube_sum() -> Rule {
         description: "(a+b)³ = a³ + 3a²b + 3ab² + b³",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1829,10 +2398,12 @@ fn perfect_cube_diff() -> Rule {
         description: "(a-b)³ = a³ - 3a²b + 3ab² - b³",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1845,10 +2416,12 @@ fn quadratic_complete_square() -> Rule {
         description: "Complete the square for quadratic",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1861,10 +2434,12 @@ fn vieta_sum() -> Rule {
         description: "Sum of roots = -b/a",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Equation],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1877,10 +2452,12 @@ fn vieta_product() -> Rule {
         description: "Product of roots = c/a",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Equation],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1893,11 +2470,70 @@ fn factor_quadratic() -> Rule {
         description: "Factor quadratic using roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
-        is_applicable: |_, _| false,
-        apply: |_, _| vec![],
+        roots: &[ExprKind::Add, ExprKind::Sub],
+        is_applicable: |expr, _ctx| quadratic_factors(expr).is_some(),
+        apply: |expr, _ctx| {
+            quadratic_factors(expr)
+                .map(|(result, roots)| RuleApplication {
+                    result,
+                    justification: format!("ax² + bx + c = a(x - r₁)(x - r₂) with roots {}", roots),
+                    condition: None,
+                })
+                .into_iter()
+                .collect()
+        },
         reversible: true,
         cost: 3,
-    }
+        ..Default::default()
+    }
+}
+
+/// Factor a quadratic in one variable with rational roots into linear
+/// factors, returning the product and its roots for the justification.
+///
+/// A root p/q gives the factor `qx - p`, so `2x² + 3x + 1` becomes
+/// `(2x + 1)(x + 1)` rather than `2(x + 1/2)(x + 1)`.
+fn quadratic_factors(expr: &Expr) -> Option<(Expr, String)> {
+    if !matches!(expr, Expr::Add(..) | Expr::Sub(..)) {
+        return None;
+    }
+    let [var] = expr.free_vars()[..] else {
+        return None;
+    };
+    let coeffs = PolynomialNF::from_expr(expr)?.coefficients(var)?;
+    if coeffs.len() != 3 {
+        return None;
+    }
+    let mut roots = rational_roots(&coeffs);
+    match roots.len() {
+        // Two rational roots, or one double root
+        1 => roots.push(roots[0]),
+        2 => roots.reverse(),
+        _ => return None,
+    }
+
+    let linear = |root: Rational| {
+        let (p, q) = (root.numer(), root.denom());
+        let x = match q {
+            1 => Expr::Var(var),
+            _ => Expr::Mul(Box::new(Expr::int(q)), Box::new(Expr::Var(var))),
+        };
+        match p {
+            0 => x,
+            p if p < 0 => Expr::Add(Box::new(x), Box::new(Expr::int(-p))),
+            p => Expr::Sub(Box::new(x), Box::new(Expr::int(p))),
+        }
+    };
+    let product = Expr::Mul(Box::new(linear(roots[0])), Box::new(linear(roots[1])));
+    let scale = coeffs[2] / Rational::from(roots[0].denom() * roots[1].denom());
+    let result = if scale.is_one() {
+        product
+    } else if scale == Rational::from(-1) {
+        Expr::Neg(Box::new(product))
+    } else {
+        Expr::Mul(Box::new(Expr::Const(scale)), Box::new(product))
+    };
+    Some((result, format!("{} and {}", roots[0], roots[1])))
 }
 
 // Rational root theorem test
@@ -1909,10 +2545,12 @@ fn rational_root_test() -> Rule {
         description: "Rational root theorem",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Equation],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1925,10 +2563,12 @@ fn synthetic_division() -> Rule {
         description: "Synthetic division",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1941,10 +2581,12 @@ fn polynomial_division() -> Rule {
         description: "Polynomial long division",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1957,10 +2599,12 @@ fn remainder_theorem() -> Rule {
         description: "Remainder theorem",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1973,10 +2617,12 @@ fn factor_theorem() -> Rule {
         description: "Factor theorem",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Equation],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1989,10 +2635,12 @@ fn bezout_identity() -> Rule {
         description: "Bezout's identity: gcd(a,b) = ax + by",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::GCD],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2005,10 +2653,12 @@ fn euclidean_division() -> Rule {
         description: "Euclidean division",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Div],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2021,10 +2671,12 @@ fn fraction_add() -> Rule {
         description: "a/b + c/d = (ad + bc)/bd",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2037,10 +2689,12 @@ fn fraction_mul() -> Rule {
         description: "(a/b) * (c/d) = (ac)/(bd)",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2053,10 +2707,55 @@ fn fraction_div() -> Rule {
         description: "(a/b) / (c/d) = (ad)/(bc)",
         domains: &[Domain::Algebra],
         requires: &[],
-        is_applicable: |_, _| false,
-        apply: |_, _| vec![],
+        roots: &[ExprKind::Div],
+        is_applicable: |expr, _| {
+            matches!(expr, Expr::Div(num, den)
+                if matches!(num.as_ref(), Expr::Div(_, _)) || matches!(den.as_ref(), Expr::Div(_, _)))
+        },
+        apply: |expr, ctx| {
+            if let Expr::Div(num, den) = expr {
+                // Treat a plain numerator or denominator x as x/1
+                let one = Expr::int(1);
+                let (a, b) = match num.as_ref() {
+                    Expr::Div(a, b) => (a.as_ref(), b.as_ref()),
+                    other => (other, &one),
+                };
+                let (c, d) = match den.as_ref() {
+                    Expr::Div(c, d) => (c.as_ref(), d.as_ref()),
+                    other => (other, &one),
+                };
+                let numer = mul_unless_one(a, d);
+                let denom = mul_unless_one(b, c);
+                let result = if denom.is_one() {
+                    numer
+                } else {
+                    Expr::Div(Box::new(numer), Box::new(denom))
+                };
+                // d leaves the denominator, so its zeros must not be lost
+                let condition =
+                    (!d.is_one() && !ctx.assume_generic).then(|| Constraint::nonzero(d.clone()));
+                return vec![RuleApplication {
+                    result,
+                    justification: "(a/b) / (c/d) = (ad)/(bc)".to_string(),
+                    condition,
+                }];
+            }
+            vec![]
+        },
         reversible: true,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+/// `a·b`, dropping a factor of 1.
+fn mul_unless_one(a: &Expr, b: &Expr) -> Expr {
+    if a.is_one() {
+        b.clone()
+    } else if b.is_one() {
+        a.clone()
+    } else {
+        Expr::Mul(Box::new(a.clone()), Box::new(b.clone()))
     }
 }
 
@@ -2069,10 +2768,12 @@ fn cross_multiply() -> Rule {
         description: "Cross multiply: a/b = c/d → ad = bc",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2085,10 +2786,12 @@ fn lcd_combine() -> Rule {
         description: "Combine fractions using LCD",
         domains: &[Domain::Algebra],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2101,10 +2804,12 @@ fn abs_nonnegative() -> Rule {
         description: "|x| ≥ 0 always",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2117,10 +2822,12 @@ fn abs_square() -> Rule {
         description: "|x|² = x²",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Pow],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2133,10 +2840,12 @@ fn triangle_inequality() -> Rule {
         description: "|a + b| ≤ |a| + |b|",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Lte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2149,10 +2858,12 @@ fn reverse_triangle() -> Rule {
         description: "||a| - |b|| ≤ |a - b|",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Lte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2165,10 +2876,12 @@ fn am_gm_2() -> Rule {
         description: "(a+b)/2 ≥ √(ab) for a,b ≥ 0",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2181,10 +2894,12 @@ fn am_gm_3() -> Rule {
         description: "(a+b+c)/3 ≥ ∛(abc) for a,b,c ≥ 0",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2197,10 +2912,12 @@ fn qm_am() -> Rule {
         description: "√((a²+b²)/2) ≥ (a+b)/2",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2213,10 +2930,12 @@ fn cauchy_schwarz_2() -> Rule {
         description: "(ab + cd)² ≤ (a²+c²)(b²+d²)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Lte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2229,10 +2948,12 @@ fn holders_inequality() -> Rule {
         description: "Holder's inequality",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Lte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2245,10 +2966,12 @@ fn minkowski_inequality() -> Rule {
         description: "Minkowski inequality",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Lte],
         is_applicable: |_, _| false,
         apply: |_, _| vec![],
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2271,6 +2994,100 @@ mod tests {
         let results = rule.apply(&expr, &ctx);
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].result, Expr::int(5));
+        // Folding constants holds everywhere
+        assert!(results[0].condition.is_none());
+    }
+
+    #[test]
+    fn test_binomial_theorem() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        let rule = binomial_theorem();
+        let ctx = RuleContext::default();
+        let var = || Box::new(Expr::Var(x));
+        let x_pow = |n| Expr::Pow(var(), Box::new(Expr::int(n)));
+
+        // (x+1)^3 → x³ + 3x² + 3x + 1
+        let expr = Expr::Pow(
+            Box::new(Expr::Add(var(), Box::new(Expr::int(1)))),
+            Box::new(Expr::int(3)),
+        );
+        assert!(rule.can_apply(&expr, &ctx));
+        let results = rule.apply(&expr, &ctx);
+        let expected = Expr::Sum(vec![
+            Term {
+                coeff: Rational::from_integer(1),
+                expr: x_pow(3),
+            },
+            Term {
+                coeff: Rational::from_integer(3),
+                expr: x_pow(2),
+            },
+            Term {
+                coeff: Rational::from_integer(3),
+                expr: Expr::Var(x),
+            },
+            Term {
+                coeff: Rational::from_integer(1),
+                expr: Expr::int(1),
+            },
+        ]);
+        assert_eq!(results[0].result.canonicalize(), expected.canonicalize());
+        assert!(results[0].result.approx_equals(&expr, 10, 1e-9));
+
+        // (x-1)^5 alternates in sign
+        let expr = Expr::Pow(
+            Box::new(Expr::Sub(var(), Box::new(Expr::int(1)))),
+            Box::new(Expr::int(5)),
+        );
+        assert!(rule.apply(&expr, &ctx)[0]
+            .result
+            .approx_equals(&expr, 10, 1e-9));
+
+        // Powers above the configured cap are left alone
+        let capped = RuleContext {
+            max_expand_power: 4,
+            ..RuleContext::default()
+        };
+        assert!(!rule.can_apply(&expr, &capped));
+    }
+
+    #[test]
+    fn test_power_subtract_condition() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        let rule = power_subtract();
+        let ctx = RuleContext::default();
+
+        // x^3 / x → x^2, valid only for x ≠ 0
+        let expr = Expr::Div(
+            Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(3)))),
+            Box::new(Expr::Var(x)),
+        );
+        assert!(rule.can_apply(&expr, &ctx));
+
+        let results = rule.apply(&expr, &ctx);
+        assert_eq!(
+            results[0].result,
+            Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))
+        );
+        let condition = results[0].condition.as_ref().expect("x ≠ 0");
+        match &condition.expr {
+            Expr::Not(inner) => assert!(matches!(
+                inner.as_ref(),
+                Expr::Equation { lhs, rhs } if **lhs == Expr::Var(x) && **rhs == Expr::int(0)
+            )),
+            other => panic!("Expected x ≠ 0, got {:?}", other),
+        }
+
+        // Generic variables carry no side condition
+        let generic = RuleContext {
+            assume_generic: true,
+            ..RuleContext::default()
+        };
+        assert!(rule.apply(&expr, &generic)[0].condition.is_none());
     }
 
     #[test]
@@ -2310,4 +3127,229 @@ mod tests {
         assert_eq!(results.len(), 1);
         // Result should be 2*x + 2*y
     }
+
+    #[test]
+    fn test_radical_extract() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        let rule = radical_extract();
+        let ctx = RuleContext::default();
+        let x_pow = |k| Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(k))));
+
+        // √8 = 2√2
+        let expr = Expr::Sqrt(Box::new(Expr::int(8)));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(
+            rule.apply(&expr, &ctx)[0].result,
+            Expr::Mul(
+                Box::new(Expr::int(2)),
+                Box::new(Expr::Sqrt(Box::new(Expr::int(2))))
+            )
+        );
+
+        // √(4x²) = 2|x|
+        let expr = Expr::Sqrt(Box::new(Expr::Mul(Box::new(Expr::int(4)), x_pow(2))));
+        assert_eq!(
+            rule.apply(&expr, &ctx)[0].result,
+            Expr::Mul(
+                Box::new(Expr::int(2)),
+                Box::new(Expr::Abs(Box::new(Expr::Var(x))))
+            )
+        );
+
+        // ... or 2x when variables are assumed nonnegative
+        let mut nonneg = RuleContext::default();
+        nonneg
+            .metadata
+            .insert(ASSUME_NONNEGATIVE.to_string(), "true".to_string());
+        assert_eq!(
+            rule.apply(&expr, &nonneg)[0].result,
+            Expr::Mul(Box::new(Expr::int(2)), Box::new(Expr::Var(x)))
+        );
+
+        // ∛(8x³) = 2x
+        let expr = Expr::Pow(
+            Box::new(Expr::Mul(Box::new(Expr::int(8)), x_pow(3))),
+            Box::new(Expr::Const(Rational::new(1, 3))),
+        );
+        assert_eq!(
+            rule.apply(&expr, &ctx)[0].result,
+            Expr::Mul(Box::new(Expr::int(2)), Box::new(Expr::Var(x)))
+        );
+
+        // Nothing to extract from √(6x)
+        let expr = Expr::Sqrt(Box::new(Expr::Mul(
+            Box::new(Expr::int(6)),
+            Box::new(Expr::Var(x)),
+        )));
+        assert!(!rule.can_apply(&expr, &ctx));
+
+        // -2⁶³ has no positive counterpart in i64, so ∛(-2⁶³) is left alone
+        let expr = Expr::Pow(
+            Box::new(Expr::int(i64::MIN)),
+            Box::new(Expr::Const(Rational::new(1, 3))),
+        );
+        assert!(!rule.can_apply(&expr, &ctx));
+    }
+
+    #[test]
+    fn test_nested_abs_and_signs() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let y = symbols.intern("y");
+        let ctx = RuleContext::default();
+        let (vx, vy) = (|| Box::new(Expr::Var(x)), || Box::new(Expr::Var(y)));
+        let neg = |e: Expr| Expr::Neg(Box::new(e));
+        let abs = |e: Expr| Expr::Abs(Box::new(e));
+        let pow = |n| Expr::Pow(vx(), Box::new(Expr::int(n)));
+
+        let cases = [
+            // |-x| = |x|
+            (abs_strip_sign(), abs(neg(Expr::Var(x))), abs(Expr::Var(x))),
+            // ||x|| = |x|
+            (abs_strip_sign(), abs(abs(Expr::Var(x))), abs(Expr::Var(x))),
+            // |-|-x|| = |x|
+            (
+                abs_strip_sign(),
+                abs(neg(abs(neg(Expr::Var(x))))),
+                abs(Expr::Var(x)),
+            ),
+            // |x²| = x², |x⁴| = x⁴
+            (abs_even_power(), abs(pow(2)), pow(2)),
+            (abs_even_power(), abs(pow(4)), pow(4)),
+            // -(-(x)) = x
+            (fold_signs(), neg(neg(Expr::Var(x))), Expr::Var(x)),
+            // -(-(-x)) = -x
+            (fold_signs(), neg(neg(neg(Expr::Var(x)))), neg(Expr::Var(x))),
+            // -(x · -y) = x·y
+            (
+                fold_signs(),
+                neg(Expr::Mul(vx(), Box::new(neg(Expr::Var(y))))),
+                Expr::Mul(vx(), vy()),
+            ),
+            // -x / -y = x/y
+            (
+                fold_signs(),
+                Expr::Div(Box::new(neg(Expr::Var(x))), Box::new(neg(Expr::Var(y)))),
+                Expr::Div(vx(), vy()),
+            ),
+        ];
+        for (rule, expr, expected) in cases {
+            assert!(rule.can_apply(&expr, &ctx), "{} on {expr:?}", rule.name);
+            let result = rule.apply(&expr, &ctx)[0].result.clone();
+            assert_eq!(result, expected);
+            assert!(
+                result.approx_equals(&expr, 20, 1e-9),
+                "{} on {expr:?}",
+                rule.name
+            );
+        }
+
+        // Odd powers can be negative, and a single sign has nothing to cancel
+        assert!(!abs_even_power().can_apply(&abs(pow(3)), &ctx));
+        assert!(!fold_signs().can_apply(&Expr::Mul(vx(), Box::new(neg(Expr::Var(y)))), &ctx));
+        assert!(!abs_strip_sign().can_apply(&abs(Expr::Var(x)), &ctx));
+    }
+
+    #[test]
+    fn test_special_form_factoring() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let ctx = RuleContext::default();
+
+        let cases = [
+            (difference_of_squares(), "x^2 - 4", "(x - 2)*(x + 2)"),
+            (difference_of_squares(), "x^2 - y^2", "(x - y)*(x + y)"),
+            (difference_of_squares(), "25 - x^2", "(5 - x)*(5 + x)"),
+            (diff_of_cubes(), "x^3 - 8", "(x - 2)*(x^2 + 2*x + 4)"),
+            (diff_of_cubes(), "x^3 - y^3", "(x - y)*(x^2 + x*y + y^2)"),
+            (sum_of_cubes(), "x^3 + 27", "(x + 3)*(x^2 - 3*x + 9)"),
+            (sum_of_cubes(), "8 + y^3", "(2 + y)*(4 - 2*y + y^2)"),
+        ];
+        for (rule, source, factored) in cases {
+            let expr = parser.parse(source).unwrap();
+            let expected = parser.parse(factored).unwrap();
+            assert!(rule.can_apply(&expr, &ctx), "{source}");
+            let result = rule.apply(&expr, &ctx)[0].result.clone();
+            assert_eq!(result, expected, "{source}");
+            assert!(result.approx_equals(&expr, 20, 1e-9), "{source}");
+        }
+
+        // 2 is not a square, and differences of numbers are arithmetic
+        for source in ["x^2 - 2", "9 - 4", "x^2 + 4"] {
+            let expr = parser.parse(source).unwrap();
+            assert!(!difference_of_squares().can_apply(&expr, &ctx), "{source}");
+        }
+        let expr = parser.parse("x^3 - 9").unwrap();
+        assert!(!diff_of_cubes().can_apply(&expr, &ctx));
+    }
+
+    #[test]
+    fn test_factor_quadratic() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let rule = factor_quadratic();
+        let ctx = RuleContext::default();
+
+        let cases = [
+            ("x^2 + 3*x + 2", "(x + 1)*(x + 2)"),
+            ("x^2 - 1", "(x - 1)*(x + 1)"),
+            ("2*x^2 + 3*x + 1", "(2*x + 1)*(x + 1)"),
+            ("x^2 - 4*x + 4", "(x - 2)*(x - 2)"),
+            ("3*x^2 - 3*x", "3*((x - 1)*x)"),
+        ];
+        for (source, factored) in cases {
+            let expr = parser.parse(source).unwrap();
+            let expected = parser.parse(factored).unwrap();
+            assert!(rule.can_apply(&expr, &ctx), "{source}");
+            let result = rule.apply(&expr, &ctx)[0].result.clone();
+            assert_eq!(result, expected, "{source}");
+            assert!(result.approx_equals(&expr, 20, 1e-9), "{source}");
+        }
+
+        // No rational roots, too many variables, or not a quadratic
+        for source in [
+            "x^2 + 1",
+            "x^2 - 2",
+            "x^2 + y",
+            "x^3 - x",
+            "x + 1",
+            "x^2 + x + 9999999967",
+        ] {
+            let expr = parser.parse(source).unwrap();
+            assert!(!rule.can_apply(&expr, &ctx), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_fraction_div() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(|n| Expr::Var(symbols.intern(n)));
+
+        let rule = fraction_div();
+        let ctx = RuleContext::default();
+        let div = |p: Expr, q: Expr| Expr::Div(Box::new(p), Box::new(q));
+
+        // 1/(1/x) = x
+        let expr = div(Expr::int(1), div(Expr::int(1), Expr::Var(x)));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::Var(x));
+
+        // (a/b)/(c/d) = ad/(bc)
+        let expr = div(div(a.clone(), b.clone()), div(c.clone(), d.clone()));
+        let result = rule.apply(&expr, &ctx)[0].result.clone();
+        assert_eq!(
+            result,
+            div(
+                Expr::Mul(Box::new(a), Box::new(d)),
+                Expr::Mul(Box::new(b), Box::new(c))
+            )
+        );
+        assert!(result.approx_equals(&expr, 20, 1e-9));
+
+        // Plain x/y is not a complex fraction
+        assert!(!rule.can_apply(&div(Expr::Var(x), Expr::int(2)), &ctx));
+    }
 }
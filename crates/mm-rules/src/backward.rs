@@ -297,7 +297,7 @@ fn exists_witness(goal: &Expr) -> Option<BackwardStep> {
 }
 
 /// Simple variable substitution for backward reasoning
-fn substitute_var(body: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
+pub(crate) fn substitute_var(body: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
     match body {
         Expr::Var(v) if *v == var => value.clone(),
         Expr::Var(_) | Expr::Const(_) | Expr::Pi | Expr::E => body.clone(),
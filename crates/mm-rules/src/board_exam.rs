@@ -10,8 +10,11 @@
 //! - Quadratic equations (roots, discriminant, sum/product of roots)
 //! - Arithmetic Progressions (nth term, sum)
 //! - Geometric Progressions (nth term, sum)
+//! - Board-exam rule set for step-by-step equation solving and integration
 
-use mm_core::{Expr, Rational, Symbol};
+use crate::polynomial::PolynomialNF;
+use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleContext, RuleId};
+use mm_core::{Expr, ExprKind, Rational, Symbol};
 
 // ============================================================================
 // Quadratic Equations: ax² + bx + c = 0
@@ -29,7 +32,7 @@ pub enum QuadraticRoots {
         real: Rational,
         imaginary_squared: Rational,
     },
-    /// Cannot solve (a = 0, not quadratic)
+    /// Cannot solve (a = 0, not quadratic, or the arithmetic overflows)
     NotQuadratic,
 }
 
@@ -45,14 +48,16 @@ pub enum QuadraticRoots {
 /// let b = Rational::from(-5);
 /// let c = Rational::from(6);
 /// let d = discriminant(a, b, c);
-/// assert_eq!(d, Rational::from(1)); // 25 - 24 = 1
+/// assert_eq!(d, Some(Rational::from(1))); // 25 - 24 = 1
 /// ```
 ///
 /// # Returns
 ///
-/// The discriminant D = b² - 4ac as a `Rational`.
-pub fn discriminant(a: Rational, b: Rational, c: Rational) -> Rational {
-    b * b - Rational::from(4) * a * c
+/// The discriminant D = b² - 4ac as a `Rational`, or `None` if computing it
+/// overflows `i64`.
+pub fn discriminant(a: Rational, b: Rational, c: Rational) -> Option<Rational> {
+    let four_ac = Rational::from(4).checked_mul(&a)?.checked_mul(&c)?;
+    b.checked_mul(&b)?.checked_sub(&four_ac)
 }
 
 /// Solves the quadratic equation ax² + bx + c = 0 and returns its roots.
@@ -81,14 +86,19 @@ pub fn solve_quadratic(a: Rational, b: Rational, c: Rational) -> QuadraticRoots
     if a.is_zero() {
         return QuadraticRoots::NotQuadratic;
     }
+    quadratic_roots(a, b, c).unwrap_or(QuadraticRoots::NotQuadratic)
+}
 
-    let d = discriminant(a, b, c);
-    let two_a = Rational::from(2) * a;
-    let neg_b = Rational::from(0) - b;
+/// The roots of ax² + bx + c = 0 for a ≠ 0, or `None` if the arithmetic
+/// overflows `i64`.
+fn quadratic_roots(a: Rational, b: Rational, c: Rational) -> Option<QuadraticRoots> {
+    let d = discriminant(a, b, c)?;
+    let two_a = Rational::from(2).checked_mul(&a)?;
+    let neg_b = Rational::from(0).checked_sub(&b)?;
 
-    if d.is_zero() {
+    let roots = if d.is_zero() {
         // D = 0: One repeated root
-        let root = neg_b / two_a;
+        let root = neg_b.checked_div(&two_a)?;
         QuadraticRoots::OneReal(root)
     } else if d.is_positive() {
         // D > 0: Two distinct real roots
@@ -97,29 +107,29 @@ pub fn solve_quadratic(a: Rational, b: Rational, c: Rational) -> QuadraticRoots
         let d_denom = d.denom();
 
         // Try to find integer square root
-        if let (Some(sqrt_numer), Some(sqrt_denom)) =
+        let sqrt_d = if let (Some(sqrt_numer), Some(sqrt_denom)) =
             (int_sqrt(d_numer.abs()), int_sqrt(d_denom.abs()))
         {
-            let sqrt_d = Rational::new(sqrt_numer, sqrt_denom);
-            let root1 = (neg_b + sqrt_d) / two_a;
-            let root2 = (neg_b - sqrt_d) / two_a;
-            QuadraticRoots::TwoReal(root1, root2)
+            Rational::new(sqrt_numer, sqrt_denom)
         } else {
             // D is not a perfect square - roots are irrational
             // For board exams, we still report them symbolically
             // Store as approximate for now
-            let sqrt_d_approx = Rational::new((d.to_f64().sqrt() * 1000.0) as i64, 1000);
-            let root1 = (neg_b + sqrt_d_approx) / two_a;
-            let root2 = (neg_b - sqrt_d_approx) / two_a;
-            QuadraticRoots::TwoReal(root1, root2)
-        }
+            Rational::new((d.to_f64().sqrt() * 1000.0) as i64, 1000)
+        };
+        let root1 = neg_b.checked_add(&sqrt_d)?.checked_div(&two_a)?;
+        let root2 = neg_b.checked_sub(&sqrt_d)?.checked_div(&two_a)?;
+        QuadraticRoots::TwoReal(root1, root2)
     } else {
         // D < 0: Complex roots
         QuadraticRoots::Complex {
-            real: neg_b / two_a,
-            imaginary_squared: (Rational::from(0) - d) / (two_a * two_a),
+            real: neg_b.checked_div(&two_a)?,
+            imaginary_squared: Rational::from(0)
+                .checked_sub(&d)?
+                .checked_div(&two_a.checked_mul(&two_a)?)?,
         }
-    }
+    };
+    Some(roots)
 }
 
 /// Return the integer square root when `n` is a perfect square.
@@ -142,9 +152,9 @@ fn int_sqrt(n: i64) -> Option<i64> {
         return Some(0);
     }
     let sqrt = (n as f64).sqrt() as i64;
-    if sqrt * sqrt == n {
+    if sqrt.checked_mul(sqrt) == Some(n) {
         Some(sqrt)
-    } else if (sqrt + 1) * (sqrt + 1) == n {
+    } else if (sqrt + 1).checked_mul(sqrt + 1) == Some(n) {
         Some(sqrt + 1)
     } else {
         None
@@ -538,6 +548,272 @@ pub fn definite_integral(
     Some(f_upper - f_lower)
 }
 
+// ============================================================================
+// Board Exam Rule Set (ID 820+)
+// Whole-problem rules that solve a structured exam question in one step,
+// with justifications written for students.
+// ============================================================================
+
+/// Rules for structured board-exam questions (IDs 820–822).
+///
+/// These are kept out of `standard_rules()`: each one solves a whole
+/// question at once, which would short-circuit the general search.
+pub fn board_exam_rules() -> Vec<Rule> {
+    vec![
+        linear_equation(),
+        quadratic_equation(),
+        indefinite_integral(),
+    ]
+}
+
+/// Bring `lhs = rhs` to the form `p(x) = 0` and return the coefficients of
+/// `p`, lowest degree first, together with the unknown.
+fn equation_coefficients(expr: &Expr, ctx: &RuleContext) -> Option<(Symbol, Vec<Rational>)> {
+    let Expr::Equation { lhs, rhs } = expr else {
+        return None;
+    };
+    let var = match ctx.target_var {
+        Some(v) => v,
+        None => match expr.free_vars().as_slice() {
+            [v] => *v,
+            _ => return None,
+        },
+    };
+    let diff = Expr::Sub(lhs.clone(), rhs.clone());
+    let coeffs = PolynomialNF::from_expr(&diff)?.coefficients(var)?;
+    Some((var, coeffs))
+}
+
+fn root_equation(var: Symbol, value: Expr) -> Expr {
+    Expr::Equation {
+        lhs: Box::new(Expr::Var(var)),
+        rhs: Box::new(value),
+    }
+}
+
+/// The roots `(-b ± √D) / 2a` for a `D > 0` that is not a perfect square,
+/// written `(p ± q√m) / r` in lowest terms with square factors of `D` taken
+/// out of the root: `p` is left out when it is zero, `q` when it is one and
+/// `r` when it is one. `None` if the arithmetic overflows `i64`.
+fn surd_roots(a: Rational, b: Rational, d: Rational) -> Option<(Expr, Expr)> {
+    // √(n/k) = √(nk) / k, then √(nk) = s√m
+    let (s, m) = split_square(d.numer().checked_mul(d.denom())?);
+    let two_a = Rational::from(2).checked_mul(&a)?;
+    let p = Rational::from(0).checked_sub(&b)?.checked_div(&two_a)?;
+    let q = Rational::new(s, d.denom()).checked_div(&two_a)?.abs();
+
+    // Over the common denominator r = lcm of the denominators
+    let r = p
+        .denom()
+        .checked_mul(Rational::new(p.denom(), q.denom()).denom())?;
+    let (p, q) = (
+        p.checked_mul(&Rational::from(r))?,
+        q.checked_mul(&Rational::from(r))?,
+    );
+
+    let surd = Expr::Sqrt(Box::new(Expr::int(m)));
+    let surd = if q.is_one() {
+        surd
+    } else {
+        Expr::Mul(Box::new(Expr::Const(q)), Box::new(surd))
+    };
+    let root = |negative: bool| {
+        let numerator = match (p.is_zero(), negative) {
+            (true, false) => surd.clone(),
+            (true, true) => Expr::Neg(Box::new(surd.clone())),
+            (false, false) => Expr::Add(Box::new(Expr::Const(p)), Box::new(surd.clone())),
+            (false, true) => Expr::Sub(Box::new(Expr::Const(p)), Box::new(surd.clone())),
+        };
+        if r == 1 {
+            numerator
+        } else {
+            Expr::Div(Box::new(numerator), Box::new(Expr::int(r)))
+        }
+    };
+    Some((root(false), root(true)))
+}
+
+/// `n = s²·m` with the square factors below 1000 moved into `s`, which is
+/// enough for exam-sized discriminants without factoring large ones.
+fn split_square(n: i64) -> (i64, i64) {
+    let (mut s, mut m) = (1, n);
+    for p in 2..1000 {
+        if p * p > m {
+            break;
+        }
+        while m % (p * p) == 0 {
+            m /= p * p;
+            s *= p;
+        }
+    }
+    (s, m)
+}
+
+// ============================================================================
+// Rule 820: ax + b = 0 → x = -b/a
+// ============================================================================
+
+fn linear_equation() -> Rule {
+    Rule {
+        id: RuleId(820),
+        name: "board_linear_equation",
+        category: RuleCategory::EquationSolving,
+        description: "ax + b = 0 → x = -b/a",
+        domains: &[Domain::Equations],
+        requires: &[Feature::Equation, Feature::Polynomial],
+        roots: &[ExprKind::Equation],
+        is_applicable: |expr, ctx| matches!(equation_coefficients(expr, ctx), Some((_, c)) if c.len() == 2),
+        apply: |expr, ctx| {
+            let Some((var, c)) = equation_coefficients(expr, ctx) else {
+                return vec![];
+            };
+            if c.len() != 2 {
+                return vec![];
+            }
+            let (b, a) = (c[0], c[1]);
+            let root = -b / a;
+            vec![RuleApplication {
+                result: root_equation(var, Expr::Const(root)),
+                justification: format!(
+                    "Linear equation in standard form ax + b = 0 with a = {}, b = {}; x = -b/a = {}",
+                    a, b, root
+                ),
+                condition: None,
+            }]
+        },
+        reversible: false,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 821: ax² + bx + c = 0 → x = (-b ± √D) / 2a
+// ============================================================================
+
+fn quadratic_equation() -> Rule {
+    Rule {
+        id: RuleId(821),
+        name: "board_quadratic_equation",
+        category: RuleCategory::EquationSolving,
+        description: "ax² + bx + c = 0 → x = (-b ± √(b² - 4ac)) / 2a",
+        domains: &[Domain::Equations],
+        requires: &[Feature::Equation, Feature::Polynomial],
+        roots: &[ExprKind::Equation],
+        is_applicable: |expr, ctx| {
+            matches!(
+                equation_coefficients(expr, ctx),
+                Some((_, c)) if c.len() == 3
+                    && discriminant(c[2], c[1], c[0]).is_some_and(|d| !d.is_negative())
+            )
+        },
+        apply: |expr, ctx| {
+            let Some((var, coeffs)) = equation_coefficients(expr, ctx) else {
+                return vec![];
+            };
+            if coeffs.len() != 3 {
+                return vec![];
+            }
+            let (c, b, a) = (coeffs[0], coeffs[1], coeffs[2]);
+            let Some(d) = discriminant(a, b, c) else {
+                return vec![];
+            };
+            let setup = format!(
+                "Standard form ax² + bx + c = 0 with a = {}, b = {}, c = {}; D = b² - 4ac = {}",
+                a, b, c, d
+            );
+
+            let (result, nature) = match solve_quadratic(a, b, c) {
+                QuadraticRoots::OneReal(r) => (
+                    root_equation(var, Expr::Const(r)),
+                    format!("D = 0, so there is one repeated root x = {}", r),
+                ),
+                QuadraticRoots::TwoReal(r1, r2)
+                    if int_sqrt(d.numer()).is_some() && int_sqrt(d.denom()).is_some() =>
+                {
+                    (
+                        Expr::Or(
+                            Box::new(root_equation(var, Expr::Const(r1))),
+                            Box::new(root_equation(var, Expr::Const(r2))),
+                        ),
+                        format!(
+                            "D > 0 is a perfect square, so the roots are x = {} and x = {}",
+                            r1, r2
+                        ),
+                    )
+                }
+                QuadraticRoots::TwoReal(_, _) => {
+                    // Irrational roots: keep √D exact instead of the approximation
+                    let Some((plus, minus)) = surd_roots(a, b, d) else {
+                        return vec![];
+                    };
+                    (
+                        Expr::Or(
+                            Box::new(root_equation(var, plus)),
+                            Box::new(root_equation(var, minus)),
+                        ),
+                        format!(
+                            "D > 0, so the roots are x = ({} ± √{}) / {}",
+                            -b,
+                            d,
+                            Rational::from(2) * a
+                        ),
+                    )
+                }
+                QuadraticRoots::Complex { .. } | QuadraticRoots::NotQuadratic => return vec![],
+            };
+
+            vec![RuleApplication {
+                result,
+                justification: format!("{}; {}", setup, nature),
+                condition: None,
+            }]
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 822: ∫f(x) dx → F(x) using the board-exam antiderivative table
+// ============================================================================
+
+fn indefinite_integral() -> Rule {
+    Rule {
+        id: RuleId(822),
+        name: "board_indefinite_integral",
+        category: RuleCategory::Integral,
+        description: "∫f(x) dx → F(x) (constant of integration omitted)",
+        domains: &[Domain::CalculusInt],
+        requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::Integral { expr, var } if integrate(expr, *var).is_some()),
+        apply: |expr, _ctx| {
+            let Expr::Integral {
+                expr: integrand,
+                var,
+            } = expr
+            else {
+                return vec![];
+            };
+            match integrate(integrand, *var) {
+                Some(antiderivative) => vec![RuleApplication {
+                    result: antiderivative,
+                    justification:
+                        "Integrate term by term using the standard integrals table (+ C)"
+                            .to_string(),
+                    condition: None,
+                }],
+                None => vec![],
+            }
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -676,4 +952,26 @@ mod tests {
 
         assert_eq!(result, Some(Rational::from(9)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_board_linear_equation_rule() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // 2x + 3 = 7 → x = 2
+        let eq = Expr::Equation {
+            lhs: Box::new(Expr::Add(
+                Box::new(Expr::Mul(Box::new(Expr::int(2)), Box::new(Expr::Var(x)))),
+                Box::new(Expr::int(3)),
+            )),
+            rhs: Box::new(Expr::int(7)),
+        };
+        let ctx = RuleContext::default();
+        let rule = linear_equation();
+
+        assert!(rule.can_apply(&eq, &ctx));
+        assert!(!quadratic_equation().can_apply(&eq, &ctx));
+        let apps = rule.apply(&eq, &ctx);
+        assert_eq!(apps[0].result, root_equation(x, Expr::int(2)));
+    }
+}
@@ -20,6 +20,8 @@ pub fn calculus_rules() -> Vec<Rule> {
         quotient_rule(),
         chain_rule_sin(),
         chain_rule_cos(),
+        chain_rule_sinh(),
+        chain_rule_cosh(),
         exp_rule(),
         ln_rule(),
     ];
@@ -41,6 +43,7 @@ fn power_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Power rule: d/dx(x^n) = n·x^(n-1)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -66,6 +69,7 @@ fn power_rule() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::int(1),
                             justification: "d/dx(x) = 1".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -83,6 +87,7 @@ fn power_rule() -> Rule {
                                     )),
                                 ),
                                 justification: format!("d/dx(x^{}) = {} · x^{}", n, n, n_minus_1),
+                                condition: None,
                             }];
                         }
                     }
@@ -92,6 +97,7 @@ fn power_rule() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -106,6 +112,7 @@ fn constant_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Constant rule: d/dx(c) = 0",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -120,6 +127,7 @@ fn constant_rule() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::int(0),
                         justification: "d/dx(c) = 0 (c does not contain x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -127,6 +135,7 @@ fn constant_rule() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -141,6 +150,7 @@ fn sum_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Sum rule: d/dx(f + g) = f' + g'",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
@@ -163,6 +173,7 @@ fn sum_rule() -> Rule {
                             }),
                         ),
                         justification: "d/dx(f + g) = f' + g'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -170,6 +181,7 @@ fn sum_rule() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -184,6 +196,7 @@ fn product_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Product rule: d/dx(fg) = f'g + fg'",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
@@ -212,6 +225,7 @@ fn product_rule() -> Rule {
                             )),
                         ),
                         justification: "d/dx(fg) = f'g + fg'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -219,6 +233,7 @@ fn product_rule() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -234,6 +249,7 @@ fn quotient_rule() -> Rule {
         description: "Quotient rule: d/dx(f/g) = (f'g - fg') / g²",
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
                 return matches!(inner.as_ref(), Expr::Div(_, _));
@@ -265,6 +281,7 @@ fn quotient_rule() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(numerator), Box::new(denominator)),
                         justification: "d/dx(f/g) = (f'g - fg') / g²".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -272,6 +289,7 @@ fn quotient_rule() -> Rule {
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -286,6 +304,7 @@ fn chain_rule_sin() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Sine chain rule: d/dx(sin(g)) = cos(g) * g'",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -308,6 +327,7 @@ fn chain_rule_sin() -> Rule {
                             return vec![RuleApplication {
                                 result: cos_g,
                                 justification: "d/dx(sin(x)) = cos(x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -320,6 +340,7 @@ fn chain_rule_sin() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Mul(Box::new(cos_g), Box::new(g_prime)),
                         justification: "d/dx(sin(g)) = cos(g) * g'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -327,6 +348,7 @@ fn chain_rule_sin() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -341,6 +363,7 @@ fn chain_rule_cos() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Cosine chain rule: d/dx(cos(g)) = -sin(g) * g'",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -363,6 +386,7 @@ fn chain_rule_cos() -> Rule {
                             return vec![RuleApplication {
                                 result: neg_sin_g,
                                 justification: "d/dx(cos(x)) = -sin(x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -375,6 +399,7 @@ fn chain_rule_cos() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Mul(Box::new(neg_sin_g), Box::new(g_prime)),
                         justification: "d/dx(cos(g)) = -sin(g) * g'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -382,6 +407,114 @@ fn chain_rule_cos() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 28: d/dx(sinh(g(x))) = cosh(g(x)) * g'(x) (Chain Rule)
+// ============================================================================
+
+fn chain_rule_sinh() -> Rule {
+    Rule {
+        id: RuleId(28),
+        name: "sinh_chain_rule",
+        category: RuleCategory::Derivative,
+        domains: &[Domain::CalculusDiff],
+        requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
+        description: "Hyperbolic sine chain rule: d/dx(sinh(g)) = cosh(g) * g'",
+        is_applicable: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Sinh(arg) = inner.as_ref() {
+                    return contains_var(arg, *var);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Sinh(g) = inner.as_ref() {
+                    let cosh_g = Expr::Cosh(g.clone());
+
+                    if matches!(g.as_ref(), Expr::Var(v) if v == var) {
+                        return vec![RuleApplication {
+                            result: cosh_g,
+                            justification: "d/dx(sinh(x)) = cosh(x)".to_string(),
+                            condition: None,
+                        }];
+                    }
+
+                    let g_prime = Expr::Derivative {
+                        expr: g.clone(),
+                        var: *var,
+                    };
+                    return vec![RuleApplication {
+                        result: Expr::Mul(Box::new(cosh_g), Box::new(g_prime)),
+                        justification: "d/dx(sinh(g)) = cosh(g) * g'".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 29: d/dx(cosh(g(x))) = sinh(g(x)) * g'(x) (Chain Rule)
+// ============================================================================
+
+fn chain_rule_cosh() -> Rule {
+    Rule {
+        id: RuleId(29),
+        name: "cosh_chain_rule",
+        category: RuleCategory::Derivative,
+        domains: &[Domain::CalculusDiff],
+        requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
+        description: "Hyperbolic cosine chain rule: d/dx(cosh(g)) = sinh(g) * g'",
+        is_applicable: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Cosh(arg) = inner.as_ref() {
+                    return contains_var(arg, *var);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Cosh(g) = inner.as_ref() {
+                    // Unlike cos, no sign change
+                    let sinh_g = Expr::Sinh(g.clone());
+
+                    if matches!(g.as_ref(), Expr::Var(v) if v == var) {
+                        return vec![RuleApplication {
+                            result: sinh_g,
+                            justification: "d/dx(cosh(x)) = sinh(x)".to_string(),
+                            condition: None,
+                        }];
+                    }
+
+                    let g_prime = Expr::Derivative {
+                        expr: g.clone(),
+                        var: *var,
+                    };
+                    return vec![RuleApplication {
+                        result: Expr::Mul(Box::new(sinh_g), Box::new(g_prime)),
+                        justification: "d/dx(cosh(g)) = sinh(g) * g'".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
     }
 }
 
@@ -396,6 +529,7 @@ fn exp_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Exponential derivative: d/dx(e^x) = e^x",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -415,6 +549,7 @@ fn exp_rule() -> Rule {
                             return vec![RuleApplication {
                                 result: inner.as_ref().clone(),
                                 justification: "d/dx(e^x) = e^x".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -424,6 +559,7 @@ fn exp_rule() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -438,6 +574,7 @@ fn ln_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "Natural log derivative: d/dx(ln(x)) = 1/x",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -457,6 +594,7 @@ fn ln_rule() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::Var(*v))),
                                 justification: "d/dx(ln(x)) = 1/x".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -466,6 +604,7 @@ fn ln_rule() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -499,6 +638,9 @@ pub fn advanced_calculus_rules() -> Vec<Rule> {
         arccot_derivative(),         // Rule 416
         arcsec_derivative(),         // Rule 417
         arccsc_derivative(),         // Rule 418
+        arcsinh_derivative(),        // Rule 477
+        arccosh_derivative(),        // Rule 478
+        arctanh_derivative(),        // Rule 479
     ]
 }
 
@@ -510,6 +652,7 @@ fn chain_rule_tan() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(tan(g(x))) = sec²(g(x)) * g'(x) = g'(x)/cos²(g(x))",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -534,6 +677,7 @@ fn chain_rule_tan() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(g_prime), Box::new(cos_sq)),
                         justification: "d/dx(tan(g)) = g'/cos²(g)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -541,6 +685,7 @@ fn chain_rule_tan() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -552,6 +697,7 @@ fn chain_rule_exp() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(e^g(x)) = e^g(x) * g'(x)",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -574,6 +720,7 @@ fn chain_rule_exp() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Mul(Box::new(Expr::Exp(g.clone())), Box::new(g_prime)),
                         justification: "d/dx(e^g) = e^g * g'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -581,6 +728,7 @@ fn chain_rule_exp() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -592,6 +740,7 @@ fn chain_rule_ln() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(ln(g(x))) = g'(x)/g(x)",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -614,6 +763,7 @@ fn chain_rule_ln() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(g_prime), g.clone()),
                         justification: "d/dx(ln(g)) = g'/g".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -621,6 +771,7 @@ fn chain_rule_ln() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -632,6 +783,7 @@ fn inverse_trig_deriv_arcsin() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arcsin(x)) = 1/√(1-x²)",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -660,6 +812,7 @@ fn inverse_trig_deriv_arcsin() -> Rule {
                             Box::new(Expr::Sqrt(Box::new(one_minus_x_sq))),
                         ),
                         justification: "d/dx(arcsin(x)) = 1/√(1-x²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -667,6 +820,7 @@ fn inverse_trig_deriv_arcsin() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -681,6 +835,7 @@ fn diff_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(f - g) = f' - g'",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -707,6 +862,7 @@ fn diff_rule() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Sub(Box::new(f_prime), Box::new(g_prime)),
                         justification: "d/dx(f - g) = f' - g'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -714,6 +870,7 @@ fn diff_rule() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -725,6 +882,7 @@ fn constant_multiple_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(c*f) = c*f' where c is constant",
         is_applicable: |expr, ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -751,6 +909,7 @@ fn constant_multiple_rule() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Mul(c.clone(), Box::new(f_prime)),
                             justification: "d/dx(c*f) = c*f'".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -759,6 +918,7 @@ fn constant_multiple_rule() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -773,6 +933,7 @@ fn constant_base_exp_simple() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(a^x) = a^x·ln(a) where a is constant",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -800,6 +961,7 @@ fn constant_base_exp_simple() -> Rule {
                             Box::new(ln_a),
                         ),
                         justification: "d/dx(a^x) = a^x·ln(a)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -807,6 +969,7 @@ fn constant_base_exp_simple() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -821,6 +984,7 @@ fn constant_base_exp_chain() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(a^f(x)) = a^f(x)·ln(a)·f'(x) where a is constant",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -857,6 +1021,7 @@ fn constant_base_exp_chain() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "d/dx(a^f) = a^f·ln(a)·f'".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -864,6 +1029,7 @@ fn constant_base_exp_chain() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -878,6 +1044,7 @@ fn sqrt_chain_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(√f) = f'/(2√f)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -902,6 +1069,7 @@ fn sqrt_chain_rule() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(f_prime), Box::new(denominator)),
                         justification: "d/dx(√f) = f'/(2√f)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -909,6 +1077,7 @@ fn sqrt_chain_rule() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -923,6 +1092,7 @@ fn general_power_rule() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(f^n) = n·f^(n-1)·f' where n is constant",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -959,6 +1129,7 @@ fn general_power_rule() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(f^n) = n·f^(n-1)·f'".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -967,6 +1138,7 @@ fn general_power_rule() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -981,6 +1153,7 @@ fn log_base_simple() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(log_a(x)) = 1/(x·ln(a))",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1008,6 +1181,7 @@ fn log_base_simple() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Div(Box::new(Expr::int(1)), Box::new(x_ln_a)),
                                 justification: "d/dx(log_a(x)) = 1/(x·ln(a))".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1017,6 +1191,7 @@ fn log_base_simple() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1031,6 +1206,7 @@ fn log_base_chain() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(log_a(f)) = f'/(f·ln(a))",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1061,6 +1237,7 @@ fn log_base_chain() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Div(Box::new(f_prime), Box::new(f_ln_a)),
                             justification: "d/dx(log_a(f)) = f'/(f·ln(a))".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1069,6 +1246,7 @@ fn log_base_chain() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1083,6 +1261,7 @@ fn sec_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(sec(f)) = f'·sec(f)·tan(f) where sec(f) = 1/cos(f)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1118,6 +1297,7 @@ fn sec_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(sec(f)) = f'·sec(f)·tan(f)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1126,6 +1306,7 @@ fn sec_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1140,6 +1321,7 @@ fn csc_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(csc(f)) = -f'·csc(f)·cot(f) where csc(f) = 1/sin(f)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1175,6 +1357,7 @@ fn csc_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(csc(f)) = -f'·csc(f)·cot(f)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1183,6 +1366,7 @@ fn csc_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1197,6 +1381,7 @@ fn cot_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(cot(f)) = -f'/sin²(f) where cot(f) = cos(f)/sin(f)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1229,6 +1414,7 @@ fn cot_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(cot(f)) = -f'/sin²(f)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1237,6 +1423,7 @@ fn cot_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1251,6 +1438,7 @@ fn arcsin_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arcsin(f)) = f'/√(1-f²)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1284,6 +1472,7 @@ fn arcsin_derivative() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "d/dx(arcsin(f)) = f'/√(1-f²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1291,6 +1480,7 @@ fn arcsin_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1305,6 +1495,7 @@ fn arccos_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arccos(f)) = -f'/√(1-f²)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1341,6 +1532,7 @@ fn arccos_derivative() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "d/dx(arccos(f)) = -f'/√(1-f²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1348,6 +1540,7 @@ fn arccos_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1362,6 +1555,7 @@ fn arctan_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arctan(f)) = f'/(1+f²)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, var } = expr {
@@ -1392,6 +1586,7 @@ fn arctan_derivative() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "d/dx(arctan(f)) = f'/(1+f²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1399,6 +1594,7 @@ fn arctan_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1413,6 +1609,7 @@ fn arccot_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arccot(f)) = -f'/(1+f²)",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
@@ -1453,6 +1650,7 @@ fn arccot_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(arccot(f)) = -f'/(1+f²)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1461,6 +1659,7 @@ fn arccot_derivative() -> Rule {
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1475,6 +1674,7 @@ fn arcsec_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arcsec(f)) = f'/(|f|√(f²-1))",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
@@ -1523,6 +1723,7 @@ fn arcsec_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(arcsec(f)) = f'/(|f|√(f²-1))".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1531,6 +1732,7 @@ fn arcsec_derivative() -> Rule {
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1545,6 +1747,7 @@ fn arccsc_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(arccsc(f)) = -f'/(|f|√(f²-1))",
         is_applicable: |expr, _ctx| {
             if let Expr::Derivative { expr: inner, .. } = expr {
@@ -1593,6 +1796,7 @@ fn arccsc_derivative() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "d/dx(arccsc(f)) = -f'/(|f|√(f²-1))".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1601,6 +1805,166 @@ fn arccsc_derivative() -> Rule {
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 477: d/dx(arcsinh(f)) = f'/√(f²+1)
+// ============================================================================
+
+fn arcsinh_derivative() -> Rule {
+    Rule {
+        id: RuleId(477),
+        name: "arcsinh_derivative",
+        category: RuleCategory::Derivative,
+        domains: &[Domain::CalculusDiff],
+        requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
+        description: "d/dx(arcsinh(f)) = f'/√(f²+1)",
+        is_applicable: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arcsinh(arg) = inner.as_ref() {
+                    return contains_var(arg, *var);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arcsinh(f) = inner.as_ref() {
+                    // f² + 1
+                    let f_sq_plus_1 = Expr::Add(
+                        Box::new(Expr::Pow(f.clone(), Box::new(Expr::int(2)))),
+                        Box::new(Expr::int(1)),
+                    );
+                    let result = Expr::Div(
+                        Box::new(inner_derivative(f, *var)),
+                        Box::new(Expr::Sqrt(Box::new(f_sq_plus_1))),
+                    );
+
+                    return vec![RuleApplication {
+                        result,
+                        justification: "d/dx(arcsinh(f)) = f'/√(f²+1)".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 3,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 478: d/dx(arccosh(f)) = f'/√(f²-1)
+// ============================================================================
+
+fn arccosh_derivative() -> Rule {
+    Rule {
+        id: RuleId(478),
+        name: "arccosh_derivative",
+        category: RuleCategory::Derivative,
+        domains: &[Domain::CalculusDiff],
+        requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
+        description: "d/dx(arccosh(f)) = f'/√(f²-1)",
+        is_applicable: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arccosh(arg) = inner.as_ref() {
+                    return contains_var(arg, *var);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arccosh(f) = inner.as_ref() {
+                    // f² - 1, defined for f > 1
+                    let f_sq_minus_1 = Expr::Sub(
+                        Box::new(Expr::Pow(f.clone(), Box::new(Expr::int(2)))),
+                        Box::new(Expr::int(1)),
+                    );
+                    let result = Expr::Div(
+                        Box::new(inner_derivative(f, *var)),
+                        Box::new(Expr::Sqrt(Box::new(f_sq_minus_1))),
+                    );
+
+                    return vec![RuleApplication {
+                        result,
+                        justification: "d/dx(arccosh(f)) = f'/√(f²-1)".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 3,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 479: d/dx(arctanh(f)) = f'/(1-f²)
+// ============================================================================
+
+fn arctanh_derivative() -> Rule {
+    Rule {
+        id: RuleId(479),
+        name: "arctanh_derivative",
+        category: RuleCategory::Derivative,
+        domains: &[Domain::CalculusDiff],
+        requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
+        description: "d/dx(arctanh(f)) = f'/(1-f²)",
+        is_applicable: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arctanh(arg) = inner.as_ref() {
+                    return contains_var(arg, *var);
+                }
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Derivative { expr: inner, var } = expr {
+                if let Expr::Arctanh(f) = inner.as_ref() {
+                    // 1 - f², defined for |f| < 1
+                    let one_minus_f_sq = Expr::Sub(
+                        Box::new(Expr::int(1)),
+                        Box::new(Expr::Pow(f.clone(), Box::new(Expr::int(2)))),
+                    );
+                    let result = Expr::Div(
+                        Box::new(inner_derivative(f, *var)),
+                        Box::new(one_minus_f_sq),
+                    );
+
+                    return vec![RuleApplication {
+                        result,
+                        justification: "d/dx(arctanh(f)) = f'/(1-f²)".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 3,
+        ..Default::default()
+    }
+}
+
+/// `f'` for the chain rule: `1` when `f` is the variable itself, otherwise
+/// the unevaluated derivative.
+fn inner_derivative(f: &Expr, var: mm_core::Symbol) -> Expr {
+    match f {
+        Expr::Var(v) if *v == var => Expr::int(1),
+        _ => Expr::Derivative {
+            expr: Box::new(f.clone()),
+            var,
+        },
     }
 }
 
@@ -1608,7 +1972,7 @@ fn arccsc_derivative() -> Rule {
 fn contains_var(expr: &Expr, var: mm_core::Symbol) -> bool {
     match expr {
         Expr::Var(v) => *v == var,
-        Expr::Const(_) | Expr::Pi | Expr::E => false,
+        Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => false,
         Expr::Neg(e)
         | Expr::Sqrt(e)
         | Expr::Sin(e)
@@ -1617,6 +1981,12 @@ fn contains_var(expr: &Expr, var: mm_core::Symbol) -> bool {
         | Expr::Arcsin(e)
         | Expr::Arccos(e)
         | Expr::Arctan(e)
+        | Expr::Sinh(e)
+        | Expr::Cosh(e)
+        | Expr::Tanh(e)
+        | Expr::Arcsinh(e)
+        | Expr::Arccosh(e)
+        | Expr::Arctanh(e)
         | Expr::Ln(e)
         | Expr::Exp(e)
         | Expr::Abs(e) => contains_var(e, var),
@@ -1628,6 +1998,12 @@ fn contains_var(expr: &Expr, var: mm_core::Symbol) -> bool {
             .iter()
             .any(|f| contains_var(&f.base, var) || contains_var(&f.power, var)),
         Expr::Derivative { expr, .. } | Expr::Integral { expr, .. } => contains_var(expr, var),
+        Expr::Limit {
+            expr,
+            var: v,
+            approaching,
+            ..
+        } => (*v != var && contains_var(expr, var)) || contains_var(approaching, var),
         Expr::Equation { lhs, rhs }
         | Expr::GCD(lhs, rhs)
         | Expr::LCM(lhs, rhs)
@@ -1685,6 +2061,85 @@ fn contains_var(expr: &Expr, var: mm_core::Symbol) -> bool {
             contains_var(a, var) || contains_var(b, var)
         }
         Expr::Not(e) => contains_var(e, var),
+        Expr::Piecewise(branches) => branches
+            .iter()
+            .any(|(c, e)| contains_var(&c.expr, var) || contains_var(e, var)),
+    }
+}
+
+/// Check if an expression is `var²`.
+fn is_var_squared(expr: &Expr, var: mm_core::Symbol) -> bool {
+    matches!(expr, Expr::Pow(base, exp)
+        if matches!(base.as_ref(), Expr::Var(v) if *v == var)
+        && matches!(exp.as_ref(), Expr::Const(n) if *n == Rational::from(2)))
+}
+
+/// The constant `a ≥ 0` of a matched `a²`: the absolute value of the base of
+/// a square, the exact root of a perfect-square rational, or else `√(a²)`.
+fn root_of_square(a_squared: &Expr) -> Expr {
+    let exact_root = |n: i64| {
+        let root = (n as f64).sqrt().round() as i64;
+        (root.checked_mul(root) == Some(n)).then_some(root)
+    };
+    match a_squared {
+        Expr::Pow(base, exp) if matches!(exp.as_ref(), Expr::Const(n) if *n == Rational::from(2)) => {
+            match base.as_ref() {
+                Expr::Const(r) => Expr::Const(r.abs()),
+                _ => Expr::Abs(base.clone()),
+            }
+        }
+        Expr::Const(r) if !r.is_negative() => {
+            match (exact_root(r.numer()), exact_root(r.denom())) {
+                (Some(numer), Some(denom)) => Expr::Const(Rational::new(numer, denom)),
+                _ => Expr::Sqrt(Box::new(a_squared.clone())),
+            }
+        }
+        _ => Expr::Sqrt(Box::new(a_squared.clone())),
+    }
+}
+
+/// The argument of `√arg` in a product `var · √arg`, either way round.
+fn x_times_sqrt(expr: &Expr, var: Symbol) -> Option<&Expr> {
+    let Expr::Mul(left, right) = expr else {
+        return None;
+    };
+    match (left.as_ref(), right.as_ref()) {
+        (Expr::Var(v), Expr::Sqrt(arg)) | (Expr::Sqrt(arg), Expr::Var(v)) if *v == var => Some(arg),
+        _ => None,
+    }
+}
+
+/// Whether `expr` is `a² - var²` with `a²` free of `var`.
+fn a2_minus_x2(expr: &Expr, var: Symbol) -> bool {
+    matches!(expr, Expr::Sub(a_squared, x_squared)
+        if !contains_var(a_squared, var) && is_var_squared(x_squared, var))
+}
+
+/// The `a²` of `var² - a²` with `a²` free of `var`.
+fn x2_minus_a2(expr: &Expr, var: Symbol) -> Option<&Expr> {
+    match expr {
+        Expr::Sub(x_squared, a_squared)
+            if is_var_squared(x_squared, var) && !contains_var(a_squared, var) =>
+        {
+            Some(a_squared)
+        }
+        _ => None,
+    }
+}
+
+/// `a² / 2`, folded when `a²` is a number.
+fn half_of(a_squared: &Expr) -> Expr {
+    match a_squared {
+        Expr::Const(r) => Expr::Const(*r / Rational::from(2)),
+        _ => Expr::Div(Box::new(a_squared.clone()), Box::new(Expr::int(2))),
+    }
+}
+
+/// `x / a`, leaving out a division by 1.
+fn over(x: Expr, a: &Expr) -> Expr {
+    match a {
+        Expr::Const(n) if n.is_one() => x,
+        _ => Expr::Div(Box::new(x), Box::new(a.clone())),
     }
 }
 
@@ -1791,6 +2246,7 @@ fn integral_constant_multiple() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫k·f(x) dx = k·∫f(x) dx",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -1814,6 +2270,7 @@ fn integral_constant_multiple() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫k·f(x) dx = k·∫f(x) dx".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1821,6 +2278,7 @@ fn integral_constant_multiple() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1831,6 +2289,7 @@ fn integral_power() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x^n dx = x^(n+1)/(n+1) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -1867,6 +2326,7 @@ fn integral_power() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: format!("∫x^{} dx = x^{}/({})", n, n_plus_1, n_plus_1),
+                            condition: None,
                         }];
                     }
                 } else if let Expr::Var(_) = inner.as_ref() {
@@ -1879,6 +2339,7 @@ fn integral_power() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫x dx = x²/2".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1886,6 +2347,7 @@ fn integral_power() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_constant() -> Rule {
@@ -1895,6 +2357,7 @@ fn integral_constant() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫k dx = kx + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -1914,12 +2377,14 @@ fn integral_constant() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫k dx = kx".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 fn integral_sum() -> Rule {
@@ -1929,6 +2394,7 @@ fn integral_sum() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫(f+g) dx = ∫f dx + ∫g dx",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, .. } = expr {
@@ -1954,6 +2420,7 @@ fn integral_sum() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫(f+g) dx = ∫f dx + ∫g dx".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1961,6 +2428,7 @@ fn integral_sum() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_difference() -> Rule {
@@ -1970,6 +2438,7 @@ fn integral_difference() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫(f-g) dx = ∫f dx - ∫g dx",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, .. } = expr {
@@ -1995,6 +2464,7 @@ fn integral_difference() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫(f-g) dx = ∫f dx - ∫g dx".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -2002,6 +2472,7 @@ fn integral_difference() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2012,6 +2483,7 @@ fn integral_exp() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫e^x dx = e^x + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2028,12 +2500,14 @@ fn integral_exp() -> Rule {
                 return vec![RuleApplication {
                     result: inner.as_ref().clone(),
                     justification: "∫e^x dx = e^x".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 fn integral_ln() -> Rule {
@@ -2043,6 +2517,7 @@ fn integral_ln() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/x dx = ln|x| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2063,12 +2538,14 @@ fn integral_ln() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫1/x dx = ln|x|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_sin() -> Rule {
@@ -2078,6 +2555,7 @@ fn integral_sin() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sin(x) dx = -cos(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2096,12 +2574,14 @@ fn integral_sin() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sin(x) dx = -cos(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_cos() -> Rule {
@@ -2111,6 +2591,7 @@ fn integral_cos() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫cos(x) dx = sin(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2129,12 +2610,14 @@ fn integral_cos() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫cos(x) dx = sin(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_tan() -> Rule {
@@ -2144,6 +2627,7 @@ fn integral_tan() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫tan(x) dx = -ln|cos(x)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2164,12 +2648,14 @@ fn integral_tan() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫tan(x) dx = -ln|cos(x)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn integral_sec2() -> Rule {
@@ -2179,6 +2665,7 @@ fn integral_sec2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sec²(x) dx = tan(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2205,12 +2692,14 @@ fn integral_sec2() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sec²(x) dx = tan(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_csc2() -> Rule {
@@ -2220,6 +2709,7 @@ fn integral_csc2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫csc²(x) dx = -cot(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2249,12 +2739,14 @@ fn integral_csc2() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫csc²(x) dx = -cot(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_sinh() -> Rule {
@@ -2264,16 +2756,19 @@ fn integral_sinh() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sinh(x) dx = cosh(x) + C",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∫sinh(x) dx = cosh(x) + C (hyperbolic integral)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integral_cosh() -> Rule {
@@ -2283,16 +2778,19 @@ fn integral_cosh() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫cosh(x) dx = sinh(x) + C",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∫cosh(x) dx = sinh(x) + C (hyperbolic integral)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn integration_by_parts() -> Rule {
@@ -2302,6 +2800,7 @@ fn integration_by_parts() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x·e^x dx = x·e^x - e^x + C (simplified pattern)",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2333,12 +2832,14 @@ fn integration_by_parts() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x·e^x dx = x·e^x - e^x (integration by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 fn u_substitution() -> Rule {
@@ -2348,6 +2849,7 @@ fn u_substitution() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫2x·e^(x²) dx = e^(x²) + C (chain rule pattern)",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2378,12 +2880,14 @@ fn u_substitution() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫2x·e^(x²) dx = e^(x²) (u-substitution with u=x²)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn partial_fractions() -> Rule {
@@ -2393,6 +2897,7 @@ fn partial_fractions() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/(x²-1) dx = (1/2)ln|(x-1)/(x+1)| + C (partial fractions)",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2440,12 +2945,14 @@ fn partial_fractions() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫1/(x²-1) dx = (1/2)ln|(x-1)/(x+1)| (partial fractions)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 fn trig_substitution() -> Rule {
@@ -2455,6 +2962,7 @@ fn trig_substitution() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/√(1-x²) dx = arcsin(x) + C (trig substitution)",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2485,12 +2993,14 @@ fn trig_substitution() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫1/√(1-x²) dx = arcsin(x) (trig substitution)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 // ============================================================================
@@ -2504,6 +3014,7 @@ fn integral_cot() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫cot(x) dx = ln|sin(x)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2525,12 +3036,14 @@ fn integral_cot() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫cot(x) dx = ln|sin(x)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2541,6 +3054,7 @@ fn integral_sec() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sec(x) dx = ln|sec(x) + tan(x)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2575,12 +3089,14 @@ fn integral_sec() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sec(x) dx = ln|sec(x) + tan(x)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2591,6 +3107,7 @@ fn integral_csc() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫csc(x) dx = -ln|csc(x) + cot(x)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2626,12 +3143,14 @@ fn integral_csc() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫csc(x) dx = -ln|csc(x) + cot(x)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2642,6 +3161,7 @@ fn integral_sec_tan() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sec(x)tan(x) dx = sec(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2666,12 +3186,14 @@ fn integral_sec_tan() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sec(x)tan(x) dx = sec(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2686,6 +3208,7 @@ fn integral_inv_sqrt_a2_minus_x2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/√(a²-x²) dx = arcsin(x/a) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2725,17 +3248,14 @@ fn integral_inv_sqrt_a2_minus_x2() -> Rule {
                 if let Expr::Div(_, denom) = inner.as_ref() {
                     if let Expr::Sqrt(sqrt_arg) = denom.as_ref() {
                         if let Expr::Sub(left, _) = sqrt_arg.as_ref() {
-                            let a_squared = left.clone();
                             // ∫1/√(a²-x²) dx = arcsin(x/a)
-                            let x_div_a = Expr::Div(
-                                Box::new(Expr::Var(*var)),
-                                Box::new(Expr::Sqrt(a_squared))
-                            );
-                            let result = Expr::Arcsin(Box::new(x_div_a));
+                            let a = root_of_square(left);
+                            let result = Expr::Arcsin(Box::new(over(Expr::Var(*var), &a)));
                             
                             return vec![RuleApplication {
                                 result,
                                 justification: "∫1/√(a²-x²) dx = arcsin(x/a)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -2745,6 +3265,7 @@ fn integral_inv_sqrt_a2_minus_x2() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2755,6 +3276,7 @@ fn integral_inv_a2_plus_x2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/(a²+x²) dx = (1/a)arctan(x/a) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2764,15 +3286,13 @@ fn integral_inv_a2_plus_x2() -> Rule {
                         if let Expr::Add(left, right) = denom.as_ref() {
                             // Check a²+x² or x²+a²
                             let pattern1 = matches!(left.as_ref(), Expr::Const(_) | Expr::Pow(..))
-                                && matches!(right.as_ref(), Expr::Pow(base, exp)
-                                    if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                                    && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
-                                    
+                                && !contains_var(left, *var)
+                                && is_var_squared(right, *var);
+
                             let pattern2 = matches!(right.as_ref(), Expr::Const(_) | Expr::Pow(..))
-                                && matches!(left.as_ref(), Expr::Pow(base, exp)
-                                    if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                                    && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
-                                    
+                                && !contains_var(right, *var)
+                                && is_var_squared(left, *var);
+
                             return pattern1 || pattern2;
                         }
                     }
@@ -2785,21 +3305,21 @@ fn integral_inv_a2_plus_x2() -> Rule {
                 if let Expr::Div(_, denom) = inner.as_ref() {
                     if let Expr::Add(left, right) = denom.as_ref() {
                         // Get a² (the constant term)
-                        let a_squared = if matches!(left.as_ref(), Expr::Pow(base, _) if matches!(base.as_ref(), Expr::Var(_))) {
-                            right.clone()
+                        let a_squared = if is_var_squared(left, *var) {
+                            right
                         } else {
-                            left.clone()
+                            left
                         };
-                        
+
                         // ∫1/(a²+x²) dx = (1/a)arctan(x/a)
-                        let a = Expr::Sqrt(a_squared.clone());
-                        let x_div_a = Expr::Div(Box::new(Expr::Var(*var)), Box::new(a.clone()));
-                        let arctan_part = Expr::Arctan(Box::new(x_div_a));
-                        let result = Expr::Div(Box::new(arctan_part), Box::new(a));
+                        let a = root_of_square(a_squared);
+                        let arctan_part = Expr::Arctan(Box::new(over(Expr::Var(*var), &a)));
+                        let result = over(arctan_part, &a);
                         
                         return vec![RuleApplication {
                             result,
                             justification: "∫1/(a²+x²) dx = (1/a)arctan(x/a)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -2808,6 +3328,7 @@ fn integral_inv_a2_plus_x2() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2818,42 +3339,49 @@ fn integral_inv_x_sqrt_x2_minus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/(x√(x²-a²)) dx = (1/a)arcsec(|x|/a) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 // Match 1/(x√(x²-a²))
                 if let Expr::Div(num, denom) = inner.as_ref() {
-                    if matches!(num.as_ref(), Expr::Const(n) if n.is_one()) {
-                        // Denominator should be x·√(x²-a²)
-                        if let Expr::Mul(left, right) = denom.as_ref() {
-                            let has_var = matches!(left.as_ref(), Expr::Var(v) if *v == *var)
-                                || matches!(right.as_ref(), Expr::Var(v) if *v == *var);
-                            let has_sqrt = matches!(left.as_ref(), Expr::Sqrt(_)) || matches!(right.as_ref(), Expr::Sqrt(_));
-                            return has_var && has_sqrt;
-                        }
-                    }
+                    return matches!(num.as_ref(), Expr::Const(n) if n.is_one())
+                        && x_times_sqrt(denom, *var)
+                            .and_then(|arg| x2_minus_a2(arg, *var))
+                            .is_some();
                 }
             }
             false
         },
         apply: |expr, _ctx| {
-            if let Expr::Integral { var, .. } = expr {
-                // Simplified result: arcsec(x) = arccos(1/x)
-                // ∫1/(x√(x²-1)) dx = arccos(1/|x|)
-                let x = Expr::Var(*var);
-                let abs_x = Expr::Abs(Box::new(x.clone()));
-                let one_over_x = Expr::Div(Box::new(Expr::int(1)), Box::new(abs_x));
-                let result = Expr::Arccos(Box::new(one_over_x));
-                
+            if let Expr::Integral { var, expr: inner } = expr {
+                let Expr::Div(_, denom) = inner.as_ref() else {
+                    return vec![];
+                };
+                let Some(a_squared) =
+                    x_times_sqrt(denom, *var).and_then(|arg| x2_minus_a2(arg, *var))
+                else {
+                    return vec![];
+                };
+                let a = root_of_square(a_squared);
+
+                // (1/a)arcsec(|x|/a), written (1/a)arccos(a/|x|)
+                let abs_x = Expr::Abs(Box::new(Expr::Var(*var)));
+                let a_over_x = Expr::Div(Box::new(a.clone()), Box::new(abs_x));
+                let result = over(Expr::Arccos(Box::new(a_over_x)), &a);
+
                 return vec![RuleApplication {
                     result,
-                    justification: "∫1/(x√(x²-a²)) dx = arccos(1/|x|) (arcsec form)".to_string(),
+                    justification: "∫1/(x√(x²-a²)) dx = (1/a)arcsec(|x|/a) = (1/a)arccos(a/|x|)"
+                        .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2868,6 +3396,7 @@ fn integral_x_sin() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x·sin(x) dx = -x·cos(x) + sin(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2898,12 +3427,14 @@ fn integral_x_sin() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x·sin(x) dx = -x·cos(x) + sin(x) (integration by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2914,6 +3445,7 @@ fn integral_x_cos() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x·cos(x) dx = x·sin(x) + cos(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2944,12 +3476,14 @@ fn integral_x_cos() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x·cos(x) dx = x·sin(x) + cos(x) (integration by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2960,6 +3494,7 @@ fn integral_ln_x() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫ln(x) dx = x·ln(x) - x + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -2985,12 +3520,14 @@ fn integral_ln_x() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫ln(x) dx = x·ln(x) - x (integration by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3001,43 +3538,65 @@ fn integral_x_exp_ax() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
-        description: "∫x·e^(ax) dx = (e^(ax)/a²)(ax-1) + C",
+        roots: &[ExprKind::Integral],
+        description: "∫x·e^(ax+b) dx = (e^(ax+b)/a²)(ax-1) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
-                // Match x·e^(ax) where a is constant
-                if let Expr::Mul(left, right) = inner.as_ref() {
-                    let var_on_left = matches!(left.as_ref(), Expr::Var(v) if *v == *var);
-                    let exp_on_right = matches!(right.as_ref(), Expr::Exp(_));
-                    let var_on_right = matches!(right.as_ref(), Expr::Var(v) if *v == *var);
-                    let exp_on_left = matches!(left.as_ref(), Expr::Exp(_));
-                    
-                    return (var_on_left && exp_on_right) || (var_on_right && exp_on_left);
-                }
+                // Match x·e^(ax+b) where a is free of x
+                return x_times_exp(inner, *var)
+                    .and_then(|arg| linear_coefficient(arg, *var))
+                    .is_some_and(|a| times(&a, a.clone()).is_some());
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Integral { var, expr: inner } = expr {
-                // Simplified for e^x case: ∫x·e^x dx = (x-1)·e^x (already have Rule 433)
-                // For general case, return result assuming a=1
-                let x = Expr::Var(*var);
-                let exp_x = Expr::Exp(Box::new(x.clone()));
-                
-                // (x-1)
-                let x_minus_1 = Expr::Sub(Box::new(x), Box::new(Expr::int(1)));
-                
-                // (x-1)·e^x
-                let result = Expr::Mul(Box::new(x_minus_1), Box::new(exp_x));
-                
+                let Some(arg) = x_times_exp(inner, *var) else {
+                    return vec![];
+                };
+                let Some(a) = linear_coefficient(arg, *var) else {
+                    return vec![];
+                };
+                let (Some(ax), Some(a_squared)) =
+                    (times(&a, Expr::Var(*var)), times(&a, a.clone()))
+                else {
+                    return vec![];
+                };
+                // By parts: (e^(ax+b)/a²)(ax-1), or (x-1)·e^(x+b) for a = 1
+                let exp = Expr::Exp(Box::new(arg.clone()));
+                let ax_minus_1 = Expr::Sub(Box::new(ax), Box::new(Expr::int(1)));
+                let result = match &a {
+                    Expr::Const(n) if n.is_one() => Expr::Mul(Box::new(ax_minus_1), Box::new(exp)),
+                    _ => Expr::Mul(Box::new(over(exp, &a_squared)), Box::new(ax_minus_1)),
+                };
+                let condition = match a {
+                    Expr::Const(_) => None,
+                    a => ctx.side_condition(mm_core::Constraint::nonzero(a)),
+                };
+
                 return vec![RuleApplication {
                     result,
-                    justification: "∫x·e^x dx = (x-1)·e^x (integration by parts)".to_string(),
+                    justification: "∫x·e^(ax+b) dx = (e^(ax+b)/a²)(ax-1) (integration by parts)"
+                        .to_string(),
+                    condition,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
+    }
+}
+
+/// The argument of `e^arg` in a product `var · e^arg`, either way round.
+fn x_times_exp(expr: &Expr, var: Symbol) -> Option<&Expr> {
+    let Expr::Mul(left, right) = expr else {
+        return None;
+    };
+    match (left.as_ref(), right.as_ref()) {
+        (Expr::Var(v), Expr::Exp(arg)) | (Expr::Exp(arg), Expr::Var(v)) if *v == var => Some(arg),
+        _ => None,
     }
 }
 
@@ -3052,6 +3611,7 @@ fn integral_x_over_x2_plus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x/(x²+a²) dx = (1/2)ln(x²+a²) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3077,6 +3637,7 @@ fn integral_x_over_x2_plus_a2() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫x/(x²+a²) dx = (1/2)ln(x²+a²)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -3084,6 +3645,7 @@ fn integral_x_over_x2_plus_a2() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3094,6 +3656,7 @@ fn integral_x_over_x2_minus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x/(x²-a²) dx = (1/2)ln|x²-a²| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3120,6 +3683,7 @@ fn integral_x_over_x2_minus_a2() -> Rule {
                     return vec![RuleApplication {
                         result,
                         justification: "∫x/(x²-a²) dx = (1/2)ln|x²-a²|".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -3127,6 +3691,7 @@ fn integral_x_over_x2_minus_a2() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3137,43 +3702,135 @@ fn integral_exp_ax() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
-        description: "∫e^(ax) dx = (1/a)e^(ax) + C",
+        roots: &[ExprKind::Integral],
+        description: "∫e^(ax+b) dx = (1/a)e^(ax+b) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
-                // Match e^(ax) where a is constant
+                // Match e^(ax+b) where a is a nonzero constant
                 if let Expr::Exp(arg) = inner.as_ref() {
-                    // Check if arg contains the variable (could be ax, 2x, etc.)
-                    if let Expr::Mul(_, _) = arg.as_ref() {
-                        return true;
-                    }
+                    return linear_coefficient(arg, *var).is_some();
                 }
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Integral { var, expr: inner } = expr {
                 if let Expr::Exp(arg) = inner.as_ref() {
-                    // For e^(ax), result is (1/a)e^(ax)
-                    // Extract coefficient a from ax
-                    if let Expr::Mul(coeff, _) = arg.as_ref() {
-                        // Result: (1/a)·e^(ax)
-                        let inv_coeff = Expr::Div(Box::new(Expr::int(1)), coeff.clone());
-                        let result = Expr::Mul(Box::new(inv_coeff), Box::new(*inner.clone()));
-                        
-                        return vec![RuleApplication {
-                            result,
-                            justification: "∫e^(ax) dx = (1/a)e^(ax)".to_string(),
-                        }];
-                    }
+                    let Some(a) = linear_coefficient(arg, *var) else {
+                        return vec![];
+                    };
+                    // Result: (1/a)·e^(ax+b), as e^(ax+b)/a for whole or
+                    // symbolic a; a symbolic a must not vanish
+                    let (result, condition) = match a {
+                        Expr::Const(a) => {
+                            let scaled = if a.abs().is_integer() {
+                                over(*inner.clone(), &Expr::Const(a.abs()))
+                            } else {
+                                Expr::Mul(
+                                    Box::new(Expr::Const(Rational::from(1) / a.abs())),
+                                    inner.clone(),
+                                )
+                            };
+                            let result = if a.is_negative() {
+                                Expr::Neg(Box::new(scaled))
+                            } else {
+                                scaled
+                            };
+                            (result, None)
+                        }
+                        a => (
+                            Expr::Div(inner.clone(), Box::new(a.clone())),
+                            ctx.side_condition(mm_core::Constraint::nonzero(a)),
+                        ),
+                    };
+
+                    return vec![RuleApplication {
+                        result,
+                        justification: "∫e^(ax+b) dx = (1/a)e^(ax+b)".to_string(),
+                        condition,
+                    }];
                 }
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
+/// The coefficient `a` of an argument `a·var + b` linear in `var`, unless
+/// it is zero. `a` and `b` may be any expressions free of `var`.
+fn linear_coefficient(arg: &Expr, var: Symbol) -> Option<Expr> {
+    linear_parts(arg, var)
+        .map(|(a, _)| a)
+        .filter(|a| !a.is_zero())
+}
+
+/// Split `expr` into `(a, b)` with `expr = a·var + b` and neither part
+/// containing `var`, following sums, negation, and products and quotients
+/// by factors free of `var`.
+fn linear_parts(expr: &Expr, var: Symbol) -> Option<(Expr, Expr)> {
+    if !contains_var(expr, var) {
+        return Some((Expr::int(0), expr.clone()));
+    }
+    let scale = |c: &Expr, (a, b): (Expr, Expr)| Some((times(c, a)?, times(c, b)?));
+    match expr {
+        Expr::Var(v) if *v == var => Some((Expr::int(1), Expr::int(0))),
+        Expr::Add(p, q) | Expr::Sub(p, q) => {
+            let (a1, b1) = linear_parts(p, var)?;
+            let (a2, b2) = linear_parts(q, var)?;
+            let combine = |x: Expr, y: Expr| match expr {
+                Expr::Add(..) => plus(x, y),
+                _ => plus(x, times(&Expr::int(-1), y)?),
+            };
+            Some((combine(a1, a2)?, combine(b1, b2)?))
+        }
+        Expr::Neg(p) => scale(&Expr::int(-1), linear_parts(p, var)?),
+        Expr::Mul(c, p) | Expr::Mul(p, c) if !contains_var(c, var) => {
+            scale(c, linear_parts(p, var)?)
+        }
+        Expr::Div(p, c) if !contains_var(c, var) => {
+            let (a, b) = linear_parts(p, var)?;
+            Some((divided(a, c)?, divided(b, c)?))
+        }
+        _ => None,
+    }
+}
+
+/// `x + y`, folding constants and leaving out zeros, or `None` if the
+/// constants overflow.
+fn plus(x: Expr, y: Expr) -> Option<Expr> {
+    Some(match (&x, &y) {
+        (Expr::Const(m), Expr::Const(n)) => Expr::Const(m.checked_add(n)?),
+        _ if x.is_zero() => y,
+        _ if y.is_zero() => x,
+        _ => Expr::Add(Box::new(x), Box::new(y)),
+    })
+}
+
+/// `c · x`, folding constants and leaving out factors of 1, or `None` if
+/// the constants overflow.
+fn times(c: &Expr, x: Expr) -> Option<Expr> {
+    Some(match (c, &x) {
+        (Expr::Const(m), Expr::Const(n)) => Expr::Const(m.checked_mul(n)?),
+        (Expr::Const(m), _) if m.is_one() => x,
+        (Expr::Const(m), _) if m.is_zero() => Expr::int(0),
+        (_, Expr::Const(n)) if n.is_zero() => x,
+        (_, Expr::Const(n)) if n.is_one() => c.clone(),
+        _ => Expr::Mul(Box::new(c.clone()), Box::new(x)),
+    })
+}
+
+/// `x / c`, folding constants, or `None` if they overflow.
+fn divided(x: Expr, c: &Expr) -> Option<Expr> {
+    Some(match (&x, c) {
+        (Expr::Const(m), Expr::Const(n)) if !n.is_zero() => Expr::Const(m.checked_div(n)?),
+        _ if x.is_zero() => x,
+        _ => over(x, c),
+    })
+}
+
 fn integral_one_over_x2_minus_a2() -> Rule {
     Rule {
         id: RuleId(455),
@@ -3181,17 +3838,16 @@ fn integral_one_over_x2_minus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/(x²-a²) dx = (1/2a)ln|(x-a)/(x+a)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 // Match 1/(x²-a²)
                 if let Expr::Div(num, denom) = inner.as_ref() {
                     if matches!(num.as_ref(), Expr::Const(n) if n.is_one()) {
-                        if let Expr::Sub(left, _) = denom.as_ref() {
-                            // Check if left is x²
-                            return matches!(left.as_ref(), Expr::Pow(base, exp)
-                                if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                                && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
+                        if let Expr::Sub(left, right) = denom.as_ref() {
+                            // Check if left is x² and right is constant
+                            return is_var_squared(left, *var) && !contains_var(right, *var);
                         }
                     }
                 }
@@ -3203,9 +3859,8 @@ fn integral_one_over_x2_minus_a2() -> Rule {
                 if let Expr::Div(_, denom) = inner.as_ref() {
                     if let Expr::Sub(_, right) = denom.as_ref() {
                         // For 1/(x²-a²), result is (1/2a)ln|(x-a)/(x+a)|
-                        // Simplified: assume a=1
                         let x = Expr::Var(*var);
-                        let a = Expr::Sqrt(right.clone());
+                        let a = root_of_square(right);
                         
                         // x-a
                         let x_minus_a = Expr::Sub(Box::new(x.clone()), Box::new(a.clone()));
@@ -3227,6 +3882,7 @@ fn integral_one_over_x2_minus_a2() -> Rule {
                         return vec![RuleApplication {
                             result,
                             justification: "∫1/(x²-a²) dx = (1/2a)ln|(x-a)/(x+a)| (partial fractions)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -3235,6 +3891,7 @@ fn integral_one_over_x2_minus_a2() -> Rule {
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -3251,6 +3908,7 @@ fn integral_sin_squared() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sin²(x) dx = x/2 - sin(2x)/4 + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3281,12 +3939,14 @@ fn integral_sin_squared() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sin²(x) dx = x/2 - sin(2x)/4 (reduction formula)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3297,6 +3957,7 @@ fn integral_cos_squared() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫cos²(x) dx = x/2 + sin(2x)/4 + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3327,12 +3988,14 @@ fn integral_cos_squared() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫cos²(x) dx = x/2 + sin(2x)/4 (reduction formula)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3343,6 +4006,7 @@ fn integral_tan_squared() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫tan²(x) dx = tan(x) - x + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3372,12 +4036,14 @@ fn integral_tan_squared() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫tan²(x) dx = tan(x) - x (reduction formula)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3388,6 +4054,7 @@ fn integral_sec_cubed() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫sec³(x) dx = (1/2)[sec(x)tan(x) + ln|sec(x)+tan(x)|] + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3426,12 +4093,14 @@ fn integral_sec_cubed() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫sec³(x) dx = (1/2)[sec(x)tan(x) + ln|sec(x)+tan(x)|]".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3444,6 +4113,7 @@ fn integral_x2_sin() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x²·sin(x) dx = -x²·cos(x) + 2x·sin(x) + 2cos(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3489,12 +4159,14 @@ fn integral_x2_sin() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x²·sin(x) dx = -x²·cos(x) + 2x·sin(x) + 2cos(x) (by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3505,6 +4177,7 @@ fn integral_x2_cos() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x²·cos(x) dx = x²·sin(x) + 2x·cos(x) - 2sin(x) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3550,12 +4223,14 @@ fn integral_x2_cos() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x²·cos(x) dx = x²·sin(x) + 2x·cos(x) - 2sin(x) (by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3566,6 +4241,7 @@ fn integral_exp_sin() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫e^x·sin(x) dx = (e^x/2)(sin(x) - cos(x)) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3602,12 +4278,14 @@ fn integral_exp_sin() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫e^x·sin(x) dx = (e^x/2)(sin(x) - cos(x)) (by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3618,6 +4296,7 @@ fn integral_exp_cos() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫e^x·cos(x) dx = (e^x/2)(sin(x) + cos(x)) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3654,12 +4333,14 @@ fn integral_exp_cos() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫e^x·cos(x) dx = (e^x/2)(sin(x) + cos(x)) (by parts)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3672,6 +4353,7 @@ fn integral_sqrt_a2_minus_x2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫√(a²-x²) dx = (x/2)√(a²-x²) + (a²/2)arcsin(x/a) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3679,10 +4361,7 @@ fn integral_sqrt_a2_minus_x2() -> Rule {
                 if let Expr::Sqrt(sqrt_arg) = inner.as_ref() {
                     if let Expr::Sub(left, right) = sqrt_arg.as_ref() {
                         // Check if it's const - x² or a² - x²
-                        let is_pattern = matches!(right.as_ref(), Expr::Pow(base, exp)
-                            if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                            && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
-                        return is_pattern;
+                        return !contains_var(left, *var) && is_var_squared(right, *var);
                     }
                 }
             }
@@ -3690,31 +4369,36 @@ fn integral_sqrt_a2_minus_x2() -> Rule {
         },
         apply: |expr, _ctx| {
             if let Expr::Integral { var, expr: inner } = expr {
-                // Simplified: assume a=1, result = (x/2)√(1-x²) + (1/2)arcsin(x)
+                let Expr::Sqrt(sqrt_arg) = inner.as_ref() else {
+                    return vec![];
+                };
+                let Expr::Sub(a_squared, _) = sqrt_arg.as_ref() else {
+                    return vec![];
+                };
                 let x = Expr::Var(*var);
-                let x2 = Expr::Pow(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let one_minus_x2 = Expr::Sub(Box::new(Expr::int(1)), Box::new(x2));
-                let sqrt_part = Expr::Sqrt(Box::new(one_minus_x2));
-                
-                // (x/2)√(1-x²)
+                let a = root_of_square(a_squared);
+
+                // (x/2)√(a²-x²)
                 let x_over_2 = Expr::Div(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let first_term = Expr::Mul(Box::new(x_over_2), Box::new(sqrt_part));
-                
-                // (1/2)arcsin(x)
-                let arcsin_x = Expr::Arcsin(Box::new(x));
-                let second_term = Expr::Div(Box::new(arcsin_x), Box::new(Expr::int(2)));
-                
+                let first_term = Expr::Mul(Box::new(x_over_2), inner.clone());
+
+                // (a²/2)arcsin(x/a)
+                let arcsin_part = Expr::Arcsin(Box::new(over(x, &a)));
+                let second_term = Expr::Mul(Box::new(half_of(a_squared)), Box::new(arcsin_part));
+
                 let result = Expr::Add(Box::new(first_term), Box::new(second_term));
-                
+
                 return vec![RuleApplication {
                     result,
                     justification: "∫√(a²-x²) dx = (x/2)√(a²-x²) + (a²/2)arcsin(x/a)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3725,6 +4409,7 @@ fn integral_sqrt_x2_plus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫√(x²+a²) dx = (x/2)√(x²+a²) + (a²/2)ln|x+√(x²+a²)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3732,12 +4417,8 @@ fn integral_sqrt_x2_plus_a2() -> Rule {
                 if let Expr::Sqrt(sqrt_arg) = inner.as_ref() {
                     if let Expr::Add(left, right) = sqrt_arg.as_ref() {
                         // Check if it's x² + const or const + x²
-                        let pattern1 = matches!(left.as_ref(), Expr::Pow(base, exp)
-                            if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                            && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
-                        let pattern2 = matches!(right.as_ref(), Expr::Pow(base, exp)
-                            if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                            && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
+                        let pattern1 = is_var_squared(left, *var) && !contains_var(right, *var);
+                        let pattern2 = is_var_squared(right, *var) && !contains_var(left, *var);
                         return pattern1 || pattern2;
                     }
                 }
@@ -3745,35 +4426,42 @@ fn integral_sqrt_x2_plus_a2() -> Rule {
             false
         },
         apply: |expr, _ctx| {
-            if let Expr::Integral { var, .. } = expr {
-                // Simplified: assume a=1, result = (x/2)√(x²+1) + (1/2)ln|x+√(x²+1)|
+            if let Expr::Integral { var, expr: inner } = expr {
+                let Expr::Sqrt(sqrt_arg) = inner.as_ref() else {
+                    return vec![];
+                };
+                let Expr::Add(left, right) = sqrt_arg.as_ref() else {
+                    return vec![];
+                };
+                let a_squared = if is_var_squared(left, *var) {
+                    right
+                } else {
+                    left
+                };
                 let x = Expr::Var(*var);
-                let x2 = Expr::Pow(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let x2_plus_1 = Expr::Add(Box::new(x2.clone()), Box::new(Expr::int(1)));
-                let sqrt_part = Expr::Sqrt(Box::new(x2_plus_1.clone()));
-                
-                // (x/2)√(x²+1)
+
+                // (x/2)√(x²+a²)
                 let x_over_2 = Expr::Div(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let first_term = Expr::Mul(Box::new(x_over_2), Box::new(sqrt_part.clone()));
-                
-                // ln|x+√(x²+1)|
-                let x_plus_sqrt = Expr::Add(Box::new(x), Box::new(sqrt_part));
+                let first_term = Expr::Mul(Box::new(x_over_2), inner.clone());
+
+                // (a²/2)ln|x+√(x²+a²)|
+                let x_plus_sqrt = Expr::Add(Box::new(x), inner.clone());
                 let ln_part = Expr::Ln(Box::new(Expr::Abs(Box::new(x_plus_sqrt))));
-                
-                // (1/2)ln|x+√(x²+1)|
-                let second_term = Expr::Div(Box::new(ln_part), Box::new(Expr::int(2)));
-                
+                let second_term = Expr::Mul(Box::new(half_of(a_squared)), Box::new(ln_part));
+
                 let result = Expr::Add(Box::new(first_term), Box::new(second_term));
-                
+
                 return vec![RuleApplication {
                     result,
                     justification: "∫√(x²+a²) dx = (x/2)√(x²+a²) + (a²/2)ln|x+√(x²+a²)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3784,6 +4472,7 @@ fn integral_sqrt_x2_minus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫√(x²-a²) dx = (x/2)√(x²-a²) - (a²/2)ln|x+√(x²-a²)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3791,45 +4480,44 @@ fn integral_sqrt_x2_minus_a2() -> Rule {
                 if let Expr::Sqrt(sqrt_arg) = inner.as_ref() {
                     if let Expr::Sub(left, right) = sqrt_arg.as_ref() {
                         // Check if it's x² - const
-                        return matches!(left.as_ref(), Expr::Pow(base, exp)
-                            if matches!(base.as_ref(), Expr::Var(v) if *v == *var)
-                            && matches!(exp.as_ref(), Expr::Const(n) if n.numer() == 2));
+                        return is_var_squared(left, *var) && !contains_var(right, *var);
                     }
                 }
             }
             false
         },
         apply: |expr, _ctx| {
-            if let Expr::Integral { var, .. } = expr {
-                // Simplified: assume a=1, result = (x/2)√(x²-1) - (1/2)ln|x+√(x²-1)|
+            if let Expr::Integral { var, expr: inner } = expr {
+                let Expr::Sqrt(sqrt_arg) = inner.as_ref() else {
+                    return vec![];
+                };
+                let Expr::Sub(_, a_squared) = sqrt_arg.as_ref() else {
+                    return vec![];
+                };
                 let x = Expr::Var(*var);
-                let x2 = Expr::Pow(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let x2_minus_1 = Expr::Sub(Box::new(x2), Box::new(Expr::int(1)));
-                let sqrt_part = Expr::Sqrt(Box::new(x2_minus_1));
-                
-                // (x/2)√(x²-1)
+
+                // (x/2)√(x²-a²)
                 let x_over_2 = Expr::Div(Box::new(x.clone()), Box::new(Expr::int(2)));
-                let first_term = Expr::Mul(Box::new(x_over_2), Box::new(sqrt_part.clone()));
-                
-                // ln|x+√(x²-1)|
-                let x_plus_sqrt = Expr::Add(Box::new(x), Box::new(sqrt_part));
+                let first_term = Expr::Mul(Box::new(x_over_2), inner.clone());
+
+                // (a²/2)ln|x+√(x²-a²)|
+                let x_plus_sqrt = Expr::Add(Box::new(x), inner.clone());
                 let ln_part = Expr::Ln(Box::new(Expr::Abs(Box::new(x_plus_sqrt))));
-                
-                // -(1/2)ln|x+√(x²-1)|
-                let second_term = Expr::Div(Box::new(ln_part), Box::new(Expr::int(2)));
-                let neg_second = Expr::Neg(Box::new(second_term));
-                
-                let result = Expr::Add(Box::new(first_term), Box::new(neg_second));
-                
+                let second_term = Expr::Mul(Box::new(half_of(a_squared)), Box::new(ln_part));
+
+                let result = Expr::Sub(Box::new(first_term), Box::new(second_term));
+
                 return vec![RuleApplication {
                     result,
                     justification: "∫√(x²-a²) dx = (x/2)√(x²-a²) - (a²/2)ln|x+√(x²-a²)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -3840,45 +4528,42 @@ fn integral_x_sqrt_a2_minus_x2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x·√(a²-x²) dx = -(1/3)(a²-x²)^(3/2) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 // Match x·√(a²-x²)
-                if let Expr::Mul(left, right) = inner.as_ref() {
-                    let x_sqrt = matches!(left.as_ref(), Expr::Var(v) if *v == *var)
-                        && matches!(right.as_ref(), Expr::Sqrt(_));
-                    let sqrt_x = matches!(right.as_ref(), Expr::Var(v) if *v == *var)
-                        && matches!(left.as_ref(), Expr::Sqrt(_));
-                    return x_sqrt || sqrt_x;
-                }
+                return x_times_sqrt(inner, *var).is_some_and(|arg| a2_minus_x2(arg, *var));
             }
             false
         },
         apply: |expr, _ctx| {
-            if let Expr::Integral { var, .. } = expr {
-                // Simplified: -(1/3)(1-x²)^(3/2)
-                let x = Expr::Var(*var);
-                let x2 = Expr::Pow(Box::new(x), Box::new(Expr::int(2)));
-                let one_minus_x2 = Expr::Sub(Box::new(Expr::int(1)), Box::new(x2));
-                
-                // (1-x²)^(3/2)
+            if let Expr::Integral { var, expr: inner } = expr {
+                let Some(sqrt_arg) = x_times_sqrt(inner, *var).filter(|arg| a2_minus_x2(arg, *var))
+                else {
+                    return vec![];
+                };
+
+                // (a²-x²)^(3/2)
                 let three_halves = Expr::Div(Box::new(Expr::int(3)), Box::new(Expr::int(2)));
-                let power_part = Expr::Pow(Box::new(one_minus_x2), Box::new(three_halves));
-                
-                // -(1/3)(1-x²)^(3/2)
+                let power_part = Expr::Pow(Box::new(sqrt_arg.clone()), Box::new(three_halves));
+
+                // -(1/3)(a²-x²)^(3/2)
                 let one_third = Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(3)));
                 let product = Expr::Mul(Box::new(one_third), Box::new(power_part));
                 let result = Expr::Neg(Box::new(product));
-                
+
                 return vec![RuleApplication {
                     result,
                     justification: "∫x·√(a²-x²) dx = -(1/3)(a²-x²)^(3/2)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -3889,6 +4574,7 @@ fn integral_inv_sqrt_x2_plus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/√(x²+a²) dx = ln|x+√(x²+a²)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3922,12 +4608,14 @@ fn integral_inv_sqrt_x2_plus_a2() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫1/√(x²+a²) dx = ln|x+√(x²+a²)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3938,6 +4626,7 @@ fn integral_inv_sqrt_x2_minus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫1/√(x²-a²) dx = ln|x+√(x²-a²)| + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -3973,12 +4662,14 @@ fn integral_inv_sqrt_x2_minus_a2() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫1/√(x²-a²) dx = ln|x+√(x²-a²)|".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3989,6 +4680,7 @@ fn integral_x_over_sqrt_x2_plus_a2() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫x/√(x²+a²) dx = √(x²+a²) + C",
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
@@ -4016,12 +4708,14 @@ fn integral_x_over_sqrt_x2_plus_a2() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "∫x/√(x²+a²) dx = √(x²+a²)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -4036,16 +4730,19 @@ fn limit_constant() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Const],
         description: "lim c = c",
         is_applicable: |expr, _| matches!(expr, Expr::Const(_)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "lim c = c (constant limit)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 fn limit_sum() -> Rule {
@@ -4055,16 +4752,19 @@ fn limit_sum() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Add],
         description: "lim(f+g) = lim f + lim g",
         is_applicable: |expr, _| matches!(expr, Expr::Add(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "lim(f+g) = lim f + lim g (sum of limits)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn limit_product() -> Rule {
@@ -4074,16 +4774,19 @@ fn limit_product() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Mul],
         description: "lim(fg) = lim f · lim g",
         is_applicable: |expr, _| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "lim(fg) = lim f · lim g (product of limits)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn limit_quotient() -> Rule {
@@ -4093,16 +4796,19 @@ fn limit_quotient() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Div],
         description: "lim(f/g) = lim f / lim g",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "lim(f/g) = lim f / lim g (quotient of limits)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn limit_power() -> Rule {
@@ -4112,16 +4818,19 @@ fn limit_power() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Pow],
         description: "lim(f^n) = (lim f)^n",
         is_applicable: |expr, _| matches!(expr, Expr::Pow(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "lim(f^n) = (lim f)^n (power of limit)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn limit_lhopital() -> Rule {
@@ -4131,16 +4840,19 @@ fn limit_lhopital() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Div],
         description: "L'Hôpital's rule for 0/0 or ∞/∞",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "L'Hôpital's rule: lim(f/g) = lim(f'/g') for indeterminate forms".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn limit_squeeze() -> Rule {
@@ -4150,16 +4862,19 @@ fn limit_squeeze() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Add, ExprKind::Sub],
         description: "Squeeze theorem",
         is_applicable: |expr, _| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Squeeze theorem: if g(x) ≤ f(x) ≤ h(x) and lim g = lim h = L, then lim f = L".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn taylor_exp() -> Rule {
@@ -4169,16 +4884,19 @@ fn taylor_exp() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Exp],
         description: "e^x = Σ x^n/n!",
         is_applicable: |expr, _| matches!(expr, Expr::Exp(_)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "e^x = Σ(x^n/n!) for n=0 to ∞ (Taylor series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn taylor_sin() -> Rule {
@@ -4188,16 +4906,19 @@ fn taylor_sin() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Sin],
         description: "sin(x) = Σ (-1)^n x^(2n+1)/(2n+1)!",
         is_applicable: |expr, _| matches!(expr, Expr::Sin(_)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(x) = Σ((-1)^n · x^(2n+1)/(2n+1)!) for n=0 to ∞ (Taylor series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn taylor_cos() -> Rule {
@@ -4207,16 +4928,19 @@ fn taylor_cos() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Cos],
         description: "cos(x) = Σ (-1)^n x^(2n)/(2n)!",
         is_applicable: |expr, _| matches!(expr, Expr::Cos(_)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(x) = Σ((-1)^n · x^(2n)/(2n)!) for n=0 to ∞ (Taylor series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn taylor_ln() -> Rule {
@@ -4226,16 +4950,19 @@ fn taylor_ln() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Ln],
         description: "ln(1+x) = Σ (-1)^(n+1) x^n/n",
         is_applicable: |expr, _| matches!(expr, Expr::Ln(_)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "ln(1+x) = Σ((-1)^(n+1) · x^n/n) for n=1 to ∞, |x| < 1 (Taylor series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn maclaurin_1mx() -> Rule {
@@ -4245,16 +4972,19 @@ fn maclaurin_1mx() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Div],
         description: "1/(1-x) = Σ x^n",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "1/(1-x) = Σ(x^n) for n=0 to ∞, |x| < 1 (geometric series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn geometric_series() -> Rule {
@@ -4264,16 +4994,19 @@ fn geometric_series() -> Rule {
         category: RuleCategory::Simplification,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Limit],
+        roots: &[ExprKind::Div],
         description: "Σ ar^n = a/(1-r) for |r|<1",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Σ(a·r^n) = a/(1-r) for |r| < 1 (infinite geometric series)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 fn power_series_diff() -> Rule {
@@ -4283,16 +5016,19 @@ fn power_series_diff() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::Derivative],
+        roots: &[ExprKind::Derivative],
         description: "d/dx(Σa_n x^n) = Σ n·a_n x^(n-1)",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "d/dx(Σ(a_n·x^n)) = Σ(n·a_n·x^(n-1)) (term-by-term differentiation)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn power_series_int() -> Rule {
@@ -4302,16 +5038,19 @@ fn power_series_int() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         description: "∫(Σa_n x^n)dx = Σ a_n x^(n+1)/(n+1)",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∫(Σ(a_n·x^n))dx = Σ(a_n·x^(n+1)/(n+1)) (term-by-term integration)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 fn partial_x() -> Rule {
@@ -4321,16 +5060,19 @@ fn partial_x() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative],
+        roots: &[ExprKind::Derivative],
         description: "∂f/∂x partial derivative",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∂f/∂x (partial derivative with respect to x)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 fn partial_y() -> Rule {
@@ -4340,16 +5082,19 @@ fn partial_y() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative],
+        roots: &[ExprKind::Derivative],
         description: "∂f/∂y partial derivative",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∂f/∂y (partial derivative with respect to y)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 fn partial_z() -> Rule {
@@ -4359,16 +5104,19 @@ fn partial_z() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative],
+        roots: &[ExprKind::Derivative],
         description: "∂f/∂z partial derivative",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∂f/∂z (partial derivative with respect to z)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 fn gradient() -> Rule {
@@ -4381,15 +5129,18 @@ fn gradient() -> Rule {
         domains: &[Domain::CalculusDiff], 
         // Corrected: Gradient needs Partial Derivatives and Vectors, NOT Integrals
         requires: &[Feature::PartialDerivative, Feature::Vector], 
+        roots: &[ExprKind::Derivative],
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∇f = (∂f/∂x, ∂f/∂y, ∂f/∂z) (gradient vector)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn divergence_vec() -> Rule {
@@ -4399,16 +5150,19 @@ fn divergence_vec() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "∇·F divergence of vector field",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∇·F = ∂F₁/∂x + ∂F₂/∂y + ∂F₃/∂z (divergence)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn curl_vec() -> Rule {
@@ -4418,16 +5172,19 @@ fn curl_vec() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "∇×F curl of vector field",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∇×F = curl(F) = (∂F₃/∂y - ∂F₂/∂z, ∂F₁/∂z - ∂F₃/∂x, ∂F₂/∂x - ∂F₁/∂y)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 fn laplacian() -> Rule {
@@ -4437,16 +5194,19 @@ fn laplacian() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "∇²f = ∂²f/∂x² + ∂²f/∂y² + ∂²f/∂z²",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∇²f = ∂²f/∂x² + ∂²f/∂y² + ∂²f/∂z² (Laplacian)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 fn chain_multivariable() -> Rule {
@@ -4456,16 +5216,19 @@ fn chain_multivariable() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "Multivariable chain rule",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "dz/dt = ∂z/∂x · dx/dt + ∂z/∂y · dy/dt (multivariable chain rule)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 fn implicit_diff() -> Rule {
@@ -4475,16 +5238,19 @@ fn implicit_diff() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "Implicit differentiation",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Implicit differentiation: differentiate both sides with respect to x".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn total_differential() -> Rule {
@@ -4494,16 +5260,19 @@ fn total_differential() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "df = ∂f/∂x dx + ∂f/∂y dy",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "df = ∂f/∂x dx + ∂f/∂y dy (total differential)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn directional_derivative() -> Rule {
@@ -4513,16 +5282,19 @@ fn directional_derivative() -> Rule {
         category: RuleCategory::Derivative,
         domains: &[Domain::CalculusDiff],
         requires: &[Feature::PartialDerivative, Feature::Vector],
+        roots: &[ExprKind::Derivative],
         description: "D_u f = ∇f · u",
         is_applicable: |expr, _| matches!(expr, Expr::Derivative { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "D_u f = ∇f · u (directional derivative in direction u)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 fn double_integral() -> Rule {
@@ -4532,16 +5304,19 @@ fn double_integral() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "∬∬f dA double integral",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∬∬f(x,y) dA = ∫∫f(x,y) dy dx (double integral)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 fn triple_integral() -> Rule {
@@ -4551,16 +5326,19 @@ fn triple_integral() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "∭∭∭f dV triple integral",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∭∭∭f(x,y,z) dV = ∫∫∫f(x,y,z) dz dy dx (triple integral)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 fn line_integral() -> Rule {
@@ -4570,16 +5348,19 @@ fn line_integral() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "∫_C F·dr line integral",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∫_C F·dr (line integral of vector field along curve C)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 fn surface_integral() -> Rule {
@@ -4589,16 +5370,19 @@ fn surface_integral() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "∬∬_S F·dS surface integral",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∬∬_S F·dS (surface integral of vector field over surface S)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 fn greens_theorem() -> Rule {
@@ -4608,16 +5392,19 @@ fn greens_theorem() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "Green's theorem: ∮_C = ∬∬_D",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Green's theorem: ∮_C (P dx + Q dy) = ∬∬_D (∂Q/∂x - ∂P/∂y) dA".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 fn stokes_theorem() -> Rule {
@@ -4627,16 +5414,19 @@ fn stokes_theorem() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "Stokes' theorem",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Stokes' theorem: ∮_C F·dr = ∬∬_S (∇×F)·dS".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 fn divergence_theorem() -> Rule {
@@ -4646,16 +5436,19 @@ fn divergence_theorem() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "Divergence theorem",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Divergence theorem (Gauss): ∬∬∬_V (∇·F) dV = ∬∬_S F·dS".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 5,
+        ..Default::default()
     }
 }
 fn jacobian_transform() -> Rule {
@@ -4665,16 +5458,19 @@ fn jacobian_transform() -> Rule {
         category: RuleCategory::Integral,
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral, Feature::Vector],
+        roots: &[ExprKind::Integral],
         description: "Jacobian coordinate transform",
         is_applicable: |expr, _| matches!(expr, Expr::Integral { .. }),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "∫∫f(x,y) dx dy = ∫∫f(u,v) |J| du dv where J = ∂(x,y)/∂(u,v) (Jacobian)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -4682,7 +5478,7 @@ fn jacobian_transform() -> Rule {
 // Symbolic Differentiation Function
 // ============================================================================
 
-use mm_core::{Rational, Symbol};
+use mm_core::{ExprKind, Rational, Symbol};
 
 /// Symbolically differentiate an expression with respect to a variable.
 /// Returns the derivative expression.
@@ -4764,6 +5560,46 @@ pub fn differentiate(expr: &Expr, var: Symbol) -> Expr {
             }
         }
 
+        // Hyperbolic functions (chain rule): sinh' = cosh, cosh' = sinh, tanh' = 1/cosh²
+        Expr::Sinh(inner) => Expr::Mul(
+            Box::new(Expr::Cosh(inner.clone())),
+            Box::new(differentiate(inner, var)),
+        ),
+        Expr::Cosh(inner) => Expr::Mul(
+            Box::new(Expr::Sinh(inner.clone())),
+            Box::new(differentiate(inner, var)),
+        ),
+        Expr::Tanh(inner) => Expr::Div(
+            Box::new(differentiate(inner, var)),
+            Box::new(Expr::Pow(
+                Box::new(Expr::Cosh(inner.clone())),
+                Box::new(Expr::int(2)),
+            )),
+        ),
+
+        // Inverse hyperbolic functions (chain rule)
+        Expr::Arcsinh(inner) => Expr::Div(
+            Box::new(differentiate(inner, var)),
+            Box::new(Expr::Sqrt(Box::new(Expr::Add(
+                Box::new(Expr::Pow(inner.clone(), Box::new(Expr::int(2)))),
+                Box::new(Expr::int(1)),
+            )))),
+        ),
+        Expr::Arccosh(inner) => Expr::Div(
+            Box::new(differentiate(inner, var)),
+            Box::new(Expr::Sqrt(Box::new(Expr::Sub(
+                Box::new(Expr::Pow(inner.clone(), Box::new(Expr::int(2)))),
+                Box::new(Expr::int(1)),
+            )))),
+        ),
+        Expr::Arctanh(inner) => Expr::Div(
+            Box::new(differentiate(inner, var)),
+            Box::new(Expr::Sub(
+                Box::new(Expr::int(1)),
+                Box::new(Expr::Pow(inner.clone(), Box::new(Expr::int(2)))),
+            )),
+        ),
+
         // For other expressions, return unevaluated derivative
         _ => Expr::Derivative {
             expr: Box::new(expr.clone()),
@@ -4884,7 +5720,7 @@ pub fn evaluate_at(expr: &Expr, var: Symbol, value: Rational) -> Option<Rational
             let base_val = evaluate_at(base, var, value)?;
             if let Expr::Const(n) = exp.as_ref() {
                 if n.is_integer() && n.numer() >= 0 {
-                    Some(base_val.pow(n.numer() as i32))
+                    base_val.checked_pow(i32::try_from(n.numer()).ok()?)
                 } else {
                     None
                 }
@@ -5053,6 +5889,82 @@ mod tests {
         assert_eq!(val, Some(Rational::from(12)));
     }
 
+    #[test]
+    fn test_hyperbolic_derivatives() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let ctx = RuleContext::default();
+        let vx = || Box::new(Expr::Var(x));
+
+        // d/dx(sinh(x)) = cosh(x)
+        let sinh = Expr::Sinh(vx());
+        assert_eq!(simplify(&differentiate(&sinh, x)), Expr::Cosh(vx()));
+
+        let expr = Expr::Derivative {
+            expr: Box::new(sinh),
+            var: x,
+        };
+        assert_eq!(
+            chain_rule_sinh().apply(&expr, &ctx)[0].result,
+            Expr::Cosh(vx())
+        );
+
+        // d/dx(cosh(x)) = sinh(x)
+        let expr = Expr::Derivative {
+            expr: Box::new(Expr::Cosh(vx())),
+            var: x,
+        };
+        assert!(!chain_rule_sinh().can_apply(&expr, &ctx));
+        assert_eq!(
+            chain_rule_cosh().apply(&expr, &ctx)[0].result,
+            Expr::Sinh(vx())
+        );
+    }
+
+    #[test]
+    fn test_inverse_hyperbolic_derivatives() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let ctx = RuleContext::default();
+        let mut env = mm_core::eval::Env::new();
+        env.insert(x, 0.5);
+
+        // (function, rule, derivative at x = 0.5)
+        let cases = [
+            (
+                Expr::Arcsinh(Box::new(Expr::Var(x))),
+                arcsinh_derivative(),
+                1.0 / 1.25f64.sqrt(),
+            ),
+            (
+                Expr::Arctanh(Box::new(Expr::Var(x))),
+                arctanh_derivative(),
+                1.0 / 0.75,
+            ),
+        ];
+        for (inner, rule, expected) in cases {
+            let expr = Expr::Derivative {
+                expr: Box::new(inner.clone()),
+                var: x,
+            };
+            assert!(rule.can_apply(&expr, &ctx));
+            let result = &rule.apply(&expr, &ctx)[0].result;
+            assert!((result.evaluate(&env).unwrap() - expected).abs() < 1e-12);
+            let direct = differentiate(&inner, x).evaluate(&env).unwrap();
+            assert!((direct - expected).abs() < 1e-12);
+        }
+
+        // arccosh needs x > 1: d/dx arccosh(x) at x = 2 is 1/√3
+        env.insert(x, 2.0);
+        let inner = Expr::Arccosh(Box::new(Expr::Var(x)));
+        let expr = Expr::Derivative {
+            expr: Box::new(inner.clone()),
+            var: x,
+        };
+        let result = &arccosh_derivative().apply(&expr, &ctx)[0].result;
+        assert!((result.evaluate(&env).unwrap() - 1.0 / 3f64.sqrt()).abs() < 1e-12);
+    }
+
     #[test]
     fn test_cbse_q8_max_value() {
         // CBSE Q8: Find max of f(x) = x³ - 3x + 2 on [0, 2]
@@ -5443,4 +6355,333 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results[0].result, Expr::Arcsin(Box::new(Expr::Var(x))));
     }
-}
\ No newline at end of file
+
+    /// Apply `rule` to `∫integrand dx` and check, by numerically
+    /// differentiating the result at `points`, that it is an antiderivative.
+    fn antiderivative(rule: Rule, integrand: Expr, x: Symbol, points: &[f64]) -> Expr {
+        let expr = Expr::Integral {
+            expr: Box::new(integrand.clone()),
+            var: x,
+        };
+        let ctx = RuleContext::default();
+        assert!(rule.can_apply(&expr, &ctx), "{} does not apply", rule.name);
+        let result = rule.apply(&expr, &ctx).remove(0).result;
+
+        let derivative = Expr::Derivative {
+            expr: Box::new(result.clone()),
+            var: x,
+        };
+        for &point in points {
+            let env = mm_core::eval::Env::from([(x, point)]);
+            let slope = derivative
+                .evaluate_differentiating(&env, mm_core::AngleMode::Radians)
+                .unwrap();
+            let expected = integrand.evaluate(&env).unwrap();
+            assert!(
+                (slope - expected).abs() < 1e-6,
+                "{}: derivative {} at {}, expected {}",
+                rule.name,
+                slope,
+                point,
+                expected
+            );
+        }
+        result
+    }
+
+    #[test]
+    fn test_square_root_integrals_general_a() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let var = || Box::new(Expr::Var(x));
+        let x2 = || Box::new(Expr::Pow(var(), Box::new(Expr::int(2))));
+        let int = |n| Box::new(Expr::int(n));
+        let inside = [-2.5, -1.0, 0.5, 2.0];
+        let outside = [3.5, 4.0, 6.0];
+
+        // ∫1/(4+x²) dx = (1/2)arctan(x/2)
+        let result = antiderivative(
+            integral_inv_a2_plus_x2(),
+            Expr::Div(int(1), Box::new(Expr::Add(int(4), x2()))),
+            x,
+            &inside,
+        );
+        assert_eq!(
+            result,
+            Expr::Div(
+                Box::new(Expr::Arctan(Box::new(Expr::Div(var(), int(2))))),
+                int(2)
+            )
+        );
+
+        // ∫√(9-x²) dx = (x/2)√(9-x²) + (9/2)arcsin(x/3)
+        let sqrt_9_minus_x2 = Expr::Sqrt(Box::new(Expr::Sub(int(9), x2())));
+        let result = antiderivative(
+            integral_sqrt_a2_minus_x2(),
+            sqrt_9_minus_x2.clone(),
+            x,
+            &inside,
+        );
+        assert_eq!(
+            result,
+            Expr::Add(
+                Box::new(Expr::Mul(
+                    Box::new(Expr::Div(var(), int(2))),
+                    Box::new(sqrt_9_minus_x2)
+                )),
+                Box::new(Expr::Mul(
+                    Box::new(Expr::Const(Rational::new(9, 2))),
+                    Box::new(Expr::Arcsin(Box::new(Expr::Div(var(), int(3)))))
+                ))
+            )
+        );
+
+        // The rest of the family, with a = 3
+        antiderivative(
+            integral_inv_sqrt_a2_minus_x2(),
+            Expr::Div(
+                int(1),
+                Box::new(Expr::Sqrt(Box::new(Expr::Sub(int(9), x2())))),
+            ),
+            x,
+            &inside,
+        );
+        antiderivative(
+            integral_one_over_x2_minus_a2(),
+            Expr::Div(int(1), Box::new(Expr::Sub(x2(), int(9)))),
+            x,
+            &outside,
+        );
+        antiderivative(
+            integral_sqrt_x2_plus_a2(),
+            Expr::Sqrt(Box::new(Expr::Add(x2(), int(9)))),
+            x,
+            &inside,
+        );
+        antiderivative(
+            integral_sqrt_x2_minus_a2(),
+            Expr::Sqrt(Box::new(Expr::Sub(x2(), int(9)))),
+            x,
+            &outside,
+        );
+
+        // ∫x·√(9-x²) dx = -(1/3)(9-x²)^(3/2)
+        let nine_minus_x2 = Expr::Sub(int(9), x2());
+        let result = antiderivative(
+            integral_x_sqrt_a2_minus_x2(),
+            Expr::Mul(var(), Box::new(Expr::Sqrt(Box::new(nine_minus_x2.clone())))),
+            x,
+            &inside,
+        );
+        assert_eq!(
+            result,
+            Expr::Neg(Box::new(Expr::Mul(
+                Box::new(Expr::Div(int(1), int(3))),
+                Box::new(Expr::Pow(
+                    Box::new(nine_minus_x2),
+                    Box::new(Expr::Div(int(3), int(2)))
+                ))
+            )))
+        );
+
+        // ∫1/(x√(x²-9)) dx = (1/3)arcsec(|x|/3), on both sides of the gap
+        let x_sqrt_x2_minus_9 = Expr::Mul(
+            Box::new(Expr::Sqrt(Box::new(Expr::Sub(x2(), int(9))))),
+            var(),
+        );
+        let result = antiderivative(
+            integral_inv_x_sqrt_x2_minus_a2(),
+            Expr::Div(int(1), Box::new(x_sqrt_x2_minus_9)),
+            x,
+            &[-6.0, -3.5, 3.5, 4.0, 6.0],
+        );
+        let abs_x = Box::new(Expr::Abs(var()));
+        assert_eq!(
+            result,
+            Expr::Div(
+                Box::new(Expr::Arccos(Box::new(Expr::Div(int(3), abs_x)))),
+                int(3)
+            )
+        );
+
+        // Other square roots are not this family
+        let ctx = RuleContext::default();
+        let x_sqrt_x_plus_1 = Expr::Integral {
+            expr: Box::new(Expr::Mul(
+                var(),
+                Box::new(Expr::Sqrt(Box::new(Expr::Add(var(), int(1))))),
+            )),
+            var: x,
+        };
+        assert!(!integral_x_sqrt_a2_minus_x2().can_apply(&x_sqrt_x_plus_1, &ctx));
+        let x_sqrt_9_minus_x = Expr::Integral {
+            expr: Box::new(Expr::Div(
+                int(1),
+                Box::new(Expr::Mul(
+                    var(),
+                    Box::new(Expr::Sqrt(Box::new(Expr::Sub(int(9), var())))),
+                )),
+            )),
+            var: x,
+        };
+        assert!(!integral_inv_x_sqrt_x2_minus_a2().can_apply(&x_sqrt_9_minus_x, &ctx));
+
+        // a² need not be a perfect square: ∫1/(2+x²) dx = arctan(x/√2)/√2
+        antiderivative(
+            integral_inv_a2_plus_x2(),
+            Expr::Div(int(1), Box::new(Expr::Add(x2(), int(2)))),
+            x,
+            &inside,
+        );
+
+        // a is taken nonnegative, and large constants do not overflow
+        let y = Box::new(Expr::Var(symbols.intern("y")));
+        let square = |base: Box<Expr>| Expr::Pow(base, int(2));
+        assert_eq!(root_of_square(&square(y.clone())), Expr::Abs(y));
+        assert_eq!(root_of_square(&square(int(-3))), Expr::int(3));
+        let big = Expr::int(i64::MAX);
+        assert_eq!(root_of_square(&big), Expr::Sqrt(Box::new(big.clone())));
+    }
+
+    #[test]
+    fn test_integral_x_exp_ax() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let var = || Box::new(Expr::Var(x));
+        let int = |n| Box::new(Expr::int(n));
+        let exp = |arg: Expr| Box::new(Expr::Exp(Box::new(arg)));
+        let points = [-1.5, 0.0, 0.5, 2.0];
+
+        // ∫x·e^x dx = (x-1)·e^x
+        let result = antiderivative(
+            integral_x_exp_ax(),
+            Expr::Mul(var(), exp(Expr::Var(x))),
+            x,
+            &points,
+        );
+        assert_eq!(
+            result,
+            Expr::Mul(Box::new(Expr::Sub(var(), int(1))), exp(Expr::Var(x)))
+        );
+
+        // ∫x·e^(2x) dx = (e^(2x)/4)(2x-1), either way round
+        let e_2x = exp(Expr::Mul(int(2), var()));
+        let expected = Expr::Mul(
+            Box::new(Expr::Div(e_2x.clone(), int(4))),
+            Box::new(Expr::Sub(Box::new(Expr::Mul(int(2), var())), int(1))),
+        );
+        let result = antiderivative(
+            integral_x_exp_ax(),
+            Expr::Mul(var(), e_2x.clone()),
+            x,
+            &points,
+        );
+        assert_eq!(result, expected);
+        let result = antiderivative(integral_x_exp_ax(), Expr::Mul(e_2x, var()), x, &points);
+        assert_eq!(result, expected);
+
+        // With a constant term and a negative coefficient
+        antiderivative(
+            integral_x_exp_ax(),
+            Expr::Mul(
+                var(),
+                exp(Expr::Sub(int(1), Box::new(Expr::Mul(int(3), var())))),
+            ),
+            x,
+            &points,
+        );
+
+        // A symbolic coefficient must not vanish
+        let k = Expr::Var(symbols.intern("k"));
+        let expr = Expr::Integral {
+            expr: Box::new(Expr::Mul(var(), exp(Expr::Mul(Box::new(k.clone()), var())))),
+            var: x,
+        };
+        let app = integral_x_exp_ax()
+            .apply(&expr, &RuleContext::default())
+            .remove(0);
+        assert_eq!(
+            app.condition.map(|c| c.expr),
+            Some(mm_core::Constraint::nonzero(k).expr)
+        );
+
+        // Not x·e^(ax+b)
+        let expr = Expr::Integral {
+            expr: Box::new(Expr::Mul(var(), exp(Expr::Pow(var(), int(2))))),
+            var: x,
+        };
+        assert!(!integral_x_exp_ax().can_apply(&expr, &RuleContext::default()));
+    }
+
+    #[test]
+    fn test_integral_exp_ax_coefficient() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let var = || Box::new(Expr::Var(x));
+        let int = |n| Box::new(Expr::int(n));
+        let exp = |arg: Expr| Expr::Exp(Box::new(arg));
+        let points = [-1.5, 0.0, 0.5, 2.0];
+
+        // ∫e^(2x) dx = e^(2x)/2
+        let e_2x = exp(Expr::Mul(int(2), var()));
+        let result = antiderivative(integral_exp_ax(), e_2x.clone(), x, &points);
+        assert_eq!(result, Expr::Div(Box::new(e_2x), int(2)));
+
+        // ∫e^(-x) dx = -e^(-x)
+        let e_minus_x = exp(Expr::Neg(var()));
+        let result = antiderivative(integral_exp_ax(), e_minus_x.clone(), x, &points);
+        assert_eq!(result, Expr::Neg(Box::new(e_minus_x)));
+
+        // Coefficient on the right: ∫e^(x·3) dx = e^(x·3)/3
+        let e_x3 = exp(Expr::Mul(var(), int(3)));
+        let result = antiderivative(integral_exp_ax(), e_x3.clone(), x, &points);
+        assert_eq!(result, Expr::Div(Box::new(e_x3), int(3)));
+
+        // With a constant term and a fractional coefficient
+        antiderivative(
+            integral_exp_ax(),
+            exp(Expr::Sub(int(1), Box::new(Expr::Div(var(), int(2))))),
+            x,
+            &points,
+        );
+
+        // A symbolic coefficient divides, on condition it is nonzero:
+        // ∫e^(kx) dx = e^(kx)/k
+        let ctx = RuleContext::default();
+        let integral = |integrand: &Expr| Expr::Integral {
+            expr: Box::new(integrand.clone()),
+            var: x,
+        };
+        let k = Expr::Var(symbols.intern("k"));
+        let e_kx = exp(Expr::Mul(Box::new(k.clone()), var()));
+        let app = integral_exp_ax().apply(&integral(&e_kx), &ctx).remove(0);
+        assert_eq!(app.result, Expr::Div(Box::new(e_kx), Box::new(k.clone())));
+        assert_eq!(
+            app.condition.map(|c| c.expr),
+            Some(mm_core::Constraint::nonzero(k).expr)
+        );
+
+        // Other variables in the constant term: ∫e^(2x+y) dx = e^(2x+y)/2
+        let y = Box::new(Expr::Var(symbols.intern("y")));
+        let e_2x_y = exp(Expr::Add(Box::new(Expr::Mul(int(2), var())), y));
+        let app = integral_exp_ax().apply(&integral(&e_2x_y), &ctx).remove(0);
+        assert_eq!(app.result, Expr::Div(Box::new(e_2x_y), int(2)));
+        assert!(app.condition.is_none());
+
+        // Not linear in x
+        for arg in [Expr::Pow(var(), int(2)), Expr::int(3)] {
+            let expr = Expr::Integral {
+                expr: Box::new(exp(arg)),
+                var: x,
+            };
+            assert!(!integral_exp_ax().can_apply(&expr, &ctx));
+        }
+
+        // A coefficient past i64 is left alone: e^((4000000000x)·4000000000)
+        let big = || int(4_000_000_000);
+        let e_big = exp(Expr::Mul(Box::new(Expr::Mul(big(), var())), big()));
+        assert!(!integral_exp_ax().can_apply(&integral(&e_big), &ctx));
+        let x_e_big = Expr::Mul(var(), Box::new(e_big));
+        assert!(!integral_x_exp_ax().can_apply(&integral(&x_e_big), &ctx));
+    }
+}
@@ -11,7 +11,7 @@
 //! - To prove P(x) for all real x, prove P(x) for x > 0, x = 0, and x < 0
 //! - To prove P(n) for integer n, prove P(even n) and P(odd n)
 
-use mm_core::{Expr, Symbol};
+use mm_core::{Constraint, Expr, Symbol};
 
 /// A case in case analysis
 #[derive(Debug, Clone)]
@@ -164,6 +164,34 @@ impl CaseAnalysis {
         self
     }
 
+    /// Split on the disjuncts of `P₁ ∨ P₂ ∨ ...`, one case per disjunct.
+    ///
+    /// The disjunction is taken to be known (e.g. `x < 0 ∨ x = 0 ∨ x > 0`),
+    /// so the split is exhaustive.
+    pub fn split_disjunction(mut self, disjunction: &Expr) -> Self {
+        for (i, condition) in disjuncts(disjunction).into_iter().enumerate() {
+            self.cases.push(Case {
+                name: format!("Case {}", i + 1),
+                condition,
+                goal: self.original_goal.clone(),
+                proven: false,
+                justification: None,
+            });
+        }
+        self.is_exhaustive = true;
+        self
+    }
+
+    /// Case conditions as constraints, ready for `ProofState::split_cases`.
+    pub fn constraints(&self) -> Vec<Constraint> {
+        self.cases
+            .iter()
+            .map(|c| Constraint {
+                expr: c.condition.clone(),
+            })
+            .collect()
+    }
+
     /// Mark exhaustiveness as verified
     pub fn set_exhaustive(mut self, exhaustive: bool) -> Self {
         self.is_exhaustive = exhaustive;
@@ -225,6 +253,18 @@ impl CaseAnalysis {
     }
 }
 
+/// Flatten a chain of `Or` into its disjuncts, left to right.
+pub fn disjuncts(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Or(a, b) => {
+            let mut cases = disjuncts(a);
+            cases.extend(disjuncts(b));
+            cases
+        }
+        _ => vec![expr.clone()],
+    }
+}
+
 /// Common case splits for backward reasoning
 pub fn suggest_case_splits(goal: &Expr) -> Vec<CaseSplitSuggestion> {
     let mut suggestions = Vec::new();
@@ -301,7 +341,7 @@ fn collect_vars_recursive(expr: &Expr, vars: &mut Vec<Symbol>) {
                 vars.push(*v);
             }
         }
-        Expr::Const(_) | Expr::Pi | Expr::E => {}
+        Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => {}
         Expr::Neg(e)
         | Expr::Sqrt(e)
         | Expr::Sin(e)
@@ -310,6 +350,12 @@ fn collect_vars_recursive(expr: &Expr, vars: &mut Vec<Symbol>) {
         | Expr::Arcsin(e)
         | Expr::Arccos(e)
         | Expr::Arctan(e)
+        | Expr::Sinh(e)
+        | Expr::Cosh(e)
+        | Expr::Tanh(e)
+        | Expr::Arcsinh(e)
+        | Expr::Arccosh(e)
+        | Expr::Arctanh(e)
         | Expr::Ln(e)
         | Expr::Exp(e)
         | Expr::Abs(e)
@@ -359,6 +405,16 @@ fn collect_vars_recursive(expr: &Expr, vars: &mut Vec<Symbol>) {
                 vars.push(*var);
             }
         }
+        Expr::Limit {
+            expr,
+            var,
+            approaching,
+            ..
+        } => {
+            collect_vars_recursive(expr, vars);
+            vars.retain(|v| v != var); // Remove bound variable
+            collect_vars_recursive(approaching, vars);
+        }
         Expr::ForAll { var, domain, body } | Expr::Exists { var, domain, body } => {
             if let Some(d) = domain {
                 collect_vars_recursive(d, vars);
@@ -383,6 +439,12 @@ fn collect_vars_recursive(expr: &Expr, vars: &mut Vec<Symbol>) {
             collect_vars_recursive(body, vars);
             vars.retain(|v| v != var);
         }
+        Expr::Piecewise(branches) => {
+            for (c, e) in branches {
+                collect_vars_recursive(&c.expr, vars);
+                collect_vars_recursive(e, vars);
+            }
+        }
     }
 }
 
@@ -483,4 +545,32 @@ mod tests {
         // Should suggest splits for both x and y
         assert!(suggestions.len() >= 4); // At least sign and parity for each
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_split_disjunction() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let var = || Box::new(Expr::Var(x));
+        let zero = || Box::new(Expr::int(0));
+
+        // x < 0 ∨ x = 0 ∨ x > 0
+        let disjunction = Expr::Or(
+            Box::new(Expr::Or(
+                Box::new(Expr::Lt(var(), zero())),
+                Box::new(Expr::Equation {
+                    lhs: var(),
+                    rhs: zero(),
+                }),
+            )),
+            Box::new(Expr::Gt(var(), zero())),
+        );
+
+        let analysis = CaseAnalysis::new(Expr::Gte(Box::new(Expr::Abs(var())), zero()))
+            .split_disjunction(&disjunction);
+
+        assert_eq!(analysis.cases.len(), 3);
+        assert!(analysis.is_exhaustive);
+        assert!(matches!(analysis.cases[0].condition, Expr::Lt(_, _)));
+        assert!(matches!(analysis.constraints()[2].expr, Expr::Gt(_, _)));
+    }
+}
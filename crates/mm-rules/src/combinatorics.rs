@@ -8,7 +8,7 @@
 //! Includes counting principles, binomial coefficients, and generating functions.
 
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{Expr, ExprKind, Rational};
 
 /// Returns the complete set of combinatorics rules used by the solver.
 ///
@@ -46,6 +46,7 @@ fn binomial_rules() -> Vec<Rule> {
             description: "C(n,0) = 1",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Factorial],
             is_applicable: |expr, _ctx| {
                 // Match: Div(Factorial, Factorial) patterns for binomial
                 matches!(expr, Expr::Div(_, _) | Expr::Factorial(_))
@@ -54,10 +55,12 @@ fn binomial_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: Expr::int(1),
                     justification: "C(n,0) = 1 for all n".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // C(n,n) = 1
         Rule {
@@ -67,15 +70,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "C(n,n) = 1",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Factorial],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
             apply: |_expr, _ctx| {
                 vec![RuleApplication {
                     result: Expr::int(1),
                     justification: "C(n,n) = 1 for all n".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // C(n,1) = n
         Rule {
@@ -85,15 +91,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "C(n,1) = n",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Var, ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Var(_)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "C(n,1) = n".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // C(n,k) = C(n,n-k) symmetry
         Rule {
@@ -103,15 +112,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "C(n,k) = C(n,n-k)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Binomial symmetry: C(n,k) = C(n,n-k)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 1,
+            ..Default::default()
         },
         // Pascal's identity: C(n,k) = C(n-1,k-1) + C(n-1,k)
         Rule {
@@ -121,15 +133,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "C(n,k) = C(n-1,k-1) + C(n-1,k)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Pascal's identity: C(n,k) = C(n-1,k-1) + C(n-1,k)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Hockey stick identity
         Rule {
@@ -139,15 +154,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "ΣC(i,k) for i=k to n = C(n+1,k+1)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Hockey stick identity: ΣC(i,k) = C(n+1,k+1)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Vandermonde's identity
         Rule {
@@ -157,15 +175,18 @@ fn binomial_rules() -> Vec<Rule> {
             description: "ΣC(m,k)C(n,r-k) = C(m+n,r)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vandermonde's identity: ΣC(m,k)C(n,r-k) = C(m+n,r)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         // Binomial sum: Σ C(n,k) = 2^n
         Rule {
@@ -175,6 +196,7 @@ fn binomial_rules() -> Vec<Rule> {
             description: "Σ C(n,k) for k=0 to n = 2^n",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 // Match Pow(2, n) pattern
                 if let Expr::Pow(base, _) = expr {
@@ -186,10 +208,12 @@ fn binomial_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Binomial sum: Σ C(n,k) = 2^n".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // (a+b)^n expansion (binomial theorem)
         Rule {
@@ -199,6 +223,7 @@ fn binomial_rules() -> Vec<Rule> {
             description: "(a+b)^n = Σ C(n,k) a^k b^(n-k)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(base.as_ref(), Expr::Add(_, _)) {
@@ -215,6 +240,7 @@ fn binomial_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 5,
+            ..Default::default()
         },
     ]
 }
@@ -233,15 +259,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "P(n,k) = n!/(n-k)!",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Factorial],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "P(n,k) = n!/(n-k)!".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Combinations: C(n,k) = n!/(k!(n-k)!)
         Rule {
@@ -251,15 +280,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "C(n,k) = n!/(k!(n-k)!)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Factorial],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "C(n,k) = n!/(k!(n-k)!)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Pigeonhole principle (n+1 items in n boxes)
         Rule {
@@ -269,6 +301,7 @@ fn counting_rules() -> Vec<Rule> {
             description: "n+1 items in n boxes => at least one box has 2+ items",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Gte, ExprKind::Gt],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Gt(_, _) | Expr::Gte(_, _)),
             apply: |_expr, _ctx| {
                 vec![RuleApplication {
@@ -276,10 +309,12 @@ fn counting_rules() -> Vec<Rule> {
                     justification:
                         "Pigeonhole: n+1 items in n boxes => at least one box has 2+ items"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // Generalized pigeonhole
         Rule {
@@ -289,6 +324,7 @@ fn counting_rules() -> Vec<Rule> {
             description: "n items in k boxes => some box has ≥ ⌈n/k⌉ items",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Ceiling],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Ceiling(_) | Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
@@ -296,10 +332,12 @@ fn counting_rules() -> Vec<Rule> {
                     justification:
                         "Generalized pigeonhole: n items in k boxes => some box has ≥ ⌈n/k⌉ items"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // Inclusion-exclusion for 2 sets
         Rule {
@@ -309,15 +347,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "|A ∪ B| = |A| + |B| - |A ∩ B|",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add, ExprKind::Sub],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Inclusion-exclusion: |A ∪ B| = |A| + |B| - |A ∩ B|".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Inclusion-exclusion for 3 sets
         Rule {
@@ -327,15 +368,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "|A ∪ B ∪ C| = |A|+|B|+|C| - |A∩B| - |B∩C| - |A∩C| + |A∩B∩C|",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add, ExprKind::Sub],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "3-set inclusion-exclusion".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Derangement formula
         Rule {
@@ -345,15 +389,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "D(n) = n! Σ (-1)^k/k! for k=0 to n",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Mul, ExprKind::Factorial],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "D(n) = n! Σ (-1)^k/k! - derangement formula".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Catalan number
         Rule {
@@ -363,15 +410,18 @@ fn counting_rules() -> Vec<Rule> {
             description: "C_n = C(2n,n)/(n+1)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Catalan number: C_n = C(2n,n)/(n+1)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
     ]
 }
@@ -390,15 +440,18 @@ fn recurrence_rules() -> Vec<Rule> {
             description: "F(n) = F(n-1) + F(n-2)",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Fibonacci recurrence: F(n) = F(n-1) + F(n-2)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Closed form Fibonacci (Binet's formula)
         Rule {
@@ -408,16 +461,19 @@ fn recurrence_rules() -> Vec<Rule> {
             description: "F(n) = (φ^n - ψ^n)/√5",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Div, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Binet's formula: F(n) = (φ^n - ψ^n)/√5 where φ = (1+√5)/2"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Linear recurrence solving
         Rule {
@@ -427,16 +483,19 @@ fn recurrence_rules() -> Vec<Rule> {
             description: "a_n = c1*a_{n-1} + c2*a_{n-2} => characteristic equation",
             domains: &[Domain::Combinatorics],
             requires: &[],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Solve linear recurrence via characteristic equation"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
     ]
 }
@@ -543,15 +602,18 @@ fn derangement_formula() -> Rule {
         description: "D(n) = n! * Σ(-1)^k/k!",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Mul, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "D(n) = n! * Σ(-1)^k/k! for k=0..n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -564,15 +626,18 @@ fn derangement_recurrence() -> Rule {
         description: "D(n) = (n-1)(D(n-1) + D(n-2))",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Derangement recurrence: D(n) = (n-1)(D(n-1) + D(n-2))".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -585,15 +650,18 @@ fn catalan_formula() -> Rule {
         description: "C(n) = C(2n,n)/(n+1)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Catalan formula: C(n) = C(2n,n)/(n+1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -606,15 +674,18 @@ fn catalan_recurrence() -> Rule {
         description: "C(n+1) = Σ C(i)*C(n-i)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Catalan recurrence: C(n+1) = Σ C(i)*C(n-i)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -627,16 +698,19 @@ fn stirling_first_recurrence() -> Rule {
         description: "s(n,k) = s(n-1,k-1) - (n-1)*s(n-1,k)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Sub, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Stirling 1st kind recurrence: s(n,k) = s(n-1,k-1) - (n-1)*s(n-1,k)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -649,15 +723,18 @@ fn stirling_second_recurrence() -> Rule {
         description: "S(n,k) = k*S(n-1,k) + S(n-1,k-1)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Stirling 2nd kind: S(n,k) = k*S(n-1,k) + S(n-1,k-1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -670,6 +747,7 @@ fn partition_recurrence() -> Rule {
         description: "Partition function pentagonal recurrence",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -677,10 +755,12 @@ fn partition_recurrence() -> Rule {
                 justification:
                     "Partition pentagonal recurrence: p(n) = Σ (-1)^{k+1} * p(n - k(3k-1)/2)"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -693,15 +773,18 @@ fn hockey_stick_identity() -> Rule {
         description: "Σ C(i,k) = C(n+1,k+1) (Hockey stick)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Hockey stick identity: Σ C(i,k) = C(n+1,k+1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -714,15 +797,18 @@ fn vandermonde_identity() -> Rule {
         description: "Σ C(m,k)*C(n,r-k) = C(m+n,r)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Vandermonde identity: Σ C(m,k)*C(n,r-k) = C(m+n,r)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -735,16 +821,19 @@ fn chu_vandermonde() -> Rule {
         description: "Chu-Vandermonde identity",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Mul, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Pow(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chu-Vandermonde: Σ C(a,k)*C(b,n-k)*(-1)^(n-k) = C(a-b,n)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -757,6 +846,7 @@ fn multinomial_theorem() -> Rule {
         description: "Multinomial theorem expansion",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -764,10 +854,12 @@ fn multinomial_theorem() -> Rule {
                 justification:
                     "Multinomial theorem: (x1+...+xk)^n = Σ n!/(n1!*...*nk!) * x1^n1 * ... * xk^nk"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -780,16 +872,19 @@ fn stars_and_bars() -> Rule {
         description: "Stars and bars: C(n+k-1,k)",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Stars and bars: C(n+k-1,k) ways to distribute k items into n bins"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -802,16 +897,19 @@ fn pigeonhole_principle() -> Rule {
         description: "n+1 items in n containers => at least 2 share",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Gte, ExprKind::Gt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Gt(_, _) | Expr::Gte(_, _)),
         apply: |_expr, _ctx| {
             vec![RuleApplication {
                 result: Expr::Const(Rational::from_integer(2)),
                 justification: "Pigeonhole: n+1 items in n containers => at least 2 share"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -824,16 +922,19 @@ fn inclusion_exclusion_2() -> Rule {
         description: "|A∪B| = |A| + |B| - |A∩B|",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Sub],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Inclusion-exclusion principle: |A∪B| = |A| + |B| - |A∩B|"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -846,15 +947,18 @@ fn inclusion_exclusion_3() -> Rule {
         description: "3-set inclusion-exclusion",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Sub],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "3-set inclusion-exclusion: |A∪B∪C| = |A|+|B|+|C| - |A∩B| - |A∩C| - |B∩C| + |A∩B∩C|".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -867,16 +971,19 @@ fn double_counting() -> Rule {
         description: "Count same set in two ways",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Double counting: Count the same set in two different ways"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -889,15 +996,18 @@ fn ordinary_gf() -> Rule {
         description: "Ordinary generating function",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Mul, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "OGF: Σ a_n * x^n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -910,15 +1020,18 @@ fn exponential_gf() -> Rule {
         description: "Exponential generating function",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Div, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "EGF: Σ a_n * x^n / n!".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -931,6 +1044,7 @@ fn binomial_sum_2n() -> Rule {
         description: "Σ C(n,k) = 2^n",
         domains: &[Domain::Combinatorics],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             if let Expr::Pow(base, _) = expr {
                 return matches!(base.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2));
@@ -941,10 +1055,12 @@ fn binomial_sum_2n() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Σ C(n,k) = 2^n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -957,15 +1073,18 @@ fn binomial_alternating_sum() -> Rule {
         description: "Σ (-1)^k * C(n,k) = 0",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |_expr, _ctx| {
             vec![RuleApplication {
                 result: Expr::int(0),
                 justification: "Σ (-1)^k * C(n,k) = 0 for n > 0".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -978,15 +1097,18 @@ fn permutation_formula() -> Rule {
         description: "P(n,k) = n!/(n-k)!",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Permutation formula: P(n,k) = n!/(n-k)!".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -999,15 +1121,18 @@ fn circular_permutation() -> Rule {
         description: "Circular permutations = (n-1)!",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Circular permutations: (n-1)!".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1020,15 +1145,18 @@ fn derangement_asymptotic() -> Rule {
         description: "D(n) ~ n!/e",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Factorial(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Derangement asymptotic: D(n) ~ n!/e as n -> ∞".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1041,15 +1169,18 @@ fn fibonacci_addition() -> Rule {
         description: "F(m+n) = F(m)*F(n+1) + F(m-1)*F(n)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Fibonacci addition: F(m+n) = F(m)*F(n+1) + F(m-1)*F(n)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1062,15 +1193,18 @@ fn fibonacci_gcd() -> Rule {
         description: "gcd(F(m), F(n)) = F(gcd(m,n))",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::GCD],
         is_applicable: |expr, _ctx| matches!(expr, Expr::GCD(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Fibonacci GCD: gcd(F(m), F(n)) = F(gcd(m,n))".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1092,15 +1226,18 @@ fn lucas_numbers() -> Rule {
         description: "L(n) = F(n-1) + F(n+1)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Lucas numbers: L(n) = F(n-1) + F(n+1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1128,6 +1265,7 @@ fn permutation_with_repetition() -> Rule {
         description: "Permutations with repetition: n^k",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match n^k pattern
             if let Expr::Pow(_, exp) = expr {
@@ -1139,10 +1277,12 @@ fn permutation_with_repetition() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Permutations with repetition: n choices k times = n^k".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1167,6 +1307,7 @@ fn combination_with_repetition() -> Rule {
         description: "Combinations with repetition: C(n+k-1, k)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match factorial division patterns
             matches!(expr, Expr::Div(_, _))
@@ -1176,10 +1317,12 @@ fn combination_with_repetition() -> Rule {
                 result: expr.clone(),
                 justification: "Combinations with repetition: C(n+k-1, k) = (n+k-1)!/(k!(n-1)!)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1207,16 +1350,19 @@ fn bell_number_recurrence() -> Rule {
         description: "B(n+1) = Σ C(n,k)*B(k)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Bell number recurrence: B(n+1) = Σ C(n,k)*B(k) for k=0..n"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1241,6 +1387,7 @@ fn multinomial_coefficient() -> Rule {
         description: "Multinomial: n!/(k1!k2!...km!)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match division with factorial
             if let Expr::Div(num, _) = expr {
@@ -1252,10 +1399,12 @@ fn multinomial_coefficient() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Multinomial coefficient: n!/(k1!k2!...km!)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1291,6 +1440,7 @@ fn binomial_weighted_sum() -> Rule {
         description: "Σ k*C(n,k) = n*2^(n-1)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             // Match multiplication pattern
             if let Expr::Mul(left, right) = expr {
@@ -1310,10 +1460,12 @@ fn binomial_weighted_sum() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Σ k*C(n,k) = n*2^(n-1) for k=0..n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1342,15 +1494,18 @@ fn subfactorial() -> Rule {
         description: "Subfactorial !n = D(n)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Subfactorial: !n = D(n) = ⌊n!/e + 1/2⌋".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1373,15 +1528,18 @@ fn christmas_stocking() -> Rule {
         description: "C(n,m)*C(m,k) = C(n,k)*C(n-k,m-k)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Christmas stocking: C(n,m)*C(m,k) = C(n,k)*C(n-k,m-k)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1406,6 +1564,7 @@ fn binomial_squares_sum() -> Rule {
         description: "Σ C(n,k)^2 = C(2n,n)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match division pattern for binomial coefficient
             matches!(expr, Expr::Div(_, _) | Expr::Pow(_, _))
@@ -1414,10 +1573,12 @@ fn binomial_squares_sum() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Σ C(n,k)^2 = C(2n,n) for k=0..n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1441,15 +1602,18 @@ fn rising_factorial() -> Rule {
         description: "Rising factorial (x)_n",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Rising factorial: (x)_n = x(x+1)(x+2)...(x+n-1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1489,15 +1653,18 @@ fn falling_factorial() -> Rule {
         description: "Falling factorial (x)^n",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Falling factorial: x^(n) = x(x-1)(x-2)...(x-n+1)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1521,16 +1688,19 @@ fn legendre_formula() -> Rule {
         description: "Legendre: vp(n!) = Σ ⌊n/p^k⌋",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Floor],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Floor(_) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Legendre's formula: highest power of p dividing n! is Σ ⌊n/p^k⌋"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1554,6 +1724,7 @@ fn kummer_theorem() -> Rule {
         description: "Kummer: vp(C(m+n,m)) = carries in base p",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Mod],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mod(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1561,10 +1732,12 @@ fn kummer_theorem() -> Rule {
                 justification:
                     "Kummer: vp(C(m+n,m)) equals number of carries when adding m and n in base p"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1588,6 +1761,7 @@ fn lucas_theorem() -> Rule {
         description: "Lucas: C(m,n) mod p",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             if let Expr::Mod(inner, _) = expr {
                 return matches!(inner.as_ref(), Expr::Div(_, _));
@@ -1600,10 +1774,12 @@ fn lucas_theorem() -> Rule {
                 justification:
                     "Lucas' theorem: C(m,n) mod p = Π C(mi,ni) mod p where m,n in base p"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1628,15 +1804,18 @@ fn burnside_lemma() -> Rule {
         description: "Burnside: |X/G| = (1/|G|) Σ |X^g|",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Burnside's lemma: |X/G| = (1/|G|) Σ |X^g| for g in G".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1660,6 +1839,7 @@ fn polya_enumeration() -> Rule {
         description: "Polya enumeration theorem",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Mul, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1667,10 +1847,12 @@ fn polya_enumeration() -> Rule {
                 justification:
                     "Polya enumeration: count inequivalent configurations under group action"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1694,6 +1876,7 @@ fn catalan_alternative() -> Rule {
         description: "C_n = (2n)!/(n!(n+1)!)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             if let Expr::Div(num, _) = expr {
                 return matches!(num.as_ref(), Expr::Factorial(_));
@@ -1704,10 +1887,12 @@ fn catalan_alternative() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Catalan alternative: C_n = (2n)!/(n!(n+1)!)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1732,15 +1917,18 @@ fn partition_into_parts() -> Rule {
         description: "p(n,k) = p(n-1,k-1) + p(n-k,k)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Partition recurrence: p(n,k) = p(n-1,k-1) + p(n-k,k)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1778,6 +1966,7 @@ fn pattern_avoidance() -> Rule {
         description: "Permutations avoiding pattern",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div, ExprKind::Factorial],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1785,10 +1974,12 @@ fn pattern_avoidance() -> Rule {
                 justification:
                     "Pattern-avoiding permutations counted by Catalan or similar sequences"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1814,16 +2005,19 @@ fn derangement_simple_recurrence() -> Rule {
         description: "D(n) = n*D(n-1) + (-1)^n",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Derangement simple recurrence: D(n) = n*D(n-1) + (-1)^n"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1849,6 +2043,7 @@ fn fibonacci_generating_function() -> Rule {
         description: "Fibonacci GF: x/(1-x-x^2)",
         domains: &[Domain::Combinatorics],
         requires: &[Feature::Combinatorics],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match division with polynomial
             if let Expr::Div(_, denom) = expr {
@@ -1862,9 +2057,11 @@ fn fibonacci_generating_function() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Fibonacci generating function: Σ F_n x^n = x/(1-x-x^2)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
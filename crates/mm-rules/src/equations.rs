@@ -7,7 +7,7 @@
 //! Equation solving rules and transformations.
 
 use crate::{Domain, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational, Symbol};
+use mm_core::{Expr, ExprKind, Rational, Symbol};
 
 /// Get all equation solving rules.
 pub fn equation_rules() -> Vec<Rule> {
@@ -34,6 +34,7 @@ fn isolate_variable() -> Rule {
         description: "Move terms to isolate variable: ax + b = c → ax = c - b",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             if let Expr::Equation { lhs, rhs: _ } = expr {
                 // Check if LHS has addition/subtraction with constant
@@ -57,6 +58,7 @@ fn isolate_variable() -> Rule {
                                 rhs: Box::new(Expr::Sub(rhs.clone(), b.clone())),
                             },
                             justification: "Subtract constant from both sides".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -69,6 +71,7 @@ fn isolate_variable() -> Rule {
                                 rhs: Box::new(Expr::Add(rhs.clone(), b.clone())),
                             },
                             justification: "Add constant to both sides".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -77,6 +80,7 @@ fn isolate_variable() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 // ============================================================================
@@ -91,6 +95,7 @@ fn cancel_addition() -> Rule {
         description: "Cancel addition: x + a = b → x = b - a",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             if let Expr::Equation { lhs, .. } = expr {
                 return matches!(lhs.as_ref(), Expr::Add(_, _));
@@ -106,6 +111,7 @@ fn cancel_addition() -> Rule {
                             rhs: Box::new(Expr::Sub(rhs.clone(), a.clone())),
                         },
                         justification: "x + a = b → x = b - a".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -113,6 +119,7 @@ fn cancel_addition() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -128,6 +135,7 @@ fn cancel_subtraction() -> Rule {
         description: "Cancel subtraction: x - a = b → x = b + a",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             if let Expr::Equation { lhs, .. } = expr {
                 return matches!(lhs.as_ref(), Expr::Sub(_, _));
@@ -143,6 +151,7 @@ fn cancel_subtraction() -> Rule {
                             rhs: Box::new(Expr::Add(rhs.clone(), a.clone())),
                         },
                         justification: "x - a = b → x = b + a".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -150,6 +159,7 @@ fn cancel_subtraction() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -165,6 +175,7 @@ fn cancel_multiplication() -> Rule {
         description: "Cancel multiplication: ax = b -> x = b/a (or xa = b -> x = b/a)",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             if let Expr::Equation { lhs, .. } = expr {
                 if let Expr::Mul(a, b) = lhs.as_ref() {
@@ -191,6 +202,7 @@ fn cancel_multiplication() -> Rule {
                                     rhs: Box::new(Expr::Div(rhs.clone(), a.clone())),
                                 },
                                 justification: format!("Divide both sides by {}", c),
+                                condition: None,
                             }];
                         }
                     }
@@ -203,6 +215,7 @@ fn cancel_multiplication() -> Rule {
                                     rhs: Box::new(Expr::Div(rhs.clone(), b.clone())),
                                 },
                                 justification: format!("Divide both sides by {}", c),
+                                condition: None,
                             }];
                         }
                     }
@@ -212,6 +225,7 @@ fn cancel_multiplication() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -227,6 +241,7 @@ fn cancel_division() -> Rule {
         description: "Cancel division: x/a = b → x = ab",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             if let Expr::Equation { lhs, .. } = expr {
                 if let Expr::Div(_, a) = lhs.as_ref() {
@@ -244,6 +259,7 @@ fn cancel_division() -> Rule {
                             rhs: Box::new(Expr::Mul(rhs.clone(), a.clone())),
                         },
                         justification: "x/a = b → x = ab".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -251,6 +267,7 @@ fn cancel_division() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -266,6 +283,7 @@ fn linear_solve() -> Rule {
         description: "Solve linear equation: ax + b = c → x = (c-b)/a",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             // Check for ax + b = c pattern
             if let Expr::Equation { lhs, rhs } = expr {
@@ -299,6 +317,7 @@ fn linear_solve() -> Rule {
                                         "x = ({} - {}) / {} = {}",
                                         c_val, b_val, a_val, solution
                                     ),
+                                    condition: None,
                                 }];
                             }
                         }
@@ -309,6 +328,7 @@ fn linear_solve() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -324,6 +344,7 @@ fn quadratic_formula() -> Rule {
         description: "Quadratic formula: ax² + bx + c = 0 → x = (-b ± √(b²-4ac))/(2a)",
         domains: &[Domain::Equations],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             // Check for ax^2 + bx + c = 0 pattern
             if let Expr::Equation { rhs, .. } = expr {
@@ -342,6 +363,7 @@ fn quadratic_formula() -> Rule {
                             return vec![RuleApplication {
                                 result: expr.clone(),
                                 justification: "No real solutions (discriminant < 0)".to_string(),
+                                condition: None,
                             }];
                         }
 
@@ -364,6 +386,7 @@ fn quadratic_formula() -> Rule {
                                 "Quadratic formula: x = (-{} + √{}) / {}",
                                 b, discriminant, two_a
                             ),
+                            condition: None,
                         }];
                     }
                 }
@@ -372,6 +395,7 @@ fn quadratic_formula() -> Rule {
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 
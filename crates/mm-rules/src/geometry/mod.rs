@@ -15,7 +15,7 @@
 //! Full transformation requires a FactBank with geometric context.
 
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleContext, RuleId};
-use mm_core::Expr;
+use mm_core::{Expr, ExprKind};
 
 /// Create all geometry rules.
 pub fn geometry_rules() -> Vec<Rule> {
@@ -47,6 +47,7 @@ fn parabola_rules() -> Vec<Rule> {
             description: "Tangent to parabola y²=4ax at parameter t: ty = x + at²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Detect y² on left side of equation (parabola signature)
                 if let Expr::Equation { lhs, rhs: _ } = expr {
@@ -59,10 +60,12 @@ fn parabola_rules() -> Vec<Rule> {
                     result: expr.clone(),
                     justification: "Parabola detected. Tangent at parameter t: ty = x + at²"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5002),
@@ -71,6 +74,7 @@ fn parabola_rules() -> Vec<Rule> {
             description: "Normal to parabola y²=4ax at parameter t: y + tx = 2at + at³",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 if let Expr::Equation { lhs, rhs: _ } = expr {
                     return is_y_squared(lhs);
@@ -82,10 +86,12 @@ fn parabola_rules() -> Vec<Rule> {
                     result: expr.clone(),
                     justification: "Parabola detected. Normal at parameter t: y + tx = 2at + at³"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5003),
@@ -94,6 +100,7 @@ fn parabola_rules() -> Vec<Rule> {
             description: "Focal chord property: 1/SP + 1/SQ = 2/l (l = semi-latus rectum)",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match 1/a + 1/b pattern
                 if let Expr::Add(left, right) = expr {
@@ -105,10 +112,12 @@ fn parabola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Focal chord reciprocal property: 1/SP + 1/SQ = 2/l".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5004),
@@ -117,10 +126,12 @@ fn parabola_rules() -> Vec<Rule> {
             description: "Parabola reflection: tangent bisects angle between focal radius and axis",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |_expr, _ctx| false, // Informational only
             apply: |_expr, _ctx| vec![],
             reversible: false,
             cost: 5,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5005),
@@ -129,6 +140,7 @@ fn parabola_rules() -> Vec<Rule> {
             description: "Chord of contact from (x₁,y₁): yy₁ = 2a(x + x₁)",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 if let Expr::Equation { lhs, rhs: _ } = expr {
                     return is_y_squared(lhs);
@@ -140,10 +152,12 @@ fn parabola_rules() -> Vec<Rule> {
                     result: expr.clone(),
                     justification: "Chord of contact from external point: yy₁ = 2a(x + x₁)"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
     ]
 }
@@ -161,6 +175,7 @@ fn ellipse_rules() -> Vec<Rule> {
             description: "Tangent to ellipse at θ: (x cos θ)/a + (y sin θ)/b = 1",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Match x²/a² + y²/b² = 1 (sum of two quotients = 1)
                 if let Expr::Equation { lhs, rhs } = expr {
@@ -177,10 +192,12 @@ fn ellipse_rules() -> Vec<Rule> {
                     result: expr.clone(),
                     justification: "Ellipse tangent at parameter θ: (x cos θ)/a + (y sin θ)/b = 1"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5102),
@@ -189,6 +206,7 @@ fn ellipse_rules() -> Vec<Rule> {
             description: "Ellipse eccentricity: e = √(1 - b²/a²)",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 // Match sqrt(1 - something)
                 if let Expr::Sqrt(inner) = expr {
@@ -202,10 +220,12 @@ fn ellipse_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Ellipse eccentricity formula: e = √(1 - b²/a²)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5103),
@@ -214,15 +234,18 @@ fn ellipse_rules() -> Vec<Rule> {
             description: "Director circle of ellipse: x² + y² = a² + b²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| is_circle_equation(expr),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Director circle: x² + y² = a² + b²".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5104),
@@ -231,15 +254,18 @@ fn ellipse_rules() -> Vec<Rule> {
             description: "Auxiliary circle of ellipse: x² + y² = a²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| is_circle_equation(expr),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Auxiliary circle: x² + y² = a²".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5105),
@@ -248,6 +274,7 @@ fn ellipse_rules() -> Vec<Rule> {
             description: "Sum of focal distances: SP + S'P = 2a",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match sum of two terms
                 matches!(expr, Expr::Add(_, _))
@@ -256,10 +283,12 @@ fn ellipse_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Ellipse property: SP + S'P = 2a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -277,6 +306,7 @@ fn hyperbola_rules() -> Vec<Rule> {
             description: "Asymptotes of hyperbola: y = ±(b/a)x",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Match x²/a² - y²/b² = 1 (subtraction pattern)
                 if let Expr::Equation { lhs, rhs } = expr {
@@ -292,10 +322,12 @@ fn hyperbola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Hyperbola asymptotes: y = ±(b/a)x".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5202),
@@ -304,6 +336,7 @@ fn hyperbola_rules() -> Vec<Rule> {
             description: "Hyperbola eccentricity: e = √(1 + b²/a²)",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 // Match sqrt(1 + something)
                 if let Expr::Sqrt(inner) = expr {
@@ -317,10 +350,12 @@ fn hyperbola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Hyperbola eccentricity: e = √(1 + b²/a²)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5203),
@@ -329,6 +364,7 @@ fn hyperbola_rules() -> Vec<Rule> {
             description: "Rectangular hyperbola (a=b): xy = c²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Match xy pattern on left side
                 if let Expr::Equation { lhs, rhs: _ } = expr {
@@ -342,10 +378,12 @@ fn hyperbola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Rectangular hyperbola: xy = c²".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5204),
@@ -354,6 +392,7 @@ fn hyperbola_rules() -> Vec<Rule> {
             description: "Difference of focal distances: |SP - S'P| = 2a",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 // Match |a - b| pattern
                 if let Expr::Abs(inner) = expr {
@@ -365,10 +404,12 @@ fn hyperbola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Hyperbola property: |SP - S'P| = 2a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5205),
@@ -377,6 +418,7 @@ fn hyperbola_rules() -> Vec<Rule> {
             description: "Conjugate hyperbola: x²/a² - y²/b² = -1",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Match = -1 on right side
                 if let Expr::Equation { lhs: _, rhs } = expr {
@@ -390,10 +432,12 @@ fn hyperbola_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Conjugate hyperbola detected".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -411,15 +455,18 @@ fn circle_rules() -> Vec<Rule> {
             description: "Tangent to circle at (x₁,y₁): xx₁ + yy₁ = r²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| is_circle_equation(expr),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Circle tangent at (x₁,y₁): xx₁ + yy₁ = r²".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5302),
@@ -428,6 +475,7 @@ fn circle_rules() -> Vec<Rule> {
             description: "Power of point: PA · PB = d² - r²",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 // Match product pattern
                 matches!(expr, Expr::Mul(_, _))
@@ -436,10 +484,12 @@ fn circle_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Power of point: PA · PB = d² - r²".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5303),
@@ -448,10 +498,12 @@ fn circle_rules() -> Vec<Rule> {
             description: "Radical axis: S₁ - S₂ = 0",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |_expr, _ctx| false, // Needs two-circle context
             apply: |_expr, _ctx| vec![],
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5304),
@@ -460,10 +512,12 @@ fn circle_rules() -> Vec<Rule> {
             description: "Orthogonal circles: 2g₁g₂ + 2f₁f₂ = c₁ + c₂",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Equation],
             is_applicable: |_expr, _ctx| false, // Needs two-circle context
             apply: |_expr, _ctx| vec![],
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         Rule {
             id: RuleId(5305),
@@ -472,6 +526,7 @@ fn circle_rules() -> Vec<Rule> {
             description: "Tangent length from (x₁,y₁): √(x₁² + y₁² - r²)",
             domains: &[Domain::Geometry],
             requires: &[Feature::ConicSection],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 // Match √(a² + b² - c²) pattern
                 if let Expr::Sqrt(inner) = expr {
@@ -485,10 +540,12 @@ fn circle_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Tangent length from external point".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -507,6 +564,7 @@ fn coordinate_rules() -> Vec<Rule> {
             description: "Distance: d = √((x₂-x₁)² + (y₂-y₁)²)",
             domains: &[Domain::Geometry],
             requires: &[],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 // Match √(a² + b²) pattern
                 if let Expr::Sqrt(inner) = expr {
@@ -531,6 +589,7 @@ fn coordinate_rules() -> Vec<Rule> {
                                 return vec![RuleApplication {
                                     result: Expr::Sqrt(Box::new(Expr::Const(sum))),
                                     justification: "Distance formula: √(a² + b²)".to_string(),
+                                    condition: None,
                                 }];
                             }
                         }
@@ -539,10 +598,12 @@ fn coordinate_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Distance formula pattern: √(Δx² + Δy²)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 1,
+            ..Default::default()
         },
         // Section formula
         Rule {
@@ -552,6 +613,7 @@ fn coordinate_rules() -> Vec<Rule> {
             description: "Internal division m:n: ((mx₂+nx₁)/(m+n), (my₂+ny₁)/(m+n))",
             domains: &[Domain::Geometry],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match (a + b)/(c + d) pattern
                 if let Expr::Div(num, den) = expr {
@@ -567,10 +629,12 @@ fn coordinate_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Section formula: point dividing line in ratio m:n".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Triangle area
         Rule {
@@ -580,6 +644,7 @@ fn coordinate_rules() -> Vec<Rule> {
             description: "Triangle area: ½|x₁(y₂-y₃) + x₂(y₃-y₁) + x₃(y₁-y₂)|",
             domains: &[Domain::Geometry],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 // Match ½ * |...| pattern
                 if let Expr::Mul(half, abs_part) = expr {
@@ -593,10 +658,12 @@ fn coordinate_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Triangle area using coordinate formula".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Collinearity
         Rule {
@@ -606,6 +673,7 @@ fn coordinate_rules() -> Vec<Rule> {
             description: "Collinear if area = 0: x₁(y₂-y₃) + x₂(y₃-y₁) + x₃(y₁-y₂) = 0",
             domains: &[Domain::Geometry],
             requires: &[],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 // Match expr = 0
                 if let Expr::Equation { lhs: _, rhs } = expr {
@@ -617,10 +685,12 @@ fn coordinate_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Collinearity condition: determinant = 0".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Centroid
         Rule {
@@ -630,6 +700,7 @@ fn coordinate_rules() -> Vec<Rule> {
             description: "Centroid: ((x₁+x₂+x₃)/3, (y₁+y₂+y₃)/3)",
             domains: &[Domain::Geometry],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match (a + b + c)/3 pattern
                 if let Expr::Div(num, den) = expr {
@@ -643,10 +714,12 @@ fn coordinate_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Centroid formula: (sum of coordinates)/3".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 1,
+            ..Default::default()
         },
     ]
 }
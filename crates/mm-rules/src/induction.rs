@@ -11,7 +11,7 @@
 //! - Strong induction: (∀k. (∀j < k. P(j)) → P(k)) → ∀n. P(n)
 //! - Starting from 1: P(1) ∧ (∀k≥1. P(k) → P(k+1)) → ∀n≥1. P(n)
 
-use mm_core::{Expr, Symbol, SymbolTable};
+use mm_core::{Constraint, Expr, Symbol, SymbolTable};
 
 /// Type of induction to use
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -211,6 +211,19 @@ impl InductionProof {
             Expr::Ceiling(e) => Expr::Ceiling(Box::new(self.substitute(e, var, value))),
             Expr::Factorial(e) => Expr::Factorial(Box::new(self.substitute(e, var, value))),
             Expr::Not(e) => Expr::Not(Box::new(self.substitute(e, var, value))),
+            Expr::Piecewise(branches) => Expr::Piecewise(
+                branches
+                    .iter()
+                    .map(|(c, e)| {
+                        (
+                            Constraint {
+                                expr: self.substitute(&c.expr, var, value),
+                            },
+                            self.substitute(e, var, value),
+                        )
+                    })
+                    .collect(),
+            ),
 
             Expr::Add(a, b) => Expr::Add(
                 Box::new(self.substitute(a, var, value)),
@@ -8,7 +8,7 @@
 //! Includes AM-GM, Cauchy-Schwarz, Jensen's, triangle inequality, and more.
 
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{Expr, ExprKind, Rational};
 
 /// Aggregates all available inequality rules into a single list.
 ///
@@ -50,6 +50,7 @@ fn am_gm_rules() -> Vec<Rule> {
             description: "AM-GM: (a+b)/2 ≥ √(ab), so a+b ≥ 2√(ab)",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match a + b pattern where we might apply AM-GM
                 // BUT NOT for pure arithmetic (no variables)
@@ -95,12 +96,14 @@ fn am_gm_rules() -> Vec<Rule> {
                     return vec![RuleApplication {
                         result: two_sqrt_ab,
                         justification: "AM-GM: a + b ≥ 2√(ab), equality iff a = b".to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // a^2 + b^2 >= 2ab (derived from AM-GM)
         Rule {
@@ -110,6 +113,7 @@ fn am_gm_rules() -> Vec<Rule> {
             description: "a² + b² ≥ 2ab (from (a-b)² ≥ 0)",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(a, b) = expr {
                     let a_is_sq = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)));
@@ -129,6 +133,7 @@ fn am_gm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: two_ab,
                             justification: "a² + b² ≥ 2ab (lower bound)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -136,6 +141,7 @@ fn am_gm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // a² + b² + c² >= ab + bc + ca
         Rule {
@@ -145,6 +151,7 @@ fn am_gm_rules() -> Vec<Rule> {
             description: "a² + b² + c² ≥ ab + bc + ca",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match (a² + b²) + c² or similar 3-term sum of squares
                 fn is_square(e: &Expr) -> bool {
@@ -194,6 +201,7 @@ fn am_gm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: sum,
                             justification: "a² + b² + c² ≥ ab + bc + ca (lower bound)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -201,6 +209,7 @@ fn am_gm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // For positive reals: a/b + b/a >= 2
         Rule {
@@ -210,6 +219,7 @@ fn am_gm_rules() -> Vec<Rule> {
             description: "a/b + b/a ≥ 2 for positive a,b",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(left, right) = expr {
                     if let (Expr::Div(a1, b1), Expr::Div(b2, a2)) = (left.as_ref(), right.as_ref())
@@ -227,6 +237,7 @@ fn am_gm_rules() -> Vec<Rule> {
                             return vec![RuleApplication {
                                 result: Expr::int(2),
                                 justification: "a/b + b/a ≥ 2 (minimum)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -235,6 +246,7 @@ fn am_gm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // (a+b+c)/3 >= cbrt(abc) - AM-GM for 3 terms
         Rule {
@@ -244,6 +256,7 @@ fn am_gm_rules() -> Vec<Rule> {
             description: "AM-GM for 3 terms: (a+b+c)/3 ≥ ∛(abc)",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match (a + b + c) / 3 or ((a + b) + c) / 3
                 if let Expr::Div(num, denom) = expr {
@@ -282,6 +295,7 @@ fn am_gm_rules() -> Vec<Rule> {
                             result: cube_root,
                             justification: "AM-GM: (a+b+c)/3 ≥ ∛(abc), equality iff a=b=c"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -289,6 +303,7 @@ fn am_gm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
     ]
 }
@@ -308,6 +323,7 @@ fn cauchy_schwarz_rules() -> Vec<Rule> {
             description: "Cauchy-Schwarz: (a²+b²)(c²+d²) ≥ (ac+bd)²",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 // Match (a² + b²)(c² + d²) pattern
                 if let Expr::Mul(left, right) = expr {
@@ -324,6 +340,7 @@ fn cauchy_schwarz_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Engel form / Titu's Lemma: a²/x + b²/y >= (a+b)²/(x+y)
         Rule {
@@ -333,6 +350,7 @@ fn cauchy_schwarz_rules() -> Vec<Rule> {
             description: "Titu's Lemma: a²/x + b²/y ≥ (a+b)²/(x+y)",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match a²/x + b²/y pattern
                 fn is_sq_over_var(e: &Expr) -> bool {
@@ -363,6 +381,7 @@ fn cauchy_schwarz_rules() -> Vec<Rule> {
                                 justification:
                                     "Titu's Lemma: a²/x + b²/y ≥ (a+b)²/(x+y) (lower bound)"
                                         .to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -371,6 +390,7 @@ fn cauchy_schwarz_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
     ]
 }
@@ -390,6 +410,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
             description: "|a + b| ≤ |a| + |b|",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Add(_, _));
@@ -405,6 +426,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
                                 Box::new(Expr::Abs(b.clone())),
                             ),
                             justification: "|a + b| ≤ |a| + |b| (upper bound)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -412,6 +434,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // |a - b| >= ||a| - |b||
         Rule {
@@ -421,6 +444,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
             description: "|a - b| ≥ ||a| - |b||",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Sub(_, _));
@@ -436,6 +460,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
                                 Box::new(Expr::Abs(b.clone())),
                             ))),
                             justification: "|a - b| ≥ ||a| - |b|| (lower bound)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -443,6 +468,7 @@ fn triangle_inequality_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -461,18 +487,21 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "|a| ≥ 0 for all a",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Abs(_)),
             apply: |expr, _ctx| {
                 if let Expr::Abs(_) = expr {
                     return vec![RuleApplication {
                         result: Expr::Gte(Box::new(expr.clone()), Box::new(Expr::int(0))),
                         justification: "|a| ≥ 0 always holds".to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // |a*b| = |a|*|b|
         Rule {
@@ -482,6 +511,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "|a·b| = |a|·|b|",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Mul(_, _));
@@ -497,6 +527,7 @@ fn absolute_value_rules() -> Vec<Rule> {
                                 Box::new(Expr::Abs(b.clone())),
                             ),
                             justification: "|a·b| = |a|·|b|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -504,6 +535,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // |a/b| = |a|/|b|
         Rule {
@@ -513,6 +545,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "|a/b| = |a|/|b|",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Div(_, _));
@@ -528,6 +561,7 @@ fn absolute_value_rules() -> Vec<Rule> {
                                 Box::new(Expr::Abs(b.clone())),
                             ),
                             justification: "|a/b| = |a|/|b|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -535,6 +569,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // |-a| = |a|
         Rule {
@@ -544,6 +579,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "|-a| = |a|",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Neg(_));
@@ -556,6 +592,7 @@ fn absolute_value_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "|-a| = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -563,6 +600,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // ||a|| = |a|
         Rule {
@@ -572,6 +610,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "||a|| = |a|",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Abs],
             is_applicable: |expr, _ctx| {
                 if let Expr::Abs(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Abs(_));
@@ -584,6 +623,7 @@ fn absolute_value_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "||a|| = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -591,6 +631,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // |a|² = a²
         Rule {
@@ -600,6 +641,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             description: "|a|² = a²",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)) {
@@ -614,6 +656,7 @@ fn absolute_value_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Pow(inner.clone(), exp.clone()),
                             justification: "|a|² = a²".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -621,6 +664,7 @@ fn absolute_value_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
     ]
 }
@@ -639,6 +683,7 @@ fn square_inequality_rules() -> Vec<Rule> {
             description: "a² ≥ 0 for all real a",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(_, exp) = expr {
                     return matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2));
@@ -651,12 +696,14 @@ fn square_inequality_rules() -> Vec<Rule> {
                         result: Expr::Gte(Box::new(expr.clone()), Box::new(Expr::int(0))),
                         justification: "a² ≥ 0 for all real a (squares are non-negative)"
                             .to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // a² = 0 iff a = 0
         Rule {
@@ -666,6 +713,7 @@ fn square_inequality_rules() -> Vec<Rule> {
             description: "a² = 0 ⟺ a = 0",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Equation],
             is_applicable: |expr, _ctx| {
                 if let Expr::Equation { lhs, rhs } = expr {
                     if matches!(rhs.as_ref(), Expr::Const(c) if c.is_zero()) {
@@ -685,6 +733,7 @@ fn square_inequality_rules() -> Vec<Rule> {
                                 rhs: Box::new(Expr::int(0)),
                             },
                             justification: "a² = 0 ⟺ a = 0".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -692,6 +741,7 @@ fn square_inequality_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // (a-b)² >= 0, so a² + b² >= 2ab
         Rule {
@@ -701,6 +751,7 @@ fn square_inequality_rules() -> Vec<Rule> {
             description: "(a-b)² ≥ 0",
             domains: &[Domain::Inequalities],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 // Match (a-b)² pattern
                 if let Expr::Pow(base, exp) = expr {
@@ -719,6 +770,7 @@ fn square_inequality_rules() -> Vec<Rule> {
                             result: Expr::Gte(Box::new(expr.clone()), Box::new(Expr::int(0))),
                             justification: "(a-b)² ≥ 0 always holds (squares are non-negative)"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -726,6 +778,7 @@ fn square_inequality_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
     ]
 }
@@ -796,6 +849,7 @@ fn bernoulli_inequality() -> Rule {
         description: "(1+x)^n >= 1 + nx for x >= -1, n >= 1",
         domains: &[Domain::Inequalities],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match (1+x)^n pattern
             if let Expr::Pow(base, _) = expr {
@@ -816,6 +870,7 @@ fn bernoulli_inequality() -> Rule {
                     return vec![RuleApplication {
                         result: lower_bound,
                         justification: "(1+x)^n >= 1+nx (Bernoulli)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -823,6 +878,7 @@ fn bernoulli_inequality() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -835,6 +891,7 @@ fn qm_am_inequality() -> Rule {
         description: "QM >= AM: √((a²+b²)/2) >= (a+b)/2",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Sqrt],
         is_applicable: |expr, _ctx| {
             // Match sqrt((a² + b²)/2) pattern
             if let Expr::Sqrt(inner) = expr {
@@ -872,6 +929,7 @@ fn qm_am_inequality() -> Rule {
                                 result: am,
                                 justification: "QM >= AM: √((a²+b²)/2) >= (a+b)/2 (lower bound)"
                                     .to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -881,6 +939,7 @@ fn qm_am_inequality() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -893,6 +952,7 @@ fn hm_gm_inequality() -> Rule {
         description: "HM <= GM: 2ab/(a+b) <= √(ab)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match 2ab/(a+b) pattern
             if let Expr::Div(num, denom) = expr {
@@ -916,6 +976,7 @@ fn hm_gm_inequality() -> Rule {
                         return vec![RuleApplication {
                             result: gm,
                             justification: "HM <= GM: 2ab/(a+b) <= √(ab) (upper bound)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -924,6 +985,7 @@ fn hm_gm_inequality() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -936,10 +998,12 @@ fn positive_square_root() -> Rule {
         description: "√a >= 0 for a >= 0",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Sqrt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sqrt(_)),
         apply: |_expr, _ctx| vec![],
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -952,10 +1016,12 @@ fn exp_positivity() -> Rule {
         description: "e^x > 0 for all x",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Exp],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Exp(_)),
         apply: |_expr, _ctx| vec![],
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -968,6 +1034,7 @@ fn abs_product() -> Rule {
         description: "|ab| = |a||b|",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Abs],
         is_applicable: |expr, _ctx| {
             if let Expr::Abs(inner) = expr {
                 return matches!(inner.as_ref(), Expr::Mul(_, _));
@@ -983,6 +1050,7 @@ fn abs_product() -> Rule {
                             Box::new(Expr::Abs(b.clone())),
                         ),
                         justification: "|ab| = |a||b|".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -990,6 +1058,7 @@ fn abs_product() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1002,6 +1071,7 @@ fn abs_quotient() -> Rule {
         description: "|a/b| = |a|/|b|",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Abs],
         is_applicable: |expr, _ctx| {
             if let Expr::Abs(inner) = expr {
                 return matches!(inner.as_ref(), Expr::Div(_, _));
@@ -1017,6 +1087,7 @@ fn abs_quotient() -> Rule {
                             Box::new(Expr::Abs(b.clone())),
                         ),
                         justification: "|a/b| = |a|/|b|".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1024,6 +1095,7 @@ fn abs_quotient() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1036,6 +1108,7 @@ fn abs_power() -> Rule {
         description: "|a^n| = |a|^n",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Abs],
         is_applicable: |expr, _ctx| {
             if let Expr::Abs(inner) = expr {
                 return matches!(inner.as_ref(), Expr::Pow(_, _));
@@ -1048,6 +1121,7 @@ fn abs_power() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Pow(Box::new(Expr::Abs(base.clone())), exp.clone()),
                         justification: "|a^n| = |a|^n".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1055,6 +1129,7 @@ fn abs_power() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1067,10 +1142,12 @@ fn add_to_both_sides() -> Rule {
         description: "Add same expression to both sides of equation",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. }),
         apply: |_expr, _ctx| vec![], // Needs c from context
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1083,10 +1160,12 @@ fn mul_positive_both_sides() -> Rule {
         description: "Multiply both sides by positive expression",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. }),
         apply: |_expr, _ctx| vec![], // Needs c from context
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1099,6 +1178,7 @@ fn sqrt_comparison() -> Rule {
         description: "For a,b >= 0: a >= b => √a >= √b",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gte, ExprKind::Gt],
         is_applicable: |expr, _ctx| {
             // Match a >= b where both could have sqrt
             matches!(expr, Expr::Gte(_, _) | Expr::Gt(_, _))
@@ -1111,12 +1191,14 @@ fn sqrt_comparison() -> Rule {
                     result: Expr::Gte(Box::new(sqrt_a), Box::new(sqrt_b)),
                     justification: "For a,b >= 0: a >= b => √a >= √b (sqrt is increasing)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1129,6 +1211,7 @@ fn ln_comparison() -> Rule {
         description: "For a,b > 0: a > b => ln(a) > ln(b)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Gt(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Gt(a, b) = expr {
@@ -1138,12 +1221,14 @@ fn ln_comparison() -> Rule {
                     result: Expr::Gt(Box::new(ln_a), Box::new(ln_b)),
                     justification: "For a,b > 0: a > b => ln(a) > ln(b) (ln is increasing)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1156,6 +1241,7 @@ fn exp_monotonic() -> Rule {
         description: "a > b => e^a > e^b (exp is increasing)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Gt(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Gt(a, b) = expr {
@@ -1164,12 +1250,14 @@ fn exp_monotonic() -> Rule {
                 return vec![RuleApplication {
                     result: Expr::Gt(Box::new(exp_a), Box::new(exp_b)),
                     justification: "a > b => e^a > e^b (exp is strictly increasing)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1198,6 +1286,7 @@ fn ln_monotonic() -> Rule {
         description: "a > b > 0 => ln(a) > ln(b) (ln is increasing)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Gt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Gt(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Gt(a, b) = expr {
@@ -1207,12 +1296,14 @@ fn ln_monotonic() -> Rule {
                     result: Expr::Gt(Box::new(ln_a), Box::new(ln_b)),
                     justification: "a > b > 0 => ln(a) > ln(b) (ln is strictly increasing on R+)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1240,16 +1331,19 @@ fn holder_inequality() -> Rule {
         description: "Holder: (Σ|ab|)^p <= (Σ|a|^p)(Σ|b|^q), 1/p+1/q=1",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Mul, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Holder's inequality: (Σ|ab|)^p <= (Σ|a|^p)(Σ|b|^q) where 1/p+1/q=1"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1275,16 +1369,19 @@ fn jensen_convex() -> Rule {
         description: "Jensen (convex): f((x+y)/2) <= (f(x)+f(y))/2",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Jensen's inequality for convex f: f((x+y)/2) <= (f(x)+f(y))/2"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1308,16 +1405,19 @@ fn jensen_concave() -> Rule {
         description: "Jensen (concave): f((x+y)/2) >= (f(x)+f(y))/2",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Jensen's inequality for concave f: f((x+y)/2) >= (f(x)+f(y))/2"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1341,6 +1441,7 @@ fn jensen_weighted() -> Rule {
         description: "Weighted Jensen: f(Σw_i·x_i) <= Σw_i·f(x_i)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1348,10 +1449,12 @@ fn jensen_weighted() -> Rule {
                 justification:
                     "Weighted Jensen for convex f: f(Σw_i·x_i) <= Σw_i·f(x_i) where Σw_i=1"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1378,6 +1481,7 @@ fn chebyshev_sum() -> Rule {
         description: "Chebyshev: (Σa)(Σb) <= n·Σab (same order)",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1385,10 +1489,12 @@ fn chebyshev_sum() -> Rule {
                 justification:
                     "Chebyshev's inequality: (Σa)(Σb) <= n·Σab for similarly ordered sequences"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1412,16 +1518,19 @@ fn power_mean_inequality() -> Rule {
         description: "Power mean: M_p <= M_q for p <= q",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Div, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Power mean inequality: M_p(a1,...,an) <= M_q(a1,...,an) for p <= q"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1444,6 +1553,7 @@ fn muirhead_inequality() -> Rule {
         description: "Muirhead: [a,b] majorizes [c,d]",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1451,10 +1561,12 @@ fn muirhead_inequality() -> Rule {
                 justification:
                     "Muirhead's inequality: symmetric sum [a,b] >= [c,d] if [a,b] majorizes [c,d]"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -1502,16 +1614,19 @@ fn schur_inequality() -> Rule {
         description: "Schur: Σx^r(x-y)(x-z) >= 0 for r>=0",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Schur's inequality: x^r(x-y)(x-z) + cyclic >= 0 for r>=0"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -1536,16 +1651,19 @@ fn nesbitt_inequality() -> Rule {
         description: "Nesbitt: a/(b+c) + b/(a+c) + c/(a+b) >= 3/2",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: Expr::Div(Box::new(Expr::int(3)), Box::new(Expr::int(2))),
                 justification: "Nesbitt's inequality: a/(b+c) + b/(a+c) + c/(a+b) >= 3/2"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1571,15 +1689,18 @@ fn rearrangement_inequality() -> Rule {
         description: "Rearrangement: same order gives max sum",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Rearrangement inequality: Σx_i·y_σ(i) is maximized when both sequences have same order".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1610,16 +1731,19 @@ fn young_inequality() -> Rule {
         description: "Young: ab <= a^p/p + b^q/q, 1/p+1/q=1",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Young's inequality: ab <= a^p/p + b^q/q where 1/p+1/q=1"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1645,15 +1769,18 @@ fn minkowski_inequality() -> Rule {
         description: "Minkowski: ||a+b||_p <= ||a||_p + ||b||_p",
         domains: &[Domain::Inequalities],
         requires: &[Feature::Inequality],
+        roots: &[ExprKind::Add, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Pow(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Minkowski's inequality: ||a+b||_p <= ||a||_p + ||b||_p for p>=1"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
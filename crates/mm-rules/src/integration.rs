@@ -7,7 +7,7 @@
 //! Integration transformation rules.
 
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{Expr, ExprKind, Rational};
 
 /// Get all integration rules.
 pub fn integration_rules() -> Vec<Rule> {
@@ -21,6 +21,7 @@ pub fn integration_rules() -> Vec<Rule> {
         exp_integral(),
         one_over_x_integral(),
         constant_multiple_integral(),
+        negation_integral(),
     ]
 }
 
@@ -60,6 +61,7 @@ fn power_integral() -> Rule {
         description: "Power rule: integral(x^n dx) = x^(n+1)/(n+1) for n != -1",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 // Case 1: Just x
@@ -89,6 +91,7 @@ fn power_integral() -> Rule {
                                 Box::new(Expr::int(2)),
                             ),
                             justification: "integral(x dx) = x^2/2".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -109,6 +112,7 @@ fn power_integral() -> Rule {
                                     "integral(x^{} dx) = x^{}/{}",
                                     n, n_plus_1, n_plus_1
                                 ),
+                                condition: None,
                             }];
                         }
                     }
@@ -118,6 +122,7 @@ fn power_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -133,6 +138,7 @@ fn constant_integral() -> Rule {
         description: "Constant rule: integral(c dx) = cx",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 return !contains_var(inner, *var);
@@ -145,6 +151,7 @@ fn constant_integral() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Mul(inner.clone(), Box::new(Expr::Var(*var))),
                         justification: "integral(c dx) = cx".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -152,6 +159,7 @@ fn constant_integral() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -167,6 +175,7 @@ fn sum_integral() -> Rule {
         description: "Sum rule: integral(f + g dx) = integral(f dx) + integral(g dx)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, .. } = expr {
                 return matches!(inner.as_ref(), Expr::Add(_, _));
@@ -188,6 +197,7 @@ fn sum_integral() -> Rule {
                             }),
                         ),
                         justification: "integral(f + g) = integral(f) + integral(g)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -195,6 +205,7 @@ fn sum_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 // ============================================================================
@@ -209,6 +220,7 @@ fn difference_integral() -> Rule {
         description: "Difference rule: integral(f - g dx) = integral(f dx) - integral(g dx)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, .. } = expr {
                 return matches!(inner.as_ref(), Expr::Sub(_, _));
@@ -230,6 +242,7 @@ fn difference_integral() -> Rule {
                             }),
                         ),
                         justification: "integral(f - g) = integral(f) - integral(g)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -237,6 +250,7 @@ fn difference_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -252,6 +266,7 @@ fn sin_integral() -> Rule {
         description: "Sine integral: integral(sin(x) dx) = -cos(x)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 if let Expr::Sin(arg) = inner.as_ref() {
@@ -270,6 +285,7 @@ fn sin_integral() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Neg(Box::new(Expr::Cos(arg.clone()))),
                                 justification: "integral(sin(x) dx) = -cos(x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -279,6 +295,7 @@ fn sin_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -294,6 +311,7 @@ fn cos_integral() -> Rule {
         description: "Cosine integral: integral(cos(x) dx) = sin(x)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 if let Expr::Cos(arg) = inner.as_ref() {
@@ -312,6 +330,7 @@ fn cos_integral() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Sin(arg.clone()),
                                 justification: "integral(cos(x) dx) = sin(x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -321,6 +340,7 @@ fn cos_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -336,6 +356,7 @@ fn exp_integral() -> Rule {
         description: "Exponential integral: integral(e^x dx) = e^x",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 if let Expr::Exp(arg) = inner.as_ref() {
@@ -354,6 +375,7 @@ fn exp_integral() -> Rule {
                             return vec![RuleApplication {
                                 result: inner.clone().as_ref().clone(),
                                 justification: "integral(e^x dx) = e^x".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -363,6 +385,7 @@ fn exp_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -378,6 +401,7 @@ fn one_over_x_integral() -> Rule {
         description: "Reciprocal integral: integral(1/x dx) = ln(x)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 // Check for 1/x or x^(-1)
@@ -407,6 +431,7 @@ fn one_over_x_integral() -> Rule {
                                         *var,
                                     ))))),
                                     justification: "integral(1/x dx) = ln|x|".to_string(),
+                                    condition: None,
                                 }];
                             }
                         }
@@ -418,6 +443,7 @@ fn one_over_x_integral() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Ln(Box::new(Expr::Abs(Box::new(Expr::Var(*var))))),
                                 justification: "integral(x^(-1) dx) = ln|x|".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -427,6 +453,7 @@ fn one_over_x_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -442,6 +469,7 @@ fn constant_multiple_integral() -> Rule {
         description: "Constant multiple: integral(c*f dx) = c * integral(f dx)",
         domains: &[Domain::CalculusInt],
         requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
         is_applicable: |expr, _ctx| {
             if let Expr::Integral { expr: inner, var } = expr {
                 if let Expr::Mul(a, b) = inner.as_ref() {
@@ -471,6 +499,7 @@ fn constant_multiple_integral() -> Rule {
                                 }),
                             ),
                             justification: "integral(c*f dx) = c * integral(f dx)".to_string(),
+                            condition: None,
                         }];
                     }
                     if contains_var(a, *var) && !contains_var(b, *var) {
@@ -484,6 +513,7 @@ fn constant_multiple_integral() -> Rule {
                                 }),
                             ),
                             justification: "integral(f*c dx) = c * integral(f dx)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -492,5 +522,76 @@ fn constant_multiple_integral() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 39: Negation: integral(-f dx) = -integral(f dx)
+// ============================================================================
+
+fn negation_integral() -> Rule {
+    Rule {
+        id: RuleId(39),
+        name: "negation_integral",
+        category: RuleCategory::Integral,
+        description: "Negation: integral(-f dx) = -integral(f dx)",
+        domains: &[Domain::CalculusInt],
+        requires: &[Feature::Integral],
+        roots: &[ExprKind::Integral],
+        is_applicable: |expr, _ctx| {
+            if let Expr::Integral { expr: inner, .. } = expr {
+                return matches!(inner.as_ref(), Expr::Neg(_));
+            }
+            false
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Integral { expr: inner, var } = expr {
+                if let Expr::Neg(f) = inner.as_ref() {
+                    return vec![RuleApplication {
+                        result: Expr::Neg(Box::new(Expr::Integral {
+                            expr: f.clone(),
+                            var: *var,
+                        })),
+                        justification: "integral(-f) = -integral(f)".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleContext;
+    use mm_core::SymbolTable;
+
+    #[test]
+    fn test_negation_integral() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let rule = negation_integral();
+        let ctx = RuleContext::default();
+        let integral = |e: Expr| Expr::Integral {
+            expr: Box::new(e),
+            var: x,
+        };
+
+        // ∫ -sin(x) dx → -∫ sin(x) dx
+        let sin = Expr::Sin(Box::new(Expr::Var(x)));
+        let expr = integral(Expr::Neg(Box::new(sin.clone())));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(
+            rule.apply(&expr, &ctx)[0].result,
+            Expr::Neg(Box::new(integral(sin.clone())))
+        );
+
+        assert!(!rule.can_apply(&integral(sin), &ctx));
     }
 }
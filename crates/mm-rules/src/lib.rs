@@ -32,6 +32,7 @@ pub mod induction;
 pub mod inequalities;
 pub mod inequality_chain;
 pub mod integration;
+pub mod logic;
 pub mod number_theory;
 pub mod patterns;
 pub mod polynomial;
@@ -44,8 +45,8 @@ pub use guardrail::{
     analyze, decompose_additive, filter_rules, is_rule_applicable, solvability_score,
     ProblemProfile,
 };
-pub use patterns::match_integral_pattern;
+pub use patterns::{match_integral_pattern, PatternRule};
 pub use rule::{
     standard_rules, Domain, Feature, Rule, RuleApplication, RuleCategory, RuleContext, RuleId,
-    RuleSet,
+    RuleInfo, RuleSet,
 };
@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Propositional simplification rules.
+//!
+//! Operates on `Not`, `And` and `Or`. Truth values use the same encoding as
+//! evaluation: `1` is true and `0` is false.
+
+use crate::{Rule, RuleApplication, RuleCategory, RuleId};
+use mm_core::{Expr, ExprKind};
+
+/// Get all logical simplification rules.
+pub fn logic_rules() -> Vec<Rule> {
+    vec![
+        double_negation(),
+        de_morgan_and(),
+        de_morgan_or(),
+        and_true(),
+        or_false(),
+    ]
+}
+
+fn is_true(expr: &Expr) -> bool {
+    matches!(expr, Expr::Const(c) if c.is_one())
+}
+
+fn is_false(expr: &Expr) -> bool {
+    matches!(expr, Expr::Const(c) if c.is_zero())
+}
+
+// ============================================================================
+// Rule 830: ¬¬P = P
+// ============================================================================
+
+fn double_negation() -> Rule {
+    Rule {
+        id: RuleId(830),
+        name: "double_negation",
+        category: RuleCategory::Simplification,
+        description: "¬¬P = P",
+        domains: &[],
+        requires: &[],
+        roots: &[ExprKind::Not],
+        is_applicable: |expr, _ctx| match expr {
+            Expr::Not(inner) => matches!(inner.as_ref(), Expr::Not(_)),
+            _ => false,
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Not(inner) = expr {
+                if let Expr::Not(p) = inner.as_ref() {
+                    return vec![RuleApplication {
+                        result: p.as_ref().clone(),
+                        justification: "¬¬P = P".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: true,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 831: ¬(P ∧ Q) = ¬P ∨ ¬Q
+// ============================================================================
+
+fn de_morgan_and() -> Rule {
+    Rule {
+        id: RuleId(831),
+        name: "de_morgan_and",
+        category: RuleCategory::Simplification,
+        description: "¬(P ∧ Q) = ¬P ∨ ¬Q",
+        domains: &[],
+        requires: &[],
+        roots: &[ExprKind::Not],
+        is_applicable: |expr, _ctx| match expr {
+            Expr::Not(inner) => matches!(inner.as_ref(), Expr::And(_, _)),
+            _ => false,
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Not(inner) = expr {
+                if let Expr::And(p, q) = inner.as_ref() {
+                    return vec![RuleApplication {
+                        result: Expr::Or(
+                            Box::new(Expr::Not(p.clone())),
+                            Box::new(Expr::Not(q.clone())),
+                        ),
+                        justification: "De Morgan: ¬(P ∧ Q) = ¬P ∨ ¬Q".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: true,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 832: ¬(P ∨ Q) = ¬P ∧ ¬Q
+// ============================================================================
+
+fn de_morgan_or() -> Rule {
+    Rule {
+        id: RuleId(832),
+        name: "de_morgan_or",
+        category: RuleCategory::Simplification,
+        description: "¬(P ∨ Q) = ¬P ∧ ¬Q",
+        domains: &[],
+        requires: &[],
+        roots: &[ExprKind::Not],
+        is_applicable: |expr, _ctx| match expr {
+            Expr::Not(inner) => matches!(inner.as_ref(), Expr::Or(_, _)),
+            _ => false,
+        },
+        apply: |expr, _ctx| {
+            if let Expr::Not(inner) = expr {
+                if let Expr::Or(p, q) = inner.as_ref() {
+                    return vec![RuleApplication {
+                        result: Expr::And(
+                            Box::new(Expr::Not(p.clone())),
+                            Box::new(Expr::Not(q.clone())),
+                        ),
+                        justification: "De Morgan: ¬(P ∨ Q) = ¬P ∧ ¬Q".to_string(),
+                        condition: None,
+                    }];
+                }
+            }
+            vec![]
+        },
+        reversible: true,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 833: P ∧ true = P
+// ============================================================================
+
+fn and_true() -> Rule {
+    Rule {
+        id: RuleId(833),
+        name: "and_true",
+        category: RuleCategory::Simplification,
+        description: "P ∧ true = P",
+        domains: &[],
+        requires: &[],
+        roots: &[ExprKind::And],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::And(p, q) if is_true(p) || is_true(q)),
+        apply: |expr, _ctx| {
+            if let Expr::And(p, q) = expr {
+                let rest = if is_true(q) { p } else { q };
+                return vec![RuleApplication {
+                    result: rest.as_ref().clone(),
+                    justification: "P ∧ true = P".to_string(),
+                    condition: None,
+                }];
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Rule 834: P ∨ false = P
+// ============================================================================
+
+fn or_false() -> Rule {
+    Rule {
+        id: RuleId(834),
+        name: "or_false",
+        category: RuleCategory::Simplification,
+        description: "P ∨ false = P",
+        domains: &[],
+        requires: &[],
+        roots: &[ExprKind::Or],
+        is_applicable: |expr, _ctx| matches!(expr, Expr::Or(p, q) if is_false(p) || is_false(q)),
+        apply: |expr, _ctx| {
+            if let Expr::Or(p, q) = expr {
+                let rest = if is_false(q) { p } else { q };
+                return vec![RuleApplication {
+                    result: rest.as_ref().clone(),
+                    justification: "P ∨ false = P".to_string(),
+                    condition: None,
+                }];
+            }
+            vec![]
+        },
+        reversible: false,
+        cost: 1,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleContext;
+    use mm_core::SymbolTable;
+
+    #[test]
+    fn test_double_negation() {
+        let mut symbols = SymbolTable::new();
+        let p = symbols.intern("p");
+        let ctx = RuleContext::default();
+
+        // ¬¬p → p
+        let expr = Expr::Not(Box::new(Expr::Not(Box::new(Expr::Var(p)))));
+        let rule = double_negation();
+
+        assert!(rule.can_apply(&expr, &ctx));
+        let results = rule.apply(&expr, &ctx);
+        assert_eq!(results[0].result, Expr::Var(p));
+    }
+
+    #[test]
+    fn test_de_morgan() {
+        let mut symbols = SymbolTable::new();
+        let p = symbols.intern("p");
+        let q = symbols.intern("q");
+        let ctx = RuleContext::default();
+        let (vp, vq) = (|| Box::new(Expr::Var(p)), || Box::new(Expr::Var(q)));
+
+        // ¬(p ∧ q) → ¬p ∨ ¬q
+        let expr = Expr::Not(Box::new(Expr::And(vp(), vq())));
+        let results = de_morgan_and().apply(&expr, &ctx);
+        match &results[0].result {
+            Expr::Or(a, b) => {
+                assert!(matches!(a.as_ref(), Expr::Not(x) if **x == Expr::Var(p)));
+                assert!(matches!(b.as_ref(), Expr::Not(x) if **x == Expr::Var(q)));
+            }
+            other => panic!("Expected Or, got {:?}", other),
+        }
+
+        // ¬(p ∨ q) → ¬p ∧ ¬q
+        let expr = Expr::Not(Box::new(Expr::Or(vp(), vq())));
+        assert!(!de_morgan_and().can_apply(&expr, &ctx));
+        let results = de_morgan_or().apply(&expr, &ctx);
+        match &results[0].result {
+            Expr::And(a, b) => {
+                assert!(matches!(a.as_ref(), Expr::Not(x) if **x == Expr::Var(p)));
+                assert!(matches!(b.as_ref(), Expr::Not(x) if **x == Expr::Var(q)));
+            }
+            other => panic!("Expected And, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_identity_elements() {
+        let mut symbols = SymbolTable::new();
+        let p = symbols.intern("p");
+        let ctx = RuleContext::default();
+
+        // true ∧ p → p
+        let expr = Expr::And(Box::new(Expr::int(1)), Box::new(Expr::Var(p)));
+        assert_eq!(and_true().apply(&expr, &ctx)[0].result, Expr::Var(p));
+
+        // p ∨ false → p
+        let expr = Expr::Or(Box::new(Expr::Var(p)), Box::new(Expr::int(0)));
+        assert_eq!(or_false().apply(&expr, &ctx)[0].result, Expr::Var(p));
+        assert!(!and_true().can_apply(&expr, &ctx));
+    }
+}
@@ -6,8 +6,9 @@
 
 //! Number theory transformation rules for IMO-level problem solving.
 
+use crate::backward::substitute_var;
 use crate::{Domain, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{numtheory, Constraint, Expr, ExprKind, Rational, Symbol};
 
 /// Get all number theory rules (100+).
 pub fn number_theory_rules() -> Vec<Rule> {
@@ -27,6 +28,10 @@ pub fn number_theory_rules() -> Vec<Rule> {
     rules.extend(sum_formulas());
     // Factorial rules
     rules.extend(factorial_rules());
+    // Product formulas
+    rules.extend(product_rules());
+    // Summation formulas
+    rules.extend(summation_rules());
     // Floor/Ceiling rules
     rules.extend(floor_ceiling_rules());
     // Phase 3: Advanced number theory
@@ -49,6 +54,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "n divides 0 for any n",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match: Divides(n, 0)
                 if let Expr::Div(_, b) = expr {
@@ -62,6 +68,7 @@ fn divisibility_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Const(Rational::from_integer(0)),
                             justification: "0/n = 0".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -69,6 +76,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // n | n for all n
         Rule {
@@ -78,18 +86,20 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "n/n = 1 for any n ≠ 0",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 if let Expr::Div(a, b) = expr {
                     return a == b && !matches!(b.as_ref(), Expr::Const(c) if c.is_zero());
                 }
                 false
             },
-            apply: |expr, _ctx| {
+            apply: |expr, ctx| {
                 if let Expr::Div(a, b) = expr {
                     if a == b {
                         return vec![RuleApplication {
                             result: Expr::Const(Rational::from_integer(1)),
                             justification: "n/n = 1".to_string(),
+                            condition: ctx.side_condition(Constraint::nonzero(b.as_ref().clone())),
                         }];
                     }
                 }
@@ -97,6 +107,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // 2 | (a + a) = 2 | 2a
         Rule {
@@ -106,6 +117,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "a + a = 2a (even)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(a, b) = expr {
                     return a == b;
@@ -118,6 +130,7 @@ fn divisibility_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Mul(Box::new(Expr::int(2)), a.clone()),
                             justification: "a + a = 2a".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -125,6 +138,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 1,
+            ..Default::default()
         },
         // Divisibility by 2: last digit even
         Rule {
@@ -134,6 +148,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "2n is divisible by 2",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mul(a, _) = expr {
                     if let Expr::Const(c) = a.as_ref() {
@@ -147,12 +162,14 @@ fn divisibility_rules() -> Vec<Rule> {
                     return vec![RuleApplication {
                         result: Expr::Mul(Box::new(Expr::int(2)), b.clone()),
                         justification: "2n is even (divisible by 2)".to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // a*b / a = b (when a != 0)
         Rule {
@@ -162,6 +179,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "(a*b)/a = b",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 if let Expr::Div(num, denom) = expr {
                     if let Expr::Mul(a, _) = num.as_ref() {
@@ -173,19 +191,23 @@ fn divisibility_rules() -> Vec<Rule> {
                 }
                 false
             },
-            apply: |expr, _ctx| {
+            apply: |expr, ctx| {
                 if let Expr::Div(num, denom) = expr {
                     if let Expr::Mul(a, b) = num.as_ref() {
                         if a == denom {
                             return vec![RuleApplication {
                                 result: b.as_ref().clone(),
                                 justification: "(a*b)/a = b".to_string(),
+                                condition: ctx
+                                    .side_condition(Constraint::nonzero(a.as_ref().clone())),
                             }];
                         }
                         if b == denom {
                             return vec![RuleApplication {
                                 result: a.as_ref().clone(),
                                 justification: "(a*b)/b = a".to_string(),
+                                condition: ctx
+                                    .side_condition(Constraint::nonzero(b.as_ref().clone())),
                             }];
                         }
                     }
@@ -194,6 +216,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // (a/b) * b = a
         Rule {
@@ -203,6 +226,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "(a/b) * b = a",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mul(a, b) = expr {
                     if let Expr::Div(_, denom) = a.as_ref() {
@@ -221,6 +245,7 @@ fn divisibility_rules() -> Vec<Rule> {
                             return vec![RuleApplication {
                                 result: num.as_ref().clone(),
                                 justification: "(a/b) * b = a".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -229,6 +254,7 @@ fn divisibility_rules() -> Vec<Rule> {
                             return vec![RuleApplication {
                                 result: num.as_ref().clone(),
                                 justification: "b * (a/b) = a".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -237,6 +263,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // a^2 - b^2 = (a+b)(a-b)
         Rule {
@@ -246,6 +273,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "a² - b² = (a+b)(a-b)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sub],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sub(a, b) = expr {
                     let a_is_sq = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)));
@@ -263,6 +291,7 @@ fn divisibility_rules() -> Vec<Rule> {
                                 Box::new(Expr::Sub(base_a.clone(), base_b.clone())),
                             ),
                             justification: "a² - b² = (a+b)(a-b)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -270,6 +299,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // a^3 - b^3 = (a-b)(a^2 + ab + b^2)
         Rule {
@@ -279,6 +309,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "a³ - b³ = (a-b)(a² + ab + b²)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sub],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sub(a, b) = expr {
                     let a_is_cube = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(3)));
@@ -302,6 +333,7 @@ fn divisibility_rules() -> Vec<Rule> {
                                 )),
                             ),
                             justification: "a³ - b³ = (a-b)(a² + ab + b²)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -309,6 +341,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // a^3 + b^3 = (a+b)(a^2 - ab + b^2)
         Rule {
@@ -318,6 +351,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "a³ + b³ = (a+b)(a² - ab + b²)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(a, b) = expr {
                     let a_is_cube = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(3)));
@@ -341,6 +375,7 @@ fn divisibility_rules() -> Vec<Rule> {
                                 )),
                             ),
                             justification: "a³ + b³ = (a+b)(a² - ab + b²)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -348,6 +383,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // (a+b)^2 = a^2 + 2ab + b^2
         Rule {
@@ -357,6 +393,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "(a+b)² = a² + 2ab + b²",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)) {
@@ -380,6 +417,7 @@ fn divisibility_rules() -> Vec<Rule> {
                                 Box::new(b_sq),
                             ),
                             justification: "(a+b)² = a² + 2ab + b²".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -387,6 +425,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // (a-b)^2 = a^2 - 2ab + b^2
         Rule {
@@ -396,6 +435,7 @@ fn divisibility_rules() -> Vec<Rule> {
             description: "(a-b)² = a² - 2ab + b²",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)) {
@@ -419,6 +459,7 @@ fn divisibility_rules() -> Vec<Rule> {
                                 Box::new(b_sq),
                             ),
                             justification: "(a-b)² = a² - 2ab + b²".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -426,6 +467,7 @@ fn divisibility_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -444,6 +486,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "a mod a = 0",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mod(a, b) = expr {
                     return a == b;
@@ -456,6 +499,7 @@ fn modular_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(0),
                             justification: "a mod a = 0".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -463,6 +507,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // 0 mod n = 0
         Rule {
@@ -472,6 +517,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "0 mod n = 0",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mod(a, _) = expr {
                     return matches!(a.as_ref(), Expr::Const(c) if c.is_zero());
@@ -484,6 +530,7 @@ fn modular_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(0),
                             justification: "0 mod n = 0".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -491,6 +538,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // a mod 1 = 0 (for integers)
         Rule {
@@ -500,6 +548,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "a mod 1 = 0",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mod(_, b) = expr {
                     return matches!(b.as_ref(), Expr::Const(c) if *c == Rational::from_integer(1));
@@ -512,6 +561,7 @@ fn modular_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(0),
                             justification: "a mod 1 = 0 (for integers)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -519,6 +569,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // Modular inverse: a⁻¹ mod m exists iff gcd(a,m) = 1
         Rule {
@@ -528,6 +579,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "a⁻¹ mod m via extended Euclidean",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 // Match: Mod(Div(1, a), m) or inverse pattern
                 if let Expr::Mod(inner, modulus) = expr {
@@ -569,6 +621,7 @@ fn modular_rules() -> Vec<Rule> {
                                             "Modular inverse: {}⁻¹ ≡ {} (mod {})",
                                             a_val, inv, m_val
                                         ),
+                                        condition: None,
                                     }];
                                 }
                             }
@@ -579,6 +632,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Modular exponentiation: a^n mod m (fast)
         Rule {
@@ -588,6 +642,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "a^n mod m via repeated squaring",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 // Match: Mod(Pow(a, n), m)
                 if let Expr::Mod(inner, modulus) = expr {
@@ -631,6 +686,7 @@ fn modular_rules() -> Vec<Rule> {
                                         "Modular exponentiation: {}^{} ≡ {} (mod {})",
                                         a_val, n_val, result, m_val
                                     ),
+                                    condition: None,
                                 }];
                             }
                         }
@@ -640,6 +696,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Extended GCD: gcd(a,b) = ax + by (Bezout coefficients)
         Rule {
@@ -649,6 +706,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Extended GCD: gcd(a,b) = ax + by",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::GCD],
             is_applicable: |expr, _ctx| {
                 // Match: GCD(a, b) where both are small constants
                 if let Expr::GCD(a, b) = expr {
@@ -683,6 +741,7 @@ fn modular_rules() -> Vec<Rule> {
                                 "Extended GCD: gcd({}, {}) = {} = {}·{} + {}·{}",
                                 a_val, b_val, g, a_val, x, b_val, y
                             ),
+                            condition: None,
                         }];
                     }
                 }
@@ -690,6 +749,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // Legendre symbol computation (a/p)
         Rule {
@@ -699,6 +759,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Legendre symbol (a/p) computation",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 // Custom pattern for Legendre symbol representation
                 // For now, check Mod(a, p) for odd primes
@@ -743,6 +804,7 @@ fn modular_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Const(Rational::from_integer(legendre)),
                             justification: format!("Legendre ({}/{}) = {}", a_val, p_val, legendre),
+                            condition: None,
                         }];
                     }
                 }
@@ -750,6 +812,7 @@ fn modular_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Tonelli-Shanks: Modular square root
         Rule {
@@ -759,6 +822,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Tonelli-Shanks modular square root",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 // Match: Sqrt(Mod(a, p)) or Mod(Sqrt(a), p) for odd primes
                 if let Expr::Sqrt(inner) = expr {
@@ -775,10 +839,12 @@ fn modular_rules() -> Vec<Rule> {
                     result: expr.clone(),
                     justification: "Tonelli-Shanks: Computes x where x² ≡ a (mod p) for prime p"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Primitive root finder
         Rule {
@@ -788,6 +854,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Find smallest primitive root mod n",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Const],
             is_applicable: |expr, _ctx| {
                 // Match: Const(n) for small n where primitive roots exist
                 if let Expr::Const(n) = expr {
@@ -801,25 +868,6 @@ fn modular_rules() -> Vec<Rule> {
                 if let Expr::Const(n) = expr {
                     let n_val = n.numer();
 
-                    // Compute Euler's totient
-                    fn euler_phi(mut n: i64) -> i64 {
-                        let mut result = n;
-                        let mut p = 2i64;
-                        while p * p <= n {
-                            if n % p == 0 {
-                                while n % p == 0 {
-                                    n /= p;
-                                }
-                                result -= result / p;
-                            }
-                            p += 1;
-                        }
-                        if n > 1 {
-                            result -= result / n;
-                        }
-                        result
-                    }
-
                     fn mod_pow(mut base: i64, mut exp: i64, modulus: i64) -> i64 {
                         let mut result = 1i64;
                         base %= modulus;
@@ -854,7 +902,7 @@ fn modular_rules() -> Vec<Rule> {
                         true
                     }
 
-                    let phi = euler_phi(n_val);
+                    let phi = numtheory::euler_totient(n_val as u64) as i64;
 
                     // Find smallest primitive root
                     for g in 2..n_val {
@@ -865,6 +913,7 @@ fn modular_rules() -> Vec<Rule> {
                                     "Primitive root: {} is smallest primitive root mod {}",
                                     g, n_val
                                 ),
+                                condition: None,
                             }];
                         }
                     }
@@ -872,10 +921,12 @@ fn modular_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "No primitive root exists for this modulus".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Discrete logarithm (baby-step giant-step for small moduli)
         Rule {
@@ -885,6 +936,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Discrete log via baby-step giant-step",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 // Match: Equation { lhs: Pow(g, x), rhs: h } in modular context
                 // For now, just detect power expressions
@@ -898,10 +950,12 @@ fn modular_rules() -> Vec<Rule> {
                     justification:
                         "Discrete log: Find x where g^x ≡ h (mod n) using baby-step giant-step"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 5,
+            ..Default::default()
         },
         // Hensel lifting for p-adic approximation
         Rule {
@@ -911,6 +965,7 @@ fn modular_rules() -> Vec<Rule> {
             description: "Hensel's lemma: lift solution mod p to mod p^k",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mod],
             is_applicable: |expr, _ctx| {
                 // Match: Mod(f(x), p^k) patterns
                 matches!(expr, Expr::Mod(_, _))
@@ -923,10 +978,12 @@ fn modular_rules() -> Vec<Rule> {
                     justification:
                         "Hensel's lemma: Solution mod p lifts to mod p^k if f'(x) ≢ 0 (mod p)"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
     ]
 }
@@ -945,6 +1002,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "gcd(a, a) = a",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::GCD],
             is_applicable: |expr, _ctx| {
                 if let Expr::GCD(a, b) = expr {
                     return a == b;
@@ -957,6 +1015,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "gcd(a, a) = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -964,6 +1023,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // gcd(a, 0) = |a|
         Rule {
@@ -973,6 +1033,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "gcd(a, 0) = |a|",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::GCD],
             is_applicable: |expr, _ctx| {
                 if let Expr::GCD(_, b) = expr {
                     return matches!(b.as_ref(), Expr::Const(c) if c.is_zero());
@@ -985,6 +1046,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "gcd(a, 0) = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -992,6 +1054,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // gcd(a, 1) = 1
         Rule {
@@ -1001,6 +1064,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "gcd(a, 1) = 1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::GCD],
             is_applicable: |expr, _ctx| {
                 if let Expr::GCD(_, b) = expr {
                     return matches!(b.as_ref(), Expr::Const(c) if *c == Rational::from_integer(1));
@@ -1013,6 +1077,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(1),
                             justification: "gcd(a, 1) = 1".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1020,6 +1085,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // lcm(a, a) = |a|
         Rule {
@@ -1029,6 +1095,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "lcm(a, a) = |a|",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::LCM],
             is_applicable: |expr, _ctx| {
                 if let Expr::LCM(a, b) = expr {
                     return a == b;
@@ -1041,6 +1108,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "lcm(a, a) = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1048,6 +1116,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // lcm(a, 1) = |a|
         Rule {
@@ -1057,6 +1126,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "lcm(a, 1) = |a|",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::LCM],
             is_applicable: |expr, _ctx| {
                 if let Expr::LCM(_, b) = expr {
                     return matches!(b.as_ref(), Expr::Const(c) if *c == Rational::from_integer(1));
@@ -1069,6 +1139,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(a.clone()),
                             justification: "lcm(a, 1) = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1076,6 +1147,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // gcd(a,b) * lcm(a,b) = |a*b|
         Rule {
@@ -1085,6 +1157,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             description: "gcd(a,b) * lcm(a,b) = |a*b|",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 // Match GCD(a,b) * LCM(a,b)
                 if let Expr::Mul(left, right) = expr {
@@ -1105,12 +1178,14 @@ fn gcd_lcm_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(Box::new(Expr::Mul(a1.clone(), b1.clone()))),
                             justification: "gcd(a,b) * lcm(a,b) = |a*b|".to_string(),
+                            condition: None,
                         }];
                     }
                     if let (Expr::LCM(a1, b1), Expr::GCD(_, _)) = (left.as_ref(), right.as_ref()) {
                         return vec![RuleApplication {
                             result: Expr::Abs(Box::new(Expr::Mul(a1.clone(), b1.clone()))),
                             justification: "gcd(a,b) * lcm(a,b) = |a*b|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1118,6 +1193,7 @@ fn gcd_lcm_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -1136,6 +1212,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             description: "√(a²) = |a|",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sqrt(inner) = expr {
                     if let Expr::Pow(_, exp) = inner.as_ref() {
@@ -1150,6 +1227,7 @@ fn perfect_power_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Abs(base.clone()),
                             justification: "√(a²) = |a|".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1157,6 +1235,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // (sqrt(a))^2 = a (for a >= 0)
         Rule {
@@ -1166,6 +1245,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             description: "(√a)² = a",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)) {
@@ -1180,6 +1260,7 @@ fn perfect_power_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: inner.as_ref().clone(),
                             justification: "(√a)² = a".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1187,6 +1268,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // sqrt(a) * sqrt(b) = sqrt(ab)
         Rule {
@@ -1196,6 +1278,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             description: "√a · √b = √(ab)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 if let Expr::Mul(a, b) = expr {
                     return matches!(a.as_ref(), Expr::Sqrt(_))
@@ -1212,13 +1295,15 @@ fn perfect_power_rules() -> Vec<Rule> {
                                 inner_b.clone(),
                             ))),
                             justification: "√a · √b = √(ab)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
                 vec![]
             },
-            reversible: true,
+            reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // sqrt(a/b) = sqrt(a)/sqrt(b)
         Rule {
@@ -1228,6 +1313,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             description: "√(a/b) = √a/√b",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sqrt],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sqrt(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Div(_, _));
@@ -1243,6 +1329,7 @@ fn perfect_power_rules() -> Vec<Rule> {
                                 Box::new(Expr::Sqrt(b.clone())),
                             ),
                             justification: "√(a/b) = √a/√b".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1250,6 +1337,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // a^(1/2) = sqrt(a)
         Rule {
@@ -1259,6 +1347,7 @@ fn perfect_power_rules() -> Vec<Rule> {
             description: "a^(1/2) = √a",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(_, exp) = expr {
                     if let Expr::Div(num, denom) = exp.as_ref() {
@@ -1273,12 +1362,14 @@ fn perfect_power_rules() -> Vec<Rule> {
                     return vec![RuleApplication {
                         result: Expr::Sqrt(base.clone()),
                         justification: "a^(1/2) = √a".to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: true,
             cost: 1,
+            ..Default::default()
         },
     ]
 }
@@ -1297,6 +1388,7 @@ fn parity_rules() -> Vec<Rule> {
             description: "(-1)^(2n) = 1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if let Expr::Neg(inner) = base.as_ref() {
@@ -1319,6 +1411,7 @@ fn parity_rules() -> Vec<Rule> {
                                     return vec![RuleApplication {
                                         result: Expr::int(1),
                                         justification: "(-1)^(2n) = 1".to_string(),
+                                        condition: None,
                                     }];
                                 }
                             }
@@ -1329,6 +1422,7 @@ fn parity_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // (-1)^(2n+1) = -1
         Rule {
@@ -1338,6 +1432,7 @@ fn parity_rules() -> Vec<Rule> {
             description: "(-1)^(2n+1) = -1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if let Expr::Neg(inner) = base.as_ref() {
@@ -1359,6 +1454,7 @@ fn parity_rules() -> Vec<Rule> {
                                     return vec![RuleApplication {
                                         result: Expr::Neg(Box::new(Expr::int(1))),
                                         justification: "(-1)^(2n+1) = -1".to_string(),
+                                        condition: None,
                                     }];
                                 }
                             }
@@ -1369,6 +1465,7 @@ fn parity_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // (-a)^2 = a^2
         Rule {
@@ -1378,6 +1475,7 @@ fn parity_rules() -> Vec<Rule> {
             description: "(-a)² = a²",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, exp) = expr {
                     if matches!(base.as_ref(), Expr::Neg(_)) {
@@ -1396,6 +1494,7 @@ fn parity_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Pow(inner.clone(), exp.clone()),
                             justification: "(-a)² = a²".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1403,6 +1502,7 @@ fn parity_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
     ]
 }
@@ -1421,6 +1521,7 @@ fn sum_formulas() -> Vec<Rule> {
             description: "Σc (n times) = cn",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| {
                 // Match: Mul(Const, Var) which could represent cn
                 if let Expr::Mul(a, b) = expr {
@@ -1439,6 +1540,7 @@ fn sum_formulas() -> Vec<Rule> {
                                 Box::new(Expr::Var(*var)),
                             ),
                             justification: "Σc (n times) = cn (sum of constant)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1446,6 +1548,7 @@ fn sum_formulas() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // 1+2+...+n = n(n+1)/2
         Rule {
@@ -1455,6 +1558,7 @@ fn sum_formulas() -> Vec<Rule> {
             description: "1+2+...+n = n(n+1)/2",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match: Div(Mul(n, Add(n, 1)), 2)
                 if let Expr::Div(num, denom) = expr {
@@ -1480,6 +1584,7 @@ fn sum_formulas() -> Vec<Rule> {
                             result: expr.clone(),
                             justification: "1+2+...+n = n(n+1)/2 (arithmetic sum formula)"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1487,6 +1592,7 @@ fn sum_formulas() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // 1²+2²+...+n² = n(n+1)(2n+1)/6
         Rule {
@@ -1496,6 +1602,7 @@ fn sum_formulas() -> Vec<Rule> {
             description: "1²+2²+...+n² = n(n+1)(2n+1)/6",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match: Div(Mul(...), 6)
                 if let Expr::Div(_, denom) = expr {
@@ -1513,6 +1620,7 @@ fn sum_formulas() -> Vec<Rule> {
                                 result: Expr::Div(num.clone(), denom.clone()),
                                 justification: "1²+2²+...+n² = n(n+1)(2n+1)/6 (sum of squares)"
                                     .to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1521,6 +1629,7 @@ fn sum_formulas() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // 1³+2³+...+n³ = [n(n+1)/2]²
         Rule {
@@ -1530,6 +1639,7 @@ fn sum_formulas() -> Vec<Rule> {
             description: "1³+2³+...+n³ = [n(n+1)/2]²",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 // Match: Pow(Div(Mul(n, n+1), 2), 2)
                 if let Expr::Pow(base, exp) = expr {
@@ -1547,6 +1657,7 @@ fn sum_formulas() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: expr.clone(),
                             justification: "1³+2³+...+n³ = [n(n+1)/2]² (sum of cubes)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1554,6 +1665,7 @@ fn sum_formulas() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // 1+r+r²+...+r^n = (r^(n+1)-1)/(r-1)
         Rule {
@@ -1563,6 +1675,7 @@ fn sum_formulas() -> Vec<Rule> {
             description: "1+r+r²+...+r^n = (r^(n+1)-1)/(r-1)",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 // Match: Div(Sub(Pow(r, n+1), 1), Sub(r, 1))
                 if let Expr::Div(num, denom) = expr {
@@ -1586,6 +1699,7 @@ fn sum_formulas() -> Vec<Rule> {
                                         justification:
                                             "1+r+r²+...+r^n = (r^(n+1)-1)/(r-1) (geometric series)"
                                                 .to_string(),
+                                        condition: None,
                                     }];
                                 }
                             }
@@ -1596,6 +1710,7 @@ fn sum_formulas() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
     ]
 }
@@ -1614,6 +1729,7 @@ fn factorial_rules() -> Vec<Rule> {
             description: "0! = 1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Factorial],
             is_applicable: |expr, _ctx| {
                 if let Expr::Factorial(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Const(c) if c.is_zero());
@@ -1626,6 +1742,7 @@ fn factorial_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(1),
                             justification: "0! = 1 (by definition)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1633,6 +1750,7 @@ fn factorial_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // 1! = 1
         Rule {
@@ -1642,6 +1760,7 @@ fn factorial_rules() -> Vec<Rule> {
             description: "1! = 1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Factorial],
             is_applicable: |expr, _ctx| {
                 if let Expr::Factorial(inner) = expr {
                     return matches!(inner.as_ref(), Expr::Const(c) if *c == Rational::from_integer(1));
@@ -1654,6 +1773,7 @@ fn factorial_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::int(1),
                             justification: "1! = 1".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1661,6 +1781,7 @@ fn factorial_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // n! = n · (n-1)!
         Rule {
@@ -1670,6 +1791,7 @@ fn factorial_rules() -> Vec<Rule> {
             description: "n! = n · (n-1)!",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Factorial],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Factorial(_)),
             apply: |expr, _ctx| {
                 if let Expr::Factorial(n) = expr {
@@ -1680,16 +1802,517 @@ fn factorial_rules() -> Vec<Rule> {
                     return vec![RuleApplication {
                         result,
                         justification: "n! = n · (n-1)! (factorial recursion)".to_string(),
+                        condition: None,
                     }];
                 }
                 vec![]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
+        },
+        // a!/b! = a · (a-1) ⋯ (b+1) when a - b is a small integer
+        Rule {
+            id: RuleId(219),
+            name: "factorial_ratio",
+            category: RuleCategory::Simplification,
+            description: "n!/(n-k)! = n · (n-1) ⋯ (n-k+1)",
+            domains: &[Domain::NumberTheory, Domain::Combinatorics],
+            requires: &[],
+            roots: &[ExprKind::Div],
+            is_applicable: |expr, _ctx| factorial_ratio_gap(expr).is_some(),
+            apply: |expr, _ctx| {
+                let Some((top, bottom, gap)) = factorial_ratio_gap(expr) else {
+                    return vec![];
+                };
+                // Cancel the smaller factorial against the bigger one
+                let (big, k) = if gap > 0 { (top, gap) } else { (bottom, -gap) };
+                let product = falling_product(big, k);
+                let result = if gap > 0 {
+                    product
+                } else {
+                    Expr::Div(Box::new(Expr::int(1)), Box::new(product))
+                };
+                vec![RuleApplication {
+                    result,
+                    justification: format!(
+                        "Cancel factorials: ratio of arguments differing by {}",
+                        k
+                    ),
+                    condition: None,
+                }]
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
         },
     ]
 }
 
+/// Largest argument gap for which a factorial ratio is multiplied out.
+const MAX_FACTORIAL_GAP: i64 = 10;
+
+/// For `a!/b!` with `a - b` a nonzero integer of at most
+/// [`MAX_FACTORIAL_GAP`], return `(a, b, a - b)`.
+fn factorial_ratio_gap(expr: &Expr) -> Option<(&Expr, &Expr, i64)> {
+    let Expr::Div(num, den) = expr else {
+        return None;
+    };
+    let (Expr::Factorial(top), Expr::Factorial(bottom)) = (num.as_ref(), den.as_ref()) else {
+        return None;
+    };
+    let (top_base, top_offset) = split_offset(top);
+    let (bottom_base, bottom_offset) = split_offset(bottom);
+    if top_base != bottom_base {
+        return None;
+    }
+    let gap = top_offset - bottom_offset;
+    if !gap.is_integer() || gap.is_zero() || gap.numer().abs() > MAX_FACTORIAL_GAP {
+        return None;
+    }
+    Some((top, bottom, gap.numer()))
+}
+
+/// Split `e + c`, `c + e` or `e - c` into `(Some(e), c)`; a bare constant
+/// has no symbolic part.
+fn split_offset(expr: &Expr) -> (Option<&Expr>, Rational) {
+    let zero = Rational::from_integer(0);
+    match expr {
+        Expr::Const(c) => (None, *c),
+        Expr::Add(a, b) => match (a.as_ref(), b.as_ref()) {
+            (e, Expr::Const(c)) | (Expr::Const(c), e) => (Some(e), *c),
+            _ => (Some(expr), zero),
+        },
+        Expr::Sub(a, b) => match (a.as_ref(), b.as_ref()) {
+            (e, Expr::Const(c)) => (Some(e), -*c),
+            _ => (Some(expr), zero),
+        },
+        _ => (Some(expr), zero),
+    }
+}
+
+/// `n · (n-1) ⋯ (n-k+1)`, folded to a number when `n` is a constant.
+fn falling_product(n: &Expr, k: i64) -> Expr {
+    if let Expr::Const(c) = n {
+        let value = (0..k).fold(Rational::from_integer(1), |acc, i| {
+            acc * (*c - Rational::from_integer(i))
+        });
+        return Expr::Const(value);
+    }
+    (1..k)
+        .map(|i| Expr::Sub(Box::new(n.clone()), Box::new(Expr::int(i))))
+        .fold(n.clone(), |acc, term| {
+            Expr::Mul(Box::new(acc), Box::new(term))
+        })
+}
+
+// ============================================================================
+// Product Formulas (ID 215+)
+// ============================================================================
+
+fn product_rules() -> Vec<Rule> {
+    vec![
+        // Π_{i=1}^{n} i = n!
+        Rule {
+            id: RuleId(215),
+            name: "product_factorial",
+            category: RuleCategory::Simplification,
+            description: "Π_{i=1}^{n} i = n!",
+            domains: &[Domain::NumberTheory, Domain::Combinatorics],
+            requires: &[],
+            roots: &[ExprKind::BigProduct],
+            is_applicable: |expr, _ctx| {
+                matches!(expr, Expr::BigProduct { var, from, body, .. }
+                    if from.is_one() && **body == Expr::Var(*var))
+            },
+            apply: |expr, _ctx| {
+                if let Expr::BigProduct { to, .. } = expr {
+                    return vec![RuleApplication {
+                        result: Expr::Factorial(to.clone()),
+                        justification: "1 · 2 ⋯ n = n!".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: true,
+            cost: 1,
+            ..Default::default()
+        },
+        // Π_{i=a}^{b} c = c^(b-a+1)
+        Rule {
+            id: RuleId(216),
+            name: "product_constant",
+            category: RuleCategory::Simplification,
+            description: "Π_{i=a}^{b} c = c^(b-a+1)",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::BigProduct],
+            is_applicable: |expr, _ctx| {
+                matches!(expr, Expr::BigProduct { var, body, .. }
+                    if !body.free_vars().contains(var))
+            },
+            apply: |expr, _ctx| {
+                if let Expr::BigProduct { from, to, body, .. } = expr {
+                    if is_empty_range(from, to) {
+                        return vec![RuleApplication {
+                            result: Expr::int(1),
+                            justification: "An empty product is 1".to_string(),
+                            condition: None,
+                        }];
+                    }
+                    return vec![RuleApplication {
+                        result: Expr::Pow(body.clone(), Box::new(term_count(from, to))),
+                        justification: "A constant factor repeated b-a+1 times".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        },
+        // Π_{i=a}^{b} f(i+1)/f(i) = f(b+1)/f(a)
+        Rule {
+            id: RuleId(217),
+            name: "product_telescoping",
+            category: RuleCategory::Simplification,
+            description: "Π_{i=a}^{b} f(i+1)/f(i) = f(b+1)/f(a)",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::BigProduct],
+            is_applicable: |expr, _ctx| matches!(expr, Expr::BigProduct { var, body, .. } if is_telescoping(body, *var)),
+            apply: |expr, _ctx| {
+                if let Expr::BigProduct {
+                    var,
+                    from,
+                    to,
+                    body,
+                } = expr
+                {
+                    if let Expr::Div(_, den) = body.as_ref() {
+                        let past_end = Expr::Add(to.clone(), Box::new(Expr::int(1)));
+                        return vec![RuleApplication {
+                            result: Expr::Div(
+                                Box::new(substitute_var(den, *var, &past_end)),
+                                Box::new(substitute_var(den, *var, from)),
+                            ),
+                            justification: "Telescoping product: inner factors cancel".to_string(),
+                            condition: None,
+                        }];
+                    }
+                }
+                vec![]
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        },
+        // Π over concrete integer bounds, multiplied out
+        Rule {
+            id: RuleId(218),
+            name: "product_expand",
+            category: RuleCategory::Simplification,
+            description: "Π_{i=a}^{b} f(i) = f(a) · f(a+1) ⋯ f(b) for integer a, b",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::BigProduct],
+            is_applicable: |expr, _ctx| {
+                matches!(expr, Expr::BigProduct { .. }) && concrete_bounds(expr).is_some()
+            },
+            apply: |expr, _ctx| {
+                let (Some((from, to)), Expr::BigProduct { var, body, .. }) =
+                    (concrete_bounds(expr), expr)
+                else {
+                    return vec![];
+                };
+                let factors: Vec<Expr> = (from..=to)
+                    .map(|i| substitute_var(body, *var, &Expr::int(i)).canonicalize())
+                    .collect();
+                let result = if factors.iter().all(Expr::is_const) {
+                    let value =
+                        factors
+                            .iter()
+                            .try_fold(Rational::from_integer(1), |acc, f| match f {
+                                Expr::Const(c) => acc.checked_mul(c),
+                                _ => Some(acc),
+                            });
+                    // Leave the product alone when its value overflows
+                    let Some(value) = value else {
+                        return vec![];
+                    };
+                    Expr::Const(value)
+                } else {
+                    factors
+                        .into_iter()
+                        .reduce(|acc, f| Expr::Mul(Box::new(acc), Box::new(f)))
+                        .unwrap_or_else(|| Expr::int(1))
+                };
+                vec![RuleApplication {
+                    result,
+                    justification: format!("Multiply out the product for i = {}..{}", from, to),
+                    condition: None,
+                }]
+            },
+            reversible: false,
+            cost: 2,
+            ..Default::default()
+        },
+    ]
+}
+
+// ============================================================================
+// Summation Formulas (ID 270+)
+// ============================================================================
+
+fn summation_rules() -> Vec<Rule> {
+    vec![
+        // Σ_{i=a}^{b} c = c(b-a+1)
+        Rule {
+            id: RuleId(270),
+            name: "summation_constant",
+            category: RuleCategory::Simplification,
+            description: "Σ_{i=a}^{b} c = c(b-a+1)",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::Summation],
+            is_applicable: |expr, _ctx| {
+                matches!(expr, Expr::Summation { var, body, .. }
+                    if !body.free_vars().contains(var))
+            },
+            apply: |expr, _ctx| {
+                if let Expr::Summation { from, to, body, .. } = expr {
+                    if is_empty_range(from, to) {
+                        return vec![RuleApplication {
+                            result: Expr::int(0),
+                            justification: "An empty sum is 0".to_string(),
+                            condition: None,
+                        }];
+                    }
+                    return vec![RuleApplication {
+                        result: Expr::Mul(body.clone(), Box::new(term_count(from, to))),
+                        justification: "A constant term repeated b-a+1 times".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        },
+        // Σ_{i=1}^{n} i = n(n+1)/2
+        Rule {
+            id: RuleId(271),
+            name: "summation_arithmetic",
+            category: RuleCategory::Simplification,
+            description: "Σ_{i=1}^{n} i = n(n+1)/2",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::Summation],
+            is_applicable: |expr, _ctx| power_sum(expr) == Some(1),
+            apply: |expr, _ctx| {
+                if let Expr::Summation { to, .. } = expr {
+                    return vec![RuleApplication {
+                        result: Expr::Div(Box::new(triangular(to)), Box::new(Expr::int(2))),
+                        justification: "1 + 2 + ⋯ + n = n(n+1)/2".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: true,
+            cost: 1,
+            ..Default::default()
+        },
+        // Σ_{i=1}^{n} i² = n(n+1)(2n+1)/6
+        Rule {
+            id: RuleId(272),
+            name: "summation_squares",
+            category: RuleCategory::Simplification,
+            description: "Σ_{i=1}^{n} i² = n(n+1)(2n+1)/6",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::Summation],
+            is_applicable: |expr, _ctx| power_sum(expr) == Some(2),
+            apply: |expr, _ctx| {
+                if let Expr::Summation { to, .. } = expr {
+                    let two_n_plus_1 = Expr::Add(
+                        Box::new(Expr::Mul(Box::new(Expr::int(2)), to.clone())),
+                        Box::new(Expr::int(1)),
+                    );
+                    return vec![RuleApplication {
+                        result: Expr::Div(
+                            Box::new(Expr::Mul(Box::new(triangular(to)), Box::new(two_n_plus_1))),
+                            Box::new(Expr::int(6)),
+                        ),
+                        justification: "1² + 2² + ⋯ + n² = n(n+1)(2n+1)/6".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: true,
+            cost: 1,
+            ..Default::default()
+        },
+        // Σ_{i=1}^{n} i³ = (n(n+1)/2)²
+        Rule {
+            id: RuleId(273),
+            name: "summation_cubes",
+            category: RuleCategory::Simplification,
+            description: "Σ_{i=1}^{n} i³ = (n(n+1)/2)²",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::Summation],
+            is_applicable: |expr, _ctx| power_sum(expr) == Some(3),
+            apply: |expr, _ctx| {
+                if let Expr::Summation { to, .. } = expr {
+                    let half = Expr::Div(Box::new(triangular(to)), Box::new(Expr::int(2)));
+                    return vec![RuleApplication {
+                        result: Expr::Pow(Box::new(half), Box::new(Expr::int(2))),
+                        justification: "1³ + 2³ + ⋯ + n³ = (n(n+1)/2)²".to_string(),
+                        condition: None,
+                    }];
+                }
+                vec![]
+            },
+            reversible: true,
+            cost: 1,
+            ..Default::default()
+        },
+        // Σ over concrete integer bounds, added up
+        Rule {
+            id: RuleId(274),
+            name: "summation_expand",
+            category: RuleCategory::Simplification,
+            description: "Σ_{i=a}^{b} f(i) = f(a) + f(a+1) + ⋯ + f(b) for integer a, b",
+            domains: &[Domain::NumberTheory],
+            requires: &[],
+            roots: &[ExprKind::Summation],
+            is_applicable: |expr, _ctx| {
+                matches!(expr, Expr::Summation { .. }) && concrete_bounds(expr).is_some()
+            },
+            apply: |expr, _ctx| {
+                let (Some((from, to)), Expr::Summation { var, body, .. }) =
+                    (concrete_bounds(expr), expr)
+                else {
+                    return vec![];
+                };
+                let terms: Vec<Expr> = (from..=to)
+                    .map(|i| substitute_var(body, *var, &Expr::int(i)).canonicalize())
+                    .collect();
+                let result = if terms.iter().all(Expr::is_const) {
+                    let value =
+                        terms
+                            .iter()
+                            .try_fold(Rational::from_integer(0), |acc, t| match t {
+                                Expr::Const(c) => acc.checked_add(c),
+                                _ => Some(acc),
+                            });
+                    // Leave the sum alone when its value overflows
+                    let Some(value) = value else {
+                        return vec![];
+                    };
+                    Expr::Const(value)
+                } else {
+                    terms
+                        .into_iter()
+                        .reduce(|acc, t| Expr::Add(Box::new(acc), Box::new(t)))
+                        .unwrap_or_else(|| Expr::int(0))
+                };
+                vec![RuleApplication {
+                    result,
+                    justification: format!("Add up the terms for i = {}..{}", from, to),
+                    condition: None,
+                }]
+            },
+            reversible: false,
+            cost: 2,
+            ..Default::default()
+        },
+    ]
+}
+
+/// `k` when `expr` is `Σ_{i=1}^{n} i^k`, with `i` itself counting as `k = 1`.
+fn power_sum(expr: &Expr) -> Option<i64> {
+    let Expr::Summation {
+        var, from, body, ..
+    } = expr
+    else {
+        return None;
+    };
+    if !from.is_one() {
+        return None;
+    }
+    match body.as_ref() {
+        Expr::Var(v) if v == var => Some(1),
+        Expr::Pow(base, exp) if **base == Expr::Var(*var) => match exp.as_ref() {
+            Expr::Const(k) if k.is_integer() => Some(k.numer()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `n(n+1)`
+fn triangular(n: &Expr) -> Expr {
+    Expr::Mul(
+        Box::new(n.clone()),
+        Box::new(Expr::Add(Box::new(n.clone()), Box::new(Expr::int(1)))),
+    )
+}
+
+/// Most terms `product_expand` and `summation_expand` write out.
+const MAX_EXPANDED_TERMS: i64 = 100;
+
+/// Number of terms in `Π_{i=from}^{to}` or `Σ_{i=from}^{to}`, folded when
+/// both bounds are constants.
+fn term_count(from: &Expr, to: &Expr) -> Expr {
+    match (from, to) {
+        (Expr::Const(a), Expr::Const(b)) => Expr::Const(*b - *a + Rational::from_integer(1)),
+        _ if from.is_one() => to.clone(),
+        _ => Expr::Add(
+            Box::new(Expr::Sub(Box::new(to.clone()), Box::new(from.clone()))),
+            Box::new(Expr::int(1)),
+        ),
+    }
+}
+
+/// Whether the bounds are constants with `to < from`, so the range has no terms.
+fn is_empty_range(from: &Expr, to: &Expr) -> bool {
+    matches!((from, to), (Expr::Const(a), Expr::Const(b)) if b < a)
+}
+
+/// Whether `body` is `f(i+1)/f(i)` for the bound variable `i`.
+fn is_telescoping(body: &Expr, var: Symbol) -> bool {
+    let Expr::Div(num, den) = body else {
+        return false;
+    };
+    if !den.free_vars().contains(&var) {
+        return false;
+    }
+    let next = Expr::Add(Box::new(Expr::Var(var)), Box::new(Expr::int(1)));
+    substitute_var(den, var, &next).canonicalize() == num.canonicalize()
+}
+
+/// Integer bounds `(a, b)` of a product or summation with at most
+/// [`MAX_EXPANDED_TERMS`] terms. An empty range (`b < a`) is allowed.
+fn concrete_bounds(expr: &Expr) -> Option<(i64, i64)> {
+    let (Expr::BigProduct { from, to, .. } | Expr::Summation { from, to, .. }) = expr else {
+        return None;
+    };
+    let (Expr::Const(a), Expr::Const(b)) = (from.as_ref(), to.as_ref()) else {
+        return None;
+    };
+    if !a.is_integer() || !b.is_integer() {
+        return None;
+    }
+    let count = b.numer().checked_sub(a.numer())?.checked_add(1)?;
+    (count <= MAX_EXPANDED_TERMS).then_some((a.numer(), b.numer()))
+}
+
 // ============================================================================
 // Floor/Ceiling Rules (ID 240+)
 // ============================================================================
@@ -1704,6 +2327,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             description: "⌊n⌋ = n for integer n",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Floor],
             is_applicable: |expr, _ctx| {
                 if let Expr::Floor(inner) = expr {
                     // Integer check: if it's a Const with denominator 1
@@ -1720,6 +2344,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
                             return vec![RuleApplication {
                                 result: *inner.clone(),
                                 justification: "⌊n⌋ = n for integer n".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1728,6 +2353,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // ⌈n⌉ = n for integer n
         Rule {
@@ -1737,6 +2363,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             description: "⌈n⌉ = n for integer n",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Ceiling],
             is_applicable: |expr, _ctx| {
                 if let Expr::Ceiling(inner) = expr {
                     if let Expr::Const(r) = inner.as_ref() {
@@ -1752,6 +2379,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
                             return vec![RuleApplication {
                                 result: *inner.clone(),
                                 justification: "⌈n⌉ = n for integer n".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1760,6 +2388,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 1,
+            ..Default::default()
         },
         // ⌈x⌉ - ⌊x⌋ = 0 or 1
         Rule {
@@ -1769,6 +2398,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             description: "⌈x⌉ - ⌊x⌋ = 0 or 1",
             domains: &[Domain::NumberTheory],
             requires: &[],
+            roots: &[ExprKind::Sub],
             is_applicable: |expr, _ctx| {
                 // Match: Sub(Ceiling(x), Floor(x))
                 if let Expr::Sub(a, b) = expr {
@@ -1789,11 +2419,13 @@ fn floor_ceiling_rules() -> Vec<Rule> {
                                 RuleApplication {
                                     result: Expr::int(0),
                                     justification: "⌈x⌉ - ⌊x⌋ = 0 (when x is integer)".to_string(),
+                                    condition: None,
                                 },
                                 RuleApplication {
                                     result: Expr::int(1),
                                     justification: "⌈x⌉ - ⌊x⌋ = 1 (when x is non-integer)"
                                         .to_string(),
+                                    condition: None,
                                 },
                             ];
                         }
@@ -1803,6 +2435,7 @@ fn floor_ceiling_rules() -> Vec<Rule> {
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -1903,6 +2536,7 @@ fn fermat_little_theorem() -> Rule {
         description: "a^(p-1) ≡ 1 (mod p)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod(Pow(a, p-1), p)
             if let Expr::Mod(inner, modulus) = expr {
@@ -1924,10 +2558,12 @@ fn fermat_little_theorem() -> Rule {
                 justification:
                     "Fermat's Little Theorem: a^(p-1) ≡ 1 (mod p) for prime p, gcd(a,p)=1"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1940,6 +2576,7 @@ fn fermat_last_theorem() -> Rule {
         description: "No integer solutions to x^n + y^n = z^n for n > 2",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             // Match: Equation{ lhs: Add(Pow(x,n), Pow(y,n)), rhs: Pow(z,n) }
             if let Expr::Equation { lhs, rhs } = expr {
@@ -1960,10 +2597,12 @@ fn fermat_last_theorem() -> Rule {
                 justification:
                     "Fermat's Last Theorem: No integer solutions for x^n + y^n = z^n when n > 2"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1976,6 +2615,7 @@ fn euler_theorem() -> Rule {
         description: "a^φ(n) ≡ 1 (mod n)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod(Pow(a, phi(n)), n)
             if let Expr::Mod(inner, _modulus) = expr {
@@ -1987,10 +2627,12 @@ fn euler_theorem() -> Rule {
             vec![RuleApplication {
                 result: Expr::int(1),
                 justification: "Euler's Theorem: a^φ(n) ≡ 1 (mod n) for gcd(a,n)=1".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2003,6 +2645,7 @@ fn euler_phi_multiplicative() -> Rule {
         description: "φ(mn) = φ(m)φ(n) for gcd(m,n)=1",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             // Match: Mul(expr, expr) as the Euler phi is multiplicative
             matches!(expr, Expr::Mul(_, _))
@@ -2014,12 +2657,14 @@ fn euler_phi_multiplicative() -> Rule {
                     result: Expr::Mul(m.clone(), n.clone()),
                     justification: "φ(mn) = φ(m)φ(n) for coprime m, n (Euler phi multiplicativity)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2032,6 +2677,7 @@ fn euler_phi_prime_power() -> Rule {
         description: "φ(p^k) = p^k - p^(k-1)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match: Pow(p, k) for prime power
             matches!(expr, Expr::Pow(_, _))
@@ -2045,12 +2691,14 @@ fn euler_phi_prime_power() -> Rule {
                 return vec![RuleApplication {
                     result,
                     justification: "φ(p^k) = p^k - p^(k-1) for prime p".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2063,6 +2711,7 @@ fn chinese_remainder_theorem() -> Rule {
         description: "CRT: x ≡ a_i (mod m_i) has unique solution mod Π m_i",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Equation, ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match system of Mod equations - for now just detect pattern
             matches!(expr, Expr::Equation { .. } | Expr::Mod(_, _))
@@ -2074,10 +2723,12 @@ fn chinese_remainder_theorem() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "CRT: System x ≡ a_i (mod m_i) has unique solution mod Π m_i for coprime m_i. Use extended GCD to compute.".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2090,6 +2741,7 @@ fn quadratic_residue() -> Rule {
         description: "Quadratic residue test",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod(Pow(x, 2), p) or Equation with Mod
             if let Expr::Mod(inner, _) = expr {
@@ -2104,12 +2756,14 @@ fn quadratic_residue() -> Rule {
                     justification:
                         "a is a quadratic residue mod p if a = x² (mod p) has a solution"
                             .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2122,6 +2776,7 @@ fn legendre_symbol_multiplicative() -> Rule {
         description: "(ab/p) = (a/p)(b/p)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             // Legendre symbol involves Mul and Mod
             matches!(expr, Expr::Mul(_, _))
@@ -2132,12 +2787,14 @@ fn legendre_symbol_multiplicative() -> Rule {
                     result: Expr::Mul(a.clone(), b.clone()),
                     justification: "Legendre symbol is multiplicative: (ab/p) = (a/p)(b/p)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2150,6 +2807,7 @@ fn euler_criterion() -> Rule {
         description: "(a/p) = a^((p-1)/2) mod p",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod(Pow(a, (p-1)/2), p)
             if let Expr::Mod(inner, _) = expr {
@@ -2164,12 +2822,14 @@ fn euler_criterion() -> Rule {
                     result: Expr::int(1),
                     justification: "Euler's criterion: (a/p) = a^((p-1)/2) mod p = ±1 or 0"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2182,6 +2842,7 @@ fn prime_counting_approx() -> Rule {
         description: "π(x) ~ x/ln(x)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match: Div(x, Ln(x))
             if let Expr::Div(num, denom) = expr {
@@ -2197,12 +2858,14 @@ fn prime_counting_approx() -> Rule {
                     result: *x.clone(),
                     justification: "Prime counting function: π(x) ~ x/ln(x) (asymptotic)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2215,6 +2878,7 @@ fn bertrand_postulate() -> Rule {
         description: "Prime exists between n and 2n",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Gt, ExprKind::Lt],
         is_applicable: |expr, _ctx| {
             // Match: Lt(n, Mul(2, n)) or Gt pattern for range
             matches!(expr, Expr::Gt(_, _) | Expr::Lt(_, _))
@@ -2224,10 +2888,12 @@ fn bertrand_postulate() -> Rule {
                 result: Expr::Const(Rational::from_integer(1)), // True
                 justification: "Bertrand's postulate: For n > 1, ∃ prime p with n < p < 2n"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2240,6 +2906,7 @@ fn linear_diophantine() -> Rule {
         description: "ax + by = c solvable iff gcd(a,b) | c",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             // Match linear equations: Equation { lhs: Add(Mul(a,x), Mul(b,y)), rhs: c }
             matches!(expr, Expr::Equation { .. })
@@ -2254,6 +2921,7 @@ fn linear_diophantine() -> Rule {
                         },
                         justification: "Linear Diophantine: ax + by = c solvable iff gcd(a,b) | c"
                             .to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -2261,6 +2929,7 @@ fn linear_diophantine() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2273,6 +2942,7 @@ fn pell_equation() -> Rule {
         description: "Pell equation x² - Dy² = 1",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Equation],
         is_applicable: |expr, _ctx| {
             // Match: Equation { lhs: Sub(Pow(x,2), Mul(D, Pow(y,2))), rhs: 1 }
             if let Expr::Equation { lhs, rhs } = expr {
@@ -2287,12 +2957,14 @@ fn pell_equation() -> Rule {
                 return vec![RuleApplication {
                     result: Expr::Equation { lhs: lhs.clone(), rhs: rhs.clone() },
                     justification: "Pell equation x² - Dy² = 1 has infinitely many solutions via continued fractions".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2305,6 +2977,7 @@ fn sum_of_two_squares() -> Rule {
         description: "Sum of two squares condition",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Match: Add(Pow(a,2), Pow(b,2))
             if let Expr::Add(a, b) = expr {
@@ -2320,10 +2993,12 @@ fn sum_of_two_squares() -> Rule {
                 justification:
                     "n = a² + b² iff primes ≡ 3 (mod 4) have even exponents in n's factorization"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2336,6 +3011,7 @@ fn sum_of_four_squares() -> Rule {
         description: "Every n = a² + b² + c² + d²",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const],
         is_applicable: |expr, _ctx| {
             // Match any positive integer - this is Lagrange's four square theorem
             matches!(expr, Expr::Const(c) if !c.is_negative())
@@ -2344,10 +3020,12 @@ fn sum_of_four_squares() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Lagrange's four square theorem: Every positive integer is a sum of four squares".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2360,6 +3038,7 @@ fn wilson_theorem() -> Rule {
         description: "(p-1)! ≡ -1 (mod p)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod(Factorial(p-1), p)
             if let Expr::Mod(inner, _) = expr {
@@ -2371,10 +3050,12 @@ fn wilson_theorem() -> Rule {
             vec![RuleApplication {
                 result: Expr::Neg(Box::new(Expr::int(1))),
                 justification: "Wilson's theorem: (p-1)! ≡ -1 (mod p) for prime p".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2387,6 +3068,7 @@ fn hensel_lemma() -> Rule {
         description: "Hensel's lemma for lifting solutions",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Equation, ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod equations that could be lifted
             matches!(expr, Expr::Mod(_, _) | Expr::Equation { .. })
@@ -2396,10 +3078,12 @@ fn hensel_lemma() -> Rule {
                 result: expr.clone(),
                 justification: "Hensel's lemma: Solutions mod p can be lifted to solutions mod p^k"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2412,6 +3096,7 @@ fn order_divides_phi() -> Rule {
         description: "ord_n(a) | φ(n)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::GCD, ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: GCD or divisibility expressions
             matches!(expr, Expr::GCD(_, _) | Expr::Mod(_, _))
@@ -2420,10 +3105,12 @@ fn order_divides_phi() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Order divides phi: ord_n(a) | φ(n) for gcd(a,n)=1".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2436,6 +3123,7 @@ fn primitive_root_existence() -> Rule {
         description: "Primitive roots exist for n = 1,2,4,p^k,2p^k",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const, ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             // Match: Const(n) where n is a prime power or 2*prime power
             matches!(expr, Expr::Const(_) | Expr::Pow(_, _))
@@ -2445,10 +3133,12 @@ fn primitive_root_existence() -> Rule {
                 result: expr.clone(),
                 justification: "Primitive roots exist for n = 1, 2, 4, p^k, 2p^k (odd prime p)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2461,6 +3151,7 @@ fn legendre_formula() -> Rule {
         description: "ν_p(n!) = Σ⌊n/p^k⌋",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Factorial],
         is_applicable: |expr, _ctx| {
             // Match: Factorial expressions
             matches!(expr, Expr::Factorial(_))
@@ -2469,10 +3160,12 @@ fn legendre_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Legendre's formula: ν_p(n!) = Σ⌊n/p^k⌋ for k ≥ 1".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2485,6 +3178,7 @@ fn lucas_theorem() -> Rule {
         description: "Lucas' theorem for binomials mod p",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mod],
         is_applicable: |expr, _ctx| {
             // Match: Mod of binomial coefficients
             matches!(expr, Expr::Mod(_, _))
@@ -2493,10 +3187,12 @@ fn lucas_theorem() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Lucas' theorem: C(m,n) ≡ Π C(m_i, n_i) (mod p)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2509,6 +3205,7 @@ fn mobius_inversion() -> Rule {
         description: "Möbius inversion formula",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match: Sum or Div expressions (divisor sums)
             matches!(expr, Expr::Div(_, _) | Expr::Add(_, _))
@@ -2517,10 +3214,12 @@ fn mobius_inversion() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Möbius inversion: f(n) = Σ g(d) ⇒ g(n) = Σ μ(d)f(n/d)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2533,6 +3232,7 @@ fn mobius_multiplicative() -> Rule {
         description: "μ(mn) = μ(m)μ(n) for gcd(m,n)=1",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             if let Expr::Mul(m, n) = expr {
@@ -2540,12 +3240,14 @@ fn mobius_multiplicative() -> Rule {
                     result: Expr::Mul(m.clone(), n.clone()),
                     justification: "μ(mn) = μ(m)μ(n) for coprime m, n (Mobius is multiplicative)"
                         .to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2558,16 +3260,19 @@ fn chebyshev_prime_bounds() -> Rule {
         description: "Chebyshev bounds on π(x)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Ln, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Ln(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev bounds: c₁x/ln(x) < π(x) < c₂x/ln(x) for x ≥ 2"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2580,6 +3285,7 @@ fn even_perfect_number() -> Rule {
         description: "Even perfect = 2^(p-1)(2^p - 1)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mul],
         is_applicable: |expr, _ctx| {
             // Match: Mul(Pow(2, p-1), Sub(Pow(2, p), 1))
             if let Expr::Mul(a, b) = expr {
@@ -2592,10 +3298,12 @@ fn even_perfect_number() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Euclid-Euler: Even perfect numbers have form 2^(p-1)(2^p - 1) where 2^p - 1 is prime".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2608,6 +3316,7 @@ fn mersenne_prime_condition() -> Rule {
         description: "2^p - 1 prime => p prime",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Sub],
         is_applicable: |expr, _ctx| {
             // Match: Sub(Pow(2, p), 1)
             if let Expr::Sub(a, b) = expr {
@@ -2623,10 +3332,12 @@ fn mersenne_prime_condition() -> Rule {
                 justification:
                     "Mersenne prime condition: If 2^p - 1 is prime, then p must be prime"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2655,6 +3366,7 @@ fn sum_of_divisors() -> Rule {
         description: "σ(n) = Σ d for d|n",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const],
         is_applicable: |expr, _ctx| {
             // Match: Const (for computing divisor sums)
             if let Expr::Const(n) = expr {
@@ -2680,6 +3392,7 @@ fn sum_of_divisors() -> Rule {
                                 "Sum of divisors: σ({}) = {} (sum of all divisors)",
                                 num, sum
                             ),
+                            condition: None,
                         }];
                     }
                 }
@@ -2687,10 +3400,12 @@ fn sum_of_divisors() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Sum of divisors: σ(n) = Σ d for all d | n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2718,6 +3433,7 @@ fn number_of_divisors() -> Rule {
         description: "τ(n) is number of divisors",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const],
         is_applicable: |expr, _ctx| {
             if let Expr::Const(n) = expr {
                 return n.is_positive() && n.is_integer() && n.numer() < 1000;
@@ -2742,6 +3458,7 @@ fn number_of_divisors() -> Rule {
                                 "Number of divisors: τ({}) = {} (count of all divisors)",
                                 num, count
                             ),
+                            condition: None,
                         }];
                     }
                 }
@@ -2749,10 +3466,12 @@ fn number_of_divisors() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Number of divisors: τ(n) counts divisors of n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2765,15 +3484,18 @@ fn totient_sum() -> Rule {
         description: "Σ φ(d) = n for d|n",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const, ExprKind::Var],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Const(_) | Expr::Var(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Totient sum identity: Σ φ(d) = n for all d | n".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2786,6 +3508,7 @@ fn primitive_root_count() -> Rule {
         description: "φ(φ(n)) primitive roots mod n",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Const(_) | Expr::Pow(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -2793,10 +3516,12 @@ fn primitive_root_count() -> Rule {
                 justification:
                     "Primitive root count: φ(φ(n)) primitive roots exist mod n (when they exist)"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2809,16 +3534,19 @@ fn carmichael_function() -> Rule {
         description: "Carmichael function λ(n)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const, ExprKind::LCM],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Const(_) | Expr::LCM(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Carmichael function λ(n) = lcm of orders of elements mod n"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2831,6 +3559,7 @@ fn square_free_density() -> Rule {
         description: "Square-free density = 6/π²",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| {
             // Match: Div(6, Pow(Pi, 2))
             if let Expr::Div(num, denom) = expr {
@@ -2844,10 +3573,12 @@ fn square_free_density() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Density of square-free integers is 6/π² = 1/ζ(2)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2860,6 +3591,7 @@ fn prime_gap_bound() -> Rule {
         description: "Prime gap upper bounds",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Sub, ExprKind::Gt],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Gt(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -2867,10 +3599,12 @@ fn prime_gap_bound() -> Rule {
                 justification:
                     "Prime gap bound: For large n, gap between consecutive primes is O(n^0.525)"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2883,6 +3617,7 @@ fn sophie_germain_prime() -> Rule {
         description: "Sophie Germain: p and 2p+1 both prime",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Match: Add(Mul(2, p), 1) pattern for 2p+1
             if let Expr::Add(a, b) = expr {
@@ -2896,10 +3631,12 @@ fn sophie_germain_prime() -> Rule {
             vec![RuleApplication {
                 result: Expr::Const(Rational::from_integer(1)),
                 justification: "Sophie Germain prime: Both p and 2p+1 are prime".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2912,6 +3649,7 @@ fn quadratic_reciprocity() -> Rule {
         description: "Quadratic reciprocity law",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Mul, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Pow(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -2919,10 +3657,12 @@ fn quadratic_reciprocity() -> Rule {
                 justification:
                     "Quadratic reciprocity: (p/q)(q/p) = (-1)^((p-1)(q-1)/4) for odd primes p,q"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2935,6 +3675,7 @@ fn jacobi_symbol() -> Rule {
         description: "Jacobi symbol (a/n)",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div, ExprKind::Mod],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mod(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -2942,10 +3683,12 @@ fn jacobi_symbol() -> Rule {
                 justification:
                     "Jacobi symbol (a/n) generalizes Legendre symbol to composite moduli"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2958,15 +3701,18 @@ fn kronecker_symbol() -> Rule {
         description: "Kronecker symbol extension",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div, ExprKind::Mod],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mod(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Kronecker symbol extends Jacobi symbol to all integers".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2979,6 +3725,7 @@ fn tonelli_shanks() -> Rule {
         description: "Tonelli-Shanks modular square root",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Sqrt, ExprKind::Mod],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Mod(_, _) | Expr::Sqrt(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -2986,10 +3733,12 @@ fn tonelli_shanks() -> Rule {
                 justification:
                     "Tonelli-Shanks: Computes modular square root x where x² ≡ a (mod p)"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -3002,15 +3751,18 @@ fn discrete_log_order() -> Rule {
         description: "Discrete logarithm order",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Pow, ExprKind::Mod],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Mod(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Discrete log: Find x such that g^x ≡ h (mod n)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3023,16 +3775,19 @@ fn continued_fraction_gcd() -> Rule {
         description: "GCD via continued fractions",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div, ExprKind::GCD],
         is_applicable: |expr, _ctx| matches!(expr, Expr::GCD(_, _) | Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Continued fraction expansion computes GCD via convergents"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3045,6 +3800,7 @@ fn farey_neighbors() -> Rule {
         description: "Farey neighbors: |ad - bc| = 1",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Abs, ExprKind::Sub],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Abs(_)),
         apply: |_expr, _ctx| {
             vec![RuleApplication {
@@ -3052,10 +3808,12 @@ fn farey_neighbors() -> Rule {
                 justification:
                     "Farey neighbors: Adjacent fractions a/b and c/d satisfy |ad - bc| = 1"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -3068,15 +3826,18 @@ fn stern_brocot() -> Rule {
         description: "Stern-Brocot tree mediant",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Add, ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Stern-Brocot: Mediant of a/b and c/d is (a+c)/(b+d)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -3089,16 +3850,19 @@ fn egyptian_fraction() -> Rule {
         description: "Egyptian fraction decomposition",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Div],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Egyptian fraction: Express a/b as sum of distinct unit fractions"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -3111,6 +3875,7 @@ fn gaussian_norm() -> Rule {
         description: "Gaussian integer norm N(a+bi) = a² + b²",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Add],
         is_applicable: |expr, _ctx| {
             // Match: Add(Pow(a, 2), Pow(b, 2))
             if let Expr::Add(a, b) = expr {
@@ -3123,10 +3888,12 @@ fn gaussian_norm() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Gaussian norm: N(a+bi) = a² + b² is multiplicative".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -3139,15 +3906,252 @@ fn gaussian_prime() -> Rule {
         description: "Gaussian prime conditions",
         domains: &[Domain::NumberTheory],
         requires: &[],
+        roots: &[ExprKind::Const, ExprKind::Add],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Const(_) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Gaussian primes: p ≡ 3 (mod 4) or factors of primes ≡ 1 (mod 4)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleContext;
+    use mm_core::SymbolTable;
+
+    fn factorial_ratio() -> Rule {
+        factorial_rules()
+            .into_iter()
+            .find(|r| r.name == "factorial_ratio")
+            .unwrap()
+    }
+
+    fn factorial(e: Expr) -> Box<Expr> {
+        Box::new(Expr::Factorial(Box::new(e)))
+    }
+
+    #[test]
+    fn test_factorial_ratio_constant() {
+        let rule = factorial_ratio();
+        let ctx = RuleContext::default();
+
+        // 5!/4! → 5
+        let expr = Expr::Div(factorial(Expr::int(5)), factorial(Expr::int(4)));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::int(5));
+
+        // 4!/7! → 1/210
+        let expr = Expr::Div(factorial(Expr::int(4)), factorial(Expr::int(7)));
+        assert_eq!(
+            rule.apply(&expr, &ctx)[0].result,
+            Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(210)))
+        );
+
+        // The rule set offers it for quotients through its root index
+        assert_eq!(rule.applicable_roots(), &[ExprKind::Div]);
+        assert!(crate::rule::standard_rules()
+            .applicable(&expr, &ctx)
+            .iter()
+            .any(|r| r.name == "factorial_ratio"));
+    }
+
+    #[test]
+    fn test_factorial_ratio_symbolic() {
+        let mut symbols = SymbolTable::new();
+        let n = symbols.intern("n");
+        let rule = factorial_ratio();
+        let ctx = RuleContext::default();
+
+        // n!/(n-1)! → n
+        let n_minus_1 = Expr::Sub(Box::new(Expr::Var(n)), Box::new(Expr::int(1)));
+        let expr = Expr::Div(factorial(Expr::Var(n)), factorial(n_minus_1));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::Var(n));
+
+        // (n+1)!/(n-1)! → (n+1) · n
+        let n_plus_1 = Expr::Add(Box::new(Expr::Var(n)), Box::new(Expr::int(1)));
+        let n_minus_1 = Expr::Sub(Box::new(Expr::Var(n)), Box::new(Expr::int(1)));
+        let expr = Expr::Div(factorial(n_plus_1.clone()), factorial(n_minus_1));
+        let result = &rule.apply(&expr, &ctx)[0].result;
+        let expected = Expr::Mul(
+            Box::new(n_plus_1.clone()),
+            Box::new(Expr::Sub(Box::new(n_plus_1), Box::new(Expr::int(1)))),
+        );
+        assert!(result.approx_equals(&expected, 10, 1e-9));
+
+        // Unrelated arguments are left alone
+        let m = symbols.intern("m");
+        let expr = Expr::Div(factorial(Expr::Var(n)), factorial(Expr::Var(m)));
+        assert!(!rule.can_apply(&expr, &ctx));
+    }
+
+    fn product_rule(name: &str) -> Rule {
+        product_rules()
+            .into_iter()
+            .find(|r| r.name == name)
+            .unwrap()
+    }
+
+    fn product(var: Symbol, from: Expr, to: Expr, body: Expr) -> Expr {
+        Expr::BigProduct {
+            var,
+            from: Box::new(from),
+            to: Box::new(to),
+            body: Box::new(body),
+        }
+    }
+
+    #[test]
+    fn test_product_expand() {
+        let mut symbols = SymbolTable::new();
+        let i = symbols.intern("i");
+        let ctx = RuleContext::default();
+        let rule = product_rule("product_expand");
+
+        // Π_{i=1}^{5} i = 120
+        let expr = product(i, Expr::int(1), Expr::int(5), Expr::Var(i));
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::int(120));
+
+        // The empty product is 1
+        let expr = product(i, Expr::int(3), Expr::int(2), Expr::Var(i));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::int(1));
+
+        // Bounds whose distance overflows are not expanded
+        let expr = product(i, Expr::int(i64::MIN), Expr::int(i64::MAX), Expr::Var(i));
+        assert!(!rule.can_apply(&expr, &ctx));
+
+        // Π_{i=1}^{30} i overflows and is left unexpanded
+        let expr = product(i, Expr::int(1), Expr::int(30), Expr::Var(i));
+        assert!(rule.apply(&expr, &ctx).is_empty());
+
+        // Symbolic bounds are left to the closed forms
+        let n = symbols.intern("n");
+        let expr = product(i, Expr::int(1), Expr::Var(n), Expr::Var(i));
+        assert!(!rule.can_apply(&expr, &ctx));
+    }
+
+    #[test]
+    fn test_product_closed_forms() {
+        let mut symbols = SymbolTable::new();
+        let i = symbols.intern("i");
+        let n = symbols.intern("n");
+        let ctx = RuleContext::default();
+
+        // Π_{i=1}^{n} i = n!
+        let expr = product(i, Expr::int(1), Expr::Var(n), Expr::Var(i));
+        let rule = product_rule("product_factorial");
+        assert!(rule.can_apply(&expr, &ctx));
+        assert!(matches!(
+            &rule.apply(&expr, &ctx)[0].result,
+            Expr::Factorial(arg) if **arg == Expr::Var(n)
+        ));
+
+        // Π_{i=1}^{n} 3 = 3^n
+        let expr = product(i, Expr::int(1), Expr::Var(n), Expr::int(3));
+        assert_eq!(
+            product_rule("product_constant").apply(&expr, &ctx)[0].result,
+            Expr::Pow(Box::new(Expr::int(3)), Box::new(Expr::Var(n)))
+        );
+
+        // ... and is 1 over an empty range
+        let expr = product(i, Expr::int(5), Expr::int(3), Expr::int(3));
+        assert_eq!(
+            product_rule("product_constant").apply(&expr, &ctx)[0].result,
+            Expr::int(1)
+        );
+
+        // Π_{i=1}^{n} (i+1)/i = (n+1)/1
+        let next = Expr::Add(Box::new(Expr::Var(i)), Box::new(Expr::int(1)));
+        let body = Expr::Div(Box::new(next), Box::new(Expr::Var(i)));
+        let expr = product(i, Expr::int(1), Expr::Var(n), body);
+        let rule = product_rule("product_telescoping");
+        assert!(rule.can_apply(&expr, &ctx));
+        let result = &rule.apply(&expr, &ctx)[0].result;
+        let expected = Expr::Add(Box::new(Expr::Var(n)), Box::new(Expr::int(1)));
+        assert!(result.approx_equals(&expected, 10, 1e-9));
+
+        // i/(i+1) telescopes the other way round, which is not this shape
+        let body = Expr::Div(
+            Box::new(Expr::Var(i)),
+            Box::new(Expr::Add(Box::new(Expr::Var(i)), Box::new(Expr::int(1)))),
+        );
+        let expr = product(i, Expr::int(1), Expr::Var(n), body);
+        assert!(!rule.can_apply(&expr, &ctx));
+    }
+
+    fn summation_rule(name: &str) -> Rule {
+        summation_rules()
+            .into_iter()
+            .find(|r| r.name == name)
+            .unwrap()
+    }
+
+    fn summation(var: Symbol, from: Expr, to: Expr, body: Expr) -> Expr {
+        Expr::Summation {
+            var,
+            from: Box::new(from),
+            to: Box::new(to),
+            body: Box::new(body),
+        }
+    }
+
+    #[test]
+    fn test_summation_rules() {
+        let mut symbols = SymbolTable::new();
+        let i = symbols.intern("i");
+        let n = symbols.intern("n");
+        let ctx = RuleContext::default();
+        let power = |k: i64| Expr::Pow(Box::new(Expr::Var(i)), Box::new(Expr::int(k)));
+
+        // Closed forms agree with adding up the first ten terms
+        let expand = summation_rule("summation_expand");
+        for (name, body) in [
+            ("summation_constant", Expr::int(4)),
+            ("summation_arithmetic", Expr::Var(i)),
+            ("summation_squares", power(2)),
+            ("summation_cubes", power(3)),
+        ] {
+            let rule = summation_rule(name);
+            let expr = summation(i, Expr::int(1), Expr::int(10), body.clone());
+            assert!(rule.can_apply(&expr, &ctx), "{}", name);
+            let closed = rule.apply(&expr, &ctx)[0].result.canonicalize();
+            assert_eq!(closed, expand.apply(&expr, &ctx)[0].result, "{}", name);
+
+            // ... and apply to symbolic bounds, which expansion cannot
+            let expr = summation(i, Expr::int(1), Expr::Var(n), body);
+            assert!(rule.can_apply(&expr, &ctx), "{}", name);
+            assert!(!expand.can_apply(&expr, &ctx));
+        }
+
+        // Σ_{i=1}^{100} i = 5050
+        let expr = summation(i, Expr::int(1), Expr::int(100), Expr::Var(i));
+        assert_eq!(expand.apply(&expr, &ctx)[0].result, Expr::int(5050));
+
+        // Σ_{i=1}^{100} i⁹ overflows and is left unexpanded
+        let expr = summation(i, Expr::int(1), Expr::int(100), power(9));
+        assert!(expand.apply(&expr, &ctx).is_empty());
+
+        // Power sums need to start at 1
+        let expr = summation(i, Expr::int(2), Expr::Var(n), Expr::Var(i));
+        assert!(!summation_rule("summation_arithmetic").can_apply(&expr, &ctx));
+
+        // The empty sum is 0
+        let expr = summation(i, Expr::int(3), Expr::int(2), Expr::Var(i));
+        assert_eq!(expand.apply(&expr, &ctx)[0].result, Expr::int(0));
+        let expr = summation(i, Expr::int(3), Expr::int(2), Expr::int(4));
+        assert_eq!(
+            summation_rule("summation_constant").apply(&expr, &ctx)[0].result,
+            Expr::int(0)
+        );
     }
 }
@@ -9,8 +9,12 @@
 //! This module provides functions to recognize high-level patterns in expressions,
 //! such as standard integral forms, specific polynomial structures, or
 //! trigonometric identities that are too complex for simple rule closures.
+//!
+//! [`PatternRule`] covers the opposite case: identities simple enough to
+//! write down as `lhs => rhs` and load at runtime.
 
-use mm_core::Expr;
+use mm_core::pattern::{instantiate, match_wildcards};
+use mm_core::{is_metavar, Expr, Symbol};
 
 /// Represents standard forms of integrals for pattern matching.
 #[derive(Debug, Clone)]
@@ -53,3 +57,69 @@ fn is_one(expr: &Expr) -> bool {
     // involves rational comparisons.
     matches!(expr, Expr::Const(c) if c.is_one())
 }
+
+/// A rewrite rule given as a pair of expressions, `lhs => rhs`.
+///
+/// The metavariables of `lhs` are its wildcards: `?a * 0 => 0` rewrites
+/// `sin(x) * 0`. A pattern without metavariables treats every variable as a
+/// wildcard, so `sin(x)^2 + cos(x)^2 => 1` rewrites `sin(2t)² + cos(2t)²` as
+/// well as `sin(x)² + cos(x)²`. Built by
+/// [`Rule::from_pattern`](crate::Rule::from_pattern).
+#[derive(Debug, Clone)]
+pub struct PatternRule {
+    source: String,
+    lhs: Expr,
+    rhs: Expr,
+    wildcards: Vec<Symbol>,
+}
+
+impl PatternRule {
+    /// Rewrite `lhs` to `rhs`, treating the metavariables of `lhs`, or all
+    /// its variables if it has none, as wildcards.
+    ///
+    /// Variables of `rhs` that `lhs` does not bind are copied through
+    /// unchanged. `source` is the rule as written, used to justify rewrites.
+    pub fn new(source: String, lhs: Expr, rhs: Expr) -> Self {
+        let mut wildcards = lhs.free_vars();
+        if wildcards.iter().any(|&v| is_metavar(v)) {
+            wildcards.retain(|&v| is_metavar(v));
+        }
+        Self {
+            source,
+            lhs,
+            rhs,
+            wildcards,
+        }
+    }
+
+    /// The rule as written, e.g. `sin(x)^2 + cos(x)^2 => 1`.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The pattern matched against.
+    pub fn lhs(&self) -> &Expr {
+        &self.lhs
+    }
+
+    /// The template the match is rewritten to.
+    pub fn rhs(&self) -> &Expr {
+        &self.rhs
+    }
+
+    /// A variable of `rhs` that should have been bound by `lhs` but is not:
+    /// a metavariable, or any variable when `lhs` has no metavariables.
+    pub fn unbound(&self) -> Option<Symbol> {
+        let metavars = self.wildcards.iter().any(|&v| is_metavar(v));
+        self.rhs
+            .free_vars()
+            .into_iter()
+            .find(|&v| !self.wildcards.contains(&v) && (is_metavar(v) || !metavars))
+    }
+
+    /// Rewrite `expr` at its root, if it matches [`lhs`](Self::lhs).
+    pub fn rewrite(&self, expr: &Expr) -> Option<Expr> {
+        let bindings = match_wildcards(&self.lhs, expr, &self.wildcards)?;
+        Some(instantiate(&self.rhs, &bindings))
+    }
+}
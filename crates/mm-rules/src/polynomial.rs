@@ -16,7 +16,7 @@
 //! ```
 //! These are structurally equal in normal form.
 
-use mm_core::{Expr, Rational, Symbol};
+use mm_core::{numtheory, Expr, Rational, Symbol};
 use std::collections::BTreeMap;
 
 /// A monomial: coefficient * x₁^e₁ * x₂^e₂ * ...
@@ -339,6 +339,31 @@ impl PolynomialNF {
         None
     }
 
+    /// Coefficients of a univariate polynomial in `var`, lowest degree first.
+    ///
+    /// Returns `None` if any other variable appears. Trailing zero
+    /// coefficients are trimmed, so the zero polynomial yields an empty vec.
+    pub fn coefficients(&self, var: Symbol) -> Option<Vec<Rational>> {
+        let mut coeffs: Vec<Rational> = Vec::new();
+        for (mono, coeff) in &self.terms {
+            let mut degree = 0usize;
+            for (v, exp) in &mono.powers {
+                if *v != var {
+                    return None;
+                }
+                degree = *exp as usize;
+            }
+            if coeffs.len() <= degree {
+                coeffs.resize(degree + 1, Rational::from(0));
+            }
+            coeffs[degree] = coeffs[degree] + *coeff / self.denominator;
+        }
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+        Some(coeffs)
+    }
+
     /// Check algebraic equality with another polynomial
     pub fn equals(&self, other: &PolynomialNF) -> bool {
         // Subtract and check if zero
@@ -404,6 +429,241 @@ pub fn algebraically_equal(a: &Expr, b: &Expr) -> Option<bool> {
     Some(pa.equals(&pb))
 }
 
+/// Divide univariate polynomials, returning `(quotient, remainder)`.
+///
+/// Coefficients are lowest degree first with no trailing zeros, as returned
+/// by [`PolynomialNF::coefficients`].
+///
+/// # Panics
+///
+/// Panics if `divisor` is the zero polynomial.
+pub fn poly_div_rem(dividend: &[Rational], divisor: &[Rational]) -> (Vec<Rational>, Vec<Rational>) {
+    let lead = *divisor.last().expect("division by the zero polynomial");
+    let mut rem = dividend.to_vec();
+    if rem.len() < divisor.len() {
+        return (Vec::new(), rem);
+    }
+    let mut quot = vec![Rational::from(0); rem.len() - divisor.len() + 1];
+    for shift in (0..quot.len()).rev() {
+        let c = rem[shift + divisor.len() - 1] / lead;
+        quot[shift] = c;
+        for (i, d) in divisor.iter().enumerate() {
+            rem[shift + i] = rem[shift + i] - c * *d;
+        }
+    }
+    rem.truncate(divisor.len() - 1);
+    trim(&mut rem);
+    (quot, rem)
+}
+
+/// Monic greatest common divisor of two univariate polynomials.
+///
+/// Same coefficient layout as [`poly_div_rem`]. The GCD of two zero
+/// polynomials is zero (an empty vec).
+pub fn poly_gcd(a: &[Rational], b: &[Rational]) -> Vec<Rational> {
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    trim(&mut a);
+    trim(&mut b);
+    while !b.is_empty() {
+        let (_, r) = poly_div_rem(&a, &b);
+        (a, b) = (b, r);
+    }
+    if let Some(&lead) = a.last() {
+        for c in &mut a {
+            *c = *c / lead;
+        }
+    }
+    a
+}
+
+/// Build `cₙxⁿ + … + c₁x + c₀` from coefficients, lowest degree first.
+pub fn poly_to_expr(coeffs: &[Rational], var: Symbol) -> Expr {
+    let mut result: Option<Expr> = None;
+    for (degree, coeff) in coeffs.iter().enumerate().rev() {
+        if coeff.is_zero() {
+            continue;
+        }
+        let magnitude = coeff.abs();
+        let power = match degree {
+            0 => None,
+            1 => Some(Expr::Var(var)),
+            n => Some(Expr::Pow(
+                Box::new(Expr::Var(var)),
+                Box::new(Expr::int(n as i64)),
+            )),
+        };
+        let term = match power {
+            None => Expr::Const(magnitude),
+            Some(p) if magnitude.is_one() => p,
+            Some(p) => Expr::Mul(Box::new(Expr::Const(magnitude)), Box::new(p)),
+        };
+        result = Some(match result {
+            None if coeff.is_negative() => Expr::Neg(Box::new(term)),
+            None => term,
+            Some(acc) if coeff.is_negative() => Expr::Sub(Box::new(acc), Box::new(term)),
+            Some(acc) => Expr::Add(Box::new(acc), Box::new(term)),
+        });
+    }
+    result.unwrap_or(Expr::int(0))
+}
+
+/// Distinct rational roots of a univariate polynomial, in increasing order.
+///
+/// Same coefficient layout as [`poly_div_rem`]. By the rational root
+/// theorem, once the coefficients are cleared of denominators every nonzero
+/// rational root is ±p/q with p dividing the lowest nonzero coefficient and
+/// q the leading one; each candidate is checked exactly. Linear and
+/// quadratic polynomials are solved directly, the latter through a
+/// perfect-square discriminant. The zero polynomial gives an empty list, and
+/// so does a higher-degree one whose coefficients have too many divisors to
+/// search: only the root 0 is found then.
+pub fn rational_roots(coeffs: &[Rational]) -> Vec<Rational> {
+    let mut coeffs = coeffs.to_vec();
+    trim(&mut coeffs);
+    let mut roots = Vec::new();
+    let Some(lowest) = coeffs.iter().position(|c| !c.is_zero()) else {
+        return roots;
+    };
+    if lowest > 0 {
+        roots.push(Rational::from(0));
+    }
+    // Divide out x^lowest so the constant term is nonzero
+    let coeffs = &coeffs[lowest..];
+    let Some(scale) = coeffs
+        .iter()
+        .try_fold(1i64, |scale, c| scale.checked_mul(c.denom()))
+    else {
+        return roots;
+    };
+    // Clear denominators, keeping the coefficients lowest degree first
+    let Some(ints) = coeffs
+        .iter()
+        .map(|c| c.numer().checked_mul(scale / c.denom()))
+        .collect::<Option<Vec<i64>>>()
+    else {
+        return roots;
+    };
+    let found = match ints[..] {
+        [_] => Vec::new(),
+        [c, b] => ratio(-i128::from(c), i128::from(b)).into_iter().collect(),
+        [c, b, a] => match quadratic_roots(c, b, a) {
+            Some(found) => found,
+            None => divisor_roots(&ints),
+        },
+        _ => divisor_roots(&ints),
+    };
+    for root in found {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots.sort();
+    roots
+}
+
+/// Rational roots of `ax² + bx + c` from the discriminant, which must be a
+/// perfect square.
+///
+/// `None` if the discriminant overflows `i128`.
+fn quadratic_roots(c: i64, b: i64, a: i64) -> Option<Vec<Rational>> {
+    let (a, b, c) = (i128::from(a), i128::from(b), i128::from(c));
+    let disc = b
+        .checked_mul(b)?
+        .checked_sub(a.checked_mul(c)?.checked_mul(4)?)?;
+    if disc < 0 {
+        return Some(Vec::new());
+    }
+    let root = disc.unsigned_abs().isqrt();
+    if root * root != disc.unsigned_abs() {
+        return Some(Vec::new());
+    }
+    // root² ≤ i128::MAX, so root fits too
+    let root = root as i128;
+    Some(
+        [-b - root, -b + root]
+            .into_iter()
+            .filter_map(|p| ratio(p, 2 * a))
+            .collect(),
+    )
+}
+
+/// Rational roots of `ints` by the rational root theorem, or none if the
+/// search is too large.
+fn divisor_roots(ints: &[i64]) -> Vec<Rational> {
+    let constant = ints[0].unsigned_abs();
+    let lead = ints[ints.len() - 1].unsigned_abs();
+    if constant > MAX_ROOT_SEARCH || lead > MAX_ROOT_SEARCH {
+        return Vec::new();
+    }
+    let (ps, qs) = (numtheory::divisors(constant), numtheory::divisors(lead));
+    if ps.len() * qs.len() > MAX_DIVISOR_PAIRS {
+        return Vec::new();
+    }
+    let mut roots = Vec::new();
+    for &p in &ps {
+        for &q in &qs {
+            let (p, q) = (p as i64, q as i64);
+            for p in [p, -p] {
+                let candidate = Rational::new(p, q);
+                if !roots.contains(&candidate) && vanishes_at(ints, p, q) == Some(true) {
+                    roots.push(candidate);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// `p/q` in lowest terms, or `None` if `q` is zero or it does not fit an
+/// `i64` fraction.
+fn ratio(p: i128, q: i128) -> Option<Rational> {
+    if q == 0 {
+        return None;
+    }
+    let (mut a, mut b) = (p.unsigned_abs(), q.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    let g = a as i128;
+    let (p, q) = (p / g * q.signum(), q.abs() / g);
+    Some(Rational::new(
+        i64::try_from(p).ok()?,
+        i64::try_from(q).ok()?,
+    ))
+}
+
+/// Largest constant or leading coefficient whose divisors
+/// [`rational_roots`] tries; factoring anything larger by trial division
+/// would take too long.
+const MAX_ROOT_SEARCH: u64 = 1_000_000_000_000;
+
+/// Most `(p, q)` divisor pairs [`rational_roots`] checks for a cubic or
+/// higher polynomial.
+const MAX_DIVISOR_PAIRS: usize = 10_000;
+
+/// Whether the integer polynomial `ints` (lowest degree first) vanishes at
+/// `p/q`, found as `q^n · P(p/q)` by Horner's rule in `i128`.
+///
+/// `None` if that overflows.
+fn vanishes_at(ints: &[i64], p: i64, q: i64) -> Option<bool> {
+    let (p, q) = (i128::from(p), i128::from(q));
+    let mut q_power = 1i128;
+    let mut acc = i128::from(ints[ints.len() - 1]);
+    for &c in ints[..ints.len() - 1].iter().rev() {
+        q_power = q_power.checked_mul(q)?;
+        acc = acc
+            .checked_mul(p)?
+            .checked_add(i128::from(c).checked_mul(q_power)?)?;
+    }
+    Some(acc == 0)
+}
+
+fn trim(coeffs: &mut Vec<Rational>) {
+    while coeffs.last().is_some_and(|c| c.is_zero()) {
+        coeffs.pop();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -480,4 +740,71 @@ mod tests {
 
         assert_eq!(algebraically_equal(&lhs, &rhs), Some(true));
     }
+
+    #[test]
+    fn test_poly_gcd() {
+        let r = |coeffs: &[i64]| {
+            coeffs
+                .iter()
+                .map(|&c| Rational::from(c))
+                .collect::<Vec<_>>()
+        };
+
+        // x² - 1 = (x - 1)(x + 1)
+        let (q, rem) = poly_div_rem(&r(&[-1, 0, 1]), &r(&[-1, 1]));
+        assert_eq!(q, r(&[1, 1]));
+        assert!(rem.is_empty());
+
+        // gcd(x² - 1, 2x² - 4x + 2) = x - 1
+        assert_eq!(poly_gcd(&r(&[-1, 0, 1]), &r(&[2, -4, 2])), r(&[-1, 1]));
+        // Coprime polynomials have gcd 1
+        assert_eq!(poly_gcd(&r(&[1, 1]), &r(&[-1, 1])), r(&[1]));
+
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        assert_eq!(
+            poly_to_expr(&r(&[1, 1]), x),
+            Expr::Add(Box::new(Expr::Var(x)), Box::new(Expr::int(1)))
+        );
+    }
+
+    #[test]
+    fn test_rational_roots() {
+        let r = |coeffs: &[i64]| {
+            coeffs
+                .iter()
+                .map(|&c| Rational::from(c))
+                .collect::<Vec<_>>()
+        };
+
+        // x² + 3x + 2 = (x + 1)(x + 2)
+        assert_eq!(rational_roots(&r(&[2, 3, 1])), r(&[-2, -1]));
+        // 2x³ - x² - x = x(2x + 1)(x - 1)
+        assert_eq!(
+            rational_roots(&r(&[0, -1, -1, 2])),
+            vec![Rational::new(-1, 2), Rational::from(0), Rational::from(1)]
+        );
+        // x²/4 - 1 has roots ±2; x² - 2 and x² + 1 have none
+        assert_eq!(
+            rational_roots(&[Rational::from(-1), Rational::from(0), Rational::new(1, 4)]),
+            r(&[-2, 2])
+        );
+        assert!(rational_roots(&r(&[-2, 0, 1])).is_empty());
+        assert!(rational_roots(&r(&[1, 0, 1])).is_empty());
+        assert!(rational_roots(&[]).is_empty());
+
+        // Large coefficients are checked without overflowing
+        assert!(rational_roots(&r(&[9_999_999_967, 1, 1])).is_empty());
+        assert_eq!(
+            rational_roots(&r(&[-9_999_999_967, -9_999_999_966, 1])),
+            r(&[-1, 9_999_999_967])
+        );
+        assert_eq!(rational_roots(&r(&[0, i64::MAX, 1, 1])), r(&[0]));
+
+        // Highly composite coefficients are not searched divisor by divisor
+        let n = 963_761_198_400;
+        assert!(rational_roots(&r(&[n, 1, n])).is_empty());
+        assert_eq!(rational_roots(&r(&[-n, n - 1, 1])), r(&[-n, 1]));
+        assert!(rational_roots(&r(&[n, 1, 0, n])).is_empty());
+    }
 }
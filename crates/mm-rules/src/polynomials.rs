@@ -7,12 +7,13 @@
 //! Advanced polynomial rules for IMO-level problem solving.
 //! Includes Vieta's formulas, symmetric polynomials, partial fractions.
 
+use crate::polynomial::{poly_div_rem, poly_gcd, poly_to_expr, PolynomialNF};
 use crate::{Domain, Feature, Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::{Expr, Rational};
+use mm_core::{Constraint, Expr, ExprKind, Rational};
 
 /// Collects the complete set of polynomial transformation and solving rules.
 ///
-/// This aggregates rules from Vieta’s formulas, symmetric polynomial identities, factoring rules, rational-root criteria, and phase-3 advanced polynomial rules (IDs 500–527, 540–561, 800–818).
+/// This aggregates rules from Vieta’s formulas, symmetric polynomial identities, factoring rules, rational-root criteria, and phase-3 advanced polynomial rules (IDs 500–527, 540–561, 800–819).
 ///
 /// # Returns
 ///
@@ -22,7 +23,7 @@ use mm_core::{Expr, Rational};
 ///
 /// ```
 /// let rules = polynomial_rules();
-/// assert_eq!(rules.len(), 54);
+/// assert_eq!(rules.len(), 55);
 /// ```
 pub fn polynomial_rules() -> Vec<Rule> {
     let mut rules = Vec::new();
@@ -52,15 +53,18 @@ fn vieta_rules() -> Vec<Rule> {
             description: "For ax² + bx + c = 0: r₁ + r₂ = -b/a",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Equation],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Equation { .. }),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vieta: For ax² + bx + c = 0, r₁ + r₂ = -b/a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Product of roots (quadratic)
         Rule {
@@ -70,15 +74,18 @@ fn vieta_rules() -> Vec<Rule> {
             description: "For ax² + bx + c = 0: r₁ · r₂ = c/a",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Mul, ExprKind::Equation],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _) | Expr::Equation { .. }),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vieta: For ax² + bx + c = 0, r₁ · r₂ = c/a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Sum of roots (cubic)
         Rule {
@@ -88,15 +95,18 @@ fn vieta_rules() -> Vec<Rule> {
             description: "For ax³ + bx² + cx + d = 0: r₁ + r₂ + r₃ = -b/a",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Equation],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Equation { .. }),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vieta (cubic): r₁ + r₂ + r₃ = -b/a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Pairwise product sum (cubic)
         Rule {
@@ -106,15 +116,18 @@ fn vieta_rules() -> Vec<Rule> {
             description: "r₁r₂ + r₂r₃ + r₁r₃ = c/a",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vieta (cubic): r₁r₂ + r₂r₃ + r₁r₃ = c/a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Product of roots (cubic)
         Rule {
@@ -124,15 +137,18 @@ fn vieta_rules() -> Vec<Rule> {
             description: "r₁ · r₂ · r₃ = -d/a",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Vieta (cubic): r₁ · r₂ · r₃ = -d/a".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
@@ -152,15 +168,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "e₁ = Σxᵢ (sum of variables)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Elementary symmetric polynomial e₁ = Σxᵢ".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(521),
@@ -169,15 +188,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "e₂ = Σxᵢxⱼ (sum of pairwise products)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Elementary symmetric polynomial e₂ = Σxᵢxⱼ".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // Power sum to elementary
         Rule {
@@ -187,15 +209,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "p₁ = e₁",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Newton's identity: p₁ = e₁".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         Rule {
             id: RuleId(523),
@@ -204,15 +229,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "p₂ = e₁² - 2e₂",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Sub, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Newton's identity: p₂ = e₁² - 2e₂".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         Rule {
             id: RuleId(524),
@@ -221,6 +249,7 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "p₃ = e₁³ - 3e₁e₂ + 3e₃",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Sub, ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _) | Expr::Pow(_, _))
             },
@@ -228,10 +257,12 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Newton's identity: p₃ = e₁³ - 3e₁e₂ + 3e₃".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // x² + y² = (x+y)² - 2xy
         Rule {
@@ -241,6 +272,7 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "x² + y² = (x+y)² - 2xy",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(a, b) = expr {
                     let a_sq = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(2)));
@@ -263,6 +295,7 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
                         return vec![RuleApplication {
                             result: Expr::Sub(Box::new(sum_sq), Box::new(two_prod)),
                             justification: "x² + y² = (x+y)² - 2xy".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -270,6 +303,7 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 2,
+            ..Default::default()
         },
         // x³ + y³ = (x+y)³ - 3xy(x+y)
         Rule {
@@ -279,15 +313,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "x³ + y³ = (x+y)³ - 3xy(x+y)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "x³ + y³ = (x+y)³ - 3xy(x+y)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // x³ + y³ + z³ - 3xyz = (x+y+z)(x²+y²+z²-xy-yz-zx)
         Rule {
@@ -297,15 +334,18 @@ fn symmetric_polynomial_rules() -> Vec<Rule> {
             description: "x³+y³+z³-3xyz = (x+y+z)(x²+y²+z²-xy-yz-zx)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Sub],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "x³+y³+z³-3xyz = (x+y+z)(x²+y²+z²-xy-yz-zx)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
     ]
 }
@@ -335,15 +375,18 @@ fn factoring_rules() -> Vec<Rule> {
             description: "(x-a) | P(x) ⟺ P(a) = 0",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div, ExprKind::Equation],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. } | Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Factor theorem: (x-a) divides P(x) iff P(a) = 0".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Remainder theorem: P(x) = (x-a)Q(x) + P(a)
         Rule {
@@ -353,6 +396,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "P(a) is remainder when dividing P(x) by (x-a)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
@@ -360,10 +404,12 @@ fn factoring_rules() -> Vec<Rule> {
                     justification:
                         "Remainder theorem: P(a) is remainder when dividing P(x) by (x-a)"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Polynomial division identity
         Rule {
@@ -373,6 +419,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "P(x) = D(x)·Q(x) + R(x)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Mul, ExprKind::Div],
             is_applicable: |expr, _ctx| {
                 matches!(expr, Expr::Div(_, _) | Expr::Add(_, _) | Expr::Mul(_, _))
             },
@@ -380,10 +427,12 @@ fn factoring_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Polynomial division: P(x) = D(x)·Q(x) + R(x)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         // Complete the square: x² + bx = (x + b/2)² - b²/4
         Rule {
@@ -393,6 +442,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "x² + bx = (x + b/2)² - b²/4",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 // Match pattern x² + bx
                 if let Expr::Add(a, b) = expr {
@@ -419,6 +469,7 @@ fn factoring_rules() -> Vec<Rule> {
                             result: Expr::Sub(Box::new(squared), Box::new(quarter_b_sq)),
                             justification: "Complete the square: x² + bx = (x + b/2)² - (b/2)²"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -426,6 +477,7 @@ fn factoring_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Difference of nth powers
         Rule {
@@ -435,32 +487,41 @@ fn factoring_rules() -> Vec<Rule> {
             description: "xⁿ - yⁿ = (x-y)(xⁿ⁻¹ + xⁿ⁻²y + ... + yⁿ⁻¹)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
-            is_applicable: |expr, _ctx| {
-                if let Expr::Sub(a, b) = expr {
-                    if let (Expr::Pow(_, exp_a), Expr::Pow(_, exp_b)) = (a.as_ref(), b.as_ref()) {
-                        return exp_a == exp_b;
-                    }
-                }
-                false
-            },
+            roots: &[ExprKind::Sub],
+            is_applicable: |expr, _ctx| diff_of_powers(expr).is_some(),
             apply: |expr, _ctx| {
-                if let Expr::Sub(a, b) = expr {
-                    if let (Expr::Pow(base_a, _), Expr::Pow(base_b, _)) = (a.as_ref(), b.as_ref()) {
-                        // xⁿ - yⁿ = (x-y)(sum of terms)
-                        // For general case, just return the factored form indication
-                        let diff = Expr::Sub(base_a.clone(), base_b.clone());
-                        return vec![RuleApplication {
-                            result: Expr::Mul(Box::new(diff), Box::new(expr.clone())),
-                            justification:
-                                "Difference of powers: xⁿ - yⁿ = (x-y)(xⁿ⁻¹ + xⁿ⁻²y + ... + yⁿ⁻¹)"
-                                    .to_string(),
-                        }];
-                    }
+                if let Some((x, y, n)) = diff_of_powers(expr) {
+                    // xⁿ⁻¹ + xⁿ⁻²y + ... + yⁿ⁻¹
+                    let power = |base: &Expr, k: u32| match k {
+                        0 => None,
+                        1 => Some(base.clone()),
+                        _ => Some(Expr::Pow(
+                            Box::new(base.clone()),
+                            Box::new(Expr::int(k as i64)),
+                        )),
+                    };
+                    let quotient = (0..n)
+                        .map(|k| match (power(x, n - 1 - k), power(y, k)) {
+                            (Some(a), Some(b)) => Expr::Mul(Box::new(a), Box::new(b)),
+                            (Some(a), None) | (None, Some(a)) => a,
+                            (None, None) => Expr::int(1),
+                        })
+                        .reduce(|acc, term| Expr::Add(Box::new(acc), Box::new(term)))
+                        .unwrap();
+                    let diff = Expr::Sub(Box::new(x.clone()), Box::new(y.clone()));
+                    return vec![RuleApplication {
+                        result: Expr::Mul(Box::new(diff), Box::new(quotient)),
+                        justification:
+                            "Difference of powers: xⁿ - yⁿ = (x-y)(xⁿ⁻¹ + xⁿ⁻²y + ... + yⁿ⁻¹)"
+                                .to_string(),
+                        condition: None,
+                    }];
                 }
                 vec![]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         // Difference of cubes: a³ - b³ = (a-b)(a² + ab + b²)
         Rule {
@@ -470,6 +531,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "a³ - b³ = (a-b)(a² + ab + b²)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Sub],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sub(a, b) = expr {
                     let a_cube = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(3)));
@@ -494,6 +556,7 @@ fn factoring_rules() -> Vec<Rule> {
                             result: Expr::Mul(Box::new(diff), Box::new(sum)),
                             justification: "Difference of cubes: a³ - b³ = (a-b)(a² + ab + b²)"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -501,6 +564,7 @@ fn factoring_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Sum of cubes: a³ + b³ = (a+b)(a² - ab + b²)
         Rule {
@@ -510,6 +574,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "a³ + b³ = (a+b)(a² - ab + b²)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| {
                 if let Expr::Add(a, b) = expr {
                     let a_cube = matches!(a.as_ref(), Expr::Pow(_, exp) if matches!(exp.as_ref(), Expr::Const(c) if *c == Rational::from_integer(3)));
@@ -534,6 +599,7 @@ fn factoring_rules() -> Vec<Rule> {
                             result: Expr::Mul(Box::new(sum), Box::new(diff)),
                             justification: "Sum of cubes: a³ + b³ = (a+b)(a² - ab + b²)"
                                 .to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -541,6 +607,7 @@ fn factoring_rules() -> Vec<Rule> {
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Sophie Germain identity: a⁴ + 4b⁴ = (a² + 2b² + 2ab)(a² + 2b² - 2ab)
         Rule {
@@ -550,16 +617,19 @@ fn factoring_rules() -> Vec<Rule> {
             description: "a⁴ + 4b⁴ = (a² + 2b² + 2ab)(a² + 2b² - 2ab)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Sophie Germain: a⁴ + 4b⁴ = (a² + 2b² + 2ab)(a² + 2b² - 2ab)"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 4,
+            ..Default::default()
         },
         // Factoring by grouping
         Rule {
@@ -569,16 +639,19 @@ fn factoring_rules() -> Vec<Rule> {
             description: "ax + ay + bx + by = (a+b)(x+y)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Factoring by grouping: ax + ay + bx + by = (a+b)(x+y)"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: true,
             cost: 3,
+            ..Default::default()
         },
         // Sum of odd powers: x^(2n+1) + y^(2n+1) divisible by (x+y)
         Rule {
@@ -588,15 +661,18 @@ fn factoring_rules() -> Vec<Rule> {
             description: "x^(2n+1) + y^(2n+1) = (x+y)·Q(x,y)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Sum of odd powers divisible by (x+y)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Difference of even powers: x^(2n) - y^(2n) = (x-y)(x+y)·Q(x,y)
         Rule {
@@ -606,16 +682,19 @@ fn factoring_rules() -> Vec<Rule> {
             description: "x^(2n) - y^(2n) = (x-y)(x+y)·Q(x,y)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Sub, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Difference of even powers: x^(2n) - y^(2n) = (x²-y²)·Q(x,y)"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Cyclotomic factorization
         Rule {
@@ -625,6 +704,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "x^n - 1 = Π Φ_d(x) for d|n",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Sub],
             is_applicable: |expr, _ctx| {
                 if let Expr::Sub(a, b) = expr {
                     if matches!(a.as_ref(), Expr::Pow(_, _))
@@ -639,10 +719,12 @@ fn factoring_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Cyclotomic factorization: x^n - 1 = Π Φ_d(x)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Binomial expansion factorization
         Rule {
@@ -652,6 +734,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "(x+y)^n expansion via binomial theorem",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Pow],
             is_applicable: |expr, _ctx| {
                 if let Expr::Pow(base, _) = expr {
                     return matches!(base.as_ref(), Expr::Add(_, _) | Expr::Sub(_, _));
@@ -662,10 +745,12 @@ fn factoring_rules() -> Vec<Rule> {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Binomial expansion: (x+y)^n = Σ C(n,k)x^k y^(n-k)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Quadratic in disguise: (x²)² + bx² + c
         Rule {
@@ -675,15 +760,18 @@ fn factoring_rules() -> Vec<Rule> {
             description: "Biquadratic: x⁴ + bx² + c via u = x²",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Pow],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Pow(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Quadratic substitution: let u = x² for biquadratic".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // Symmetric factorization
         Rule {
@@ -693,6 +781,7 @@ fn factoring_rules() -> Vec<Rule> {
             description: "Symmetric polynomial factorization",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
@@ -700,10 +789,12 @@ fn factoring_rules() -> Vec<Rule> {
                     justification:
                         "Symmetric polynomial can be factored via elementary symmetric functions"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Partial fraction decomposition
         Rule {
@@ -713,16 +804,19 @@ fn factoring_rules() -> Vec<Rule> {
             description: "P(x)/Q(x) = Σ A_i/(x-r_i)^k",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Partial fraction decomposition: P(x)/Q(x) = Σ A_i/(x-r_i)^k"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Horner's method for evaluation
         Rule {
@@ -732,15 +826,18 @@ fn factoring_rules() -> Vec<Rule> {
             description: "P(x) = (...((a_n·x + a_{n-1})x + ...)x + a_0",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Add, ExprKind::Mul],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Horner's method: efficient polynomial evaluation".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // Synthetic division
         Rule {
@@ -750,16 +847,19 @@ fn factoring_rules() -> Vec<Rule> {
             description: "Synthetic division by (x-a)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Synthetic division: efficient division by linear factor"
                         .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
         // Polynomial long division
         Rule {
@@ -769,15 +869,18 @@ fn factoring_rules() -> Vec<Rule> {
             description: "Long division algorithm for polynomials",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Polynomial long division: P(x) = D(x)·Q(x) + R(x)".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
         // Ruffini's rule (special case of synthetic division)
         Rule {
@@ -787,19 +890,43 @@ fn factoring_rules() -> Vec<Rule> {
             description: "Ruffini's rule for polynomial division",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Ruffini's rule: synthetic division variant".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 2,
+            ..Default::default()
         },
     ]
 }
 
+/// Highest power `diff_nth_power` expands the quotient for.
+const MAX_FACTORED_POWER: i64 = 12;
+
+/// `xⁿ - yⁿ` with a shared integer exponent `2 ≤ n ≤ MAX_FACTORED_POWER`.
+fn diff_of_powers(expr: &Expr) -> Option<(&Expr, &Expr, u32)> {
+    let Expr::Sub(a, b) = expr else {
+        return None;
+    };
+    let (Expr::Pow(x, exp_a), Expr::Pow(y, exp_b)) = (a.as_ref(), b.as_ref()) else {
+        return None;
+    };
+    match (exp_a.as_ref(), exp_b.as_ref()) {
+        (Expr::Const(n), Expr::Const(m))
+            if n == m && n.is_integer() && (2..=MAX_FACTORED_POWER).contains(&n.numer()) =>
+        {
+            Some((x, y, n.numer() as u32))
+        }
+        _ => None,
+    }
+}
+
 // ============================================================================
 // Rational Root Theorem Rules (ID 560+)
 // ============================================================================
@@ -815,6 +942,7 @@ fn rational_root_rules() -> Vec<Rule> {
                 "Rational roots of aₙxⁿ + ... + a₀ have form ±(factor of a₀)/(factor of aₙ)",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div, ExprKind::Equation],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Equation { .. }),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
@@ -822,10 +950,12 @@ fn rational_root_rules() -> Vec<Rule> {
                     justification:
                         "Rational root theorem: roots have form ±(factor of a₀)/(factor of aₙ)"
                             .to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 4,
+            ..Default::default()
         },
         // Integer root criterion
         Rule {
@@ -835,15 +965,18 @@ fn rational_root_rules() -> Vec<Rule> {
             description: "Integer roots divide constant term",
             domains: &[Domain::Algebra],
             requires: &[Feature::Polynomial],
+            roots: &[ExprKind::Div, ExprKind::Mod],
             is_applicable: |expr, _ctx| matches!(expr, Expr::Div(_, _) | Expr::Mod(_, _)),
             apply: |expr, _ctx| {
                 vec![RuleApplication {
                     result: expr.clone(),
                     justification: "Integer roots divide constant term".to_string(),
+                    condition: None,
                 }]
             },
             reversible: false,
             cost: 3,
+            ..Default::default()
         },
     ]
 }
@@ -883,6 +1016,8 @@ pub fn advanced_polynomial_rules() -> Vec<Rule> {
         hermite_recurrence(),
         legendre_recurrence(),
         laguerre_recurrence(),
+        // Cancellation
+        polynomial_gcd_cancel(),
     ]
 }
 
@@ -895,15 +1030,18 @@ fn quadratic_formula() -> Rule {
         description: "x = (-b ± √(b²-4ac)) / 2a",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sqrt, ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. } | Expr::Sqrt(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Quadratic formula: x = (-b ± √(b²-4ac)) / 2a".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -916,6 +1054,7 @@ fn discriminant_sign() -> Rule {
         description: "Δ > 0 ⟹ 2 real roots; Δ = 0 ⟹ 1 repeated; Δ < 0 ⟹ complex",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Equation, ExprKind::Gt, ExprKind::Lt],
         is_applicable: |expr, _ctx| {
             matches!(
                 expr,
@@ -926,10 +1065,12 @@ fn discriminant_sign() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Discriminant sign determines root nature".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -942,15 +1083,18 @@ fn discriminant_perfect_square() -> Rule {
         description: "Δ = k² ⟹ rational roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sqrt, ExprKind::Pow],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Sqrt(_)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Perfect square discriminant implies rational roots".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -963,15 +1107,18 @@ fn cardano_formula() -> Rule {
         description: "Cardano's formula for x³ + px + q = 0",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Pow, ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Equation { .. }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Cardano's formula for depressed cubic".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -984,6 +1131,7 @@ fn cubic_discriminant() -> Rule {
         description: "Cubic discriminant Δ = -4p³ - 27q²",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add, ExprKind::Sub, ExprKind::Pow],
         is_applicable: |expr, _ctx| {
             matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _) | Expr::Pow(_, _))
         },
@@ -991,10 +1139,12 @@ fn cubic_discriminant() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Cubic discriminant: Δ = -4p³ - 27q²".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1007,15 +1157,18 @@ fn quartic_resolvent() -> Rule {
         description: "Resolvent cubic for quartic equations",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Pow, ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Pow(_, _) | Expr::Equation { .. }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Quartic resolvent cubic".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 5,
+        ..Default::default()
     }
 }
 
@@ -1028,6 +1181,7 @@ fn descartes_rule() -> Rule {
         description: "Number of positive roots ≤ sign changes",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add, ExprKind::Sub, ExprKind::Lte],
         is_applicable: |expr, _ctx| {
             matches!(expr, Expr::Add(_, _) | Expr::Sub(_, _) | Expr::Lte(_, _))
         },
@@ -1035,10 +1189,12 @@ fn descartes_rule() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Descartes' rule: positive roots ≤ sign changes".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1051,15 +1207,18 @@ fn sturm_sequence() -> Rule {
         description: "Sturm's theorem for counting real roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Derivative],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Derivative { expr: _, var: _ }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Sturm's theorem: count real roots via sequence".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1072,15 +1231,18 @@ fn resultant_definition() -> Rule {
         description: "Res(f,g) = 0 ⟺ f and g share a root",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Mul, ExprKind::Equation],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Equation { .. } | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Resultant: Res(f,g) = 0 iff f and g share a root".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1093,16 +1255,19 @@ fn bezout_theorem() -> Rule {
         description: "f·u + g·v = gcd(f,g) for some polynomials u,v",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add, ExprKind::GCD],
         is_applicable: |expr, _ctx| matches!(expr, Expr::GCD(_, _) | Expr::Add(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Bezout's identity for polynomials: f·u + g·v = gcd(f,g)"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1115,15 +1280,18 @@ fn cauchy_bound() -> Rule {
         description: "Cauchy bound on polynomial roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Abs, ExprKind::Lte],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Abs(_) | Expr::Lte(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Cauchy root bound: |roots| ≤ 1 + max|aᵢ/aₙ|".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1136,15 +1304,18 @@ fn fujiwara_bound() -> Rule {
         description: "Fujiwara bound on polynomial roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Abs, ExprKind::Lte],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Abs(_) | Expr::Lte(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Fujiwara root bound".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1157,6 +1328,7 @@ fn gauss_lucas_theorem() -> Rule {
         description: "Critical points in convex hull of roots",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Derivative],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Derivative { expr: _, var: _ }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
@@ -1164,10 +1336,12 @@ fn gauss_lucas_theorem() -> Rule {
                 justification:
                     "Gauss-Lucas: derivative roots lie in convex hull of polynomial roots"
                         .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1180,15 +1354,18 @@ fn lagrange_interpolation() -> Rule {
         description: "P(x) = Σ yᵢ Π(x-xⱼ)/(xᵢ-xⱼ)",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sum, ExprKind::Product],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Product { .. } | Expr::Sum { .. }),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Lagrange interpolation: P(x) = Σ yᵢ Π(x-xⱼ)/(xᵢ-xⱼ)".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1201,15 +1378,18 @@ fn newton_interpolation() -> Rule {
         description: "Newton form of interpolating polynomial",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Add, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Add(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Newton interpolation: divided differences form".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -1222,15 +1402,18 @@ fn chebyshev_recurrence() -> Rule {
         description: "Chebyshev T_{n+1} = 2xT_n - T_{n-1}",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sub, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev recurrence: T_{n+1} = 2xT_n - T_{n-1}".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1243,15 +1426,18 @@ fn hermite_recurrence() -> Rule {
         description: "Hermite H_{n+1} = 2xH_n - 2nH_{n-1}",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sub, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Hermite recurrence: H_{n+1} = 2xH_n - 2nH_{n-1}".to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1264,16 +1450,19 @@ fn legendre_recurrence() -> Rule {
         description: "Legendre (n+1)P_{n+1} = (2n+1)xP_n - nP_{n-1}",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sub, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Legendre recurrence: (n+1)P_{n+1} = (2n+1)xP_n - nP_{n-1}"
                     .to_string(),
+                condition: None,
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1286,14 +1475,129 @@ fn laguerre_recurrence() -> Rule {
         description: "Laguerre L_{n+1} = (2n+1-x)L_n - n²L_{n-1}",
         domains: &[Domain::Algebra],
         requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Sub, ExprKind::Mul],
         is_applicable: |expr, _ctx| matches!(expr, Expr::Sub(_, _) | Expr::Mul(_, _)),
         apply: |expr, _ctx| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Laguerre recurrence: L_{n+1} = (2n+1-x)L_n - n²L_{n-1}".to_string(),
+                condition: None,
+            }]
+        },
+        reversible: false,
+        cost: 2,
+        ..Default::default()
+    }
+}
+
+// p(x)/q(x) = (p/g)/(q/g) with g = gcd(p, q)
+fn polynomial_gcd_cancel() -> Rule {
+    Rule {
+        id: RuleId(819),
+        name: "polynomial_gcd_cancel",
+        category: RuleCategory::Simplification,
+        description: "p(x)/q(x) = (p/g)/(q/g) where g = gcd(p, q)",
+        domains: &[Domain::Algebra],
+        requires: &[Feature::Polynomial],
+        roots: &[ExprKind::Div],
+        is_applicable: |expr, _ctx| gcd_cancel(expr).is_some(),
+        apply: |expr, ctx| {
+            let Some((result, condition)) = gcd_cancel(expr) else {
+                return vec![];
+            };
+            vec![RuleApplication {
+                result,
+                justification: "Cancel the common polynomial factor gcd(p, q)".to_string(),
+                condition: ctx.side_condition(condition),
             }]
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
+    }
+}
+
+/// Reduce a ratio of univariate polynomials by their GCD, returning the
+/// reduced expression and the condition that the cancelled factor is nonzero.
+fn gcd_cancel(expr: &Expr) -> Option<(Expr, Constraint)> {
+    let Expr::Div(num, den) = expr else {
+        return None;
+    };
+    let [var] = expr.free_vars()[..] else {
+        return None;
+    };
+    let p = PolynomialNF::from_expr(num)?.coefficients(var)?;
+    let q = PolynomialNF::from_expr(den)?.coefficients(var)?;
+    if q.is_empty() {
+        return None;
+    }
+    let g = poly_gcd(&p, &q);
+    if g.len() < 2 {
+        return None;
+    }
+
+    let (p, _) = poly_div_rem(&p, &g);
+    let (q, _) = poly_div_rem(&q, &g);
+    let result = match q[..] {
+        [c] => poly_to_expr(&p.iter().map(|a| *a / c).collect::<Vec<_>>(), var),
+        _ => Expr::Div(
+            Box::new(poly_to_expr(&p, var)),
+            Box::new(poly_to_expr(&q, var)),
+        ),
+    };
+
+    // A linear factor x - r reads better as x ≠ r
+    let condition = match g[..] {
+        [r, _] => Constraint {
+            expr: Expr::Not(Box::new(Expr::Equation {
+                lhs: Box::new(Expr::Var(var)),
+                rhs: Box::new(Expr::Const(-r)),
+            })),
+        },
+        _ => Constraint::nonzero(poly_to_expr(&g, var)),
+    };
+    Some((result, condition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleContext;
+    use mm_core::SymbolTable;
+
+    #[test]
+    fn test_polynomial_gcd_cancel() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let rule = polynomial_gcd_cancel();
+        let ctx = RuleContext::default();
+        let var = || Box::new(Expr::Var(x));
+
+        // (x² - 1)/(x - 1) → x + 1, for x ≠ 1
+        let expr = Expr::Div(
+            Box::new(Expr::Sub(
+                Box::new(Expr::Pow(var(), Box::new(Expr::int(2)))),
+                Box::new(Expr::int(1)),
+            )),
+            Box::new(Expr::Sub(var(), Box::new(Expr::int(1)))),
+        );
+        assert!(rule.can_apply(&expr, &ctx));
+        let results = rule.apply(&expr, &ctx);
+        assert_eq!(results[0].result, Expr::Add(var(), Box::new(Expr::int(1))));
+        let x_is_one = Expr::Equation {
+            lhs: var(),
+            rhs: Box::new(Expr::int(1)),
+        };
+        assert_eq!(
+            results[0].condition.as_ref().map(|c| &c.expr),
+            Some(&Expr::Not(Box::new(x_is_one)))
+        );
+
+        // Coprime numerator and denominator are left alone
+        let expr = Expr::Div(
+            Box::new(Expr::Add(var(), Box::new(Expr::int(1)))),
+            Box::new(Expr::Sub(var(), Box::new(Expr::int(1)))),
+        );
+        assert!(!rule.can_apply(&expr, &ctx));
     }
 }
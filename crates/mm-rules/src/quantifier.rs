@@ -10,8 +10,16 @@
 //! - Universal Instantiation: ∀x.P(x) → P(a)
 //! - Existential Introduction: P(a) → ∃x.P(x)  
 //! - Quantifier Negation: ¬∀x.P(x) ↔ ∃x.¬P(x)
+//! - Bounded evaluation: ∀x ∈ {1, 2, 3}. P(x) decided by checking each value
 
-use mm_core::{Expr, Symbol, SymbolTable};
+use mm_core::eval::Env;
+use mm_core::{Constraint, Expr, Symbol, SymbolTable};
+
+/// Largest domain that bounded evaluation will enumerate.
+const MAX_DOMAIN_SIZE: i64 = 1000;
+
+/// Tolerance for deciding an equation holds numerically.
+const EQUATION_TOLERANCE: f64 = 1e-9;
 
 /// Result of instantiating a universally quantified statement.
 #[derive(Debug, Clone)]
@@ -180,6 +188,61 @@ impl QuantifierEngine {
         suggestions
     }
 
+    /// Decide a closed statement whose quantifiers range over bounded domains.
+    ///
+    /// Each quantifier's domain must pin its variable to finitely many values:
+    /// either integer bounds on the variable (`1 ≤ x ∧ x ≤ 3`) or a finite set
+    /// written as equations (`x = 1 ∨ x = 4`). `∀` checks the body at every
+    /// value and `∃` stops at the first witness.
+    ///
+    /// Returns `None` if a domain is missing or unbounded, or if the body
+    /// cannot be evaluated (e.g. it has free variables).
+    ///
+    /// # Example
+    /// ```text
+    /// ∀x ∈ [1, 3]. x > 0   → Some(true)
+    /// ∃x ∈ [1, 3]. x = 5   → Some(false)
+    /// ```
+    pub fn evaluate_bounded(&self, expr: &Expr) -> Option<bool> {
+        match expr {
+            Expr::ForAll { var, domain, body } => {
+                for value in domain_values(domain.as_deref()?, *var)? {
+                    if !self.evaluate_bounded(&self.substitute(body, *var, &value))? {
+                        return Some(false);
+                    }
+                }
+                Some(true)
+            }
+            Expr::Exists { .. } => Some(self.find_witness(expr)?.is_some()),
+            Expr::And(a, b) => Some(self.evaluate_bounded(a)? && self.evaluate_bounded(b)?),
+            Expr::Or(a, b) => Some(self.evaluate_bounded(a)? || self.evaluate_bounded(b)?),
+            Expr::Implies(a, b) => Some(!self.evaluate_bounded(a)? || self.evaluate_bounded(b)?),
+            Expr::Not(e) => Some(!self.evaluate_bounded(e)?),
+            Expr::Equation { lhs, rhs } => {
+                let env = Env::new();
+                Some((lhs.evaluate(&env)? - rhs.evaluate(&env)?).abs() < EQUATION_TOLERANCE)
+            }
+            _ => Some(expr.evaluate(&Env::new())? != 0.0),
+        }
+    }
+
+    /// Search a bounded domain for a witness of `∃x. P(x)`.
+    ///
+    /// Returns `Some(Some(value))` for the first value satisfying the body,
+    /// `Some(None)` if the domain has no witness, and `None` if the statement
+    /// is not a bounded existential or cannot be evaluated.
+    pub fn find_witness(&self, exists: &Expr) -> Option<Option<Expr>> {
+        let Expr::Exists { var, domain, body } = exists else {
+            return None;
+        };
+        for value in domain_values(domain.as_deref()?, *var)? {
+            if self.evaluate_bounded(&self.substitute(body, *var, &value))? {
+                return Some(Some(value));
+            }
+        }
+        Some(None)
+    }
+
     /// Recursively substitutes all free occurrences of `var` with `value` in `body`.
     ///
     /// The substitution respects binding: occurrences of `var` that are shadowed by inner quantifiers,
@@ -208,7 +271,9 @@ impl QuantifierEngine {
     fn substitute(&self, body: &Expr, var: Symbol, value: &Expr) -> Expr {
         match body {
             Expr::Var(v) if *v == var => value.clone(),
-            Expr::Var(_) | Expr::Const(_) | Expr::Pi | Expr::E => body.clone(),
+            Expr::Var(_) | Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => {
+                body.clone()
+            }
 
             Expr::Neg(e) => Expr::Neg(Box::new(self.substitute(e, var, value))),
             Expr::Sqrt(e) => Expr::Sqrt(Box::new(self.substitute(e, var, value))),
@@ -218,6 +283,12 @@ impl QuantifierEngine {
             Expr::Arcsin(e) => Expr::Arcsin(Box::new(self.substitute(e, var, value))),
             Expr::Arccos(e) => Expr::Arccos(Box::new(self.substitute(e, var, value))),
             Expr::Arctan(e) => Expr::Arctan(Box::new(self.substitute(e, var, value))),
+            Expr::Sinh(e) => Expr::Sinh(Box::new(self.substitute(e, var, value))),
+            Expr::Cosh(e) => Expr::Cosh(Box::new(self.substitute(e, var, value))),
+            Expr::Tanh(e) => Expr::Tanh(Box::new(self.substitute(e, var, value))),
+            Expr::Arcsinh(e) => Expr::Arcsinh(Box::new(self.substitute(e, var, value))),
+            Expr::Arccosh(e) => Expr::Arccosh(Box::new(self.substitute(e, var, value))),
+            Expr::Arctanh(e) => Expr::Arctanh(Box::new(self.substitute(e, var, value))),
             Expr::Ln(e) => Expr::Ln(Box::new(self.substitute(e, var, value))),
             Expr::Exp(e) => Expr::Exp(Box::new(self.substitute(e, var, value))),
             Expr::Abs(e) => Expr::Abs(Box::new(self.substitute(e, var, value))),
@@ -225,6 +296,19 @@ impl QuantifierEngine {
             Expr::Ceiling(e) => Expr::Ceiling(Box::new(self.substitute(e, var, value))),
             Expr::Factorial(e) => Expr::Factorial(Box::new(self.substitute(e, var, value))),
             Expr::Not(e) => Expr::Not(Box::new(self.substitute(e, var, value))),
+            Expr::Piecewise(branches) => Expr::Piecewise(
+                branches
+                    .iter()
+                    .map(|(c, e)| {
+                        (
+                            Constraint {
+                                expr: self.substitute(&c.expr, var, value),
+                            },
+                            self.substitute(e, var, value),
+                        )
+                    })
+                    .collect(),
+            ),
 
             Expr::Add(a, b) => Expr::Add(
                 Box::new(self.substitute(a, var, value)),
@@ -323,6 +407,22 @@ impl QuantifierEngine {
                 expr: Box::new(self.substitute(expr, var, value)),
                 var: *v,
             },
+            // The limit variable shadows `var` in the body, not in the point
+            Expr::Limit {
+                expr,
+                var: v,
+                approaching,
+                direction,
+            } => Expr::Limit {
+                expr: if *v == var {
+                    expr.clone()
+                } else {
+                    Box::new(self.substitute(expr, var, value))
+                },
+                var: *v,
+                approaching: Box::new(self.substitute(approaching, var, value)),
+                direction: *direction,
+            },
 
             // For nested quantifiers, don't substitute if variable is shadowed
             Expr::ForAll {
@@ -385,6 +485,88 @@ impl QuantifierEngine {
     }
 }
 
+/// Enumerate the values a domain constraint allows for `var`.
+///
+/// Supports finite sets (`x = a ∨ x = b ∨ ...`) and integer ranges given by
+/// a conjunction of bounds on `var`. Returns `None` for anything else, or if
+/// the range is unbounded or larger than `MAX_DOMAIN_SIZE`.
+fn domain_values(domain: &Expr, var: Symbol) -> Option<Vec<Expr>> {
+    if let Some(values) = finite_set(domain, var) {
+        return Some(values);
+    }
+
+    let (mut lo, mut hi) = (None, None);
+    collect_bounds(domain, var, &mut lo, &mut hi)?;
+    let (lo, hi) = (lo?, hi?);
+    if hi.checked_sub(lo)? >= MAX_DOMAIN_SIZE {
+        return None;
+    }
+    Some((lo..=hi).map(Expr::int).collect())
+}
+
+fn finite_set(domain: &Expr, var: Symbol) -> Option<Vec<Expr>> {
+    match domain {
+        Expr::Or(a, b) => {
+            let mut values = finite_set(a, var)?;
+            values.extend(finite_set(b, var)?);
+            Some(values)
+        }
+        Expr::Equation { lhs, rhs } => match (lhs.as_ref(), rhs.as_ref()) {
+            (Expr::Var(v), value) | (value, Expr::Var(v)) if *v == var && value.is_const() => {
+                Some(vec![value.clone()])
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Tighten integer bounds `lo ≤ var ≤ hi` from a conjunction of comparisons.
+fn collect_bounds(
+    domain: &Expr,
+    var: Symbol,
+    lo: &mut Option<i64>,
+    hi: &mut Option<i64>,
+) -> Option<()> {
+    let bound = |e: &Expr| e.evaluate(&Env::new()).filter(|v| v.is_finite());
+    let is_var = |e: &Expr| matches!(e, Expr::Var(v) if *v == var);
+    let raise = |lo: &mut Option<i64>, v: i64| *lo = Some(lo.map_or(v, |l| l.max(v)));
+    let lower = |hi: &mut Option<i64>, v: i64| *hi = Some(hi.map_or(v, |h| h.min(v)));
+
+    match domain {
+        Expr::And(a, b) => {
+            collect_bounds(a, var, lo, hi)?;
+            collect_bounds(b, var, lo, hi)
+        }
+        // var ≥ c  or  c ≤ var
+        Expr::Gte(a, b) | Expr::Lte(b, a) if is_var(a) => {
+            raise(lo, integer(bound(b)?.ceil())?);
+            Some(())
+        }
+        // var > c  or  c < var
+        Expr::Gt(a, b) | Expr::Lt(b, a) if is_var(a) => {
+            raise(lo, integer(bound(b)?.floor())?.checked_add(1)?);
+            Some(())
+        }
+        // var ≤ c  or  c ≥ var
+        Expr::Lte(a, b) | Expr::Gte(b, a) if is_var(a) => {
+            lower(hi, integer(bound(b)?.floor())?);
+            Some(())
+        }
+        // var < c  or  c > var
+        Expr::Lt(a, b) | Expr::Gt(b, a) if is_var(a) => {
+            lower(hi, integer(bound(b)?.ceil())?.checked_sub(1)?);
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+/// A whole `f64` as an `i64`, or `None` if it is out of range.
+fn integer(v: f64) -> Option<i64> {
+    (v >= i64::MIN as f64 && v < i64::MAX as f64).then_some(v as i64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -496,4 +678,112 @@ mod tests {
             _ => panic!("Expected Exists"),
         }
     }
-}
\ No newline at end of file
+
+    /// Integer range 1 ≤ x ≤ 3.
+    fn one_to_three(x: Symbol) -> Box<Expr> {
+        Box::new(Expr::And(
+            Box::new(Expr::Gte(Box::new(Expr::Var(x)), Box::new(Expr::int(1)))),
+            Box::new(Expr::Lte(Box::new(Expr::Var(x)), Box::new(Expr::int(3)))),
+        ))
+    }
+
+    #[test]
+    fn test_bounded_forall() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let engine = QuantifierEngine::new();
+
+        // ∀x ∈ [1, 3]. x > 0
+        let forall = Expr::ForAll {
+            var: x,
+            domain: Some(one_to_three(x)),
+            body: Box::new(Expr::Gt(Box::new(Expr::Var(x)), Box::new(Expr::int(0)))),
+        };
+        assert_eq!(engine.evaluate_bounded(&forall), Some(true));
+
+        // ∀x ∈ [1, 3]. x > 1 fails at x = 1
+        let forall = Expr::ForAll {
+            var: x,
+            domain: Some(one_to_three(x)),
+            body: Box::new(Expr::Gt(Box::new(Expr::Var(x)), Box::new(Expr::int(1)))),
+        };
+        assert_eq!(engine.evaluate_bounded(&forall), Some(false));
+    }
+
+    #[test]
+    fn test_bounded_exists() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let engine = QuantifierEngine::new();
+
+        // ∃x ∈ [1, 3]. x = 5
+        let exists = |target: i64| Expr::Exists {
+            var: x,
+            domain: Some(one_to_three(x)),
+            body: Box::new(Expr::Equation {
+                lhs: Box::new(Expr::Var(x)),
+                rhs: Box::new(Expr::int(target)),
+            }),
+        };
+        assert_eq!(engine.evaluate_bounded(&exists(5)), Some(false));
+        assert_eq!(engine.evaluate_bounded(&exists(2)), Some(true));
+        assert_eq!(engine.find_witness(&exists(2)), Some(Some(Expr::int(2))));
+    }
+
+    #[test]
+    fn test_bounded_finite_set_and_unbounded() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let engine = QuantifierEngine::new();
+        let eq = |v: i64| {
+            Box::new(Expr::Equation {
+                lhs: Box::new(Expr::Var(x)),
+                rhs: Box::new(Expr::int(v)),
+            })
+        };
+        let even = Box::new(Expr::Equation {
+            lhs: Box::new(Expr::Mod(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+            rhs: Box::new(Expr::int(0)),
+        });
+
+        // ∀x ∈ {2, 4}. x mod 2 = 0
+        let forall = Expr::ForAll {
+            var: x,
+            domain: Some(Box::new(Expr::Or(eq(2), eq(4)))),
+            body: even.clone(),
+        };
+        assert_eq!(engine.evaluate_bounded(&forall), Some(true));
+
+        // x ≥ 1 alone has no upper bound
+        let unbounded = Expr::ForAll {
+            var: x,
+            domain: Some(Box::new(Expr::Gte(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(1)),
+            ))),
+            body: even,
+        };
+        assert_eq!(engine.evaluate_bounded(&unbounded), None);
+
+        // Bounds past the range of i64 give up instead of overflowing
+        let huge = Box::new(Expr::And(
+            Box::new(Expr::Gt(Box::new(Expr::Var(x)), Box::new(Expr::int(-1)))),
+            Box::new(Expr::Lt(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(i64::MIN)),
+            )),
+        ));
+        assert_eq!(domain_values(&huge, x), None);
+        let wide = Box::new(Expr::And(
+            Box::new(Expr::Gte(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(i64::MIN)),
+            )),
+            Box::new(Expr::Lte(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(i64::MAX)),
+            )),
+        ));
+        assert_eq!(domain_values(&wide, x), None);
+    }
+}
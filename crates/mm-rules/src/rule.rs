@@ -6,10 +6,12 @@
 
 //! Core rule definitions and structures.
 
-use mm_core::Expr;
+use crate::patterns::PatternRule;
+use mm_core::{AngleMode, Constraint, Expr, ExprKind, MathError, SymbolTable};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 /// Unique identifier for a rule.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -125,12 +127,39 @@ pub enum Feature {
 ///
 /// Contains information that rules might need, such as the variable
 /// being solved for in an equation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct RuleContext {
     /// The variable we're trying to solve for (if any).
     pub target_var: Option<mm_core::Symbol>,
     /// Additional metadata.
     pub metadata: HashMap<String, String>,
+    /// Unit for constant trig arguments such as `sin(30)`.
+    pub angle_mode: AngleMode,
+    /// Largest power `n` that `(a + b)^n` is expanded for.
+    pub max_expand_power: u32,
+    /// Treat variables as generic: real, nonzero, and positive where a rule
+    /// needs it. Rules then record no side conditions.
+    pub assume_generic: bool,
+}
+
+impl Default for RuleContext {
+    fn default() -> Self {
+        Self {
+            target_var: None,
+            metadata: HashMap::new(),
+            angle_mode: AngleMode::default(),
+            max_expand_power: 10,
+            assume_generic: false,
+        }
+    }
+}
+
+impl RuleContext {
+    /// `condition` as a rule's side condition, or `None` when variables are
+    /// assumed generic.
+    pub fn side_condition(&self, condition: Constraint) -> Option<Constraint> {
+        (!self.assume_generic).then_some(condition)
+    }
 }
 
 /// A single rule application result.
@@ -140,9 +169,13 @@ pub struct RuleApplication {
     pub result: Expr,
     /// Human-readable justification for this step.
     pub justification: String,
+    /// Side condition the rewrite relies on, e.g. `x ≠ 0` for a
+    /// cancellation. `None` for rewrites valid everywhere.
+    pub condition: Option<Constraint>,
 }
 
 /// A mathematical transformation rule.
+#[derive(Clone)]
 pub struct Rule {
     /// Unique identifier.
     pub id: RuleId,
@@ -158,29 +191,145 @@ pub struct Rule {
     /// AST features required for this rule to be considered.
     /// Empty slice means "no specific features required" (backward compatibility).
     pub requires: &'static [Feature],
+    /// Root expression kinds this rule can rewrite.
+    /// Empty slice means "undeclared": the rule set tries it at every root.
+    pub roots: &'static [ExprKind],
     /// Check if this rule can be applied to the expression.
     pub is_applicable: fn(&Expr, &RuleContext) -> bool,
     /// Apply the rule, returning possible results.
     pub apply: fn(&Expr, &RuleContext) -> Vec<RuleApplication>,
     /// Is this rule bidirectional?
+    ///
+    /// A reversible rule is an equivalence: every result equals the input at
+    /// every point where the application's `condition` holds, and is defined
+    /// at exactly the same points, so the rewrite is sound read right-to-left
+    /// under the same condition. Identities that only hold on part of the
+    /// domain (`√(ab) = √a·√b`) or pick one branch (`sin(x/2) = √((1-cos x)/2)`)
+    /// are one-way.
     pub reversible: bool,
     /// Cost heuristic (lower = prefer).
     pub cost: u32,
+    /// Declarative rewrite that [`Rule::can_apply`] and [`Rule::apply`] run
+    /// in place of `is_applicable` and `apply`.
+    ///
+    /// `None` for built-in rules; set by [`Rule::from_pattern`].
+    pub pattern: Option<Arc<PatternRule>>,
+}
+
+/// Id of the first rule in a pattern spec; built-in rules stay below it.
+///
+/// [`RuleSet::load_patterns`] numbers rules by their line in the spec, so a
+/// rule keeps its id, and its policy label, however many rules are loaded
+/// before it.
+pub const FIRST_PATTERN_RULE_ID: u32 = 10_000;
+
+impl Default for Rule {
+    /// A rule that never applies, for filling in the fields a rule literal
+    /// leaves out: `Rule { id, name, ..Default::default() }`.
+    fn default() -> Self {
+        Rule {
+            id: RuleId(0),
+            name: "",
+            category: RuleCategory::Simplification,
+            description: "",
+            domains: &[],
+            requires: &[],
+            roots: &[],
+            is_applicable: |_, _| false,
+            apply: |_, _| Vec::new(),
+            reversible: false,
+            cost: 1,
+            pattern: None,
+        }
+    }
 }
 
 impl Rule {
+    /// Build a rule rewriting `lhs` to `rhs`, both in the parser's syntax.
+    ///
+    /// Metavariables of `lhs` (`?a`, `?b`, …) are wildcards for any subtree,
+    /// and repeated ones must match equal subtrees: `?a + ?a` => `2*?a`. A
+    /// `lhs` without metavariables treats every variable that way, so
+    /// `sin(x)^2 + cos(x)^2` => `1` rewrites `sin(2t)² + cos(2t)²` too.
+    /// Matching is at the root only, and structural up to the order and
+    /// grouping of sums and products.
+    ///
+    /// The rule has id [`FIRST_PATTERN_RULE_ID`]; give it another before
+    /// adding several to one rule set. Its `is_applicable` and `apply`
+    /// fields never match: [`can_apply`](Self::can_apply) and
+    /// [`apply`](Self::apply) run the [`pattern`](Self::pattern) instead.
+    ///
+    /// Returns [`MathError::ParseError`] if either side fails to parse, and
+    /// [`MathError::UndefinedVariable`] if `rhs` uses a wildcard `lhs` lacks.
+    pub fn from_pattern(
+        lhs: &str,
+        rhs: &str,
+        symbols: &mut SymbolTable,
+    ) -> Result<Rule, MathError> {
+        let mut parser = mm_core::parse::Parser::new(symbols);
+        let (pattern, template) = (parser.parse(lhs)?, parser.parse(rhs)?);
+        let roots = match pattern {
+            Expr::Var(_) => &[],
+            _ => {
+                let i = ExprKind::ALL
+                    .iter()
+                    .position(|&k| k == pattern.kind())
+                    .unwrap();
+                &ExprKind::ALL[i..=i]
+            }
+        };
+        let rewrite = PatternRule::new(format!("{} => {}", lhs, rhs), pattern, template);
+        if let Some(unbound) = rewrite.unbound() {
+            return Err(MathError::UndefinedVariable(format!(
+                "{} is not bound by {}",
+                symbols.resolve(unbound).unwrap_or("?"),
+                lhs
+            )));
+        }
+
+        Ok(Rule {
+            id: RuleId(FIRST_PATTERN_RULE_ID),
+            name: "pattern",
+            description: "Rewrite loaded from a pattern spec",
+            roots,
+            pattern: Some(Arc::new(rewrite)),
+            ..Default::default()
+        })
+    }
+
     /// Check if this rule can be applied.
     pub fn can_apply(&self, expr: &Expr, ctx: &RuleContext) -> bool {
-        (self.is_applicable)(expr, ctx)
+        match &self.pattern {
+            Some(pattern) => pattern.rewrite(expr).is_some(),
+            None => (self.is_applicable)(expr, ctx),
+        }
     }
 
     /// Apply this rule to an expression.
     pub fn apply(&self, expr: &Expr, ctx: &RuleContext) -> Vec<RuleApplication> {
-        (self.apply)(expr, ctx)
+        let Some(pattern) = &self.pattern else {
+            return (self.apply)(expr, ctx);
+        };
+        pattern
+            .rewrite(expr)
+            .map(|result| RuleApplication {
+                result,
+                justification: pattern.source().to_string(),
+                condition: None,
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Root expression kinds this rule declares it can rewrite.
+    ///
+    /// Empty if the rule has not declared any.
+    pub fn applicable_roots(&self) -> &'static [ExprKind] {
+        self.roots
     }
 }
 
-/// Macro for creating Rule structs with default `domains` and `requires` fields.
+/// Macro for creating Rule structs with default `domains`, `requires` and `roots` fields.
 ///
 /// This provides backward compatibility - existing rules don't need to specify
 /// the new domain/feature fields. They default to empty slices, which means
@@ -239,10 +388,12 @@ macro_rules! rule {
             description: $description,
             domains: $domains,
             requires: $requires,
+            roots: &[],
             is_applicable: $is_applicable,
             apply: $apply,
             reversible: $reversible,
             cost: $cost,
+            ..Default::default()
         }
     };
     // Version without domains/requires (backward compatibility)
@@ -265,10 +416,12 @@ macro_rules! rule {
             description: $description,
             domains: &[],  // Default: applicable to all domains
             requires: &[], // Default: no specific features required
+            roots: &[],    // Default: tried at every root
             is_applicable: $is_applicable,
             apply: $apply,
             reversible: $reversible,
             cost: $cost,
+            ..Default::default()
         }
     };
 }
@@ -283,12 +436,52 @@ impl fmt::Debug for Rule {
     }
 }
 
+/// Descriptive data about a rule, without its match and apply functions.
+///
+/// Serializable, so tools can list and search rules without linking them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuleInfo {
+    /// Unique identifier.
+    pub id: RuleId,
+    /// Human-readable name.
+    pub name: String,
+    /// Category for organization and strategy.
+    pub category: RuleCategory,
+    /// Description for explanation.
+    pub description: String,
+    /// Cost heuristic (lower = prefer).
+    pub cost: u32,
+    /// Is this rule bidirectional? See [`Rule::reversible`].
+    pub reversible: bool,
+    /// Mathematical domains this rule applies to (empty means all).
+    pub domains: Vec<Domain>,
+}
+
+impl Rule {
+    /// Descriptive data about this rule.
+    pub fn info(&self) -> RuleInfo {
+        RuleInfo {
+            id: self.id,
+            name: self.name.to_string(),
+            category: self.category,
+            description: self.description.to_string(),
+            cost: self.cost,
+            reversible: self.reversible,
+            domains: self.domains.to_vec(),
+        }
+    }
+}
+
 /// A collection of rules.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct RuleSet {
     rules: Vec<Rule>,
     by_id: HashMap<RuleId, usize>,
     by_category: HashMap<RuleCategory, Vec<RuleId>>,
+    /// Indices of rules declaring each root kind, in insertion order.
+    by_root: HashMap<ExprKind, Vec<usize>>,
+    /// Indices of rules without declared roots, tried at every root.
+    unrooted: Vec<usize>,
 }
 
 impl RuleSet {
@@ -303,11 +496,64 @@ impl RuleSet {
         let category = rule.category;
         let idx = self.rules.len();
 
+        if rule.roots.is_empty() {
+            self.unrooted.push(idx);
+        }
+        for &kind in rule.roots {
+            self.by_root.entry(kind).or_default().push(idx);
+        }
         self.rules.push(rule);
         self.by_id.insert(id, idx);
         self.by_category.entry(category).or_default().push(id);
     }
 
+    /// Add pattern rules from a spec, one `lhs => rhs` rule per line.
+    ///
+    /// Blank lines and lines starting with `#` are skipped. Each rule is
+    /// built by [`Rule::from_pattern`] and numbered by its line: the rule on
+    /// line `n` gets id `FIRST_PATTERN_RULE_ID + n - 1`. If any line fails,
+    /// nothing is added and the error names the line. Returns the number of
+    /// rules added.
+    ///
+    /// ```text
+    /// # Pythagorean identity
+    /// sin(x)^2 + cos(x)^2 => 1
+    /// ```
+    pub fn load_patterns(
+        &mut self,
+        spec: &str,
+        symbols: &mut SymbolTable,
+    ) -> Result<usize, MathError> {
+        let mut rules = Vec::new();
+        for (i, line) in spec.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let rule = match line.split_once("=>") {
+                Some((lhs, rhs)) => Rule::from_pattern(lhs.trim(), rhs.trim(), symbols),
+                None => Err(MathError::ParseError("expected `lhs => rhs`".to_string())),
+            };
+            let mut rule = rule.map_err(|e| match e {
+                MathError::ParseError(msg) => {
+                    MathError::ParseError(format!("line {}: {}", i + 1, msg))
+                }
+                MathError::UndefinedVariable(msg) => {
+                    MathError::UndefinedVariable(format!("line {}: {}", i + 1, msg))
+                }
+                other => other,
+            })?;
+            rule.id = RuleId(FIRST_PATTERN_RULE_ID + i as u32);
+            rules.push(rule);
+        }
+
+        let count = rules.len();
+        for rule in rules {
+            self.add(rule);
+        }
+        Ok(count)
+    }
+
     /// Get a rule by ID.
     pub fn get(&self, id: RuleId) -> Option<&Rule> {
         self.by_id.get(&id).map(|&idx| &self.rules[idx])
@@ -326,14 +572,56 @@ impl RuleSet {
             .unwrap_or_default()
     }
 
+    /// A new rule set holding the rules for which `keep` returns true.
+    pub fn subset(&self, keep: impl Fn(&Rule) -> bool) -> RuleSet {
+        let mut subset = RuleSet::new();
+        for rule in self.rules.iter().filter(|rule| keep(rule)) {
+            subset.add(rule.clone());
+        }
+        subset
+    }
+
+    /// A new rule set holding only the rules of `category`.
+    ///
+    /// For focused solvers, e.g. a differentiation-only search.
+    pub fn category_subset(&self, category: RuleCategory) -> RuleSet {
+        self.subset(|rule| rule.category == category)
+    }
+
+    /// Rules that might apply at the root of `expr`, in insertion order.
+    ///
+    /// Rules declaring other root kinds are skipped without calling their
+    /// `is_applicable`; rules declaring none are always tried.
+    pub fn candidates(&self, expr: &Expr) -> impl Iterator<Item = &Rule> {
+        let declared = self
+            .by_root
+            .get(&expr.kind())
+            .map_or(&[][..], Vec::as_slice);
+        // Both lists are in insertion order and disjoint, so merge them
+        let mut declared = declared.iter().copied().peekable();
+        let mut unrooted = self.unrooted.iter().copied().peekable();
+        std::iter::from_fn(move || {
+            let idx = match (declared.peek(), unrooted.peek()) {
+                (Some(d), Some(u)) if u < d => unrooted.next(),
+                (Some(_), _) => declared.next(),
+                (None, _) => unrooted.next(),
+            };
+            idx.map(|idx| &self.rules[idx])
+        })
+    }
+
     /// Find all applicable rules for an expression.
     pub fn applicable(&self, expr: &Expr, ctx: &RuleContext) -> Vec<&Rule> {
-        self.rules
-            .iter()
+        self.candidates(expr)
             .filter(|rule| rule.can_apply(expr, ctx))
             .collect()
     }
 
+    /// Descriptive data for every rule, in insertion order.
+    pub fn catalog(&self) -> Vec<RuleInfo> {
+        self.rules.iter().map(Rule::info).collect()
+    }
+
     /// Get the number of rules.
     pub fn len(&self) -> usize {
         self.rules.len()
@@ -369,11 +657,16 @@ pub fn standard_rules() -> RuleSet {
         rules.add(rule);
     }
 
-    // Add integration rules - 9 working, 0 stubs
+    // Add integration rules - 10 working, 0 stubs
     for rule in crate::integration::integration_rules() {
         rules.add(rule);
     }
 
+    // Add logic rules - 5 working, 0 stubs
+    for rule in crate::logic::logic_rules() {
+        rules.add(rule);
+    }
+
     // MIXED MODULES (have both working and stub rules):
 
     // Add calculus rules - 15 working, 2 stubs
@@ -423,4 +716,389 @@ mod tests {
         assert!(!rules.is_empty());
         println!("Loaded {} rules", rules.len());
     }
+
+    #[test]
+    fn test_catalog() {
+        let rules = standard_rules();
+        let catalog = rules.catalog();
+        assert_eq!(catalog.len(), rules.len());
+
+        let info = catalog.iter().find(|r| r.name == "const_fold").unwrap();
+        let rule = rules.get(info.id).unwrap();
+        assert_eq!(info.category, rule.category);
+        assert_eq!(info.description, rule.description);
+        assert_eq!(info.cost, rule.cost);
+        assert_eq!(info.reversible, rule.reversible);
+        assert_eq!(info.domains, rule.domains);
+
+        let json = serde_json::to_string(&catalog).unwrap();
+        let parsed: Vec<RuleInfo> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, catalog);
+    }
+
+    #[test]
+    fn test_pattern_rule() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let rule = Rule::from_pattern("sin(x)^2 + cos(x)^2", "1", &mut symbols).unwrap();
+        assert_eq!(rule.id, RuleId(FIRST_PATTERN_RULE_ID));
+        assert_eq!(rule.applicable_roots(), &[ExprKind::Add]);
+
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let subject = parser.parse("sin(2*t)^2 + cos(2*t)^2").unwrap();
+        let mixed = parser.parse("sin(t)^2 + cos(2*t)^2").unwrap();
+        let reversed = parser.parse("cos(2*t)^2 + sin(2*t)^2").unwrap();
+        let ctx = RuleContext::default();
+        assert!(rule.can_apply(&subject, &ctx));
+        assert!(rule.can_apply(&reversed, &ctx));
+        let apps = rule.apply(&subject, &ctx);
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].result, Expr::int(1));
+        assert_eq!(apps[0].justification, "sin(x)^2 + cos(x)^2 => 1");
+        assert!(!rule.can_apply(&mixed, &ctx));
+
+        // The rhs may only use variables the lhs binds
+        assert!(matches!(
+            Rule::from_pattern("sin(x)", "y", &mut symbols),
+            Err(MathError::UndefinedVariable(_))
+        ));
+
+        // With metavariables, plain variables are literal on both sides
+        let double = Rule::from_pattern("?a + ?a", "2*?a + y", &mut symbols).unwrap();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let subject = parser.parse("t^2 + t^2").unwrap();
+        let expected = parser.parse("2*t^2 + y").unwrap();
+        assert_eq!(double.apply(&subject, &ctx)[0].result, expected);
+        assert!(Rule::from_pattern("x + ?a", "x", &mut symbols)
+            .unwrap()
+            .apply(&subject, &ctx)
+            .is_empty());
+        assert!(matches!(
+            Rule::from_pattern("?a", "?b", &mut symbols),
+            Err(MathError::UndefinedVariable(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_patterns() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut rules = standard_rules();
+        let before = rules.len();
+        let spec = "
+            # Pythagorean identity
+            sin(x)^2 + cos(x)^2 => 1
+
+            tan(x) * cos(x) => sin(x)
+        ";
+        assert_eq!(rules.load_patterns(spec, &mut symbols).unwrap(), 2);
+        assert_eq!(rules.len(), before + 2);
+
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let expr = parser.parse("sin(a + b)^2 + cos(a + b)^2").unwrap();
+        let ctx = RuleContext::default();
+        let pythagorean = rules
+            .applicable(&expr, &ctx)
+            .into_iter()
+            .find(|r| r.pattern.is_some())
+            .unwrap();
+        assert_eq!(pythagorean.id, RuleId(FIRST_PATTERN_RULE_ID + 2));
+        assert_eq!(pythagorean.apply(&expr, &ctx)[0].result, Expr::int(1));
+        let tan_cos = rules.get(RuleId(FIRST_PATTERN_RULE_ID + 4)).unwrap();
+        assert_eq!(
+            tan_cos.pattern.as_ref().unwrap().source(),
+            "tan(x) * cos(x) => sin(x)"
+        );
+
+        // A bad line rejects the whole spec
+        let err = rules
+            .load_patterns("x + 0 => x\nx +", &mut symbols)
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+        assert_eq!(rules.len(), before + 2);
+    }
+
+    #[test]
+    fn test_derivative_root_skips_other_rules() {
+        let rules = standard_rules();
+        let x = mm_core::SymbolTable::new().intern("x");
+        let deriv = Expr::Derivative {
+            expr: Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+            var: x,
+        };
+
+        let checked = rules.candidates(&deriv).count();
+        assert!(checked > 0);
+        assert!(
+            checked * 5 < rules.len(),
+            "checked {checked} of {}",
+            rules.len()
+        );
+        assert!(rules.candidates(&deriv).all(|r| {
+            r.applicable_roots().is_empty() || r.applicable_roots().contains(&ExprKind::Derivative)
+        }));
+        assert!(!rules.applicable(&deriv, &RuleContext::default()).is_empty());
+    }
+
+    #[test]
+    fn test_undeclared_roots_are_tried_everywhere() {
+        let mut rules = RuleSet::new();
+        rules.add(Rule {
+            id: RuleId(9001),
+            name: "drop_derivative",
+            category: RuleCategory::Simplification,
+            description: "d/dx(f) → f",
+            domains: &[],
+            requires: &[],
+            roots: &[],
+            is_applicable: |expr, _ctx| matches!(expr, Expr::Derivative { .. }),
+            apply: |_expr, _ctx| vec![],
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        });
+        let x = mm_core::SymbolTable::new().intern("x");
+        let deriv = Expr::Derivative {
+            expr: Box::new(Expr::Var(x)),
+            var: x,
+        };
+
+        // A simplification rule is not assumed to skip calculus nodes
+        assert_eq!(rules.candidates(&deriv).count(), 1);
+        assert_eq!(rules.applicable(&deriv, &RuleContext::default()).len(), 1);
+    }
+
+    fn sample_exprs(symbols: &mut mm_core::SymbolTable) -> Vec<Expr> {
+        let mut parser = mm_core::parse::Parser::new(symbols);
+        [
+            "2 + 3",
+            "x^2 - 1",
+            "x^3 + 8",
+            "(x + 1)^2",
+            "(x - 1)^2 * x^2",
+            "x^2 / x^3 + (x/2)^2",
+            "1/(1/x) - x*1 + 0*x",
+            "sqrt(8) + sqrt(x^2) + sqrt(4*x)",
+            "sin(x)^2 + cos(x)^2",
+            "ln(x * y) + ln(exp(x)) + exp(ln(x)) + exp(0)",
+            "diff(x^3 + sin(x), x)",
+            "diff(x * exp(x), x)",
+            "diff(ln(x) / x, x)",
+            "int(x^2, x)",
+            "int(cos(x) + 1/x, x)",
+            "x + 2 = 5",
+            "x^2 - 5*x + 6 = 0",
+            "gcd(12, 18)",
+            "2*sin(x)*cos(x) + tan(x)",
+            "cos(x)^2 - sin(x)^2 + sin(pi/6)",
+            "abs(x*y) + 5! + 12 % 5",
+            "binomial(5, 2) - (x^3 - 1)",
+        ]
+        .iter()
+        .map(|src| parser.parse(src).unwrap())
+        .collect()
+    }
+
+    /// The expression and all of its subexpressions.
+    fn subexprs(expr: &Expr, out: &mut Vec<Expr>) {
+        out.push(expr.clone());
+        match expr {
+            Expr::Neg(e)
+            | Expr::Sqrt(e)
+            | Expr::Sin(e)
+            | Expr::Cos(e)
+            | Expr::Tan(e)
+            | Expr::Ln(e)
+            | Expr::Exp(e)
+            | Expr::Abs(e)
+            | Expr::Derivative { expr: e, .. }
+            | Expr::Integral { expr: e, .. } => subexprs(e, out),
+            Expr::Add(a, b)
+            | Expr::Sub(a, b)
+            | Expr::Mul(a, b)
+            | Expr::Div(a, b)
+            | Expr::Pow(a, b)
+            | Expr::GCD(a, b)
+            | Expr::Equation { lhs: a, rhs: b } => {
+                subexprs(a, out);
+                subexprs(b, out);
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_declared_roots_cover_matches() {
+        let rules = standard_rules();
+        let ctx = RuleContext::default();
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut exprs = Vec::new();
+        for expr in sample_exprs(&mut symbols) {
+            subexprs(&expr, &mut exprs);
+        }
+
+        let declared: Vec<&Rule> = rules
+            .all()
+            .iter()
+            .filter(|r| !r.applicable_roots().is_empty())
+            .collect();
+        assert!(declared.len() > 100);
+        for rule in declared {
+            for expr in exprs.iter().filter(|e| rule.can_apply(e, &ctx)) {
+                assert!(
+                    rule.applicable_roots().contains(&expr.kind()),
+                    "{} matched a {:?} root it does not declare",
+                    rule.name,
+                    expr.kind()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_root_index_matches_full_scan() {
+        let rules = standard_rules();
+        let ctx = RuleContext::default();
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut exprs = Vec::new();
+        for expr in sample_exprs(&mut symbols) {
+            subexprs(&expr, &mut exprs);
+        }
+
+        for expr in &exprs {
+            let indexed: Vec<RuleId> = rules.applicable(expr, &ctx).iter().map(|r| r.id).collect();
+            let scanned: Vec<RuleId> = rules
+                .all()
+                .iter()
+                .filter(|r| r.can_apply(expr, &ctx))
+                .map(|r| r.id)
+                .collect();
+            assert_eq!(
+                indexed, scanned,
+                "index disagrees with full scan on {expr:?}"
+            );
+        }
+    }
+
+    /// Points each variable of a round-trip expression is sampled at.
+    const SAMPLES: [f64; 9] = [0.3, 1.3, 2.1, -0.8, 4.0, -2.5, 0.0, 1.0, -1.0];
+
+    /// Whether `condition` is known to hold under `env`.
+    fn holds(condition: &Option<Constraint>, env: &mm_core::eval::Env) -> bool {
+        let Some(condition) = condition else {
+            return true;
+        };
+        let value = |e: &Expr| e.evaluate(env).filter(|v| v.is_finite());
+        match &condition.expr {
+            Expr::Not(inner) => match inner.as_ref() {
+                Expr::Equation { lhs, rhs } => matches!(
+                    (value(lhs), value(rhs)),
+                    (Some(a), Some(b)) if (a - b).abs() > 1e-9
+                ),
+                _ => false,
+            },
+            Expr::Gt(a, b) => matches!((value(a), value(b)), (Some(a), Some(b)) if a > b),
+            _ => false,
+        }
+    }
+
+    /// Apply `rule` to `expr` and check every result against the
+    /// [`Rule::reversible`] contract.
+    ///
+    /// Read backward, each result must rewrite to `expr`: at every sample
+    /// point where the condition holds, the two sides are defined together
+    /// and agree in value.
+    fn assert_reversible(rule: &Rule, expr: &Expr, ctx: &RuleContext) {
+        let vars = expr.free_vars();
+        for app in rule.apply(expr, ctx) {
+            for k in 0..SAMPLES.len() {
+                let env = vars
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &v)| (v, SAMPLES[(k + 4 * i) % SAMPLES.len()]))
+                    .collect();
+                if !holds(&app.condition, &env) {
+                    continue;
+                }
+                let forward = expr.evaluate(&env).filter(|v| v.is_finite());
+                let backward = app.result.evaluate(&env).filter(|v| v.is_finite());
+                let agree = match (forward, backward) {
+                    (Some(a), Some(b)) => (a - b).abs() <= 1e-9 * (1.0 + a.abs()),
+                    (None, None) => true,
+                    _ => false,
+                };
+                assert!(
+                    agree,
+                    "{} is not reversible: {expr:?} -> {:?} gives {forward:?} vs {backward:?} at {env:?}",
+                    rule.name, app.result
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reversible_rules_round_trip() {
+        let rules = standard_rules();
+        let ctx = RuleContext::default();
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let exprs: Vec<Expr> = [
+            "(x + 1)^2",
+            "(x - y)^2",
+            "(x + 1)^3",
+            "x^2 - y^2",
+            "x^3 - y^3",
+            "x^3 + 8",
+            "x*(y + 1)",
+            "(x + y)*(x - y)",
+            "x + x",
+            "x^2 * x^3",
+            "x^5 / x^2",
+            "(x^2)^3",
+            "(x*y)^2",
+            "x^(-2)",
+            "1/(1/x)",
+            "x/y + y/x",
+            "sqrt(x*y)",
+            "ln(x*y)",
+            "ln(x/y)",
+            "ln(x^3)",
+            "exp(x + y)",
+            "exp(x)*exp(y)",
+            "sin(2*x)",
+            "cos(2*x)",
+            "tan(2*x)",
+            "sin(3*x)",
+            "cos(3*x)",
+            "sin(x/2)",
+            "cos(x/2)",
+            "tan(x)",
+            "sin(x)/cos(x)",
+            "sin(x + y)",
+            "cos(x - y)",
+            "tan(x + y)",
+            "sin(-x)",
+            "cos(-x)",
+            "tan(-x)",
+            "2*sin(x)*cos(x)",
+            "cos(x)^2 - sin(x)^2",
+            "sin(x)^2 + cos(x)^2",
+            "1 - sin(x)^2",
+            "1 + tan(x)^2",
+            "sin(x)*cos(y)",
+            "cos(x)*cos(y)",
+            "sin(x)*sin(y)",
+            "sin(x) + sin(y)",
+        ]
+        .iter()
+        .map(|src| parser.parse(src).unwrap())
+        .collect();
+
+        let mut checked = 0;
+        for rule in rules.all().iter().filter(|r| r.reversible) {
+            for expr in exprs.iter().filter(|e| rule.can_apply(e, &ctx)) {
+                assert_reversible(rule, expr, &ctx);
+                checked += 1;
+            }
+        }
+        assert!(checked > 50, "only {checked} round trips checked");
+    }
 }
@@ -6,8 +6,8 @@
 
 //! Trigonometric identity rules.
 
-use crate::{Rule, RuleApplication, RuleCategory, RuleId};
-use mm_core::Expr;
+use crate::{Rule, RuleApplication, RuleCategory, RuleContext, RuleId};
+use mm_core::{AngleMode, Constraint, Expr, ExprKind};
 
 /// Get all trigonometric rules.
 pub fn trig_rules() -> Vec<Rule> {
@@ -60,6 +60,7 @@ fn pythagorean_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "Pythagorean identity: sin²(x) + cos²(x) = 1",
         is_applicable: |expr, _ctx| {
             // Check for sin²(x) + cos²(x) pattern
@@ -87,6 +88,7 @@ fn pythagorean_identity() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::int(1),
                                 justification: "sin²(x) + cos²(x) = 1".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -96,6 +98,7 @@ fn pythagorean_identity() -> Rule {
         },
         reversible: false,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -110,6 +113,7 @@ fn sin_double_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "Double angle: 2sin(x)cos(x) = sin(2x)",
         is_applicable: |expr, _ctx| {
             // Check for 2 * sin(x) * cos(x) pattern
@@ -138,6 +142,7 @@ fn sin_double_angle() -> Rule {
                                         arg1.clone(),
                                     ))),
                                     justification: "2sin(x)cos(x) = sin(2x)".to_string(),
+                                    condition: None,
                                 }];
                             }
                         }
@@ -148,6 +153,7 @@ fn sin_double_angle() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -162,6 +168,7 @@ fn cos_double_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "Double angle: cos²(x) - sin²(x) = cos(2x)",
         is_applicable: |expr, _ctx| {
             // Check for cos²(x) - sin²(x) pattern
@@ -181,6 +188,7 @@ fn cos_double_angle() -> Rule {
                                     Box::new(arg1),
                                 ))),
                                 justification: "cos²(x) - sin²(x) = cos(2x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -190,6 +198,7 @@ fn cos_double_angle() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -204,6 +213,7 @@ fn sin_zero() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(0) = 0",
         is_applicable: |expr, _ctx| {
             if let Expr::Sin(inner) = expr {
@@ -217,6 +227,7 @@ fn sin_zero() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::int(0),
                         justification: "sin(0) = 0".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -224,6 +235,7 @@ fn sin_zero() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -238,6 +250,7 @@ fn cos_zero() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(0) = 1",
         is_applicable: |expr, _ctx| {
             if let Expr::Cos(inner) = expr {
@@ -251,6 +264,7 @@ fn cos_zero() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::int(1),
                         justification: "cos(0) = 1".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -258,6 +272,7 @@ fn cos_zero() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -272,6 +287,7 @@ fn tan_zero() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(0) = 0",
         is_applicable: |expr, _ctx| {
             if let Expr::Tan(inner) = expr {
@@ -285,6 +301,7 @@ fn tan_zero() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::int(0),
                         justification: "tan(0) = 0".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -292,6 +309,7 @@ fn tan_zero() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -309,6 +327,38 @@ fn is_squared_trig(expr: &Expr, is_sin: bool) -> bool {
     false
 }
 
+/// The base of `b^2`, if `expr` is a square.
+fn squared(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Pow(base, exp) if exp.as_ref() == &Expr::int(2) => Some(base),
+        _ => None,
+    }
+}
+
+/// Whether `arg` is the angle π/`n` in the context's angle mode.
+///
+/// In degree mode the angle is the constant `180/n`, so `sin(30)` matches
+/// `n = 6`.
+fn is_pi_over(arg: &Expr, n: i64, ctx: &RuleContext) -> bool {
+    match ctx.angle_mode {
+        AngleMode::Radians if n == 1 => matches!(arg, Expr::Pi),
+        AngleMode::Radians => match arg {
+            Expr::Div(num, denom) => matches!(num.as_ref(), Expr::Pi) && **denom == Expr::int(n),
+            _ => false,
+        },
+        AngleMode::Degrees => *arg == Expr::int(180 / n),
+    }
+}
+
+/// The angle π/`n` as written in the context's angle mode.
+fn angle_name(n: i64, ctx: &RuleContext) -> String {
+    match ctx.angle_mode {
+        AngleMode::Radians if n == 1 => "π".to_string(),
+        AngleMode::Radians => format!("π/{}", n),
+        AngleMode::Degrees => format!("{}°", 180 / n),
+    }
+}
+
 /// Extract the argument from a squared trig function.
 fn get_trig_arg(expr: &Expr) -> Option<Expr> {
     if let Expr::Pow(base, _) = expr {
@@ -331,19 +381,21 @@ fn sin_pi() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π) = 0",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                return matches!(arg.as_ref(), Expr::Pi);
+                return is_pi_over(arg, 1, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if matches!(arg.as_ref(), Expr::Pi) {
+                if is_pi_over(arg, 1, ctx) {
                     return vec![RuleApplication {
                         result: Expr::int(0),
-                        justification: "sin(π) = 0".to_string(),
+                        justification: format!("sin({}) = 0", angle_name(1, ctx)),
+                        condition: None,
                     }];
                 }
             }
@@ -351,6 +403,7 @@ fn sin_pi() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -361,19 +414,21 @@ fn cos_pi() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π) = -1",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                return matches!(arg.as_ref(), Expr::Pi);
+                return is_pi_over(arg, 1, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if matches!(arg.as_ref(), Expr::Pi) {
+                if is_pi_over(arg, 1, ctx) {
                     return vec![RuleApplication {
                         result: Expr::Neg(Box::new(Expr::int(1))),
-                        justification: "cos(π) = -1".to_string(),
+                        justification: format!("cos({}) = -1", angle_name(1, ctx)),
+                        condition: None,
                     }];
                 }
             }
@@ -381,6 +436,7 @@ fn cos_pi() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -391,30 +447,29 @@ fn sin_pi_over_2() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/2) = 1",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(2);
-                }
+                return is_pi_over(arg, 2, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(2) {
-                        return vec![RuleApplication {
-                            result: Expr::int(1),
-                            justification: "sin(π/2) = 1".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 2, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::int(1),
+                        justification: format!("sin({}) = 1", angle_name(2, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -425,30 +480,29 @@ fn cos_pi_over_2() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/2) = 0",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(2);
-                }
+                return is_pi_over(arg, 2, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(2) {
-                        return vec![RuleApplication {
-                            result: Expr::int(0),
-                            justification: "cos(π/2) = 0".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 2, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::int(0),
+                        justification: format!("cos({}) = 0", angle_name(2, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -459,33 +513,32 @@ fn sin_pi_over_4() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/4) = √2/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(4);
-                }
+                return is_pi_over(arg, 4, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(4) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(
-                                Box::new(Expr::Sqrt(Box::new(Expr::int(2)))),
-                                Box::new(Expr::int(2)),
-                            ),
-                            justification: "sin(π/4) = √2/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 4, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(
+                            Box::new(Expr::Sqrt(Box::new(Expr::int(2)))),
+                            Box::new(Expr::int(2)),
+                        ),
+                        justification: format!("sin({}) = √2/2", angle_name(4, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -496,33 +549,32 @@ fn cos_pi_over_4() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/4) = √2/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(4);
-                }
+                return is_pi_over(arg, 4, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(4) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(
-                                Box::new(Expr::Sqrt(Box::new(Expr::int(2)))),
-                                Box::new(Expr::int(2)),
-                            ),
-                            justification: "cos(π/4) = √2/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 4, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(
+                            Box::new(Expr::Sqrt(Box::new(Expr::int(2)))),
+                            Box::new(Expr::int(2)),
+                        ),
+                        justification: format!("cos({}) = √2/2", angle_name(4, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -533,30 +585,29 @@ fn sin_pi_over_6() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/6) = 1/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(6);
-                }
+                return is_pi_over(arg, 6, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(6) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2))),
-                            justification: "sin(π/6) = 1/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 6, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2))),
+                        justification: format!("sin({}) = 1/2", angle_name(6, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -567,33 +618,32 @@ fn cos_pi_over_6() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/6) = √3/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(6);
-                }
+                return is_pi_over(arg, 6, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(6) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(
-                                Box::new(Expr::Sqrt(Box::new(Expr::int(3)))),
-                                Box::new(Expr::int(2)),
-                            ),
-                            justification: "cos(π/6) = √3/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 6, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(
+                            Box::new(Expr::Sqrt(Box::new(Expr::int(3)))),
+                            Box::new(Expr::int(2)),
+                        ),
+                        justification: format!("cos({}) = √3/2", angle_name(6, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -604,33 +654,32 @@ fn sin_pi_over_3() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/3) = √3/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(3);
-                }
+                return is_pi_over(arg, 3, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(3) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(
-                                Box::new(Expr::Sqrt(Box::new(Expr::int(3)))),
-                                Box::new(Expr::int(2)),
-                            ),
-                            justification: "sin(π/3) = √3/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 3, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(
+                            Box::new(Expr::Sqrt(Box::new(Expr::int(3)))),
+                            Box::new(Expr::int(2)),
+                        ),
+                        justification: format!("sin({}) = √3/2", angle_name(3, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -641,30 +690,29 @@ fn cos_pi_over_3() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/3) = 1/2",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    return matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(3);
-                }
+                return is_pi_over(arg, 3, ctx);
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(arg) = expr {
-                if let Expr::Div(num, denom) = arg.as_ref() {
-                    if matches!(num.as_ref(), Expr::Pi) && *denom.as_ref() == Expr::int(3) {
-                        return vec![RuleApplication {
-                            result: Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2))),
-                            justification: "cos(π/3) = 1/2".to_string(),
-                        }];
-                    }
+                if is_pi_over(arg, 3, ctx) {
+                    return vec![RuleApplication {
+                        result: Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2))),
+                        justification: format!("cos({}) = 1/2", angle_name(3, ctx)),
+                        condition: None,
+                    }];
                 }
             }
             vec![]
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -679,6 +727,7 @@ fn tan_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(x) = sin(x)/cos(x)",
         is_applicable: |expr, _ctx| matches!(expr, Expr::Tan(_)),
         apply: |expr, _ctx| {
@@ -689,12 +738,14 @@ fn tan_identity() -> Rule {
                         Box::new(Expr::Cos(arg.clone())),
                     ),
                     justification: "tan(x) = sin(x)/cos(x)".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -705,6 +756,7 @@ fn sec_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "1/cos(x) = sec(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Div(num, denom) = expr {
@@ -723,6 +775,7 @@ fn sec_identity() -> Rule {
                             Box::new(Expr::Neg(Box::new(Expr::int(1)))),
                         ),
                         justification: "1/cos(x) = cos(x)^(-1)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -730,6 +783,7 @@ fn sec_identity() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -740,6 +794,7 @@ fn csc_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "1/sin(x) = csc(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Div(num, denom) = expr {
@@ -757,6 +812,7 @@ fn csc_identity() -> Rule {
                             Box::new(Expr::Neg(Box::new(Expr::int(1)))),
                         ),
                         justification: "1/sin(x) = sin(x)^(-1)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -764,6 +820,7 @@ fn csc_identity() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -774,6 +831,7 @@ fn cot_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "cos(x)/sin(x) = cot(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Div(num, denom) = expr {
@@ -793,6 +851,7 @@ fn cot_identity() -> Rule {
                                 Box::new(Expr::Tan(arg1.clone())),
                             ),
                             justification: "cos(x)/sin(x) = cot(x) = 1/tan(x)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -801,6 +860,7 @@ fn cot_identity() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -815,6 +875,7 @@ fn sin_neg() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(-x) = -sin(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Sin(arg) = expr {
@@ -828,6 +889,7 @@ fn sin_neg() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Neg(Box::new(Expr::Sin(inner.clone()))),
                         justification: "sin(-x) = -sin(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -835,6 +897,7 @@ fn sin_neg() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -845,6 +908,7 @@ fn cos_neg() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(-x) = cos(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Cos(arg) = expr {
@@ -858,6 +922,7 @@ fn cos_neg() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Cos(inner.clone()),
                         justification: "cos(-x) = cos(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -865,6 +930,7 @@ fn cos_neg() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -875,6 +941,7 @@ fn tan_neg() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(-x) = -tan(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Tan(arg) = expr {
@@ -888,6 +955,7 @@ fn tan_neg() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Neg(Box::new(Expr::Tan(inner.clone()))),
                         justification: "tan(-x) = -tan(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -895,6 +963,7 @@ fn tan_neg() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -909,6 +978,7 @@ fn sin_sum_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "2·sin(x)·cos(x) = sin(2x)",
         is_applicable: |expr, _ctx| {
             // Pattern: 2 * sin(x) * cos(x)
@@ -948,6 +1018,7 @@ fn sin_sum_formula() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Sin(Box::new(Expr::Mul(Box::new(Expr::int(2)), arg))),
                                 justification: "2·sin(x)·cos(x) = sin(2x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -957,6 +1028,7 @@ fn sin_sum_formula() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -967,6 +1039,7 @@ fn cos_sum_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "cos²(x) - sin²(x) = cos(2x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Sub(left, right) = expr {
@@ -987,6 +1060,7 @@ fn cos_sum_formula() -> Rule {
                                     Box::new(arg1),
                                 ))),
                                 justification: "cos²(x) - sin²(x) = cos(2x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -996,6 +1070,7 @@ fn cos_sum_formula() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1038,6 +1113,7 @@ fn cos_double_angle_2cos() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "2cos²(x) - 1 = cos(2x)",
         is_applicable: |expr, _ctx| {
             // Match: 2*cos²(x) - 1
@@ -1066,6 +1142,7 @@ fn cos_double_angle_2cos() -> Rule {
                                 Box::new(arg),
                             ))),
                             justification: "2cos²(x) - 1 = cos(2x)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1074,6 +1151,7 @@ fn cos_double_angle_2cos() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1085,6 +1163,7 @@ fn cos_double_angle_2sin() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "1 - 2sin²(x) = cos(2x)",
         is_applicable: |expr, _ctx| {
             // Match: 1 - 2*sin²(x)
@@ -1113,6 +1192,7 @@ fn cos_double_angle_2sin() -> Rule {
                                 Box::new(arg),
                             ))),
                             justification: "1 - 2sin²(x) = cos(2x)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1121,6 +1201,7 @@ fn cos_double_angle_2sin() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1132,6 +1213,7 @@ fn tan_double_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(2x) ↔ 2tan(x)/(1-tan²(x))",
         is_applicable: |expr, _ctx| {
             // Match tan(2x) where arg is 2*something
@@ -1167,6 +1249,7 @@ fn tan_double_angle() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(numerator), Box::new(denominator)),
                         justification: "tan(2x) = 2tan(x)/(1-tan²(x))".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1174,6 +1257,7 @@ fn tan_double_angle() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1185,6 +1269,7 @@ fn sin_triple_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(3x) = 3sin(x) - 4sin³(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Sin(inner) = expr {
@@ -1219,6 +1304,7 @@ fn sin_triple_angle() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Sub(Box::new(term1), Box::new(term2)),
                         justification: "sin(3x) = 3sin(x) - 4sin³(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1226,6 +1312,7 @@ fn sin_triple_angle() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1237,6 +1324,7 @@ fn cos_triple_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(3x) = 4cos³(x) - 3cos(x)",
         is_applicable: |expr, _ctx| {
             if let Expr::Cos(inner) = expr {
@@ -1271,6 +1359,7 @@ fn cos_triple_angle() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Sub(Box::new(term1), Box::new(term2)),
                         justification: "cos(3x) = 4cos³(x) - 3cos(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1278,6 +1367,7 @@ fn cos_triple_angle() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1289,6 +1379,7 @@ fn tan_sec_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "1 + tan²(x) = sec²(x) = 1/cos²(x)",
         is_applicable: |expr, _ctx| {
             // Match: 1 + tan²(x)
@@ -1315,6 +1406,7 @@ fn tan_sec_identity() -> Rule {
                         return vec![RuleApplication {
                             result: Expr::Div(Box::new(Expr::int(1)), Box::new(cos_sq)),
                             justification: "1 + tan²(x) = sec²(x) = 1/cos²(x)".to_string(),
+                            condition: None,
                         }];
                     }
                 }
@@ -1323,6 +1415,7 @@ fn tan_sec_identity() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1334,6 +1427,7 @@ fn cot_csc_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "1 + cot²(x) = csc²(x) = 1/sin²(x)",
         is_applicable: |expr, _ctx| {
             // Match: 1 + (cos/sin)² - simplified check
@@ -1370,6 +1464,7 @@ fn cot_csc_identity() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Div(Box::new(Expr::int(1)), Box::new(sin_sq)),
                                 justification: "1 + cot²(x) = csc²(x) = 1/sin²(x)".to_string(),
+                                condition: None,
                             }];
                         }
                     }
@@ -1379,6 +1474,7 @@ fn cot_csc_identity() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1390,6 +1486,7 @@ fn sin_sin_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "sin(a)sin(b) = (cos(a-b) - cos(a+b))/2",
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(left, right) = expr {
@@ -1408,6 +1505,7 @@ fn sin_sin_product() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(numerator), Box::new(Expr::int(2))),
                         justification: "sin(a)sin(b) = (cos(a-b) - cos(a+b))/2".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1415,6 +1513,7 @@ fn sin_sin_product() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1426,6 +1525,7 @@ fn cos_cos_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "cos(a)cos(b) = (cos(a-b) + cos(a+b))/2",
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(left, right) = expr {
@@ -1444,6 +1544,7 @@ fn cos_cos_product() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Div(Box::new(numerator), Box::new(Expr::int(2))),
                         justification: "cos(a)cos(b) = (cos(a-b) + cos(a+b))/2".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1451,6 +1552,7 @@ fn cos_cos_product() -> Rule {
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1462,6 +1564,7 @@ fn sin_cos_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "sin(a)cos(b) = (sin(a+b) + sin(a-b))/2",
         is_applicable: |expr, _ctx| {
             if let Expr::Mul(left, right) = expr {
@@ -1488,12 +1591,14 @@ fn sin_cos_product() -> Rule {
                 return vec![RuleApplication {
                     result: Expr::Div(Box::new(numerator), Box::new(Expr::int(2))),
                     justification: "sin(a)cos(b) = (sin(a+b) + sin(a-b))/2".to_string(),
+                    condition: None,
                 }];
             }
             vec![]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1505,6 +1610,7 @@ fn sin_half_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(x/2) = √((1-cos(x))/2)",
         is_applicable: |expr, _ctx| {
             if let Expr::Sin(inner) = expr {
@@ -1516,7 +1622,7 @@ fn sin_half_angle() -> Rule {
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(inner) = expr {
                 if let Expr::Div(num, den) = inner.as_ref() {
                     if let Expr::Const(c) = den.as_ref() {
@@ -1530,6 +1636,7 @@ fn sin_half_angle() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Sqrt(Box::new(fraction)),
                                 justification: "sin(x/2) = √((1-cos(x))/2)".to_string(),
+                                condition: ctx.side_condition(Constraint::positive(expr.clone())),
                             }];
                         }
                     }
@@ -1537,8 +1644,9 @@ fn sin_half_angle() -> Rule {
             }
             vec![]
         },
-        reversible: true,
+        reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1550,6 +1658,7 @@ fn cos_half_angle() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(x/2) = √((1+cos(x))/2)",
         is_applicable: |expr, _ctx| {
             if let Expr::Cos(inner) = expr {
@@ -1561,7 +1670,7 @@ fn cos_half_angle() -> Rule {
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(inner) = expr {
                 if let Expr::Div(num, den) = inner.as_ref() {
                     if let Expr::Const(c) = den.as_ref() {
@@ -1575,6 +1684,7 @@ fn cos_half_angle() -> Rule {
                             return vec![RuleApplication {
                                 result: Expr::Sqrt(Box::new(fraction)),
                                 justification: "cos(x/2) = √((1+cos(x))/2)".to_string(),
+                                condition: ctx.side_condition(Constraint::positive(expr.clone())),
                             }];
                         }
                     }
@@ -1582,8 +1692,9 @@ fn cos_half_angle() -> Rule {
             }
             vec![]
         },
-        reversible: true,
+        reversible: false,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -1595,26 +1706,23 @@ fn sin_cos_cofunction() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/2 - x) = cos(x)",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Sin(inner) = expr {
                 if let Expr::Sub(left, _) = inner.as_ref() {
-                    // Check if left is π/2
-                    if let Expr::Div(num, den) = left.as_ref() {
-                        if let (Expr::Pi, Expr::Const(c)) = (num.as_ref(), den.as_ref()) {
-                            return *c == mm_core::Rational::from_integer(2);
-                        }
-                    }
+                    return is_pi_over(left, 2, ctx);
                 }
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Sin(inner) = expr {
                 if let Expr::Sub(_, right) = inner.as_ref() {
                     return vec![RuleApplication {
                         result: Expr::Cos(right.clone()),
-                        justification: "sin(π/2 - x) = cos(x)".to_string(),
+                        justification: format!("sin({} - x) = cos(x)", angle_name(2, ctx)),
+                        condition: None,
                     }];
                 }
             }
@@ -1622,6 +1730,7 @@ fn sin_cos_cofunction() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1633,25 +1742,23 @@ fn cos_sin_cofunction() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/2 - x) = sin(x)",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Cos(inner) = expr {
                 if let Expr::Sub(left, _) = inner.as_ref() {
-                    if let Expr::Div(num, den) = left.as_ref() {
-                        if let (Expr::Pi, Expr::Const(c)) = (num.as_ref(), den.as_ref()) {
-                            return *c == mm_core::Rational::from_integer(2);
-                        }
-                    }
+                    return is_pi_over(left, 2, ctx);
                 }
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Cos(inner) = expr {
                 if let Expr::Sub(_, right) = inner.as_ref() {
                     return vec![RuleApplication {
                         result: Expr::Sin(right.clone()),
-                        justification: "cos(π/2 - x) = sin(x)".to_string(),
+                        justification: format!("cos({} - x) = sin(x)", angle_name(2, ctx)),
+                        condition: None,
                     }];
                 }
             }
@@ -1659,6 +1766,7 @@ fn cos_sin_cofunction() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1670,20 +1778,17 @@ fn tan_cot_cofunction() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(π/2 - x) = cot(x) = cos(x)/sin(x)",
-        is_applicable: |expr, _ctx| {
+        is_applicable: |expr, ctx| {
             if let Expr::Tan(inner) = expr {
                 if let Expr::Sub(left, _) = inner.as_ref() {
-                    if let Expr::Div(num, den) = left.as_ref() {
-                        if let (Expr::Pi, Expr::Const(c)) = (num.as_ref(), den.as_ref()) {
-                            return *c == mm_core::Rational::from_integer(2);
-                        }
-                    }
+                    return is_pi_over(left, 2, ctx);
                 }
             }
             false
         },
-        apply: |expr, _ctx| {
+        apply: |expr, ctx| {
             if let Expr::Tan(inner) = expr {
                 if let Expr::Sub(_, right) = inner.as_ref() {
                     // cot(x) = cos(x)/sin(x)
@@ -1692,7 +1797,11 @@ fn tan_cot_cofunction() -> Rule {
                             Box::new(Expr::Cos(right.clone())),
                             Box::new(Expr::Sin(right.clone())),
                         ),
-                        justification: "tan(π/2 - x) = cot(x) = cos(x)/sin(x)".to_string(),
+                        justification: format!(
+                            "tan({} - x) = cot(x) = cos(x)/sin(x)",
+                            angle_name(2, ctx)
+                        ),
+                        condition: None,
                     }];
                 }
             }
@@ -1700,6 +1809,7 @@ fn tan_cot_cofunction() -> Rule {
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1771,16 +1881,19 @@ fn hyperbolic_sinh() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "sinh(x) = (e^x - e^(-x))/2",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sinh(x) = (e^x - e^(-x))/2 (hyperbolic sine definition)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1792,16 +1905,19 @@ fn hyperbolic_cosh() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "cosh(x) = (e^x + e^(-x))/2",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cosh(x) = (e^x + e^(-x))/2 (hyperbolic cosine definition)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1813,16 +1929,19 @@ fn hyperbolic_tanh() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "tanh(x) = sinh(x)/cosh(x)",
         is_applicable: |expr, _| matches!(expr, Expr::Div(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "tanh(x) = sinh(x)/cosh(x) (hyperbolic tangent definition)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1834,16 +1953,19 @@ fn sinh_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "sinh(2x) = 2sinh(x)cosh(x)",
         is_applicable: |expr, _| matches!(expr, Expr::Mul(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sinh(2x) = 2sinh(x)cosh(x) (double angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1855,16 +1977,19 @@ fn cosh_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "cosh(2x) = cosh²(x) + sinh²(x)",
         is_applicable: |expr, _| matches!(expr, Expr::Add(_, _)),
         apply: |expr, _| {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cosh(2x) = cosh²(x) + sinh²(x) (double angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -1876,18 +2001,26 @@ fn sinh_cosh_identity() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "cosh²(x) - sinh²(x) = 1",
         is_applicable: |expr, _| {
-            matches!(expr, Expr::Sub(_, _))
+            if let Expr::Sub(a, b) = expr {
+                if let (Some(c), Some(s)) = (squared(a), squared(b)) {
+                    return matches!((c, s), (Expr::Cosh(x), Expr::Sinh(y)) if x == y);
+                }
+            }
+            false
         },
-        apply: |expr, _| {
+        apply: |_expr, _| {
             vec![RuleApplication {
-                result: expr.clone(),
+                result: Expr::int(1),
                 justification: "cosh²(x) - sinh²(x) = 1 (hyperbolic Pythagorean identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1899,6 +2032,7 @@ fn sin_arcsin() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(arcsin(x)) = x",
         is_applicable: |expr, _| {
             if let Expr::Sin(inner) = expr {
@@ -1912,6 +2046,7 @@ fn sin_arcsin() -> Rule {
                     return vec![RuleApplication {
                         result: (**x).clone(),
                         justification: "sin(arcsin(x)) = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1919,6 +2054,7 @@ fn sin_arcsin() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1930,6 +2066,7 @@ fn cos_arccos() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(arccos(x)) = x",
         is_applicable: |expr, _| {
             if let Expr::Cos(inner) = expr {
@@ -1943,6 +2080,7 @@ fn cos_arccos() -> Rule {
                     return vec![RuleApplication {
                         result: (**x).clone(),
                         justification: "cos(arccos(x)) = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1950,6 +2088,7 @@ fn cos_arccos() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1961,6 +2100,7 @@ fn tan_arctan() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(arctan(x)) = x",
         is_applicable: |expr, _| {
             if let Expr::Tan(inner) = expr {
@@ -1974,6 +2114,7 @@ fn tan_arctan() -> Rule {
                     return vec![RuleApplication {
                         result: (**x).clone(),
                         justification: "tan(arctan(x)) = x".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -1981,6 +2122,7 @@ fn tan_arctan() -> Rule {
         },
         reversible: false,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -1992,6 +2134,7 @@ fn arcsin_arccos_sum() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "arcsin(x) + arccos(x) = π/2",
         is_applicable: |expr, _| {
             if let Expr::Add(left, right) = expr {
@@ -2008,10 +2151,12 @@ fn arcsin_arccos_sum() -> Rule {
                     Box::new(Expr::int(2)),
                 ),
                 justification: "arcsin(x) + arccos(x) = π/2".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2023,6 +2168,7 @@ fn sin_sum_to_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "sinA + sinB = 2sin((A+B)/2)cos((A-B)/2)",
         is_applicable: |expr, _| {
             if let Expr::Add(left, right) = expr {
@@ -2034,10 +2180,12 @@ fn sin_sum_to_product() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sinA + sinB = 2sin((A+B)/2)cos((A-B)/2) (sum-to-product)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2049,6 +2197,7 @@ fn cos_sum_to_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "cosA + cosB = 2cos((A+B)/2)cos((A-B)/2)",
         is_applicable: |expr, _| {
             if let Expr::Add(left, right) = expr {
@@ -2060,10 +2209,12 @@ fn cos_sum_to_product() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cosA + cosB = 2cos((A+B)/2)cos((A-B)/2) (sum-to-product)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2075,6 +2226,7 @@ fn sin_diff_to_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "sinA - sinB = 2cos((A+B)/2)sin((A-B)/2)",
         is_applicable: |expr, _| {
             if let Expr::Sub(left, right) = expr {
@@ -2086,10 +2238,12 @@ fn sin_diff_to_product() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sinA - sinB = 2cos((A+B)/2)sin((A-B)/2) (difference-to-product)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2101,6 +2255,7 @@ fn cos_diff_to_product() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "cosA - cosB = -2sin((A+B)/2)sin((A-B)/2)",
         is_applicable: |expr, _| {
             if let Expr::Sub(left, right) = expr {
@@ -2112,10 +2267,12 @@ fn cos_diff_to_product() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cosA - cosB = -2sin((A+B)/2)sin((A-B)/2) (difference-to-product)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2127,6 +2284,7 @@ fn sin_squared_half() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "sin²(x/2) = (1 - cos(x))/2",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2135,10 +2293,12 @@ fn sin_squared_half() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin²(x/2) = (1 - cos(x))/2 (power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2150,6 +2310,7 @@ fn cos_squared_half() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "cos²(x/2) = (1 + cos(x))/2",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2158,10 +2319,12 @@ fn cos_squared_half() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos²(x/2) = (1 + cos(x))/2 (power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2173,6 +2336,7 @@ fn tan_half_sin() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(x/2) = sin(x)/(1 + cos(x))",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Tan(_))
@@ -2181,10 +2345,12 @@ fn tan_half_sin() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "tan(x/2) = sin(x)/(1 + cos(x)) (half-angle formula)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2196,6 +2362,7 @@ fn tan_half_cos() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(x/2) = (1 - cos(x))/sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Tan(_))
@@ -2204,10 +2371,12 @@ fn tan_half_cos() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "tan(x/2) = (1 - cos(x))/sin(x) (half-angle formula)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2219,6 +2388,7 @@ fn sin_3x_expand() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(3x) = 3sin(x) - 4sin³(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2227,10 +2397,12 @@ fn sin_3x_expand() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(3x) = 3sin(x) - 4sin³(x) (triple angle formula)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2242,6 +2414,7 @@ fn cos_3x_expand() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(3x) = 4cos³(x) - 3cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2250,10 +2423,12 @@ fn cos_3x_expand() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(3x) = 4cos³(x) - 3cos(x) (triple angle formula)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2265,6 +2440,7 @@ fn sin_4x_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(4x) = 4sin(x)cos(x)(1 - 2sin²(x))",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2273,10 +2449,12 @@ fn sin_4x_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(4x) = 4sin(x)cos(x)(1 - 2sin²(x)) (quadruple angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2288,6 +2466,7 @@ fn cos_4x_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(4x) = 8cos⁴(x) - 8cos²(x) + 1",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2296,10 +2475,12 @@ fn cos_4x_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(4x) = 8cos⁴(x) - 8cos²(x) + 1 (quadruple angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2311,6 +2492,7 @@ fn cot_reciprocal() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "cot(x) = 1/tan(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Div(_, _))
@@ -2319,10 +2501,12 @@ fn cot_reciprocal() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cot(x) = 1/tan(x) (reciprocal identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2334,6 +2518,7 @@ fn sec_reciprocal() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "sec(x) = 1/cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Div(_, _))
@@ -2342,10 +2527,12 @@ fn sec_reciprocal() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sec(x) = 1/cos(x) (reciprocal identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2357,6 +2544,7 @@ fn csc_reciprocal() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Div],
         description: "csc(x) = 1/sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Div(_, _))
@@ -2365,10 +2553,12 @@ fn csc_reciprocal() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "csc(x) = 1/sin(x) (reciprocal identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2380,6 +2570,7 @@ fn sin_neg_x() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(-x) = -sin(x)",
         is_applicable: |expr, _| {
             if let Expr::Sin(inner) = expr {
@@ -2393,6 +2584,7 @@ fn sin_neg_x() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Neg(Box::new(Expr::Sin(x.clone()))),
                         justification: "sin(-x) = -sin(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -2400,6 +2592,7 @@ fn sin_neg_x() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2411,6 +2604,7 @@ fn cos_neg_x() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(-x) = cos(x)",
         is_applicable: |expr, _| {
             if let Expr::Cos(inner) = expr {
@@ -2424,6 +2618,7 @@ fn cos_neg_x() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Cos(x.clone()),
                         justification: "cos(-x) = cos(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -2431,6 +2626,7 @@ fn cos_neg_x() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2442,6 +2638,7 @@ fn tan_neg_x() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(-x) = -tan(x)",
         is_applicable: |expr, _| {
             if let Expr::Tan(inner) = expr {
@@ -2455,6 +2652,7 @@ fn tan_neg_x() -> Rule {
                     return vec![RuleApplication {
                         result: Expr::Neg(Box::new(Expr::Tan(x.clone()))),
                         justification: "tan(-x) = -tan(x)".to_string(),
+                        condition: None,
                     }];
                 }
             }
@@ -2462,6 +2660,7 @@ fn tan_neg_x() -> Rule {
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2473,6 +2672,7 @@ fn sin_pi_minus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π - x) = sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2481,10 +2681,12 @@ fn sin_pi_minus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(π - x) = sin(x) (supplementary angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2496,6 +2698,7 @@ fn cos_pi_minus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π - x) = -cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2504,10 +2707,12 @@ fn cos_pi_minus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(π - x) = -cos(x) (supplementary angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2519,6 +2724,7 @@ fn sin_pi_plus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π + x) = -sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2527,10 +2733,12 @@ fn sin_pi_plus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(π + x) = -sin(x) (angle shift)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2542,6 +2750,7 @@ fn cos_pi_plus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π + x) = -cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2550,10 +2759,12 @@ fn cos_pi_plus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(π + x) = -cos(x) (angle shift)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2565,6 +2776,7 @@ fn sin_2pi_plus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(2π + x) = sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2573,10 +2785,12 @@ fn sin_2pi_plus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(2π + x) = sin(x) (periodicity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2588,6 +2802,7 @@ fn cos_2pi_plus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(2π + x) = cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2596,10 +2811,12 @@ fn cos_2pi_plus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(2π + x) = cos(x) (periodicity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2611,6 +2828,7 @@ fn tan_pi_plus() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Tan],
         description: "tan(π + x) = tan(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Tan(_))
@@ -2619,10 +2837,12 @@ fn tan_pi_plus() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "tan(π + x) = tan(x) (periodicity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 1,
+        ..Default::default()
     }
 }
 
@@ -2634,6 +2854,7 @@ fn sin_complementary() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π/2 - x) = cos(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2642,10 +2863,12 @@ fn sin_complementary() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(π/2 - x) = cos(x) (complementary angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2657,6 +2880,7 @@ fn cos_complementary() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Cos],
         description: "cos(π/2 - x) = sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Cos(_))
@@ -2665,10 +2889,12 @@ fn cos_complementary() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(π/2 - x) = sin(x) (complementary angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2680,6 +2906,7 @@ fn sin_supplementary() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sin],
         description: "sin(π - x) = sin(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sin(_))
@@ -2688,10 +2915,12 @@ fn sin_supplementary() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin(π - x) = sin(x) (supplementary angle)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2703,6 +2932,7 @@ fn sin_squared_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "sin²(x) = (1 - cos(2x))/2",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2711,10 +2941,12 @@ fn sin_squared_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin²(x) = (1 - cos(2x))/2 (power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2726,6 +2958,7 @@ fn cos_squared_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "cos²(x) = (1 + cos(2x))/2",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2734,10 +2967,12 @@ fn cos_squared_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos²(x) = (1 + cos(2x))/2 (power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2749,6 +2984,7 @@ fn tan_squared_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "tan²(x) = (1 - cos(2x))/(1 + cos(2x))",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2757,10 +2993,12 @@ fn tan_squared_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "tan²(x) = (1 - cos(2x))/(1 + cos(2x)) (power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2772,6 +3010,7 @@ fn sin_pow4() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "sin⁴(x) = (3 - 4cos(2x) + cos(4x))/8",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2780,10 +3019,12 @@ fn sin_pow4() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "sin⁴(x) = (3 - 4cos(2x) + cos(4x))/8 (fourth power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2795,6 +3036,7 @@ fn cos_pow4() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Pow],
         description: "cos⁴(x) = (3 + 4cos(2x) + cos(4x))/8",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Pow(_, _))
@@ -2803,10 +3045,12 @@ fn cos_pow4() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos⁴(x) = (3 + 4cos(2x) + cos(4x))/8 (fourth power reduction)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 4,
+        ..Default::default()
     }
 }
 
@@ -2818,6 +3062,7 @@ fn triple_sin_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "3sin(x) - sin(3x) = 4sin³(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sub(_, _))
@@ -2826,10 +3071,12 @@ fn triple_sin_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "3sin(x) - sin(3x) = 4sin³(x) (triple angle identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2841,6 +3088,7 @@ fn triple_cos_formula() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Add],
         description: "cos(3x) + 3cos(x) = 4cos³(x)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Add(_, _))
@@ -2849,10 +3097,12 @@ fn triple_cos_formula() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "cos(3x) + 3cos(x) = 4cos³(x) (triple angle identity)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2864,6 +3114,7 @@ fn chebyshev_t2() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "T_2(x) = 2x² - 1",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sub(_, _))
@@ -2872,10 +3123,12 @@ fn chebyshev_t2() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev polynomial T₂(x) = 2x² - 1".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2887,6 +3140,7 @@ fn chebyshev_t3() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "T_3(x) = 4x³ - 3x",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sub(_, _))
@@ -2895,10 +3149,12 @@ fn chebyshev_t3() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev polynomial T₃(x) = 4x³ - 3x".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2910,6 +3166,7 @@ fn chebyshev_u2() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "U_2(x) = 4x² - 1",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sub(_, _))
@@ -2918,10 +3175,12 @@ fn chebyshev_u2() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev polynomial U₂(x) = 4x² - 1".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2933,6 +3192,7 @@ fn chebyshev_u3() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Sub],
         description: "U_3(x) = 8x³ - 4x",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Sub(_, _))
@@ -2941,10 +3201,12 @@ fn chebyshev_u3() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "Chebyshev polynomial U₃(x) = 8x³ - 4x".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 2,
+        ..Default::default()
     }
 }
 
@@ -2956,6 +3218,7 @@ fn prosthaphaeresis_1() -> Rule {
         category: RuleCategory::TrigIdentity,
         domains: &[crate::rule::Domain::Trigonometry],
         requires: &[crate::rule::Feature::Trig],
+        roots: &[ExprKind::Mul],
         description: "2cos(A)cos(B) = cos(A+B) + cos(A-B)",
         is_applicable: |expr, _| {
             matches!(expr, Expr::Mul(_, _))
@@ -2964,10 +3227,12 @@ fn prosthaphaeresis_1() -> Rule {
             vec![RuleApplication {
                 result: expr.clone(),
                 justification: "2cos(A)cos(B) = cos(A+B) + cos(A-B) (product-to-sum)".to_string(),
+                condition: None,
             }]
         },
         reversible: true,
         cost: 3,
+        ..Default::default()
     }
 }
 
@@ -2977,6 +3242,36 @@ mod tests {
     use crate::RuleContext;
     use mm_core::SymbolTable;
 
+    #[test]
+    fn test_hyperbolic_pythagorean() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let y = symbols.intern("y");
+        let ctx = RuleContext::default();
+        let sq = |e: Expr| Box::new(Expr::Pow(Box::new(e), Box::new(Expr::int(2))));
+
+        // cosh²(x) - sinh²(x) = 1
+        let expr = Expr::Sub(
+            sq(Expr::Cosh(Box::new(Expr::Var(x)))),
+            sq(Expr::Sinh(Box::new(Expr::Var(x)))),
+        );
+        let rule = sinh_cosh_identity();
+        assert!(rule.can_apply(&expr, &ctx));
+        assert_eq!(rule.apply(&expr, &ctx)[0].result, Expr::int(1));
+
+        // Different arguments or order do not match
+        let mismatched = Expr::Sub(
+            sq(Expr::Cosh(Box::new(Expr::Var(x)))),
+            sq(Expr::Sinh(Box::new(Expr::Var(y)))),
+        );
+        assert!(!rule.can_apply(&mismatched, &ctx));
+        let swapped = Expr::Sub(
+            sq(Expr::Sinh(Box::new(Expr::Var(x)))),
+            sq(Expr::Cosh(Box::new(Expr::Var(x)))),
+        );
+        assert!(!rule.can_apply(&swapped, &ctx));
+    }
+
     #[test]
     fn test_pythagorean_identity() {
         let mut symbols = SymbolTable::new();
@@ -3002,4 +3297,37 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].result, Expr::int(1));
     }
+
+    #[test]
+    fn test_degree_mode_special_values() {
+        let degrees = RuleContext {
+            angle_mode: AngleMode::Degrees,
+            ..RuleContext::default()
+        };
+        let radians = RuleContext::default();
+        let rule = sin_pi_over_6();
+
+        // sin(30) = 1/2 in degrees; in radians it is left as sin(30 rad)
+        let expr = Expr::Sin(Box::new(Expr::int(30)));
+        assert!(rule.can_apply(&expr, &degrees));
+        let results = rule.apply(&expr, &degrees);
+        assert_eq!(
+            results[0].result,
+            Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2)))
+        );
+        assert_eq!(results[0].justification, "sin(30°) = 1/2");
+        assert!(!rule.can_apply(&expr, &radians));
+
+        // sin(π/6) is a radian angle only
+        let expr = Expr::Sin(Box::new(Expr::Div(
+            Box::new(Expr::Pi),
+            Box::new(Expr::int(6)),
+        )));
+        assert!(rule.can_apply(&expr, &radians));
+        assert!(!rule.can_apply(&expr, &degrees));
+
+        // cos(180) = -1
+        let expr = Expr::Cos(Box::new(Expr::int(180)));
+        assert!(cos_pi().can_apply(&expr, &degrees));
+    }
 }
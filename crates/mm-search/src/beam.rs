@@ -6,11 +6,14 @@
 
 //! Beam search algorithm for finding solution paths.
 
-use crate::{SearchConfig, Solution, Step};
-use mm_core::Expr;
+use crate::rewrite::Rewrite;
+use crate::{SearchConfig, SearchOutcome, Solution, Step};
+use mm_core::{CanonCache, Constraint, Expr};
 use mm_rules::{RuleContext, RuleSet};
+use mm_verifier::division::division_conditions;
 use mm_verifier::Verifier;
 use std::collections::HashSet;
+use std::time::Instant;
 
 /// Beam search solver.
 pub struct BeamSearch {
@@ -19,6 +22,27 @@ pub struct BeamSearch {
     config: SearchConfig,
 }
 
+/// Statistics from a beam search run.
+#[derive(Debug, Clone, Default)]
+pub struct BeamStats {
+    /// Number of candidates whose successors were generated.
+    pub nodes_expanded: usize,
+    /// Deepest level of the beam that was reached.
+    pub depth_reached: usize,
+    /// Widest beam used, larger than the configured width only when the
+    /// adaptive beam widened it.
+    pub max_width: usize,
+    /// Whether the search stopped at a candidate no rule could simplify.
+    pub terminated_early: bool,
+    /// Whether the search gave up at the configured time limit.
+    pub timed_out: bool,
+    /// Canonical forms computed during the search.
+    pub canonicalizations: usize,
+    /// Canonical forms looked up again and served from the search's
+    /// [`CanonCache`] instead of recomputed.
+    pub canon_cache_hits: usize,
+}
+
 /// A candidate state in beam search.
 #[derive(Clone)]
 struct Candidate {
@@ -46,95 +70,144 @@ impl BeamSearch {
         }
     }
 
+    /// The configuration this search runs with.
+    pub fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    /// The context rules are applied in.
+    fn context(&self) -> RuleContext {
+        RuleContext {
+            assume_generic: self.config.assume_generic,
+            ..RuleContext::default()
+        }
+    }
+
     /// Search for a solution that satisfies the goal predicate.
     pub fn search<F>(&self, start: Expr, goal: F) -> Option<Solution>
     where
         F: Fn(&Expr) -> bool,
     {
+        self.run(start, goal, false).0
+    }
+
+    /// Like [`search`](Self::search), also reporting search statistics.
+    pub fn search_tracked<F>(&self, start: Expr, goal: F) -> (Option<Solution>, BeamStats)
+    where
+        F: Fn(&Expr) -> bool,
+    {
+        self.run(start, goal, false)
+    }
+
+    /// Beam search proper.
+    ///
+    /// With `stop_at_simplest`, a candidate that no rule can make strictly
+    /// simpler is not expanded further, and is returned at once if it is the
+    /// best candidate in the beam. Only valid when the goal is "simplest form":
+    /// other goals may need steps that grow the expression first.
+    fn run<F>(&self, start: Expr, goal: F, stop_at_simplest: bool) -> (Option<Solution>, BeamStats)
+    where
+        F: Fn(&Expr) -> bool,
+    {
+        let mut cache = CanonCache::new();
+        let (solution, stats) = self.run_cached(start, goal, stop_at_simplest, &mut cache);
+        let stats = BeamStats {
+            canonicalizations: cache.computed(),
+            canon_cache_hits: cache.hits(),
+            ..stats
+        };
+        (solution, stats)
+    }
+
+    /// [`run`](Self::run), canonicalizing through `cache`.
+    fn run_cached<F>(
+        &self,
+        start: Expr,
+        goal: F,
+        stop_at_simplest: bool,
+        cache: &mut CanonCache,
+    ) -> (Option<Solution>, BeamStats)
+    where
+        F: Fn(&Expr) -> bool,
+    {
+        let mut stats = BeamStats {
+            max_width: self.config.beam_width,
+            ..BeamStats::default()
+        };
+        let solution = |expr: &Expr, steps: &[Step]| Solution {
+            problem: start.clone(),
+            result: expr.clone(),
+            steps: steps.to_vec(),
+            verified: true,
+        };
+
         // Check if already at goal
         if goal(&start) {
-            return Some(Solution {
-                problem: start.clone(),
-                result: start,
-                steps: vec![],
-                verified: true,
-            });
+            return (Some(solution(&start, &[])), stats);
         }
 
         // Initialize beam with starting state
         let mut beam = vec![Candidate {
             expr: start.clone(),
             steps: vec![],
-            score: 0.0,
+            score: self.score_expr(&start),
         }];
 
         // Track visited states to avoid cycles
         let mut visited: HashSet<Expr> = HashSet::new();
-        visited.insert(start.canonicalize());
+        visited.insert(cache.canonicalize(&start));
 
-        let ctx = RuleContext::default();
+        let ctx = self.context();
+
+        // Adaptive beam state
+        let mut width = self.config.beam_width;
+        let mut best_seen = beam[0].score;
+        let mut stalled = 0;
+
+        let started = Instant::now();
 
         // Search
-        for _depth in 0..self.config.max_depth {
+        for depth in 0..self.config.max_depth {
+            if self
+                .config
+                .time_limit
+                .is_some_and(|limit| started.elapsed() >= limit)
+            {
+                stats.timed_out = true;
+                break;
+            }
+            stats.depth_reached = depth + 1;
             let mut candidates = Vec::new();
+            let best_score = beam.iter().map(|c| c.score).fold(f64::INFINITY, f64::min);
 
             for candidate in &beam {
                 // Check if this candidate reaches the goal
                 if goal(&candidate.expr) {
-                    return Some(Solution {
-                        problem: start.clone(),
-                        result: candidate.expr.clone(),
-                        steps: candidate.steps.clone(),
-                        verified: true,
-                    });
+                    return (Some(solution(&candidate.expr, &candidate.steps)), stats);
                 }
 
-                // Find applicable rules
-                let applicable = self.rules.applicable(&candidate.expr, &ctx);
-
-                for rule in applicable {
-                    let applications = rule.apply(&candidate.expr, &ctx);
-
-                    for app in applications {
-                        let canonical = app.result.canonicalize();
-
-                        // Skip if already visited
-                        if visited.contains(&canonical) {
-                            continue;
-                        }
-
-                        // Verify the step
-                        let verify_result =
-                            self.verifier
-                                .verify_step(&candidate.expr, &app.result, rule, &ctx);
-
-                        if !verify_result.is_valid() {
-                            continue;
-                        }
-
-                        // Create new step
-                        let step = Step {
-                            before: candidate.expr.clone(),
-                            after: app.result.clone(),
-                            rule_id: rule.id,
-                            rule_name: rule.name,
-                            justification: app.justification,
-                        };
-
-                        // Create new candidate
-                        let mut new_steps = candidate.steps.clone();
-                        new_steps.push(step);
-
-                        let new_candidate = Candidate {
-                            expr: app.result.clone(),
-                            steps: new_steps,
-                            score: self.score_expr(&app.result),
-                        };
-
-                        candidates.push(new_candidate);
-                        visited.insert(canonical);
+                let applications = self.applications(&candidate.expr, &ctx);
+                if stop_at_simplest
+                    && applications
+                        .iter()
+                        .all(|r| self.score_expr(&r.app.result) >= candidate.score)
+                {
+                    // Nothing makes this branch simpler: stop expanding it
+                    if candidate.score <= best_score {
+                        stats.terminated_early = true;
+                        return (Some(solution(&candidate.expr, &candidate.steps)), stats);
                     }
+                    continue;
                 }
+                stats.nodes_expanded += 1;
+                candidates.extend(self.successors(
+                    candidate,
+                    applications,
+                    &ctx,
+                    &mut visited,
+                    cache,
+                    &|e| self.score_expr(e),
+                ));
             }
 
             if candidates.is_empty() {
@@ -142,85 +215,383 @@ impl BeamSearch {
                 break;
             }
 
-            // Sort by score (lower is better - we want simpler expressions)
-            candidates.sort_by(|a, b| {
-                a.score
-                    .partial_cmp(&b.score)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+            beam = self.prune(candidates, width, cache);
 
-            // Keep top beam_width candidates
-            beam = candidates
-                .into_iter()
-                .take(self.config.beam_width)
-                .collect();
+            if self.config.adaptive_beam {
+                let level_best = beam.iter().map(|c| c.score).fold(f64::INFINITY, f64::min);
+                if level_best < best_seen {
+                    best_seen = level_best;
+                    stalled = 0;
+                    width = self.config.beam_width;
+                } else {
+                    stalled += 1;
+                    if stalled >= self.config.stall_depths {
+                        // Stalled: trade time for a wider look
+                        width = (width * 2).min(self.config.max_beam_width.max(width));
+                        stats.max_width = stats.max_width.max(width);
+                        stalled = 0;
+                    }
+                }
+            }
 
             // Check if any candidate reaches goal
             for candidate in &beam {
                 if goal(&candidate.expr) {
-                    return Some(Solution {
-                        problem: start.clone(),
-                        result: candidate.expr.clone(),
-                        steps: candidate.steps.clone(),
-                        verified: true,
-                    });
+                    return (Some(solution(&candidate.expr, &candidate.steps)), stats);
                 }
             }
         }
 
         // Search exhausted
-        None
+        (None, stats)
     }
 
     /// Simplify an expression by repeatedly applying simplification rules.
     ///
     /// Returns the simplest form found.
     pub fn simplify(&self, expr: Expr) -> Solution {
+        self.simplify_tracked(expr).0
+    }
+
+    /// Like [`simplify`](Self::simplify), also reporting search statistics.
+    pub fn simplify_tracked(&self, expr: Expr) -> (Solution, BeamStats) {
         // First, canonicalize to apply basic simplifications
         let canonical = expr.canonicalize();
 
         // If canonicalization already simplified it, we're done
         if canonical != expr {
             // Create a solution showing the simplification
-            return Solution {
+            let solution = Solution {
                 problem: expr,
                 result: canonical,
                 steps: vec![], // Canonicalization is atomic - could expand later
                 verified: true,
             };
+            return (solution, BeamStats::default());
         }
 
         // Otherwise, try to find a simplification path using rules
-        let goal = |e: &Expr| {
-            // Goal: expression is in simplest form (no applicable simplification rules)
-            // OR it's simpler than what we started with
-            let ctx = RuleContext::default();
-            let applicable = self.rules.applicable(e, &ctx);
-            let has_simplification = applicable
-                .iter()
-                .any(|r| r.category == mm_rules::RuleCategory::Simplification);
-
-            !has_simplification || e.complexity() < expr.complexity()
-        };
+        let goal = |e: &Expr| self.is_simplified(e, &expr);
 
         // Try beam search
-        if let Some(solution) = self.search(expr.clone(), goal) {
+        let (found, stats) = self.run(expr.clone(), goal, true);
+        if let Some(solution) = found {
             // Return the best result, canonicalized
-            return Solution {
+            let solution = Solution {
                 problem: solution.problem,
                 result: solution.result.canonicalize(),
                 steps: solution.steps,
                 verified: solution.verified,
             };
+            return (solution, stats);
         }
 
         // No simplification found, return canonical form
-        Solution {
+        let solution = Solution {
             problem: expr.clone(),
             result: canonical,
             steps: vec![],
             verified: true,
+        };
+        (solution, stats)
+    }
+
+    /// Like [`simplify`](Self::simplify), but reports why no simplified
+    /// form was found instead of handing back the input.
+    pub fn try_simplify(&self, expr: Expr) -> SearchOutcome {
+        let canonical = expr.canonicalize();
+        if canonical != expr {
+            return SearchOutcome::Solved(Solution {
+                problem: expr,
+                result: canonical,
+                steps: vec![],
+                verified: true,
+            });
+        }
+
+        let ctx = self.context();
+        if self.applications(&expr, &ctx).is_empty() {
+            return SearchOutcome::NoApplicableRules;
+        }
+
+        let goal = |e: &Expr| self.is_simplified(e, &expr);
+        match self.run(expr.clone(), goal, true) {
+            (Some(solution), _) => SearchOutcome::Solved(Solution {
+                result: solution.result.canonicalize(),
+                ..solution
+            }),
+            (None, stats) if stats.timed_out => SearchOutcome::Timeout,
+            (None, _) => SearchOutcome::ExhaustedDepth,
+        }
+    }
+
+    /// Find every distinct simplified form of an expression.
+    ///
+    /// Unlike [`simplify`](Self::simplify), the search keeps going after the
+    /// first simplified form and collects each terminal form with the path
+    /// that reached it, deduplicated by canonical form. Stops after
+    /// `max_solutions` forms. Falls back to the canonical form alone when no
+    /// rule path simplifies the expression.
+    pub fn simplify_all(&self, expr: Expr) -> Vec<Solution> {
+        let start = expr.canonicalize();
+        let ctx = self.context();
+        let mut cache = CanonCache::new();
+
+        let mut solutions = Vec::new();
+        let mut found: HashSet<Expr> = HashSet::new();
+
+        let mut beam = vec![Candidate {
+            expr: start.clone(),
+            steps: vec![],
+            score: self.score_expr(&start),
+        }];
+        let mut visited: HashSet<Expr> = HashSet::new();
+        visited.insert(start.clone());
+
+        for _depth in 0..self.config.max_depth {
+            let mut candidates = Vec::new();
+
+            for candidate in &beam {
+                // Terminal forms are recorded, not expanded
+                if self.is_simplified(&candidate.expr, &start) {
+                    let result = cache.canonicalize(&candidate.expr);
+                    if found.insert(result.clone()) {
+                        solutions.push(Solution {
+                            problem: expr.clone(),
+                            result,
+                            steps: candidate.steps.clone(),
+                            verified: true,
+                        });
+                        if solutions.len() >= self.config.max_solutions {
+                            return solutions;
+                        }
+                    }
+                    continue;
+                }
+
+                let applications = self.applications(&candidate.expr, &ctx);
+                candidates.extend(self.successors(
+                    candidate,
+                    applications,
+                    &ctx,
+                    &mut visited,
+                    &mut cache,
+                    &|e| self.score_expr(e),
+                ));
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+            beam = self.prune(candidates, self.config.beam_width, &mut cache);
+        }
+
+        if solutions.is_empty() {
+            solutions.push(Solution {
+                problem: expr,
+                result: start,
+                steps: vec![],
+                verified: true,
+            });
+        }
+        solutions
+    }
+
+    /// Find the reachable form of `expr` that minimizes `objective`.
+    ///
+    /// Runs the same beam as [`simplify`](Self::simplify), but ranks
+    /// candidates by `objective` instead of complexity, so the caller decides
+    /// what "best" means: fewest radicals, factored rather than expanded, and
+    /// so on. Searches to the configured depth and time limit and returns the
+    /// lowest-scoring form seen at any depth, the input itself if nothing
+    /// reachable scores lower. The result is not canonicalized, since that
+    /// could undo the shape the objective asked for.
+    pub fn optimize(&self, expr: Expr, objective: impl Fn(&Expr) -> f64) -> Solution {
+        let ctx = self.context();
+        let mut cache = CanonCache::new();
+        let mut visited: HashSet<Expr> = HashSet::new();
+        visited.insert(cache.canonicalize(&expr));
+
+        let mut best = Candidate {
+            expr: expr.clone(),
+            steps: vec![],
+            score: objective(&expr),
+        };
+        let mut beam = vec![best.clone()];
+        let started = Instant::now();
+
+        for _depth in 0..self.config.max_depth {
+            if self
+                .config
+                .time_limit
+                .is_some_and(|limit| started.elapsed() >= limit)
+            {
+                break;
+            }
+            let mut candidates = Vec::new();
+            for candidate in &beam {
+                let applications = self.applications(&candidate.expr, &ctx);
+                candidates.extend(self.successors(
+                    candidate,
+                    applications,
+                    &ctx,
+                    &mut visited,
+                    &mut cache,
+                    &objective,
+                ));
+            }
+
+            if candidates.is_empty() {
+                break;
+            }
+            beam = self.prune(candidates, self.config.beam_width, &mut cache);
+            // Pruning sorts by score, so the head is this level's best
+            if beam[0].score < best.score {
+                best = beam[0].clone();
+            }
+        }
+
+        Solution {
+            problem: expr,
+            result: best.expr,
+            steps: best.steps,
+            verified: true,
+        }
+    }
+
+    /// Whether `expr` counts as simplified relative to `start`: no
+    /// simplification rule applies, or it is already simpler than `start`.
+    fn is_simplified(&self, expr: &Expr, start: &Expr) -> bool {
+        let ctx = self.context();
+        let has_simplification = self
+            .rules
+            .applicable(expr, &ctx)
+            .iter()
+            .any(|r| r.category == mm_rules::RuleCategory::Simplification);
+
+        !has_simplification || expr.complexity() < start.complexity()
+    }
+
+    /// Rule applications at the positions of `expr` picked by the
+    /// configured [`RewriteStrategy`].
+    fn applications<'a>(&'a self, expr: &Expr, ctx: &RuleContext) -> Vec<Rewrite<'a>> {
+        self.config
+            .rewrite_strategy
+            .rewrites(expr, &self.verifier, ctx, &|e| {
+                self.root_applications(e, ctx)
+            })
+    }
+
+    /// Rule applications at the root of `expr`, at most
+    /// [`max_rule_fanout`](SearchConfig::max_rule_fanout) per rule.
+    fn root_applications<'a>(&'a self, expr: &Expr, ctx: &RuleContext) -> Vec<Rewrite<'a>> {
+        self.rules
+            .applicable(expr, ctx)
+            .into_iter()
+            .flat_map(|rule| {
+                let mut apps = rule.apply(expr, ctx);
+                if let Some(cap) = self.config.max_rule_fanout {
+                    if apps.len() > cap {
+                        // Stable, so equally scored results keep rule order
+                        apps.sort_by(|a, b| {
+                            self.score_expr(&a.result)
+                                .total_cmp(&self.score_expr(&b.result))
+                        });
+                        apps.truncate(cap);
+                    }
+                }
+                apps.into_iter().map(move |app| Rewrite::new(rule, app))
+            })
+            .collect()
+    }
+
+    /// Verified, not yet visited successors of `candidate`.
+    fn successors(
+        &self,
+        candidate: &Candidate,
+        applications: Vec<Rewrite<'_>>,
+        ctx: &RuleContext,
+        visited: &mut HashSet<Expr>,
+        cache: &mut CanonCache,
+        score: &dyn Fn(&Expr) -> f64,
+    ) -> Vec<Candidate> {
+        let mut candidates = Vec::new();
+
+        for mut rewrite in applications {
+            let canonical = cache.canonicalize(&rewrite.app.result);
+
+            // Skip if already visited
+            if visited.contains(&canonical) {
+                continue;
+            }
+
+            // Skip forms past the size cap
+            if self
+                .config
+                .max_expr_size
+                .is_some_and(|cap| rewrite.app.result.size() > cap)
+            {
+                continue;
+            }
+
+            // Verify the step where the rule matched
+            if !rewrite.is_valid(&candidate.expr, &self.verifier, ctx) {
+                continue;
+            }
+            let condition = rewrite.app.condition.take().or_else(|| {
+                let (before, after) = rewrite.step(&candidate.expr);
+                division_condition(before, after).and_then(|c| ctx.side_condition(c))
+            });
+            let Rewrite { rule, app, .. } = rewrite;
+
+            // Create new step
+            let step = Step {
+                before: candidate.expr.clone(),
+                after: app.result.clone(),
+                rule_id: rule.id,
+                rule_name: rule.name,
+                justification: app.justification,
+                condition,
+            };
+
+            // Create new candidate
+            let mut new_steps = candidate.steps.clone();
+            new_steps.push(step);
+
+            let new_candidate = Candidate {
+                expr: app.result.clone(),
+                steps: new_steps,
+                score: score(&app.result),
+            };
+
+            candidates.push(new_candidate);
+            visited.insert(canonical);
         }
+
+        candidates
+    }
+
+    /// Keep the best `width` candidates (lower score is better).
+    ///
+    /// Ties are broken by the hash of the canonical form, then by the id of
+    /// the last rule applied, so the retained beam does not depend on the
+    /// order candidates were generated in.
+    fn prune(
+        &self,
+        candidates: Vec<Candidate>,
+        width: usize,
+        cache: &mut CanonCache,
+    ) -> Vec<Candidate> {
+        let mut keyed: Vec<_> = candidates
+            .into_iter()
+            .map(|c| {
+                let rule = c.steps.last().map(|s| s.rule_id.0);
+                ((cache.canonical_hash(&c.expr), rule), c)
+            })
+            .collect();
+        keyed.sort_by(|(ka, a), (kb, b)| a.score.total_cmp(&b.score).then(ka.cmp(kb)));
+        keyed.truncate(width);
+        keyed.into_iter().map(|(_, c)| c).collect()
     }
 
     /// Score an expression (lower is better).
@@ -231,10 +602,22 @@ impl BeamSearch {
     }
 }
 
+/// The denominators the step `before → after` relies on being nonzero,
+/// joined into one condition; `None` if it relies on none.
+fn division_condition(before: &Expr, after: &Expr) -> Option<Constraint> {
+    division_conditions(before, after)
+        .into_iter()
+        .map(|c| c.expr)
+        .reduce(|a, b| Expr::And(Box::new(a), Box::new(b)))
+        .map(|expr| Constraint { expr })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::RewriteStrategy;
     use mm_rules::rule::standard_rules;
+    use mm_rules::{Rule, RuleApplication};
 
     #[test]
     fn test_beam_search_creation() {
@@ -255,4 +638,423 @@ mod tests {
 
         assert_eq!(solution.result.canonicalize(), Expr::int(5));
     }
+
+    #[test]
+    fn test_step_canonical_after() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let expr = parser.parse("sin(x)^2 + cos(x)^2").unwrap();
+        let padded = parser.parse("x * 1 + 0").unwrap();
+        let x = Expr::Var(symbols.intern("x"));
+
+        // The identity lands on 1, which is also its canonical form
+        let solution = searcher.simplify(expr);
+        let last = solution.steps.last().expect("the identity takes a step");
+        assert_eq!(last.canonical_after(), Expr::int(1));
+        assert_eq!(solution.result, Expr::int(1));
+
+        // A raw `after` is reported in the form search compares states in
+        let step = Step {
+            before: padded.clone(),
+            after: padded,
+            rule_id: mm_rules::RuleId(0),
+            rule_name: "constant_fold",
+            justification: String::new(),
+            condition: None,
+        };
+        assert_eq!(step.canonical_after(), x);
+        assert_eq!(
+            step.canonical_after(),
+            CanonCache::new().canonicalize(&step.after)
+        );
+    }
+
+    #[test]
+    fn test_steps_record_division_conditions() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let expr = parser.parse("diff(ln(x), x)").unwrap();
+        let x_nonzero = Constraint::nonzero(parser.parse("x").unwrap()).expr;
+
+        // d/dx ln(x) = 1/x introduces a denominator, so the step needs x ≠ 0
+        let solution = searcher
+            .search(expr, |e| !matches!(e, Expr::Derivative { .. }))
+            .unwrap();
+        assert!(solution
+            .steps
+            .iter()
+            .any(|step| step.condition.as_ref().map(|c| &c.expr) == Some(&x_nonzero)));
+    }
+
+    #[test]
+    fn test_canon_cache_saves_work() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("(x + 1)^2 - (x - 1)^2")
+            .unwrap();
+
+        // Never reaching the goal runs the search to its full depth
+        let (_, stats) = searcher.search_tracked(expr, |_| false);
+        assert!(stats.canonicalizations > 0);
+        // Without the cache every hit would have been one more canonicalization
+        assert!(stats.canon_cache_hits > stats.canonicalizations);
+    }
+
+    #[test]
+    fn test_optimize_custom_objective() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let expr = parser.parse("x^2 + 3*x + 2").unwrap();
+        let factored = parser.parse("(x + 1)*(x + 2)").unwrap();
+
+        // Products beat sums; size only breaks ties
+        let prefer_factored = |e: &Expr| {
+            let sum = !matches!(e, Expr::Mul(..)) as u8 as f64;
+            sum + e.complexity() as f64 / 1000.0
+        };
+        let solution = searcher.optimize(expr.clone(), prefer_factored);
+        assert_eq!(solution.result, factored);
+        assert_eq!(solution.problem, expr);
+        assert!(!solution.steps.is_empty());
+        assert_ne!(searcher.simplify(expr.clone()).result, factored);
+
+        // Nothing reachable beats the start under a constant objective
+        assert_eq!(searcher.optimize(expr.clone(), |_| 0.0).result, expr);
+    }
+
+    #[test]
+    fn test_innermost_rewrites_converge_faster() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("(sin(x)^2 + cos(x)^2)^2")
+            .unwrap();
+        let search = |rewrite_strategy| {
+            let config = SearchConfig {
+                rewrite_strategy,
+                ..SearchConfig::default()
+            };
+            BeamSearch::with_config(standard_rules(), Verifier::new(), config)
+                .search_tracked(expr.clone(), |e| e.canonicalize() == Expr::int(1))
+        };
+
+        // The Pythagorean identity only matches inside the square
+        let (solution, innermost) = search(RewriteStrategy::Innermost);
+        let solution = solution.expect("innermost rewriting should reach 1");
+        assert_eq!(solution.steps[0].rule_name, "pythagorean_identity");
+        assert_eq!(solution.steps[0].before, expr);
+
+        // Outermost keeps expanding the square instead
+        let (_, outermost) = search(RewriteStrategy::Outermost);
+        assert!(innermost.nodes_expanded < outermost.nodes_expanded);
+
+        let (solution, _) = search(RewriteStrategy::All);
+        assert!(solution.is_some());
+        let (solution, _) = search(RewriteStrategy::Root);
+        assert!(solution.is_none());
+    }
+
+    #[test]
+    fn test_simplify_stops_early() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+
+        // 2 + 3 folds to 5 before any node is expanded
+        let expr = Expr::Add(Box::new(Expr::int(2)), Box::new(Expr::int(3)));
+        let (solution, stats) = searcher.simplify_tracked(expr);
+        assert_eq!(solution.result, Expr::int(5));
+        assert_eq!(solution.num_steps(), 0);
+        assert_eq!(stats.nodes_expanded, 0);
+        assert_eq!(stats.depth_reached, 0);
+
+        // cos(x + y) only expands into larger forms, so the start is returned unexpanded
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("cos(x + y)")
+            .unwrap();
+        let (solution, stats) = searcher.simplify_tracked(expr.clone());
+        assert!(stats.terminated_early);
+        assert_eq!(stats.nodes_expanded, 0);
+        assert_eq!(solution.result, expr);
+    }
+
+    #[test]
+    fn test_derivative_only_rules() {
+        let rules = standard_rules().category_subset(mm_rules::RuleCategory::Derivative);
+        assert!(!rules.is_empty());
+        assert!(rules
+            .all()
+            .iter()
+            .all(|r| r.category == mm_rules::RuleCategory::Derivative));
+
+        // d/dx(x^3) = 3x^2 using derivative rules alone
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("diff(x^3, x)")
+            .unwrap();
+        let x = symbols.intern("x");
+        let solution = BeamSearch::new(rules, Verifier::new())
+            .search(expr, |e| !matches!(e, Expr::Derivative { .. }))
+            .expect("derivative rules should evaluate d/dx(x^3)");
+
+        let mut env = mm_core::eval::Env::new();
+        env.insert(x, 2.0);
+        assert_eq!(solution.result.evaluate(&env), Some(12.0));
+    }
+
+    #[test]
+    fn test_search_is_deterministic() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("(x + 0) * 1 + sin(x)^2 + cos(x)^2")
+            .unwrap();
+
+        let run = || {
+            let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+            let solution = searcher.simplify(expr.clone());
+            format!("{:?}", solution.steps)
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_simplify_all_keeps_both_routes() {
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("x*y + x*y")
+            .unwrap();
+
+        // Collecting like terms gives 2xy, factoring gives x(y + y)
+        let solutions = searcher.simplify_all(expr);
+        let routes: Vec<_> = solutions.iter().map(|s| s.steps[0].rule_name).collect();
+        assert!(routes.contains(&"collect_like_terms"));
+        assert!(routes.contains(&"factor_common"));
+
+        let forms: HashSet<_> = solutions.iter().map(|s| s.result.clone()).collect();
+        assert_eq!(forms.len(), solutions.len());
+    }
+
+    #[test]
+    fn test_max_expr_size_bounds_search() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("(x+1)^3")
+            .unwrap();
+
+        // Largest form the search visits, with an unreachable goal
+        let largest = |config: SearchConfig| {
+            let searcher = BeamSearch::with_config(standard_rules(), Verifier::new(), config);
+            let largest = std::cell::Cell::new(0);
+            searcher.search(expr.clone(), |e| {
+                largest.set(largest.get().max(e.size()));
+                false
+            });
+            largest.get()
+        };
+
+        // Expanding the cube grows well past the cap
+        assert!(largest(SearchConfig::default()) > 12);
+
+        let capped = SearchConfig {
+            max_expr_size: Some(12),
+            ..SearchConfig::default()
+        };
+        assert!(largest(capped.clone()) <= 12);
+
+        let searcher = BeamSearch::with_config(standard_rules(), Verifier::new(), capped);
+        let solution = searcher.simplify(expr);
+        assert!(solution.result.size() <= 12);
+    }
+
+    #[test]
+    fn test_adaptive_beam_escapes_greedy_path() {
+        use mm_rules::{RuleCategory, RuleId};
+
+        // Two identity rewrites of any expression. The cheap one always wins
+        // a width-1 beam; the goal needs the costly one.
+        let identity = |id, name, apply| Rule {
+            id: RuleId(id),
+            name,
+            category: RuleCategory::Simplification,
+            description: name,
+            domains: &[],
+            requires: &[],
+            roots: &[],
+            is_applicable: |_, _| true,
+            apply,
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        };
+        let rules = || {
+            let mut rules = RuleSet::new();
+            rules.add(identity(1, "sinh_arcsinh", |e, _| {
+                vec![RuleApplication {
+                    result: Expr::Sinh(Box::new(Expr::Arcsinh(Box::new(e.clone())))),
+                    justification: "e = sinh(arcsinh(e))".to_string(),
+                    condition: None,
+                }]
+            }));
+            rules.add(identity(2, "arcsinh_sinh_twice", |e, _| {
+                let wrap = |e: Expr| Expr::Arcsinh(Box::new(Expr::Sinh(Box::new(e))));
+                vec![RuleApplication {
+                    result: wrap(wrap(e.clone())),
+                    justification: "e = arcsinh(sinh(arcsinh(sinh(e))))".to_string(),
+                    condition: None,
+                }]
+            }));
+            rules
+        };
+
+        let mut symbols = mm_core::SymbolTable::new();
+        let x = Expr::Var(symbols.intern("x"));
+        let goal = |e: &Expr| matches!(e, Expr::Arcsinh(_));
+        let run = |adaptive_beam| {
+            let config = SearchConfig {
+                beam_width: 1,
+                max_depth: 6,
+                adaptive_beam,
+                ..SearchConfig::default()
+            };
+            BeamSearch::with_config(rules(), Verifier::new(), config)
+                .search_tracked(x.clone(), goal)
+        };
+
+        let (solution, stats) = run(false);
+        assert!(solution.is_none());
+        assert_eq!(stats.max_width, 1);
+
+        let (solution, stats) = run(true);
+        let solution = solution.expect("a wider beam keeps the costly branch");
+        assert_eq!(
+            solution.steps.last().unwrap().rule_name,
+            "arcsinh_sinh_twice"
+        );
+        assert!(stats.max_width > 1);
+    }
+
+    #[test]
+    fn test_max_rule_fanout() {
+        use mm_rules::{RuleCategory, RuleId};
+
+        // x = sinh(arcsinh(x)), wrapped 1 to 30 times: one rule, 30 results
+        let rules = || {
+            let mut rules = RuleSet::new();
+            rules.add(Rule {
+                id: RuleId(1),
+                name: "sinh_arcsinh_tower",
+                category: RuleCategory::Simplification,
+                description: "e = sinh(arcsinh(e)), repeatedly",
+                domains: &[],
+                requires: &[],
+                roots: &[],
+                is_applicable: |_, _| true,
+                apply: |e, _| {
+                    let mut tower = e.clone();
+                    let mut apps: Vec<_> = (0..30)
+                        .map(|_| {
+                            tower = Expr::Sinh(Box::new(Expr::Arcsinh(Box::new(tower.clone()))));
+                            RuleApplication {
+                                result: tower.clone(),
+                                justification: "e = sinh(arcsinh(e))".to_string(),
+                                condition: None,
+                            }
+                        })
+                        .collect();
+                    // Tallest first, so the cap has to pick
+                    apps.reverse();
+                    apps
+                },
+                reversible: false,
+                cost: 1,
+                ..Default::default()
+            });
+            rules
+        };
+
+        let mut symbols = mm_core::SymbolTable::new();
+        let x = Expr::Var(symbols.intern("x"));
+        let searcher = |max_rule_fanout| {
+            let config = SearchConfig {
+                beam_width: 100,
+                max_depth: 1,
+                max_rule_fanout,
+                ..SearchConfig::default()
+            };
+            BeamSearch::with_config(rules(), Verifier::new(), config)
+        };
+        // Forms in the beam after one expansion of x
+        let successors = |max_rule_fanout| {
+            let seen = std::cell::RefCell::new(Vec::new());
+            searcher(max_rule_fanout).search(x.clone(), |e| {
+                seen.borrow_mut().push(e.clone());
+                false
+            });
+            seen.into_inner()
+                .into_iter()
+                .filter(|e| *e != x)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(successors(None).len(), 30);
+        let capped = successors(Some(3));
+        assert_eq!(capped.len(), 3);
+        // The cheapest results are the ones kept
+        assert!(capped.iter().all(|e| e.complexity() <= 1 + 3 * 2));
+
+        let goal = Expr::Sinh(Box::new(Expr::Arcsinh(Box::new(x.clone()))));
+        let solution = searcher(Some(3))
+            .search(x.clone(), |e| *e == goal)
+            .expect("the cap keeps the cheapest result");
+        assert_eq!(solution.steps.len(), 1);
+    }
+
+    #[test]
+    fn test_try_simplify_outcomes() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let searcher = BeamSearch::new(standard_rules(), Verifier::new());
+
+        // 2 + 3 → 5
+        let expr = Expr::Add(Box::new(Expr::int(2)), Box::new(Expr::int(3)));
+        match searcher.try_simplify(expr) {
+            SearchOutcome::Solved(solution) => assert_eq!(solution.result, Expr::int(5)),
+            other => panic!("Expected Solved, got {:?}", other),
+        }
+
+        // π: nothing to rewrite
+        assert!(matches!(
+            searcher.try_simplify(Expr::Pi),
+            SearchOutcome::NoApplicableRules
+        ));
+
+        // sin²x + cos²x needs at least one step
+        let expr = mm_core::parse::Parser::new(&mut symbols)
+            .parse("sin(x)^2 + cos(x)^2")
+            .unwrap();
+        let shallow = BeamSearch::with_config(
+            standard_rules(),
+            Verifier::new(),
+            SearchConfig {
+                max_depth: 0,
+                ..SearchConfig::default()
+            },
+        );
+        assert!(matches!(
+            shallow.try_simplify(expr.clone()),
+            SearchOutcome::ExhaustedDepth
+        ));
+
+        let hurried = BeamSearch::with_config(
+            standard_rules(),
+            Verifier::new(),
+            SearchConfig {
+                time_limit: Some(std::time::Duration::ZERO),
+                ..SearchConfig::default()
+            },
+        );
+        assert!(matches!(hurried.try_simplify(expr), SearchOutcome::Timeout));
+    }
 }
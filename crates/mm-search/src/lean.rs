@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Export of solutions as Lean 4 proof skeletons.
+//!
+//! Each step becomes one line of a `calc` chain, proved by a tactic picked
+//! from the rule name:
+//!
+//! ```text
+//! example (x : ℝ) :
+//!     (x + 0) * 1 = x := by
+//!   calc (x + 0) * 1
+//!       _ = x + 0 := by simp only [mul_one, one_mul] -- identity_mul_one
+//!       _ = x := by simp only [add_zero, zero_add] -- identity_add_zero
+//! ```
+//!
+//! Rules without a known Mathlib counterpart are left as `sorry` with a
+//! `TODO` naming the rule. Expressions are written in the parser's ASCII
+//! syntax, so function applications such as `sin(x)` may still need
+//! qualifying (`Real.sin x`) before Lean accepts them.
+
+use crate::Solution;
+use mm_core::{Expr, SymbolTable};
+
+/// Tactics for rules with a direct Mathlib counterpart.
+const TACTICS: &[(&str, &str)] = &[
+    ("const_fold", "norm_num"),
+    ("identity_add_zero", "simp only [add_zero, zero_add]"),
+    ("identity_mul_one", "simp only [mul_one, one_mul]"),
+    ("zero_mul", "simp only [zero_mul, mul_zero]"),
+    ("collect_like_terms", "ring"),
+    ("distribute", "ring"),
+    ("factor_common", "ring"),
+    ("difference_of_squares", "ring"),
+    ("perfect_square_sum", "ring"),
+    ("perfect_square_diff", "ring"),
+    ("binomial_expand", "ring"),
+    ("binomial_expand_diff", "ring"),
+    ("binomial_square_expand", "ring"),
+    ("binomial_cube_expand", "ring"),
+    ("power_of_one", "rw [pow_one]"),
+    ("power_of_zero", "rw [pow_zero]"),
+    ("sub_same", "rw [sub_self]"),
+    ("double_negative", "rw [neg_neg]"),
+    ("exp_zero", "rw [Real.exp_zero]"),
+    ("ln_exp", "rw [Real.log_exp]"),
+    ("log_one", "rw [Real.log_one]"),
+    ("pythagorean_identity", "rw [Real.sin_sq_add_cos_sq]"),
+    ("sin_double_angle", "rw [Real.sin_two_mul]"),
+    ("sin_zero", "rw [Real.sin_zero]"),
+    ("cos_zero", "rw [Real.cos_zero]"),
+    ("sin_neg", "rw [Real.sin_neg]"),
+    ("cos_neg", "rw [Real.cos_neg]"),
+];
+
+impl Solution {
+    /// Render this solution as a Lean 4 `example` proved by a `calc` chain.
+    ///
+    /// Best effort: steps whose rule has no known tactic are `sorry` with a
+    /// `TODO` comment, and variables are all declared real. See the
+    /// [module docs](crate::lean) for the layout.
+    pub fn to_lean_skeleton(&self, symbols: &SymbolTable) -> String {
+        let ascii = |e: &Expr| e.to_ascii(symbols);
+
+        let mut names: Vec<&str> = std::iter::once(&self.problem)
+            .chain(self.steps.iter().map(|s| &s.after))
+            .flat_map(|e| e.free_vars())
+            .filter_map(|v| symbols.resolve(v))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        let binders = if names.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} : ℝ)", names.join(" "))
+        };
+
+        let mut out = format!(
+            "example{} :\n    {} = {} := by\n",
+            binders,
+            ascii(&self.problem),
+            ascii(&self.result)
+        );
+        if self.steps.is_empty() {
+            out.push_str("  sorry -- TODO: no steps recorded\n");
+            return out;
+        }
+
+        out.push_str(&format!("  calc {}\n", ascii(&self.problem)));
+        for step in &self.steps {
+            let tactic = TACTICS
+                .iter()
+                .find(|(rule, _)| *rule == step.rule_name)
+                .map(|(_, tactic)| format!("{} -- {}", tactic, step.rule_name))
+                .unwrap_or_else(|| format!("sorry -- TODO: {}", step.rule_name));
+            out.push_str(&format!(
+                "      _ = {} := by {}\n",
+                ascii(&step.after),
+                tactic
+            ));
+        }
+        // The reported result is the canonical form of the last state
+        if self.steps.last().is_some_and(|s| s.after != self.result) {
+            out.push_str(&format!(
+                "      _ = {} := by sorry -- TODO: canonical form\n",
+                ascii(&self.result)
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Step;
+    use mm_core::parse::Parser;
+    use mm_rules::RuleId;
+
+    fn step(before: &Expr, after: &Expr, rule_name: &'static str) -> Step {
+        Step {
+            before: before.clone(),
+            after: after.clone(),
+            rule_id: RuleId(0),
+            rule_name,
+            justification: String::new(),
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn test_lean_skeleton() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let problem = parser.parse("(x + 0) * 1").unwrap();
+        let middle = parser.parse("x + 0").unwrap();
+        let result = parser.parse("x").unwrap();
+        let mut solution = Solution {
+            problem: problem.clone(),
+            result: result.clone(),
+            steps: vec![
+                step(&problem, &middle, "identity_mul_one"),
+                step(&middle, &result, "identity_add_zero"),
+            ],
+            verified: true,
+        };
+
+        let lean = solution.to_lean_skeleton(&symbols);
+        assert!(lean.starts_with("example (x : ℝ) :\n    (x + 0) * 1 = x := by\n"));
+        assert!(lean.contains("  calc (x + 0) * 1\n"));
+        assert!(lean.contains("_ = x + 0 := by simp only [mul_one, one_mul] -- identity_mul_one"));
+        assert!(lean.contains("_ = x := by simp only [add_zero, zero_add] -- identity_add_zero"));
+        assert!(!lean.contains("sorry"));
+
+        // Unknown rules are left for the user
+        solution.steps[1].rule_name = "mystery_rule";
+        let lean = solution.to_lean_skeleton(&symbols);
+        assert!(lean.contains("_ = x := by sorry -- TODO: mystery_rule"));
+    }
+}
@@ -19,10 +19,15 @@ pub mod beam;
 pub mod boink_mcts;
 pub mod bridge;
 pub mod deep_mcts;
+pub mod lean;
 pub mod mcts;
+mod rewrite;
 
-use mm_core::Expr;
-use mm_rules::RuleId;
+use mm_core::parse::Parser;
+use mm_core::{Constraint, Expr, SymbolTable};
+use mm_rules::{RuleContext, RuleId, RuleSet};
+use mm_synth::SolutionStep;
+use std::time::Duration;
 
 /// A step in a solution path.
 #[derive(Debug, Clone)]
@@ -37,6 +42,69 @@ pub struct Step {
     pub rule_name: &'static str,
     /// Justification for this step.
     pub justification: String,
+    /// Side condition the rewrite relies on, if any.
+    pub condition: Option<Constraint>,
+}
+
+impl Step {
+    /// Canonical form of [`after`](Self::after).
+    ///
+    /// This is the form search uses to recognise states it has already
+    /// visited, so two steps with equal canonical forms lead to the same
+    /// search state.
+    pub fn canonical_after(&self) -> Expr {
+        self.after.canonicalize()
+    }
+
+    /// Whether the step only rearranges the expression, i.e. both sides
+    /// have the same canonical form (`x · 1 → x`, `2 + 3 → 5`).
+    pub fn is_bookkeeping(&self) -> bool {
+        self.before.canonicalize() == self.canonical_after()
+    }
+
+    /// Write as a synthetic-solution step, naming variables from `symbols`.
+    ///
+    /// The expressions are written in the parser's syntax, as
+    /// [`verify_dataset`](mm_verifier::dataset::verify_dataset) reads them:
+    /// `action` is the expression before, `result` the expression after and
+    /// `technique` the rule name.
+    pub fn to_solution_step(&self, symbols: &SymbolTable) -> SolutionStep {
+        SolutionStep {
+            action: self.before.to_ascii(symbols),
+            result: self.after.to_ascii(symbols),
+            technique: self.rule_name.to_string(),
+            justification: self.justification.clone(),
+        }
+    }
+
+    /// Read back a step written by [`to_solution_step`](Self::to_solution_step).
+    ///
+    /// The rule is looked up by name in `rules`, and its side condition
+    /// recovered by applying it again. Returns `None` if either expression
+    /// does not parse or no rule has the step's technique as its name.
+    pub fn from_solution_step(
+        step: &SolutionStep,
+        rules: &RuleSet,
+        symbols: &mut SymbolTable,
+    ) -> Option<Step> {
+        let rule = rules.all().iter().find(|r| r.name == step.technique)?;
+        let mut parser = Parser::new(symbols);
+        let before = parser.parse(&step.action).ok()?;
+        let after = parser.parse(&step.result).ok()?;
+        let condition = rule
+            .apply(&before, &RuleContext::default())
+            .into_iter()
+            .find(|app| app.result == after)
+            .and_then(|app| app.condition);
+        Some(Step {
+            before,
+            after,
+            rule_id: rule.id,
+            rule_name: rule.name,
+            justification: step.justification.clone(),
+            condition,
+        })
+    }
 }
 
 /// A complete solution.
@@ -64,6 +132,19 @@ impl Solution {
     }
 }
 
+/// How a search attempt ended.
+#[derive(Debug, Clone)]
+pub enum SearchOutcome {
+    /// A solution was found.
+    Solved(Solution),
+    /// The search ran out of depth, or of unvisited candidates, first.
+    ExhaustedDepth,
+    /// The configured time limit ran out first.
+    Timeout,
+    /// No rule applies to the input at all.
+    NoApplicableRules,
+}
+
 /// A predicate for checking if a goal has been reached.
 pub trait GoalPredicate: Fn(&Expr) -> bool {}
 impl<F: Fn(&Expr) -> bool> GoalPredicate for F {}
@@ -79,6 +160,33 @@ pub struct SearchConfig {
     pub mcts_iterations: usize,
     /// Exploration weight for UCB.
     pub exploration_weight: f64,
+    /// Maximum number of distinct forms returned by multi-solution search.
+    pub max_solutions: usize,
+    /// Candidates whose [`Expr::size`] exceeds this are pruned.
+    pub max_expr_size: Option<usize>,
+    /// Widen the beam while the search stalls.
+    ///
+    /// After `stall_depths` levels without the best score improving, beam
+    /// search doubles its width, up to `max_beam_width`. The width drops back
+    /// to `beam_width` once the best score improves again.
+    pub adaptive_beam: bool,
+    /// Levels without progress before the beam is widened.
+    pub stall_depths: usize,
+    /// Upper bound on the widened beam.
+    pub max_beam_width: usize,
+    /// Wall-clock budget for a beam search run.
+    pub time_limit: Option<Duration>,
+    /// Which subexpressions beam search rewrites.
+    pub rewrite_strategy: RewriteStrategy,
+    /// Most results beam search takes from one rule at one position.
+    ///
+    /// Rules such as AC pattern matches can return dozens of results; past
+    /// the cap only the lowest-scoring are kept. `None` takes them all.
+    pub max_rule_fanout: Option<usize>,
+    /// Assume variables are generic, so steps carry no side conditions.
+    ///
+    /// See [`RuleContext::assume_generic`].
+    pub assume_generic: bool,
 }
 
 impl Default for SearchConfig {
@@ -88,11 +196,94 @@ impl Default for SearchConfig {
             beam_width: 10,
             mcts_iterations: 1000,
             exploration_weight: 1.41,
+            max_solutions: 8,
+            max_expr_size: None,
+            adaptive_beam: false,
+            stall_depths: 2,
+            max_beam_width: 80,
+            time_limit: None,
+            rewrite_strategy: RewriteStrategy::default(),
+            max_rule_fanout: None,
+            assume_generic: false,
         }
     }
 }
 
-pub use beam::BeamSearch;
+/// Where in an expression a search looks for rule applications.
+///
+/// ```text
+/// (x·1 + 0)²   Root       → rules matching the whole square
+///              Outermost  → the square, or failing that its operands
+///              Innermost  → x·1, before its parents
+///              All        → every subexpression
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RewriteStrategy {
+    /// Rewrite the whole expression only.
+    #[default]
+    Root,
+    /// Rewrite the outermost subexpressions some rule applies to.
+    Outermost,
+    /// Rewrite the innermost subexpressions some rule applies to, so
+    /// operands are normalized before their parents.
+    Innermost,
+    /// Rewrite any subexpression some rule applies to.
+    All,
+}
+
+pub use beam::{BeamSearch, BeamStats};
 pub use boink_mcts::{BoinkMCTS, BoinkStats};
 pub use deep_mcts::{DeepMCTS, DeepMCTSConfig, DeepNode, SearchStats};
 pub use mcts::{MCTSConfig, MCTSNode, NeuralMCTS, MCTS};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mm_rules::rule::standard_rules;
+
+    #[test]
+    fn test_solution_step_round_trip() {
+        let rules = standard_rules();
+        let mut symbols = SymbolTable::new();
+        let before = Parser::new(&mut symbols).parse("x^3 / x^2").unwrap();
+        let rule = rules
+            .all()
+            .iter()
+            .find(|r| r.name == "power_subtract")
+            .unwrap();
+        let app = rule.apply(&before, &RuleContext::default()).remove(0);
+        let step = Step {
+            before,
+            after: app.result,
+            rule_id: rule.id,
+            rule_name: rule.name,
+            justification: app.justification,
+            condition: app.condition,
+        };
+
+        let written = step.to_solution_step(&symbols);
+        assert_eq!(written.action, "x^3 / x^2");
+        assert_eq!(written.result, "x");
+        assert_eq!(written.technique, "power_subtract");
+        assert_eq!(written.justification, step.justification);
+
+        let read = Step::from_solution_step(&written, &rules, &mut symbols).unwrap();
+        assert_eq!(read.before, step.before);
+        assert_eq!(read.after, step.after);
+        assert_eq!(read.rule_id, step.rule_id);
+        assert_eq!(read.rule_name, step.rule_name);
+        assert_eq!(read.justification, step.justification);
+        assert!(step.condition.is_some());
+        assert_eq!(
+            read.condition.map(|c| c.expr),
+            step.condition.map(|c| c.expr)
+        );
+
+        // A technique no rule is named after has no search counterpart
+        let unknown = SolutionStep {
+            technique: "Assume f is linear".to_string(),
+            ..written
+        };
+        assert!(Step::from_solution_step(&unknown, &rules, &mut symbols).is_none());
+    }
+}
@@ -11,12 +11,14 @@
 //! - Value network evaluates leaf nodes
 //! - UCB formula balances exploration/exploitation
 
-use crate::{SearchConfig, Solution, Step};
+use crate::rewrite::Rewrite;
+use crate::{RewriteStrategy, SearchConfig, Solution, Step};
 use mm_brain::PolicyNetwork;
 use mm_core::{Expr, Rational};
 use mm_rules::{RuleCategory, RuleContext, RuleId, RuleSet};
 use mm_verifier::Verifier;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Compute GCD using Euclidean algorithm.
 fn gcd(mut a: i64, mut b: i64) -> i64 {
@@ -114,6 +116,9 @@ pub struct NeuralMCTS {
     verifier: Verifier,
     policy: PolicyNetwork,
     config: MCTSConfig,
+    time_limit: Option<Duration>,
+    context: RuleContext,
+    rewrite_strategy: RewriteStrategy,
 }
 
 /// MCTS configuration.
@@ -149,6 +154,9 @@ impl NeuralMCTS {
             verifier,
             policy,
             config: MCTSConfig::default(),
+            time_limit: None,
+            context: RuleContext::default(),
+            rewrite_strategy: RewriteStrategy::default(),
         }
     }
 
@@ -160,15 +168,54 @@ impl NeuralMCTS {
             verifier,
             policy,
             config,
+            time_limit: None,
+            context: RuleContext::default(),
+            rewrite_strategy: RewriteStrategy::default(),
         }
     }
 
+    /// Stop searching once `limit` has passed.
+    ///
+    /// [`search`](Self::search) runs no further simulations past the limit,
+    /// and [`simplify`](Self::simplify) starts no further iterations.
+    pub fn with_time_limit(mut self, limit: Option<Duration>) -> Self {
+        self.time_limit = limit;
+        self
+    }
+
+    /// Assume variables are generic, so rules record no side conditions.
+    ///
+    /// See [`RuleContext::assume_generic`].
+    pub fn with_assume_generic(mut self, enabled: bool) -> Self {
+        self.context.assume_generic = enabled;
+        self
+    }
+
+    /// Expand nodes by rewriting the subexpressions `strategy` picks.
+    ///
+    /// See [`RewriteStrategy`]; the default rewrites the whole expression only.
+    pub fn with_rewrite_strategy(mut self, strategy: RewriteStrategy) -> Self {
+        self.rewrite_strategy = strategy;
+        self
+    }
+
+    /// Whether the time limit has run out for a run begun at `started`.
+    fn out_of_time(&self, started: Instant) -> bool {
+        self.time_limit
+            .is_some_and(|limit| started.elapsed() >= limit)
+    }
+
     /// Set a custom policy network (e.g., trained).
     pub fn with_policy(mut self, policy: PolicyNetwork) -> Self {
         self.policy = policy;
         self
     }
 
+    /// The policy network guiding the search.
+    pub fn policy(&self) -> &PolicyNetwork {
+        &self.policy
+    }
+
     /// Search for a solution using neural MCTS.
     pub fn search<F>(&self, start: Expr, goal: F) -> Option<Solution>
     where
@@ -188,7 +235,11 @@ impl NeuralMCTS {
         let mut root = MCTSNode::new(start.clone(), 1.0);
 
         // Run MCTS simulations
+        let started = Instant::now();
         for _ in 0..self.config.simulations {
+            if self.out_of_time(started) {
+                break;
+            }
             self.simulate(&mut root, &goal, 0);
         }
 
@@ -247,7 +298,7 @@ impl NeuralMCTS {
     /// Expand a node by adding children for all valid actions.
     /// Uses BOINK guardrail to filter rules by domain/features before expansion.
     fn expand(&self, node: &mut MCTSNode) {
-        let ctx = RuleContext::default();
+        let ctx = self.context.clone();
 
         // BOINK: Enhanced domain analysis (detects all Expr types)
         let profile = mm_boink::analyze(&node.state);
@@ -261,21 +312,30 @@ impl NeuralMCTS {
             .forward(&node.state)
             .unwrap_or_else(|_| vec![1.0 / self.rules.len() as f32; self.rules.len()]);
 
-        // Expand only using guardrail-filtered rules
-        for rule in valid_rules {
-            let applications = rule.apply(&node.state, &ctx);
-
-            for app in applications {
-                // Verify the transformation
-                let verify_result = self
-                    .verifier
-                    .verify_step(&node.state, &app.result, rule, &ctx);
-
-                if verify_result.is_valid() {
-                    let prior = priors.get(rule.id.0 as usize).copied().unwrap_or(0.01);
-                    let child = MCTSNode::with_rule(app.result, prior as f64, rule.id, rule.name);
-                    node.children.insert(rule.id.0, Box::new(child));
-                }
+        // Expand only using guardrail-filtered rules, at the positions the
+        // rewrite strategy picks
+        let root = |expr: &Expr| {
+            valid_rules
+                .iter()
+                .flat_map(|&rule| {
+                    rule.apply(expr, &ctx)
+                        .into_iter()
+                        .map(move |app| Rewrite::new(rule, app))
+                })
+                .collect()
+        };
+        let rewrites = self
+            .rewrite_strategy
+            .rewrites(&node.state, &self.verifier, &ctx, &root);
+
+        for rewrite in rewrites {
+            // Verify the transformation
+            if rewrite.is_valid(&node.state, &self.verifier, &ctx) {
+                let rule = rewrite.rule;
+                let prior = priors.get(rule.id.0 as usize).copied().unwrap_or(0.01);
+                let child =
+                    MCTSNode::with_rule(rewrite.app.result, prior as f64, rule.id, rule.name);
+                node.children.insert(rule.id.0, Box::new(child));
             }
         }
     }
@@ -326,6 +386,7 @@ impl NeuralMCTS {
                     rule_id,
                     rule_name,
                     justification: format!("Applied {} (visits: {})", rule_name, best_child.visits),
+                    condition: None,
                 });
             }
 
@@ -359,12 +420,16 @@ impl NeuralMCTS {
 
     /// Simplify an expression using neural MCTS with multi-step chaining.
     /// Continues applying rules until no more simplifications are possible.
+    ///
+    /// Every step, including the clean-up passes at the end, is recorded and
+    /// checked by the verifier; the solution is verified only if all are.
     pub fn simplify(&self, expr: Expr) -> Solution {
         const MAX_ITERATIONS: usize = 50;
+        let started = Instant::now();
 
         let mut current = expr.clone();
         let mut all_steps: Vec<Step> = Vec::new();
-        let ctx = RuleContext::default();
+        let ctx = self.context.clone();
 
         // Track seen expressions to prevent infinite loops (e.g., distribute <-> factor_common)
         let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -374,6 +439,10 @@ impl NeuralMCTS {
         let profile = mm_boink::analyze(&expr);
 
         for _iteration in 0..MAX_ITERATIONS {
+            if self.out_of_time(started) {
+                break;
+            }
+
             // BOINK: Filter rules by domain - prevents wrong-domain matches!
             let applicable = mm_boink::filter_rules(self.rules.all(), &profile);
             if applicable.is_empty() {
@@ -383,7 +452,7 @@ impl NeuralMCTS {
             // Define goal for this iteration: any simplification
             let current_complexity = current.complexity();
             let goal = |e: &Expr| {
-                let ctx = RuleContext::default();
+                let ctx = self.context.clone();
                 let applicable = self.rules.applicable(e, &ctx);
 
                 // For equations: if LHS is just a variable, we're done!
@@ -418,6 +487,7 @@ impl NeuralMCTS {
                                 rule_id: rule.id,
                                 rule_name: rule.name,
                                 justification: app.justification.clone(),
+                                condition: app.condition.clone(),
                             });
                             current = app.result.clone();
                             found_rule = true;
@@ -464,6 +534,7 @@ impl NeuralMCTS {
                             rule_id: rule.id,
                             rule_name: rule.name,
                             justification: app.justification.clone(),
+                            condition: app.condition.clone(),
                         });
                         current = app.result.clone();
                         found_rule = true;
@@ -478,10 +549,20 @@ impl NeuralMCTS {
 
         // Recursively simplify sub-expressions (for nested derivatives, etc.)
         let mut simplified = self.simplify_subexpressions(&current);
+        if simplified != current {
+            all_steps.push(Step {
+                before: current.clone(),
+                after: simplified.clone(),
+                rule_id: RuleId(0),
+                rule_name: "simplify_subexpressions",
+                justification: "Simplified each operand".to_string(),
+                condition: None,
+            });
+        }
 
         // Keep applying SIMPLIFICATION rules until stable (handles chained patterns like x^2 * x^3 * x^4)
         // Skip expansion rules (distribute) to avoid undoing collect_like_terms
-        let ctx = RuleContext::default();
+        let ctx = self.context.clone();
         for _ in 0..10 {
             let applicable = self.rules.applicable(&simplified, &ctx);
             if applicable.is_empty() {
@@ -494,10 +575,18 @@ impl NeuralMCTS {
                 .collect();
 
             if let Some(rule) = simplification_rules.first() {
-                let results = (rule.apply)(&simplified, &ctx);
+                let results = rule.apply(&simplified, &ctx);
                 if let Some(app) = results.first() {
                     // Only apply if result is simpler or same complexity
                     if app.result.complexity() <= simplified.complexity() {
+                        all_steps.push(Step {
+                            before: simplified.clone(),
+                            after: app.result.clone(),
+                            rule_id: rule.id,
+                            rule_name: rule.name,
+                            justification: app.justification.clone(),
+                            condition: app.condition.clone(),
+                        });
                         simplified = app.result.clone();
                         continue;
                     }
@@ -508,13 +597,36 @@ impl NeuralMCTS {
 
         // Apply constant folding to final result if possible
         let final_result = self.try_const_fold(&simplified);
+        if final_result != simplified {
+            all_steps.push(Step {
+                before: simplified,
+                after: final_result.clone(),
+                rule_id: RuleId(0),
+                rule_name: "constant_fold",
+                justification: "Folded constant arithmetic".to_string(),
+                condition: None,
+            });
+        }
 
+        let verified = all_steps.iter().all(|step| self.verify(step));
         Solution {
             problem: expr,
             result: final_result,
             steps: all_steps,
-            verified: true,
+            verified,
+        }
+    }
+
+    /// Check one step with the verifier, by its rule where it has one.
+    fn verify(&self, step: &Step) -> bool {
+        let ctx = self.context.clone();
+        match self.rules.get(step.rule_id) {
+            Some(rule) => self
+                .verifier
+                .verify_step(&step.before, &step.after, rule, &ctx),
+            None => self.verifier.verify_rewrite(&step.before, &step.after),
         }
+        .is_valid()
     }
 
     /// Progressive solve: pattern match → decompose → solve easiest first → recombine.
@@ -533,6 +645,7 @@ impl NeuralMCTS {
                     rule_id: RuleId(0),
                     rule_name: "pattern_match",
                     justification: "Matched known integral pattern".to_string(),
+                    condition: None,
                 }],
                 verified: true,
             };
@@ -573,6 +686,7 @@ impl NeuralMCTS {
                                 rule_id: RuleId(0),
                                 rule_name: "pattern_match",
                                 justification: format!("Pattern matched: {:?} -> {:?}", term, result),
+                                condition: None,
                             });
                             partial_results.push(result);
                         } else {
@@ -606,7 +720,7 @@ impl NeuralMCTS {
 
     /// Recursively simplify sub-expressions by applying rules to inner parts.
     fn simplify_subexpressions(&self, expr: &Expr) -> Expr {
-        let ctx = RuleContext::default();
+        let ctx = self.context.clone();
 
         match expr {
             // For derivatives inside Add/Sub/Mul, simplify each side
@@ -631,7 +745,7 @@ impl NeuralMCTS {
 
     /// Apply first applicable rule to an expression (single step).
     fn simplify_single_step(&self, expr: &Expr) -> Expr {
-        let ctx = RuleContext::default();
+        let ctx = self.context.clone();
 
         // First recursively handle sub-expressions
         let processed = match expr {
@@ -657,7 +771,7 @@ impl NeuralMCTS {
                 // Apply derivative rules if possible
                 let applicable = self.rules.applicable(expr, &ctx);
                 if let Some(rule) = applicable.first() {
-                    let results = (rule.apply)(expr, &ctx);
+                    let results = rule.apply(expr, &ctx);
                     if let Some(app) = results.first() {
                         return self.simplify_single_step(&app.result);
                     }
@@ -670,7 +784,7 @@ impl NeuralMCTS {
         // Now try to simplify the processed expression
         let applicable = self.rules.applicable(&processed, &ctx);
         if let Some(rule) = applicable.first() {
-            let results = (rule.apply)(&processed, &ctx);
+            let results = rule.apply(&processed, &ctx);
             if let Some(app) = results.first() {
                 return app.result.clone();
             }
@@ -989,4 +1103,44 @@ mod tests {
         // Should simplify to 5
         assert_eq!(result.result.canonicalize(), Expr::int(5));
     }
+
+    #[test]
+    fn test_neural_mcts_verifies_steps() {
+        let mcts = NeuralMCTS::new(standard_rules(), Verifier::new());
+        let x = Expr::Var(mm_core::SymbolTable::new().intern("x"));
+        let step = |after: Expr| Step {
+            before: Expr::Add(Box::new(x.clone()), Box::new(Expr::int(0))),
+            after,
+            rule_id: RuleId(0),
+            rule_name: "constant_fold",
+            justification: String::new(),
+            condition: None,
+        };
+
+        assert!(mcts.verify(&step(x.clone())));
+        assert!(!mcts.verify(&step(Expr::Add(
+            Box::new(x.clone()),
+            Box::new(Expr::int(1))
+        ))));
+    }
+
+    #[test]
+    fn test_neural_mcts_rewrites_subexpressions() {
+        let mut symbols = mm_core::SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let expr = parser.parse("ln(sin(x)^2 + cos(x)^2)").unwrap();
+        let search = |strategy| {
+            let config = MCTSConfig {
+                simulations: 20,
+                ..Default::default()
+            };
+            NeuralMCTS::with_config(standard_rules(), Verifier::new(), config)
+                .with_rewrite_strategy(strategy)
+                .search(expr.clone(), |e| *e == Expr::int(0))
+        };
+
+        // The sum of squares only simplifies inside the logarithm
+        assert!(search(RewriteStrategy::Innermost).is_some());
+        assert!(search(RewriteStrategy::Root).is_none());
+    }
 }
@@ -0,0 +1,141 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Rule applications at subexpressions, shared by the search drivers.
+
+use crate::RewriteStrategy;
+use mm_core::Expr;
+use mm_rules::{Rule, RuleApplication, RuleContext};
+use mm_verifier::Verifier;
+
+/// A rule application, with its result rebuilt into the whole expression.
+pub(crate) struct Rewrite<'a> {
+    pub(crate) rule: &'a Rule,
+    pub(crate) app: RuleApplication,
+    /// The subexpression the rule matched and what it became, when that is
+    /// not the whole expression.
+    pub(crate) redex: Option<(Expr, Expr)>,
+    /// Whether the verifier already accepted the step at the redex.
+    pub(crate) verified: bool,
+}
+
+impl<'a> Rewrite<'a> {
+    /// An application of `rule` to the whole expression.
+    pub(crate) fn new(rule: &'a Rule, app: RuleApplication) -> Self {
+        Self {
+            rule,
+            app,
+            redex: None,
+            verified: false,
+        }
+    }
+
+    /// The step the rule made where it matched, given the whole
+    /// expression `expr` it was applied to.
+    pub(crate) fn step<'e>(&'e self, expr: &'e Expr) -> (&'e Expr, &'e Expr) {
+        match &self.redex {
+            Some((before, after)) => (before, after),
+            None => (expr, &self.app.result),
+        }
+    }
+
+    /// Whether the verifier accepts the step where the rule matched,
+    /// checking it only if that was not done while collecting it.
+    pub(crate) fn is_valid(&self, expr: &Expr, verifier: &Verifier, ctx: &RuleContext) -> bool {
+        let (before, after) = self.step(expr);
+        self.verified
+            || verifier
+                .verify_step(before, after, self.rule, ctx)
+                .is_valid()
+    }
+}
+
+impl RewriteStrategy {
+    /// Rule applications at the positions of `expr` this strategy picks,
+    /// where `root` gives the applications at the root of a subexpression.
+    pub(crate) fn rewrites<'a>(
+        self,
+        expr: &Expr,
+        verifier: &Verifier,
+        ctx: &RuleContext,
+        root: &dyn Fn(&Expr) -> Vec<Rewrite<'a>>,
+    ) -> Vec<Rewrite<'a>> {
+        // Only rewrites that change the subexpression and verify make a
+        // position rewritable
+        let here = || {
+            let mut rewrites = root(expr);
+            rewrites.retain(|r| {
+                r.app.result != *expr
+                    && verifier
+                        .verify_step(expr, &r.app.result, r.rule, ctx)
+                        .is_valid()
+            });
+            for rewrite in &mut rewrites {
+                rewrite.verified = true;
+            }
+            rewrites
+        };
+        let below = || {
+            let mut rewrites = Vec::new();
+            if !rewrites_inside(expr) {
+                return rewrites;
+            }
+            for (i, child) in expr.children().into_iter().enumerate() {
+                for mut rewrite in self.rewrites(child, verifier, ctx, root) {
+                    let mut rebuilt = expr.clone();
+                    *rebuilt.children_mut().swap_remove(i) = rewrite.app.result.clone();
+                    if rewrite.redex.is_none() {
+                        rewrite.redex = Some((child.clone(), rewrite.app.result));
+                    }
+                    rewrite.app.result = rebuilt;
+                    rewrites.push(rewrite);
+                }
+            }
+            rewrites
+        };
+
+        match self {
+            RewriteStrategy::Root => root(expr),
+            RewriteStrategy::Outermost => {
+                let rewrites = here();
+                if rewrites.is_empty() {
+                    below()
+                } else {
+                    rewrites
+                }
+            }
+            RewriteStrategy::Innermost => {
+                let rewrites = below();
+                if rewrites.is_empty() {
+                    here()
+                } else {
+                    rewrites
+                }
+            }
+            RewriteStrategy::All => {
+                let mut rewrites = here();
+                rewrites.extend(below());
+                rewrites
+            }
+        }
+    }
+}
+
+/// Whether rules may rewrite the children of `expr` in place.
+///
+/// Binders and piecewise branches are left alone: rewriting their parts
+/// can change what a bound variable or condition refers to.
+fn rewrites_inside(expr: &Expr) -> bool {
+    !matches!(
+        expr,
+        Expr::Summation { .. }
+            | Expr::BigProduct { .. }
+            | Expr::Limit { .. }
+            | Expr::ForAll { .. }
+            | Expr::Exists { .. }
+            | Expr::Piecewise(_)
+    )
+}
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Shared expression battery for benchmarks.
+//!
+//! Every bench target pulls its inputs from here so results stay comparable
+//! across targets and over time. Add new entries at the end; renaming or
+//! reordering makes old criterion baselines meaningless.
+
+// Each bench target compiles its own copy and uses only part of it.
+#![allow(dead_code)]
+
+use mm_core::{parse::Parser, Expr, Symbol, SymbolTable};
+use std::collections::HashMap;
+
+/// Named expressions of increasing size, in `x` and `y`.
+pub const BATTERY: &[(&str, &str)] = &[
+    ("linear", "2*x + 3"),
+    ("quadratic", "x^2 + 2*x + 1"),
+    ("trig", "sin(x)^2 + cos(x)^2"),
+    ("rational", "(x^2 - 1)/(x + 1) + 1/(1/y)"),
+    ("mixed", "exp(x) * ln(y + 2) - sqrt(x^2 + y^2) / (1 + x*y)"),
+    (
+        "large",
+        "(x + 1)^3 - (x - 1)^3 + 3*(x*y + y)^2 - sin(x + y)*cos(x - y) + (x^4 - y^4)/(x^2 + y^2)",
+    ),
+];
+
+/// Sample point used wherever a bench needs numeric values.
+pub const SAMPLE_POINT: (f64, f64) = (1.25, -0.75);
+
+/// The parsed battery together with the symbols it uses.
+pub struct Battery {
+    pub symbols: SymbolTable,
+    pub x: Symbol,
+    pub y: Symbol,
+    pub exprs: Vec<(&'static str, Expr)>,
+}
+
+impl Battery {
+    /// Parse every entry of [`BATTERY`].
+    pub fn load() -> Self {
+        let mut symbols = SymbolTable::new();
+        let exprs = BATTERY
+            .iter()
+            .map(|&(name, src)| {
+                let expr = Parser::new(&mut symbols)
+                    .parse(src)
+                    .unwrap_or_else(|e| panic!("battery entry {name} failed to parse: {e}"));
+                (name, expr)
+            })
+            .collect();
+        let x = symbols.intern("x");
+        let y = symbols.intern("y");
+        Self {
+            symbols,
+            x,
+            y,
+            exprs,
+        }
+    }
+
+    /// Environment binding `x` and `y` to [`SAMPLE_POINT`].
+    pub fn env(&self) -> HashMap<Symbol, f64> {
+        HashMap::from([(self.x, SAMPLE_POINT.0), (self.y, SAMPLE_POINT.1)])
+    }
+}
@@ -0,0 +1,81 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Benchmarks for core expression operations over the shared battery.
+//!
+//! Run with `cargo bench -p mm-solver --bench core_ops`, or
+//! `cargo bench -p mm-solver --bench core_ops -- --test` to just check that
+//! every benchmark runs once.
+
+mod common;
+
+use common::Battery;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mm_core::Expr;
+use mm_solver::LemmaSolver;
+
+fn canonicalize(c: &mut Criterion) {
+    let battery = Battery::load();
+    let mut group = c.benchmark_group("canonicalize");
+    for (name, expr) in &battery.exprs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), expr, |b, expr| {
+            b.iter(|| black_box(expr).canonicalize())
+        });
+    }
+    group.finish();
+}
+
+fn clone(c: &mut Criterion) {
+    let battery = Battery::load();
+    let mut group = c.benchmark_group("clone");
+    for (name, expr) in &battery.exprs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), expr, |b, expr| {
+            b.iter(|| black_box(expr).clone())
+        });
+    }
+    group.finish();
+}
+
+fn substitute(c: &mut Criterion) {
+    let battery = Battery::load();
+    // x → (y + 1), so the result still has free variables
+    let value = Expr::Add(Box::new(Expr::Var(battery.y)), Box::new(Expr::int(1)));
+    let mut group = c.benchmark_group("substitute");
+    for (name, expr) in &battery.exprs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), expr, |b, expr| {
+            b.iter(|| mm_verifier::substitute(black_box(expr), battery.x, &value))
+        });
+    }
+    group.finish();
+}
+
+fn evaluate(c: &mut Criterion) {
+    let battery = Battery::load();
+    let env = battery.env();
+    let mut group = c.benchmark_group("evaluate");
+    for (name, expr) in &battery.exprs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), expr, |b, expr| {
+            b.iter(|| black_box(expr).evaluate(&env))
+        });
+    }
+    group.finish();
+}
+
+fn simplify(c: &mut Criterion) {
+    let battery = Battery::load();
+    let solver = LemmaSolver::new();
+    let mut group = c.benchmark_group("simplify");
+    group.sample_size(10);
+    for (name, expr) in &battery.exprs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), expr, |b, expr| {
+            b.iter(|| solver.simplify_expr(black_box(expr).clone()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, canonicalize, clone, substitute, evaluate, simplify);
+criterion_main!(benches);
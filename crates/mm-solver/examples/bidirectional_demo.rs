@@ -39,7 +39,7 @@ fn main() {
 
     let goal = Expr::Gte(Box::new(lhs.clone()), Box::new(rhs.clone()));
 
-    println!("Goal: {:?}", goal);
+    println!("Goal: {}", goal.to_ascii(&symbols));
     println!("\nBACKWARD REASONING:\n");
 
     // Apply backward search
@@ -52,7 +52,7 @@ fn main() {
         println!("  Justification: {}", step.justification);
         println!("  New subgoals:");
         for (j, subgoal) in step.subgoals.iter().enumerate() {
-            println!("    {}. {:?}", j + 1, subgoal);
+            println!("    {}. {}", j + 1, subgoal.to_ascii(&symbols));
         }
         println!();
     }
@@ -88,7 +88,7 @@ fn main() {
         rhs: Box::new(rhs2),
     };
 
-    println!("Goal: {:?}\n", goal2);
+    println!("Goal: {}\n", goal2.to_ascii(&symbols));
     println!("BACKWARD REASONING:\n");
 
     let backward_steps2 = backward_search(&goal2);
@@ -312,14 +312,14 @@ fn test_q10_integration(symbols: &mut SymbolTable, mcts: &NeuralMCTS, passed: &m
     println!("   Step 1: Apply neural search to integrand");
     let solution = mcts.simplify(integrand.clone());
     println!("   Neural network steps: {}", solution.num_steps());
-    println!("   Neural network result: {:?}", solution.result);
+    println!("   Neural network result: {}", solution.result.to_ascii(symbols));
     
     if solution.num_steps() > 0 {
         println!("\n   Transformation rules the neural network applied:");
         for (i, step) in solution.steps.iter().take(5).enumerate() {
             println!("   {}. {}", i + 1, step.rule_name);
-            println!("       Before: {:?}", step.before);
-            println!("       After:  {:?}", step.after);
+            println!("       Before: {}", step.before.to_ascii(symbols));
+            println!("       After:  {}", step.after.to_ascii(symbols));
         }
     } else {
         println!("   ℹ️  Neural network found no applicable transformations");
@@ -440,7 +440,7 @@ fn test_q17_area(symbols: &mut SymbolTable, mcts: &NeuralMCTS, passed: &mut i32,
     
     let solution = mcts.simplify(integrand.clone());
     println!("   Neural network applied {} rules", solution.num_steps());
-    println!("   Neural network result: {:?}\n", solution.result);
+    println!("   Neural network result: {}\n", solution.result.to_ascii(symbols));
     
     if solution.num_steps() > 0 {
         println!("   Transformation rules the neural network applied:");
@@ -499,7 +499,7 @@ fn test_q21_differentiation(symbols: &mut SymbolTable, mcts: &NeuralMCTS, passed
     println!("   Step 1: Apply LEMMA neural search");
     let solution = mcts.simplify(expr.clone());
     println!("   Neural network applied {} transformation rules", solution.num_steps());
-    println!("   Neural network result: {:?}\n", solution.result);
+    println!("   Neural network result: {}\n", solution.result.to_ascii(symbols));
     
     if solution.num_steps() > 0 {
         println!("   What the neural network did:");
@@ -567,8 +567,8 @@ fn test_q22_trigonometry(symbols: &mut SymbolTable, mcts: &NeuralMCTS, passed: &
         println!("\n   🎯 Key transformation found:");
         if let Some(first_step) = solution.steps.first() {
             println!("   Rule: {} (LEMMA has built-in special angle rules!)", first_step.rule_name);
-            println!("   Before: {:?}", first_step.before);
-            println!("   After:  {:?}", first_step.after);
+            println!("   Before: {}", first_step.before.to_ascii(symbols));
+            println!("   After:  {}", first_step.after.to_ascii(symbols));
         }
         
         *passed += 1;
@@ -839,7 +839,7 @@ fn test_q28_integration_trig(symbols: &mut SymbolTable, mcts: &NeuralMCTS, passe
     println!("   Step 1: Apply LEMMA neural search");
     let solution = mcts.simplify(integrand.clone());
     println!("   Neural network applied {} transformation rules", solution.num_steps());
-    println!("   Neural network result: {:?}\n", solution.result);
+    println!("   Neural network result: {}\n", solution.result.to_ascii(symbols));
     
     if solution.num_steps() > 0 {
         println!("   Transformation rules applied by neural network:");
@@ -155,45 +155,5 @@ fn main() {
 }
 
 fn format_expr(expr: &Expr, symbols: &SymbolTable) -> String {
-    match expr {
-        Expr::Const(r) => {
-            if r.is_integer() {
-                format!("{}", r.numer())
-            } else {
-                format!("{}/{}", r.numer(), r.denom())
-            }
-        }
-        Expr::Var(s) => symbols.resolve(*s).unwrap_or("?").to_string(),
-        Expr::Add(a, b) => format!(
-            "({} + {})",
-            format_expr(a, symbols),
-            format_expr(b, symbols)
-        ),
-        Expr::Sub(a, b) => format!(
-            "({} - {})",
-            format_expr(a, symbols),
-            format_expr(b, symbols)
-        ),
-        Expr::Mul(a, b) => format!("{} * {}", format_expr(a, symbols), format_expr(b, symbols)),
-        Expr::Div(a, b) => format!("{} / {}", format_expr(a, symbols), format_expr(b, symbols)),
-        Expr::Pow(a, b) => format!("{}^{}", format_expr(a, symbols), format_expr(b, symbols)),
-        Expr::Neg(a) => format!("-{}", format_expr(a, symbols)),
-        Expr::Sin(a) => format!("sin({})", format_expr(a, symbols)),
-        Expr::Cos(a) => format!("cos({})", format_expr(a, symbols)),
-        Expr::Tan(a) => format!("tan({})", format_expr(a, symbols)),
-        Expr::Exp(a) => format!("e^{}", format_expr(a, symbols)),
-        Expr::Ln(a) => format!("ln({})", format_expr(a, symbols)),
-        Expr::Sqrt(a) => format!("sqrt({})", format_expr(a, symbols)),
-        Expr::Equation { lhs, rhs } => format!(
-            "{} = {}",
-            format_expr(lhs, symbols),
-            format_expr(rhs, symbols)
-        ),
-        Expr::Derivative { expr, var } => format!(
-            "d/d{}({})",
-            symbols.resolve(*var).unwrap_or("?"),
-            format_expr(expr, symbols)
-        ),
-        _ => format!("{:?}", expr),
-    }
+    expr.to_ascii(symbols)
 }
@@ -48,7 +48,7 @@ fn main() {
     // Build: x² + y² - 2xy
     let diff = Expr::Sub(Box::new(lhs.clone()), Box::new(rhs.clone()));
     println!("Step 1: Rewrite as x² + y² - 2xy ≥ 0");
-    println!("        Expression: {:?}\n", diff);
+    println!("        Expression: {}\n", diff.to_ascii(&symbols));
     // Try to factor it
     let ctx = RuleContext::default();
     let applicable = rules.applicable(&diff, &ctx);
@@ -64,7 +64,7 @@ fn main() {
             // Check if result is (x-y)²
             if is_perfect_square_diff(&result.result) {
                 println!("\n  ✅ FOUND: {} produces a perfect square!", rule.name);
-                println!("     Result: {:?}", result.result);
+                println!("     Result: {}", result.result.to_ascii(&symbols));
                 solution_found = true;
                 break;
             }
@@ -105,7 +105,7 @@ fn main() {
         if (rule.is_applicable)(&factored, &ctx) {
             let results = (rule.apply)(&factored, &ctx);
             for result in results.iter().take(1) {
-                println!("  {} → {:?}", rule.name, result.result);
+                println!("  {} → {}", rule.name, result.result.to_ascii(&symbols));
             }
         }
     }
@@ -131,7 +131,7 @@ fn main() {
     for rule in &applicable {
         let results = (rule.apply)(&a_plus_b_cubed, &ctx);
         if !results.is_empty() {
-            let result_str = format!("{:?}", results[0].result);
+            let result_str = results[0].result.to_ascii(&symbols);
             if result_str.len() > 80 {
                 println!("  {} → {}...", rule.name, &result_str[..80]);
             } else {
@@ -195,7 +195,7 @@ fn main() {
     if let Some(path) = solution {
         println!("Solution path:");
         for (i, step) in path.iter().enumerate() {
-            let step_str = format!("{:?}", step);
+            let step_str = step.to_ascii(&symbols);
             if step_str.len() > 60 {
                 println!("  Step {}: {}...", i + 1, &step_str[..60]);
             } else {
@@ -56,7 +56,7 @@ fn main() {
         )))),
     );
 
-    test(&mcts, "AM-GM", am_gm_diff, |_| true);
+    test(&mcts, &symbols, "AM-GM", am_gm_diff, |_| true);
 
     // IMO 2: Cauchy-Schwarz: (a² + b²)(c² + d²) ≥ (ac + bd)²
     // Show LHS - RHS = (ad - bc)² ≥ 0
@@ -85,7 +85,7 @@ fn main() {
     );
 
     let cauchy_schwarz = Expr::Sub(Box::new(lhs), Box::new(rhs));
-    test(&mcts, "Cauchy-Schwarz", cauchy_schwarz, |_| true);
+    test(&mcts, &symbols, "Cauchy-Schwarz", cauchy_schwarz, |_| true);
 
     // IMO 3: Nesbitt's Inequality (IMO 1961 Problem)
     // For positive a, b, c: a/(b+c) + b/(a+c) + c/(a+b) ≥ 3/2
@@ -113,7 +113,7 @@ fn main() {
         )),
         Box::new(Expr::Div(Box::new(Expr::int(3)), Box::new(Expr::int(2)))),
     );
-    test(&mcts, "Nesbitt", nesbitt, |_| true);
+    test(&mcts, &symbols, "Nesbitt", nesbitt, |_| true);
 
     println!("═══════════════════════════════════════════════════════════════");
     println!("       ALGEBRAIC IDENTITIES (IMO Algebraic Style)");
@@ -131,7 +131,7 @@ fn main() {
             Box::new(Expr::Pow(Box::new(Expr::Var(b)), Box::new(Expr::int(4)))),
         )),
     );
-    test(&mcts, "Sophie-Germain", sophie_germain, |_| true);
+    test(&mcts, &symbols, "Sophie-Germain", sophie_germain, |_| true);
 
     // IMO 5: x³ + y³ + z³ - 3xyz = (x+y+z)(x² + y² + z² - xy - yz - xz)
     // This is a key factorization for many IMO problems
@@ -155,7 +155,7 @@ fn main() {
             )),
         )),
     );
-    test(&mcts, "Three-Cubes", three_cubes, |_| true);
+    test(&mcts, &symbols, "Three-Cubes", three_cubes, |_| true);
 
     println!("═══════════════════════════════════════════════════════════════");
     println!("       NUMBER THEORY (IMO Style)");
@@ -174,6 +174,7 @@ fn main() {
     );
     test(
         &mcts,
+        &symbols,
         "Fermat-Little",
         fermat,
         |e| matches!(e, Expr::Const(r) if r.numer() == 1),
@@ -189,7 +190,7 @@ fn main() {
         Box::new(Expr::Factorial(Box::new(Expr::int(4)))),
         Box::new(Expr::int(5)),
     );
-    test(&mcts, "Wilson", wilson, |e| {
+    test(&mcts, &symbols, "Wilson", wilson, |e| {
         matches!(e, Expr::Const(r) if r.numer() == 4) // 4 ≡ -1 (mod 5)
     });
 
@@ -208,6 +209,7 @@ fn main() {
     );
     test(
         &mcts,
+        &symbols,
         "Gauss-Sum",
         gauss_sum,
         |e| matches!(e, Expr::Const(r) if r.numer() == 5050),
@@ -227,7 +229,7 @@ fn main() {
         Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
         Box::new(Expr::Pow(Box::new(Expr::Var(y)), Box::new(Expr::int(2)))),
     );
-    test(&mcts, "Newton-p2", newton_p2, |_| true);
+    test(&mcts, &symbols, "Newton-p2", newton_p2, |_| true);
 
     // IMO 10: Power sum p₃ = e₁³ - 3e₁e₂ + 3e₃
     // x³ + y³ + z³ expression
@@ -241,7 +243,7 @@ fn main() {
         )),
         Box::new(Expr::Pow(Box::new(Expr::Var(c)), Box::new(Expr::int(3)))),
     );
-    test(&mcts, "Power-Sum-p3", power_sum_3, |_| true);
+    test(&mcts, &symbols, "Power-Sum-p3", power_sum_3, |_| true);
 
     println!("═══════════════════════════════════════════════════════════════");
     println!("                    SUMMARY");
@@ -254,7 +256,7 @@ fn main() {
     println!("typically require human-guided search or specialized tactics.");
 }
 
-fn test<F>(mcts: &NeuralMCTS, name: &str, expr: Expr, check: F)
+fn test<F>(mcts: &NeuralMCTS, symbols: &SymbolTable, name: &str, expr: Expr, check: F)
 where
     F: Fn(&Expr) -> bool,
 {
@@ -270,5 +272,5 @@ where
         result.steps.len(),
         elapsed
     );
-    println!("   Result: {:?}\n", result.result);
+    println!("   Result: {}\n", result.result.to_ascii(symbols));
 }
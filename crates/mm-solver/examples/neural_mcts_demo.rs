@@ -126,7 +126,7 @@ fn main() {
     for rule in &applicable {
         let results = (rule.apply)(&expr, &ctx);
         if !results.is_empty() {
-            let result_str = format!("{:?}", results[0].result);
+            let result_str = results[0].result.to_ascii(&symbols);
             let truncated = if result_str.len() > 50 {
                 format!("{}...", &result_str[..50])
             } else {
@@ -27,6 +27,7 @@ fn main() {
     // Test 1: (2+3)*(4+5) → 45
     test(
         &mcts,
+        &symbols,
         "1",
         "(2+3)*(4+5) → 45",
         Expr::Mul(
@@ -39,6 +40,7 @@ fn main() {
     // Test 2: ((x+0)*1)+0 → x
     test(
         &mcts,
+        &symbols,
         "2",
         "((x+0)*1)+0 → x",
         Expr::Add(
@@ -54,6 +56,7 @@ fn main() {
     // Test 3: x^2 * x^3 * x^4 → x^9
     test(
         &mcts,
+        &symbols,
         "3",
         "x² * x³ * x⁴ → x⁹",
         Expr::Mul(
@@ -72,6 +75,7 @@ fn main() {
     // Test 4: d/dx(x² + x³) → 2x + 3x²
     test(
         &mcts,
+        &symbols,
         "4",
         "d/dx(x² + x³) → 2x + 3x²",
         Expr::Derivative {
@@ -87,6 +91,7 @@ fn main() {
     // Test 5: 3x + 5 = 17 → x = 4
     test(
         &mcts,
+        &symbols,
         "5",
         "3x + 5 = 17 → x = 4",
         Expr::Equation {
@@ -108,6 +113,7 @@ fn main() {
     // Test 6: d/dx(x⁴) → 4x³
     test(
         &mcts,
+        &symbols,
         "6",
         "d/dx(x⁴) → 4x³",
         Expr::Derivative {
@@ -126,6 +132,7 @@ fn main() {
     // Test 7: (x+1)*(x+1) - should distribute
     test(
         &mcts,
+        &symbols,
         "7",
         "(x+1)*(x+1) distribute",
         Expr::Mul(
@@ -138,6 +145,7 @@ fn main() {
     // Test 8: sin²(x) + cos²(x) - 1 → 0
     test(
         &mcts,
+        &symbols,
         "8",
         "sin²x + cos²x - 1 → 0",
         Expr::Sub(
@@ -159,6 +167,7 @@ fn main() {
     // Test 9: 2*(x+y) + 3*(x+y) → 5*(x+y) (collect like terms)
     test(
         &mcts,
+        &symbols,
         "9",
         "2(x+y) + 3(x+y) → 5(x+y)",
         Expr::Add(
@@ -180,6 +189,7 @@ fn main() {
     // Test 10: (100-50)*2 + 10 → 110
     test(
         &mcts,
+        &symbols,
         "10",
         "(100-50)*2 + 10 → 110",
         Expr::Add(
@@ -193,7 +203,7 @@ fn main() {
     );
 }
 
-fn test<F>(mcts: &NeuralMCTS, id: &str, name: &str, expr: Expr, check: F)
+fn test<F>(mcts: &NeuralMCTS, symbols: &SymbolTable, id: &str, name: &str, expr: Expr, check: F)
 where
     F: Fn(&Expr) -> bool,
 {
@@ -209,5 +219,5 @@ where
         result.steps.len(),
         elapsed
     );
-    println!("   Result: {:?}\n", result.result);
+    println!("   Result: {}\n", result.result.to_ascii(symbols));
 }
@@ -110,7 +110,7 @@ fn main() {
     for (name, expr) in test_exprs {
         println!("Input: {}", name);
         let result = mcts.simplify(expr.clone());
-        println!("  Output: {:?}", result.result);
+        println!("  Output: {}", result.result.to_ascii(&symbols));
         println!("  Steps: {}", result.steps.len());
         println!("  Verified: {}", result.verified);
         if !result.steps.is_empty() {
@@ -131,7 +131,7 @@ fn main() {
     for rule in rules.applicable(&eq1, &ctx) {
         let results = rule.apply(&eq1, &ctx);
         for app in results {
-            println!("  {} → {:?}", rule.name, app.result);
+            println!("  {} → {}", rule.name, app.result.to_ascii(&symbols));
             println!("    Justification: {}", app.justification);
         }
     }
@@ -141,7 +141,7 @@ fn main() {
     for rule in rules.applicable(&eq2, &ctx) {
         let results = rule.apply(&eq2, &ctx);
         for app in results {
-            println!("  {} → {:?}", rule.name, app.result);
+            println!("  {} → {}", rule.name, app.result.to_ascii(&symbols));
             println!("    Justification: {}", app.justification);
         }
     }
@@ -151,7 +151,7 @@ fn main() {
     for rule in rules.applicable(&eq3, &ctx) {
         let results = rule.apply(&eq3, &ctx);
         for app in results {
-            println!("  {} → {:?}", rule.name, app.result);
+            println!("  {} → {}", rule.name, app.result.to_ascii(&symbols));
             println!("    Justification: {}", app.justification);
         }
     }
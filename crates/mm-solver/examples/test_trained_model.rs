@@ -148,8 +148,8 @@ fn main() {
 
         let result = mcts.simplify(expr.clone());
 
-        println!("  Input:  {:?}", expr);
-        println!("  Output: {:?}", result.result);
+        println!("  Input:  {}", expr.to_ascii(&symbols));
+        println!("  Output: {}", result.result.to_ascii(&symbols));
         println!("  Steps:  {}", result.steps.len());
 
         for (i, step) in result.steps.iter().enumerate() {
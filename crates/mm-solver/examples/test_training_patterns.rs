@@ -70,7 +70,12 @@ fn main() {
             passed += 1;
         }
         let status = if is_const { "✅" } else { "❌" };
-        println!("  {} {} → {:?}", status, name, result.result);
+        println!(
+            "  {} {} → {}",
+            status,
+            name,
+            result.result.to_ascii(&symbols)
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -118,7 +123,13 @@ fn main() {
             "❌"
         };
         let rule = result.steps.first().map(|s| s.rule_name).unwrap_or("-");
-        println!("  {} {} → {:?} ({})", status, name, result.result, rule);
+        println!(
+            "  {} {} → {} ({})",
+            status,
+            name,
+            result.result.to_ascii(&symbols),
+            rule
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -152,7 +163,13 @@ fn main() {
         }
         let status = if simplified { "✅" } else { "⚠️" };
         let rule = result.steps.first().map(|s| s.rule_name).unwrap_or("-");
-        println!("  {} {} → {:?} ({})", status, name, result.result, rule);
+        println!(
+            "  {} {} → {} ({})",
+            status,
+            name,
+            result.result.to_ascii(&symbols),
+            rule
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -186,7 +203,13 @@ fn main() {
         }
         let status = if did_something { "✅" } else { "⚠️" };
         let rule = result.steps.first().map(|s| s.rule_name).unwrap_or("-");
-        println!("  {} {} → {:?} ({})", status, name, result.result, rule);
+        println!(
+            "  {} {} → {} ({})",
+            status,
+            name,
+            result.result.to_ascii(&symbols),
+            rule
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -232,7 +255,13 @@ fn main() {
         }
         let status = if is_one { "✅" } else { "❌" };
         let rule = result.steps.first().map(|s| s.rule_name).unwrap_or("-");
-        println!("  {} {} → {:?} ({})", status, name, result.result, rule);
+        println!(
+            "  {} {} → {} ({})",
+            status,
+            name,
+            result.result.to_ascii(&symbols),
+            rule
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -266,7 +295,13 @@ fn main() {
         }
         let status = if did_something { "✅" } else { "⚠️" };
         let rule = result.steps.first().map(|s| s.rule_name).unwrap_or("-");
-        println!("  {} {} → {:?} ({})", status, name, result.result, rule);
+        println!(
+            "  {} {} → {} ({})",
+            status,
+            name,
+            result.result.to_ascii(&symbols),
+            rule
+        );
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -97,7 +97,14 @@ fn main() {
         weight_decay: 0.01,
         batch_size: 128, // Larger batch for GPU
         epochs: 100,     // More epochs
+        seed: 42,
+        validation_split: 0.0,
+        patience: 5,
+        policy_weight: 1.0,
         value_weight: 0.5,
+        entropy_coeff: 0.0,
+        max_grad_norm: Some(1.0),
+        value_discount: 0.9,
     };
 
     println!("\n--- Training Configuration ---");
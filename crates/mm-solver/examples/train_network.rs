@@ -44,7 +44,14 @@ fn main() {
         weight_decay: 0.01,
         batch_size: 32, // Reduced from 64 for better gradients
         epochs: 50,     // Increased from 30
+        seed: 42,
+        validation_split: 0.0,
+        patience: 5,
+        policy_weight: 1.0,
         value_weight: 0.5,
+        entropy_coeff: 0.0,
+        max_grad_norm: Some(1.0),
+        value_discount: 0.9,
     };
 
     // Create trainer
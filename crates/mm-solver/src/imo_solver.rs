@@ -7,7 +7,7 @@
 //! - Verifier for proof step validation
 
 use mm_brain::{SubstitutionPrediction, SubstitutionPredictor};
-use mm_core::{Expr, Rational, SymbolTable};
+use mm_core::{Constraint, Expr, Rational, SymbolTable};
 use mm_rules::{rule::standard_rules, RuleContext, RuleId, RuleSet};
 use mm_search::{DeepMCTS, DeepMCTSConfig, SearchStats};
 use mm_verifier::Verifier;
@@ -85,6 +85,8 @@ pub struct SolutionStep {
     pub rule_name: String,
     /// Explanation
     pub explanation: String,
+    /// Side condition the step relies on, if any
+    pub condition: Option<Constraint>,
 }
 
 /// The integrated IMO Solver
@@ -239,6 +241,7 @@ impl IMOSolver {
                     after: w[1].clone(),
                     rule_name: "transformation".to_string(),
                     explanation: "Applied rule".to_string(),
+                    condition: None,
                 })
                 .collect()
         });
@@ -284,6 +287,7 @@ impl IMOSolver {
                     after: w[1].clone(),
                     rule_name: "simplification".to_string(),
                     explanation: "Simplified expression".to_string(),
+                    condition: None,
                 })
                 .collect()
         });
@@ -32,13 +32,23 @@
 
 pub mod imo_solver;
 pub mod orchestrator;
+pub mod system;
 
-use mm_core::{Expr, MathError, SymbolTable};
-use mm_rules::{rule::standard_rules, RuleSet};
-use mm_search::{BeamSearch, SearchConfig, Step};
+use candle_core::Device;
+use mm_brain::network::NetworkConfig;
+use mm_brain::PolicyNetwork;
+use mm_core::{Constraint, Expr, MathError, Rational, Symbol, SymbolTable};
+use mm_rules::board_exam::board_exam_rules;
+use mm_rules::calculus::differentiate;
+use mm_rules::polynomial::PolynomialNF;
+use mm_rules::{rule::standard_rules, RuleCategory, RuleContext, RuleId, RuleSet};
+use mm_search::{BeamSearch, MCTSConfig, NeuralMCTS, SearchConfig, Solution, Step};
 use mm_verifier::{Verifier, VerifyResult};
+use std::collections::HashMap;
+use std::path::Path;
 
 pub use imo_solver::{IMOSolveResult, IMOSolver, IMOSolverConfig, SolutionStep};
+pub use system::SystemSolution;
 
 /// The LEMMA solver.
 ///
@@ -48,6 +58,8 @@ pub struct LemmaSolver {
     verifier: Verifier,
     search: BeamSearch,
     symbols: SymbolTable,
+    mcts: Option<NeuralMCTS>,
+    answer_format: AnswerFormat,
 }
 
 impl Default for LemmaSolver {
@@ -69,6 +81,8 @@ impl LemmaSolver {
             verifier,
             search,
             symbols,
+            mcts: None,
+            answer_format: AnswerFormat::default(),
         }
     }
 
@@ -84,36 +98,215 @@ impl LemmaSolver {
             verifier,
             search,
             symbols,
+            mcts: None,
+            answer_format: AnswerFormat::default(),
         }
     }
 
+    /// Assume variables are generic: real, nonzero, and positive where a
+    /// rule needs it.
+    ///
+    /// Cancellations and radical rules then simplify without recording side
+    /// conditions, so `x^2/x` gives plain `x`. This matches classroom
+    /// convention but is unsound at the excluded points: at `x = 0` the
+    /// original expression is undefined while the result is not.
+    pub fn with_assume_generic(mut self, enabled: bool) -> Self {
+        let config = SearchConfig {
+            assume_generic: enabled,
+            ..self.search.config().clone()
+        };
+        self.search = BeamSearch::with_config(self.rules.clone(), self.verifier.clone(), config);
+        self.mcts = self.mcts.map(|mcts| mcts.with_assume_generic(enabled));
+        self
+    }
+
+    /// Render rational answers as `format` in
+    /// [`SolveResult::format`](SolveResult::format).
+    pub fn with_answer_format(mut self, format: AnswerFormat) -> Self {
+        self.answer_format = format;
+        self
+    }
+
+    /// Search with neural MCTS guided by `policy` instead of beam search.
+    ///
+    /// The policy also ranks [`suggest_rules`](Self::suggest_rules).
+    pub fn with_policy(mut self, policy: PolicyNetwork) -> Self {
+        self.mcts = Some(self.neural_mcts(policy));
+        self
+    }
+
+    /// Load policy weights saved by [`Trainer::save`](mm_brain::Trainer::save)
+    /// and search with them, as in [`with_policy`](Self::with_policy).
+    ///
+    /// The checkpoint must come from a network with the default
+    /// [`NetworkConfig`]. On error the solver is left unchanged.
+    pub fn load_policy(&mut self, path: &Path) -> Result<(), candle_core::Error> {
+        let policy = PolicyNetwork::load(path, NetworkConfig::default(), Device::Cpu)?;
+        self.mcts = Some(self.neural_mcts(policy));
+        Ok(())
+    }
+
+    /// Neural MCTS over this solver's rules and verifier, configured from
+    /// its [`SearchConfig`] and guided by `policy`.
+    fn neural_mcts(&self, policy: PolicyNetwork) -> NeuralMCTS {
+        let search = self.search.config();
+        let config = MCTSConfig {
+            simulations: search.mcts_iterations,
+            exploration_weight: search.exploration_weight,
+            max_depth: search.max_depth,
+            ..MCTSConfig::default()
+        };
+        NeuralMCTS::with_config(self.rules.clone(), self.verifier.clone(), config)
+            .with_policy(policy)
+            .with_time_limit(search.time_limit)
+            .with_assume_generic(search.assume_generic)
+    }
+
+    /// Whether a policy network is guiding the search.
+    pub fn has_policy(&self) -> bool {
+        self.mcts.is_some()
+    }
+
+    /// The rules the policy would try first on `input`, without searching.
+    ///
+    /// Returns up to `top_k` `(rule, probability)` pairs, most likely first.
+    /// Without a policy network, or if it fails, every rule applicable at the
+    /// root scores the same and they come in id order.
+    pub fn suggest_rules(
+        &mut self,
+        input: &str,
+        top_k: usize,
+    ) -> Result<Vec<(RuleId, f32)>, MathError> {
+        let expr = self.parse(input)?;
+
+        if let Some(ranked) = self
+            .mcts
+            .as_ref()
+            .and_then(|mcts| mcts.policy().top_k(&expr, top_k).ok())
+        {
+            return Ok(ranked);
+        }
+
+        let mut ids: Vec<RuleId> = self
+            .rules
+            .applicable(&expr, &self.context())
+            .into_iter()
+            .map(|rule| rule.id)
+            .collect();
+        ids.sort_unstable_by_key(|id| id.0);
+        let score = 1.0 / ids.len().max(1) as f32;
+        Ok(ids.into_iter().take(top_k).map(|id| (id, score)).collect())
+    }
+
     /// Parse an expression from a string.
     pub fn parse(&mut self, input: &str) -> Result<Expr, MathError> {
-        use mm_core::parse::Parser;
-        let mut parser = Parser::new(&mut self.symbols);
-        parser.parse(input)
+        parse_with(input, &mut self.symbols)
+    }
+
+    /// Parse an expression and render it back in the parser's syntax.
+    ///
+    /// Shows how the input was read, e.g. which operator bound tighter.
+    pub fn parse_to_string(&mut self, input: &str) -> Result<String, MathError> {
+        let expr = self.parse(input)?;
+        Ok(expr.to_ascii(&self.symbols))
+    }
+
+    /// Parse an expression and put it in canonical form.
+    ///
+    /// A fast path that skips rule search; see [`simplify`](Self::simplify)
+    /// for step-by-step simplification.
+    pub fn canonical(&mut self, input: &str) -> Result<Expr, MathError> {
+        Ok(self.parse(input)?.canonicalize())
+    }
+
+    /// Like [`canonical`](Self::canonical), rendered in the parser's syntax.
+    pub fn canonical_string(&mut self, input: &str) -> Result<String, MathError> {
+        let expr = self.canonical(input)?;
+        Ok(expr.to_ascii(&self.symbols))
     }
 
     /// Simplify an expression.
     pub fn simplify(&mut self, input: &str) -> Result<SolveResult, MathError> {
         let expr = self.parse(input)?;
-        let solution = self.search.simplify(expr);
+        let solution = self.run_simplify(expr);
 
-        Ok(SolveResult {
-            result: solution.result,
-            steps: solution.steps,
-            verified: solution.verified,
-        })
+        Ok(self.finish(solution.result, solution.steps, solution.verified))
+    }
+
+    /// Simplify an expression whose symbols come from another table.
+    ///
+    /// The symbols are first mapped by name into the solver's table, so the
+    /// result uses the solver's [`symbols`](Self::symbols). Fails with
+    /// [`MathError::UndefinedVariable`] if `symbols` cannot name one of them.
+    pub fn simplify_external(
+        &mut self,
+        expr: &Expr,
+        symbols: &SymbolTable,
+    ) -> Result<SolveResult, MathError> {
+        let expr = expr.remap_symbols(symbols, &mut self.symbols)?;
+        Ok(self.simplify_expr(expr))
+    }
+
+    /// Simplify an expression, interning its variables in `symbols`.
+    ///
+    /// Unlike [`simplify`](Self::simplify) this only borrows the solver, so
+    /// one solver can serve several callers that each keep their own symbol
+    /// table.
+    pub fn simplify_in(
+        &self,
+        input: &str,
+        symbols: &mut SymbolTable,
+    ) -> Result<SolveResult, MathError> {
+        let expr = parse_with(input, symbols)?;
+        Ok(self.simplify_expr(expr))
+    }
+
+    /// Simplify each input in turn.
+    ///
+    /// All variables share the solver's symbol table, so results can be
+    /// rendered with [`symbols`](Self::symbols). A parse error affects only
+    /// its own entry.
+    pub fn solve_batch(&mut self, inputs: &[&str]) -> Vec<Result<SolveResult, MathError>> {
+        inputs.iter().map(|input| self.simplify(input)).collect()
+    }
+
+    /// Simplify the inputs in parallel.
+    ///
+    /// Each problem gets a fresh symbol table, returned next to its result
+    /// for rendering.
+    #[cfg(feature = "parallel")]
+    pub fn solve_batch_parallel(
+        &self,
+        inputs: &[&str],
+    ) -> Vec<(SymbolTable, Result<SolveResult, MathError>)> {
+        use rayon::prelude::*;
+
+        inputs
+            .par_iter()
+            .map(|input| {
+                let mut symbols = SymbolTable::new();
+                let result = self.simplify_in(input, &mut symbols);
+                (symbols, result)
+            })
+            .collect()
     }
 
     /// Simplify an already-parsed expression.
+    ///
+    /// `expr` must use this solver's [`symbols`](Self::symbols); for an
+    /// expression built against another table use
+    /// [`simplify_external`](Self::simplify_external).
     pub fn simplify_expr(&self, expr: Expr) -> SolveResult {
-        let solution = self.search.simplify(expr);
+        let solution = self.run_simplify(expr);
+
+        self.finish(solution.result, solution.steps, solution.verified)
+    }
 
-        SolveResult {
-            result: solution.result,
-            steps: solution.steps,
-            verified: solution.verified,
+    /// Simplify with neural MCTS if a policy is loaded, else beam search.
+    fn run_simplify(&self, expr: Expr) -> Solution {
+        match &self.mcts {
+            Some(mcts) => mcts.simplify(expr),
+            None => self.search.simplify(expr),
         }
     }
 
@@ -129,18 +322,208 @@ impl LemmaSolver {
         };
 
         // Simplify to evaluate the derivative
-        let solution = self.search.simplify(deriv);
+        let solution = self.run_simplify(deriv);
+
+        Ok(self.finish(solution.result, solution.steps, solution.verified))
+    }
+
+    /// Evaluate the sum of `body` for `var` running from `from` to `to`.
+    ///
+    /// Closed forms are used where the summation rules know one (arithmetic
+    /// series, sums of squares and cubes); a short sum with concrete bounds
+    /// is added up term by term.
+    pub fn sum(
+        &mut self,
+        body: &str,
+        var: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<SolveResult, MathError> {
+        let (var, [from, to, body]) = self.parse_range(body, var, from, to)?;
+        let sum = Expr::Summation {
+            var,
+            from,
+            to,
+            body,
+        };
+
+        Ok(self.evaluate_big_operator(sum))
+    }
+
+    /// Evaluate the product of `body` for `var` running from `from` to `to`,
+    /// as [`sum`](Self::sum) does for sums.
+    pub fn product(
+        &mut self,
+        body: &str,
+        var: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<SolveResult, MathError> {
+        let (var, [from, to, body]) = self.parse_range(body, var, from, to)?;
+        let product = Expr::BigProduct {
+            var,
+            from,
+            to,
+            body,
+        };
+
+        Ok(self.evaluate_big_operator(product))
+    }
+
+    /// Rewrite a summation or product with the rule giving the simplest
+    /// result, then tidy that up.
+    ///
+    /// Closed forms such as `n(n+1)/2` are larger than the `Σ` they replace,
+    /// so plain simplification would not choose them.
+    fn evaluate_big_operator(&self, expr: Expr) -> SolveResult {
+        let ctx = self.context();
+        let best = self
+            .rules
+            .applicable(&expr, &ctx)
+            .into_iter()
+            .filter(|rule| rule.applicable_roots().contains(&expr.kind()))
+            .flat_map(|rule| {
+                rule.apply(&expr, &ctx)
+                    .into_iter()
+                    .map(move |app| (rule, app))
+            })
+            .min_by_key(|(_, app)| app.result.canonicalize().complexity());
+
+        let Some((rule, app)) = best else {
+            return self.simplify_expr(expr);
+        };
+
+        let verified = self
+            .verifier
+            .verify_step(&expr, &app.result, rule, &ctx)
+            .is_valid();
+        let mut steps = vec![Step {
+            before: expr,
+            after: app.result.clone(),
+            rule_id: rule.id,
+            rule_name: rule.name,
+            justification: app.justification,
+            condition: app.condition,
+        }];
+        let tidy = self.search.simplify(app.result);
+        steps.extend(tidy.steps);
 
-        Ok(SolveResult {
-            result: solution.result,
-            steps: solution.steps,
-            verified: solution.verified,
-        })
+        self.finish(tidy.result, steps, verified && tidy.verified)
+    }
+
+    /// Parse the index and `[from, to, body]` of a summation or product.
+    fn parse_range(
+        &mut self,
+        body: &str,
+        var: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<(Symbol, [Box<Expr>; 3]), MathError> {
+        let Expr::Var(var) = self.parse(var)? else {
+            return Err(MathError::ParseError(format!(
+                "Index must be a variable: {}",
+                var
+            )));
+        };
+        let from = Box::new(self.parse(from)?);
+        let to = Box::new(self.parse(to)?);
+        let body = Box::new(self.parse(body)?);
+
+        Ok((var, [from, to, body]))
+    }
+
+    /// Solve a structured board-exam question step by step.
+    ///
+    /// Equations are brought to standard form and solved with the board-exam
+    /// rules (linear and quadratic formulas, discriminant shown), integrals use
+    /// the standard antiderivative table. Every root is checked by substitution
+    /// and every antiderivative by differentiating it back. Anything the
+    /// board-exam rules do not cover falls back to plain simplification.
+    pub fn solve_board_exam(&mut self, input: &str) -> Result<SolveResult, MathError> {
+        let expr = self.parse(input)?;
+        let ctx = self.context();
+
+        let applied = board_exam_rules()
+            .into_iter()
+            .filter(|rule| rule.can_apply(&expr, &ctx))
+            .find_map(|rule| {
+                let app = rule.apply(&expr, &ctx).into_iter().next()?;
+                Some((rule, app))
+            });
+
+        let Some((rule, app)) = applied else {
+            return Ok(self.simplify_expr(expr));
+        };
+
+        let mut steps = vec![Step {
+            before: expr.clone(),
+            after: app.result.clone(),
+            rule_id: rule.id,
+            rule_name: rule.name,
+            justification: app.justification,
+            condition: app.condition,
+        }];
+
+        // Antiderivatives come out of the table unsimplified
+        let result = if matches!(expr, Expr::Integral { .. }) {
+            let tidy = self.search.simplify(app.result);
+            steps.extend(tidy.steps);
+            tidy.result
+        } else {
+            app.result
+        };
+
+        let verified = self.check_board_answer(&expr, &result);
+
+        Ok(self.finish(result, steps, verified))
+    }
+
+    /// The context the solver applies rules in.
+    fn context(&self) -> RuleContext {
+        RuleContext {
+            assume_generic: self.search.config().assume_generic,
+            ..RuleContext::default()
+        }
+    }
+
+    /// Package a result in the solver's answer format.
+    fn finish(&self, result: Expr, steps: Vec<Step>, verified: bool) -> SolveResult {
+        let mut result = SolveResult::new(result, steps, verified);
+        result.answer_format = self.answer_format;
+        result
+    }
+
+    /// Check a board-exam answer against the original question.
+    fn check_board_answer(&self, question: &Expr, answer: &Expr) -> bool {
+        match question {
+            Expr::Equation { .. } => {
+                let mut roots = Vec::new();
+                collect_roots(answer, &mut roots);
+                !roots.is_empty()
+                    && roots.iter().all(|(var, value)| {
+                        self.verifier
+                            .verify_solution(question, *var, value)
+                            .is_valid()
+                    })
+            }
+            Expr::Integral { expr, var } => {
+                differentiate(answer, *var).approx_equals(expr, 10, 1e-6)
+            }
+            _ => false,
+        }
     }
 
     /// Solve an equation for a variable.
     ///
-    /// Returns all solutions found.
+    /// Linear (`ax + b = c`) and quadratic (`ax² + bx + c = 0`) equations
+    /// with rational coefficients are solved: everything is moved to the
+    /// left-hand side, the powers of `var` collected, and the root found by
+    /// `x = -b/a` or the discriminant formula, keeping irrational roots as
+    /// surds. Each root is its own result `x = r`, carrying the working and
+    /// checked by substitution.
+    ///
+    /// Returns no solutions for an equation of any other form, or a
+    /// quadratic without real roots.
     pub fn solve_for(&mut self, equation: &str, var: &str) -> Result<Vec<SolveResult>, MathError> {
         // Parse the equation
         // For now, we expect "lhs = rhs" format
@@ -155,14 +538,73 @@ impl LemmaSolver {
         let rhs = self.parse(parts[1].trim())?;
         let var_symbol = self.symbols.intern(var);
 
+        let difference = Expr::Sub(Box::new(lhs.clone()), Box::new(rhs.clone()));
         let eq = Expr::Equation {
             lhs: Box::new(lhs),
             rhs: Box::new(rhs),
         };
 
-        // TODO: Implement equation solving
-        // For now, return empty
-        Ok(vec![])
+        let Some(coeffs) = PolynomialNF::from_expr(&difference)
+            .and_then(|poly| poly.coefficients(var_symbol))
+            .filter(|coeffs| matches!(coeffs.len(), 2 | 3))
+        else {
+            return Ok(vec![]);
+        };
+        let standard = Expr::Equation {
+            lhs: Box::new(polynomial(var_symbol, &coeffs)),
+            rhs: Box::new(Expr::int(0)),
+        };
+
+        let ctx = RuleContext {
+            target_var: Some(var_symbol),
+            ..self.context()
+        };
+        let applied = board_exam_rules()
+            .into_iter()
+            .filter(|rule| rule.can_apply(&standard, &ctx))
+            .find_map(|rule| {
+                let app = rule.apply(&standard, &ctx).into_iter().next()?;
+                Some((rule, app))
+            });
+        let Some((rule, app)) = applied else {
+            return Ok(vec![]);
+        };
+
+        let steps = vec![
+            Step {
+                before: eq.clone(),
+                after: standard.clone(),
+                rule_id: RuleId(0),
+                rule_name: "standard_form",
+                justification: format!(
+                    "Move every term to the left-hand side and collect powers of {}",
+                    var
+                ),
+                condition: None,
+            },
+            Step {
+                before: standard,
+                after: app.result.clone(),
+                rule_id: rule.id,
+                rule_name: rule.name,
+                justification: app.justification,
+                condition: app.condition,
+            },
+        ];
+
+        let mut roots = Vec::new();
+        collect_roots(&app.result, &mut roots);
+        Ok(roots
+            .into_iter()
+            .map(|(var, value)| {
+                let verified = self.verifier.verify_solution(&eq, var, &value).is_valid();
+                let root = Expr::Equation {
+                    lhs: Box::new(Expr::Var(var)),
+                    rhs: Box::new(value),
+                };
+                self.finish(root, steps.clone(), verified)
+            })
+            .collect())
     }
 
     /// Verify that a value is a solution to an equation.
@@ -206,8 +648,107 @@ impl LemmaSolver {
     pub fn num_rules(&self) -> usize {
         self.rules.len()
     }
+
+    /// The rules the solver simplifies with.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+}
+
+fn parse_with(input: &str, symbols: &mut SymbolTable) -> Result<Expr, MathError> {
+    mm_core::parse::Parser::new(symbols).parse(input)
+}
+
+/// `c₂x² + c₁x + c₀`, highest degree first, from the coefficients of a
+/// polynomial lowest degree first.
+fn polynomial(var: Symbol, coeffs: &[Rational]) -> Expr {
+    let mut terms = coeffs
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, c)| !c.is_zero())
+        .map(|(degree, c)| {
+            let power = match degree {
+                0 => None,
+                1 => Some(Expr::Var(var)),
+                n => Some(Expr::Pow(
+                    Box::new(Expr::Var(var)),
+                    Box::new(Expr::int(n as i64)),
+                )),
+            };
+            let magnitude = match power {
+                None => Expr::Const(c.abs()),
+                Some(power) if c.abs().is_one() => power,
+                Some(power) => Expr::Mul(Box::new(Expr::Const(c.abs())), Box::new(power)),
+            };
+            (c.is_negative(), magnitude)
+        });
+
+    let Some((negative, first)) = terms.next() else {
+        return Expr::int(0);
+    };
+    let first = if negative {
+        Expr::Neg(Box::new(first))
+    } else {
+        first
+    };
+    terms.fold(first, |sum, (negative, term)| {
+        if negative {
+            Expr::Sub(Box::new(sum), Box::new(term))
+        } else {
+            Expr::Add(Box::new(sum), Box::new(term))
+        }
+    })
+}
+
+/// Collect `var = value` pairs from a disjunction of root equations.
+fn collect_roots(answer: &Expr, roots: &mut Vec<(mm_core::Symbol, Expr)>) {
+    match answer {
+        Expr::Or(a, b) => {
+            collect_roots(a, roots);
+            collect_roots(b, roots);
+        }
+        Expr::Equation { lhs, rhs } => {
+            if let Expr::Var(v) = lhs.as_ref() {
+                roots.push((*v, rhs.as_ref().clone()));
+            }
+        }
+        _ => {}
+    }
 }
 
+/// How much of the work [`SolveResult::format_with`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Just the answer.
+    Terse,
+    /// Substantive steps, skipping bookkeeping such as `x · 1 → x`.
+    #[default]
+    Normal,
+    /// Every step, plus the final canonicalization when it changes the
+    /// result.
+    Verbose,
+}
+
+/// How [`SolveResult::format_with`] writes rational numbers.
+///
+/// Only rational constants are affected: `√2`, `π` and `e` stay symbolic in
+/// every format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnswerFormat {
+    /// Exact fractions: `1/3`.
+    #[default]
+    ExactFraction,
+    /// Decimals rounded to the given number of places: `0.3333` at 4.
+    Decimal(usize),
+    /// Exact fractions, with a decimal approximation after a rational
+    /// answer: `1/3 ≈ 0.3333`.
+    Mixed,
+}
+
+/// Decimal places of the approximation [`AnswerFormat::Mixed`] adds.
+const MIXED_PRECISION: usize = 4;
+
 /// Result of solving a problem.
 #[derive(Debug, Clone)]
 pub struct SolveResult {
@@ -217,9 +758,30 @@ pub struct SolveResult {
     pub steps: Vec<Step>,
     /// Whether the result was verified.
     pub verified: bool,
+    /// Side conditions the steps rely on, e.g. `x ≠ 0` after a cancellation.
+    pub conditions: Vec<Constraint>,
+    /// How [`format`](Self::format) writes rational numbers.
+    pub answer_format: AnswerFormat,
 }
 
 impl SolveResult {
+    /// Build a result, collecting the side conditions of its steps.
+    fn new(result: Expr, steps: Vec<Step>, verified: bool) -> Self {
+        let mut conditions: Vec<Constraint> = Vec::new();
+        for condition in steps.iter().filter_map(|s| s.condition.as_ref()) {
+            if !conditions.iter().any(|c| c.expr == condition.expr) {
+                conditions.push(condition.clone());
+            }
+        }
+        Self {
+            result,
+            steps,
+            verified,
+            conditions,
+            answer_format: AnswerFormat::default(),
+        }
+    }
+
     /// Get the number of steps.
     pub fn num_steps(&self) -> usize {
         self.steps.len()
@@ -230,24 +792,66 @@ impl SolveResult {
         self.steps.is_empty()
     }
 
-    /// Format the solution as a human-readable string.
-    pub fn format(&self, _symbols: &SymbolTable) -> String {
+    /// How many steps applied each rule, by rule name.
+    pub fn rule_histogram(&self) -> HashMap<&'static str, usize> {
+        let mut histogram = HashMap::new();
+        for step in &self.steps {
+            *histogram.entry(step.rule_name).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// How many steps applied a rule of each category.
+    ///
+    /// Categories are looked up by rule id in `rules`, usually the solver's
+    /// [`rules`](LemmaSolver::rules); steps whose rule it lacks, such as
+    /// board-exam steps, are not counted.
+    pub fn category_histogram(&self, rules: &RuleSet) -> HashMap<RuleCategory, usize> {
+        let mut histogram = HashMap::new();
+        for rule in self.steps.iter().filter_map(|step| rules.get(step.rule_id)) {
+            *histogram.entry(rule.category).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Format the solution as a human-readable string, showing every step.
+    pub fn format(&self, symbols: &SymbolTable) -> String {
+        self.format_with(symbols, Verbosity::Verbose)
+    }
+
+    /// Format the solution, showing as much work as `verbosity` asks for.
+    ///
+    /// Numbers are written as [`answer_format`](Self::answer_format) says.
+    pub fn format_with(&self, symbols: &SymbolTable, verbosity: Verbosity) -> String {
         let mut output = String::new();
 
+        let shown: Vec<&Step> = match verbosity {
+            Verbosity::Terse => vec![],
+            Verbosity::Normal => self.steps.iter().filter(|s| !s.is_bookkeeping()).collect(),
+            Verbosity::Verbose => self.steps.iter().collect(),
+        };
+        let result = self.render(&self.result, symbols);
+
         if self.steps.is_empty() {
-            output.push_str(&format!("Result: {:?}\n", self.result));
+            output.push_str(&format!("Result: {}\n", result));
             output.push_str("(No simplification needed)\n");
+        } else if verbosity == Verbosity::Terse {
+            output.push_str(&format!("Result: {}\n", result));
         } else {
-            for (i, step) in self.steps.iter().enumerate() {
+            for (i, step) in shown.iter().enumerate() {
                 output.push_str(&format!(
                     "Step {}: {} ({})\n",
                     i + 1,
                     step.rule_name,
                     step.justification
                 ));
-                output.push_str(&format!("  → {:?}\n", step.after));
+                output.push_str(&format!("  → {}\n", self.render(&step.after, symbols)));
             }
-            output.push_str(&format!("\nFinal Result: {:?}\n", self.result));
+            let last = self.steps.last().map(|s| &s.after);
+            if verbosity == Verbosity::Verbose && last != Some(&self.result) {
+                output.push_str(&format!("Canonicalize\n  → {}\n", result));
+            }
+            output.push_str(&format!("\nFinal Result: {}\n", result));
         }
 
         if self.verified {
@@ -256,6 +860,23 @@ impl SolveResult {
 
         output
     }
+
+    /// Render `expr` in the result's [`AnswerFormat`].
+    fn render(&self, expr: &Expr, symbols: &SymbolTable) -> String {
+        let display = expr.display(symbols);
+        match self.answer_format {
+            AnswerFormat::ExactFraction => display.to_string(),
+            AnswerFormat::Decimal(precision) => display.with_decimals(precision).to_string(),
+            AnswerFormat::Mixed => match expr {
+                Expr::Const(r) if !r.is_integer() => format!(
+                    "{} ≈ {}",
+                    display,
+                    expr.display(symbols).with_decimals(MIXED_PRECISION)
+                ),
+                _ => display.to_string(),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -276,6 +897,367 @@ mod tests {
         assert_eq!(result.result.canonicalize(), Expr::int(5));
     }
 
+    #[test]
+    fn test_solve_batch() {
+        let mut solver = LemmaSolver::new();
+
+        let results = solver.solve_batch(&["2 + 3", "x + 0", "2 +", "4 * 5"]);
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().result, Expr::int(5));
+        let x = solver.symbols_mut().intern("x");
+        assert_eq!(results[1].as_ref().unwrap().result, Expr::Var(x));
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_ref().unwrap().result, Expr::int(20));
+
+        // Solving with a caller-owned table leaves the solver untouched
+        let mut symbols = SymbolTable::new();
+        let result = solver.simplify_in("y * 1", &mut symbols).unwrap();
+        let y = symbols.intern("y");
+        assert_eq!(result.result, Expr::Var(y));
+        assert!(solver.symbols().get("y").is_none());
+    }
+
+    #[test]
+    fn test_simplify_external_remaps_symbols() {
+        let mut solver = LemmaSolver::new();
+        // Occupy the first slots so the tables disagree on what `x` is
+        solver.parse("a + b").unwrap();
+
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        // x * 1 + 0
+        let expr = Expr::Add(
+            Box::new(Expr::Mul(Box::new(Expr::Var(x)), Box::new(Expr::int(1)))),
+            Box::new(Expr::int(0)),
+        );
+
+        let result = solver.simplify_external(&expr, &symbols).unwrap();
+        let x_solver = solver.symbols_mut().intern("x");
+        assert_ne!(x, x_solver);
+        assert_eq!(result.result, Expr::Var(x_solver));
+        assert_eq!(result.result.to_ascii(solver.symbols()), "x");
+
+        // A symbol from yet another table is rejected rather than misread
+        let mut other = SymbolTable::new();
+        for name in ["p", "q", "r", "s"] {
+            other.intern(name);
+        }
+        let stray = Expr::Var(other.intern("t"));
+        assert!(matches!(
+            solver.simplify_external(&stray, &symbols),
+            Err(MathError::UndefinedVariable(_))
+        ));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_solve_batch_parallel() {
+        let solver = LemmaSolver::new();
+
+        let results = solver.solve_batch_parallel(&["2 + 3", "x * 1", "2 +"]);
+        assert_eq!(results[0].1.as_ref().unwrap().result, Expr::int(5));
+        let (symbols, result) = &results[1];
+        assert_eq!(
+            result.as_ref().unwrap().result.to_ascii(symbols),
+            "x".to_string()
+        );
+        assert!(results[2].1.is_err());
+    }
+
+    #[test]
+    fn test_format_verbosity() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let var = || Box::new(Expr::Var(x));
+        let pythagorean = Expr::Add(
+            Box::new(Expr::Pow(
+                Box::new(Expr::Sin(var())),
+                Box::new(Expr::int(2)),
+            )),
+            Box::new(Expr::Pow(
+                Box::new(Expr::Cos(var())),
+                Box::new(Expr::int(2)),
+            )),
+        );
+        let step = |before, after, rule_name| Step {
+            before,
+            after,
+            rule_id: mm_rules::RuleId(0),
+            rule_name,
+            justification: String::new(),
+            condition: None,
+        };
+
+        // (sin²x + cos²x) · 1 → sin²x + cos²x → 1
+        let result = SolveResult::new(
+            Expr::int(1),
+            vec![
+                step(
+                    Expr::Mul(Box::new(pythagorean.clone()), Box::new(Expr::int(1))),
+                    pythagorean.clone(),
+                    "identity_mul_one",
+                ),
+                step(pythagorean, Expr::int(1), "pythagorean_identity"),
+            ],
+            true,
+        );
+
+        let terse = result.format_with(&symbols, Verbosity::Terse);
+        assert!(!terse.contains("Step"));
+        assert!(terse.contains("Result: 1"));
+
+        let normal = result.format_with(&symbols, Verbosity::Normal);
+        assert!(normal.contains("Step 1: pythagorean_identity"));
+        assert!(!normal.contains("identity_mul_one"));
+
+        let verbose = result.format_with(&symbols, Verbosity::Verbose);
+        assert!(verbose.contains("Step 1: identity_mul_one"));
+        assert!(verbose.contains("Step 2: pythagorean_identity"));
+        assert_eq!(result.format(&symbols), verbose);
+    }
+
+    #[test]
+    fn test_simplify_reports_conditions() {
+        let mut solver = LemmaSolver::new();
+
+        let result = solver.simplify("x^2/x").unwrap();
+        let x = solver.symbols_mut().intern("x");
+        assert_eq!(result.result, Expr::Var(x));
+
+        // x ≠ 0 is stored as ¬(x = 0)
+        assert_eq!(result.conditions.len(), 1);
+        match &result.conditions[0].expr {
+            Expr::Not(inner) => assert!(matches!(
+                inner.as_ref(),
+                Expr::Equation { lhs, rhs } if **lhs == Expr::Var(x) && **rhs == Expr::int(0)
+            )),
+            other => panic!("Expected x ≠ 0, got {:?}", other),
+        }
+
+        let result = solver.simplify("2 + 3").unwrap();
+        assert!(result.conditions.is_empty());
+    }
+
+    #[test]
+    fn test_assume_generic_drops_conditions() {
+        let mut solver = LemmaSolver::new().with_assume_generic(true);
+
+        let result = solver.simplify("x^2/x").unwrap();
+        let x = solver.symbols_mut().intern("x");
+        assert_eq!(result.result, Expr::Var(x));
+        assert!(result.conditions.is_empty());
+        assert!(result.steps.iter().all(|s| s.condition.is_none()));
+
+        // Strict mode keeps x ≠ 0
+        let mut strict = LemmaSolver::new().with_assume_generic(false);
+        assert_eq!(strict.simplify("x^2/x").unwrap().conditions.len(), 1);
+    }
+
+    const POWER_RULE: RuleId = RuleId(11);
+
+    /// A network whose policy head always prefers the power rule.
+    fn power_rule_network(varmap: &candle_nn::VarMap) -> mm_brain::MathNetwork {
+        use candle_core::{DType, Tensor};
+        use candle_nn::VarBuilder;
+
+        let vb = VarBuilder::from_varmap(varmap, DType::F32, &Device::Cpu);
+        let config = NetworkConfig::default();
+        let network = mm_brain::MathNetwork::new_with_vb(config.clone(), vb).unwrap();
+        let mut bias = vec![0.0f32; config.num_rules];
+        bias[(POWER_RULE.0 - 1) as usize] = 10.0;
+        for (name, var) in varmap.data().lock().unwrap().iter() {
+            if name == "policy.weight" {
+                var.set(&var.zeros_like().unwrap()).unwrap();
+            } else if name == "policy.bias" {
+                var.set(&Tensor::new(bias.as_slice(), &Device::Cpu).unwrap())
+                    .unwrap();
+            }
+        }
+        network
+    }
+
+    #[test]
+    fn test_suggest_rules() {
+        // Without a network every applicable rule scores the same
+        let mut solver = LemmaSolver::new();
+        let fallback = solver.suggest_rules("diff(x^3, x)", 50).unwrap();
+        assert!(fallback.iter().any(|&(id, _)| id == POWER_RULE));
+        assert!(fallback.iter().all(|&(_, p)| p == fallback[0].1));
+
+        let varmap = candle_nn::VarMap::new();
+        let network = power_rule_network(&varmap);
+        let mut solver =
+            LemmaSolver::new().with_policy(PolicyNetwork::from_network(network, Device::Cpu));
+        let suggested = solver.suggest_rules("diff(x^3, x)", 3).unwrap();
+        assert_eq!(suggested.len(), 3);
+        assert_eq!(suggested[0].0, POWER_RULE);
+        assert!(suggested[0].1 > 0.9);
+    }
+
+    #[test]
+    fn test_policy_simplify_steps() {
+        let varmap = candle_nn::VarMap::new();
+        let network = power_rule_network(&varmap);
+        let config = SearchConfig {
+            mcts_iterations: 10,
+            ..SearchConfig::default()
+        };
+        let mut solver = LemmaSolver::with_config(config)
+            .with_policy(PolicyNetwork::from_network(network, Device::Cpu));
+
+        // The steps chain from the input to the result, clean-up included
+        let result = solver.simplify("diff(x^3, x) + 2 * 3").unwrap();
+        let last = result.steps.last().unwrap();
+        assert_eq!(last.after, result.result);
+        for pair in result.steps.windows(2) {
+            assert_eq!(pair[0].after, pair[1].before);
+        }
+    }
+
+    #[test]
+    fn test_load_policy() {
+        let varmap = candle_nn::VarMap::new();
+        power_rule_network(&varmap);
+        let path =
+            std::env::temp_dir().join(format!("lemma-policy-{}.safetensors", std::process::id()));
+        varmap.save(&path).unwrap();
+
+        let mut solver = LemmaSolver::new();
+        assert!(solver
+            .load_policy(Path::new("/nonexistent/policy.safetensors"))
+            .is_err());
+        assert!(!solver.has_policy());
+
+        solver.load_policy(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(solver.has_policy());
+
+        let suggested = solver.suggest_rules("diff(x^3, x)", 3).unwrap();
+        assert_eq!(suggested[0].0, POWER_RULE);
+        assert!(suggested[0].1 > 0.9);
+        assert!(suggested[1].1 < 0.1);
+    }
+
+    #[test]
+    fn test_sum_and_product() {
+        let mut solver = LemmaSolver::new();
+
+        // Σ_{i=1}^{100} i = 5050
+        let result = solver.sum("i", "i", "1", "100").unwrap();
+        assert_eq!(result.result, Expr::int(5050));
+        assert!(result.num_steps() > 0);
+        assert!(result.verified);
+
+        // Π_{i=1}^{4} i = 24
+        let result = solver.product("i", "i", "1", "4").unwrap();
+        assert_eq!(result.result, Expr::int(24));
+        assert!(result.num_steps() > 0);
+
+        // Π_{i=1}^{25} i overflows and is not multiplied out
+        let result = solver.product("i", "i", "1", "25").unwrap();
+        assert!(!result.result.is_const());
+
+        // Σ_{k=1}^{3} k² = 14
+        let result = solver.sum("k^2", "k", "1", "3").unwrap();
+        assert_eq!(result.result, Expr::int(14));
+
+        // Symbolic bounds take the closed form: Σ_{i=1}^{n} i = n(n+1)/2
+        let result = solver.sum("i", "i", "1", "n").unwrap();
+        assert_eq!(result.steps[0].rule_name, "summation_arithmetic");
+        let n = solver.symbols_mut().intern("n");
+        let mut env = mm_core::eval::Env::new();
+        env.insert(n, 10.0);
+        assert_eq!(result.result.evaluate(&env), Some(55.0));
+
+        // A constant summed over an empty range is 0
+        let result = solver.sum("4", "i", "3", "2").unwrap();
+        assert_eq!(result.result, Expr::int(0));
+
+        // Σ_{i=1}^{100} i⁹ overflows and is not added up
+        let result = solver.sum("i^9", "i", "1", "100").unwrap();
+        assert!(!result.result.is_const());
+
+        assert!(solver.sum("i", "2", "1", "10").is_err());
+    }
+
+    #[test]
+    fn test_rule_histogram() {
+        let solver = LemmaSolver::new();
+        let rule = |name: &str| {
+            let rule = solver.rules().all().iter().find(|r| r.name == name).unwrap();
+            Step {
+                before: Expr::int(0),
+                after: Expr::int(0),
+                rule_id: rule.id,
+                rule_name: rule.name,
+                justification: String::new(),
+                condition: None,
+            }
+        };
+        // Board-exam steps are not in the standard rule set
+        let board_exam = Step {
+            rule_id: RuleId(u32::MAX),
+            rule_name: "board_exam",
+            ..rule("sub_to_add")
+        };
+        let steps = vec![
+            rule("ln_derivative"),
+            rule("sub_to_add"),
+            rule("sin_double_angle"),
+            rule("ln_derivative"),
+            board_exam,
+        ];
+        let result = SolveResult::new(Expr::int(0), steps, true);
+
+        // Repeated rules are counted each time
+        let rules = result.rule_histogram();
+        assert_eq!(rules.values().sum::<usize>(), result.num_steps());
+        assert_eq!(
+            rules,
+            HashMap::from([
+                ("ln_derivative", 2),
+                ("sub_to_add", 1),
+                ("sin_double_angle", 1),
+                ("board_exam", 1),
+            ])
+        );
+
+        let categories = result.category_histogram(solver.rules());
+        assert_eq!(
+            categories,
+            HashMap::from([
+                (RuleCategory::Derivative, 2),
+                (RuleCategory::Simplification, 1),
+                (RuleCategory::TrigIdentity, 1),
+            ])
+        );
+
+        assert!(SolveResult::new(Expr::int(0), vec![], true)
+            .rule_histogram()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_answer_format() {
+        let solve = |format| {
+            let mut solver = LemmaSolver::new().with_answer_format(format);
+            let result = solver.simplify("1/6 + 1/6").unwrap();
+            assert_eq!(result.result, Expr::frac(1, 3));
+            let output = result.format_with(solver.symbols(), Verbosity::Terse);
+            output.lines().next().unwrap().to_string()
+        };
+
+        assert_eq!(solve(AnswerFormat::ExactFraction), "Result: 1/3");
+        assert_eq!(solve(AnswerFormat::Decimal(4)), "Result: 0.3333");
+        assert_eq!(solve(AnswerFormat::Mixed), "Result: 1/3 ≈ 0.3333");
+
+        // Irrational parts stay symbolic
+        let mut solver = LemmaSolver::new().with_answer_format(AnswerFormat::Decimal(4));
+        let result = solver.simplify("sqrt(2) / 2").unwrap();
+        let output = result.format_with(solver.symbols(), Verbosity::Terse);
+        assert!(output.contains('√'), "{output}");
+    }
+
     #[test]
     fn test_parse() {
         let mut solver = LemmaSolver::new();
@@ -283,4 +1265,111 @@ mod tests {
         let expr = solver.parse("x + 1").unwrap();
         assert!(matches!(expr, Expr::Add(_, _)));
     }
+
+    #[test]
+    fn test_parse_to_string() {
+        let mut solver = LemmaSolver::new();
+
+        assert_eq!(solver.parse_to_string("2+3*4").unwrap(), "2 + 3 * 4");
+        assert_eq!(solver.parse_to_string("(2+3)*4").unwrap(), "(2 + 3) * 4");
+        assert!(solver.parse_to_string("2+").is_err());
+    }
+
+    #[test]
+    fn test_canonical() {
+        let mut solver = LemmaSolver::new();
+
+        let a = solver.canonical("y + x").unwrap();
+        let b = solver.canonical("x + y").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(
+            solver.canonical_string("y + x").unwrap(),
+            solver.canonical_string("x + y").unwrap()
+        );
+        assert_eq!(solver.canonical("2 + 3").unwrap(), Expr::int(5));
+    }
+
+    #[test]
+    fn test_solve_board_exam_quadratic() {
+        let mut solver = LemmaSolver::new();
+
+        // x² - 5x + 6 = 0 → x = 3 or x = 2
+        let result = solver.solve_board_exam("x^2 - 5*x + 6 = 0").unwrap();
+        let x = solver.symbols_mut().intern("x");
+
+        let root = |r: i64| Expr::Equation {
+            lhs: Box::new(Expr::Var(x)),
+            rhs: Box::new(Expr::int(r)),
+        };
+        match &result.result {
+            Expr::Or(a, b) => {
+                assert_eq!(**a, root(3));
+                assert_eq!(**b, root(2));
+            }
+            other => panic!("Expected two roots, got {:?}", other),
+        }
+        assert_eq!(result.num_steps(), 1);
+        assert!(result.steps[0].justification.contains("D = b² - 4ac = 1"));
+        assert!(result.verified);
+    }
+
+    #[test]
+    fn test_solve_for() {
+        let mut solver = LemmaSolver::new();
+        let x = solver.symbols_mut().intern("x");
+        let root = |value: Expr| Expr::Equation {
+            lhs: Box::new(Expr::Var(x)),
+            rhs: Box::new(value),
+        };
+
+        // 2x + 3 = 7 → x = 2
+        let results = solver.solve_for("2*x + 3 = 7", "x").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result, root(Expr::int(2)));
+        assert!(results[0].verified);
+        let rules: Vec<_> = results[0].steps.iter().map(|s| s.rule_name).collect();
+        assert_eq!(rules, ["standard_form", "board_linear_equation"]);
+        assert_eq!(
+            results[0].steps[0].after.to_ascii(solver.symbols()),
+            "2 * x - 4 = 0"
+        );
+
+        // x² = 5x - 6 → x = 3 or x = 2
+        let results = solver.solve_for("x^2 = 5*x - 6", "x").unwrap();
+        let roots: Vec<_> = results.iter().map(|r| r.result.clone()).collect();
+        assert_eq!(roots, [root(Expr::int(3)), root(Expr::int(2))]);
+        assert!(results.iter().all(|r| r.verified));
+
+        // x² - 2 = 0 → x = ±√2, kept exact
+        let results = solver.solve_for("x^2 - 2 = 0", "x").unwrap();
+        let roots: Vec<_> = results
+            .iter()
+            .map(|r| r.result.to_ascii(solver.symbols()))
+            .collect();
+        assert_eq!(roots, ["x = sqrt(2)", "x = -sqrt(2)"]);
+        assert!(results.iter().all(|r| r.verified));
+
+        // 2x² + 2x - 1 = 0 → x = (-1 ± √3) / 2, with √12 = 2√3
+        let results = solver.solve_for("2*x^2 + 2*x - 1 = 0", "x").unwrap();
+        let roots: Vec<_> = results
+            .iter()
+            .map(|r| r.result.to_ascii(solver.symbols()))
+            .collect();
+        assert_eq!(roots, ["x = (-1 + sqrt(3)) / 2", "x = (-1 - sqrt(3)) / 2"]);
+        assert!(results.iter().all(|r| r.verified));
+
+        // A discriminant overflowing i64 gives no roots rather than a panic
+        let results = solver
+            .solve_for("4000000000*x^2 + 4000000000*x + 3 = 0", "x")
+            .unwrap();
+        assert!(results.is_empty());
+
+        // Symbolic coefficients, no real roots, too high a degree, not
+        // polynomial
+        assert!(solver.solve_for("a*x = 1", "x").unwrap().is_empty());
+        assert!(solver.solve_for("x^2 + 1 = 0", "x").unwrap().is_empty());
+        assert!(solver.solve_for("x^3 = 8", "x").unwrap().is_empty());
+        assert!(solver.solve_for("sin(x) = 0", "x").unwrap().is_empty());
+        assert!(solver.solve_for("x + 1", "x").is_err());
+    }
 }
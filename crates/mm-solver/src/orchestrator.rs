@@ -112,7 +112,7 @@ impl ProofOrchestrator {
             println!("═══════════════════════════════════════════════════════════════");
             println!("  LEMMA Proof Solver");
             println!("═══════════════════════════════════════════════════════════════");
-            println!("\n📋 Goal: {:?}\n", goal);
+            println!("\n📋 Goal: {}\n", goal.to_ascii(&self.symbols));
         }
 
         let mut steps = Vec::new();
@@ -219,7 +219,7 @@ impl ProofOrchestrator {
         // Step 1: Base case
         let base_goal = induction.base_case();
         if self.verbose {
-            println!("   📌 Base case: {:?}", base_goal);
+            println!("   📌 Base case: {}", base_goal.to_ascii(&self.symbols));
         }
 
         let base_result = self.prove_subgoal(&base_goal, steps);
@@ -252,7 +252,7 @@ impl ProofOrchestrator {
 
         if self.verbose {
             println!("   📌 Inductive step: Assume P(k), prove P(k+1)");
-            println!("      Need to show: {:?}", step_goal);
+            println!("      Need to show: {}", step_goal.to_ascii(&self.symbols));
         }
 
         // Try to verify the step using the inductive hypothesis
@@ -470,8 +470,8 @@ impl ProofOrchestrator {
 
         if self.verbose {
             println!(
-                "   Splitting on variable {:?} into {} cases:",
-                var,
+                "   Splitting on variable {} into {} cases:",
+                self.symbols.resolve(var).unwrap_or("?"),
                 analysis.cases.len()
             );
         }
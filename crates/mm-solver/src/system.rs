@@ -0,0 +1,505 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Systems of two linear equations in two unknowns, solved by elimination.
+//!
+//! Each stage of the working is recorded as a [`Step`], the way a student
+//! would write it out:
+//!
+//! ```text
+//! 2x + 3y = 8,  x - y = -1
+//! scale_equations   2x + 3y = 8,  3x - 3y = -3
+//! eliminate         5x = 5            (add the equations to eliminate y)
+//! solve_linear      x = 1
+//! back_substitute   2 + 3y = 8
+//! isolate           3y = 6
+//! solve_linear      y = 2
+//! ```
+
+use crate::{LemmaSolver, SolveResult};
+use mm_core::{Expr, MathError, Rational, Symbol, SymbolTable};
+use mm_rules::RuleId;
+use mm_search::Step;
+use std::collections::BTreeMap;
+
+/// The solution of a system, with the elimination that found it.
+#[derive(Debug, Clone)]
+pub struct SystemSolution {
+    /// The system as given, as the conjunction of its equations.
+    pub problem: Expr,
+    /// The value of each unknown, in name order.
+    pub values: Vec<(Symbol, Rational)>,
+    /// Scaling, elimination and back-substitution, in order.
+    pub steps: Vec<Step>,
+    /// Whether the values satisfy both equations exactly.
+    pub verified: bool,
+}
+
+impl SystemSolution {
+    /// The value found for `var`.
+    pub fn value(&self, var: Symbol) -> Option<Rational> {
+        self.values
+            .iter()
+            .find(|(v, _)| *v == var)
+            .map(|(_, value)| *value)
+    }
+
+    /// The answer as a [`SolveResult`]: `x = 1 ∧ y = 2`, reached by the
+    /// combined steps.
+    pub fn to_solve_result(&self) -> SolveResult {
+        let result = self
+            .values
+            .iter()
+            .map(|(var, value)| equation(Expr::Var(*var), Expr::Const(*value)))
+            .reduce(|a, b| Expr::And(Box::new(a), Box::new(b)))
+            .unwrap_or_else(|| self.problem.clone());
+        SolveResult::new(result, self.steps.clone(), self.verified)
+    }
+
+    /// Format the working and the values, one step per line.
+    pub fn format(&self, symbols: &SymbolTable) -> String {
+        self.to_solve_result().format(symbols)
+    }
+}
+
+impl LemmaSolver {
+    /// Solve two linear equations in two unknowns by elimination.
+    ///
+    /// Each equation is written `lhs = rhs`, e.g. `2*x + 3*y = 8`, with
+    /// rational coefficients. The second unknown (by name) is eliminated,
+    /// the first solved for, and its value substituted back; every stage is
+    /// a [`Step`] of the result.
+    ///
+    /// Fails with [`MathError::DomainError`] if an equation is not linear,
+    /// the system does not have exactly two unknowns, or the elimination
+    /// overflows `i64`, and with [`MathError::NoSolutionFound`] if the
+    /// equations are dependent or inconsistent.
+    pub fn solve_system(&mut self, first: &str, second: &str) -> Result<SystemSolution, MathError> {
+        let equations = [self.parse(first)?, self.parse(second)?];
+        let forms = [
+            LinearEquation::from_expr(&equations[0])?,
+            LinearEquation::from_expr(&equations[1])?,
+        ];
+
+        let mut vars: Vec<Symbol> = forms
+            .iter()
+            .flat_map(|form| form.coeffs.keys().copied())
+            .collect();
+        vars.sort_by_key(|v| self.symbols.resolve(*v).unwrap_or_default().to_string());
+        vars.dedup();
+        let [x, y] = vars[..] else {
+            return Err(MathError::DomainError(format!(
+                "Expected a system in two unknowns, found {}",
+                vars.len()
+            )));
+        };
+
+        let problem = Expr::And(
+            Box::new(equations[0].clone()),
+            Box::new(equations[1].clone()),
+        );
+        let names = [x, y].map(|v| self.symbols.resolve(v).unwrap_or("?"));
+        eliminate(problem, &forms, [x, y], names)
+    }
+}
+
+/// `a·x + b·y + … = constant`.
+#[derive(Debug, Clone, Default)]
+struct LinearEquation {
+    coeffs: BTreeMap<Symbol, Rational>,
+    constant: Rational,
+}
+
+impl LinearEquation {
+    /// Read `lhs = rhs` as a linear equation, moving everything with an
+    /// unknown to the left and the constants to the right.
+    fn from_expr(expr: &Expr) -> Result<Self, MathError> {
+        let Expr::Equation { lhs, rhs } = expr else {
+            return Err(MathError::DomainError(
+                "Expected an equation 'lhs = rhs'".to_string(),
+            ));
+        };
+        let mut form = LinearEquation::default();
+        form.add(lhs, Rational::from(1))?;
+        form.add(rhs, Rational::from(-1))?;
+        form.coeffs.retain(|_, c| !c.is_zero());
+        Ok(form)
+    }
+
+    /// Add `scale · expr` to the left-hand side, moving constants to the
+    /// right. Fails if `expr` is not linear or a coefficient overflows.
+    fn add(&mut self, expr: &Expr, scale: Rational) -> Result<(), MathError> {
+        match expr {
+            Expr::Const(c) => {
+                let term = checked(scale.checked_mul(c))?;
+                self.constant = checked(self.constant.checked_sub(&term))?;
+            }
+            Expr::Var(v) => {
+                let coeff = self.coeffs.entry(*v).or_default();
+                *coeff = checked(coeff.checked_add(&scale))?;
+            }
+            Expr::Neg(e) => self.add(e, negated(scale)?)?,
+            Expr::Add(a, b) => {
+                self.add(a, scale)?;
+                self.add(b, scale)?;
+            }
+            Expr::Sub(a, b) => {
+                self.add(a, scale)?;
+                self.add(b, negated(scale)?)?;
+            }
+            Expr::Mul(a, b) => match (constant(a), constant(b)) {
+                (Some(c), _) => self.add(b, checked(scale.checked_mul(&c))?)?,
+                (_, Some(c)) => self.add(a, checked(scale.checked_mul(&c))?)?,
+                _ => return Err(not_linear()),
+            },
+            Expr::Div(a, b) => match constant(b) {
+                Some(c) if !c.is_zero() => self.add(a, checked(scale.checked_div(&c))?)?,
+                _ => return Err(not_linear()),
+            },
+            _ => return Err(not_linear()),
+        }
+        Ok(())
+    }
+
+    fn coeff(&self, var: Symbol) -> Rational {
+        self.coeffs.get(&var).copied().unwrap_or_default()
+    }
+
+    fn scaled(&self, factor: Rational) -> Result<Self, MathError> {
+        let mut coeffs = BTreeMap::new();
+        for (v, c) in &self.coeffs {
+            coeffs.insert(*v, checked(c.checked_mul(&factor))?);
+        }
+        Ok(LinearEquation {
+            coeffs,
+            constant: checked(self.constant.checked_mul(&factor))?,
+        })
+    }
+
+    fn minus(&self, other: &Self) -> Result<Self, MathError> {
+        let mut result = self.clone();
+        for (v, c) in &other.coeffs {
+            let coeff = result.coeffs.entry(*v).or_default();
+            *coeff = checked(coeff.checked_sub(c))?;
+        }
+        result.coeffs.retain(|_, c| !c.is_zero());
+        result.constant = checked(self.constant.checked_sub(&other.constant))?;
+        Ok(result)
+    }
+
+    /// Whether `values` satisfy the equation exactly. A left-hand side that
+    /// overflows does not.
+    fn holds(&self, values: &[(Symbol, Rational)]) -> bool {
+        let lhs = values
+            .iter()
+            .try_fold(Rational::from(0), |sum, (v, value)| {
+                sum.checked_add(&self.coeff(*v).checked_mul(value)?)
+            });
+        lhs == Some(self.constant)
+    }
+
+    fn to_expr(&self) -> Expr {
+        let terms: Vec<_> = self.coeffs.iter().map(|(v, c)| (*c, *v)).collect();
+        equation(linear_combination(&terms), Expr::Const(self.constant))
+    }
+}
+
+/// The value of a constant subexpression.
+fn constant(expr: &Expr) -> Option<Rational> {
+    let mut form = LinearEquation::default();
+    form.add(expr, Rational::from(-1)).ok()?;
+    form.coeffs
+        .values()
+        .all(|c| c.is_zero())
+        .then_some(form.constant)
+}
+
+/// `-r`, failing for `-i64::MIN`.
+fn negated(r: Rational) -> Result<Rational, MathError> {
+    checked(Rational::from(0).checked_sub(&r))
+}
+
+/// The result of a checked operation, or an error if it overflowed.
+fn checked(value: Option<Rational>) -> Result<Rational, MathError> {
+    value.ok_or_else(|| MathError::DomainError("Coefficients overflow i64".to_string()))
+}
+
+fn not_linear() -> MathError {
+    MathError::DomainError("Equation is not linear".to_string())
+}
+
+/// Eliminate `y`, solve for `x`, then substitute back for `y`. `names`
+/// spell `x` and `y` in the justifications.
+fn eliminate(
+    problem: Expr,
+    forms: &[LinearEquation; 2],
+    [x, y]: [Symbol; 2],
+    [x_name, y_name]: [&str; 2],
+) -> Result<SystemSolution, MathError> {
+    let [first, second] = forms;
+    let (a1, b1) = (first.coeff(x), first.coeff(y));
+    let (a2, b2) = (second.coeff(x), second.coeff(y));
+    let (a1b2, a2b1) = (checked(a1.checked_mul(&b2))?, checked(a2.checked_mul(&b1))?);
+    if checked(a1b2.checked_sub(&a2b1))?.is_zero() {
+        return Err(MathError::NoSolutionFound);
+    }
+
+    let mut steps = Vec::new();
+    let mut push = |before: Expr, after: Expr, rule_name, justification: String| {
+        steps.push(Step {
+            before,
+            after,
+            rule_id: RuleId(0),
+            rule_name,
+            justification,
+            condition: None,
+        })
+    };
+    let both = |a: &LinearEquation, b: &LinearEquation| {
+        Expr::And(Box::new(a.to_expr()), Box::new(b.to_expr()))
+    };
+
+    // An equation without y needs no elimination
+    let x_only = if b1.is_zero() {
+        first.clone()
+    } else if b2.is_zero() {
+        second.clone()
+    } else {
+        // Positive multipliers matching the size of the y coefficients, as
+        // small as integers allow
+        let (m1, m2) = match gcd(b1, b2) {
+            Some(g) => (b2.abs() / g, b1.abs() / g),
+            None => (b2.abs(), b1.abs()),
+        };
+        let (scaled1, scaled2) = (first.scaled(m1)?, second.scaled(m2)?);
+        let scaling = match (m1.is_one(), m2.is_one()) {
+            (true, true) => None,
+            (false, true) => Some(format!("Multiply equation 1 by {}", m1)),
+            (true, false) => Some(format!("Multiply equation 2 by {}", m2)),
+            (false, false) => Some(format!(
+                "Multiply equation 1 by {} and equation 2 by {}",
+                m1, m2
+            )),
+        };
+        if let Some(scaling) = scaling {
+            push(
+                problem.clone(),
+                both(&scaled1, &scaled2),
+                "scale_equations",
+                format!("{} to match the coefficients of {}", scaling, y_name),
+            );
+        }
+
+        // Equal coefficients subtract away, opposite ones add
+        let (how, eliminated) = if scaled1.coeff(y) == scaled2.coeff(y) {
+            ("Subtract", scaled1.minus(&scaled2)?)
+        } else {
+            ("Add", scaled1.minus(&scaled2.scaled(Rational::from(-1))?)?)
+        };
+        push(
+            both(&scaled1, &scaled2),
+            eliminated.to_expr(),
+            "eliminate",
+            format!("{} the equations to eliminate {}", how, y_name),
+        );
+        eliminated
+    };
+    let x_value = solve_single(&x_only, x, &mut push)?;
+
+    // Substitute into an equation that still has y
+    let (target, number) = if b1.is_zero() {
+        (second, 2)
+    } else {
+        (first, 1)
+    };
+    let (a, b) = (target.coeff(x), target.coeff(y));
+    let ax = checked(a.checked_mul(&x_value))?;
+    let substituted = equation(
+        Expr::Add(
+            Box::new(Expr::Const(ax)),
+            Box::new(linear_combination(&[(b, y)])),
+        ),
+        Expr::Const(target.constant),
+    );
+    push(
+        target.to_expr(),
+        substituted.clone(),
+        "back_substitute",
+        format!(
+            "Substitute {} = {} into equation {}",
+            x_name, x_value, number
+        ),
+    );
+    let y_only = LinearEquation {
+        coeffs: BTreeMap::from([(y, b)]),
+        constant: checked(target.constant.checked_sub(&ax))?,
+    };
+    push(
+        substituted,
+        y_only.to_expr(),
+        "isolate",
+        "Move the constant to the right-hand side".to_string(),
+    );
+    let y_value = solve_single(&y_only, y, &mut push)?;
+
+    let values = vec![(x, x_value), (y, y_value)];
+    let verified = forms.iter().all(|form| form.holds(&values));
+
+    Ok(SystemSolution {
+        problem,
+        values,
+        steps,
+        verified,
+    })
+}
+
+/// Solve `a·var = c` for `var`, recording the division unless `a` is 1.
+fn solve_single(
+    form: &LinearEquation,
+    var: Symbol,
+    push: &mut impl FnMut(Expr, Expr, &'static str, String),
+) -> Result<Rational, MathError> {
+    let a = form.coeff(var);
+    let value = checked(form.constant.checked_div(&a))?;
+    if !a.is_one() {
+        push(
+            form.to_expr(),
+            equation(Expr::Var(var), Expr::Const(value)),
+            "solve_linear",
+            format!("Divide both sides by {}", a),
+        );
+    }
+    Ok(value)
+}
+
+/// `c₁·v₁ + c₂·v₂ + …`, written the way it would be by hand: unit
+/// coefficients dropped and negative ones subtracted.
+fn linear_combination(terms: &[(Rational, Symbol)]) -> Expr {
+    let term = |c: Rational, v: Symbol| {
+        if c.is_one() {
+            Expr::Var(v)
+        } else {
+            Expr::Mul(Box::new(Expr::Const(c)), Box::new(Expr::Var(v)))
+        }
+    };
+    let mut terms = terms.iter().filter(|(c, _)| !c.is_zero());
+    let Some(&(c, v)) = terms.next() else {
+        return Expr::int(0);
+    };
+    let first = if c.is_negative() {
+        Expr::Neg(Box::new(term(-c, v)))
+    } else {
+        term(c, v)
+    };
+    terms.fold(first, |sum, &(c, v)| {
+        if c.is_negative() {
+            Expr::Sub(Box::new(sum), Box::new(term(-c, v)))
+        } else {
+            Expr::Add(Box::new(sum), Box::new(term(c, v)))
+        }
+    })
+}
+
+fn equation(lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Equation {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+/// The gcd of two integers, or `None` if either is a fraction.
+fn gcd(a: Rational, b: Rational) -> Option<Rational> {
+    if !a.is_integer() || !b.is_integer() {
+        return None;
+    }
+    let (mut a, mut b) = (a.numer().abs(), b.numer().abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    Some(Rational::from(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_system_by_elimination() {
+        let mut solver = LemmaSolver::new();
+        let solution = solver.solve_system("2*x + 3*y = 8", "x - y = -1").unwrap();
+        let x = solver.symbols_mut().intern("x");
+        let y = solver.symbols_mut().intern("y");
+
+        assert_eq!(solution.value(x), Some(Rational::from(1)));
+        assert_eq!(solution.value(y), Some(Rational::from(2)));
+        assert!(solution.verified);
+
+        let names: Vec<_> = solution.steps.iter().map(|s| s.rule_name).collect();
+        assert_eq!(
+            names,
+            [
+                "scale_equations",
+                "eliminate",
+                "solve_linear",
+                "back_substitute",
+                "isolate",
+                "solve_linear"
+            ]
+        );
+        let elimination = &solution.steps[1];
+        assert_eq!(
+            elimination.justification,
+            "Add the equations to eliminate y"
+        );
+        assert_eq!(elimination.after.to_ascii(solver.symbols()), "5 * x = 5");
+
+        assert_eq!(solution.steps[0].before, solution.problem);
+        assert_eq!(solution.steps[1].before, solution.steps[0].after);
+        assert_eq!(
+            solution.steps[3].after.to_ascii(solver.symbols()),
+            "2 + 3 * y = 8"
+        );
+
+        let result = solution.to_solve_result();
+        assert_eq!(result.num_steps(), 6);
+        let text = solution.format(solver.symbols());
+        assert!(text.contains("eliminate"));
+        assert!(text.contains("x = 1"));
+        assert!(text.contains("y = 2"));
+    }
+
+    #[test]
+    fn test_solve_system_special_cases() {
+        let mut solver = LemmaSolver::new();
+
+        // Fractions, and an equation already free of y
+        let solution = solver.solve_system("x/2 + y = 4", "3*x = 6").unwrap();
+        let x = solver.symbols_mut().intern("x");
+        let y = solver.symbols_mut().intern("y");
+        assert_eq!(solution.value(x), Some(Rational::from(2)));
+        assert_eq!(solution.value(y), Some(Rational::from(3)));
+        assert!(solution.steps.iter().all(|s| s.rule_name != "eliminate"));
+
+        assert!(matches!(
+            solver.solve_system("x + y = 1", "2*x + 2*y = 2"),
+            Err(MathError::NoSolutionFound)
+        ));
+        assert!(matches!(
+            solver.solve_system("x * y = 1", "x + y = 2"),
+            Err(MathError::DomainError(_))
+        ));
+        assert!(matches!(
+            solver.solve_system("x + y + z = 1", "x - y = 0"),
+            Err(MathError::DomainError(_))
+        ));
+
+        // The determinant 4000000000² - 3 overflows i64
+        assert!(matches!(
+            solver.solve_system("4000000000*x + 3*y = 1", "x + 4000000000*y = 2"),
+            Err(MathError::DomainError(_))
+        ));
+    }
+}
@@ -35,6 +35,7 @@ fn main() {
             ProblemCategory::Inequality,
             ProblemCategory::NumberTheory,
         ],
+        cache_solutions: true,
     };
 
     let mut generator = ProblemGenerator::new(config);
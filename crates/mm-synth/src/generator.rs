@@ -2,6 +2,14 @@
 
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// Inclusive band of difficulty scores making up one curriculum stage
+pub type DifficultyRange = RangeInclusive<u8>;
+
+/// Candidates tried per requested problem before a stage gives up
+const CURRICULUM_ATTEMPTS: usize = 50;
 
 /// A synthetically generated problem with solution trace
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,7 +37,7 @@ pub enum ProblemCategory {
 }
 
 /// A single step in the solution
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SolutionStep {
     /// Action taken (substitution, rule application, etc.)
     pub action: String,
@@ -37,6 +45,9 @@ pub struct SolutionStep {
     pub result: String,
     /// Rule or technique used
     pub technique: String,
+    /// Why the step holds
+    #[serde(default)]
+    pub justification: String,
 }
 
 /// Configuration for problem generation
@@ -50,6 +61,8 @@ pub struct GeneratorConfig {
     pub max_depth: usize,
     /// Categories to generate
     pub categories: Vec<ProblemCategory>,
+    /// Reuse traced solutions for problems with the same canonical statement
+    pub cache_solutions: bool,
 }
 
 impl Default for GeneratorConfig {
@@ -64,6 +77,7 @@ impl Default for GeneratorConfig {
                 ProblemCategory::Inequality,
                 ProblemCategory::NumberTheory,
             ],
+            cache_solutions: false,
         }
     }
 }
@@ -72,6 +86,9 @@ impl Default for GeneratorConfig {
 pub struct ProblemGenerator {
     config: GeneratorConfig,
     rng: rand::rngs::StdRng,
+    /// Traced solutions by canonical statement (when caching is enabled)
+    solved: HashMap<String, Vec<SolutionStep>>,
+    cache_hits: usize,
 }
 
 impl ProblemGenerator {
@@ -81,7 +98,33 @@ impl ProblemGenerator {
         ProblemGenerator {
             rng: rand::rngs::StdRng::seed_from_u64(config.seed),
             config,
+            solved: HashMap::new(),
+            cache_hits: 0,
+        }
+    }
+
+    /// Number of problems whose solution came from the cache
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    /// Trace the solution of `statement`, or reuse the cached trace
+    fn solve(
+        &mut self,
+        statement: &str,
+        trace: impl FnOnce() -> Vec<SolutionStep>,
+    ) -> Vec<SolutionStep> {
+        if !self.config.cache_solutions {
+            return trace();
         }
+        let key = canonical_statement(statement);
+        if let Some(steps) = self.solved.get(&key) {
+            self.cache_hits += 1;
+            return steps.clone();
+        }
+        let steps = trace();
+        self.solved.insert(key, steps.clone());
+        steps
     }
 
     /// Generate all problems
@@ -89,14 +132,7 @@ impl ProblemGenerator {
         let mut problems = Vec::with_capacity(self.config.num_problems);
 
         for i in 0..self.config.num_problems {
-            let category = &self.config.categories[i % self.config.categories.len()];
-            let problem = match category {
-                ProblemCategory::FunctionalEquation => self.gen_functional_equation(),
-                ProblemCategory::Algebra => self.gen_algebra(),
-                ProblemCategory::Inequality => self.gen_inequality(),
-                ProblemCategory::NumberTheory => self.gen_number_theory(),
-                ProblemCategory::Combinatorics => self.gen_combinatorics(),
-            };
+            let problem = self.gen_problem(i);
             problems.push(problem);
 
             if i % 10000 == 0 && i > 0 {
@@ -107,6 +143,55 @@ impl ProblemGenerator {
         problems
     }
 
+    /// Generate problems stage by stage, easiest stage first.
+    ///
+    /// Each stage gets up to `per_stage` problems whose difficulty lies in its
+    /// range, sorted by difficulty. Candidates outside the range are thrown
+    /// away; after `per_stage * 50` candidates a stage settles for what it
+    /// has, so a range the templates rarely hit cannot stall the stream.
+    /// Stages are ordered by range, so with disjoint ranges difficulty never
+    /// decreases along the output.
+    pub fn curriculum(
+        &mut self,
+        stages: &[DifficultyRange],
+        per_stage: usize,
+    ) -> Vec<SyntheticProblem> {
+        let mut stages = stages.to_vec();
+        stages.sort_by_key(|range| (*range.start(), *range.end()));
+
+        let mut problems = Vec::with_capacity(stages.len() * per_stage);
+        let mut i = 0;
+        for range in &stages {
+            let mut stage = Vec::with_capacity(per_stage);
+            for _ in 0..per_stage * CURRICULUM_ATTEMPTS {
+                if stage.len() == per_stage {
+                    break;
+                }
+                let problem = self.gen_problem(i);
+                i += 1;
+                if range.contains(&problem.difficulty) {
+                    stage.push(problem);
+                }
+            }
+            stage.sort_by_key(|problem| problem.difficulty);
+            problems.extend(stage);
+        }
+
+        problems
+    }
+
+    /// Generate the `i`-th problem, cycling through the configured categories
+    fn gen_problem(&mut self, i: usize) -> SyntheticProblem {
+        let category = &self.config.categories[i % self.config.categories.len()];
+        match category {
+            ProblemCategory::FunctionalEquation => self.gen_functional_equation(),
+            ProblemCategory::Algebra => self.gen_algebra(),
+            ProblemCategory::Inequality => self.gen_inequality(),
+            ProblemCategory::NumberTheory => self.gen_number_theory(),
+            ProblemCategory::Combinatorics => self.gen_combinatorics(),
+        }
+    }
+
     /// Generate a functional equation problem
     fn gen_functional_equation(&mut self) -> SyntheticProblem {
         let templates = [
@@ -149,14 +234,20 @@ impl ProblemGenerator {
             domain, domain, equation
         );
 
+        let first = subs[0];
+        let solution_steps = self.solve(&statement, || {
+            vec![SolutionStep {
+                action: first.to_string(),
+                result: "Simplified form".to_string(),
+                technique: "Substitution".to_string(),
+                justification: String::new(),
+            }]
+        });
+
         SyntheticProblem {
             statement,
             category: ProblemCategory::FunctionalEquation,
-            solution_steps: vec![SolutionStep {
-                action: subs[0].to_string(),
-                result: "Simplified form".to_string(),
-                technique: "Substitution".to_string(),
-            }],
+            solution_steps,
             substitutions: subs.iter().map(|s| s.to_string()).collect(),
             difficulty: self.rng.gen_range(3..8),
         }
@@ -296,6 +387,11 @@ impl ProblemGenerator {
     }
 }
 
+/// Statement with whitespace normalized, so layout differences share a key
+fn canonical_statement(statement: &str) -> String {
+    statement.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,4 +408,50 @@ mod tests {
         assert_eq!(problems.len(), 100);
         assert!(!problems[0].statement.is_empty());
     }
+
+    #[test]
+    fn test_solution_cache() {
+        let config = GeneratorConfig {
+            num_problems: 200,
+            categories: vec![ProblemCategory::FunctionalEquation],
+            cache_solutions: true,
+            ..Default::default()
+        };
+        let mut gen = ProblemGenerator::new(config.clone());
+        let problems = gen.generate_all();
+
+        // 10 templates × 5 domains, so most of 200 problems repeat
+        assert!(gen.cache_hits() > 0);
+        let mut seen: HashMap<&str, &Vec<SolutionStep>> = HashMap::new();
+        for problem in &problems {
+            let steps = seen
+                .entry(problem.statement.as_str())
+                .or_insert(&problem.solution_steps);
+            assert_eq!(*steps, &problem.solution_steps);
+        }
+        assert_eq!(problems.len() - seen.len(), gen.cache_hits());
+
+        let mut uncached = ProblemGenerator::new(GeneratorConfig {
+            cache_solutions: false,
+            ..config
+        });
+        uncached.generate_all();
+        assert_eq!(uncached.cache_hits(), 0);
+    }
+
+    #[test]
+    fn test_curriculum() {
+        let mut gen = ProblemGenerator::new(GeneratorConfig::default());
+        let problems = gen.curriculum(&[6..=8, 1..=3, 4..=5], 20);
+
+        assert_eq!(problems.len(), 60);
+        assert!(problems
+            .windows(2)
+            .all(|pair| pair[0].difficulty <= pair[1].difficulty));
+        assert!(problems[..20].iter().all(|p| p.difficulty <= 3));
+        assert!(problems[40..].iter().all(|p| p.difficulty >= 6));
+
+        // No template is rated 10, so that stage stops at the attempt cap
+        assert!(gen.curriculum(&[10..=10], 5).is_empty());
+    }
 }
@@ -12,5 +12,6 @@ pub mod generator;
 pub mod number_theory;
 
 pub use generator::{
-    GeneratorConfig, ProblemCategory, ProblemGenerator, SolutionStep, SyntheticProblem,
+    DifficultyRange, GeneratorConfig, ProblemCategory, ProblemGenerator, SolutionStep,
+    SyntheticProblem,
 };
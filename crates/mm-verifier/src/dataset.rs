@@ -0,0 +1,223 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Verification of generated datasets before they are used for training.
+//!
+//! A [`SyntheticProblem`] step is checked as a rule application: its
+//! `action` is the expression before the step, its `result` the expression
+//! after, and its `technique` the name of the rule. Steps that are not of
+//! that form, such as `"Assume f is linear"` or a technique no rule is
+//! named after, cannot be checked and are counted apart from failures.
+
+use crate::{Verifier, VerifyResult};
+use mm_core::{parse::Parser, SymbolTable};
+use mm_rules::{RuleContext, RuleSet};
+use mm_synth::SyntheticProblem;
+use std::collections::HashMap;
+
+/// Most failing cases a [`VerificationReport`] keeps as examples.
+pub const MAX_EXAMPLES: usize = 10;
+
+/// Aggregate outcome of [`verify_dataset`].
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    /// Problems checked.
+    pub total: usize,
+    /// Problems whose every step verified.
+    pub passed: usize,
+    /// Problems with at least one invalid step.
+    pub failed: usize,
+    /// Problems with no invalid step but some step that could not be
+    /// checked, or no steps at all.
+    pub unchecked: usize,
+    /// Invalid steps, by rule name.
+    pub rule_failures: HashMap<String, usize>,
+    /// The first [`MAX_EXAMPLES`] invalid steps.
+    pub examples: Vec<FailedStep>,
+}
+
+/// An invalid step found by [`verify_dataset`].
+#[derive(Debug, Clone)]
+pub struct FailedStep {
+    /// Index of the problem in the dataset.
+    pub problem: usize,
+    /// Index of the step in the problem's solution.
+    pub step: usize,
+    /// The problem statement.
+    pub statement: String,
+    /// The rule the step claims to apply.
+    pub technique: String,
+    /// Why verification rejected it.
+    pub reason: String,
+}
+
+impl VerificationReport {
+    /// Fraction of problems that passed, or 1 for an empty dataset.
+    pub fn pass_rate(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.passed as f64 / self.total as f64
+        }
+    }
+}
+
+/// How one problem fared.
+enum Outcome {
+    Passed,
+    Failed,
+    Unchecked,
+}
+
+/// Verify every step of every problem's solution against `rules`.
+///
+/// Uses a default [`Verifier`]; see [`verify_dataset_with`] to configure it.
+pub fn verify_dataset(problems: &[SyntheticProblem], rules: &RuleSet) -> VerificationReport {
+    verify_dataset_with(problems, rules, &Verifier::new())
+}
+
+/// [`verify_dataset`] with a configured verifier.
+pub fn verify_dataset_with(
+    problems: &[SyntheticProblem],
+    rules: &RuleSet,
+    verifier: &Verifier,
+) -> VerificationReport {
+    let mut report = VerificationReport {
+        total: problems.len(),
+        ..VerificationReport::default()
+    };
+    let ctx = RuleContext::default();
+
+    for (index, problem) in problems.iter().enumerate() {
+        let mut symbols = SymbolTable::new();
+        let mut outcome = if problem.solution_steps.is_empty() {
+            Outcome::Unchecked
+        } else {
+            Outcome::Passed
+        };
+
+        for (step_index, step) in problem.solution_steps.iter().enumerate() {
+            let rule = rules.all().iter().find(|r| r.name == step.technique);
+            let mut parser = Parser::new(&mut symbols);
+            let (Some(rule), Ok(before), Ok(after)) =
+                (rule, parser.parse(&step.action), parser.parse(&step.result))
+            else {
+                if matches!(outcome, Outcome::Passed) {
+                    outcome = Outcome::Unchecked;
+                }
+                continue;
+            };
+
+            match verifier.verify_step(&before, &after, rule, &ctx) {
+                VerifyResult::Valid { .. } => {}
+                VerifyResult::Invalid { reason } => {
+                    outcome = Outcome::Failed;
+                    *report
+                        .rule_failures
+                        .entry(step.technique.clone())
+                        .or_insert(0) += 1;
+                    if report.examples.len() < MAX_EXAMPLES {
+                        report.examples.push(FailedStep {
+                            problem: index,
+                            step: step_index,
+                            statement: problem.statement.clone(),
+                            technique: step.technique.clone(),
+                            reason,
+                        });
+                    }
+                }
+                VerifyResult::Unknown { .. } => {
+                    if matches!(outcome, Outcome::Passed) {
+                        outcome = Outcome::Unchecked;
+                    }
+                }
+            }
+        }
+
+        match outcome {
+            Outcome::Passed => report.passed += 1,
+            Outcome::Failed => report.failed += 1,
+            Outcome::Unchecked => report.unchecked += 1,
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mm_rules::rule::standard_rules;
+    use mm_synth::{ProblemCategory, SolutionStep};
+
+    fn problem(statement: &str, steps: &[(&str, &str, &str)]) -> SyntheticProblem {
+        SyntheticProblem {
+            statement: statement.to_string(),
+            category: ProblemCategory::Algebra,
+            solution_steps: steps
+                .iter()
+                .map(|(action, result, technique)| SolutionStep {
+                    action: action.to_string(),
+                    result: result.to_string(),
+                    technique: technique.to_string(),
+                    justification: String::new(),
+                })
+                .collect(),
+            substitutions: vec![],
+            difficulty: 1,
+        }
+    }
+
+    #[test]
+    fn test_verify_dataset() {
+        let problems = vec![
+            problem(
+                "Simplify (x + 0) * 1",
+                &[
+                    ("(x + 0) * 1", "x + 0", "identity_mul_one"),
+                    ("x + 0", "x", "identity_add_zero"),
+                ],
+            ),
+            problem("Simplify 2 + 3", &[("2 + 3", "5", "const_fold")]),
+            // Wrong result for the rule
+            problem("Simplify 2 + 3", &[("2 + 3", "6", "const_fold")]),
+            // A valid step followed by a rule that does not apply
+            problem(
+                "Simplify x * 1 + 0",
+                &[
+                    ("x * 1 + 0", "x * 1", "identity_add_zero"),
+                    ("x * 1", "x", "identity_add_zero"),
+                ],
+            ),
+            // Prose steps cannot be checked
+            problem(
+                "Find all f with f(x + y) = f(x) + f(y)",
+                &[("x = 0", "Simplified form", "Substitution")],
+            ),
+            problem("Count arrangements", &[]),
+        ];
+
+        let report = verify_dataset(&problems, &standard_rules());
+        assert_eq!(report.total, 6);
+        assert_eq!(report.passed, 2);
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.unchecked, 2);
+        assert_eq!(report.rule_failures.get("const_fold"), Some(&1));
+        assert_eq!(report.rule_failures.get("identity_add_zero"), Some(&1));
+
+        assert_eq!(report.examples.len(), 2);
+        assert_eq!(
+            (report.examples[0].problem, report.examples[0].step),
+            (2, 0)
+        );
+        assert_eq!(
+            (report.examples[1].problem, report.examples[1].step),
+            (3, 1)
+        );
+        assert!(report.examples[1].reason.contains("not applicable"));
+        assert!((report.pass_rate() - 2.0 / 6.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Denominators a step relies on being nonzero.
+//!
+//! Cancelling `x·g / g → x` is only valid where `g ≠ 0`: the left side is
+//! undefined at the zeros of `g`, the right side is not. A quotient rule
+//! output `(f'g - fg')/g²` divides by `g²` where its input may not have.
+//! Either way the two sides agree only away from the zeros of a denominator
+//! that appears on one side and not the other.
+
+use mm_core::eval::Env;
+use mm_core::{Constraint, Expr};
+
+/// Side conditions `d ≠ 0` for the denominators on exactly one side of the
+/// step `before → after`.
+///
+/// Denominators are compared by canonical form, so `x/(y+1) → 2x/(1+y)`
+/// relies on nothing. Constant denominators that evaluate to a nonzero
+/// number are left out; a constant zero is kept, as its condition can never
+/// hold.
+///
+/// ```rust
+/// use mm_core::{parse::Parser, SymbolTable};
+/// use mm_verifier::division::division_conditions;
+///
+/// let mut symbols = SymbolTable::new();
+/// let mut parser = Parser::new(&mut symbols);
+/// let before = parser.parse("(x * y) / y").unwrap();
+/// let after = parser.parse("x").unwrap();
+///
+/// let conditions = division_conditions(&before, &after);
+/// assert_eq!(conditions.len(), 1);
+/// ```
+pub fn division_conditions(before: &Expr, after: &Expr) -> Vec<Constraint> {
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    collect_denominators(before, &mut left);
+    collect_denominators(after, &mut right);
+    let canonical = |ds: &[Expr]| ds.iter().map(Expr::canonicalize).collect::<Vec<_>>();
+    let (left_canon, right_canon) = (canonical(&left), canonical(&right));
+
+    let mut conditions: Vec<Constraint> = Vec::new();
+    let one_sided = left
+        .iter()
+        .zip(&left_canon)
+        .filter(|(_, c)| !right_canon.contains(c))
+        .chain(
+            right
+                .iter()
+                .zip(&right_canon)
+                .filter(|(_, c)| !left_canon.contains(c)),
+        );
+    for (denominator, canon) in one_sided {
+        let seen = conditions
+            .iter()
+            .any(|c| denominator_of(c).is_some_and(|d| d.canonicalize() == *canon));
+        if !seen && !is_nonzero_constant(denominator) {
+            conditions.push(Constraint::nonzero(denominator.clone()));
+        }
+    }
+    conditions
+}
+
+/// The `d` of a condition `d ≠ 0`.
+pub(crate) fn denominator_of(condition: &Constraint) -> Option<&Expr> {
+    match &condition.expr {
+        Expr::Not(inner) => match inner.as_ref() {
+            Expr::Equation { lhs, rhs } if rhs.is_zero() => Some(lhs),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collect divisors: `d` in `n/d`, and `b` in `b^(-k)`.
+fn collect_denominators(expr: &Expr, out: &mut Vec<Expr>) {
+    match expr {
+        Expr::Div(_, d) => out.push(d.as_ref().clone()),
+        Expr::Pow(base, exp) if matches!(exp.as_ref(), Expr::Const(k) if k.is_negative()) => {
+            out.push(base.as_ref().clone())
+        }
+        Expr::Product(factors) => out.extend(
+            factors
+                .iter()
+                .filter(|f| matches!(&f.power, Expr::Const(k) if k.is_negative()))
+                .map(|f| f.base.clone()),
+        ),
+        _ => {}
+    }
+    for child in expr.children() {
+        collect_denominators(child, out);
+    }
+}
+
+fn is_nonzero_constant(expr: &Expr) -> bool {
+    expr.free_vars().is_empty()
+        && expr
+            .evaluate(&Env::new())
+            .is_some_and(|v| v.is_finite() && v != 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mm_core::{parse::Parser, SymbolTable};
+
+    #[test]
+    fn test_division_conditions() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        let mut parse = |s: &str| parser.parse(s).unwrap();
+
+        // Cancelling g = y + 1 is valid only where g ≠ 0
+        let conditions = division_conditions(&parse("(x * (y + 1)) / (y + 1)"), &parse("x"));
+        assert_eq!(conditions.len(), 1);
+        assert_eq!(denominator_of(&conditions[0]), Some(&parse("y + 1")));
+
+        // So is introducing a division, as the quotient rule does
+        let conditions = division_conditions(&parse("x"), &parse("(x * y^2) / y^2"));
+        assert_eq!(denominator_of(&conditions[0]), Some(&parse("y^2")));
+
+        // The same denominator on both sides, or a nonzero constant, needs
+        // nothing
+        assert!(division_conditions(&parse("x / (y + 1)"), &parse("2 * x / (1 + y)")).is_empty());
+        assert!(division_conditions(&parse("x / 2"), &parse("x * (1/2)")).is_empty());
+        assert!(division_conditions(&parse("x / pi"), &parse("x * pi^(-1)")).is_empty());
+    }
+}
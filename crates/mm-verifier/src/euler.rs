@@ -0,0 +1,262 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+// Author: Pushp Kharat
+
+//! Verification of trig identities through Euler's formula.
+//!
+//! Rewriting `sin(u) = (e^{iu} - e^{-iu})/(2i)` and
+//! `cos(u) = (e^{iu} + e^{-iu})/2` turns a polynomial in sines and cosines
+//! into a sum of exponentials `c·e^{i(k₁x₁ + k₂x₂ + …)}` with Gaussian
+//! rational coefficients `c`. Distinct exponentials are linearly
+//! independent, so two expressions are equal exactly when their sums agree
+//! term by term:
+//!
+//! ```text
+//! sin(3x)             = (i/2)e^{-3ix} - (i/2)e^{3ix}
+//! 3sin(x) - 4sin³(x)  = (i/2)e^{-3ix} - (i/2)e^{3ix}
+//! ```
+//!
+//! Only integer multiples of variables may appear inside a sine or cosine,
+//! and variables may not appear outside one; anything else is reported as
+//! out of reach rather than guessed at.
+
+use mm_core::{Expr, Rational, Symbol};
+use std::collections::BTreeMap;
+
+/// Exponents larger than this are not expanded.
+const MAX_POWER: i64 = 16;
+
+/// Sums with more exponentials than this are abandoned.
+const MAX_TERMS: usize = 4096;
+
+/// A Gaussian rational `re + im·i`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Gaussian {
+    re: Rational,
+    im: Rational,
+}
+
+impl Gaussian {
+    fn real(re: Rational) -> Self {
+        Self {
+            re,
+            im: Rational::from(0),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// `self + other`, or `None` on overflow.
+    fn add(self, other: Self) -> Option<Self> {
+        Some(Self {
+            re: self.re.checked_add(&other.re)?,
+            im: self.im.checked_add(&other.im)?,
+        })
+    }
+
+    /// `self · other`, or `None` on overflow.
+    fn mul(self, other: Self) -> Option<Self> {
+        Some(Self {
+            re: (self.re.checked_mul(&other.re)?).checked_sub(&self.im.checked_mul(&other.im)?)?,
+            im: (self.re.checked_mul(&other.im)?).checked_add(&self.im.checked_mul(&other.re)?)?,
+        })
+    }
+}
+
+/// The frequency `k₁x₁ + k₂x₂ + …` of an exponential, zero entries omitted.
+type Frequency = BTreeMap<Symbol, i64>;
+
+/// A sum of exponentials: frequency ↦ coefficient, zero coefficients omitted.
+#[derive(Debug, Clone, PartialEq)]
+struct ExpSum(BTreeMap<Frequency, Gaussian>);
+
+impl ExpSum {
+    fn constant(c: Gaussian) -> Self {
+        let mut sum = Self(BTreeMap::new());
+        if !c.is_zero() {
+            sum.0.insert(Frequency::new(), c);
+        }
+        sum
+    }
+
+    /// Add `c·e^{i·frequency}` in place, or `None` on overflow.
+    fn plus(mut self, frequency: &Frequency, c: Gaussian) -> Option<Self> {
+        let sum = match self.0.get(frequency) {
+            Some(&d) => d.add(c)?,
+            None => c,
+        };
+        if sum.is_zero() {
+            self.0.remove(frequency);
+        } else {
+            self.0.insert(frequency.clone(), sum);
+        }
+        Some(self)
+    }
+
+    fn add(&self, other: &ExpSum) -> Option<ExpSum> {
+        other
+            .0
+            .iter()
+            .try_fold(self.clone(), |acc, (f, &c)| acc.plus(f, c))
+    }
+
+    fn scale(&self, c: Gaussian) -> Option<ExpSum> {
+        self.0
+            .iter()
+            .try_fold(ExpSum(BTreeMap::new()), |acc, (f, &d)| {
+                acc.plus(f, c.mul(d)?)
+            })
+    }
+
+    fn mul(&self, other: &ExpSum) -> Option<ExpSum> {
+        let mut product = ExpSum(BTreeMap::new());
+        for (f, &c) in &self.0 {
+            for (g, &d) in &other.0 {
+                let mut frequency = f.clone();
+                for (&v, &k) in g {
+                    let entry = frequency.entry(v).or_insert(0);
+                    *entry = entry.checked_add(k)?;
+                    if *entry == 0 {
+                        frequency.remove(&v);
+                    }
+                }
+                product = product.plus(&frequency, c.mul(d)?)?;
+            }
+        }
+        (product.0.len() <= MAX_TERMS).then_some(product)
+    }
+}
+
+/// Decide whether `a` and `b` are equal by expanding both into exponentials.
+///
+/// `Some(true)` and `Some(false)` are exact answers. `None` means one side
+/// is not a polynomial in sines and cosines of integer multiples of
+/// variables, or expands too far to compare.
+pub fn verify_via_exponentials(a: &Expr, b: &Expr) -> Option<bool> {
+    Some(exponential_form(a)? == exponential_form(b)?)
+}
+
+fn exponential_form(expr: &Expr) -> Option<ExpSum> {
+    let half = Rational::new(1, 2);
+    Some(match expr {
+        Expr::Const(c) => ExpSum::constant(Gaussian::real(*c)),
+        Expr::Neg(e) => exponential_form(e)?.scale(Gaussian::real(Rational::from(-1)))?,
+        Expr::Add(a, b) => exponential_form(a)?.add(&exponential_form(b)?)?,
+        Expr::Sub(a, b) => exponential_form(a)?
+            .add(&exponential_form(b)?.scale(Gaussian::real(Rational::from(-1)))?)?,
+        Expr::Mul(a, b) => exponential_form(a)?.mul(&exponential_form(b)?)?,
+        Expr::Div(a, b) => match b.as_ref() {
+            Expr::Const(c) if !c.is_zero() => {
+                exponential_form(a)?.scale(Gaussian::real(c.recip()))?
+            }
+            _ => return None,
+        },
+        Expr::Pow(base, exp) => match exp.as_ref() {
+            Expr::Const(n) if n.is_integer() && (0..=MAX_POWER).contains(&n.numer()) => {
+                let base = exponential_form(base)?;
+                let one = ExpSum::constant(Gaussian::real(Rational::from(1)));
+                (0..n.numer()).try_fold(one, |acc, _| acc.mul(&base))?
+            }
+            _ => return None,
+        },
+        // sin(u) = -(i/2)e^{iu} + (i/2)e^{-iu}
+        Expr::Sin(u) => {
+            let (plus, minus) = frequencies(u)?;
+            let i_half = |im| Gaussian {
+                re: Rational::from(0),
+                im,
+            };
+            ExpSum(BTreeMap::new())
+                .plus(&plus, i_half(-half))?
+                .plus(&minus, i_half(half))?
+        }
+        // cos(u) = (1/2)e^{iu} + (1/2)e^{-iu}
+        Expr::Cos(u) => {
+            let (plus, minus) = frequencies(u)?;
+            ExpSum(BTreeMap::new())
+                .plus(&plus, Gaussian::real(half))?
+                .plus(&minus, Gaussian::real(half))?
+        }
+        _ => return None,
+    })
+}
+
+/// The frequencies of `e^{iu}` and `e^{-iu}`, if `u` is an integer
+/// combination of variables.
+fn frequencies(u: &Expr) -> Option<(Frequency, Frequency)> {
+    fn collect(u: &Expr, sign: i64, frequency: &mut Frequency) -> Option<()> {
+        match u {
+            Expr::Var(v) => {
+                let entry = frequency.entry(*v).or_insert(0);
+                *entry = entry.checked_add(sign)?;
+            }
+            Expr::Const(c) if c.is_zero() => {}
+            Expr::Neg(e) => collect(e, -sign, frequency)?,
+            Expr::Add(a, b) => {
+                collect(a, sign, frequency)?;
+                collect(b, sign, frequency)?;
+            }
+            Expr::Sub(a, b) => {
+                collect(a, sign, frequency)?;
+                collect(b, -sign, frequency)?;
+            }
+            Expr::Mul(a, b) => match (a.as_ref(), b.as_ref()) {
+                (Expr::Const(k), e) | (e, Expr::Const(k)) if k.is_integer() => {
+                    collect(e, sign.checked_mul(k.numer())?, frequency)?
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+        Some(())
+    }
+    let mut plus = Frequency::new();
+    collect(u, 1, &mut plus)?;
+    plus.retain(|_, k| *k != 0);
+    let minus = plus.iter().map(|(&v, &k)| (v, -k)).collect();
+    Some((plus, minus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mm_core::parse::Parser;
+    use mm_core::SymbolTable;
+
+    fn check(lhs: &str, rhs: &str) -> Option<bool> {
+        let mut symbols = SymbolTable::new();
+        let mut parser = Parser::new(&mut symbols);
+        verify_via_exponentials(&parser.parse(lhs).unwrap(), &parser.parse(rhs).unwrap())
+    }
+
+    #[test]
+    fn test_trig_identities() {
+        assert_eq!(check("sin(3*x)", "3*sin(x) - 4*sin(x)^3"), Some(true));
+        assert_eq!(check("cos(3*x)", "4*cos(x)^3 - 3*cos(x)"), Some(true));
+        assert_eq!(check("sin(x)^2 + cos(x)^2", "1"), Some(true));
+        assert_eq!(
+            check("sin(x + y)", "sin(x)*cos(y) + cos(x)*sin(y)"),
+            Some(true)
+        );
+        assert_eq!(check("cos(2*x)", "1 - 2*sin(x)^2"), Some(true));
+        assert_eq!(check("sin(-x)", "-sin(x)"), Some(true));
+    }
+
+    #[test]
+    fn test_disproves_and_declines() {
+        // Off by one coefficient
+        assert_eq!(check("sin(3*x)", "3*sin(x) - 3*sin(x)^3"), Some(false));
+        assert_eq!(check("cos(x)", "sin(x)"), Some(false));
+        // Variables outside trig, non-integer frequencies, other functions
+        assert_eq!(check("x*sin(x)", "sin(x)*x"), None);
+        assert_eq!(check("sin(x/2)", "sin(x/2)"), None);
+        assert_eq!(check("tan(x)", "sin(x)/cos(x)"), None);
+        // Coefficients that overflow i64
+        let big = "9223372036854775807*cos(x)";
+        assert_eq!(check(&format!("{big} + {big}"), "cos(x)"), None);
+    }
+}
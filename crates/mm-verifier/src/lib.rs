@@ -10,13 +10,21 @@
 //!
 //! Provides multiple levels of verification to ensure mathematical correctness:
 //! - **Numerical**: Fast spot-checking at random points
-//! - **Symbolic**: Canonical form comparison
+//! - **Symbolic**: Canonical form comparison, optionally extended to trig
+//!   identities through Euler's formula
 //! - **Formal**: SMT solver proof (future)
+//!
+//! [`division::division_conditions`] reports the denominators a step
+//! relies on being nonzero, and [`dataset::verify_dataset`] checks every
+//! step of a generated training set.
 
+pub mod dataset;
+pub mod division;
+pub mod euler;
 pub mod numerical;
 pub mod symbolic;
 
-use mm_core::{Expr, MathError};
+use mm_core::{AngleMode, Expr, MathError};
 use mm_rules::{Rule, RuleContext};
 
 /// Verification confidence level.
@@ -66,13 +74,16 @@ impl VerifyResult {
 /// # Examples
 ///
 /// ```
+/// use mm_core::Expr;
+/// use mm_verifier::is_calculus_expr;
+///
 /// // A simple non-calculus expression
-/// let expr = Expr::Neg(Box::new(Expr::Const(1.0)));
+/// let expr = Expr::Neg(Box::new(Expr::int(1)));
 /// assert!(!is_calculus_expr(&expr));
 /// ```
-fn is_calculus_expr(expr: &Expr) -> bool {
+pub fn is_calculus_expr(expr: &Expr) -> bool {
     match expr {
-        Expr::Derivative { .. } | Expr::Integral { .. } => true,
+        Expr::Derivative { .. } | Expr::Integral { .. } | Expr::Limit { .. } => true,
         Expr::Neg(e)
         | Expr::Sqrt(e)
         | Expr::Sin(e)
@@ -81,6 +92,12 @@ fn is_calculus_expr(expr: &Expr) -> bool {
         | Expr::Arcsin(e)
         | Expr::Arccos(e)
         | Expr::Arctan(e)
+        | Expr::Sinh(e)
+        | Expr::Cosh(e)
+        | Expr::Tanh(e)
+        | Expr::Arcsinh(e)
+        | Expr::Arccosh(e)
+        | Expr::Arctanh(e)
         | Expr::Ln(e)
         | Expr::Exp(e)
         | Expr::Abs(e) => is_calculus_expr(e),
@@ -115,15 +132,35 @@ fn is_calculus_expr(expr: &Expr) -> bool {
             is_calculus_expr(a) || is_calculus_expr(b)
         }
         Expr::Not(e) => is_calculus_expr(e),
-        Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E => false,
+        Expr::Piecewise(branches) => branches
+            .iter()
+            .any(|(c, e)| is_calculus_expr(&c.expr) || is_calculus_expr(e)),
+        Expr::Const(_) | Expr::Var(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => false,
     }
 }
 
 /// Verifier for mathematical steps.
+#[derive(Clone)]
 pub struct Verifier {
     level: VerificationLevel,
     num_samples: usize,
-    tolerance: f64,
+    /// Absolute tolerance for numerical comparison.
+    atol: f64,
+    /// Relative tolerance for numerical comparison.
+    rtol: f64,
+    /// Unit for trig arguments during numerical comparison.
+    angle_mode: AngleMode,
+    /// Whether symbolic checks fall back to numerical sampling.
+    fallback: bool,
+    /// Whether symbolic checks also compare exponential forms of trig
+    /// expressions.
+    euler: bool,
+    /// Whether steps with derivatives or integrals are checked numerically
+    /// rather than trusted.
+    calculus: bool,
+    /// Whether steps dividing by a denominator that vanishes identically
+    /// are rejected.
+    strict_division: bool,
 }
 
 impl Default for Verifier {
@@ -138,7 +175,13 @@ impl Verifier {
         Self {
             level: VerificationLevel::Symbolic,
             num_samples: 10,
-            tolerance: 1e-10,
+            atol: 1e-10,
+            rtol: 1e-10,
+            angle_mode: AngleMode::Radians,
+            fallback: true,
+            euler: false,
+            calculus: false,
+            strict_division: false,
         }
     }
 
@@ -154,6 +197,67 @@ impl Verifier {
         self
     }
 
+    /// Set the numerical tolerances.
+    ///
+    /// Sampled values `a` and `b` agree when `|a - b| ≤ atol + rtol · max(|a|, |b|)`.
+    pub fn with_tolerances(mut self, atol: f64, rtol: f64) -> Self {
+        self.atol = atol;
+        self.rtol = rtol;
+        self
+    }
+
+    /// Set the angle unit used when sampling trig functions.
+    pub fn with_angle_mode(mut self, mode: AngleMode) -> Self {
+        self.angle_mode = mode;
+        self
+    }
+
+    /// Set whether symbolic verification may fall back to numerical sampling.
+    ///
+    /// On by default. With it off, anything canonical forms cannot settle is
+    /// `Unknown` rather than a sampled `Valid`, and a claimed result must
+    /// match the rule's output symbolically.
+    pub fn with_fallback(mut self, enabled: bool) -> Self {
+        self.fallback = enabled;
+        self
+    }
+
+    /// Set whether symbolic verification also rewrites sines and cosines as
+    /// complex exponentials and compares those.
+    ///
+    /// Off by default. This settles identities such as
+    /// `sin(3x) = 3sin(x) - 4sin³(x)` exactly where canonical forms cannot;
+    /// see [`euler::verify_via_exponentials`] for what it covers.
+    pub fn with_euler(mut self, enabled: bool) -> Self {
+        self.euler = enabled;
+        self
+    }
+
+    /// Set whether steps involving derivatives or integrals are checked.
+    ///
+    /// Off by default: such a step is trusted once the rule is shown to
+    /// produce it, at confidence 0.95. With it on, both sides are compared
+    /// by sampling, with derivatives taken by central differences; an
+    /// integration step `∫f dx → F` is checked by differentiating both
+    /// sides. Steps whose integrals cannot be removed that way come out
+    /// `Unknown`.
+    pub fn with_calculus_verification(mut self, enabled: bool) -> Self {
+        self.calculus = enabled;
+        self
+    }
+
+    /// Set whether steps are rejected for dividing by zero.
+    ///
+    /// Off by default: a step that cancels or introduces a denominator is
+    /// valid away from its zeros, and [`division::division_conditions`]
+    /// names them. With it on, each such denominator is also sampled, and a
+    /// step is `Invalid` if one is zero at every sample point, as
+    /// `sin²x + cos²x - 1` is: then the step holds nowhere.
+    pub fn with_strict_division(mut self, enabled: bool) -> Self {
+        self.strict_division = enabled;
+        self
+    }
+
     /// Verify a transformation step.
     ///
     /// Checks that applying the rule to `before` produces `after`.
@@ -183,36 +287,79 @@ impl Verifier {
             };
         }
 
+        self.verify_rewrite(before, after)
+    }
+
+    /// Verify that `before` may be rewritten to `after`, whatever produced it.
+    ///
+    /// This is the equivalence check of [`verify_step`](Self::verify_step)
+    /// without the rule, for steps such as constant folding that no rule
+    /// records.
+    pub fn verify_rewrite(&self, before: &Expr, after: &Expr) -> VerifyResult {
+        if self.strict_division {
+            let vanishing = division::division_conditions(before, after)
+                .iter()
+                .filter_map(division::denominator_of)
+                .any(|d| {
+                    numerical::compare_zero_in(
+                        d,
+                        self.num_samples,
+                        self.atol,
+                        self.rtol,
+                        self.angle_mode,
+                    ) == Some(true)
+                });
+            if vanishing {
+                return VerifyResult::Invalid {
+                    reason: "Division by a denominator that is identically zero".to_string(),
+                };
+            }
+        }
+
         // 3. Additional verification based on level
-        // For calculus expressions (derivatives/integrals), skip numerical verification
-        // since they cannot be numerically evaluated - trust the rule application
+        // Calculus expressions (derivatives/integrals) do not evaluate
+        // directly: either differentiate numerically or trust the rule
         if is_calculus_expr(before) || is_calculus_expr(after) {
-            return VerifyResult::Valid { confidence: 0.95 };
+            if !self.calculus {
+                return VerifyResult::Valid { confidence: 0.95 };
+            }
+            return match self.compare_calculus(before, after) {
+                Some(true) => VerifyResult::Valid { confidence: 0.999 },
+                Some(false) => VerifyResult::Invalid {
+                    reason: "Numerical differentiation check failed".to_string(),
+                },
+                None => too_few_samples(),
+            };
         }
 
         match self.level {
-            VerificationLevel::Numerical => {
-                if numerical::verify_equivalent(before, after, self.num_samples, self.tolerance) {
-                    VerifyResult::Valid { confidence: 0.999 }
-                } else {
-                    VerifyResult::Invalid {
-                        reason: "Numerical verification failed".to_string(),
-                    }
-                }
-            }
+            VerificationLevel::Numerical => match self.compare_numerically(before, after) {
+                Some(true) => VerifyResult::Valid { confidence: 0.999 },
+                Some(false) => VerifyResult::Invalid {
+                    reason: "Numerical verification failed".to_string(),
+                },
+                None => too_few_samples(),
+            },
             VerificationLevel::Symbolic => {
-                if symbolic::verify_equivalent(before, after) {
-                    VerifyResult::Valid { confidence: 1.0 }
+                let equal = if symbolic::verify_equivalent(before, after) {
+                    Some(true)
                 } else {
+                    self.euler_equal(before, after)
+                };
+                match equal {
+                    Some(true) => VerifyResult::Valid { confidence: 1.0 },
+                    Some(false) => VerifyResult::Invalid {
+                        reason: "Exponential forms differ".to_string(),
+                    },
+                    None if !self.fallback => inconclusive(),
                     // Fall back to numerical
-                    if numerical::verify_equivalent(before, after, self.num_samples, self.tolerance)
-                    {
-                        VerifyResult::Valid { confidence: 0.999 }
-                    } else {
-                        VerifyResult::Invalid {
+                    None => match self.compare_numerically(before, after) {
+                        Some(true) => VerifyResult::Valid { confidence: 0.999 },
+                        Some(false) => VerifyResult::Invalid {
                             reason: "Symbolic verification failed".to_string(),
-                        }
-                    }
+                        },
+                        None => too_few_samples(),
+                    },
                 }
             }
             VerificationLevel::Formal => {
@@ -241,14 +388,24 @@ impl Verifier {
                 return VerifyResult::Valid { confidence: 1.0 };
             }
 
-            // Try numerical verification
-            let diff = Expr::Sub(Box::new(lhs_subst.clone()), Box::new(rhs_subst.clone()));
-            if numerical::is_zero(&diff, self.num_samples, self.tolerance) {
-                return VerifyResult::Valid { confidence: 0.999 };
+            if !self.fallback {
+                return inconclusive();
             }
 
-            return VerifyResult::Invalid {
-                reason: "Solution does not satisfy the equation".to_string(),
+            // Try numerical verification
+            let diff = Expr::Sub(Box::new(lhs_subst.clone()), Box::new(rhs_subst.clone()));
+            return match numerical::compare_zero_in(
+                &diff,
+                self.num_samples,
+                self.atol,
+                self.rtol,
+                self.angle_mode,
+            ) {
+                Some(true) => VerifyResult::Valid { confidence: 0.999 },
+                Some(false) => VerifyResult::Invalid {
+                    reason: "Solution does not satisfy the equation".to_string(),
+                },
+                None => too_few_samples(),
             };
         }
 
@@ -271,8 +428,71 @@ impl Verifier {
             return true;
         }
 
+        if self.euler_equal(a, b) == Some(true) {
+            return true;
+        }
+
         // Finally try numerical
-        a.approx_equals(b, self.num_samples, self.tolerance)
+        self.fallback && self.compare_numerically(a, b) == Some(true)
+    }
+
+    /// Compare through Euler's formula, when enabled and applicable.
+    fn euler_equal(&self, a: &Expr, b: &Expr) -> Option<bool> {
+        if !self.euler {
+            return None;
+        }
+        euler::verify_via_exponentials(a, b)
+    }
+
+    /// Compare expressions with derivatives or integrals by sampling.
+    ///
+    /// An integral at the top of either side is removed by differentiating
+    /// both sides with respect to its variable.
+    fn compare_calculus(&self, a: &Expr, b: &Expr) -> Option<bool> {
+        let (a, b) = match (a, b) {
+            (Expr::Integral { var, .. }, _) | (_, Expr::Integral { var, .. }) => {
+                let differentiate = |e: &Expr| Expr::Derivative {
+                    expr: Box::new(e.clone()),
+                    var: *var,
+                };
+                (differentiate(a), differentiate(b))
+            }
+            _ => (a.clone(), b.clone()),
+        };
+        numerical::compare_differentiating_in(
+            &a,
+            &b,
+            self.num_samples,
+            self.atol,
+            self.rtol,
+            self.angle_mode,
+        )
+    }
+
+    /// Compare by sampling with this verifier's tolerances.
+    ///
+    /// `None` means too few sample points evaluated to finite values.
+    fn compare_numerically(&self, a: &Expr, b: &Expr) -> Option<bool> {
+        numerical::compare_in(
+            a,
+            b,
+            self.num_samples,
+            self.atol,
+            self.rtol,
+            self.angle_mode,
+        )
+    }
+}
+
+fn inconclusive() -> VerifyResult {
+    VerifyResult::Unknown {
+        reason: "Symbolic verification inconclusive and numerical fallback disabled".to_string(),
+    }
+}
+
+fn too_few_samples() -> VerifyResult {
+    VerifyResult::Unknown {
+        reason: "Too few finite sample points for numerical verification".to_string(),
     }
 }
 
@@ -293,10 +513,10 @@ impl Verifier {
 /// let replaced = substitute(&expr, x, &Expr::Const(3.0));
 /// assert_eq!(replaced, Expr::Add(Box::new(Expr::Const(3.0)), Box::new(Expr::Const(1.0))));
 /// ```
-fn substitute(expr: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
+pub fn substitute(expr: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
     match expr {
         Expr::Var(v) if *v == var => value.clone(),
-        Expr::Var(_) | Expr::Const(_) | Expr::Pi | Expr::E => expr.clone(),
+        Expr::Var(_) | Expr::Const(_) | Expr::Pi | Expr::E | Expr::Infinity { .. } => expr.clone(),
         Expr::Neg(e) => Expr::Neg(Box::new(substitute(e, var, value))),
         Expr::Sqrt(e) => Expr::Sqrt(Box::new(substitute(e, var, value))),
         Expr::Sin(e) => Expr::Sin(Box::new(substitute(e, var, value))),
@@ -305,6 +525,12 @@ fn substitute(expr: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
         Expr::Arcsin(e) => Expr::Arcsin(Box::new(substitute(e, var, value))),
         Expr::Arccos(e) => Expr::Arccos(Box::new(substitute(e, var, value))),
         Expr::Arctan(e) => Expr::Arctan(Box::new(substitute(e, var, value))),
+        Expr::Sinh(e) => Expr::Sinh(Box::new(substitute(e, var, value))),
+        Expr::Cosh(e) => Expr::Cosh(Box::new(substitute(e, var, value))),
+        Expr::Tanh(e) => Expr::Tanh(Box::new(substitute(e, var, value))),
+        Expr::Arcsinh(e) => Expr::Arcsinh(Box::new(substitute(e, var, value))),
+        Expr::Arccosh(e) => Expr::Arccosh(Box::new(substitute(e, var, value))),
+        Expr::Arctanh(e) => Expr::Arctanh(Box::new(substitute(e, var, value))),
         Expr::Ln(e) => Expr::Ln(Box::new(substitute(e, var, value))),
         Expr::Exp(e) => Expr::Exp(Box::new(substitute(e, var, value))),
         Expr::Abs(e) => Expr::Abs(Box::new(substitute(e, var, value))),
@@ -354,6 +580,22 @@ fn substitute(expr: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
             expr: Box::new(substitute(e, var, value)),
             var: *v,
         },
+        Expr::Limit {
+            expr: e,
+            var: v,
+            approaching,
+            direction,
+        } => Expr::Limit {
+            // The limit variable is bound in the body
+            expr: if *v == var {
+                e.clone()
+            } else {
+                Box::new(substitute(e, var, value))
+            },
+            var: *v,
+            approaching: Box::new(substitute(approaching, var, value)),
+            direction: *direction,
+        },
         Expr::Equation { lhs, rhs } => Expr::Equation {
             lhs: Box::new(substitute(lhs, var, value)),
             rhs: Box::new(substitute(rhs, var, value)),
@@ -490,6 +732,19 @@ fn substitute(expr: &Expr, var: mm_core::Symbol, value: &Expr) -> Expr {
             Box::new(substitute(a, var, value)),
             Box::new(substitute(b, var, value)),
         ),
+        Expr::Piecewise(branches) => Expr::Piecewise(
+            branches
+                .iter()
+                .map(|(c, e)| {
+                    (
+                        mm_core::Constraint {
+                            expr: substitute(&c.expr, var, value),
+                        },
+                        substitute(e, var, value),
+                    )
+                })
+                .collect(),
+        ),
     }
 }
 
@@ -518,5 +773,265 @@ mod tests {
             result,
             Expr::Add(Box::new(Expr::int(3)), Box::new(Expr::int(1)))
         );
+
+        // Conditions and values of a piecewise are both substituted
+        let branch = |c: Expr, v: Expr| (mm_core::Constraint { expr: c }, v);
+        let expr = Expr::Piecewise(vec![branch(
+            Expr::Lt(Box::new(Expr::Var(x)), Box::new(Expr::int(0))),
+            Expr::Neg(Box::new(Expr::Var(x))),
+        )]);
+        let result = substitute(&expr, x, &Expr::int(3));
+        assert_eq!(
+            result,
+            Expr::Piecewise(vec![branch(
+                Expr::Lt(Box::new(Expr::int(3)), Box::new(Expr::int(0))),
+                Expr::Neg(Box::new(Expr::int(3))),
+            )])
+        );
+    }
+
+    #[test]
+    fn test_verify_solution_large_magnitude() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // (x + 10⁶)² = x² + 2·10⁶·x + 10¹² at x = √7
+        let rhs = |c: i64| {
+            Expr::Add(
+                Box::new(Expr::Add(
+                    Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+                    Box::new(Expr::Mul(
+                        Box::new(Expr::int(2_000_000)),
+                        Box::new(Expr::Var(x)),
+                    )),
+                )),
+                Box::new(Expr::int(c)),
+            )
+        };
+        let lhs = Expr::Pow(
+            Box::new(Expr::Add(
+                Box::new(Expr::Var(x)),
+                Box::new(Expr::int(1_000_000)),
+            )),
+            Box::new(Expr::int(2)),
+        );
+        let eq = |c: i64| Expr::Equation {
+            lhs: Box::new(lhs.clone()),
+            rhs: Box::new(rhs(c)),
+        };
+        let root_seven = Expr::Sqrt(Box::new(Expr::int(7)));
+
+        let verifier = Verifier::new();
+        assert!(verifier
+            .verify_solution(&eq(1_000_000_000_000), x, &root_seven)
+            .is_valid());
+        assert!(!verifier
+            .verify_solution(&eq(1_000_001_000_000), x, &root_seven)
+            .is_valid());
+
+        // Purely absolute comparison cannot absorb the rounding error
+        let absolute = Verifier::new().with_tolerances(1e-10, 0.0);
+        assert!(!absolute
+            .verify_solution(&eq(1_000_000_000_000), x, &root_seven)
+            .is_valid());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_verify_solution_overflow_is_unknown() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // e^x = e^x + 1 at x = 1000: both sides overflow to ∞
+        let exp_x = || Expr::Exp(Box::new(Expr::Var(x)));
+        let eq = Expr::Equation {
+            lhs: Box::new(exp_x()),
+            rhs: Box::new(Expr::Add(Box::new(exp_x()), Box::new(Expr::int(1)))),
+        };
+
+        let result = Verifier::new().verify_solution(&eq, x, &Expr::int(1000));
+        assert!(matches!(result, VerifyResult::Unknown { .. }));
+    }
+
+    #[test]
+    fn test_verify_solution_in_degrees() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // sin(x) = 1/2 holds at x = 30 only when x is in degrees
+        let eq = Expr::Equation {
+            lhs: Box::new(Expr::Sin(Box::new(Expr::Var(x)))),
+            rhs: Box::new(Expr::Div(Box::new(Expr::int(1)), Box::new(Expr::int(2)))),
+        };
+        let degrees = Verifier::new().with_angle_mode(AngleMode::Degrees);
+        assert!(degrees.verify_solution(&eq, x, &Expr::int(30)).is_valid());
+        assert!(!Verifier::new()
+            .verify_solution(&eq, x, &Expr::int(30))
+            .is_valid());
+    }
+
+    #[test]
+    fn test_fallback_toggle() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let ctx = RuleContext::default();
+        let rules = mm_rules::trig::trig_rules();
+        let rule = rules
+            .iter()
+            .find(|r| r.name == "pythagorean_identity")
+            .unwrap();
+
+        // sin²x + cos²x = 1 has no canonical-form proof, only a numerical one
+        let sq = |e: Expr| Box::new(Expr::Pow(Box::new(e), Box::new(Expr::int(2))));
+        let before = Expr::Add(
+            sq(Expr::Sin(Box::new(Expr::Var(x)))),
+            sq(Expr::Cos(Box::new(Expr::Var(x)))),
+        );
+        let after = Expr::int(1);
+
+        let result = Verifier::new().verify_step(&before, &after, rule, &ctx);
+        assert_eq!(result.confidence(), Some(0.999));
+
+        let strict = Verifier::new().with_fallback(false);
+        let result = strict.verify_step(&before, &after, rule, &ctx);
+        assert!(matches!(result, VerifyResult::Unknown { .. }));
+    }
+
+    #[test]
+    fn test_euler_verification() {
+        let mut symbols = SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let before = parser.parse("sin(3*x)").unwrap();
+        let after = parser.parse("3*sin(x) - 4*sin(x)^3").unwrap();
+        let wrong = parser.parse("3*sin(x) - 3*sin(x)^3").unwrap();
+        let ctx = RuleContext::default();
+        let rules = mm_rules::trig::trig_rules();
+        let rule = rules.iter().find(|r| r.name == "sin_triple_angle").unwrap();
+
+        // Without sampling, canonical forms alone cannot confirm the step
+        let strict = Verifier::new().with_fallback(false);
+        let result = strict.verify_step(&before, &after, rule, &ctx);
+        assert!(matches!(result, VerifyResult::Unknown { .. }));
+
+        // The exponential forms settle it exactly
+        let euler = strict.with_euler(true);
+        let result = euler.verify_step(&before, &after, rule, &ctx);
+        assert_eq!(result.confidence(), Some(1.0));
+        assert_eq!(euler::verify_via_exponentials(&before, &wrong), Some(false));
+    }
+
+    #[test]
+    fn test_calculus_verification() {
+        use mm_rules::{RuleApplication, RuleCategory, RuleId};
+
+        let mut symbols = SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let before = parser.parse("diff(x^3, x)").unwrap();
+        let wrong = parser.parse("3*x^3").unwrap();
+        let ctx = RuleContext::default();
+        assert!(is_calculus_expr(&before));
+        assert!(!is_calculus_expr(&wrong));
+
+        // d/dx(xⁿ) = n·xⁿ, dropping the decrement
+        let buggy = Rule {
+            id: RuleId(1),
+            name: "buggy_power_rule",
+            category: RuleCategory::Derivative,
+            description: "d/dx(x^n) = n·x^n",
+            domains: &[],
+            requires: &[],
+            roots: &[],
+            is_applicable: |e, _| matches!(e, Expr::Derivative { .. }),
+            apply: |e, _| match e {
+                Expr::Derivative { expr, .. } => match expr.as_ref() {
+                    Expr::Pow(_, n) => vec![RuleApplication {
+                        result: Expr::Mul(n.clone(), expr.clone()),
+                        justification: "d/dx(x^n) = n·x^n".to_string(),
+                        condition: None,
+                    }],
+                    _ => vec![],
+                },
+                _ => vec![],
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        };
+
+        // Trusted by default, caught when checked
+        let result = Verifier::new().verify_step(&before, &wrong, &buggy, &ctx);
+        assert_eq!(result.confidence(), Some(0.95));
+        let checked = Verifier::new().with_calculus_verification(true);
+        let result = checked.verify_step(&before, &wrong, &buggy, &ctx);
+        assert!(matches!(result, VerifyResult::Invalid { .. }));
+
+        // Correct derivative and integration steps still pass
+        let rules = mm_rules::calculus::calculus_rules();
+        let power_rule = rules.iter().find(|r| r.name == "power_rule").unwrap();
+        let app = power_rule.apply(&before, &ctx).remove(0);
+        let result = checked.verify_step(&before, &app.result, power_rule, &ctx);
+        assert_eq!(result.confidence(), Some(0.999));
+
+        let integral = parser.parse("int(x^2, x)").unwrap();
+        let rules = mm_rules::calculus::phase4_calculus_rules();
+        let integral_power = rules.iter().find(|r| r.name == "integral_power").unwrap();
+        let app = integral_power.apply(&integral, &ctx).remove(0);
+        let result = checked.verify_step(&integral, &app.result, integral_power, &ctx);
+        assert_eq!(result.confidence(), Some(0.999));
+    }
+
+    #[test]
+    fn test_strict_division() {
+        use mm_rules::{RuleApplication, RuleCategory, RuleId};
+
+        let mut symbols = SymbolTable::new();
+        let mut parser = mm_core::parse::Parser::new(&mut symbols);
+        let ctx = RuleContext::default();
+        // (a·g)/g = a, valid only where g ≠ 0
+        let cancel = Rule {
+            id: RuleId(1),
+            name: "cancel_common_factor",
+            category: RuleCategory::Simplification,
+            description: "(a·g)/g = a",
+            domains: &[],
+            requires: &[],
+            roots: &[],
+            is_applicable: |e, _| matches!(e, Expr::Div(n, _) if matches!(**n, Expr::Mul(..))),
+            apply: |e, _| match e {
+                Expr::Div(n, g) => match n.as_ref() {
+                    Expr::Mul(a, b) if b == g => vec![RuleApplication {
+                        result: a.as_ref().clone(),
+                        justification: "(a·g)/g = a".to_string(),
+                        condition: None,
+                    }],
+                    _ => vec![],
+                },
+                _ => vec![],
+            },
+            reversible: false,
+            cost: 1,
+            ..Default::default()
+        };
+        let x = parser.parse("x").unwrap();
+        let strict = Verifier::new().with_strict_division(true);
+
+        // Flagged with g ≠ 0, and accepted: g is not always zero
+        let before = parser.parse("(x * (y + 1)) / (y + 1)").unwrap();
+        let conditions = division::division_conditions(&before, &x);
+        assert_eq!(conditions.len(), 1);
+        assert_eq!(
+            division::denominator_of(&conditions[0]),
+            Some(&parser.parse("y + 1").unwrap())
+        );
+        assert!(strict.verify_step(&before, &x, &cancel, &ctx).is_valid());
+
+        // g = sin²y + cos²y - 1 is zero everywhere, so the step holds nowhere
+        let before = parser
+            .parse("(x * (sin(y)^2 + cos(y)^2 - 1)) / (sin(y)^2 + cos(y)^2 - 1)")
+            .unwrap();
+        assert_eq!(division::division_conditions(&before, &x).len(), 1);
+        let result = strict.verify_step(&before, &x, &cancel, &ctx);
+        assert!(
+            matches!(result, VerifyResult::Invalid { ref reason } if reason.contains("identically zero"))
+        );
+    }
+}
@@ -6,46 +6,265 @@
 
 //! Numerical verification via random sampling.
 
-use mm_core::Expr;
+use mm_core::eval::Env;
+use mm_core::{AngleMode, Expr};
 use rand::Rng;
 use std::collections::HashMap;
 
+/// Whether two values agree: `|a - b| ≤ atol + rtol · max(|a|, |b|)`.
+pub fn within_tolerance(a: f64, b: f64, atol: f64, rtol: f64) -> bool {
+    (a - b).abs() <= atol + rtol * a.abs().max(b.abs())
+}
+
+/// Fewest finite sample points needed before sampling gives a verdict.
+pub const MIN_FINITE_SAMPLES: usize = 5;
+
+/// Points drawn per requested sample before giving up on finite values.
+const ATTEMPTS_PER_SAMPLE: usize = 4;
+
+/// Largest `|x|ⁿ` (and smallest `1/|x|ⁿ`) sampling aims for, where `n` is
+/// the polynomial degree: keeps `x^400` from overflowing or vanishing.
+const MAX_POWER_MAGNITUDE: f64 = 1e6;
+
+/// Rounding slack per unit of intermediate magnitude, see [`magnitude`];
+/// capped by the relative tolerance, so `rtol = 0` stays purely absolute.
+const ROUNDING: f64 = 64.0 * f64::EPSILON;
+
+/// Tolerance floor for comparisons that differentiate numerically: central
+/// differences are only good to about `1e-8` relative.
+pub const DIFFERENTIATION_TOLERANCE: f64 = 1e-6;
+
 /// Verify that two expressions are equivalent by numerical sampling.
 pub fn verify_equivalent(a: &Expr, b: &Expr, num_samples: usize, tolerance: f64) -> bool {
-    a.approx_equals(b, num_samples, tolerance)
+    verify_equivalent_tol(a, b, num_samples, tolerance, tolerance)
 }
 
-/// Check if an expression evaluates to zero.
-pub fn is_zero(expr: &Expr, num_samples: usize, tolerance: f64) -> bool {
+/// Verify equivalence by sampling, with separate absolute and relative tolerances.
+///
+/// Returns `false` both on disagreement and when too few finite samples were
+/// obtained; use [`compare`] to tell the two apart.
+pub fn verify_equivalent_tol(a: &Expr, b: &Expr, num_samples: usize, atol: f64, rtol: f64) -> bool {
+    compare(a, b, num_samples, atol, rtol) == Some(true)
+}
+
+/// Compare two expressions at random sample points.
+///
+/// Points where either side is infinite or NaN (overflow, poles of `tan`)
+/// are skipped rather than counted as disagreement, as are points where
+/// neither side is defined. Returns `Some(true)` once `num_samples` finite
+/// points agree, `Some(false)` on the first finite disagreement, and `None`
+/// if fewer than [`MIN_FINITE_SAMPLES`] finite points could be found.
+///
+/// High-degree polynomials are sampled closer to `±1` so their powers stay
+/// bounded, and the tolerance allows for the rounding error of cancelling
+/// terms, so `(x - 1)²⁰` matches its expansion.
+pub fn compare(a: &Expr, b: &Expr, num_samples: usize, atol: f64, rtol: f64) -> Option<bool> {
+    compare_in(a, b, num_samples, atol, rtol, AngleMode::Radians)
+}
+
+/// [`compare`], evaluating trig functions with angles in `mode`.
+pub fn compare_in(
+    a: &Expr,
+    b: &Expr,
+    num_samples: usize,
+    atol: f64,
+    rtol: f64,
+    mode: AngleMode,
+) -> Option<bool> {
+    compare_by(a, b, num_samples, atol, rtol, mode, false)
+}
+
+/// [`compare_in`], also evaluating derivatives by central differences.
+///
+/// See [`Expr::evaluate_differentiating`]. Tolerances are raised to at
+/// least [`DIFFERENTIATION_TOLERANCE`], and points where only one side
+/// evaluates are skipped: a failed difference quotient says nothing about
+/// where the expressions are defined.
+pub fn compare_differentiating_in(
+    a: &Expr,
+    b: &Expr,
+    num_samples: usize,
+    atol: f64,
+    rtol: f64,
+    mode: AngleMode,
+) -> Option<bool> {
+    let atol = atol.max(DIFFERENTIATION_TOLERANCE);
+    let rtol = rtol.max(DIFFERENTIATION_TOLERANCE);
+    compare_by(a, b, num_samples, atol, rtol, mode, true)
+}
+
+fn compare_by(
+    a: &Expr,
+    b: &Expr,
+    num_samples: usize,
+    atol: f64,
+    rtol: f64,
+    mode: AngleMode,
+    differentiate: bool,
+) -> Option<bool> {
     let mut rng = rand::thread_rng();
+    let evaluate = |e: &Expr, env: &Env| {
+        if differentiate {
+            e.evaluate_differentiating(env, mode)
+        } else {
+            e.evaluate_with(env, mode)
+        }
+    };
 
     // Get all variables
-    let vars = expr.free_vars();
+    let mut vars = a.free_vars();
+    for v in b.free_vars() {
+        if !vars.contains(&v) {
+            vars.push(v);
+        }
+    }
+
+    // |x| in [1/radius, radius] keeps |x|^degree within MAX_POWER_MAGNITUDE
+    let degree = degree(a).max(degree(b)).max(1);
+    let radius = (MAX_POWER_MAGNITUDE.ln() / degree as f64).exp().min(10.0);
+    let low = radius.recip().max(0.5);
+
+    let max_attempts = num_samples.max(MIN_FINITE_SAMPLES) * ATTEMPTS_PER_SAMPLE;
+    let mut finite = 0;
+
+    for _ in 0..max_attempts {
+        if finite >= num_samples {
+            break;
+        }
 
-    for _ in 0..num_samples {
         // Generate random environment
         let mut env = HashMap::new();
         for &var in &vars {
-            let val: f64 = rng.gen_range(-10.0..10.0);
             // Avoid values close to zero to prevent domain issues
-            let val = if val.abs() < 0.5 {
-                val + if val >= 0.0 { 1.0 } else { -1.0 }
-            } else {
-                val
-            };
-            env.insert(var, val);
+            let val: f64 = rng.gen_range(low..radius);
+            env.insert(var, if rng.gen_bool(0.5) { val } else { -val });
         }
 
-        // Evaluate
-        if let Some(val) = expr.evaluate(&env) {
-            if val.abs() > tolerance {
-                return false;
+        match (evaluate(a, &env), evaluate(b, &env)) {
+            (Some(va), Some(vb)) if va.is_finite() && vb.is_finite() => {
+                if !within_tolerance(va, vb, atol, rtol) {
+                    let scale = [a, b]
+                        .iter()
+                        .filter_map(|e| magnitude(e, &env, mode))
+                        .fold(0.0, f64::max);
+                    if !within_tolerance(va, vb, atol + rtol.min(ROUNDING) * scale, rtol) {
+                        return Some(false);
+                    }
+                }
+                finite += 1;
+            }
+            // Defined on one side only
+            (Some(v), None) | (None, Some(v)) if v.is_finite() && !differentiate => {
+                return Some(false)
             }
+            // Undefined, infinite or NaN: skip this sample
+            _ => {}
         }
-        // If evaluation fails, skip this sample
     }
 
-    true
+    if finite >= num_samples.min(MIN_FINITE_SAMPLES) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Polynomial degree of `expr`, counting only arithmetic nodes.
+///
+/// Non-polynomial functions count as constants; this only picks the
+/// sampling range.
+fn degree(expr: &Expr) -> u32 {
+    match expr {
+        Expr::Var(_) => 1,
+        Expr::Neg(e) => degree(e),
+        Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Equation { lhs: a, rhs: b } => {
+            degree(a).max(degree(b))
+        }
+        // Both sides of a quotient reach their full size
+        Expr::Mul(a, b) | Expr::Div(a, b) => degree(a).saturating_add(degree(b)),
+        Expr::Pow(base, exp) => match exp.as_ref() {
+            Expr::Const(n) if n.is_integer() => {
+                let n = u32::try_from(n.numer().unsigned_abs()).unwrap_or(u32::MAX);
+                degree(base).saturating_mul(n)
+            }
+            _ => degree(base),
+        },
+        Expr::Sum(terms) => terms.iter().map(|t| degree(&t.expr)).max().unwrap_or(0),
+        Expr::Product(factors) => factors
+            .iter()
+            .map(|f| {
+                degree(&Expr::Pow(
+                    Box::new(f.base.clone()),
+                    Box::new(f.power.clone()),
+                ))
+            })
+            .fold(0, u32::saturating_add),
+        _ => 0,
+    }
+}
+
+/// Rough size of the largest intermediate when evaluating `expr`: its value
+/// with every subtraction turned into an addition.
+///
+/// Floating-point rounding error grows with this rather than with the
+/// result, which can be tiny after cancellation.
+fn magnitude(expr: &Expr, env: &Env, mode: AngleMode) -> Option<f64> {
+    match expr {
+        Expr::Neg(e) => magnitude(e, env, mode),
+        Expr::Add(a, b) | Expr::Sub(a, b) => {
+            Some(magnitude(a, env, mode)? + magnitude(b, env, mode)?)
+        }
+        Expr::Mul(a, b) => Some(magnitude(a, env, mode)? * magnitude(b, env, mode)?),
+        Expr::Sum(terms) => terms
+            .iter()
+            .map(|t| Some(t.coeff.to_f64().abs() * magnitude(&t.expr, env, mode)?))
+            .sum(),
+        Expr::Pow(base, exp) => match exp.as_ref() {
+            Expr::Const(n) if n.is_integer() && n.is_positive() => {
+                Some(magnitude(base, env, mode)?.powf(n.to_f64()))
+            }
+            _ => expr.evaluate_with(env, mode).map(f64::abs),
+        },
+        _ => expr.evaluate_with(env, mode).map(f64::abs),
+    }
+}
+
+/// Check if an expression evaluates to zero.
+///
+/// `tolerance` is used both as the absolute and the relative tolerance;
+/// see [`is_zero_tol`].
+pub fn is_zero(expr: &Expr, num_samples: usize, tolerance: f64) -> bool {
+    is_zero_tol(expr, num_samples, tolerance, tolerance)
+}
+
+/// Check if an expression evaluates to zero, with separate tolerances.
+///
+/// A top-level difference `a - b` is checked as `a ≈ b`, so the relative
+/// tolerance scales with the magnitude of the two sides rather than of
+/// their (cancelled) difference.
+pub fn is_zero_tol(expr: &Expr, num_samples: usize, atol: f64, rtol: f64) -> bool {
+    compare_zero(expr, num_samples, atol, rtol) == Some(true)
+}
+
+/// Compare an expression against zero at random sample points.
+///
+/// Same conventions as [`compare`]; a top-level `a - b` compares `a` with `b`.
+pub fn compare_zero(expr: &Expr, num_samples: usize, atol: f64, rtol: f64) -> Option<bool> {
+    compare_zero_in(expr, num_samples, atol, rtol, AngleMode::Radians)
+}
+
+/// [`compare_zero`], evaluating trig functions with angles in `mode`.
+pub fn compare_zero_in(
+    expr: &Expr,
+    num_samples: usize,
+    atol: f64,
+    rtol: f64,
+    mode: AngleMode,
+) -> Option<bool> {
+    match expr {
+        Expr::Sub(a, b) => compare_in(a, b, num_samples, atol, rtol, mode),
+        _ => compare_in(expr, &Expr::int(0), num_samples, atol, rtol, mode),
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +297,143 @@ mod tests {
         let expr = Expr::Var(x);
         assert!(!is_zero(&expr, 10, 1e-10));
     }
+
+    /// (x + 10⁶)² and its expansion, both of magnitude ~10¹².
+    fn large_square(x: mm_core::Symbol, offset: i64) -> (Expr, Expr) {
+        let big = || Box::new(Expr::int(1_000_000));
+        let lhs = Expr::Pow(
+            Box::new(Expr::Add(Box::new(Expr::Var(x)), big())),
+            Box::new(Expr::int(2)),
+        );
+        let rhs = Expr::Add(
+            Box::new(Expr::Add(
+                Box::new(Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(2)))),
+                Box::new(Expr::Mul(
+                    Box::new(Expr::int(2_000_000)),
+                    Box::new(Expr::Var(x)),
+                )),
+            )),
+            Box::new(Expr::int(1_000_000_000_000 + offset)),
+        );
+        (lhs, rhs)
+    }
+
+    #[test]
+    fn test_relative_tolerance_large_magnitude() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // Rounding error at ~10¹² is far above 1e-10 absolute
+        let (lhs, rhs) = large_square(x, 0);
+        let diff = Expr::Sub(Box::new(lhs), Box::new(rhs));
+        assert!(!is_zero_tol(&diff, 50, 1e-10, 0.0));
+        assert!(is_zero_tol(&diff, 20, 1e-10, 1e-10));
+        assert!(is_zero(&diff, 20, 1e-10));
+
+        // Off by 10⁶ is a real difference, not rounding
+        let (lhs, rhs) = large_square(x, 1_000_000);
+        assert!(!verify_equivalent_tol(&lhs, &rhs, 20, 1e-10, 1e-10));
+        let diff = Expr::Sub(Box::new(lhs), Box::new(rhs));
+        assert!(!is_zero_tol(&diff, 20, 1e-10, 1e-10));
+    }
+
+    fn pow(base: Expr, exp: i64) -> Expr {
+        Expr::Pow(Box::new(base), Box::new(Expr::int(exp)))
+    }
+
+    #[test]
+    fn test_tan_skips_poles() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+        let tan = || Expr::Tan(Box::new(Expr::Var(x)));
+        let sin_over_cos = || {
+            Expr::Div(
+                Box::new(Expr::Sin(Box::new(Expr::Var(x)))),
+                Box::new(Expr::Cos(Box::new(Expr::Var(x)))),
+            )
+        };
+
+        assert_eq!(
+            compare(&tan(), &sin_over_cos(), 20, 1e-10, 1e-10),
+            Some(true)
+        );
+
+        // tan⁴⁰⁰ overflows to ∞ near every pole; those points are skipped
+        let a = pow(tan(), 400);
+        let b = pow(sin_over_cos(), 400);
+        assert_eq!(compare(&a, &b, 20, 1e-10, 1e-10), Some(true));
+
+        // Away from the poles a real difference is still caught
+        let c = Expr::Add(Box::new(pow(tan(), 400)), Box::new(Expr::int(1)));
+        assert_eq!(compare(&a, &c, 20, 1e-10, 1e-10), Some(false));
+    }
+
+    #[test]
+    fn test_too_few_finite_samples() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // e^(10000·x²) overflows for every sampled |x| ≥ 0.5
+        let blowup = || {
+            Expr::Exp(Box::new(Expr::Mul(
+                Box::new(Expr::int(10_000)),
+                Box::new(pow(Expr::Var(x), 2)),
+            )))
+        };
+        let other = Expr::Add(Box::new(blowup()), Box::new(Expr::int(1)));
+
+        assert_eq!(compare(&blowup(), &other, 10, 1e-10, 1e-10), None);
+        assert!(!verify_equivalent(&blowup(), &other, 10, 1e-10));
+        assert_eq!(compare_zero(&blowup(), 10, 1e-10, 1e-10), None);
+    }
+
+    /// `(x - 1)ⁿ` expanded by the binomial theorem, as a `Sum`.
+    fn binomial_expansion(x: mm_core::Symbol, n: i64) -> Expr {
+        let mut coeff = 1i64;
+        let terms = (0..=n)
+            .map(|k| {
+                let sign = if (n - k) % 2 == 0 { 1 } else { -1 };
+                let term = mm_core::Term {
+                    coeff: mm_core::Rational::from_integer(sign * coeff),
+                    expr: pow(Expr::Var(x), k),
+                };
+                coeff = coeff * (n - k) / (k + 1);
+                term
+            })
+            .collect();
+        Expr::Sum(terms)
+    }
+
+    #[test]
+    fn test_high_degree_polynomial() {
+        let mut symbols = SymbolTable::new();
+        let x = symbols.intern("x");
+
+        // The expansion cancels down to a tiny value near x = 1
+        let factored = pow(
+            Expr::Sub(Box::new(Expr::Var(x)), Box::new(Expr::int(1))),
+            20,
+        );
+        let expanded = binomial_expansion(x, 20);
+        for _ in 0..20 {
+            assert_eq!(compare(&factored, &expanded, 20, 1e-10, 1e-10), Some(true));
+        }
+
+        // A wrong coefficient is still caught
+        let mut wrong = binomial_expansion(x, 20);
+        if let Expr::Sum(terms) = &mut wrong {
+            terms[2].coeff = mm_core::Rational::from_integer(191);
+        }
+        assert_eq!(compare(&factored, &wrong, 20, 1e-10, 1e-10), Some(false));
+
+        // x⁴⁰⁰ / x³⁹⁹ neither overflows nor underflows
+        let quotient = Expr::Div(
+            Box::new(pow(Expr::Var(x), 400)),
+            Box::new(pow(Expr::Var(x), 399)),
+        );
+        assert_eq!(
+            compare(&quotient, &Expr::Var(x), 20, 1e-10, 1e-10),
+            Some(true)
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! Round-trip property test: integrating the derivative of a polynomial
+//! with the integration rules must give the polynomial back, up to an
+//! additive constant.
+//!
+//! Usage: cargo test --test diff_integrate -p mm-verifier
+//!
+//! Every integration step is checked with calculus verification, which
+//! differentiates both sides and so ignores the constant of integration.
+
+use mm_core::{AngleMode, Expr, Rational, Symbol, SymbolTable};
+use mm_rules::calculus::differentiate;
+use mm_rules::{standard_rules, Rule, RuleCategory, RuleContext};
+use mm_verifier::{numerical, Verifier, VerifyResult};
+use proptest::prelude::*;
+
+/// Integration steps allowed before giving up on an integral.
+const MAX_STEPS: usize = 64;
+
+/// A term `c·xⁿ`, written the ways a polynomial is usually typed:
+/// `c`, `x`, `x^n`, `c*x` or `c*x^n`.
+fn arb_term(x: Symbol) -> impl Strategy<Value = Expr> {
+    (-6i64..=6, 1i64..=4, 0i64..=4).prop_map(move |(numer, denom, degree)| {
+        let coeff = Rational::new(numer, denom);
+        let power = match degree {
+            0 => return Expr::Const(coeff),
+            1 => Expr::Var(x),
+            n => Expr::Pow(Box::new(Expr::Var(x)), Box::new(Expr::int(n))),
+        };
+        if coeff.is_one() {
+            power
+        } else {
+            Expr::Mul(Box::new(Expr::Const(coeff)), Box::new(power))
+        }
+    })
+}
+
+/// An arbitrary polynomial in `x` of degree at most 4, as a sum or
+/// difference of terms.
+fn arb_polynomial(x: Symbol) -> impl Strategy<Value = Expr> {
+    (
+        arb_term(x),
+        prop::collection::vec((any::<bool>(), arb_term(x)), 0..4),
+    )
+        .prop_map(|(first, rest)| {
+            rest.into_iter().fold(first, |sum, (minus, term)| {
+                if minus {
+                    Expr::Sub(Box::new(sum), Box::new(term))
+                } else {
+                    Expr::Add(Box::new(sum), Box::new(term))
+                }
+            })
+        })
+}
+
+/// Remove every integral from `expr` with `rules`, checking each step.
+///
+/// Fails if an integral is left that no rule applies to, if a step is
+/// shown wrong, or after `fuel` steps.
+fn integrate(
+    expr: &Expr,
+    rules: &[&Rule],
+    verifier: &Verifier,
+    fuel: &mut usize,
+) -> Result<Expr, String> {
+    let mut boxed = |e: &Expr| integrate(e, rules, verifier, fuel).map(Box::new);
+    match expr {
+        Expr::Integral { .. } => {
+            if *fuel == 0 {
+                return Err("integration did not finish".to_string());
+            }
+            *fuel -= 1;
+            // Placeholder rules hand the integral back unchanged
+            let ctx = RuleContext::default();
+            let (rule, result) = rules
+                .iter()
+                .filter(|rule| rule.can_apply(expr, &ctx))
+                .find_map(|rule| {
+                    let app = rule
+                        .apply(expr, &ctx)
+                        .into_iter()
+                        .find(|app| app.result != *expr)?;
+                    Some((rule, app.result))
+                })
+                .ok_or_else(|| format!("no rule integrates {:?}", expr))?;
+            // A step leaving an integral inside, as `2·∫x dx`, is `Unknown`
+            let check = verifier.verify_step(expr, &result, rule, &ctx);
+            if let VerifyResult::Invalid { reason } = check {
+                return Err(format!("{} gave {:?}: {}", rule.name, result, reason));
+            }
+            integrate(&result, rules, verifier, fuel)
+        }
+        Expr::Neg(a) => Ok(Expr::Neg(boxed(a)?)),
+        Expr::Add(a, b) => Ok(Expr::Add(boxed(a)?, boxed(b)?)),
+        Expr::Sub(a, b) => Ok(Expr::Sub(boxed(a)?, boxed(b)?)),
+        Expr::Mul(a, b) => Ok(Expr::Mul(boxed(a)?, boxed(b)?)),
+        Expr::Div(a, b) => Ok(Expr::Div(boxed(a)?, boxed(b)?)),
+        Expr::Pow(a, b) => Ok(Expr::Pow(boxed(a)?, boxed(b)?)),
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// The variable of the generated polynomials.
+fn variable() -> Symbol {
+    SymbolTable::new().intern("x")
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn differentiate_then_integrate(f in arb_polynomial(variable())) {
+        let x = variable();
+        let rules = standard_rules();
+        let rules = rules.by_category(RuleCategory::Integral);
+        let verifier = Verifier::new().with_calculus_verification(true);
+
+        let derivative = differentiate(&f, x).canonicalize();
+        let integral = Expr::Integral {
+            expr: Box::new(derivative.clone()),
+            var: x,
+        };
+        let antiderivative = integrate(&integral, &rules, &verifier, &mut MAX_STEPS.clone())
+            .map_err(|e| TestCaseError::fail(format!("∫({:?}) dx: {}", derivative, e)))?;
+
+        // F' = f' means F = f + C
+        let slope = |e: &Expr| Expr::Derivative {
+            expr: Box::new(e.clone()),
+            var: x,
+        };
+        prop_assert_eq!(
+            numerical::compare_differentiating_in(
+                &slope(&antiderivative),
+                &slope(&f),
+                20,
+                1e-6,
+                1e-6,
+                AngleMode::Radians,
+            ),
+            Some(true),
+            "∫({:?}) dx = {:?}, expected {:?} + C",
+            derivative,
+            antiderivative,
+            f
+        );
+    }
+}